@@ -0,0 +1,18 @@
+#!/bin/bash
+# Initialise and start the chain $CHAIN_ID in the /data volume,
+# keeping the container in the foreground while the node runs.
+
+set -e
+
+CHAIN_DATA=/data
+
+if [ -z "$CHAIN_ID" ]; then
+  echo "Missing CHAIN_ID environment variable."
+  exit 1
+fi
+
+rm -rf "${CHAIN_DATA:?}/$CHAIN_ID"
+
+one-chain gaiad "$CHAIN_ID" "$CHAIN_DATA" 26657 26656 6060 9090 100000000000
+
+exec tail -f "$CHAIN_DATA/$CHAIN_ID.log"
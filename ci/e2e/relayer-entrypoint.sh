@@ -0,0 +1,32 @@
+#!/bin/bash
+# Import the keys of the chains initialised in the /data volume, then run
+# the end-to-end scenario $SCENARIO on each of the paths in $PATHS.
+
+set -e
+
+CHAIN_DATA=/data
+CONFIG_FILE=ci/e2e/config.toml
+CHAINS="${CHAINS:-ibc-0 ibc-1}"
+PATHS="${PATHS:-ibc-0:ibc-1}"
+
+for CHAIN_ID in $CHAINS; do
+  echo "Waiting for $CHAIN_ID to produce blocks..."
+  until curl -sf "http://$CHAIN_ID:26657/status" | grep -Eq '"latest_block_height": ?"[1-9]'; do
+    sleep 1
+  done
+
+  hermes --config "$CONFIG_FILE" keys add --chain "$CHAIN_ID" \
+    --key-file "$CHAIN_DATA/$CHAIN_ID/user_seed.json" --overwrite
+  hermes --config "$CONFIG_FILE" keys add --chain "$CHAIN_ID" \
+    --key-file "$CHAIN_DATA/$CHAIN_ID/user2_seed.json" --key-name user2 --overwrite
+done
+
+for CHAIN_PATH in $PATHS; do
+  echo "Running scenario '${SCENARIO:-all}' between ${CHAIN_PATH%:*} and ${CHAIN_PATH#*:}..."
+  python3 e2e/run.py -c "$CONFIG_FILE" --cmd hermes \
+    --scenario "${SCENARIO:-all}" \
+    --chains "${CHAIN_PATH%:*}" "${CHAIN_PATH#*:}" \
+    --chain-data "$CHAIN_DATA" \
+    --gaiad-node 'tcp://{chain_id}:26657' \
+    --log-level "${LOG_LEVEL:-info}"
+done
@@ -0,0 +1,20 @@
+//! Asynchronous interchain queries, which let a controller chain query the state of a
+//! host chain over an unordered channel, with the `icq` module of Strangelove.
+//!
+//! The host executes the queries of the packets it receives, and writes their responses
+//! in the acknowledgements of the packets, which are relayed back like any other.
+
+pub mod packet;
+
+use crate::core::ics24_host::identifier::PortId;
+
+/// The port of the host chains.
+pub const HOST_PORT_ID_STR: &str = "icqhost";
+
+/// The version of the protocol, proposed by both ends of the channels.
+pub const VERSION: &str = "icq-1";
+
+/// Whether the given port is the port of a host of interchain queries.
+pub fn is_host_port(port_id: &PortId) -> bool {
+    port_id.as_str() == HOST_PORT_ID_STR
+}
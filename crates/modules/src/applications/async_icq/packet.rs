@@ -0,0 +1,64 @@
+//! The data of the packets of the interchain queries, i.e. the queries a controller
+//! requests the host to execute.
+
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::abci::RequestQuery;
+
+/// The data of a packet of interchain queries, encoded as JSON, whose `data` is the
+/// base64 encoding of a protobuf [`CosmosQuery`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchainQueryPacketData {
+    pub data: String,
+    #[serde(default)]
+    pub memo: String,
+}
+
+/// The queries of a packet, executed by the host as ABCI queries.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CosmosQuery {
+    #[prost(message, repeated, tag = "1")]
+    pub requests: Vec<RequestQuery>,
+}
+
+/// The queries requested by the given data of a packet, if it is the one of a packet
+/// of interchain queries.
+pub fn decode_queries(packet_data: &[u8]) -> Option<Vec<RequestQuery>> {
+    let data: InterchainQueryPacketData = serde_json::from_slice(packet_data).ok()?;
+    let query = subtle_encoding::base64::decode(data.data).ok()?;
+
+    prost::Message::decode(query.as_slice())
+        .ok()
+        .map(|query: CosmosQuery| query.requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+
+    #[test]
+    fn decode_packet_queries() {
+        let query = CosmosQuery {
+            requests: vec![RequestQuery {
+                data: vec![1, 2, 3],
+                path: "/cosmos.bank.v1beta1.Query/AllBalances".to_string(),
+                height: 0,
+                prove: false,
+            }],
+        };
+
+        let data = InterchainQueryPacketData {
+            data: String::from_utf8(subtle_encoding::base64::encode(query.encode_to_vec()))
+                .unwrap(),
+            memo: String::new(),
+        };
+
+        let packet_data = serde_json::to_vec(&data).unwrap();
+
+        assert_eq!(decode_queries(&packet_data), Some(query.requests));
+        assert_eq!(decode_queries(br#"{"amount":"1","denom":"uatom"}"#), None);
+    }
+}
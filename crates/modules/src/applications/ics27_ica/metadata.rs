@@ -0,0 +1,79 @@
+//! The metadata negotiated in the version of the channels of the interchain accounts.
+
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::ics04_channel::Version;
+use crate::core::ics24_host::identifier::ConnectionId;
+
+use super::{ENCODING_PROTOBUF, TX_TYPE_SDK_MULTI_MSG, VERSION};
+
+/// The metadata of a channel of an interchain account, encoded as JSON in its version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub version: String,
+    pub controller_connection_id: ConnectionId,
+    pub host_connection_id: ConnectionId,
+    /// The address of the account on the host, only known once the host opened its channel end
+    pub address: String,
+    pub encoding: String,
+    pub tx_type: String,
+}
+
+impl Metadata {
+    /// The metadata proposed by a controller on `ChanOpenInit`, for the channel
+    /// over the given connections of the controller and the host.
+    pub fn for_init(
+        controller_connection_id: ConnectionId,
+        host_connection_id: ConnectionId,
+    ) -> Self {
+        Self {
+            version: VERSION.to_string(),
+            controller_connection_id,
+            host_connection_id,
+            address: String::new(),
+            encoding: ENCODING_PROTOBUF.to_string(),
+            tx_type: TX_TYPE_SDK_MULTI_MSG.to_string(),
+        }
+    }
+
+    /// The metadata encoded in the given version, if it is the version of a channel
+    /// of an interchain account.
+    pub fn from_version(version: &Version) -> Option<Self> {
+        serde_json::from_str::<Self>(&version.to_string())
+            .ok()
+            .filter(|metadata| metadata.version == VERSION)
+    }
+}
+
+impl From<Metadata> for Version {
+    fn from(metadata: Metadata) -> Self {
+        // Serializing a struct of strings cannot fail
+        Version::new(serde_json::to_string(&metadata).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn metadata_roundtrip() {
+        let metadata = Metadata::for_init(
+            ConnectionId::from_str("connection-0").unwrap(),
+            ConnectionId::from_str("connection-7").unwrap(),
+        );
+
+        let version = Version::from(metadata.clone());
+        assert_eq!(
+            version.to_string(),
+            r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-7","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}"#
+        );
+
+        assert_eq!(Metadata::from_version(&version), Some(metadata));
+        assert_eq!(Metadata::from_version(&Version::ics20()), None);
+    }
+}
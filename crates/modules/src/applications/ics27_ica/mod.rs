@@ -0,0 +1,36 @@
+//! ICS 27: Interchain Accounts, which let a controller chain execute transactions
+//! on a host chain with an account it owns there, over an ordered channel.
+
+pub mod metadata;
+
+use crate::core::ics24_host::identifier::PortId;
+
+/// The prefix of the ports of the controllers, followed by the address of the owner of the account.
+pub const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
+
+/// The port of the host chains.
+pub const HOST_PORT_ID_STR: &str = "icahost";
+
+/// The version of the protocol, as negotiated in the metadata of the channels.
+pub const VERSION: &str = "ics27-1";
+
+/// The encoding of the transactions executed on the host, as negotiated in the metadata.
+pub const ENCODING_PROTOBUF: &str = "proto3";
+
+/// The type of the transactions executed on the host, as negotiated in the metadata.
+pub const TX_TYPE_SDK_MULTI_MSG: &str = "sdk_multi_msg";
+
+/// Whether the given port is the port of a controller of interchain accounts.
+pub fn is_controller_port(port_id: &PortId) -> bool {
+    port_id.as_str().starts_with(CONTROLLER_PORT_PREFIX)
+}
+
+/// Whether the given port is the port of a host of interchain accounts.
+pub fn is_host_port(port_id: &PortId) -> bool {
+    port_id.as_str() == HOST_PORT_ID_STR
+}
+
+/// Whether the given port is a port of the interchain accounts application.
+pub fn is_ica_port(port_id: &PortId) -> bool {
+    is_controller_port(port_id) || is_host_port(port_id)
+}
@@ -0,0 +1,29 @@
+use flex_error::define_error;
+
+use crate::applications::transfer::error::Error as TransferError;
+use crate::core::ics24_host::error::ValidationError;
+use crate::prelude::*;
+use crate::signer::SignerError;
+
+define_error! {
+    #[derive(Debug, PartialEq, Eq)]
+    Error {
+        Transfer
+            [ TransferError ]
+            | _ | { "transfer error" },
+
+        Signer
+            [ SignerError ]
+            | _ | { "failed to parse signer" },
+
+        Ics24
+            [ ValidationError ]
+            | _ | { "ics24 error" },
+
+        EmptyFee
+            | _ | { "expect fee field to be non-empty" },
+
+        EmptyPacketId
+            | _ | { "expect packet_id field to be non-empty" },
+    }
+}
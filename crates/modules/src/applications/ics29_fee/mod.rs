@@ -0,0 +1,6 @@
+//! ICS 29: Fee middleware, which lets the senders of the packets escrow fees
+//! paid out to the relayers receiving, acknowledging and timing them out.
+
+pub mod error;
+pub mod msgs;
+pub mod packet_fee;
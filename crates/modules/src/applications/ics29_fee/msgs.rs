@@ -0,0 +1,2 @@
+pub mod pay_packet;
+pub mod register_payee;
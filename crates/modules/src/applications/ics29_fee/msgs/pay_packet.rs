@@ -0,0 +1,77 @@
+//! The message escrowing the fees for the relaying of the next packet sent on a channel,
+//! to be submitted along with the message sending the packet.
+
+use crate::prelude::*;
+
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::applications::fee::v1::{Fee as RawFee, MsgPayPacketFee};
+use prost::Message;
+
+use crate::applications::transfer::coin::RawCoin;
+use crate::core::ics24_host::identifier::{ChannelId, PortId};
+use crate::signer::Signer;
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgPayPacketFee";
+
+/// Build the message escrowing the given fees from the account of the payer,
+/// which is refunded whatever was not paid out.
+pub fn build_pay_packet_message(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    payer: &Signer,
+    recv_fee: Vec<RawCoin>,
+    ack_fee: Vec<RawCoin>,
+    timeout_fee: Vec<RawCoin>,
+) -> Any {
+    let fee = RawFee {
+        recv_fee: recv_fee.into_iter().map(Into::into).collect(),
+        ack_fee: ack_fee.into_iter().map(Into::into).collect(),
+        timeout_fee: timeout_fee.into_iter().map(Into::into).collect(),
+    };
+
+    let message = MsgPayPacketFee {
+        fee: Some(fee),
+        source_port_id: port_id.to_string(),
+        source_channel_id: channel_id.to_string(),
+        signer: payer.to_string(),
+        relayers: Vec::new(),
+    };
+
+    Any {
+        type_url: TYPE_URL.to_string(),
+        value: message.encode_to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn pay_packet_message() {
+        let payer = Signer::from_str("cosmos1payer").unwrap();
+        let recv_fee = RawCoin::from_string_list("100uatom").unwrap();
+
+        let any = build_pay_packet_message(
+            &PortId::transfer(),
+            &ChannelId::from_str("channel-0").unwrap(),
+            &payer,
+            recv_fee,
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(any.type_url, TYPE_URL);
+
+        let message = MsgPayPacketFee::decode(any.value.as_slice()).unwrap();
+        assert_eq!(message.source_channel_id, "channel-0");
+        assert_eq!(message.signer, "cosmos1payer");
+
+        let fee = message.fee.unwrap();
+        assert_eq!(fee.recv_fee[0].denom, "uatom");
+        assert_eq!(fee.recv_fee[0].amount, "100");
+        assert!(fee.ack_fee.is_empty());
+    }
+}
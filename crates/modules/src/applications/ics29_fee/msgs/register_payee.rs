@@ -0,0 +1,34 @@
+//! The message registering the address on the counterparty chain to which the fees
+//! of the packets received by a relayer on a channel are paid out.
+
+use crate::prelude::*;
+
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::applications::fee::v1::MsgRegisterCounterpartyPayee;
+use prost::Message;
+
+use crate::core::ics24_host::identifier::{ChannelId, PortId};
+use crate::signer::Signer;
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgRegisterCounterpartyPayee";
+
+/// Build the message registering, on the destination chain of the channel, the address
+/// on the source chain of the channel to which the receive fees of the relayer are paid.
+pub fn build_register_counterparty_payee_message(
+    relayer: &Signer,
+    counterparty_payee: &Signer,
+    channel_id: &ChannelId,
+    port_id: &PortId,
+) -> Any {
+    let message = MsgRegisterCounterpartyPayee {
+        port_id: port_id.to_string(),
+        channel_id: channel_id.to_string(),
+        relayer: relayer.to_string(),
+        counterparty_payee: counterparty_payee.to_string(),
+    };
+
+    Any {
+        type_url: TYPE_URL.to_string(),
+        value: message.encode_to_vec(),
+    }
+}
@@ -0,0 +1,151 @@
+//! The fees escrowed for the relaying of a packet.
+
+use crate::prelude::*;
+
+use ibc_proto::ibc::applications::fee::v1::{
+    Fee as RawFee, IdentifiedPacketFees as RawIdentifiedPacketFees, PacketFee as RawPacketFee,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::applications::transfer::amount::Amount;
+use crate::applications::transfer::coin::RawCoin;
+use crate::core::ics04_channel::packet::Sequence;
+use crate::core::ics24_host::identifier::{ChannelId, PortId};
+use crate::signer::Signer;
+
+use super::error::Error;
+
+/// The fees paid out to the relayers receiving, acknowledging or timing out a packet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fee {
+    pub recv_fee: Vec<RawCoin>,
+    pub ack_fee: Vec<RawCoin>,
+    pub timeout_fee: Vec<RawCoin>,
+}
+
+/// Fees escrowed for a packet by an account, which is refunded the unspent fees.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketFee {
+    pub fee: Fee,
+    pub refund_address: Signer,
+    /// The relayers permitted to receive the fees, any relayer if empty
+    pub relayers: Vec<Signer>,
+}
+
+/// All the fees escrowed for a packet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentifiedPacketFees {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequence: Sequence,
+    pub packet_fees: Vec<PacketFee>,
+}
+
+impl IdentifiedPacketFees {
+    /// The total of the receive fees in the given denomination escrowed for the packet,
+    /// for the given relayer, saturating on overflow.
+    pub fn total_recv_fee(&self, denom: &str, relayer: &Signer) -> Amount {
+        self.packet_fees
+            .iter()
+            .filter(|packet_fee| {
+                packet_fee.relayers.is_empty() || packet_fee.relayers.contains(relayer)
+            })
+            .flat_map(|packet_fee| &packet_fee.fee.recv_fee)
+            .filter(|coin| coin.denom == denom)
+            .fold(Amount::from(0), |total, coin| {
+                total.checked_add(coin.amount).unwrap_or(total)
+            })
+    }
+}
+
+fn coins(raw_coins: Vec<ibc_proto::cosmos::base::v1beta1::Coin>) -> Result<Vec<RawCoin>, Error> {
+    raw_coins
+        .into_iter()
+        .map(|coin| RawCoin::try_from(coin).map_err(Error::transfer))
+        .collect()
+}
+
+impl TryFrom<RawFee> for Fee {
+    type Error = Error;
+
+    fn try_from(raw: RawFee) -> Result<Self, Self::Error> {
+        Ok(Fee {
+            recv_fee: coins(raw.recv_fee)?,
+            ack_fee: coins(raw.ack_fee)?,
+            timeout_fee: coins(raw.timeout_fee)?,
+        })
+    }
+}
+
+impl TryFrom<RawPacketFee> for PacketFee {
+    type Error = Error;
+
+    fn try_from(raw: RawPacketFee) -> Result<Self, Self::Error> {
+        Ok(PacketFee {
+            fee: raw.fee.ok_or_else(Error::empty_fee)?.try_into()?,
+            refund_address: raw.refund_address.parse().map_err(Error::signer)?,
+            relayers: raw
+                .relayers
+                .iter()
+                .map(|relayer| relayer.parse().map_err(Error::signer))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<RawIdentifiedPacketFees> for IdentifiedPacketFees {
+    type Error = Error;
+
+    fn try_from(raw: RawIdentifiedPacketFees) -> Result<Self, Self::Error> {
+        let packet_id = raw.packet_id.ok_or_else(Error::empty_packet_id)?;
+
+        Ok(IdentifiedPacketFees {
+            port_id: packet_id.port_id.parse().map_err(Error::ics24)?,
+            channel_id: packet_id.channel_id.parse().map_err(Error::ics24)?,
+            sequence: Sequence::from(packet_id.sequence),
+            packet_fees: raw
+                .packet_fees
+                .into_iter()
+                .map(PacketFee::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn total_recv_fee_of_relayer() {
+        let relayer = Signer::from_str("cosmos1relayer").unwrap();
+        let other = Signer::from_str("cosmos1other").unwrap();
+
+        let packet_fee = |recv_fee: &str, relayers: Vec<Signer>| PacketFee {
+            fee: Fee {
+                recv_fee: RawCoin::from_string_list(recv_fee).unwrap(),
+                ack_fee: vec![],
+                timeout_fee: vec![],
+            },
+            refund_address: Signer::from_str("cosmos1payer").unwrap(),
+            relayers,
+        };
+
+        let fees = IdentifiedPacketFees {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::from_str("channel-0").unwrap(),
+            sequence: Sequence::from(1),
+            packet_fees: vec![
+                packet_fee("100uatom,5stake", vec![]),
+                packet_fee("50uatom", vec![relayer.clone()]),
+                packet_fee("1000uatom", vec![other]),
+            ],
+        };
+
+        assert_eq!(fees.total_recv_fee("uatom", &relayer), Amount::from(150));
+        assert_eq!(fees.total_recv_fee("stake", &relayer), Amount::from(5));
+        assert_eq!(fees.total_recv_fee("uosmo", &relayer), Amount::from(0));
+    }
+}
@@ -1,4 +1,7 @@
 //! Various packet encoding semantics which underpin the various types of transactions.
 
+pub mod async_icq;
+pub mod ics27_ica;
+pub mod ics29_fee;
 pub mod ics31_cross_chain_query;
 pub mod transfer;
@@ -4,7 +4,7 @@ use crate::prelude::*;
 
 use ibc_proto::cosmos::base::v1beta1::Coin;
 use ibc_proto::google::protobuf::Any;
-use ibc_proto::ibc::applications::transfer::v1::MsgTransfer as RawMsgTransfer;
+use ibc_proto::ibc::core::client::v1::Height as RawHeight;
 use ibc_proto::protobuf::Protobuf;
 
 use crate::applications::transfer::error::Error;
@@ -16,6 +16,28 @@ use crate::tx_msg::Msg;
 
 pub const TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
 
+/// The Protobuf `MsgTransfer` of ibc-go, with the `memo` field of the later versions
+/// of ibc-go, which the definitions `ibc-proto` is generated from predate.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RawMsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: String,
+    #[prost(string, tag = "2")]
+    pub source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    pub token: Option<Coin>,
+    #[prost(string, tag = "4")]
+    pub sender: String,
+    #[prost(string, tag = "5")]
+    pub receiver: String,
+    #[prost(message, optional, tag = "6")]
+    pub timeout_height: Option<RawHeight>,
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: String,
+}
+
 /// Message used to build an ICS20 token transfer packet.
 ///
 /// Note that this message is not a packet yet, as it lacks the proper sequence
@@ -41,6 +63,9 @@ pub struct MsgTransfer<C = Coin> {
     /// Timeout timestamp relative to the current block timestamp.
     /// The timeout is disabled when set to 0.
     pub timeout_timestamp: Timestamp,
+    /// An optional memo, eg. used by middlewares on the receiving chain.
+    /// No memo is attached to the packet when empty.
+    pub memo: String,
 }
 
 impl Msg for MsgTransfer {
@@ -81,6 +106,7 @@ impl TryFrom<RawMsgTransfer> for MsgTransfer {
             receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
             timeout_height,
             timeout_timestamp,
+            memo: raw_msg.memo,
         })
     }
 }
@@ -95,6 +121,7 @@ impl From<MsgTransfer> for RawMsgTransfer {
             receiver: domain_msg.receiver.to_string(),
             timeout_height: domain_msg.timeout_height.into(),
             timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
+            memo: domain_msg.memo,
         }
     }
 }
@@ -125,6 +152,7 @@ impl From<MsgTransfer> for Any {
 
 #[cfg(test)]
 pub mod test_util {
+    use crate::prelude::*;
     use core::ops::Add;
     use core::time::Duration;
 
@@ -162,6 +190,7 @@ pub mod test_util {
             timeout_timestamp: timeout_timestamp
                 .unwrap_or_else(|| Timestamp::now().add(Duration::from_secs(10)).unwrap()),
             timeout_height,
+            memo: String::new(),
         }
     }
 
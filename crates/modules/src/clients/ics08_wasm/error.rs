@@ -0,0 +1,12 @@
+use crate::prelude::*;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    #[derive(Debug, PartialEq, Eq)]
+    Error {
+        Decode
+            [ TraceError<prost::DecodeError> ]
+            | _ | { "failed to decode the state or message of a wasm client" },
+    }
+}
@@ -0,0 +1,136 @@
+//! ICS 08: Wasm clients, which run a light client implemented by a Wasm contract
+//! stored on the host chain, identified by its checksum.
+//!
+//! The client states, consensus states and client messages of a Wasm client wrap those of
+//! the light client implemented by its contract, encoded as `Any` in their `data`. The
+//! relayer wraps the states and headers of the light client it builds when the client is
+//! hosted by a chain which only accepts Wasm clients, and unwraps the states it queries.
+
+pub mod error;
+
+use crate::prelude::*;
+
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::lightclients::wasm::v1::{
+    ClientMessage as RawClientMessage, ClientState as RawClientState,
+    ConsensusState as RawConsensusState,
+};
+use prost::Message;
+
+use crate::Height;
+
+use self::error::Error;
+
+pub const WASM_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ClientState";
+pub const WASM_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ConsensusState";
+pub const WASM_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ClientMessage";
+
+/// Wrap the given client state in the client state of a Wasm client running the contract
+/// of the given checksum.
+pub fn wrap_client_state(client_state: Any, checksum: Vec<u8>, latest_height: Height) -> Any {
+    let raw = RawClientState {
+        data: client_state.encode_to_vec(),
+        checksum,
+        latest_height: Some(latest_height.into()),
+    };
+
+    Any {
+        type_url: WASM_CLIENT_STATE_TYPE_URL.to_string(),
+        value: raw.encode_to_vec(),
+    }
+}
+
+/// Wrap the given consensus state in a consensus state of a Wasm client.
+pub fn wrap_consensus_state(consensus_state: Any) -> Any {
+    let raw = RawConsensusState {
+        data: consensus_state.encode_to_vec(),
+    };
+
+    Any {
+        type_url: WASM_CONSENSUS_STATE_TYPE_URL.to_string(),
+        value: raw.encode_to_vec(),
+    }
+}
+
+/// Wrap the given header or misbehaviour in a client message of a Wasm client.
+pub fn wrap_client_message(message: Any) -> Any {
+    let raw = RawClientMessage {
+        data: message.encode_to_vec(),
+    };
+
+    Any {
+        type_url: WASM_CLIENT_MESSAGE_TYPE_URL.to_string(),
+        value: raw.encode_to_vec(),
+    }
+}
+
+/// Unwrap the state or message of the light client from the given state or message
+/// of a Wasm client, or return it as is if it is not one of a Wasm client.
+pub fn unwrap(any: Any) -> Result<Any, Error> {
+    let data = match any.type_url.as_str() {
+        WASM_CLIENT_STATE_TYPE_URL => {
+            RawClientState::decode(any.value.as_slice())
+                .map_err(Error::decode)?
+                .data
+        }
+        WASM_CONSENSUS_STATE_TYPE_URL => {
+            RawConsensusState::decode(any.value.as_slice())
+                .map_err(Error::decode)?
+                .data
+        }
+        WASM_CLIENT_MESSAGE_TYPE_URL => {
+            RawClientMessage::decode(any.value.as_slice())
+                .map_err(Error::decode)?
+                .data
+        }
+        _ => return Ok(any),
+    };
+
+    Any::decode(data.as_slice()).map_err(Error::decode)
+}
+
+/// The checksum of the contract run by the Wasm client of the given client state,
+/// if it is the client state of a Wasm client.
+pub fn checksum(client_state: &Any) -> Result<Option<Vec<u8>>, Error> {
+    if client_state.type_url != WASM_CLIENT_STATE_TYPE_URL {
+        return Ok(None);
+    }
+
+    let raw = RawClientState::decode(client_state.value.as_slice()).map_err(Error::decode)?;
+
+    Ok(Some(raw.checksum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_and_unwrap() {
+        let client_state = Any {
+            type_url: "/ibc.lightclients.tendermint.v1.ClientState".to_string(),
+            value: vec![1, 2, 3],
+        };
+
+        let wrapped = wrap_client_state(
+            client_state.clone(),
+            vec![0xab; 32],
+            Height::new(1, 10).unwrap(),
+        );
+
+        assert_eq!(wrapped.type_url, WASM_CLIENT_STATE_TYPE_URL);
+        assert_eq!(checksum(&wrapped).unwrap(), Some(vec![0xab; 32]));
+        assert_eq!(unwrap(wrapped).unwrap(), client_state);
+
+        let header = Any {
+            type_url: "/ibc.lightclients.tendermint.v1.Header".to_string(),
+            value: vec![4, 5, 6],
+        };
+
+        assert_eq!(unwrap(wrap_client_message(header.clone())).unwrap(), header);
+
+        // The states of other clients are returned as is
+        assert_eq!(unwrap(header.clone()).unwrap(), header);
+        assert_eq!(checksum(&header).unwrap(), None);
+    }
+}
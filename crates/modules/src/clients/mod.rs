@@ -1,3 +1,4 @@
 //! Implementations of client verification algorithms for specific types of chains.
 
 pub mod ics07_tendermint;
+pub mod ics08_wasm;
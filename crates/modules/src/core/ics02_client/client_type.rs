@@ -8,6 +8,8 @@ use super::error::Error;
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ClientType {
     Tendermint = 1,
+    /// Clients implemented by a Wasm contract, wrapping the states of another client type
+    Wasm = 8,
 
     #[cfg(any(test, feature = "mocks"))]
     Mock = 9999,
@@ -15,6 +17,7 @@ pub enum ClientType {
 
 impl ClientType {
     const TENDERMINT_STR: &'static str = "07-tendermint";
+    const WASM_STR: &'static str = "08-wasm";
 
     #[cfg_attr(not(test), allow(dead_code))]
     const MOCK_STR: &'static str = "9999-mock";
@@ -23,6 +26,7 @@ impl ClientType {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Tendermint => Self::TENDERMINT_STR,
+            Self::Wasm => Self::WASM_STR,
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock => Self::MOCK_STR,
@@ -42,6 +46,7 @@ impl core::str::FromStr for ClientType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             Self::TENDERMINT_STR => Ok(Self::Tendermint),
+            Self::WASM_STR => Ok(Self::Wasm),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::MOCK_STR => Ok(Self::Mock),
@@ -109,4 +114,12 @@ mod tests {
         let client_type_from_str = ClientType::from_str(type_string).unwrap();
         assert_eq!(client_type_from_str, client_type);
     }
+
+    #[test]
+    fn parse_wasm_as_string_result() {
+        let client_type = ClientType::Wasm;
+        let type_string = client_type.as_str();
+        let client_type_from_str = ClientType::from_str(type_string).unwrap();
+        assert_eq!(client_type_from_str, client_type);
+    }
 }
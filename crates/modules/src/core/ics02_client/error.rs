@@ -3,6 +3,7 @@ use crate::prelude::*;
 use flex_error::{define_error, TraceError};
 use ibc_proto::protobuf::Error as TendermintProtoError;
 
+use crate::clients::ics08_wasm::error::Error as Ics08Error;
 use crate::core::ics02_client::client_type::ClientType;
 use crate::core::ics02_client::height::HeightError;
 use crate::core::ics23_commitment::error::Error as Ics23Error;
@@ -110,6 +111,10 @@ define_error! {
         MissingRawClientState
             | _ | { "missing raw client state" },
 
+        DecodeWasmState
+            [ Ics08Error ]
+            | _ | { "error unwrapping the state of a wasm client" },
+
         InvalidRawConsensusState
             [ TraceError<TendermintProtoError> ]
             | _ | { "invalid raw client consensus state" },
@@ -12,6 +12,7 @@ use ibc_proto::ibc::core::channel::v1::{
     IdentifiedChannel as RawIdentifiedChannel,
 };
 
+use crate::core::ics04_channel::packet::Sequence;
 use crate::core::ics04_channel::{error::Error, Version};
 use crate::core::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
 
@@ -44,6 +45,7 @@ impl TryFrom<RawIdentifiedChannel> for IdentifiedChannelEnd {
             counterparty: value.counterparty,
             connection_hops: value.connection_hops,
             version: value.version,
+            upgrade_sequence: value.upgrade_sequence,
         };
 
         Ok(IdentifiedChannelEnd {
@@ -69,6 +71,7 @@ impl From<IdentifiedChannelEnd> for RawIdentifiedChannel {
             version: value.channel_end.version.to_string(),
             port_id: value.port_id.to_string(),
             channel_id: value.channel_id.to_string(),
+            upgrade_sequence: value.channel_end.upgrade_sequence.into(),
         }
     }
 }
@@ -80,14 +83,17 @@ pub struct ChannelEnd {
     pub remote: Counterparty,
     pub connection_hops: Vec<ConnectionId>,
     pub version: Version,
+    /// The sequence of the latest upgrade attempt of the channel, zero if it was never upgraded
+    #[serde(default)]
+    pub upgrade_sequence: Sequence,
 }
 
 impl Display for ChannelEnd {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         write!(
             f,
-            "ChannelEnd {{ state: {}, ordering: {}, remote: {}, connection_hops: {}, version: {} }}",
-            self.state, self.ordering, self.remote, PrettySlice(&self.connection_hops), self.version
+            "ChannelEnd {{ state: {}, ordering: {}, remote: {}, connection_hops: {}, version: {}, upgrade_sequence: {} }}",
+            self.state, self.ordering, self.remote, PrettySlice(&self.connection_hops), self.version, self.upgrade_sequence
         )
     }
 }
@@ -100,6 +106,7 @@ impl Default for ChannelEnd {
             remote: Counterparty::default(),
             connection_hops: Vec::new(),
             version: Version::default(),
+            upgrade_sequence: Sequence::default(),
         }
     }
 }
@@ -134,13 +141,10 @@ impl TryFrom<RawChannel> for ChannelEnd {
 
         let version = value.version.into();
 
-        Ok(ChannelEnd::new(
-            chan_state,
-            chan_ordering,
-            remote,
-            connection_hops,
-            version,
-        ))
+        Ok(
+            ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version)
+                .with_upgrade_sequence(value.upgrade_sequence.into()),
+        )
     }
 }
 
@@ -156,6 +160,7 @@ impl From<ChannelEnd> for RawChannel {
                 .map(|v| v.as_str().to_string())
                 .collect(),
             version: value.version.to_string(),
+            upgrade_sequence: value.upgrade_sequence.into(),
         }
     }
 }
@@ -175,6 +180,15 @@ impl ChannelEnd {
             remote,
             connection_hops,
             version,
+            upgrade_sequence: Sequence::default(),
+        }
+    }
+
+    /// Sets the sequence of the latest upgrade attempt of the channel.
+    pub fn with_upgrade_sequence(self, upgrade_sequence: Sequence) -> Self {
+        Self {
+            upgrade_sequence,
+            ..self
         }
     }
 
@@ -216,6 +230,16 @@ impl ChannelEnd {
         &self.version
     }
 
+    pub fn upgrade_sequence(&self) -> Sequence {
+        self.upgrade_sequence
+    }
+
+    /// Returns `true` if this `ChannelEnd` is in the middle of an upgrade handshake,
+    /// i.e. is flushing its in-flight packets or has flushed them.
+    pub fn is_upgrading(&self) -> bool {
+        matches!(self.state, State::Flushing | State::FlushComplete)
+    }
+
     pub fn validate_basic(&self) -> Result<(), Error> {
         if self.connection_hops.len() != 1 {
             return Err(Error::invalid_connection_hops_length(
@@ -328,6 +352,9 @@ pub enum Order {
     None = 0,
     Unordered = 1,
     Ordered = 2,
+    /// Packets are received in the order they were sent, but the ones which time out
+    /// are skipped instead of closing the channel
+    OrderedAllowTimeout = 3,
 }
 
 impl Default for Order {
@@ -349,15 +376,28 @@ impl Order {
             Self::None => "UNINITIALIZED",
             Self::Unordered => "ORDER_UNORDERED",
             Self::Ordered => "ORDER_ORDERED",
+            Self::OrderedAllowTimeout => "ORDER_ORDERED_ALLOW_TIMEOUT",
         }
     }
 
+    /// Whether the packets of the channel are received in the order they were sent,
+    /// i.e. whether it is `ORDERED` or `ORDERED_ALLOW_TIMEOUT`.
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, Self::Ordered | Self::OrderedAllowTimeout)
+    }
+
+    /// Whether the timeout of a packet closes the channel, i.e. whether it is `ORDERED`.
+    pub fn closes_on_timeout(&self) -> bool {
+        matches!(self, Self::Ordered)
+    }
+
     // Parses the Order out from a i32.
     pub fn from_i32(nr: i32) -> Result<Self, Error> {
         match nr {
             0 => Ok(Self::None),
             1 => Ok(Self::Unordered),
             2 => Ok(Self::Ordered),
+            3 => Ok(Self::OrderedAllowTimeout),
             _ => Err(Error::unknown_order_type(nr.to_string())),
         }
     }
@@ -371,6 +411,7 @@ impl FromStr for Order {
             "uninitialized" => Ok(Self::None),
             "unordered" => Ok(Self::Unordered),
             "ordered" => Ok(Self::Ordered),
+            "ordered_allow_timeout" => Ok(Self::OrderedAllowTimeout),
             _ => Err(Error::unknown_order_type(s.to_string())),
         }
     }
@@ -383,6 +424,8 @@ pub enum State {
     TryOpen = 2,
     Open = 3,
     Closed = 4,
+    Flushing = 5,
+    FlushComplete = 6,
 }
 
 impl State {
@@ -394,6 +437,8 @@ impl State {
             Self::TryOpen => "TRYOPEN",
             Self::Open => "OPEN",
             Self::Closed => "CLOSED",
+            Self::Flushing => "FLUSHING",
+            Self::FlushComplete => "FLUSHCOMPLETE",
         }
     }
 
@@ -405,6 +450,8 @@ impl State {
             2 => Ok(Self::TryOpen),
             3 => Ok(Self::Open),
             4 => Ok(Self::Closed),
+            5 => Ok(Self::Flushing),
+            6 => Ok(Self::FlushComplete),
             _ => Err(Error::unknown_state(s)),
         }
     }
@@ -459,6 +506,7 @@ pub mod test_util {
             counterparty: Some(get_dummy_raw_counterparty()),
             connection_hops: vec![ConnectionId::default().to_string()],
             version: "ics20".to_string(), // The version is not validated.
+            upgrade_sequence: 0,
         }
     }
 }
@@ -589,6 +637,11 @@ mod tests {
                 want_res: Order::Ordered,
                 want_err: false,
             },
+            Test {
+                ordering: "ORDER_ORDERED_ALLOW_TIMEOUT",
+                want_res: Order::OrderedAllowTimeout,
+                want_err: false,
+            },
             Test {
                 ordering: "UNKNOWN_ORDER",
                 want_res: Order::None,
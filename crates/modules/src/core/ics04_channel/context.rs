@@ -208,6 +208,11 @@ pub trait ChannelKeeper {
                     port_id,
                     channel_id,
                     next_seq_recv,
+                }
+                | RecvPacketResult::Timeout {
+                    port_id,
+                    channel_id,
+                    next_seq_recv,
                 } => self.store_next_sequence_recv(port_id, channel_id, next_seq_recv)?,
                 RecvPacketResult::Unordered {
                     port_id,
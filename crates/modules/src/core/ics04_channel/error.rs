@@ -104,6 +104,18 @@ define_error! {
         MissingChannel
             | _ | { "missing channel end" },
 
+        MissingUpgrade
+            | _ | { "missing channel upgrade" },
+
+        MissingUpgradeFields
+            | _ | { "missing fields of the channel upgrade" },
+
+        MissingUpgradeTimeout
+            | _ | { "missing timeout of the channel upgrade" },
+
+        MissingErrorReceipt
+            | _ | { "missing error receipt of the channel upgrade" },
+
         InvalidVersionLengthConnection
             | _ | { "single version must be negociated on connection before opening channel" },
 
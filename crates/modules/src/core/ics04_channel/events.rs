@@ -6,7 +6,7 @@ use tendermint::abci::tag::Tag;
 use tendermint::abci::Event as AbciEvent;
 
 use crate::core::ics04_channel::error::Error;
-use crate::core::ics04_channel::packet::Packet;
+use crate::core::ics04_channel::packet::{Packet, Sequence};
 use crate::core::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
 use crate::events::{Error as EventError, IbcEvent, IbcEventType};
 use crate::prelude::*;
@@ -18,6 +18,7 @@ pub const CHANNEL_ID_ATTRIBUTE_KEY: &str = "channel_id";
 pub const PORT_ID_ATTRIBUTE_KEY: &str = "port_id";
 pub const COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY: &str = "counterparty_channel_id";
 pub const COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY: &str = "counterparty_port_id";
+pub const UPGRADE_SEQUENCE_ATTRIBUTE_KEY: &str = "upgrade_sequence";
 
 /// Packet event attribute keys
 pub const PKT_SEQ_ATTRIBUTE_KEY: &str = "packet_sequence";
@@ -561,6 +562,117 @@ impl_from_ibc_to_abci_event!(
     CloseConfirm
 );
 
+/// The steps of the channel upgrade handshake, each of which emits an [`UpgradeChannel`] event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum UpgradeStep {
+    Init,
+    Try,
+    Ack,
+    Confirm,
+    Open,
+    Timeout,
+    Cancel,
+    Error,
+}
+
+impl UpgradeStep {
+    pub fn event_type(&self) -> IbcEventType {
+        match self {
+            Self::Init => IbcEventType::UpgradeInitChannel,
+            Self::Try => IbcEventType::UpgradeTryChannel,
+            Self::Ack => IbcEventType::UpgradeAckChannel,
+            Self::Confirm => IbcEventType::UpgradeConfirmChannel,
+            Self::Open => IbcEventType::UpgradeOpenChannel,
+            Self::Timeout => IbcEventType::UpgradeTimeoutChannel,
+            Self::Cancel => IbcEventType::UpgradeCancelChannel,
+            Self::Error => IbcEventType::UpgradeErrorChannel,
+        }
+    }
+
+    /// The step which emitted the events of the given type, if it is a channel upgrade event.
+    pub fn from_event_type(event_type: &IbcEventType) -> Option<Self> {
+        match event_type {
+            IbcEventType::UpgradeInitChannel => Some(Self::Init),
+            IbcEventType::UpgradeTryChannel => Some(Self::Try),
+            IbcEventType::UpgradeAckChannel => Some(Self::Ack),
+            IbcEventType::UpgradeConfirmChannel => Some(Self::Confirm),
+            IbcEventType::UpgradeOpenChannel => Some(Self::Open),
+            IbcEventType::UpgradeTimeoutChannel => Some(Self::Timeout),
+            IbcEventType::UpgradeCancelChannel => Some(Self::Cancel),
+            IbcEventType::UpgradeErrorChannel => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// An event emitted by a step of the upgrade handshake of a channel end.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UpgradeChannel {
+    pub step: UpgradeStep,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub counterparty_port_id: PortId,
+    pub counterparty_channel_id: Option<ChannelId>,
+    pub upgrade_sequence: Sequence,
+}
+
+impl Display for UpgradeChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "UpgradeChannel {{ step: {:?}, port_id: {}, channel_id: {}, counterparty_port_id: {}, counterparty_channel_id: {}, upgrade_sequence: {} }}",
+            self.step,
+            self.port_id,
+            self.channel_id,
+            self.counterparty_port_id,
+            self.counterparty_channel_id
+                .as_ref()
+                .map_or_else(|| "None".to_string(), |id| id.to_string()),
+            self.upgrade_sequence,
+        )
+    }
+}
+
+impl From<UpgradeChannel> for IbcEvent {
+    fn from(v: UpgradeChannel) -> Self {
+        IbcEvent::UpgradeChannel(v)
+    }
+}
+
+impl From<UpgradeChannel> for AbciEvent {
+    fn from(v: UpgradeChannel) -> Self {
+        let mut attributes = vec![
+            Tag {
+                key: PORT_ID_ATTRIBUTE_KEY.parse().unwrap(),
+                value: v.port_id.to_string().parse().unwrap(),
+            },
+            Tag {
+                key: CHANNEL_ID_ATTRIBUTE_KEY.parse().unwrap(),
+                value: v.channel_id.to_string().parse().unwrap(),
+            },
+            Tag {
+                key: COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY.parse().unwrap(),
+                value: v.counterparty_port_id.to_string().parse().unwrap(),
+            },
+        ];
+        if let Some(channel_id) = v.counterparty_channel_id {
+            attributes.push(Tag {
+                key: COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY.parse().unwrap(),
+                value: channel_id.to_string().parse().unwrap(),
+            });
+        }
+        attributes.push(Tag {
+            key: UPGRADE_SEQUENCE_ATTRIBUTE_KEY.parse().unwrap(),
+            value: v.upgrade_sequence.to_string().parse().unwrap(),
+        });
+
+        AbciEvent {
+            type_str: v.step.event_type().as_str().to_string(),
+            attributes,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct SendPacket {
     pub packet: Packet,
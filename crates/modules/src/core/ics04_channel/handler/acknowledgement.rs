@@ -1,6 +1,6 @@
 use crate::core::ics03_connection::connection::State as ConnectionState;
 use crate::core::ics04_channel::channel::State;
-use crate::core::ics04_channel::channel::{Counterparty, Order};
+use crate::core::ics04_channel::channel::Counterparty;
 use crate::core::ics04_channel::events::AcknowledgePacket;
 use crate::core::ics04_channel::handler::verify::verify_packet_acknowledgement_proofs;
 use crate::core::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
@@ -77,7 +77,7 @@ pub fn process<Ctx: ChannelReader>(
         &msg.proofs,
     )?;
 
-    let result = if source_channel_end.order_matches(&Order::Ordered) {
+    let result = if source_channel_end.ordering().is_ordered() {
         let next_seq_ack =
             ctx.get_next_sequence_ack(&packet.source_port, &packet.source_channel)?;
 
@@ -1,5 +1,5 @@
 use crate::core::ics03_connection::connection::State as ConnectionState;
-use crate::core::ics04_channel::channel::{Counterparty, Order, State};
+use crate::core::ics04_channel::channel::{Counterparty, State};
 use crate::core::ics04_channel::context::ChannelReader;
 use crate::core::ics04_channel::error::Error;
 use crate::core::ics04_channel::events::ReceivePacket;
@@ -25,6 +25,13 @@ pub enum RecvPacketResult {
         channel_id: ChannelId,
         next_seq_recv: Sequence,
     },
+    /// A packet which timed out on an `ORDERED_ALLOW_TIMEOUT` channel, skipped without
+    /// being delivered to the module so that the next packets can be received.
+    Timeout {
+        port_id: PortId,
+        channel_id: ChannelId,
+        next_seq_recv: Sequence,
+    },
 }
 
 pub fn process<Ctx: ChannelReader>(
@@ -65,8 +72,13 @@ pub fn process<Ctx: ChannelReader>(
         ));
     }
 
+    // The packets which timed out on the `ORDERED_ALLOW_TIMEOUT` channels are skipped
+    let allows_timeout = dest_channel_end.ordering().is_ordered()
+        && !dest_channel_end.ordering().closes_on_timeout();
+
     let latest_height = ChannelReader::host_height(ctx);
-    if packet.timeout_height.has_expired(latest_height) {
+    let height_expired = packet.timeout_height.has_expired(latest_height);
+    if height_expired && !allows_timeout {
         return Err(Error::low_packet_height(
             latest_height,
             packet.timeout_height,
@@ -74,7 +86,11 @@ pub fn process<Ctx: ChannelReader>(
     }
 
     let latest_timestamp = ChannelReader::host_timestamp(ctx);
-    if let Expiry::Expired = latest_timestamp.check_expiry(&packet.timeout_timestamp) {
+    let timestamp_expired = matches!(
+        latest_timestamp.check_expiry(&packet.timeout_timestamp),
+        Expiry::Expired
+    );
+    if timestamp_expired && !allows_timeout {
         return Err(Error::low_packet_timestamp());
     }
 
@@ -86,7 +102,7 @@ pub fn process<Ctx: ChannelReader>(
         &msg.proofs,
     )?;
 
-    let result = if dest_channel_end.order_matches(&Order::Ordered) {
+    let result = if dest_channel_end.ordering().is_ordered() {
         let next_seq_recv =
             ctx.get_next_sequence_recv(&packet.destination_port, &packet.destination_channel)?;
 
@@ -102,6 +118,18 @@ pub fn process<Ctx: ChannelReader>(
             ));
         }
 
+        if height_expired || timestamp_expired {
+            output.log("success: timed out packet skipped");
+
+            return Ok(
+                output.with_result(PacketResult::Recv(RecvPacketResult::Timeout {
+                    port_id: packet.destination_port.clone(),
+                    channel_id: packet.destination_channel.clone(),
+                    next_seq_recv: next_seq_recv.increment(),
+                })),
+            );
+        }
+
         PacketResult::Recv(RecvPacketResult::Ordered {
             port_id: packet.destination_port.clone(),
             channel_id: packet.destination_channel.clone(),
@@ -154,9 +182,10 @@ mod tests {
     use crate::core::ics03_connection::connection::State as ConnectionState;
     use crate::core::ics03_connection::version::get_compatible_versions;
     use crate::core::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
-    use crate::core::ics04_channel::handler::recv_packet::process;
+    use crate::core::ics04_channel::handler::recv_packet::{process, RecvPacketResult};
     use crate::core::ics04_channel::msgs::recv_packet::test_util::get_dummy_raw_msg_recv_packet;
     use crate::core::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+    use crate::core::ics04_channel::packet::PacketResult;
     use crate::core::ics04_channel::Version;
     use crate::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::mock::context::MockContext;
@@ -302,4 +331,68 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn recv_timed_out_packet_on_ordered_allow_timeout_channel() {
+        let context = MockContext::default();
+
+        let host_height = context.query_latest_height().increment();
+
+        let client_height = host_height.increment();
+
+        let msg = MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(
+            client_height.revision_height(),
+        ))
+        .unwrap();
+
+        let packet = Packet {
+            sequence: 1.into(),
+            source_port: PortId::default(),
+            source_channel: ChannelId::default(),
+            destination_port: PortId::default(),
+            destination_channel: ChannelId::default(),
+            data: Vec::new(),
+            timeout_height: client_height.into(),
+            timeout_timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+        };
+
+        let msg = MsgRecvPacket::new(packet.clone(), msg.proofs, get_dummy_account_id());
+
+        let dest_channel_end = ChannelEnd::new(
+            State::Open,
+            Order::OrderedAllowTimeout,
+            Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+            vec![ConnectionId::default()],
+            Version::ics20(),
+        );
+
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            ZERO_DURATION,
+        );
+
+        let ctx = context
+            .with_client(&ClientId::default(), client_height)
+            .with_connection(ConnectionId::default(), connection_end)
+            .with_channel(PortId::default(), ChannelId::default(), dest_channel_end)
+            .with_height(host_height)
+            .with_recv_sequence(PortId::default(), ChannelId::default(), 1.into());
+
+        // The packet is skipped without being received, advancing the next sequence
+        let output = process(&ctx, &msg).unwrap();
+
+        assert!(output.events.is_empty());
+        assert!(matches!(
+            output.result,
+            PacketResult::Recv(RecvPacketResult::Timeout { next_seq_recv, .. })
+                if next_seq_recv == 2.into()
+        ));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::core::ics04_channel::channel::State;
-use crate::core::ics04_channel::channel::{ChannelEnd, Counterparty, Order};
+use crate::core::ics04_channel::channel::{ChannelEnd, Counterparty};
 use crate::core::ics04_channel::events::TimeoutPacket;
 use crate::core::ics04_channel::handler::verify::{
     verify_next_sequence_recv, verify_packet_receipt_absence,
@@ -34,7 +34,7 @@ pub fn process<Ctx: ChannelReader>(
 
     let packet = &msg.packet;
 
-    let mut source_channel_end = ctx.channel_end(&packet.source_port, &packet.source_channel)?;
+    let source_channel_end = ctx.channel_end(&packet.source_port, &packet.source_channel)?;
 
     if !source_channel_end.state_matches(&State::Open) {
         return Err(Error::channel_closed(packet.source_channel.clone()));
@@ -91,7 +91,7 @@ pub fn process<Ctx: ChannelReader>(
         return Err(Error::incorrect_packet_commitment(packet.sequence));
     }
 
-    let result = if source_channel_end.order_matches(&Order::Ordered) {
+    let result = if source_channel_end.ordering().is_ordered() {
         if packet.sequence < msg.next_sequence_recv {
             return Err(Error::invalid_packet_sequence(
                 packet.sequence,
@@ -107,12 +107,20 @@ pub fn process<Ctx: ChannelReader>(
             &msg.proofs,
         )?;
 
-        source_channel_end.state = State::Closed;
+        // The `ORDERED_ALLOW_TIMEOUT` channels are left open, skipping the packet
+        let channel = source_channel_end
+            .ordering()
+            .closes_on_timeout()
+            .then(|| ChannelEnd {
+                state: State::Closed,
+                ..source_channel_end
+            });
+
         PacketResult::Timeout(TimeoutPacketResult {
             port_id: packet.source_port.clone(),
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
-            channel: Some(source_channel_end),
+            channel,
         })
     } else {
         verify_packet_receipt_absence(
@@ -154,6 +162,7 @@ mod tests {
     use crate::core::ics04_channel::handler::timeout::process;
     use crate::core::ics04_channel::msgs::timeout::test_util::get_dummy_raw_msg_timeout;
     use crate::core::ics04_channel::msgs::timeout::MsgTimeout;
+    use crate::core::ics04_channel::packet::PacketResult;
     use crate::core::ics04_channel::Version;
     use crate::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::events::IbcEvent;
@@ -209,6 +218,9 @@ mod tests {
         let mut source_ordered_channel_end = source_channel_end.clone();
         source_ordered_channel_end.ordering = Order::Ordered;
 
+        let mut source_allow_timeout_channel_end = source_channel_end.clone();
+        source_allow_timeout_channel_end.ordering = Order::OrderedAllowTimeout;
+
         let connection_end = ConnectionEnd::new(
             ConnectionState::Open,
             ClientId::default(),
@@ -272,6 +284,26 @@ mod tests {
                 msg: msg_ok.clone(),
                 want_pass: true,
             },
+            Test {
+                name: "Good parameters Ordered Allow Timeout Channel".to_string(),
+                ctx: context
+                    .clone()
+                    .with_client(&ClientId::default(), client_height)
+                    .with_connection(ConnectionId::default(), connection_end.clone())
+                    .with_channel(
+                        packet.source_port.clone(),
+                        packet.source_channel.clone(),
+                        source_allow_timeout_channel_end,
+                    )
+                    .with_packet_commitment(
+                        msg_ok.packet.source_port.clone(),
+                        msg_ok.packet.source_channel.clone(),
+                        msg_ok.packet.sequence,
+                        data.clone(),
+                    ),
+                msg: msg_ok.clone(),
+                want_pass: true,
+            },
             Test {
                 name: "Good parameters Ordered Channel".to_string(),
                 ctx: context
@@ -318,6 +350,20 @@ mod tests {
                     for e in proto_output.events.iter() {
                         assert!(matches!(e, &IbcEvent::TimeoutPacket(_)));
                     }
+
+                    // Only the timeouts on `ORDERED` channels close them
+                    let ordering = test
+                        .ctx
+                        .channel_end(
+                            &test.msg.packet.source_port,
+                            &test.msg.packet.source_channel,
+                        )
+                        .unwrap()
+                        .ordering;
+
+                    if let PacketResult::Timeout(result) = proto_output.result {
+                        assert_eq!(result.channel.is_some(), ordering.closes_on_timeout());
+                    }
                 }
                 Err(e) => {
                     assert!(
@@ -1,5 +1,5 @@
 use crate::core::ics04_channel::channel::State;
-use crate::core::ics04_channel::channel::{ChannelEnd, Counterparty, Order};
+use crate::core::ics04_channel::channel::{ChannelEnd, Counterparty};
 use crate::core::ics04_channel::events::TimeoutOnClosePacket;
 use crate::core::ics04_channel::handler::verify::verify_channel_proofs;
 use crate::core::ics04_channel::handler::verify::{
@@ -88,7 +88,7 @@ pub fn process<Ctx: ChannelReader>(
         &proofs,
     )?;
 
-    let result = if source_channel_end.order_matches(&Order::Ordered) {
+    let result = if source_channel_end.ordering().is_ordered() {
         if packet.sequence < msg.next_sequence_recv {
             return Err(Error::invalid_packet_sequence(
                 packet.sequence,
@@ -0,0 +1,262 @@
+//! Types of the channel upgrade handshake, by which the two ends of an open channel
+//! agree on new parameters, i.e. a new version, ordering or connection, before
+//! flushing their in-flight packets and reopening the channel with the new parameters.
+
+use crate::prelude::*;
+
+use core::str::FromStr;
+
+use ibc_proto::protobuf::Protobuf;
+use serde::{Deserialize, Serialize};
+
+use ibc_proto::ibc::core::channel::v1::{
+    ErrorReceipt as RawErrorReceipt, Timeout as RawTimeout, Upgrade as RawUpgrade,
+    UpgradeFields as RawUpgradeFields,
+};
+
+use crate::core::ics02_client::height::Height;
+use crate::core::ics04_channel::channel::Order;
+use crate::core::ics04_channel::error::Error;
+use crate::core::ics04_channel::packet::Sequence;
+use crate::core::ics04_channel::timeout::TimeoutHeight;
+use crate::core::ics04_channel::Version;
+use crate::core::ics24_host::identifier::ConnectionId;
+use crate::timestamp::{Expiry, Timestamp};
+
+/// The parameters of a channel end which are changed by an upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeFields {
+    pub ordering: Order,
+    pub connection_hops: Vec<ConnectionId>,
+    pub version: Version,
+}
+
+impl UpgradeFields {
+    pub fn new(ordering: Order, connection_hops: Vec<ConnectionId>, version: Version) -> Self {
+        Self {
+            ordering,
+            connection_hops,
+            version,
+        }
+    }
+}
+
+impl Protobuf<RawUpgradeFields> for UpgradeFields {}
+
+impl TryFrom<RawUpgradeFields> for UpgradeFields {
+    type Error = Error;
+
+    fn try_from(value: RawUpgradeFields) -> Result<Self, Self::Error> {
+        let connection_hops = value
+            .connection_hops
+            .iter()
+            .map(|conn_id| ConnectionId::from_str(conn_id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::identifier)?;
+
+        Ok(Self {
+            ordering: Order::from_i32(value.ordering)?,
+            connection_hops,
+            version: value.version.into(),
+        })
+    }
+}
+
+impl From<UpgradeFields> for RawUpgradeFields {
+    fn from(value: UpgradeFields) -> Self {
+        Self {
+            ordering: value.ordering as i32,
+            connection_hops: value
+                .connection_hops
+                .iter()
+                .map(|conn_id| conn_id.to_string())
+                .collect(),
+            version: value.version.to_string(),
+        }
+    }
+}
+
+/// The deadline of an upgrade on the counterparty chain, after which the
+/// upgrade can be cancelled on the chain which proposed it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeTimeout {
+    pub height: TimeoutHeight,
+    pub timestamp: Timestamp,
+}
+
+impl UpgradeTimeout {
+    /// Whether the upgrade times out at the given height and time of the counterparty chain.
+    pub fn has_expired(&self, height: Height, timestamp: Timestamp) -> bool {
+        let height_expired = match self.height {
+            TimeoutHeight::At(timeout_height) => height >= timeout_height,
+            TimeoutHeight::Never => false,
+        };
+
+        // The timestamp does not expire if it is not set
+        let timestamp_expired = timestamp.check_expiry(&self.timestamp) == Expiry::Expired;
+
+        height_expired || timestamp_expired
+    }
+}
+
+impl Protobuf<RawTimeout> for UpgradeTimeout {}
+
+impl TryFrom<RawTimeout> for UpgradeTimeout {
+    type Error = Error;
+
+    fn try_from(value: RawTimeout) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: value
+                .height
+                .try_into()
+                .map_err(|_| Error::invalid_timeout_height())?,
+            timestamp: Timestamp::from_nanoseconds(value.timestamp)
+                .map_err(Error::invalid_packet_timestamp)?,
+        })
+    }
+}
+
+impl From<UpgradeTimeout> for RawTimeout {
+    fn from(value: UpgradeTimeout) -> Self {
+        Self {
+            height: value.height.into(),
+            timestamp: value.timestamp.nanoseconds(),
+        }
+    }
+}
+
+/// An upgrade proposed by a channel end, along with its timeout on the counterparty
+/// chain, and the sequence of the next packet sent on the channel before its upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgrade {
+    pub fields: UpgradeFields,
+    pub timeout: UpgradeTimeout,
+    pub next_sequence_send: Sequence,
+}
+
+impl Protobuf<RawUpgrade> for Upgrade {}
+
+impl TryFrom<RawUpgrade> for Upgrade {
+    type Error = Error;
+
+    fn try_from(value: RawUpgrade) -> Result<Self, Self::Error> {
+        Ok(Self {
+            fields: value
+                .fields
+                .ok_or_else(Error::missing_upgrade_fields)?
+                .try_into()?,
+            timeout: value
+                .timeout
+                .ok_or_else(Error::missing_upgrade_timeout)?
+                .try_into()?,
+            next_sequence_send: value.next_sequence_send.into(),
+        })
+    }
+}
+
+impl From<Upgrade> for RawUpgrade {
+    fn from(value: Upgrade) -> Self {
+        Self {
+            fields: Some(value.fields.into()),
+            timeout: Some(value.timeout.into()),
+            next_sequence_send: value.next_sequence_send.into(),
+        }
+    }
+}
+
+/// The receipt written by a channel end which aborted the upgrade of the given sequence,
+/// which is proven to its counterparty to cancel the upgrade there too.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReceipt {
+    pub sequence: Sequence,
+    pub message: String,
+}
+
+impl Protobuf<RawErrorReceipt> for ErrorReceipt {}
+
+impl TryFrom<RawErrorReceipt> for ErrorReceipt {
+    type Error = Error;
+
+    fn try_from(value: RawErrorReceipt) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence: value.sequence.into(),
+            message: value.message,
+        })
+    }
+}
+
+impl From<ErrorReceipt> for RawErrorReceipt {
+    fn from(value: ErrorReceipt) -> Self {
+        Self {
+            sequence: value.sequence.into(),
+            message: value.message,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use crate::prelude::*;
+
+    use ibc_proto::ibc::core::channel::v1::{
+        Timeout as RawTimeout, Upgrade as RawUpgrade, UpgradeFields as RawUpgradeFields,
+    };
+    use ibc_proto::ibc::core::client::v1::Height as RawHeight;
+
+    use crate::core::ics24_host::identifier::ConnectionId;
+
+    /// Returns a dummy `RawUpgrade`, for testing only!
+    pub fn get_dummy_raw_upgrade(timeout_height: u64) -> RawUpgrade {
+        RawUpgrade {
+            fields: Some(RawUpgradeFields {
+                ordering: 1,
+                connection_hops: vec![ConnectionId::default().to_string()],
+                version: "ics20-2".to_string(),
+            }),
+            timeout: Some(RawTimeout {
+                height: Some(RawHeight {
+                    revision_number: 0,
+                    revision_height: timeout_height,
+                }),
+                timestamp: 0,
+            }),
+            next_sequence_send: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::get_dummy_raw_upgrade;
+    use super::*;
+
+    #[test]
+    fn upgrade_to_and_from_raw() {
+        let raw = get_dummy_raw_upgrade(10);
+        let upgrade = Upgrade::try_from(raw.clone()).unwrap();
+        assert_eq!(RawUpgrade::from(upgrade), raw);
+
+        let missing_timeout = RawUpgrade {
+            timeout: None,
+            ..raw
+        };
+        assert!(Upgrade::try_from(missing_timeout).is_err());
+    }
+
+    #[test]
+    fn upgrade_timeouts() {
+        let upgrade = Upgrade::try_from(get_dummy_raw_upgrade(10)).unwrap();
+        let now = Timestamp::now();
+
+        assert!(!upgrade.timeout.has_expired(Height::new(0, 9).unwrap(), now));
+        assert!(upgrade
+            .timeout
+            .has_expired(Height::new(0, 10).unwrap(), now));
+
+        let timeout = UpgradeTimeout {
+            height: TimeoutHeight::Never,
+            timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+        };
+        assert!(timeout.has_expired(Height::new(0, 1).unwrap(), now));
+    }
+}
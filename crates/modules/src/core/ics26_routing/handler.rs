@@ -111,6 +111,17 @@ where
                 return Ok(handler_builder.with_result(()));
             }
 
+            // The packets which timed out are skipped without being delivered to the module
+            if matches!(
+                packet_result,
+                PacketResult::Recv(RecvPacketResult::Timeout { .. })
+            ) {
+                ctx.store_packet_result(packet_result)
+                    .map_err(Error::ics04_channel)?;
+
+                return Ok(handler_builder.with_result(()));
+            }
+
             let cb_result = ics4_packet_callback(ctx, &module_id, &msg, &mut handler_builder);
             cb_result.map_err(Error::ics04_channel)?;
 
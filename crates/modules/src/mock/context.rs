@@ -201,6 +201,7 @@ impl MockContext {
                 Some(MockClientState::new(MockHeader::new(client_state_height)).into_box()),
                 MockConsensusState::new(MockHeader::new(cs_height)).into_box(),
             ),
+            ClientType::Wasm => panic!("the mock context does not host 08-wasm clients"),
             // If it's a Tendermint client, we need TM states.
             ClientType::Tendermint => {
                 let light_block = HostBlock::generate_tm_block(
@@ -252,6 +253,7 @@ impl MockContext {
                 Some(MockClientState::new(MockHeader::new(client_state_height)).into_box()),
                 MockConsensusState::new(MockHeader::new(cs_height)).into_box(),
             ),
+            ClientType::Wasm => panic!("the mock context does not host 08-wasm clients"),
             // If it's a Tendermint client, we need TM states.
             ClientType::Tendermint => {
                 let light_block = HostBlock::generate_tm_block(
@@ -271,6 +273,7 @@ impl MockContext {
         let prev_consensus_state = match client_type {
             // If it's a mock client, create the corresponding mock states.
             ClientType::Mock => MockConsensusState::new(MockHeader::new(prev_cs_height)).into_box(),
+            ClientType::Wasm => panic!("the mock context does not host 08-wasm clients"),
             // If it's a Tendermint client, we need TM states.
             ClientType::Tendermint => {
                 let light_block = HostBlock::generate_tm_block(
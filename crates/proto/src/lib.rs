@@ -143,6 +143,11 @@ pub mod ibc {
                 include_proto!("ibc.applications.ibc_query.v1.rs");
             }
         }
+        pub mod fee {
+            pub mod v1 {
+                include_proto!("ibc.applications.fee.v1.rs");
+            }
+        }
         pub mod interchain_accounts {
             pub mod v1 {
                 include_proto!("ibc.applications.interchain_accounts.v1.rs");
@@ -202,12 +207,44 @@ pub mod ibc {
                 include_proto!("ibc.lightclients.tendermint.v1.rs");
             }
         }
+        pub mod wasm {
+            pub mod v1 {
+                include_proto!("ibc.lightclients.wasm.v1.rs");
+            }
+        }
     }
     pub mod mock {
         include_proto!("ibc.mock.rs");
     }
 }
 
+pub mod sdk {
+    pub mod auction {
+        pub mod v1 {
+            /// MsgAuctionBid defines a request type for sending bids to the auction
+            /// module of the Skip block SDK, for the top-of-block placement of a bundle
+            /// of transactions.
+            /// TODO: generate it along with the other definitions of the block SDK
+            /// if/when its protos are vendored by the proto compiler
+            #[derive(Clone, PartialEq, ::prost::Message)]
+            pub struct MsgAuctionBid {
+                /// bidder is the address of the account that is submitting a bid to the
+                /// auction.
+                #[prost(string, tag = "1")]
+                pub bidder: ::prost::alloc::string::String,
+                /// bid is the amount of coins that the bidder is bidding to participate in
+                /// the auction.
+                #[prost(message, optional, tag = "2")]
+                pub bid: ::core::option::Option<super::super::super::cosmos::base::v1beta1::Coin>,
+                /// transactions are the bytes of the transactions that the bidder wants to
+                /// bundle together.
+                #[prost(bytes = "vec", repeated, tag = "3")]
+                pub transactions: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+            }
+        }
+    }
+}
+
 pub mod ics23 {
     include_proto!("ics23.rs");
 }
@@ -0,0 +1,172 @@
+/// ClientState defines a 08-wasm client state, wrapping the client state of the
+/// light client implemented by the contract of the given checksum.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientState {
+    /// bytes encoding the client state of the underlying light client
+    /// implemented as a Wasm contract.
+    #[prost(bytes="vec", tag="1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="2")]
+    pub checksum: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag="3")]
+    pub latest_height: ::core::option::Option<super::super::super::core::client::v1::Height>,
+}
+/// ConsensusState defines a 08-wasm consensus state, wrapping the consensus state
+/// of the underlying light client.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsensusState {
+    /// bytes encoding the consensus state of the underlying light client
+    /// implemented as a Wasm contract.
+    #[prost(bytes="vec", tag="1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+/// ClientMessage defines a 08-wasm client message, wrapping the headers and
+/// misbehaviours of the underlying light client.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientMessage {
+    #[prost(bytes="vec", tag="1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+/// QueryChecksumsRequest is the request type for the Query/Checksums RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryChecksumsRequest {
+    /// pagination defines an optional pagination for the request.
+    #[prost(message, optional, tag="1")]
+    pub pagination: ::core::option::Option<super::super::super::super::cosmos::base::query::v1beta1::PageRequest>,
+}
+/// QueryChecksumsResponse is the response type for the Query/Checksums RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryChecksumsResponse {
+    /// checksums is a list of the hex encoded checksums of all wasm codes stored.
+    #[prost(string, repeated, tag="1")]
+    pub checksums: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// pagination defines the pagination in the response.
+    #[prost(message, optional, tag="2")]
+    pub pagination: ::core::option::Option<super::super::super::super::cosmos::base::query::v1beta1::PageResponse>,
+}
+/// QueryCodeRequest is the request type for the Query/Code RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryCodeRequest {
+    /// checksum is a hex encoded string of the code stored.
+    #[prost(string, tag="1")]
+    pub checksum: ::prost::alloc::string::String,
+}
+/// QueryCodeResponse is the response type for the Query/Code RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryCodeResponse {
+    #[prost(bytes="vec", tag="1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+/// Generated client implementations.
+#[cfg(feature = "client")]
+pub mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Query service for wasm module
+    #[derive(Debug, Clone)]
+    pub struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl QueryClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> QueryClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            QueryClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Get all Wasm checksums
+        pub async fn checksums(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryChecksumsRequest>,
+        ) -> Result<tonic::Response<super::QueryChecksumsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ibc.lightclients.wasm.v1.Query/Checksums",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Get Wasm code for given checksum
+        pub async fn code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryCodeRequest>,
+        ) -> Result<tonic::Response<super::QueryCodeResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ibc.lightclients.wasm.v1.Query/Code",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
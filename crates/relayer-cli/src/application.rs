@@ -1,6 +1,6 @@
 //! Definition of the application, based on the Abscissa framework
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use abscissa_core::{
     application::{self, AppCell},
@@ -98,6 +98,13 @@ impl Application for CliApp {
         &self.state
     }
 
+    /// Load the configuration through [`ibc_relayer::config::load`], which resolves the
+    /// settings that the chains inherit, so that the commands and the library share it.
+    fn load_config(&mut self, path: &Path) -> Result<Self::Cfg, FrameworkError> {
+        ibc_relayer::config::load(path)
+            .map_err(|e| FrameworkErrorKind::ConfigError.context(e).into())
+    }
+
     /// Register all components used by this application.
     ///
     /// If you would like to add additional components to your application
@@ -115,22 +122,16 @@ impl Application for CliApp {
     /// time in app lifecycle when configuration would be loaded if
     /// possible.
     fn after_config(&mut self, config: Self::Cfg) -> Result<(), FrameworkError> {
-        use crate::config::Diagnostic;
-
         // Configure components
         let mut components = self.state.components_mut();
         components.after_config(&config)?;
 
-        if let Err(diagnostic) = validate_config(&config) {
-            match diagnostic {
-                Diagnostic::Warning(e) => {
-                    tracing::warn!("relayer may be misconfigured: {}", e);
-                }
-                Diagnostic::Error(e) => {
-                    return Err(FrameworkErrorKind::ConfigError.context(e).into());
-                }
-            }
-        };
+        let warnings =
+            validate_config(&config).map_err(|e| FrameworkErrorKind::ConfigError.context(e))?;
+
+        for e in warnings {
+            tracing::warn!("relayer may be misconfigured: {}", e);
+        }
 
         self.config.set_once(config);
 
@@ -173,11 +174,11 @@ impl Application for CliApp {
 
         if command.json {
             // Enable JSON by using the crate-level `Tracing`
-            let tracing = JsonTracing::new(config.global)?;
+            let tracing = JsonTracing::new(config.global, &config.telemetry.tracing)?;
             Ok(vec![Box::new(terminal), Box::new(tracing)])
         } else {
             // Use abscissa's tracing, which pretty-prints to the terminal obeying log levels
-            let tracing = PrettyTracing::new(config.global)?;
+            let tracing = PrettyTracing::new(config.global, &config.telemetry.tracing)?;
             Ok(vec![Box::new(terminal), Box::new(tracing)])
         }
     }
@@ -104,24 +104,36 @@ where
         id: chain_data.chain_id,
         r#type: default::chain_type(),
         rpc_addr: rpc_data.rpc_address,
+        secondary_rpc_addr: None,
+        archive_rpc_addr: None,
         websocket_addr: rpc_data.websocket,
         grpc_addr: grpc_address,
+        grpc_transport: Default::default(),
+        proxy: None,
         rpc_timeout: default::rpc_timeout(),
+        query_timeout: default::query_timeout(),
+        query_page_size: None,
+        broadcast_timeout: default::broadcast_timeout(),
         account_prefix: chain_data.bech32_prefix,
         key_name: String::new(),
+        key_names: Vec::new(),
         key_store_type: Store::default(),
+        key_store_folder: None,
+        tenant: None,
         store_prefix: "ibc".to_string(),
         default_gas: Some(100000),
         max_gas: Some(400000),
         gas_adjustment: None,
         gas_multiplier: Some(GasMultiplier::new(1.1).unwrap()),
         fee_granter: None,
+        fee_payer: None,
         max_msg_num: MaxMsgNum::default(),
         max_tx_size: MaxTxSize::default(),
         clock_drift: default::clock_drift(),
         max_block_time: default::max_block_time(),
         trusting_period: None,
         memo_prefix: Memo::default(),
+        tx_memo_template: None,
         proof_specs: Default::default(),
         trust_threshold: TrustThreshold::default(),
         gas_price: GasPrice {
@@ -129,8 +141,23 @@ where
             denom: asset.base.to_owned(),
         },
         packet_filter: packet_filter.unwrap_or_default(),
+        quotas: Default::default(),
+        top_of_block: Default::default(),
+        event_source: Default::default(),
+        fee_replenishment: None,
+        dynamic_gas_price: None,
+        remote_signer: None,
+        client_tls: None,
+        priorities: Vec::new(),
+        packet_limits: Vec::new(),
+        schedules: Vec::new(),
+        min_fees: Vec::new(),
+        channel_versions: Vec::new(),
+        pre_send_check: Default::default(),
         address_type: AddressType::default(),
+        check_query_proof_consistency: false,
         sequential_batch_tx: false,
+        client_wrapper: None,
         extension_options: Vec::new(),
     })
 }
@@ -1,25 +1,34 @@
 //! Definition of all the Hermes subcommands
 
+mod audit;
 mod clear;
 mod completions;
 mod config;
 mod create;
+mod devnet;
+mod dry_run;
 mod health;
 mod keys;
 mod listen;
 mod misbehaviour;
+mod path;
 mod query;
+mod replay;
 mod start;
+mod state;
+mod stats;
+mod top;
 mod tx;
 mod update;
 mod upgrade;
 mod version;
 
 use self::{
-    clear::ClearCmds, completions::CompletionsCmd, config::ConfigCmd, create::CreateCmds,
-    health::HealthCheckCmd, keys::KeysCmd, listen::ListenCmd, misbehaviour::MisbehaviourCmd,
-    query::QueryCmd, start::StartCmd, tx::TxCmd, update::UpdateCmds, upgrade::UpgradeCmds,
-    version::VersionCmd,
+    audit::AuditCmds, clear::ClearCmds, completions::CompletionsCmd, config::ConfigCmd,
+    create::CreateCmds, devnet::DevnetCmds, dry_run::DryRunCmds, health::HealthCheckCmd,
+    keys::KeysCmd, listen::ListenCmd, misbehaviour::MisbehaviourCmd, path::PathCmds,
+    query::QueryCmd, replay::ReplayCmd, start::StartCmd, state::StateCmds, stats::StatsCmds,
+    top::TopCmd, tx::TxCmd, update::UpdateCmds, upgrade::UpgradeCmds, version::VersionCmd,
 };
 
 use core::time::Duration;
@@ -89,6 +98,37 @@ pub enum CliCmd {
     /// Performs a health check of all chains in the the config
     HealthCheck(HealthCheckCmd),
 
+    /// Show a live dashboard of a running relayer
+    Top(TopCmd),
+
+    /// Simulate the messages relaying the pending packets of a path, without broadcasting them
+    #[clap(subcommand)]
+    DryRun(DryRunCmds),
+
+    /// Pause or resume relaying on a path of a running relayer
+    #[clap(subcommand)]
+    Path(PathCmds),
+
+    /// Replay the events recorded in a journal, answering the queries
+    /// to the chains from fixtures and recording the messages submitted to them
+    Replay(ReplayCmd),
+
+    /// Export the IBC state of the chains
+    #[clap(subcommand)]
+    State(StateCmds),
+
+    /// Report the historical performance of the relayer from its submission log
+    #[clap(subcommand)]
+    Stats(StatsCmds),
+
+    /// Cross-check the state of objects on the chains at both of their ends
+    #[clap(subcommand)]
+    Audit(AuditCmds),
+
+    /// Run local chains for developing and testing IBC applications
+    #[clap(subcommand)]
+    Devnet(DevnetCmds),
+
     /// Generate auto-complete scripts for different shells.
     #[clap(display_order = 1000)]
     Completions(CompletionsCmd),
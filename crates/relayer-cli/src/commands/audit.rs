@@ -0,0 +1,14 @@
+//! `hermes audit` subcommands, cross-checking the state of objects on the chains at both ends.
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+mod path;
+
+/// `audit` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum AuditCmds {
+    /// Cross-check the commitments, receipts, acknowledgements and next sequences of a
+    /// channel on both of its ends, and report the inconsistencies along with how to fix them
+    Path(path::AuditPathCmd),
+}
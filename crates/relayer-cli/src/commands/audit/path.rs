@@ -0,0 +1,141 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::audit::{audit_channel, ChannelAudit};
+use ibc_relayer::chain::counterparty::channel_on_destination;
+use ibc_relayer::chain::handle::BaseChainHandle;
+
+use crate::cli_utils::spawn_chain_counterparty;
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct AuditPathCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain at one end of the channel"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        help_heading = "REQUIRED",
+        help = "Port identifier on the chain given by <CHAIN_ID>"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Channel identifier on the chain given by <CHAIN_ID>"
+    )]
+    channel_id: ChannelId,
+}
+
+impl AuditPathCmd {
+    fn execute(&self) -> Result<ChannelAudit, Error> {
+        let config = app_config();
+
+        let (chains, chan_conn_cli) = spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.chain_id,
+            &self.port_id,
+            &self.channel_id,
+        )?;
+
+        let counterparty_channel = channel_on_destination(
+            &chan_conn_cli.channel,
+            &chan_conn_cli.connection,
+            &chains.dst,
+        )
+        .map_err(Error::supervisor)?
+        .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel.clone()))?;
+
+        audit_channel(
+            &chains.src,
+            &chains.dst,
+            &chan_conn_cli.channel,
+            &counterparty_channel,
+        )
+        .map_err(Error::supervisor)
+    }
+}
+
+impl Runnable for AuditPathCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(audit) if audit.consistent => Output::success(audit).exit(),
+            // Report the inconsistencies with an error status, for scripts to catch them
+            Ok(audit) => Output::with_error().with_result(audit).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuditPathCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_audit_path() {
+        assert_eq!(
+            AuditPathCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap()
+            },
+            AuditPathCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_audit_path_chan_alias() {
+        assert_eq!(
+            AuditPathCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap()
+            },
+            AuditPathCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--chan",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_audit_path_no_channel() {
+        assert!(
+            AuditPathCmd::try_parse_from(&["test", "--chain", "chain_id", "--port", "port_id"])
+                .is_err()
+        )
+    }
+}
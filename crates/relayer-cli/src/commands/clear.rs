@@ -4,6 +4,7 @@ use abscissa_core::{Command, FrameworkErrorKind, Runnable};
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::events::IbcEvent;
+use ibc::Height;
 use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
 use ibc_relayer::config::Config;
 use ibc_relayer::link::error::LinkError;
@@ -64,6 +65,20 @@ pub struct ClearPacketsCmd {
         help = "use the given signing key for the counterparty chain (default: `counterparty_key_name` config)"
     )]
     counterparty_key_name: Option<String>,
+
+    #[clap(
+        long = "from-height",
+        value_name = "HEIGHT",
+        help = "Only scan the blocks of the chain from the given height for the events of the packets (default: the whole history)"
+    )]
+    from_height: Option<u64>,
+
+    #[clap(
+        long = "counterparty-from-height",
+        value_name = "HEIGHT",
+        help = "Only scan the blocks of the counterparty chain from the given height for the events of the packets (default: the whole history)"
+    )]
+    counterparty_from_height: Option<u64>,
 }
 
 impl Override<Config> for ClearPacketsCmd {
@@ -108,6 +123,17 @@ impl Runnable for ClearPacketsCmd {
             }
         }
 
+        let scan_from = |chain_id: ChainId, height: Option<u64>| {
+            height.map(|height| {
+                Height::new(chain_id.version(), height).unwrap_or_else(|e| {
+                    Output::error(format!("invalid height {}: {}", height, e)).exit()
+                })
+            })
+        };
+
+        let src_scan_from = scan_from(chains.src.id(), self.from_height);
+        let dst_scan_from = scan_from(chains.dst.id(), self.counterparty_from_height);
+
         let mut ev_list = vec![];
 
         // Construct links in both directions.
@@ -127,15 +153,19 @@ impl Runnable for ClearPacketsCmd {
         // Schedule RecvPacket messages for pending packets in both directions.
         // This may produce pending acks which will be processed in the next phase.
         run_and_collect_events(&mut ev_list, || {
-            fwd_link.relay_recv_packet_and_timeout_messages()
+            fwd_link.relay_recv_packet_and_timeout_messages_from(None, src_scan_from)
         });
         run_and_collect_events(&mut ev_list, || {
-            rev_link.relay_recv_packet_and_timeout_messages()
+            rev_link.relay_recv_packet_and_timeout_messages_from(None, dst_scan_from)
         });
 
         // Schedule AckPacket messages in both directions.
-        run_and_collect_events(&mut ev_list, || fwd_link.relay_ack_packet_messages());
-        run_and_collect_events(&mut ev_list, || rev_link.relay_ack_packet_messages());
+        run_and_collect_events(&mut ev_list, || {
+            fwd_link.relay_ack_packet_messages_from(src_scan_from)
+        });
+        run_and_collect_events(&mut ev_list, || {
+            rev_link.relay_ack_packet_messages_from(dst_scan_from)
+        });
 
         Output::success(ev_list).exit()
     }
@@ -169,6 +199,8 @@ mod tests {
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 key_name: None,
                 counterparty_key_name: None,
+                from_height: None,
+                counterparty_from_height: None,
             },
             ClearPacketsCmd::parse_from(&[
                 "test",
@@ -190,7 +222,9 @@ mod tests {
                 port_id: PortId::from_str("port_id").unwrap(),
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 key_name: None,
-                counterparty_key_name: None
+                counterparty_key_name: None,
+                from_height: None,
+                counterparty_from_height: None,
             },
             ClearPacketsCmd::parse_from(&[
                 "test",
@@ -213,6 +247,8 @@ mod tests {
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 key_name: Some("key_name".to_owned()),
                 counterparty_key_name: None,
+                from_height: None,
+                counterparty_from_height: None,
             },
             ClearPacketsCmd::parse_from(&[
                 "test",
@@ -237,6 +273,8 @@ mod tests {
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 key_name: None,
                 counterparty_key_name: Some("counterparty_key_name".to_owned()),
+                from_height: None,
+                counterparty_from_height: None,
             },
             ClearPacketsCmd::parse_from(&[
                 "test",
@@ -252,6 +290,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_clear_packets_from_heights() {
+        assert_eq!(
+            ClearPacketsCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                key_name: None,
+                counterparty_key_name: None,
+                from_height: Some(1000),
+                counterparty_from_height: Some(2000),
+            },
+            ClearPacketsCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07",
+                "--from-height",
+                "1000",
+                "--counterparty-from-height",
+                "2000"
+            ])
+        )
+    }
+
     #[test]
     fn test_clear_packets_no_chan() {
         assert!(ClearPacketsCmd::try_parse_from(&[
@@ -42,7 +42,23 @@ impl Runnable for ValidateCmd {
         // No need to output the underlying error, this is done already when the application boots.
         // See `application::CliApp::after_config`.
         match config::validate_config(&config) {
-            Ok(_) => Output::success("configuration is valid").exit(),
+            Ok(warnings) if warnings.is_empty() => Output::success("configuration is valid").exit(),
+            // The configurations raising warnings are reported as invalid, for the checks
+            // running this command to catch the misconfigurations they point at
+            Ok(warnings) => {
+                let count = warnings.len();
+                let warnings = warnings
+                    .iter()
+                    .map(|e| format!("- {}", e))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                Output::error(format!(
+                    "configuration is invalid, found {} warning(s):\n{}",
+                    count, warnings
+                ))
+                .exit()
+            }
             Err(_) => Output::error("configuration is invalid").exit(),
         }
     }
@@ -103,7 +103,7 @@ pub struct CreateChannelCommand {
     #[clap(
         long = "order",
         value_name = "ORDER",
-        help = "The channel ordering, valid options 'unordered' (default) and 'ordered'",
+        help = "The channel ordering, valid options 'unordered' (default), 'ordered' and 'ordered_allow_timeout'",
         default_value_t
     )]
     order: Order,
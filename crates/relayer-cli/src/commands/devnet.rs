@@ -0,0 +1,14 @@
+//! `hermes devnet` subcommands, running local chains for developing and testing IBC applications.
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+mod node;
+mod up;
+
+/// `devnet` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum DevnetCmds {
+    /// Start two local chains connected by a transfer channel, and relay between them
+    Up(up::DevnetUpCmd),
+}
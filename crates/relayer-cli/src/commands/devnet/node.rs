@@ -0,0 +1,192 @@
+//! Initialization and start of the full node of a local chain, through the
+//! CLI of its binary, e.g. `gaiad` or `simd`.
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use eyre::eyre;
+use ibc::core::ics24_host::identifier::ChainId;
+use serde::Deserialize;
+
+/// Amount of tokens of each denomination given to the accounts in the genesis
+const GENESIS_COINS: &str = "100000000000stake,100000000000samoleans";
+
+/// Amount of tokens staked by the validator
+const VALIDATOR_STAKE: &str = "100000000000stake";
+
+/// A key created with the binary of the chain.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NodeKey {
+    pub address: String,
+    pub mnemonic: String,
+}
+
+/// The ports on which the full node of a chain listens.
+#[derive(Copy, Clone, Debug)]
+pub struct Ports {
+    pub rpc: u16,
+    pub p2p: u16,
+    pub grpc: u16,
+    pub pprof: u16,
+}
+
+impl Ports {
+    /// The ports of the `index`-th chain of the devnet, from the given base port.
+    pub fn new(base: u16, index: u16) -> Self {
+        let base = base + 10 * index;
+
+        Self {
+            rpc: base,
+            p2p: base + 1,
+            grpc: base + 2,
+            pprof: base + 3,
+        }
+    }
+}
+
+/// The full node of a local chain.
+pub struct Node {
+    pub binary: String,
+    pub chain_id: ChainId,
+    pub home: PathBuf,
+    pub ports: Ports,
+}
+
+impl Node {
+    /// Initialize the home of the node, wiping any previous state, with a genesis
+    /// in which a validator, a user and the relayer are funded.
+    ///
+    /// Returns the keys of the user and of the relayer.
+    pub fn init(&self) -> eyre::Result<(NodeKey, NodeKey)> {
+        if self.home.exists() {
+            fs::remove_dir_all(&self.home)?;
+        }
+
+        let chain_id = self.chain_id.to_string();
+
+        self.run(&["init", &chain_id, "--chain-id", &chain_id])?;
+
+        let validator = self.add_key("validator")?;
+        let user = self.add_key("user")?;
+        let relayer = self.add_key("relayer")?;
+
+        for key in [&validator, &user, &relayer] {
+            self.run(&["add-genesis-account", &key.address, GENESIS_COINS])?;
+        }
+
+        self.run(&[
+            "gentx",
+            "validator",
+            VALIDATOR_STAKE,
+            "--keyring-backend",
+            "test",
+            "--chain-id",
+            &chain_id,
+        ])?;
+
+        self.run(&["collect-gentxs"])?;
+
+        self.configure()?;
+
+        Ok((user, relayer))
+    }
+
+    /// Start the node in the background, logging to `<home>.log`.
+    pub fn start(&self) -> eyre::Result<Child> {
+        let log = File::create(self.home.with_extension("log"))?;
+
+        let child = Command::new(&self.binary)
+            .arg("--home")
+            .arg(&self.home)
+            .args([
+                "start",
+                "--pruning=nothing",
+                "--log_level=error",
+                "--grpc-web.enable=false",
+            ])
+            .arg(format!("--grpc.address=127.0.0.1:{}", self.ports.grpc))
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .map_err(|e| eyre!("failed to start `{}`: {}", self.binary, e))?;
+
+        Ok(child)
+    }
+
+    /// Create a key in the test keyring of the node.
+    fn add_key(&self, name: &str) -> eyre::Result<NodeKey> {
+        let output = self.run(&[
+            "keys",
+            "add",
+            name,
+            "--keyring-backend",
+            "test",
+            "--output",
+            "json",
+        ])?;
+
+        // Some versions of the SDK print the key on stderr, after warnings
+        let start = output
+            .find('{')
+            .ok_or_else(|| eyre!("no key in the output of `keys add`: {}", output))?;
+
+        let key = serde_json::Deserializer::from_str(&output[start..])
+            .into_iter::<NodeKey>()
+            .next()
+            .ok_or_else(|| eyre!("no key in the output of `keys add`: {}", output))??;
+
+        Ok(key)
+    }
+
+    /// Listen on the ports of the node, and produce blocks every second.
+    fn configure(&self) -> eyre::Result<()> {
+        let path = self.home.join("config").join("config.toml");
+
+        let config = fs::read_to_string(&path)?
+            .replace(
+                "\"tcp://127.0.0.1:26657\"",
+                &format!("\"tcp://127.0.0.1:{}\"", self.ports.rpc),
+            )
+            .replace(
+                "\"tcp://0.0.0.0:26656\"",
+                &format!("\"tcp://127.0.0.1:{}\"", self.ports.p2p),
+            )
+            .replace(
+                "\"localhost:6060\"",
+                &format!("\"localhost:{}\"", self.ports.pprof),
+            )
+            .replace("timeout_commit = \"5s\"", "timeout_commit = \"1s\"")
+            .replace("timeout_propose = \"3s\"", "timeout_propose = \"1s\"");
+
+        fs::write(&path, config)?;
+
+        Ok(())
+    }
+
+    /// Run the given command of the binary against the home of the node,
+    /// returning its combined output.
+    fn run(&self, args: &[&str]) -> eyre::Result<String> {
+        let output = Command::new(&self.binary)
+            .arg("--home")
+            .arg(&self.home)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| eyre!("failed to run `{}`: {}", self.binary, e))?;
+
+        let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+        combined.push_str(&String::from_utf8_lossy(&output.stderr));
+
+        if output.status.success() {
+            Ok(combined)
+        } else {
+            Err(eyre!(
+                "`{} {}` failed: {}",
+                self.binary,
+                args.join(" "),
+                combined
+            ))
+        }
+    }
+}
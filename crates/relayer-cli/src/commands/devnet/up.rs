@@ -0,0 +1,332 @@
+use core::str::FromStr;
+use core::time::Duration;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::thread;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use eyre::eyre;
+
+use ibc::core::ics04_channel::channel::Order;
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::{ChainId, PortId};
+use ibc_relayer::chain::handle::{CachingChainHandle, ChainHandle};
+use ibc_relayer::channel::Channel;
+use ibc_relayer::config::{self, default::connection_delay, Config};
+use ibc_relayer::connection::Connection;
+use ibc_relayer::foreign_client::ForeignClient;
+use ibc_relayer::keyring::{HDPath, KeyRing, Store};
+
+use super::node::{Node, NodeKey, Ports};
+use crate::cli_utils::spawn_chain_runtime;
+use crate::commands::start::make_supervisor;
+use crate::conclude::Output;
+use crate::prelude::*;
+
+/// Name of the key of the relayer on both chains
+const RELAYER_KEY_NAME: &str = "relayer";
+
+/// How many times the chains are checked for producing blocks, every second
+const MAX_STARTUP_ATTEMPTS: u32 = 60;
+
+/// The data structure that represents the arguments when invoking the `devnet up` CLI command.
+///
+/// `devnet up [--binary <BINARY>]`
+///
+/// The state of the chains started by a previous invocation of the command is wiped.
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct DevnetUpCmd {
+    #[clap(
+        long = "binary",
+        value_name = "BINARY",
+        default_value = "gaiad",
+        help = "Binary of the chains, e.g. `gaiad` or `simd`"
+    )]
+    binary: String,
+
+    #[clap(
+        long = "home",
+        value_name = "DIR",
+        help = "Directory holding the state of the chains, their logs and the config of the relayer (default: ~/.hermes/devnet)"
+    )]
+    home: Option<PathBuf>,
+
+    #[clap(
+        long = "a-chain",
+        value_name = "A_CHAIN_ID",
+        default_value = "ibc-0",
+        help = "Identifier of the first chain"
+    )]
+    chain_a: ChainId,
+
+    #[clap(
+        long = "b-chain",
+        value_name = "B_CHAIN_ID",
+        default_value = "ibc-1",
+        help = "Identifier of the second chain"
+    )]
+    chain_b: ChainId,
+
+    #[clap(
+        long = "base-port",
+        value_name = "PORT",
+        default_value = "27000",
+        help = "First of the ports on which the nodes listen, the nodes of the chains taking the next ten ports each"
+    )]
+    base_port: u16,
+}
+
+impl Runnable for DevnetUpCmd {
+    fn run(&self) {
+        let mut children = Vec::new();
+
+        if let Err(e) = self.up(&mut children) {
+            for child in children.iter_mut() {
+                let _ = child.kill();
+            }
+
+            Output::error(format!("failed to bring up the devnet: {}", e)).exit();
+        }
+    }
+}
+
+impl DevnetUpCmd {
+    fn up(&self, children: &mut Vec<Child>) -> eyre::Result<()> {
+        let home = match &self.home {
+            Some(home) => home.clone(),
+            None => dirs_next::home_dir()
+                .ok_or_else(|| eyre!("could not find the home directory"))?
+                .join(".hermes")
+                .join("devnet"),
+        };
+
+        fs::create_dir_all(&home)?;
+
+        // The keys of the relayer are kept apart from the ones in `~/.hermes/keys`
+        let keys_folder = home.join("keys");
+
+        let nodes = [&self.chain_a, &self.chain_b]
+            .into_iter()
+            .zip(0..)
+            .map(|(chain_id, index)| Node {
+                binary: self.binary.clone(),
+                chain_id: chain_id.clone(),
+                home: home.join(chain_id.as_str()),
+                ports: Ports::new(self.base_port, index),
+            })
+            .collect::<Vec<_>>();
+
+        let mut users = Vec::new();
+
+        for node in &nodes {
+            info!(
+                "initializing chain {} in {}",
+                node.chain_id,
+                node.home.display()
+            );
+
+            let (user, relayer) = node.init()?;
+            children.push(node.start()?);
+
+            users.push(user);
+
+            // The relayer is given the key funded in the genesis
+            restore_relayer_key(node, &relayer, &keys_folder)?;
+        }
+
+        let config_path = home.join("config.toml");
+        fs::write(&config_path, relayer_config(&nodes, &keys_folder))?;
+
+        let config = config::load(&config_path)?;
+
+        let chain_a = wait_for_chain(&config, &nodes[0])?;
+        let chain_b = wait_for_chain(&config, &nodes[1])?;
+
+        info!("creating the clients, connection and transfer channel between the chains");
+
+        let client_a = ForeignClient::new(chain_a.clone(), chain_b.clone())?;
+        let client_b = ForeignClient::new(chain_b, chain_a)?;
+        let connection = Connection::new(client_a, client_b, connection_delay())?;
+
+        let channel = Channel::new(
+            connection,
+            Order::Unordered,
+            PortId::transfer(),
+            PortId::transfer(),
+            Some(Version::ics20()),
+        )?;
+
+        info!(
+            "the devnet is up, with the config of the relayer at {}",
+            config_path.display()
+        );
+
+        for (node, user) in nodes.iter().zip(&users) {
+            info!(
+                "chain {}: RPC at http://127.0.0.1:{}, gRPC at http://127.0.0.1:{}, funded user {}",
+                node.chain_id, node.ports.rpc, node.ports.grpc, user.address
+            );
+        }
+
+        info!(
+            "transfer channel {} on {} to channel {} on {}",
+            channel
+                .a_side
+                .channel_id()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            self.chain_a,
+            channel
+                .b_side
+                .channel_id()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            self.chain_b,
+        );
+
+        let supervisor = make_supervisor::<CachingChainHandle>(config, false)
+            .map_err(|e| eyre!("failed to start relaying: {}", e))?;
+
+        info!("relaying between the chains, press Ctrl-C to stop the chains and the relayer");
+
+        supervisor.wait();
+
+        Ok(())
+    }
+}
+
+/// Add the key of the relayer on the chain of the given node to the keyring in the given folder.
+fn restore_relayer_key(node: &Node, key: &NodeKey, keys_folder: &Path) -> eyre::Result<()> {
+    let mut keyring = KeyRing::new_namespaced(
+        Store::Test,
+        "cosmos",
+        &node.chain_id,
+        None,
+        Some(keys_folder),
+    )?;
+
+    let hd_path =
+        HDPath::from_str("m/44'/118'/0'/0/0").map_err(|_| eyre!("invalid derivation path"))?;
+
+    let key_entry = keyring.key_from_mnemonic(&key.mnemonic, &hd_path, &Default::default())?;
+    keyring.add_key(RELAYER_KEY_NAME, key_entry)?;
+
+    Ok(())
+}
+
+/// Wait for the chain of the given node to produce blocks, and return a handle to it.
+fn wait_for_chain(config: &Config, node: &Node) -> eyre::Result<impl ChainHandle> {
+    for _ in 0..MAX_STARTUP_ATTEMPTS {
+        thread::sleep(Duration::from_secs(1));
+
+        if let Ok(chain) = spawn_chain_runtime(config, &node.chain_id) {
+            if matches!(chain.query_latest_height(), Ok(height) if height.revision_height() > 1) {
+                return Ok(chain);
+            }
+        }
+    }
+
+    Err(eyre!(
+        "chain {} did not start producing blocks, see its logs in {}",
+        node.chain_id,
+        node.home.with_extension("log").display()
+    ))
+}
+
+/// The config of the relayer, relaying packets between the chains of the given nodes
+/// with the keys in the given folder.
+fn relayer_config(nodes: &[Node], keys_folder: &Path) -> String {
+    let mut config = String::from(
+        "[global]
+log_level = 'info'
+
+[mode.clients]
+enabled = true
+refresh = true
+misbehaviour = false
+
+[mode.connections]
+enabled = false
+
+[mode.channels]
+enabled = false
+
+[mode.packets]
+enabled = true
+clear_interval = 100
+clear_on_start = true
+",
+    );
+
+    for node in nodes {
+        config.push_str(&format!(
+            "
+[[chains]]
+id = '{id}'
+rpc_addr = 'http://127.0.0.1:{rpc}'
+grpc_addr = 'http://127.0.0.1:{grpc}'
+websocket_addr = 'ws://127.0.0.1:{rpc}/websocket'
+account_prefix = 'cosmos'
+key_name = '{key_name}'
+key_store_folder = '{keys_folder}'
+store_prefix = 'ibc'
+gas_price = {{ price = 0.001, denom = 'stake' }}
+trusting_period = '14days'
+trust_threshold = {{ numerator = '1', denominator = '3' }}
+",
+            id = node.chain_id,
+            rpc = node.ports.rpc,
+            grpc = node.ports.grpc,
+            key_name = RELAYER_KEY_NAME,
+            keys_folder = keys_folder.display(),
+        ));
+    }
+
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DevnetUpCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_devnet_up_defaults() {
+        assert_eq!(
+            DevnetUpCmd {
+                binary: "gaiad".to_string(),
+                home: None,
+                chain_a: ChainId::from_string("ibc-0"),
+                chain_b: ChainId::from_string("ibc-1"),
+                base_port: 27000,
+            },
+            DevnetUpCmd::parse_from(&["test"])
+        )
+    }
+
+    #[test]
+    fn test_devnet_up_binary_and_chains() {
+        assert_eq!(
+            DevnetUpCmd {
+                binary: "simd".to_string(),
+                home: None,
+                chain_a: ChainId::from_string("alpha"),
+                chain_b: ChainId::from_string("beta"),
+                base_port: 27000,
+            },
+            DevnetUpCmd::parse_from(&[
+                "test",
+                "--binary",
+                "simd",
+                "--a-chain",
+                "alpha",
+                "--b-chain",
+                "beta"
+            ])
+        )
+    }
+}
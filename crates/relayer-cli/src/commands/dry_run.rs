@@ -0,0 +1,125 @@
+//! `hermes dry-run path`: build the messages relaying the pending packets of a path
+//! and simulate them on the chains, without broadcasting anything.
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_relayer::link::{Link, LinkParameters};
+
+use crate::application::app_config;
+use crate::cli_utils::spawn_chain_counterparty;
+use crate::conclude::Output;
+use crate::error::Error;
+
+/// `dry-run` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum DryRunCmds {
+    /// Build the messages relaying the pending packets, acknowledgements and timeouts
+    /// of a path in both directions, and simulate them on the chains they target.
+    /// The path is identified by the chain, port, and channel IDs at one of its ends.
+    /// Reports the expected gas, fees and failures, and the messages, without
+    /// broadcasting anything
+    Path(DryRunPathCmd),
+}
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct DryRunPathCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the port"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel"
+    )]
+    channel_id: ChannelId,
+}
+
+impl Runnable for DryRunPathCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chains = match spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.chain_id,
+            &self.port_id,
+            &self.channel_id,
+        ) {
+            Ok((chains, _)) => chains,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        let opts = LinkParameters {
+            src_port_id: self.port_id.clone(),
+            src_channel_id: self.channel_id.clone(),
+        };
+        let fwd_link = match Link::new_from_opts(chains.src, chains.dst, opts, false) {
+            Ok(link) => link,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+        let rev_link = match fwd_link.reverse(false) {
+            Ok(link) => link,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        let reports = [&fwd_link, &rev_link]
+            .iter()
+            .map(|link| link.dry_run())
+            .collect::<Result<Vec<_>, _>>();
+
+        match reports {
+            Ok(reports) => Output::success(reports).exit(),
+            Err(e) => Output::error(Error::link(e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DryRunPathCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_dry_run_path() {
+        assert_eq!(
+            DryRunPathCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+            },
+            DryRunPathCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07"
+            ])
+        )
+    }
+}
@@ -32,6 +32,16 @@ impl Runnable for HealthCheckCmd {
                 }
                 Err(e) => error!("failed to perform health check, reason: {}", e.detail()),
             }
+
+            // A watch-only relayer signs no transaction and may have no key
+            if !config.mode.watch_only {
+                match chain.check_key() {
+                    Ok(Healthy) => {}
+                    // The error is already printed in `Chain::check_key`
+                    Ok(Unhealthy(_)) => warn!("key is not usable"),
+                    Err(e) => error!("failed to check the key, reason: {}", e.detail()),
+                }
+            }
         }
 
         Output::success_msg("performed health check for all chains in the config").exit()
@@ -190,7 +190,13 @@ pub fn add_key(
     hd_path: &HDPath,
     overwrite: bool,
 ) -> eyre::Result<KeyEntry> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new_namespaced(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    )?;
 
     check_key_exists(&keyring, key_name, overwrite);
 
@@ -211,7 +217,13 @@ pub fn restore_key(
     let mnemonic_content =
         fs::read_to_string(mnemonic).map_err(|_| eyre!("error reading the mnemonic file"))?;
 
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new_namespaced(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    )?;
 
     check_key_exists(&keyring, key_name, overwrite);
 
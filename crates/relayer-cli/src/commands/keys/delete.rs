@@ -112,13 +112,25 @@ impl Runnable for KeysDeleteCmd {
 }
 
 pub fn delete_key(config: &ChainConfig, key_name: &str) -> eyre::Result<()> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new_namespaced(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    )?;
     keyring.remove_key(key_name)?;
     Ok(())
 }
 
 pub fn delete_all_keys(config: &ChainConfig) -> eyre::Result<()> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new_namespaced(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    )?;
     let keys = keyring.keys()?;
     for key in keys {
         keyring.remove_key(&key.0)?;
@@ -68,7 +68,13 @@ pub struct KeysListOptions {
 }
 
 pub fn list_keys(config: ChainConfig) -> eyre::Result<Vec<(String, KeyEntry)>> {
-    let keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let keyring = KeyRing::new_namespaced(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    )?;
     let keys = keyring.keys()?;
     Ok(keys)
 }
@@ -0,0 +1,230 @@
+//! `hermes path pause/resume`: pause and resume relaying on a path
+//! of a running relayer, through its REST API.
+
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::object::Object;
+use ibc_relayer::supervisor::cmd::PathSpec;
+
+use super::top::data::{parse_json, with_scheme};
+use crate::conclude::Output;
+use crate::prelude::*;
+
+/// Timeout for the request made to the relayer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `path` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum PathCmds {
+    /// Pause relaying on a path of a running relayer, in both directions. The path is
+    /// identified by the chain, port, and channel IDs at one of its ends. Its workers
+    /// keep their state but stop building and submitting messages until it is resumed
+    Pause(PausePathCmd),
+
+    /// Resume relaying on a path of a running relayer which was paused
+    Resume(ResumePathCmd),
+}
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct PausePathCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the port"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "rest-addr",
+        value_name = "ADDRESS",
+        help = "Address of the REST server of the relayer (default: taken from the [rest] section of the config)"
+    )]
+    rest_addr: Option<String>,
+}
+
+impl Runnable for PausePathCmd {
+    fn run(&self) {
+        let spec = path_spec(&self.chain_id, &self.port_id, &self.channel_id);
+
+        match control_path(self.rest_addr.as_deref(), "pause", &spec) {
+            Ok(objects) => Output::success(objects).exit(),
+            Err(e) => Output::error(format!("failed to pause {}: {}", spec, e)).exit(),
+        }
+    }
+}
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct ResumePathCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the port"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "rest-addr",
+        value_name = "ADDRESS",
+        help = "Address of the REST server of the relayer (default: taken from the [rest] section of the config)"
+    )]
+    rest_addr: Option<String>,
+}
+
+impl Runnable for ResumePathCmd {
+    fn run(&self) {
+        let spec = path_spec(&self.chain_id, &self.port_id, &self.channel_id);
+
+        match control_path(self.rest_addr.as_deref(), "resume", &spec) {
+            Ok(objects) => Output::success(objects).exit(),
+            Err(e) => Output::error(format!("failed to resume {}: {}", spec, e)).exit(),
+        }
+    }
+}
+
+fn path_spec(chain_id: &ChainId, port_id: &PortId, channel_id: &ChannelId) -> PathSpec {
+    PathSpec {
+        chain_id: chain_id.clone(),
+        port_id: port_id.clone(),
+        channel_id: channel_id.clone(),
+    }
+}
+
+/// Instruct the relayer to pause or resume the path, returning
+/// the objects whose workers were paused or resumed.
+fn control_path(
+    rest_addr: Option<&str>,
+    action: &str,
+    spec: &PathSpec,
+) -> Result<Vec<Object>, String> {
+    let rest_addr = match rest_addr {
+        Some(addr) => addr.to_string(),
+        None => {
+            let config = app_config();
+            format!("{}:{}", config.rest.host, config.rest.port)
+        }
+    };
+
+    let body = serde_json::to_string(spec).map_err(|e| e.to_string())?;
+
+    let response = ureq::post(&format!("{}/paths/{}", with_scheme(&rest_addr), action))
+        .timeout(REQUEST_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&body)
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+
+    parse_json(&response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PausePathCmd, ResumePathCmd};
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_pause_path() {
+        assert_eq!(
+            PausePathCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                rest_addr: None,
+            },
+            PausePathCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_resume_path_rest_addr() {
+        assert_eq!(
+            ResumePathCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                rest_addr: Some("127.0.0.1:4000".to_owned()),
+            },
+            ResumePathCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--chan",
+                "channel-07",
+                "--rest-addr",
+                "127.0.0.1:4000"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_pause_path_no_chain() {
+        assert!(PausePathCmd::try_parse_from(&[
+            "test",
+            "--port",
+            "port_id",
+            "--channel",
+            "channel-07"
+        ])
+        .is_err())
+    }
+}
@@ -170,6 +170,7 @@ impl Runnable for QueryClientConsensusCmd {
                 let res = chain.query_consensus_states(QueryConsensusStatesRequest {
                     client_id: self.client_id.clone(),
                     pagination: Some(PageRequest::all()),
+                    height: QueryHeight::Latest,
                 });
 
                 match res {
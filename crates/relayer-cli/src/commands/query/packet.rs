@@ -3,6 +3,7 @@ use abscissa_core::{Command, Runnable};
 
 mod ack;
 mod acks;
+mod by_tx;
 mod commitment;
 mod commitments;
 mod pending;
@@ -31,4 +32,7 @@ pub enum QueryPacketCmds {
 
     /// Output a summary of pending packets in both directions
     Pending(pending::QueryPendingPacketsCmd),
+
+    /// Query the packets sent and acknowledged by a transaction, and their relaying status
+    ByTx(by_tx::QueryPacketsByTxCmd),
 }
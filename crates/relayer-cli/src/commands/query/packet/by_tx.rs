@@ -0,0 +1,215 @@
+use core::str::FromStr;
+use std::collections::HashMap;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+use tendermint::abci::transaction::Hash;
+
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::IbcEvent;
+use ibc_relayer::chain::counterparty::channel_connection_client;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{
+    QueryTxHash, QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+};
+
+use crate::cli_utils::spawn_chain_runtime_generic;
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The relaying status of a packet or acknowledgement found in a transaction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RelayStatus {
+    /// The packet was not received on its destination chain yet
+    NotReceived,
+    /// The packet was received, but its acknowledgement was not relayed back to its source chain
+    AckPending,
+    /// The packet was acknowledged or timed out on its source chain
+    Completed,
+}
+
+/// A packet sent or acknowledged in a transaction, with its relaying status.
+#[derive(Clone, Debug, Serialize)]
+struct PacketStatus {
+    /// The type of the event emitted by the transaction
+    event: String,
+    sequence: Sequence,
+    src_port: PortId,
+    src_channel: ChannelId,
+    dst_port: PortId,
+    dst_channel: ChannelId,
+    /// The chain at the other end of the channel
+    counterparty_chain: ChainId,
+    status: RelayStatus,
+}
+
+/// The data structure that represents the arguments when invoking the `query packet by-tx` CLI command.
+///
+/// `query packet by-tx --chain <CHAIN_ID> --hash <HASH>`
+///
+/// The packets sent and the acknowledgements written by the transaction are looked up,
+/// and the counterparty chain of their channel is queried for their relaying status.
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct QueryPacketsByTxCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the transaction was executed"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "hash",
+        required = true,
+        value_name = "HASH",
+        help_heading = "REQUIRED",
+        help = "Hash of the transaction"
+    )]
+    hash: String,
+}
+
+impl QueryPacketsByTxCmd {
+    fn execute(&self) -> Result<Vec<PacketStatus>, Error> {
+        let config = app_config();
+
+        let hash =
+            Hash::from_str(&self.hash).map_err(|e| Error::invalid_hash(self.hash.clone(), e))?;
+
+        let chain = spawn_chain_runtime_generic::<BaseChainHandle>(&config, &self.chain_id)?;
+
+        let events = chain
+            .query_txs(QueryTxRequest::Transaction(QueryTxHash(hash)))
+            .map_err(Error::relayer)?;
+
+        // The counterparty chains, spawned once for all the packets on their channels
+        let mut counterparties: HashMap<ChainId, BaseChainHandle> = HashMap::new();
+        let mut statuses = Vec::new();
+
+        for event_with_height in events {
+            let (packet, sent) = match &event_with_height.event {
+                IbcEvent::SendPacket(e) => (&e.packet, true),
+                IbcEvent::WriteAcknowledgement(e) => (&e.packet, false),
+                _ => continue,
+            };
+
+            // The channel end on the chain of the transaction
+            let (port_id, channel_id) = if sent {
+                (&packet.source_port, &packet.source_channel)
+            } else {
+                (&packet.destination_port, &packet.destination_channel)
+            };
+
+            let counterparty_chain_id = channel_connection_client(&chain, port_id, channel_id)
+                .map_err(Error::supervisor)?
+                .client
+                .client_state
+                .chain_id();
+
+            let counterparty = match counterparties.get(&counterparty_chain_id) {
+                Some(counterparty) => counterparty.clone(),
+                None => {
+                    let counterparty = spawn_chain_runtime_generic::<BaseChainHandle>(
+                        &config,
+                        &counterparty_chain_id,
+                    )?;
+                    counterparties.insert(counterparty_chain_id.clone(), counterparty.clone());
+                    counterparty
+                }
+            };
+
+            let status = if sent {
+                packet_status(&chain, &counterparty, packet)?
+            } else {
+                packet_status(&counterparty, &chain, packet)?
+            };
+
+            statuses.push(PacketStatus {
+                event: event_with_height.event.event_type().as_str().to_string(),
+                sequence: packet.sequence,
+                src_port: packet.source_port.clone(),
+                src_channel: packet.source_channel.clone(),
+                dst_port: packet.destination_port.clone(),
+                dst_channel: packet.destination_channel.clone(),
+                counterparty_chain: counterparty_chain_id,
+                status,
+            });
+        }
+
+        Ok(statuses)
+    }
+}
+
+/// The relaying status of the given packet, sent from the `src` chain to the `dst` chain.
+fn packet_status(
+    src: &impl ChainHandle,
+    dst: &impl ChainHandle,
+    packet: &Packet,
+) -> Result<RelayStatus, Error> {
+    // The commitment of the packet is deleted on its source chain once it is
+    // acknowledged or timed out, and only then
+    let unacknowledged = src
+        .query_unreceived_acknowledgements(QueryUnreceivedAcksRequest {
+            port_id: packet.source_port.clone(),
+            channel_id: packet.source_channel.clone(),
+            packet_ack_sequences: vec![packet.sequence],
+        })
+        .map_err(Error::relayer)?;
+
+    if unacknowledged.is_empty() {
+        return Ok(RelayStatus::Completed);
+    }
+
+    let unreceived = dst
+        .query_unreceived_packets(QueryUnreceivedPacketsRequest {
+            port_id: packet.destination_port.clone(),
+            channel_id: packet.destination_channel.clone(),
+            packet_commitment_sequences: vec![packet.sequence],
+        })
+        .map_err(Error::relayer)?;
+
+    if unreceived.is_empty() {
+        Ok(RelayStatus::AckPending)
+    } else {
+        Ok(RelayStatus::NotReceived)
+    }
+}
+
+// cargo run --bin hermes -- query packet by-tx --chain ibc-0 --hash B8E78AD83810239E21863AC7B5FC4F99396ABB39EB534F721EEF43A4979C2821
+impl Runnable for QueryPacketsByTxCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(statuses) => Output::success(statuses).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryPacketsByTxCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_query_packets_by_tx() {
+        assert_eq!(
+            QueryPacketsByTxCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                hash: "abcdefg".to_owned()
+            },
+            QueryPacketsByTxCmd::parse_from(&["test", "--chain", "chain_id", "--hash", "abcdefg"])
+        )
+    }
+
+    #[test]
+    fn test_query_packets_by_tx_no_hash() {
+        assert!(QueryPacketsByTxCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err())
+    }
+}
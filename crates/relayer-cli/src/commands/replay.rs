@@ -0,0 +1,155 @@
+//! `hermes replay`: replay the events recorded in a journal by `hermes start --record-journal`,
+//! answering the queries made to the chains from fixtures and recording the messages
+//! submitted to them instead of sending them.
+
+use core::time::Duration;
+use std::path::PathBuf;
+use std::thread;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_relayer::registry::SharedRegistry;
+use ibc_relayer::replay::fixtures::Fixtures;
+use ibc_relayer::replay::{journal, Replay, SubmittedMsgs};
+use ibc_relayer::supervisor::{spawn_supervisor, SupervisorOptions};
+
+use crate::conclude::Output;
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct ReplayCmd {
+    #[clap(
+        long = "journal",
+        required = true,
+        value_name = "FILE",
+        help_heading = "REQUIRED",
+        help = "Journal of the events to replay, as recorded with `hermes start --record-journal`"
+    )]
+    journal: PathBuf,
+
+    #[clap(
+        long = "fixtures",
+        value_name = "FILE",
+        help = "JSON file describing the state of the chains, from which the queries made while replaying are answered"
+    )]
+    fixtures: Option<PathBuf>,
+
+    #[clap(
+        long = "wait",
+        value_name = "SECONDS",
+        default_value = "10",
+        help = "How long to wait after replaying the events for the workers to handle them"
+    )]
+    wait: u64,
+}
+
+/// The outcome of a replay.
+#[derive(Debug, Serialize)]
+struct ReplayOutcome {
+    /// The number of batches of events which were replayed
+    batches: usize,
+    /// The messages submitted to the chains, in order
+    submitted: Vec<SubmittedMsgs>,
+}
+
+impl Runnable for ReplayCmd {
+    fn run(&self) {
+        let mut config = (*app_config()).clone();
+
+        // Nothing from the replay must leak to the outside world
+        config.persistence.enabled = false;
+        config.rest.enabled = false;
+        config.telemetry.enabled = false;
+
+        let batches = match journal::read_journal(&self.journal) {
+            Ok(entries) => journal::into_batches(entries),
+            Err(e) => Output::error(format!("failed to read the journal: {}", e)).exit(),
+        };
+
+        let fixtures = match &self.fixtures {
+            Some(path) => Fixtures::load(path).unwrap_or_else(|e| {
+                Output::error(format!("failed to load the fixtures: {}", e)).exit()
+            }),
+            None => Fixtures::default(),
+        };
+
+        let replay = Replay::<BaseChainHandle>::new(&config, &fixtures);
+        let registry = SharedRegistry::with_pool(config.clone(), replay.pool());
+
+        let options = SupervisorOptions {
+            health_check: false,
+            force_full_scan: true,
+        };
+
+        let supervisor = spawn_supervisor(config, registry, None, options).unwrap_or_else(|e| {
+            Output::error(format!("failed to spawn the supervisor: {}", e)).exit()
+        });
+
+        let replayed = replay.replay(batches);
+
+        info!(
+            "replayed {} batches of events, waiting {}s for the workers to handle them",
+            replayed, self.wait
+        );
+
+        thread::sleep(Duration::from_secs(self.wait));
+
+        let outcome = ReplayOutcome {
+            batches: replayed,
+            submitted: replay.submitted(),
+        };
+
+        supervisor.shutdown();
+
+        Output::success(outcome).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplayCmd;
+
+    use std::path::PathBuf;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_replay_journal() {
+        assert_eq!(
+            ReplayCmd {
+                journal: PathBuf::from("journal.jsonl"),
+                fixtures: None,
+                wait: 10,
+            },
+            ReplayCmd::parse_from(&["test", "--journal", "journal.jsonl"])
+        )
+    }
+
+    #[test]
+    fn test_replay_fixtures_wait() {
+        assert_eq!(
+            ReplayCmd {
+                journal: PathBuf::from("journal.jsonl"),
+                fixtures: Some(PathBuf::from("fixtures.json")),
+                wait: 30,
+            },
+            ReplayCmd::parse_from(&[
+                "test",
+                "--journal",
+                "journal.jsonl",
+                "--fixtures",
+                "fixtures.json",
+                "--wait",
+                "30"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_replay_no_journal() {
+        assert!(ReplayCmd::try_parse_from(&["test"]).is_err())
+    }
+}
@@ -1,81 +1,248 @@
 use ibc_relayer::supervisor::SupervisorOptions;
 use std::error::Error;
 use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
 use crossbeam_channel::Sender;
 
+use ibc_relayer::cache::packet_events;
 use ibc_relayer::chain::handle::{CachingChainHandle, ChainHandle};
-use ibc_relayer::config::Config;
+use ibc_relayer::config::{self, filter, Config};
+use ibc_relayer::ha::claims;
+use ibc_relayer::ha::{LeaderElector, Leadership};
+use ibc_relayer::link::policy::{self, HttpPolicy};
 use ibc_relayer::registry::SharedRegistry;
+use ibc_relayer::replay::journal;
 use ibc_relayer::rest;
+use ibc_relayer::stats;
 use ibc_relayer::supervisor::{cmd::SupervisorCmd, spawn_supervisor, SupervisorHandle};
 
+use crate::components::shutdown_tracing;
 use crate::conclude::json;
 use crate::conclude::Output;
+use crate::config::config_path;
 use crate::prelude::*;
 
 #[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
 pub struct StartCmd {
     #[clap(
         long = "full-scan",
-        help = "Force a full scan of the chains for clients, connections and channels"
+        help = "Force a full scan of the chains for clients, connections and channels, even if a saved supervisor state could be restored"
     )]
     full_scan: bool,
+
+    #[clap(
+        long = "record-journal",
+        value_name = "FILE",
+        help = "Record the events received from the chains into the given journal file, appending to it if it exists, for replaying them with `hermes replay`"
+    )]
+    record_journal: Option<PathBuf>,
 }
 
 impl Runnable for StartCmd {
     fn run(&self) {
         let config = (*app_config()).clone();
 
+        if let Some(path) = &self.record_journal {
+            journal::record_to(path).unwrap_or_else(|e| {
+                Output::error(format!("failed to record the events to a journal: {}", e)).exit()
+            });
+        }
+
+        stats::record_to(&config.stats).unwrap_or_else(|e| {
+            Output::error(format!("failed to log the submissions: {}", e)).exit()
+        });
+
+        claims::claim_with(&config.claims).unwrap_or_else(|e| {
+            Output::error(format!("failed to set up the packet claims: {}", e)).exit()
+        });
+
+        packet_events::open_with(&config.packet_cache).unwrap_or_else(|e| {
+            Output::error(format!("failed to open the packet event cache: {}", e)).exit()
+        });
+
+        if config.packet_policy.enabled {
+            let http_policy = HttpPolicy::new(config.packet_policy.clone()).unwrap_or_else(|e| {
+                Output::error(format!("failed to set up the packet policy: {}", e)).exit()
+            });
+
+            policy::register(http_policy);
+        }
+
+        // In high-availability mode, stand by until this instance becomes the leader
+        let leadership = config.ha.enabled.then(|| become_leader(&config));
+
         let supervisor_handle = make_supervisor::<CachingChainHandle>(config, self.full_scan)
             .unwrap_or_else(|e| {
                 Output::error(format!("Hermes failed to start, last error: {}", e)).exit()
             });
 
-        match crate::config::config_path() {
-            Some(_) => {
-                register_signals(supervisor_handle.sender.clone()).unwrap_or_else(|e| {
-                    warn!("failed to install signal handler: {}", e);
-                });
-            }
+        // Notified by the signal handler once the supervisor is drained, for it to be shut down
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(1);
+
+        let signals_registered = match crate::config::config_path() {
+            Some(_) => register_signals(
+                supervisor_handle.sender.clone(),
+                app_config().global.shutdown_grace_period,
+                leadership.clone(),
+                shutdown_tx,
+            )
+            .map_err(|e| {
+                warn!("failed to install signal handler: {}", e);
+            })
+            .is_ok(),
             None => {
                 warn!("cannot figure out configuration path, skipping registration of signal handlers");
+                false
             }
         };
 
         info!("Hermes has started");
 
-        supervisor_handle.wait();
+        let shutdown_rx = if signals_registered {
+            shutdown_rx
+        } else {
+            crossbeam_channel::never()
+        };
+
+        let exit_rx = supervisor_handle.exit_requested();
+
+        // Shut down once drained on a termination signal, or once the supervisor asks for
+        // Hermes to exit, e.g. after giving up on a crashed worker, in which case it exits
+        // with an error once shut down
+        let exit_requested = crossbeam_channel::select! {
+            recv(shutdown_rx) -> shutdown => {
+                if shutdown.is_ok() {
+                    supervisor_handle.shutdown();
+                } else {
+                    supervisor_handle.wait();
+                }
+
+                false
+            }
+            recv(exit_rx) -> _ => {
+                drain_supervisor(
+                    &supervisor_handle.sender,
+                    app_config().global.shutdown_grace_period,
+                );
+
+                supervisor_handle.shutdown();
+
+                true
+            }
+        };
+
+        if let Some(leadership) = leadership {
+            leadership.release();
+        }
+
+        shutdown_tracing();
+
+        if exit_requested {
+            std::process::exit(1);
+        }
     }
 }
 
-/// Register the SIGHUP and SIGUSR1 signals, and notify the supervisor.
-/// - [DEPRECATED] SIGHUP: Trigger a reload of the configuration.
+/// Block until this instance is elected as the leader among the instances
+/// sharing the same high-availability lock, and keep renewing its lease from then on,
+/// including while the supervisor scans the chains.
+fn become_leader(config: &Config) -> Leadership {
+    let mut elector = LeaderElector::new(&config.ha).unwrap_or_else(|e| {
+        Output::error(format!(
+            "failed to connect to the high-availability lock backend: {}",
+            e
+        ))
+        .exit()
+    });
+
+    elector.wait_for_leadership();
+
+    elector.hold_leadership(|e| {
+        // Exit right away rather than risk relaying alongside the new leader
+        error!("{}, exiting so that a standby instance takes over", e);
+        shutdown_tracing();
+        std::process::exit(1);
+    })
+}
+
+/// Reload the packet filters of the chains from the configuration file, which apply
+/// to the messages relayed from then on by the workers already spawned, and to the
+/// workers spawned for the channels they newly allow.
+fn reload_packet_filters() {
+    let path = match config_path() {
+        Some(path) => path,
+        None => {
+            warn!("no configuration file to reload the packet filters from");
+            return;
+        }
+    };
+
+    match config::load(&path) {
+        Ok(config) => {
+            filter::reload(
+                config
+                    .chains
+                    .into_iter()
+                    .map(|chain| (chain.id, chain.packet_filter)),
+            );
+
+            info!("reloaded the packet filters from {}", path.display());
+        }
+        Err(e) => error!(
+            "failed to reload the packet filters from {}: {}",
+            path.display(),
+            e
+        ),
+    }
+}
+
+/// Register the SIGHUP, SIGUSR1, SIGINT and SIGTERM signals, and notify the supervisor.
+/// - SIGHUP: Reload the packet filters of the chains from the configuration file.
 /// - SIGUSR1: Ask the supervisor to dump its state and print it to the console.
-fn register_signals(tx_cmd: Sender<SupervisorCmd>) -> Result<(), io::Error> {
+/// - SIGINT, SIGTERM: Shut down gracefully, by asking the supervisor to stop handling
+///   new events and to wait, up to the given grace period, for the transactions already
+///   submitted to be confirmed, and to save its state to disk if persistence is enabled.
+///   The supervisor is then shut down through `shutdown_tx`, for Hermes to exit.
+///   Receiving either signal a second time exits right away. In high-availability mode,
+///   the leadership is released before exiting.
+fn register_signals(
+    tx_cmd: Sender<SupervisorCmd>,
+    grace_period: Duration,
+    leadership: Option<Leadership>,
+    shutdown_tx: Sender<()>,
+) -> Result<(), io::Error> {
     use signal_hook::{consts::signal::*, iterator::Signals};
 
-    let sigs = vec![
-        SIGHUP,  // Reload of configuration (disabled)
+    let sigs = [
+        SIGHUP,  // Reload of the packet filters
         SIGUSR1, // Dump state
+        SIGINT,  // Graceful shutdown
+        SIGTERM, // Graceful shutdown
     ];
 
     let mut signals = Signals::new(&sigs)?;
 
     std::thread::spawn(move || {
+        let mut shutting_down = false;
+
         for signal in &mut signals {
             match signal {
-                SIGHUP => warn!(
-                    "configuration reloading via SIGHUP has been disabled, \
-                     the signal handler will be removed in the future"
-                ),
+                SIGHUP => {
+                    info!("reloading the packet filters (triggered by SIGHUP)");
+                    reload_packet_filters();
+                }
                 SIGUSR1 => {
                     info!("dumping state (triggered by SIGUSR1)");
 
                     let (tx, rx) = crossbeam_channel::bounded(1);
-                    tx_cmd.try_send(SupervisorCmd::DumpState(tx)).unwrap();
+                    if let Err(e) = tx_cmd.try_send(SupervisorCmd::DumpState(tx)) {
+                        error!("failed to ask the supervisor to dump its state: {}", e);
+                        continue;
+                    }
 
                     std::thread::spawn(move || {
                         if let Ok(state) = rx.recv() {
@@ -93,6 +260,32 @@ fn register_signals(tx_cmd: Sender<SupervisorCmd>) -> Result<(), io::Error> {
                     });
                 }
 
+                SIGINT | SIGTERM if shutting_down => {
+                    warn!("received a second termination signal, exiting right away");
+
+                    if let Some(leadership) = &leadership {
+                        leadership.release();
+                    }
+
+                    shutdown_tracing();
+                    std::process::exit(1);
+                }
+
+                SIGINT | SIGTERM => {
+                    info!("shutting down gracefully, send the signal again to exit right away");
+                    shutting_down = true;
+
+                    let tx_cmd = tx_cmd.clone();
+                    let shutdown_tx = shutdown_tx.clone();
+
+                    std::thread::spawn(move || {
+                        drain_supervisor(&tx_cmd, grace_period);
+
+                        // The leadership is released and the traces flushed once shut down
+                        let _ = shutdown_tx.send(());
+                    });
+                }
+
                 _ => (),
             }
         }
@@ -101,6 +294,24 @@ fn register_signals(tx_cmd: Sender<SupervisorCmd>) -> Result<(), io::Error> {
     Ok(())
 }
 
+/// Ask the supervisor to stop handling new events and to wait, up to the given grace
+/// period, for the transactions already submitted to be confirmed, and block until it
+/// is done, leaving it some time for saving its state after the grace period.
+fn drain_supervisor(tx_cmd: &Sender<SupervisorCmd>, grace_period: Duration) {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+
+    if let Err(e) = tx_cmd.try_send(SupervisorCmd::Drain(grace_period, tx)) {
+        error!("failed to ask the supervisor to drain: {}", e);
+        return;
+    }
+
+    match rx.recv_timeout(grace_period + Duration::from_secs(10)) {
+        Ok(Ok(())) => (),
+        Ok(Err(e)) => error!("failed to shut down gracefully: {}", e),
+        Err(e) => error!("failed to shut down gracefully: {}", e),
+    }
+}
+
 #[cfg(feature = "rest-server")]
 fn spawn_rest_server(config: &Config) -> Option<rest::Receiver> {
     let _span = tracing::error_span!("rest").entered();
@@ -170,7 +381,7 @@ fn spawn_telemetry_server(config: &Config) -> Result<(), Box<dyn Error + Send +
     Ok(())
 }
 
-fn make_supervisor<Chain: ChainHandle>(
+pub(crate) fn make_supervisor<Chain: ChainHandle>(
     config: Config,
     force_full_scan: bool,
 ) -> Result<SupervisorHandle, Box<dyn Error + Send + Sync>> {
@@ -194,12 +405,17 @@ fn make_supervisor<Chain: ChainHandle>(
 mod tests {
     use super::StartCmd;
 
+    use std::path::PathBuf;
+
     use abscissa_core::clap::Parser;
 
     #[test]
     fn test_start_required_only() {
         assert_eq!(
-            StartCmd { full_scan: false },
+            StartCmd {
+                full_scan: false,
+                record_journal: None,
+            },
             StartCmd::parse_from(&["test"])
         )
     }
@@ -207,8 +423,22 @@ mod tests {
     #[test]
     fn test_start_full_scan() {
         assert_eq!(
-            StartCmd { full_scan: true },
+            StartCmd {
+                full_scan: true,
+                record_journal: None,
+            },
             StartCmd::parse_from(&["test", "--full-scan"])
         )
     }
+
+    #[test]
+    fn test_start_record_journal() {
+        assert_eq!(
+            StartCmd {
+                full_scan: false,
+                record_journal: Some(PathBuf::from("events.jsonl")),
+            },
+            StartCmd::parse_from(&["test", "--record-journal", "events.jsonl"])
+        )
+    }
 }
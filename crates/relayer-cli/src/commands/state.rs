@@ -0,0 +1,14 @@
+//! `hermes state` subcommands, exporting the IBC state of the chains.
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+mod export;
+
+/// `state` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum StateCmds {
+    /// Export the clients with their consensus states, the connections and the channels
+    /// with their pending packets of a chain at a given height, as JSON
+    Export(export::StateExportCmd),
+}
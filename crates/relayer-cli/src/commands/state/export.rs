@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::PathBuf;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::QueryHeight;
+use ibc_relayer::snapshot::export_state;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{exit_with_unrecoverable_error, Output};
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct StateExportCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain whose state to export"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "height",
+        value_name = "HEIGHT",
+        help = "Height at which to export the state (default: the latest height)"
+    )]
+    height: Option<u64>,
+
+    #[clap(
+        long = "output",
+        value_name = "FILE",
+        help = "File to write the state to (default: the standard output)"
+    )]
+    output: Option<PathBuf>,
+}
+
+/// Command for exporting the IBC state of a chain.
+/// hermes state export --chain ibc-0 --height 1200 --output ibc-0.json
+impl Runnable for StateExportCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let height = self.height.map_or(QueryHeight::Latest, |revision_height| {
+            QueryHeight::Specific(
+                ibc::Height::new(chain.id().version(), revision_height)
+                    .unwrap_or_else(exit_with_unrecoverable_error),
+            )
+        });
+
+        let snapshot = export_state(&chain, height)
+            .unwrap_or_else(|e| Output::error(format!("failed to export the state: {}", e)).exit());
+
+        let path = match &self.output {
+            Some(path) => path,
+            None => Output::success(snapshot).exit(),
+        };
+
+        let json = serde_json::to_string_pretty(&snapshot)
+            .unwrap_or_else(|e| Output::error(format!("failed to encode the state: {}", e)).exit());
+
+        match fs::write(path, json) {
+            Ok(()) => Output::success_msg(format!(
+                "exported the state of chain {} at height {} to {}, skipping {} objects",
+                snapshot.chain_id,
+                snapshot.height,
+                path.display(),
+                snapshot.skipped.len()
+            ))
+            .exit(),
+            Err(e) => Output::error(format!("failed to write {}: {}", path.display(), e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StateExportCmd;
+
+    use std::path::PathBuf;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_state_export() {
+        assert_eq!(
+            StateExportCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                height: None,
+                output: None,
+            },
+            StateExportCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_state_export_height_output() {
+        assert_eq!(
+            StateExportCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                height: Some(1200),
+                output: Some(PathBuf::from("state.json")),
+            },
+            StateExportCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--height",
+                "1200",
+                "--output",
+                "state.json"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_state_export_no_chain() {
+        assert!(StateExportCmd::try_parse_from(&["test"]).is_err())
+    }
+}
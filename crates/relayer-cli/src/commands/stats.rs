@@ -0,0 +1,15 @@
+//! `hermes stats` subcommands, reporting the historical performance of the relayer
+//! from the log of the packet messages it submitted.
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+mod channel;
+
+/// `stats` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum StatsCmds {
+    /// Report the packets relayed to a channel end over a period, along with
+    /// the latency, failure rate and gas used of their transactions
+    Channel(channel::StatsChannelCmd),
+}
@@ -0,0 +1,162 @@
+use core::time::Duration;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::stats::{self, ChannelStats};
+
+use crate::conclude::Output;
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct StatsChannelCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain the packet messages were submitted to"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Channel identifier on the chain given by <CHAIN_ID>"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "port",
+        value_name = "PORT_ID",
+        help = "Port identifier on the chain given by <CHAIN_ID> (default: any port)"
+    )]
+    port_id: Option<PortId>,
+
+    #[clap(
+        long = "since",
+        value_name = "DURATION",
+        default_value = "7d",
+        help = "How far back to report, in a human-readable format, e.g. `12h` or `30d`"
+    )]
+    since: humantime::Duration,
+
+    #[clap(
+        long = "log",
+        value_name = "FILE",
+        help = "Log of the submissions to read (default: the one in the `stats` section of the config)"
+    )]
+    log: Option<PathBuf>,
+}
+
+/// The performance of a channel end, as reported by the command.
+#[derive(Debug, Serialize)]
+struct ChannelReport {
+    chain_id: ChainId,
+    channel_id: ChannelId,
+    port_id: Option<PortId>,
+    /// Start of the reported period, in RFC 3339 format
+    since: String,
+    #[serde(flatten)]
+    stats: ChannelStats,
+}
+
+impl Runnable for StatsChannelCmd {
+    fn run(&self) {
+        let path = match &self.log {
+            Some(path) => path.clone(),
+            None => stats::log_path(&app_config().stats)
+                .unwrap_or_else(|e| Output::error(format!("{}", e)).exit()),
+        };
+
+        let entries = stats::read_log(&path).unwrap_or_else(|e| {
+            Output::error(format!("failed to read the submission log: {}", e)).exit()
+        });
+
+        let since = stats::now().saturating_sub(Duration::from(self.since).as_secs());
+        let entries = stats::retain_since(entries, since);
+
+        let stats =
+            ChannelStats::from_entries(entries.iter().filter(|entry| {
+                entry.is_on(&self.chain_id, self.port_id.as_ref(), &self.channel_id)
+            }));
+
+        let report = ChannelReport {
+            chain_id: self.chain_id.clone(),
+            channel_id: self.channel_id.clone(),
+            port_id: self.port_id.clone(),
+            since: humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(since))
+                .to_string(),
+            stats,
+        };
+
+        Output::success(report).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatsChannelCmd;
+
+    use core::time::Duration;
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_stats_channel() {
+        assert_eq!(
+            StatsChannelCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                port_id: None,
+                since: Duration::from_secs(7 * 24 * 3600).into(),
+                log: None,
+            },
+            StatsChannelCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--channel",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_stats_channel_port_since() {
+        assert_eq!(
+            StatsChannelCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                port_id: Some(PortId::from_str("port_id").unwrap()),
+                since: Duration::from_secs(12 * 3600).into(),
+                log: None,
+            },
+            StatsChannelCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--chan",
+                "channel-07",
+                "--port",
+                "port_id",
+                "--since",
+                "12h"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_stats_channel_no_channel() {
+        assert!(StatsChannelCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err())
+    }
+}
@@ -0,0 +1,156 @@
+//! `hermes top`: a terminal dashboard for a running relayer, showing the
+//! data exposed by its REST API and telemetry server, refreshed periodically.
+
+use core::time::Duration;
+use std::thread;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use console::{Key, Term};
+use crossbeam_channel as channel;
+
+use crate::conclude::Output;
+use crate::prelude::*;
+
+pub(super) mod data;
+mod render;
+
+use self::data::{Dashboard, Endpoints};
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct TopCmd {
+    #[clap(
+        long = "rest-addr",
+        value_name = "ADDRESS",
+        help = "Address of the REST server of the relayer (default: taken from the [rest] section of the config)"
+    )]
+    rest_addr: Option<String>,
+
+    #[clap(
+        long = "telemetry-addr",
+        value_name = "ADDRESS",
+        help = "Address of the telemetry server of the relayer (default: taken from the [telemetry] section of the config)"
+    )]
+    telemetry_addr: Option<String>,
+
+    #[clap(
+        long = "refresh",
+        value_name = "SECONDS",
+        default_value = "2",
+        help = "Interval between two refreshes of the dashboard, in seconds"
+    )]
+    refresh: u64,
+}
+
+impl TopCmd {
+    fn endpoints(&self) -> Endpoints {
+        let config = app_config();
+
+        let rest = self
+            .rest_addr
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", config.rest.host, config.rest.port));
+
+        let telemetry = self
+            .telemetry_addr
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", config.telemetry.host, config.telemetry.port));
+
+        Endpoints::new(&rest, &telemetry)
+    }
+}
+
+impl Runnable for TopCmd {
+    fn run(&self) {
+        let endpoints = self.endpoints();
+        let refresh = Duration::from_secs(self.refresh.max(1));
+        let term = Term::stdout();
+
+        // When not attached to a terminal, e.g. when piped, print a single snapshot
+        if !term.is_term() {
+            let dashboard = Dashboard::fetch(&endpoints);
+            let lines = render::render(&dashboard, &endpoints, refresh, usize::MAX);
+
+            for line in lines {
+                println!("{}", line);
+            }
+
+            return;
+        }
+
+        let (key_tx, key_rx) = channel::unbounded();
+
+        thread::spawn(move || {
+            let term = Term::stdout();
+
+            while let Ok(key) = term.read_key() {
+                if key_tx.send(key).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let _ = term.hide_cursor();
+
+        loop {
+            let dashboard = Dashboard::fetch(&endpoints);
+            let (height, width) = term.size();
+            let lines = render::render(&dashboard, &endpoints, refresh, height as usize);
+
+            let _ = term.clear_screen();
+            for line in lines {
+                let _ = term.write_line(&console::truncate_str(&line, width as usize, "…"));
+            }
+
+            match key_rx.recv_timeout(refresh) {
+                Ok(Key::Char('q')) | Ok(Key::Escape) => break,
+                Ok(_) | Err(channel::RecvTimeoutError::Timeout) => continue,
+                Err(channel::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let _ = term.clear_screen();
+        let _ = term.show_cursor();
+
+        Output::success_msg("exited the dashboard").exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_top_defaults() {
+        assert_eq!(
+            TopCmd {
+                rest_addr: None,
+                telemetry_addr: None,
+                refresh: 2,
+            },
+            TopCmd::parse_from(&["test"])
+        )
+    }
+
+    #[test]
+    fn test_top_addresses() {
+        assert_eq!(
+            TopCmd {
+                rest_addr: Some("127.0.0.1:4000".to_owned()),
+                telemetry_addr: Some("127.0.0.1:4001".to_owned()),
+                refresh: 5,
+            },
+            TopCmd::parse_from(&[
+                "test",
+                "--rest-addr",
+                "127.0.0.1:4000",
+                "--telemetry-addr",
+                "127.0.0.1:4001",
+                "--refresh",
+                "5"
+            ])
+        )
+    }
+}
@@ -0,0 +1,334 @@
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+use std::time::SystemTime;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use ibc_relayer::supervisor::dump_state::SupervisorState;
+use ibc_relayer::util::recent_errors::RecentError;
+
+/// Timeout for each request made to the relayer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The URLs at which the data shown in the dashboard is fetched.
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    pub rest: String,
+    pub telemetry: String,
+}
+
+impl Endpoints {
+    pub fn new(rest: &str, telemetry: &str) -> Self {
+        Self {
+            rest: with_scheme(rest),
+            telemetry: with_scheme(telemetry),
+        }
+    }
+}
+
+/// The URL of the server at the given address, defaulting to the `http` scheme.
+pub fn with_scheme(addr: &str) -> String {
+    if addr.starts_with("http://") || addr.starts_with("https://") {
+        addr.trim_end_matches('/').to_string()
+    } else {
+        format!("http://{}", addr.trim_end_matches('/'))
+    }
+}
+
+/// A snapshot of the data shown in the dashboard. Each part is fetched
+/// independently, so that the others are shown even if one is unavailable.
+#[derive(Debug)]
+pub struct Dashboard {
+    pub fetched_at: SystemTime,
+    pub state: Result<SupervisorState, String>,
+    pub errors: Result<Vec<RecentError>, String>,
+    pub metrics: Result<Metrics, String>,
+}
+
+impl Dashboard {
+    pub fn fetch(endpoints: &Endpoints) -> Self {
+        Self {
+            fetched_at: SystemTime::now(),
+            state: fetch_json(&format!("{}/state", endpoints.rest)),
+            errors: fetch_json(&format!("{}/errors", endpoints.rest)),
+            metrics: fetch(&format!("{}/metrics", endpoints.telemetry))
+                .map(|text| Metrics::parse(&text)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "status", content = "result")]
+#[serde(rename_all = "lowercase")]
+enum JsonResult<R> {
+    Success(R),
+    Error(serde_json::Value),
+}
+
+fn fetch(url: &str) -> Result<String, String> {
+    ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())
+}
+
+fn fetch_json<R: DeserializeOwned>(url: &str) -> Result<R, String> {
+    parse_json(&fetch(url)?)
+}
+
+/// Parse the result out of the body of a response of the REST API of the relayer.
+pub fn parse_json<R: DeserializeOwned>(body: &str) -> Result<R, String> {
+    match serde_json::from_str(body).map_err(|e| e.to_string())? {
+        JsonResult::Success(result) => Ok(result),
+        JsonResult::Error(e) => Err(e.to_string()),
+    }
+}
+
+/// The number of packets pending on a path, as tracked by the relayer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backlog {
+    pub chain: String,
+    pub counterparty: String,
+    pub port: String,
+    pub channel: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletBalance {
+    pub chain: String,
+    pub account: String,
+    pub denom: String,
+    pub amount: f64,
+}
+
+/// The health of the event source of a chain, as seen by the relayer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventSource {
+    pub chain: String,
+    pub events: u64,
+    pub reconnects: u64,
+}
+
+/// The metrics of the telemetry server which are shown in the dashboard.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    pub backlogs: Vec<Backlog>,
+    pub wallets: Vec<WalletBalance>,
+    pub event_sources: Vec<EventSource>,
+}
+
+impl Metrics {
+    /// Extract the metrics from the Prometheus text format.
+    pub fn parse(text: &str) -> Self {
+        let mut metrics = Metrics::default();
+        let mut event_sources = BTreeMap::<String, EventSource>::new();
+
+        for sample in text.lines().filter_map(Sample::parse) {
+            let label = |name: &str| sample.labels.get(name).cloned().unwrap_or_default();
+
+            match sample.name {
+                "backlog_size" => metrics.backlogs.push(Backlog {
+                    chain: label("chain"),
+                    counterparty: label("counterparty"),
+                    port: label("port"),
+                    channel: label("channel"),
+                    size: sample.value as u64,
+                }),
+                "wallet_balance" => metrics.wallets.push(WalletBalance {
+                    chain: label("chain"),
+                    account: label("account"),
+                    denom: label("denom"),
+                    amount: sample.value,
+                }),
+                "ws_events" | "ws_reconnect" => {
+                    let chain = label("chain");
+                    let source = event_sources.entry(chain.clone()).or_insert(EventSource {
+                        chain,
+                        ..Default::default()
+                    });
+
+                    if sample.name == "ws_events" {
+                        source.events = sample.value as u64;
+                    } else {
+                        source.reconnects = sample.value as u64;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        metrics.event_sources = event_sources.into_values().collect();
+        metrics
+    }
+}
+
+/// A sample in the Prometheus text format, eg. `name{label="value"} 42`.
+#[derive(Debug, PartialEq)]
+struct Sample<'a> {
+    name: &'a str,
+    labels: BTreeMap<String, String>,
+    value: f64,
+}
+
+impl<'a> Sample<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (name, labels, rest) = match line.find('{') {
+            Some(start) => {
+                let end = line.rfind('}')?;
+                (
+                    &line[..start],
+                    parse_labels(&line[start + 1..end])?,
+                    &line[end + 1..],
+                )
+            }
+            None => {
+                let (name, rest) = line.split_once(' ')?;
+                (name, BTreeMap::new(), rest)
+            }
+        };
+
+        // The value may be followed by a timestamp
+        let value = rest.split_whitespace().next()?.parse().ok()?;
+
+        Some(Self {
+            name,
+            labels,
+            value,
+        })
+    }
+}
+
+fn parse_labels(s: &str) -> Option<BTreeMap<String, String>> {
+    let mut labels = BTreeMap::new();
+    let mut chars = s.chars().peekable();
+
+    loop {
+        while chars
+            .peek()
+            .map_or(false, |c| *c == ',' || c.is_whitespace())
+        {
+            chars.next();
+        }
+
+        if chars.peek().is_none() {
+            return Some(labels);
+        }
+
+        let name: String = chars.by_ref().take_while(|c| *c != '=').collect();
+
+        if chars.next()? != '"' {
+            return None;
+        }
+
+        let mut value = String::new();
+
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                c => value.push(c),
+            }
+        }
+
+        labels.insert(name.trim().to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = r#"
+# HELP backlog_size Total number of SendPacket events in the backlog
+# TYPE backlog_size gauge
+backlog_size{chain="ibc-0",channel="channel-0",counterparty="ibc-1",port="transfer"} 3
+# HELP wallet_balance The balance of each wallet Hermes uses per chain
+# TYPE wallet_balance gauge
+wallet_balance{account="cosmos1a450s556xf9n63vdd9aet6g6t29tm207ygp5rj",chain="ibc-1",denom="stake"} 99969960
+# TYPE ws_events counter
+ws_events{chain="ibc-0"} 115
+ws_reconnect{chain="ibc-0"} 2
+ws_events{chain="ibc-1"} 128
+workers{type="packet"} 2
+"#;
+
+    #[test]
+    fn parse_metrics() {
+        let metrics = Metrics::parse(METRICS);
+
+        assert_eq!(
+            metrics.backlogs,
+            vec![Backlog {
+                chain: "ibc-0".to_string(),
+                counterparty: "ibc-1".to_string(),
+                port: "transfer".to_string(),
+                channel: "channel-0".to_string(),
+                size: 3,
+            }]
+        );
+
+        assert_eq!(
+            metrics.wallets,
+            vec![WalletBalance {
+                chain: "ibc-1".to_string(),
+                account: "cosmos1a450s556xf9n63vdd9aet6g6t29tm207ygp5rj".to_string(),
+                denom: "stake".to_string(),
+                amount: 99969960.0,
+            }]
+        );
+
+        assert_eq!(
+            metrics.event_sources,
+            vec![
+                EventSource {
+                    chain: "ibc-0".to_string(),
+                    events: 115,
+                    reconnects: 2,
+                },
+                EventSource {
+                    chain: "ibc-1".to_string(),
+                    events: 128,
+                    reconnects: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_sample() {
+        let sample = Sample::parse(r#"tx_latency{chain="a\"b",denom="x"} 1.5 1660000000"#).unwrap();
+
+        assert_eq!(sample.name, "tx_latency");
+        assert_eq!(sample.labels["chain"], "a\"b");
+        assert_eq!(sample.labels["denom"], "x");
+        assert_eq!(sample.value, 1.5);
+
+        let sample = Sample::parse("uptime 42").unwrap();
+        assert_eq!(sample.name, "uptime");
+        assert!(sample.labels.is_empty());
+
+        assert!(Sample::parse("# TYPE uptime counter").is_none());
+        assert!(Sample::parse(r#"broken{chain=ibc-0} 1"#).is_none());
+    }
+
+    #[test]
+    fn endpoints() {
+        let endpoints = Endpoints::new("127.0.0.1:3000", "http://127.0.0.1:3001/");
+
+        assert_eq!(endpoints.rest, "http://127.0.0.1:3000");
+        assert_eq!(endpoints.telemetry, "http://127.0.0.1:3001");
+    }
+}
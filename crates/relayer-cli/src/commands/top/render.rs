@@ -0,0 +1,211 @@
+use core::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use console::style;
+
+use ibc_relayer::worker::WorkerData;
+
+use super::data::{Dashboard, Endpoints};
+
+/// How many recent errors are shown at most.
+const MAX_ERRORS: usize = 10;
+
+/// Render the dashboard as lines of text, truncating the list of
+/// workers so that the whole dashboard fits in the given height.
+pub fn render(
+    dashboard: &Dashboard,
+    endpoints: &Endpoints,
+    refresh: Duration,
+    height: usize,
+) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "{} - REST: {} - telemetry: {} - {} (every {}s) - press 'q' to quit",
+            style("Hermes top").bold(),
+            endpoints.rest,
+            endpoints.telemetry,
+            humantime::format_rfc3339_seconds(dashboard.fetched_at),
+            refresh.as_secs()
+        ),
+        String::new(),
+    ];
+
+    let mut bottom = Vec::new();
+    render_metrics(dashboard, &mut bottom);
+    render_errors(dashboard, &mut bottom);
+
+    let available = height.saturating_sub(lines.len() + bottom.len());
+    render_workers(dashboard, available, &mut lines);
+
+    lines.extend(bottom);
+    lines
+}
+
+fn section(title: &str, lines: &mut Vec<String>) {
+    lines.push(style(title).bold().underlined().to_string());
+}
+
+fn unavailable(reason: &str, lines: &mut Vec<String>) {
+    lines.push(format!("  {} {}", style("unavailable:").red(), reason));
+}
+
+fn render_workers(dashboard: &Dashboard, available: usize, lines: &mut Vec<String>) {
+    let state = match &dashboard.state {
+        Ok(state) => state,
+        Err(e) => {
+            section("Workers", lines);
+            unavailable(e, lines);
+            lines.push(String::new());
+            return;
+        }
+    };
+
+    let count: usize = state.workers.values().map(Vec::len).sum();
+    section(
+        &format!(
+            "Workers ({}) on chains {}",
+            count,
+            state
+                .chains
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        lines,
+    );
+
+    // Keep room for the title, the truncation notice and the blank line
+    let max_rows = available.saturating_sub(3).max(1);
+
+    let rows: Vec<String> = state
+        .workers
+        .iter()
+        .flat_map(|(object_type, workers)| {
+            workers.iter().map(move |desc| {
+                let data = match desc.data {
+                    Some(WorkerData::Client {
+                        misbehaviour,
+                        refresh,
+                    }) => format!(" (misbehaviour: {}, refresh: {})", misbehaviour, refresh),
+                    None => String::new(),
+                };
+
+                let paused = if desc.paused {
+                    format!(" {}", style("[paused]").yellow())
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "  {:<12} #{:<5} {}{}{}",
+                    format!("{:?}", object_type),
+                    desc.id,
+                    desc.object.short_name(),
+                    data,
+                    paused
+                )
+            })
+        })
+        .collect();
+
+    let shown = rows.len().min(max_rows);
+    lines.extend(rows.iter().take(shown).cloned());
+
+    if rows.len() > shown {
+        lines.push(format!("  ... and {} more", rows.len() - shown));
+    }
+
+    lines.push(String::new());
+}
+
+fn render_metrics(dashboard: &Dashboard, lines: &mut Vec<String>) {
+    let metrics = match &dashboard.metrics {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            section("Pending packets, event sources and wallets", lines);
+            unavailable(e, lines);
+            lines.push(String::new());
+            return;
+        }
+    };
+
+    section("Pending packets", lines);
+    let pending: Vec<_> = metrics.backlogs.iter().filter(|b| b.size > 0).collect();
+
+    if pending.is_empty() {
+        lines.push("  no pending packets".to_string());
+    }
+
+    for backlog in pending {
+        lines.push(format!(
+            "  {} {}/{} -> {}: {}",
+            backlog.chain,
+            backlog.port,
+            backlog.channel,
+            backlog.counterparty,
+            style(backlog.size).yellow()
+        ));
+    }
+
+    lines.push(String::new());
+
+    section("Event sources", lines);
+    for source in &metrics.event_sources {
+        let reconnects = if source.reconnects > 0 {
+            style(source.reconnects).yellow()
+        } else {
+            style(source.reconnects).green()
+        };
+
+        lines.push(format!(
+            "  {:<20} events: {:<10} reconnects: {}",
+            source.chain, source.events, reconnects
+        ));
+    }
+
+    lines.push(String::new());
+
+    section("Wallets", lines);
+    for wallet in &metrics.wallets {
+        lines.push(format!(
+            "  {:<20} {} {} {}",
+            wallet.chain, wallet.account, wallet.amount, wallet.denom
+        ));
+    }
+
+    lines.push(String::new());
+}
+
+fn render_errors(dashboard: &Dashboard, lines: &mut Vec<String>) {
+    section("Recent errors", lines);
+
+    let errors = match &dashboard.errors {
+        Ok(errors) => errors,
+        Err(e) => {
+            unavailable(e, lines);
+            return;
+        }
+    };
+
+    if errors.is_empty() {
+        lines.push(format!("  {}", style("no errors").green()));
+    }
+
+    for error in errors.iter().take(MAX_ERRORS) {
+        let time = UNIX_EPOCH + Duration::from_secs(error.timestamp);
+        let kind = if error.fatal {
+            style("fatal").red()
+        } else {
+            style("error").yellow()
+        };
+
+        lines.push(format!(
+            "  {} {} [{}] {}",
+            humantime::format_rfc3339_seconds(time),
+            kind,
+            error.task,
+            error.message.replace('\n', " ")
+        ));
+    }
+}
@@ -6,7 +6,9 @@ use ibc_relayer::config::Config;
 mod channel;
 pub(crate) mod client;
 mod connection;
+mod fee;
 mod packet;
+mod recover;
 mod transfer;
 mod upgrade;
 
@@ -53,6 +55,12 @@ pub enum TxCmd {
     /// Relay acknowledgment packets
     PacketAck(packet::TxPacketAckCmd),
 
+    /// Register the address on the counterparty chain to which the receive fees of the relayer are paid (ICS29)
+    RegisterCounterpartyPayee(fee::TxRegisterCounterpartyPayeeCmd),
+
+    /// Submit a proposal to recover a frozen or expired client
+    RecoverClient(recover::TxRecoverClientCmd),
+
     /// Send an IBC upgrade plan
     UpgradeChain(upgrade::TxIbcUpgradeChainCmd),
 }
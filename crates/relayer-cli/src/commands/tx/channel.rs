@@ -101,7 +101,7 @@ pub struct TxChanOpenInitCmd {
         long = "order",
         default_value_t,
         value_name = "ORDER",
-        help = "The channel ordering, valid options 'unordered' (default) and 'ordered'"
+        help = "The channel ordering, valid options 'unordered' (default), 'ordered' and 'ordered_allow_timeout'"
     )]
     order: Order,
 }
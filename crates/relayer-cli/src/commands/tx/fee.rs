@@ -0,0 +1,161 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::applications::ics29_fee::msgs::register_payee::build_register_counterparty_payee_message;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::signer::Signer;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::tracking::TrackedMsgs;
+use ibc_relayer::config::Config;
+use tendermint_rpc::endpoint::broadcast::tx_sync::Response as TxResponse;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct TxRegisterCounterpartyPayeeCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain receiving the packets relayed over the channel"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        help_heading = "REQUIRED",
+        help = "Port identifier on the chain given by <CHAIN_ID>"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Channel identifier on the chain given by <CHAIN_ID>"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "counterparty-payee",
+        required = true,
+        value_name = "COUNTERPARTY_PAYEE_ADDRESS",
+        help_heading = "REQUIRED",
+        help = "Address on the counterparty chain to which the receive fees of the relayer are paid"
+    )]
+    counterparty_payee: Signer,
+}
+
+impl Runnable for TxRegisterCounterpartyPayeeCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain_config = config
+            .find_chain(&self.chain_id)
+            .unwrap_or_else(|| {
+                Output::error(format!(
+                    "missing configuration for chain '{}'",
+                    self.chain_id
+                ))
+                .exit()
+            })
+            .clone();
+
+        // The keys of `key_names` sign the packets in turn with the key of `key_name`,
+        // so that the payee is registered for each of them
+        let mut key_names = vec![chain_config.key_name.clone()];
+
+        for key_name in chain_config.key_names {
+            if !key_names.contains(&key_name) {
+                key_names.push(key_name);
+            }
+        }
+
+        let mut hashes = Vec::new();
+
+        for key_name in key_names {
+            match self.register_with_key(&config, &key_name) {
+                Ok(responses) => hashes.extend(responses.into_iter().map(|r| r.hash)),
+                Err(e) => Output::error(format!(
+                    "failed to register the counterparty payee for the key '{}': {}",
+                    key_name, e
+                ))
+                .exit(),
+            }
+        }
+
+        Output::success(hashes).exit()
+    }
+}
+
+impl TxRegisterCounterpartyPayeeCmd {
+    /// Register the counterparty payee of the relayer with the address of the given key.
+    fn register_with_key(&self, config: &Config, key_name: &str) -> Result<Vec<TxResponse>, Error> {
+        let mut config = config.clone();
+
+        if let Some(chain_config) = config.find_chain_mut(&self.chain_id) {
+            chain_config.key_name = key_name.to_string();
+            chain_config.key_names = Vec::new();
+        }
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        chain
+            .get_signer()
+            .and_then(|relayer| {
+                let msg = build_register_counterparty_payee_message(
+                    &relayer,
+                    &self.counterparty_payee,
+                    &self.channel_id,
+                    &self.port_id,
+                );
+
+                // No IBC event is emitted by the registration, so the transaction is only checked
+                chain.send_messages_and_wait_check_tx(TrackedMsgs::new_single(
+                    msg,
+                    "register-counterparty-payee",
+                ))
+            })
+            .map_err(Error::relayer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TxRegisterCounterpartyPayeeCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_register_counterparty_payee() {
+        assert_eq!(
+            TxRegisterCounterpartyPayeeCmd {
+                chain_id: ChainId::from_string("chain_b"),
+                port_id: PortId::from_str("transfer").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+                counterparty_payee: "cosmos1payee".parse().unwrap(),
+            },
+            TxRegisterCounterpartyPayeeCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_b",
+                "--port",
+                "transfer",
+                "--channel",
+                "channel-0",
+                "--counterparty-payee",
+                "cosmos1payee"
+            ])
+        )
+    }
+}
@@ -0,0 +1,212 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use tracing::warn;
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{IncludeProof, QueryClientStateRequest, QueryHeight};
+use ibc_relayer::config::Config;
+use ibc_relayer::foreign_client::ForeignClient;
+use ibc_relayer::recover_client::{build_and_send_recover_client_proposal, RecoverClientOptions};
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{exit_with_unrecoverable_error, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct TxRecoverClientCmd {
+    #[clap(
+        long = "host-chain",
+        required = true,
+        value_name = "HOST_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain hosting the client to recover"
+    )]
+    host_chain_id: ChainId,
+
+    #[clap(
+        long = "subject-client",
+        required = true,
+        value_name = "SUBJECT_CLIENT_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the frozen or expired client to recover"
+    )]
+    subject_client_id: ClientId,
+
+    #[clap(
+        long = "substitute-client",
+        required = true,
+        value_name = "SUBSTITUTE_CLIENT_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the active client whose state replaces the one of the subject client"
+    )]
+    substitute_client_id: ClientId,
+
+    #[clap(
+        long = "amount",
+        required = true,
+        value_name = "AMOUNT",
+        help_heading = "REQUIRED",
+        help = "Amount of stake"
+    )]
+    amount: u64,
+
+    #[clap(
+        long = "denom",
+        value_name = "DENOM",
+        help = "Denomination for the deposit (default: 'stake')"
+    )]
+    denom: Option<String>,
+}
+
+impl TxRecoverClientCmd {
+    fn validate_options(&self, config: &Config) -> Result<RecoverClientOptions, String> {
+        let host_chain_config = config.find_chain(&self.host_chain_id).ok_or_else(|| {
+            format!(
+                "missing configuration for host chain '{}'",
+                self.host_chain_id
+            )
+        })?;
+
+        if self.subject_client_id == self.substitute_client_id {
+            return Err("the subject and substitute clients must be different".to_string());
+        }
+
+        let opts = RecoverClientOptions {
+            chain_config: host_chain_config.clone(),
+            subject_client_id: self.subject_client_id.clone(),
+            substitute_client_id: self.substitute_client_id.clone(),
+            amount: self.amount,
+            denom: self.denom.as_deref().unwrap_or("stake").into(),
+        };
+
+        Ok(opts)
+    }
+}
+
+impl Runnable for TxRecoverClientCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let opts = match self.validate_options(&config) {
+            Err(err) => Output::error(err).exit(),
+            Ok(result) => result,
+        };
+
+        let host_chain = spawn_chain_runtime(&config, &self.host_chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let reference_chain_id = match host_chain.query_client_state(
+            QueryClientStateRequest {
+                client_id: self.subject_client_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        ) {
+            Ok((cs, _)) => cs.chain_id(),
+            Err(e) => Output::error(format!(
+                "Query of client '{}' on chain '{}' failed with error: {}",
+                self.subject_client_id, self.host_chain_id, e
+            ))
+            .exit(),
+        };
+
+        // The substitute client is also checked to be active if its reference chain is
+        // configured, otherwise only its parameters are checked against the subject client
+        let res = if config.find_chain(&reference_chain_id).is_some() {
+            let reference_chain = spawn_chain_runtime(&config, &reference_chain_id)
+                .unwrap_or_else(exit_with_unrecoverable_error);
+
+            ForeignClient::restore(opts.subject_client_id.clone(), host_chain, reference_chain)
+                .recover(&opts.substitute_client_id, opts.amount, &opts.denom)
+                .map_err(Error::foreign_client)
+        } else {
+            warn!(
+                "chain '{}' of client '{}' is not configured, not checking that client '{}' is active",
+                reference_chain_id, self.subject_client_id, self.substitute_client_id
+            );
+
+            build_and_send_recover_client_proposal(host_chain, &opts).map_err(Error::recover_client)
+        };
+
+        match res {
+            Ok(ev) => Output::success(ev).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TxRecoverClientCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_recover_client_required_only() {
+        assert_eq!(
+            TxRecoverClientCmd {
+                host_chain_id: ChainId::from_string("chain_host"),
+                subject_client_id: ClientId::from_str("07-tendermint-0").unwrap(),
+                substitute_client_id: ClientId::from_str("07-tendermint-1").unwrap(),
+                amount: 42,
+                denom: None
+            },
+            TxRecoverClientCmd::parse_from(&[
+                "test",
+                "--host-chain",
+                "chain_host",
+                "--subject-client",
+                "07-tendermint-0",
+                "--substitute-client",
+                "07-tendermint-1",
+                "--amount",
+                "42"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_recover_client_denom() {
+        assert_eq!(
+            TxRecoverClientCmd {
+                host_chain_id: ChainId::from_string("chain_host"),
+                subject_client_id: ClientId::from_str("07-tendermint-0").unwrap(),
+                substitute_client_id: ClientId::from_str("07-tendermint-1").unwrap(),
+                amount: 42,
+                denom: Some("my_denom".to_owned())
+            },
+            TxRecoverClientCmd::parse_from(&[
+                "test",
+                "--host-chain",
+                "chain_host",
+                "--subject-client",
+                "07-tendermint-0",
+                "--substitute-client",
+                "07-tendermint-1",
+                "--amount",
+                "42",
+                "--denom",
+                "my_denom"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_recover_client_no_substitute() {
+        assert!(TxRecoverClientCmd::try_parse_from(&[
+            "test",
+            "--host-chain",
+            "chain_host",
+            "--subject-client",
+            "07-tendermint-0",
+            "--amount",
+            "42"
+        ])
+        .is_err())
+    }
+}
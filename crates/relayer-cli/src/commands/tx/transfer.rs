@@ -11,13 +11,20 @@ use ibc::{
     },
     events::IbcEvent,
 };
-use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::counterparty::counterparty_chain_from_channel;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
 use ibc_relayer::chain::requests::{
     IncludeProof, QueryChannelRequest, QueryClientStateRequest, QueryConnectionRequest, QueryHeight,
 };
 use ibc_relayer::{
     config::Config,
-    transfer::{build_and_send_transfer_messages, TransferOptions},
+    registry::Registry,
+    transfer::{
+        build_and_send_transfer_messages,
+        forward::{ForwardHop, ForwardMemo},
+        unwind::{build_and_send_unwind_transfer_messages, unwind_route, UnwindMode},
+        TransferOptions,
+    },
 };
 
 use crate::cli_utils::ChainHandlePair;
@@ -25,6 +32,10 @@ use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::error::Error;
 use crate::prelude::*;
 
+/// How long to wait for the packet of each hop to be received when unwinding
+/// the path of a token.
+const UNWIND_HOP_TIMEOUT: Duration = Duration::from_secs(300);
+
 #[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
 pub struct TxIcs20MsgTransferCmd {
     #[clap(
@@ -77,7 +88,7 @@ pub struct TxIcs20MsgTransferCmd {
         long = "timeout-height-offset",
         default_value = "0",
         value_name = "TIMEOUT_HEIGHT_OFFSET",
-        help = "Timeout in number of blocks since current"
+        help = "Timeout in number of blocks since current, derived from the timeout in seconds and the block time of the destination chain if 0"
     )]
     timeout_height_offset: u64,
 
@@ -85,7 +96,7 @@ pub struct TxIcs20MsgTransferCmd {
         long = "timeout-seconds",
         default_value = "0",
         value_name = "TIMEOUT_SECONDS",
-        help = "Timeout in seconds since current"
+        help = "Timeout in seconds since current, derived from the timeout in blocks and the block time of the destination chain if 0"
     )]
     timeout_seconds: u64,
 
@@ -117,6 +128,55 @@ pub struct TxIcs20MsgTransferCmd {
         help = "Use the given signing key name (default: `key_name` config)"
     )]
     key_name: Option<String>,
+
+    #[clap(
+        long = "memo",
+        value_name = "MEMO",
+        help = "Memo to attach to the transfer, eg. a packet-forward-middleware JSON memo"
+    )]
+    memo: Option<String>,
+
+    #[clap(
+        long = "forward",
+        value_name = "PORT/CHANNEL/RECEIVER",
+        multiple_occurrences = true,
+        conflicts_with_all = &["memo", "unwind"],
+        help = "Hop on which the packet-forward-middleware of the destination chain forwards the tokens, \
+                can be repeated in the order in which the tokens travel. Sets the memo of the transfer"
+    )]
+    forward: Vec<ForwardHop>,
+
+    #[clap(
+        long = "forward-timeout",
+        value_name = "SECONDS",
+        requires = "forward",
+        help = "Timeout of the packets forwarded on each hop, in seconds (default: the packet-forward-middleware default)"
+    )]
+    forward_timeout: Option<u64>,
+
+    #[clap(
+        long = "forward-retries",
+        value_name = "RETRIES",
+        requires = "forward",
+        help = "Number of times to retry forwarding on each hop on failure (default: the packet-forward-middleware default)"
+    )]
+    forward_retries: Option<u8>,
+
+    #[clap(
+        long = "unwind",
+        help = "Send the tokens back along their denomination trace to their origin chain before forwarding them. \
+                The source port and channel then identify the channel from the origin chain to the destination chain"
+    )]
+    unwind: bool,
+
+    #[clap(
+        long = "pfm",
+        requires = "unwind",
+        conflicts_with = "memo",
+        help = "When unwinding, send a single transfer whose memo instructs the packet-forward-middleware \
+                of the chains on the route to forward the tokens, instead of one transfer per hop"
+    )]
+    pfm: bool,
 }
 
 impl Override<Config> for TxIcs20MsgTransferCmd {
@@ -159,6 +219,12 @@ impl TxIcs20MsgTransferCmd {
             return Err(eyre!("number of messages should be greater than zero"));
         }
 
+        if self.unwind && number_msgs > 1 {
+            return Err(eyre!(
+                "sending multiple messages is not supported when unwinding the path of a token"
+            ));
+        }
+
         let opts = TransferOptions {
             packet_src_port_id: self.src_port_id.clone(),
             packet_src_channel_id: self.src_channel_id.clone(),
@@ -168,10 +234,37 @@ impl TxIcs20MsgTransferCmd {
             timeout_height_offset: self.timeout_height_offset,
             timeout_duration: Duration::from_secs(self.timeout_seconds),
             number_msgs,
+            memo: self.memo()?,
         };
 
         Ok(opts)
     }
+
+    /// The memo given as is, or built from the forward hops, if any.
+    fn memo(&self) -> eyre::Result<Option<String>> {
+        if self.forward.is_empty() {
+            return Ok(self.memo.clone());
+        }
+
+        let memo = self
+            .forward
+            .iter()
+            .cloned()
+            .map(|hop| match self.forward_timeout {
+                Some(seconds) => hop.with_timeout(Duration::from_secs(seconds)),
+                None => hop,
+            })
+            .map(|hop| match self.forward_retries {
+                Some(retries) => hop.with_retries(retries),
+                None => hop,
+            })
+            .fold(ForwardMemo::builder(), |builder, hop| builder.hop(hop))
+            .build()
+            .and_then(|memo| memo.to_json())
+            .map_err(|e| eyre!("invalid forward hops: {}", e))?;
+
+        Ok(Some(memo))
+    }
 }
 
 impl Runnable for TxIcs20MsgTransferCmd {
@@ -183,6 +276,10 @@ impl Runnable for TxIcs20MsgTransferCmd {
             Ok(result) => result,
         };
 
+        if self.unwind {
+            self.run_unwind(&config, &opts);
+        }
+
         let chains = ChainHandlePair::spawn(&config, &self.src_chain_id, &self.dst_chain_id)
             .unwrap_or_else(exit_with_unrecoverable_error);
 
@@ -273,12 +370,74 @@ impl Runnable for TxIcs20MsgTransferCmd {
     }
 }
 
+impl TxIcs20MsgTransferCmd {
+    fn run_unwind(&self, config: &Config, opts: &TransferOptions) -> ! {
+        let mut registry = Registry::<BaseChainHandle>::new(config.clone());
+
+        let route = unwind_route(&mut registry, &self.src_chain_id, &opts.denom)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let origin_chain_id = route.origin_chain_id();
+
+        // Unless the tokens arrive at the destination chain by unwinding their path,
+        // check that the given port/channel on the origin chain leads to the destination chain.
+        if origin_chain_id != &self.dst_chain_id {
+            let origin_chain = registry
+                .get_or_spawn(origin_chain_id)
+                .unwrap_or_else(exit_with_unrecoverable_error);
+
+            let counterparty_chain_id = counterparty_chain_from_channel(
+                &origin_chain,
+                &opts.packet_src_channel_id,
+                &opts.packet_src_port_id,
+            )
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+            if counterparty_chain_id != self.dst_chain_id {
+                Output::error(format!(
+                    "the requested port/channel ('{}'/'{}') on origin chain '{}' provides a path to \
+                     chain '{}' (not to the destination chain '{}'). Bailing due to mismatching arguments.",
+                    opts.packet_src_port_id,
+                    opts.packet_src_channel_id,
+                    origin_chain_id,
+                    counterparty_chain_id,
+                    self.dst_chain_id
+                ))
+                .exit();
+            }
+        }
+
+        let mode = if self.pfm {
+            UnwindMode::Forward
+        } else {
+            UnwindMode::Sequential {
+                hop_timeout: UNWIND_HOP_TIMEOUT,
+            }
+        };
+
+        let res: Result<Vec<IbcEvent>, Error> = build_and_send_unwind_transfer_messages(
+            &mut registry,
+            &self.src_chain_id,
+            &self.dst_chain_id,
+            opts,
+            mode,
+        )
+        .map_err(Error::transfer);
+
+        match res {
+            Ok(ev) => Output::success(ev).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ibc::{
         applications::transfer::Amount,
         core::ics24_host::identifier::{ChainId, ChannelId, PortId},
     };
+    use ibc_relayer::transfer::forward::ForwardHop;
 
     use super::TxIcs20MsgTransferCmd;
 
@@ -299,7 +458,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -331,7 +496,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -363,7 +534,13 @@ mod tests {
                 receiver: None,
                 denom: "my_denom".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -397,7 +574,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: Some("key_name".to_owned())
+                key_name: Some("key_name".to_owned()),
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -431,7 +614,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: Some(21),
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -465,7 +654,13 @@ mod tests {
                 receiver: Some("receiver_addr".to_owned()),
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -499,7 +694,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -533,7 +734,13 @@ mod tests {
                 receiver: None,
                 denom: "samoleans".to_owned(),
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -553,6 +760,213 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_ft_transfer_memo() {
+        assert_eq!(
+            TxIcs20MsgTransferCmd {
+                dst_chain_id: ChainId::from_string("chain_receiver"),
+                src_chain_id: ChainId::from_string("chain_sender"),
+                src_port_id: PortId::from_str("port_sender").unwrap(),
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                amount: Amount::from(42),
+                timeout_height_offset: 0,
+                timeout_seconds: 0,
+                receiver: None,
+                denom: "samoleans".to_owned(),
+                number_msgs: None,
+                key_name: None,
+                memo: Some("my memo".to_owned()),
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: false,
+                pfm: false,
+            },
+            TxIcs20MsgTransferCmd::parse_from(&[
+                "test",
+                "--dst-chain",
+                "chain_receiver",
+                "--src-chain",
+                "chain_sender",
+                "--src-port",
+                "port_sender",
+                "--src-channel",
+                "channel_sender",
+                "--amount",
+                "42",
+                "--memo",
+                "my memo"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_ft_transfer_unwind() {
+        assert_eq!(
+            TxIcs20MsgTransferCmd {
+                dst_chain_id: ChainId::from_string("chain_receiver"),
+                src_chain_id: ChainId::from_string("chain_sender"),
+                src_port_id: PortId::from_str("port_sender").unwrap(),
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                amount: Amount::from(42),
+                timeout_height_offset: 0,
+                timeout_seconds: 0,
+                receiver: None,
+                denom: "samoleans".to_owned(),
+                number_msgs: None,
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: true,
+                pfm: false,
+            },
+            TxIcs20MsgTransferCmd::parse_from(&[
+                "test",
+                "--dst-chain",
+                "chain_receiver",
+                "--src-chain",
+                "chain_sender",
+                "--src-port",
+                "port_sender",
+                "--src-channel",
+                "channel_sender",
+                "--amount",
+                "42",
+                "--unwind"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_ft_transfer_unwind_pfm() {
+        assert_eq!(
+            TxIcs20MsgTransferCmd {
+                dst_chain_id: ChainId::from_string("chain_receiver"),
+                src_chain_id: ChainId::from_string("chain_sender"),
+                src_port_id: PortId::from_str("port_sender").unwrap(),
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                amount: Amount::from(42),
+                timeout_height_offset: 0,
+                timeout_seconds: 0,
+                receiver: None,
+                denom: "samoleans".to_owned(),
+                number_msgs: None,
+                key_name: None,
+                memo: None,
+                forward: vec![],
+                forward_timeout: None,
+                forward_retries: None,
+                unwind: true,
+                pfm: true,
+            },
+            TxIcs20MsgTransferCmd::parse_from(&[
+                "test",
+                "--dst-chain",
+                "chain_receiver",
+                "--src-chain",
+                "chain_sender",
+                "--src-port",
+                "port_sender",
+                "--src-channel",
+                "channel_sender",
+                "--amount",
+                "42",
+                "--unwind",
+                "--pfm"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_ft_transfer_pfm_no_unwind() {
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--pfm"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_ft_transfer_forward() {
+        assert_eq!(
+            TxIcs20MsgTransferCmd {
+                dst_chain_id: ChainId::from_string("chain_receiver"),
+                src_chain_id: ChainId::from_string("chain_sender"),
+                src_port_id: PortId::from_str("port_sender").unwrap(),
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                amount: Amount::from(42),
+                timeout_height_offset: 0,
+                timeout_seconds: 0,
+                receiver: None,
+                denom: "samoleans".to_owned(),
+                number_msgs: None,
+                key_name: None,
+                memo: None,
+                forward: vec![
+                    ForwardHop::from_str("transfer/channel-1/juno1receiver").unwrap(),
+                    ForwardHop::from_str("transfer/channel-42/osmo1receiver").unwrap(),
+                ],
+                forward_timeout: None,
+                forward_retries: Some(2),
+                unwind: false,
+                pfm: false,
+            },
+            TxIcs20MsgTransferCmd::parse_from(&[
+                "test",
+                "--dst-chain",
+                "chain_receiver",
+                "--src-chain",
+                "chain_sender",
+                "--src-port",
+                "port_sender",
+                "--src-channel",
+                "channel_sender",
+                "--amount",
+                "42",
+                "--forward",
+                "transfer/channel-1/juno1receiver",
+                "--forward",
+                "transfer/channel-42/osmo1receiver",
+                "--forward-retries",
+                "2"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_ft_transfer_forward_and_memo() {
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--forward",
+            "transfer/channel-1/juno1receiver",
+            "--memo",
+            "my memo"
+        ])
+        .is_err())
+    }
+
     #[test]
     fn test_ft_transfer_no_amount() {
         assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
@@ -1,9 +1,13 @@
 //! Various components for internal use by the Abscissa subsystem.
 
 use abscissa_core::{Component, FrameworkError, FrameworkErrorKind};
-use tracing_subscriber::{filter::EnvFilter, util::SubscriberInitExt, FmtSubscriber};
+use tracing::Subscriber;
+use tracing_subscriber::{
+    filter::EnvFilter, fmt, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt,
+    Layer,
+};
 
-use ibc_relayer::config::{GlobalConfig, LogLevel};
+use ibc_relayer::config::{GlobalConfig, LogLevel, TracingExportConfig};
 
 use crate::config::Error;
 
@@ -17,27 +21,30 @@ const HERMES_LOG_VAR: &str = "RUST_LOG";
 /// - Customizing the log output level, for filtering the output produced via tracing macros
 ///   (`debug!`, `info!`, etc.) or abscissa macros (`status_err`, `status_info`, etc.).
 /// - Enabling JSON-formatted output without coloring
+/// - Exporting the spans of the relaying to an OpenTelemetry collector
 #[derive(Component, Debug)]
 pub struct JsonTracing;
 
 impl JsonTracing {
     /// Creates a new [`JsonTracing`] component
-    pub fn new(cfg: GlobalConfig) -> Result<Self, FrameworkError> {
+    pub fn new(cfg: GlobalConfig, export: &TracingExportConfig) -> Result<Self, FrameworkError> {
         let filter = build_tracing_filter(cfg.log_level)?;
         // Note: JSON formatter is un-affected by ANSI 'color' option. Set to 'false'.
         let use_color = false;
 
         // Construct a tracing subscriber with the supplied filter and enable reloading.
-        let builder = FmtSubscriber::builder()
+        let fmt_layer = fmt::layer()
             .with_target(false)
-            .with_env_filter(filter)
             .with_writer(std::io::stdout)
             .with_ansi(use_color)
             .with_thread_ids(true)
             .json();
 
-        let subscriber = builder.finish();
-        subscriber.init();
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt_layer)
+            .with(build_export_layer(export)?)
+            .init();
 
         Ok(Self)
     }
@@ -51,23 +58,26 @@ impl JsonTracing {
 /// - Customizing the log output level, for filtering the output produced via tracing macros
 ///   (`debug!`, `info!`, etc.) or abscissa macros (`status_err`, `status_info`, etc.).
 /// - Enabling pretty output with coloring
+/// - Exporting the spans of the relaying to an OpenTelemetry collector
 pub struct PrettyTracing;
 
 impl PrettyTracing {
     /// Creates a new [`PrettyTracing`] component
-    pub fn new(cfg: GlobalConfig) -> Result<Self, FrameworkError> {
+    pub fn new(cfg: GlobalConfig, export: &TracingExportConfig) -> Result<Self, FrameworkError> {
         let filter = build_tracing_filter(cfg.log_level)?;
 
         // Construct a tracing subscriber with the supplied filter and enable reloading.
-        let builder = FmtSubscriber::builder()
+        let fmt_layer = fmt::layer()
             .with_target(false)
-            .with_env_filter(filter)
             .with_writer(std::io::stderr)
             .with_ansi(enable_ansi())
             .with_thread_ids(true);
 
-        let subscriber = builder.finish();
-        subscriber.init();
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt_layer)
+            .with(build_export_layer(export)?)
+            .init();
 
         Ok(Self)
     }
@@ -117,3 +127,165 @@ fn build_tracing_filter(default_level: LogLevel) -> Result<EnvFilter, FrameworkE
         }
     }
 }
+
+/// The prefix of the names of the spans of the relaying, which are exported.
+#[cfg(feature = "telemetry")]
+const EXPORTED_SPANS_PREFIX: &str = "relay.";
+
+/// The name of the spans of the relaying of the packets, see [`PacketTraceIds`].
+#[cfg(feature = "telemetry")]
+const PACKET_SPAN: &str = "relay.packet";
+
+/// Builds the layer exporting the spans of the relaying, i.e. the spans whose name starts
+/// with [`EXPORTED_SPANS_PREFIX`], along with the events within them, to the OpenTelemetry
+/// collector of the given configuration, if the export is enabled.
+///
+/// The spans are exported as soon as they are closed, from a dedicated thread.
+#[cfg(feature = "telemetry")]
+fn build_export_layer<S>(
+    export: &TracingExportConfig,
+) -> Result<Option<impl Layer<S>>, FrameworkError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    use opentelemetry::sdk::{trace, Resource};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use tracing_subscriber::filter::filter_fn;
+
+    if !export.enabled {
+        return Ok(None);
+    }
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(export.endpoint.clone()),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                export.service_name.clone(),
+            )])),
+        )
+        .install_simple()
+        .map_err(|e| {
+            FrameworkErrorKind::ComponentError.context(Error::tracing_export(
+                export.endpoint.clone(),
+                e.to_string(),
+            ))
+        })?;
+
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(filter_fn(|metadata| {
+            metadata.is_event() || metadata.name().starts_with(EXPORTED_SPANS_PREFIX)
+        }))
+        .and_then(PacketTraceIds);
+
+    Ok(Some(layer))
+}
+
+/// Flush the spans not exported yet and stop their export, before exiting.
+#[cfg(feature = "telemetry")]
+pub fn shutdown_tracing() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Without the `telemetry` feature, there are no spans to flush.
+#[cfg(not(feature = "telemetry"))]
+pub fn shutdown_tracing() {}
+
+/// Exports the spans of the relaying of a packet in the trace identified by the chain
+/// the packet was sent from and the sequence, port and channel of the packet, so that
+/// the spans of all the events of the packet, on both chains and across restarts, are
+/// found in the same trace.
+///
+/// Must be composed after the OpenTelemetry layer, as it amends the span it builds.
+#[cfg(feature = "telemetry")]
+struct PacketTraceIds;
+
+#[cfg(feature = "telemetry")]
+impl<S> Layer<S> for PacketTraceIds
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        use opentelemetry::trace::TraceId;
+        use sha2::{Digest, Sha256};
+        use tracing_opentelemetry::OtelData;
+
+        if attrs.metadata().name() != PACKET_SPAN {
+            return;
+        }
+
+        let mut packet = PacketFields::default();
+        attrs.record(&mut packet);
+
+        let digest = Sha256::digest(
+            format!(
+                "{}/{}/{}/{}",
+                packet.chain, packet.port, packet.channel, packet.sequence
+            )
+            .as_bytes(),
+        );
+
+        let mut trace_id = [0; 16];
+        trace_id.copy_from_slice(&digest[..16]);
+
+        if let Some(span) = ctx.span(id) {
+            if let Some(data) = span.extensions_mut().get_mut::<OtelData>() {
+                data.builder.trace_id = Some(TraceId::from_bytes(trace_id));
+            }
+        }
+    }
+}
+
+/// The fields of a packet span identifying its trace.
+#[cfg(feature = "telemetry")]
+#[derive(Default)]
+struct PacketFields {
+    chain: String,
+    port: String,
+    channel: String,
+    sequence: String,
+}
+
+#[cfg(feature = "telemetry")]
+impl tracing::field::Visit for PacketFields {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn core::fmt::Debug) {
+        let value = format!("{:?}", value);
+
+        match field.name() {
+            "packet_chain" => self.chain = value,
+            "src_port" => self.port = value,
+            "src_channel" => self.channel = value,
+            "sequence" => self.sequence = value,
+            _ => {}
+        }
+    }
+}
+
+/// Without the `telemetry` feature, the spans cannot be exported.
+#[cfg(not(feature = "telemetry"))]
+fn build_export_layer<S>(
+    export: &TracingExportConfig,
+) -> Result<Option<impl Layer<S>>, FrameworkError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    if export.enabled {
+        eprintln!(
+            "WARNING: the export of the spans is enabled, but Hermes was built without the `telemetry` feature"
+        );
+    }
+
+    Ok(None::<tracing_subscriber::layer::Identity>)
+}
@@ -9,7 +9,12 @@ use std::path::PathBuf;
 
 use flex_error::{define_error, TraceError};
 use ibc::core::ics24_host::identifier::ChainId;
-use ibc_relayer::config::{ChainConfig, Config, ModeConfig};
+use ibc_relayer::config::claims::ClaimsConfig;
+use ibc_relayer::config::event_source::EventSource;
+use ibc_relayer::config::halt::HaltConfig;
+use ibc_relayer::config::packet_policy::PacketPolicyConfig;
+use ibc_relayer::config::quarantine::QuarantineConfig;
+use ibc_relayer::config::{ChainConfig, Config, GrpcTransport, HaConfig, ModeConfig};
 use tendermint_light_client_verifier::types::TrustThreshold;
 use tracing_subscriber::filter::ParseError;
 
@@ -35,6 +40,13 @@ define_error! {
                 format!("invalid log directive: {0:?}", e.directive)
             },
 
+        TracingExport
+            { endpoint: String, reason: String }
+            |e| {
+                format!("failed to set up the export of the spans to {0}: {1}",
+                    e.endpoint, e.reason)
+            },
+
         InvalidMode
             { reason: String, }
             |e| {
@@ -60,6 +72,105 @@ define_error! {
                     e.threshold, e.chain_id, e.reason)
             },
 
+        SameSecondaryRpc
+            { chain_id: ChainId }
+            |e| {
+                format!("config file specifies the same `secondary_rpc_addr` as `rpc_addr` for the chain '{0}', \
+                    headers are not cross-verified against an independent endpoint",
+                    e.chain_id)
+            },
+
+        InvalidHa
+            { reason: String, }
+            |e| {
+                format!("config file specifies invalid high-availability config, caused by: {0}",
+                    e.reason)
+            },
+
+        InvalidQuarantine
+            { reason: String, }
+            |e| {
+                format!("config file specifies invalid quarantine config, caused by: {0}",
+                    e.reason)
+            },
+
+        InvalidHalt
+            { reason: String, }
+            |e| {
+                format!("config file specifies invalid halt config, caused by: {0}",
+                    e.reason)
+            },
+
+        InvalidPacketPolicy
+            { reason: String, }
+            |e| {
+                format!("config file specifies invalid packet policy config, caused by: {0}",
+                    e.reason)
+            },
+
+        DuplicateTenants
+            { tenant: String }
+            |e| {
+                format!("config file has duplicate entry for the tenant '{0}'",
+                    e.tenant)
+            },
+
+        UnknownTenant
+            { chain_id: ChainId, tenant: String }
+            |e| {
+                format!("config file specifies the tenant '{1}' for the chain '{0}', which has no `[[tenants]]` entry",
+                    e.chain_id, e.tenant)
+            },
+
+        InvalidFeeReplenishment
+            { chain_id: ChainId, reason: String }
+            |e| {
+                format!("config file specifies an invalid `fee_replenishment` for the chain '{0}', caused by: {1}",
+                    e.chain_id, e.reason)
+            },
+
+        InvalidPacketLimit
+            { chain_id: ChainId, reason: String }
+            |e| {
+                format!("config file specifies an invalid `packet_limits` entry for the chain '{0}', caused by: {1}",
+                    e.chain_id, e.reason)
+            },
+
+        InvalidQueryPageSize
+            { chain_id: ChainId }
+            |e| {
+                format!("config file specifies `query_page_size = 0` for the chain '{0}', \
+                    which must be at least 1, or unset to fetch each listing in a single query", e.chain_id)
+            },
+
+        InvalidTopOfBlock
+            { chain_id: ChainId, reason: String }
+            |e| {
+                format!("config file specifies an invalid `top_of_block` configuration for the chain '{0}', caused by: {1}",
+                    e.chain_id, e.reason)
+            },
+
+        InvalidDynamicGasPrice
+            { chain_id: ChainId, reason: String }
+            |e| {
+                format!("config file specifies an invalid `dynamic_gas_price` configuration for the chain '{0}', caused by: {1}",
+                    e.chain_id, e.reason)
+            },
+
+        SameFeePayer
+            { chain_id: ChainId }
+            |e| {
+                format!("config file specifies the key of `key_name` as the `fee_payer` of the chain '{0}', \
+                    which already pays the fees of its transactions", e.chain_id)
+            },
+
+        InvalidProxy
+            { chain_id: ChainId, proxy: String, reason: String }
+            |e| {
+                format!("config file specifies the proxy {1} for the chain '{0}', caused by: {2}",
+                    e.chain_id, e.proxy, e.reason)
+            },
+
         DeprecatedGasAdjustment
             {
                 gas_adjustment: f64,
@@ -76,44 +187,182 @@ define_error! {
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum Diagnostic<E> {
-    Warning(E),
-    Error(E),
-}
-
 /// Method for syntactic validation of the input configuration file.
-pub fn validate_config(config: &Config) -> Result<(), Diagnostic<Error>> {
+///
+/// Returns the warnings about the configuration once all of it is validated,
+/// or the first error found.
+pub fn validate_config(config: &Config) -> Result<Vec<Error>, Error> {
+    let mut warnings = Vec::new();
+
+    // Check for duplicate tenants
+    let mut unique_tenants = BTreeSet::new();
+    for tenant in config.tenants.iter() {
+        if !unique_tenants.insert(tenant.id.as_str()) {
+            return Err(Error::duplicate_tenants(tenant.id.clone()));
+        }
+    }
+
     // Check for duplicate chain configuration and invalid trust thresholds
     let mut unique_chain_ids = BTreeSet::new();
     for c in config.chains.iter() {
         let already_present = !unique_chain_ids.insert(c.id.clone());
         if already_present {
-            return Err(Diagnostic::Error(Error::duplicate_chains(c.id.clone())));
+            return Err(Error::duplicate_chains(c.id.clone()));
+        }
+
+        if let Some(tenant) = &c.tenant {
+            if !unique_tenants.contains(tenant.as_str()) {
+                return Err(Error::unknown_tenant(c.id.clone(), tenant.clone()));
+            }
         }
 
         validate_trust_threshold(&c.id, c.trust_threshold)?;
 
         // Validate gas-related settings
         validate_gas_settings(&c.id, c)?;
+
+        validate_fee_replenishment(&c.id, c)?;
+
+        validate_packet_limits(&c.id, c)?;
+
+        validate_top_of_block(&c.id, c)?;
+
+        validate_dynamic_gas_price(&c.id, c)?;
+
+        // Pages of zero entries would never get to the end of the listings
+        if c.query_page_size == Some(0) {
+            return Err(Error::invalid_query_page_size(c.id.clone()));
+        }
+
+        validate_proxy(&c.id, c)?;
+
+        // The fee payer would sign the transactions twice
+        if c.fee_payer.as_ref() == Some(&c.key_name) {
+            return Err(Error::same_fee_payer(c.id.clone()));
+        }
+
+        if c.secondary_rpc_addr.as_ref() == Some(&c.rpc_addr) {
+            warnings.push(Error::same_secondary_rpc(c.id.clone()));
+        }
     }
 
     // Check for invalid mode config
-    validate_mode(&config.mode)?;
+    validate_mode(&config.mode, &mut warnings)?;
 
-    Ok(())
+    // Check for invalid high-availability config
+    validate_ha(&config.ha, &config.claims, &mut warnings)?;
+
+    // Check for invalid quarantine config
+    validate_quarantine(&config.quarantine)?;
+
+    // Check for invalid halt config
+    validate_halt(&config.halt)?;
+
+    // Check for invalid packet policy config
+    validate_packet_policy(&config.packet_policy)?;
+
+    Ok(warnings)
 }
 
-fn validate_mode(mode: &ModeConfig) -> Result<(), Diagnostic<Error>> {
+fn validate_mode(mode: &ModeConfig, warnings: &mut Vec<Error>) -> Result<(), Error> {
     if mode.all_disabled() {
-        return Err(Diagnostic::Warning(Error::invalid_mode(
+        warnings.push(Error::invalid_mode(
             "all operation modes of Hermes are disabled, relayer won't perform any action aside from subscribing to events".to_string(),
-        )));
+        ));
     }
 
-    if mode.clients.enabled && !mode.clients.refresh && !mode.clients.misbehaviour {
-        return Err(Diagnostic::Error(Error::invalid_mode(
+    // A watch-only relayer reports the age of the clients even if it does not refresh them
+    if mode.clients.enabled
+        && !mode.clients.refresh
+        && !mode.clients.misbehaviour
+        && !mode.watch_only
+    {
+        return Err(Error::invalid_mode(
             "either `refresh` or `misbehaviour` must be set to true if `clients.enabled` is set to true".to_string(),
+        ));
+    }
+
+    if !(mode.clients.refresh_rate > 0.0 && mode.clients.refresh_rate < 1.0) {
+        return Err(Error::invalid_mode(format!(
+            "`clients.refresh_rate` must be between 0 and 1 exclusive, found {}",
+            mode.clients.refresh_rate
+        )));
+    }
+
+    if !(0.0..1.0).contains(&mode.clients.expiry_warning_rate) {
+        return Err(Error::invalid_mode(format!(
+            "`clients.expiry_warning_rate` must be between 0 inclusive and 1 exclusive, found {}",
+            mode.clients.expiry_warning_rate
+        )));
+    }
+
+    if mode.watch_only && (mode.connections.enabled || mode.channels.enabled) {
+        warnings.push(Error::invalid_mode(
+            "the relayer is watch-only, the connection and channel handshakes will not be completed".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_ha(
+    ha: &HaConfig,
+    claims: &ClaimsConfig,
+    warnings: &mut Vec<Error>,
+) -> Result<(), Error> {
+    if ha.enabled && ha.renew_interval >= ha.lease_ttl {
+        return Err(Error::invalid_ha(
+            "`renew_interval` must be shorter than `lease_ttl`, otherwise the lease expires before being renewed".to_string(),
+        ));
+    }
+
+    if ha.enabled && claims.enabled {
+        warnings.push(Error::invalid_ha(
+            "only the leader relays in high-availability mode, the packet claims are not needed"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_quarantine(quarantine: &QuarantineConfig) -> Result<(), Error> {
+    if quarantine.enabled
+        && (quarantine.failure_threshold == 0 || quarantine.recovery_threshold == 0)
+    {
+        return Err(Error::invalid_quarantine(
+            "`failure_threshold` and `recovery_threshold` must be at least 1".to_string(),
+        ));
+    }
+
+    if quarantine.enabled && quarantine.check_timeout.is_zero() {
+        return Err(Error::invalid_quarantine(
+            "`check_timeout` must be greater than 0".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_halt(halt: &HaltConfig) -> Result<(), Error> {
+    if halt.enabled && halt.halt_threshold <= halt.check_interval {
+        return Err(Error::invalid_halt(
+            "`halt_threshold` must be greater than `check_interval`".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_packet_policy(packet_policy: &PacketPolicyConfig) -> Result<(), Error> {
+    let valid_url = packet_policy.url.parse::<http::Uri>().map_or(false, |uri| {
+        matches!(uri.scheme_str(), Some("http" | "https"))
+    });
+
+    if packet_policy.enabled && !valid_url {
+        return Err(Error::invalid_packet_policy(format!(
+            "`url` must be an HTTP or HTTPS URL, not '{}'",
+            packet_policy.url
         )));
     }
 
@@ -125,47 +374,163 @@ fn validate_mode(mode: &ModeConfig) -> Result<(), Diagnostic<Error>> {
 /// a) non-zero
 /// b) greater or equal to 1/3
 /// c) strictly less than 1
-fn validate_trust_threshold(
-    id: &ChainId,
-    trust_threshold: TrustThreshold,
-) -> Result<(), Diagnostic<Error>> {
+fn validate_trust_threshold(id: &ChainId, trust_threshold: TrustThreshold) -> Result<(), Error> {
     if trust_threshold.denominator() == 0 {
-        return Err(Diagnostic::Error(Error::invalid_trust_threshold(
+        return Err(Error::invalid_trust_threshold(
             trust_threshold,
             id.clone(),
             "trust threshold denominator cannot be zero".to_string(),
-        )));
+        ));
     }
 
     if trust_threshold.numerator() * 3 < trust_threshold.denominator() {
-        return Err(Diagnostic::Error(Error::invalid_trust_threshold(
+        return Err(Error::invalid_trust_threshold(
             trust_threshold,
             id.clone(),
             "trust threshold cannot be < 1/3".to_string(),
-        )));
+        ));
     }
 
     if trust_threshold.numerator() >= trust_threshold.denominator() {
-        return Err(Diagnostic::Error(Error::invalid_trust_threshold(
+        return Err(Error::invalid_trust_threshold(
             trust_threshold,
             id.clone(),
             "trust threshold cannot be >= 1".to_string(),
-        )));
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_fee_replenishment(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
+    let replenishment = match &config.fee_replenishment {
+        Some(replenishment) => replenishment,
+        None => return Ok(()),
+    };
+
+    let reason = if replenishment.reserve_denom == config.gas_price.denom {
+        "`reserve_denom` must differ from the denomination of the gas price"
+    } else if replenishment.swap_amount == 0 || replenishment.min_amount_out == 0 {
+        "`swap_amount` and `min_amount_out` must be greater than 0"
+    } else if replenishment.max_swaps_per_day == 0 {
+        "`max_swaps_per_day` must be at least 1"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::invalid_fee_replenishment(
+        id.clone(),
+        reason.to_string(),
+    ))
+}
+
+fn validate_packet_limits(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
+    // A quota of zero packets would hold the packets of the channels forever
+    if config
+        .packet_limits
+        .iter()
+        .any(|limit| limit.max_packets_per_block == Some(0))
+    {
+        return Err(Error::invalid_packet_limit(
+            id.clone(),
+            "`max_packets_per_block` must be at least 1".to_string(),
+        ));
     }
 
     Ok(())
 }
 
-fn validate_gas_settings(id: &ChainId, config: &ChainConfig) -> Result<(), Diagnostic<Error>> {
+fn validate_top_of_block(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
+    let top_of_block = &config.top_of_block;
+
+    if !top_of_block.enabled {
+        return Ok(());
+    }
+
+    // No bid would ever be made, or one without a denomination rejected by the chain
+    let reason = if top_of_block.tip_per_packet == 0 {
+        "`tip_per_packet` must be at least 1"
+    } else if top_of_block.max_tip == 0 {
+        "`max_tip` must be at least 1"
+    } else if top_of_block.denom.is_empty() {
+        "`denom` must be set"
+    } else if top_of_block.budget == 0 {
+        "`budget` must be at least 1"
+    } else if top_of_block.budget_period.is_zero() {
+        "`budget_period` must be greater than 0"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::invalid_top_of_block(id.clone(), reason.to_string()))
+}
+
+fn validate_dynamic_gas_price(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
+    let dynamic = match &config.dynamic_gas_price {
+        Some(dynamic) => dynamic,
+        None => return Ok(()),
+    };
+
+    // The static gas price is paid until the first query succeeds, and is the default minimum
+    let min = dynamic.min.unwrap_or(config.gas_price.price);
+
+    let reason = if !dynamic.multiplier.is_finite() || dynamic.multiplier <= 0.0 {
+        format!("`multiplier` must be positive, got {}", dynamic.multiplier)
+    } else if !min.is_finite() || min < 0.0 {
+        format!("`min` must not be negative, got {}", min)
+    } else if !dynamic.max.is_finite() || dynamic.max < min {
+        format!(
+            "`max` ({}) must not be lower than `min`, or than the price of `gas_price` if not set ({})",
+            dynamic.max, min
+        )
+    } else if dynamic.max < config.gas_price.price {
+        format!(
+            "`max` ({}) must not be lower than the price of `gas_price` ({}), \
+            which is paid until the gas price is first queried",
+            dynamic.max, config.gas_price.price
+        )
+    } else if dynamic.refresh_interval.is_zero() {
+        "`refresh_interval` must not be zero".to_string()
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::invalid_dynamic_gas_price(id.clone(), reason))
+}
+
+fn validate_proxy(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
+    let proxy = match &config.proxy {
+        Some(proxy) => proxy,
+        None => return Ok(()),
+    };
+
+    // Neither the WebSocket client nor the gRPC-web transport can be proxied
+    let reason = if config.event_source == EventSource::Push {
+        "the WebSocket endpoint cannot be reached through a proxy, \
+        the events must be pulled over RPC with `event_source = { mode = 'pull' }`"
+    } else if config.grpc_transport == GrpcTransport::Web {
+        "the gRPC-web transport cannot be used through a proxy"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::invalid_proxy(
+        id.clone(),
+        proxy.to_string(),
+        reason.to_string(),
+    ))
+}
+
+fn validate_gas_settings(id: &ChainId, config: &ChainConfig) -> Result<(), Error> {
     // Check that the gas_adjustment option is not set
     if let Some(gas_adjustment) = config.gas_adjustment {
         let gas_multiplier = gas_adjustment + 1.0;
 
-        return Err(Diagnostic::Error(Error::deprecated_gas_adjustment(
+        return Err(Error::deprecated_gas_adjustment(
             gas_adjustment,
             gas_multiplier,
             id.clone(),
-        )));
+        ));
     }
 
     Ok(())
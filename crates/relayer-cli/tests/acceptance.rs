@@ -57,3 +57,23 @@ fn example_configuration_is_valid() {
     cmd.stdout().expect_regex("configuration is valid");
     cmd.wait().unwrap().expect_success();
 }
+
+#[cfg(not(tarpaulin))]
+#[test]
+fn configuration_with_warnings_is_invalid() {
+    let mut runner = RUNNER.clone();
+    let mut cmd = runner
+        .capture_stdout()
+        .args([
+            "--config",
+            "tests/fixtures/all_modes_disabled.toml",
+            "config",
+            "validate",
+        ])
+        .run();
+    cmd.stdout()
+        .expect_regex(r"configuration is invalid, found 1 warning\(s\):");
+    cmd.stdout()
+        .expect_regex("all operation modes of Hermes are disabled");
+    cmd.wait().unwrap().expect_code(1);
+}
@@ -5,9 +5,12 @@ use tracing::error;
 use crossbeam_channel as channel;
 
 use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::supervisor::cmd::{ObjectSpec, PathSpec};
 use ibc_relayer::supervisor::dump_state::SupervisorState;
+use ibc_relayer::util::recent_errors::RecentError;
 use ibc_relayer::{
     config::ChainConfig,
+    object::Object,
     rest::{
         request::{reply_channel, ReplySender, Request, VersionInfo},
         RestApiError,
@@ -64,6 +67,38 @@ pub fn supervisor_state(
     submit_request(sender, |reply_to| Request::State { reply_to })
 }
 
+pub fn recent_errors(sender: &channel::Sender<Request>) -> Result<Vec<RecentError>, RestApiError> {
+    submit_request(sender, |reply_to| Request::RecentErrors { reply_to })
+}
+
+pub fn start_object(
+    sender: &channel::Sender<Request>,
+    object: ObjectSpec,
+) -> Result<Vec<Object>, RestApiError> {
+    submit_request(sender, |reply_to| Request::StartObject { object, reply_to })
+}
+
+pub fn stop_object(
+    sender: &channel::Sender<Request>,
+    object: ObjectSpec,
+) -> Result<Vec<Object>, RestApiError> {
+    submit_request(sender, |reply_to| Request::StopObject { object, reply_to })
+}
+
+pub fn pause_path(
+    sender: &channel::Sender<Request>,
+    path: PathSpec,
+) -> Result<Vec<Object>, RestApiError> {
+    submit_request(sender, |reply_to| Request::PausePath { path, reply_to })
+}
+
+pub fn resume_path(
+    sender: &channel::Sender<Request>,
+    path: PathSpec,
+) -> Result<Vec<Object>, RestApiError> {
+    submit_request(sender, |reply_to| Request::ResumePath { path, reply_to })
+}
+
 pub fn assemble_version_info(sender: &channel::Sender<Request>) -> Vec<VersionInfo> {
     // Fetch the relayer library version
     let lib_version = submit_request(sender, |reply_to| Request::Version { reply_to })
@@ -4,10 +4,13 @@ use crossbeam_channel as channel;
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use ibc_relayer::rest::request::Request;
+use ibc_relayer::rest::{request::Request, RestApiError};
 
 use crate::{
-    handle::{all_chain_ids, assemble_version_info, chain_config, supervisor_state},
+    handle::{
+        all_chain_ids, assemble_version_info, chain_config, pause_path, recent_errors, resume_path,
+        start_object, stop_object, supervisor_state,
+    },
     Config,
 };
 
@@ -82,6 +85,44 @@ fn run(config: Config, sender: channel::Sender<Request>) -> ServerHandle {
                 rouille::Response::json(&JsonResult::from(result))
             },
 
+            (GET) (/errors) => {
+                trace!("[rest] GET /errors");
+                let result = recent_errors(&sender);
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/objects/start) => {
+                trace!("[rest] POST /objects/start");
+                let result = rouille::input::json_input(request)
+                    .map_err(|e| RestApiError::InvalidObjectSpec(e.to_string()))
+                    .and_then(|object| start_object(&sender, object));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/objects/stop) => {
+                trace!("[rest] POST /objects/stop");
+                let result = rouille::input::json_input(request)
+                    .map_err(|e| RestApiError::InvalidObjectSpec(e.to_string()))
+                    .and_then(|object| stop_object(&sender, object));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/paths/pause) => {
+                trace!("[rest] POST /paths/pause");
+                let result = rouille::input::json_input(request)
+                    .map_err(|e| RestApiError::InvalidPathSpec(e.to_string()))
+                    .and_then(|path| pause_path(&sender, path));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/paths/resume) => {
+                trace!("[rest] POST /paths/resume");
+                let result = rouille::input::json_input(request)
+                    .map_err(|e| RestApiError::InvalidPathSpec(e.to_string()))
+                    .and_then(|path| resume_path(&sender, path));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
             _ => rouille::Response::empty_404(),
         )
     })
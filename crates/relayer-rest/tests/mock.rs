@@ -2,11 +2,16 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-use ibc::core::ics24_host::identifier::ChainId;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer::{
     config::ChainConfig,
+    object::{Object, Packet},
     rest::request::{Request, VersionInfo},
-    supervisor::dump_state::SupervisorState,
+    supervisor::{
+        cmd::{ObjectSpec, PathSpec},
+        dump_state::SupervisorState,
+    },
+    util::recent_errors::RecentError,
 };
 
 use ibc_relayer_rest::{server::spawn, Config};
@@ -28,6 +33,29 @@ fn run_test<R, F>(port: u16, path: &str, expected: R, handler: F)
 where
     R: Serialize,
     F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    run_request(port, expected, handler, |url| {
+        ureq::get(&format!("{}{}", url, path)).call()
+    })
+}
+
+fn run_post_test<R, F>(port: u16, path: &str, body: &str, expected: R, handler: F)
+where
+    R: Serialize,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    run_request(port, expected, handler, |url| {
+        ureq::post(&format!("{}{}", url, path))
+            .set("Content-Type", "application/json")
+            .send_string(body)
+    })
+}
+
+fn run_request<R, F, S>(port: u16, expected: R, handler: F, send: S)
+where
+    R: Serialize,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
+    S: FnOnce(&str) -> Result<ureq::Response, ureq::Error>,
 {
     let config = Config::new("127.0.0.1".to_string(), port);
 
@@ -41,8 +69,7 @@ where
         Err(e) => panic!("got an error: {}", e),
     });
 
-    let response = ureq::get(&format!("http://127.0.0.1:{}{}", port, path))
-        .call()
+    let response = send(&format!("http://127.0.0.1:{}", port))
         .unwrap()
         .into_string()
         .unwrap();
@@ -137,3 +164,80 @@ fn state() {
         req => TestResult::WrongRequest(req),
     });
 }
+
+#[test]
+fn start_object() {
+    let spec = ObjectSpec::Channel {
+        chain_id: ChainId::from_string("mock-0"),
+        port_id: PortId::transfer(),
+        channel_id: ChannelId::new(0),
+    };
+
+    let objects = vec![Object::Packet(Packet {
+        dst_chain_id: ChainId::from_string("mock-1"),
+        src_chain_id: ChainId::from_string("mock-0"),
+        src_channel_id: ChannelId::new(0),
+        src_port_id: PortId::transfer(),
+    })];
+
+    let result: JsonResult<_, ()> = JsonResult::Success(objects.clone());
+
+    let body =
+        r#"{"type":"Channel","chain_id":"mock-0","port_id":"transfer","channel_id":"channel-0"}"#;
+
+    run_post_test(19105, "/objects/start", body, result, |req| match req {
+        Request::StartObject { object, reply_to } if object == spec => {
+            reply_to.send(Ok(objects)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn recent_errors() {
+    let errors = vec![RecentError {
+        timestamp: 1_600_000_000,
+        task: "worker.channel".to_string(),
+        message: "failed to query channel".to_string(),
+        fatal: false,
+    }];
+
+    let result: JsonResult<_, ()> = JsonResult::Success(errors.clone());
+
+    run_test(19106, "/errors", result, |req| match req {
+        Request::RecentErrors { reply_to } => {
+            reply_to.send(Ok(errors)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn pause_path() {
+    let spec = PathSpec {
+        chain_id: ChainId::from_string("mock-0"),
+        port_id: PortId::transfer(),
+        channel_id: ChannelId::new(0),
+    };
+
+    let objects = vec![Object::Packet(Packet {
+        dst_chain_id: ChainId::from_string("mock-1"),
+        src_chain_id: ChainId::from_string("mock-0"),
+        src_channel_id: ChannelId::new(0),
+        src_port_id: PortId::transfer(),
+    })];
+
+    let result: JsonResult<_, ()> = JsonResult::Success(objects.clone());
+
+    let body = r#"{"chain_id":"mock-0","port_id":"transfer","channel_id":"channel-0"}"#;
+
+    run_post_test(19107, "/paths/pause", body, result, |req| match req {
+        Request::PausePath { path, reply_to } if path == spec => {
+            reply_to.send(Ok(objects)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
@@ -0,0 +1,70 @@
+//! Decoding of the ABCI events emitted by the full nodes into IBC events.
+
+#![no_main]
+
+use core::str::FromStr;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tendermint::abci::tag::{Key, Tag, Value};
+use tendermint::abci::Event as AbciEvent;
+
+use ibc_relayer::event::ibc_event_try_from_abci_event;
+
+/// The types of the events decoded into IBC events, so that most inputs
+/// go past the dispatch on the event type and exercise the decoding of the attributes.
+const EVENT_TYPES: &[&str] = &[
+    "create_client",
+    "update_client",
+    "upgrade_client",
+    "client_misbehaviour",
+    "connection_open_init",
+    "connection_open_try",
+    "connection_open_ack",
+    "connection_open_confirm",
+    "channel_open_init",
+    "channel_open_try",
+    "channel_open_ack",
+    "channel_open_confirm",
+    "channel_close_init",
+    "channel_close_confirm",
+    "send_packet",
+    "write_acknowledgement",
+    "acknowledge_packet",
+    "timeout_packet",
+    "cross_chain_query",
+];
+
+#[derive(Arbitrary, Debug)]
+enum EventType {
+    Known(u8),
+    Other(String),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    event_type: EventType,
+    attributes: Vec<(String, String)>,
+}
+
+fuzz_target!(|input: Input| {
+    let type_str = match input.event_type {
+        EventType::Known(i) => EVENT_TYPES[usize::from(i) % EVENT_TYPES.len()].to_string(),
+        EventType::Other(type_str) => type_str,
+    };
+
+    // The parsing of `Key`s and `Value`s is infallible
+    let attributes = input
+        .attributes
+        .iter()
+        .map(|(key, value)| Tag {
+            key: Key::from_str(key).unwrap(),
+            value: Value::from_str(value).unwrap(),
+        })
+        .collect();
+
+    let _ = ibc_event_try_from_abci_event(&AbciEvent {
+        type_str,
+        attributes,
+    });
+});
@@ -0,0 +1,130 @@
+//! Conversions of the query requests of the relayer to their Protobuf counterparts,
+//! which must preserve the identifiers, sequences and pagination of the requests.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use tendermint::block::Height as TmHeight;
+use tonic::metadata::AsciiMetadataValue;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, ClientId, PortId};
+use ibc::Height;
+use ibc_proto::ibc::core::channel::v1::{
+    QueryPacketAcknowledgementsRequest as RawQueryPacketAcknowledgementsRequest,
+    QueryUnreceivedPacketsRequest as RawQueryUnreceivedPacketsRequest,
+};
+use ibc_proto::ibc::core::client::v1::QueryConsensusStatesRequest as RawQueryConsensusStatesRequest;
+use ibc_relayer::chain::requests::{
+    CrossChainQueryRequest, PageRequest, QueryConsensusStatesRequest, QueryHeight,
+    QueryPacketAcknowledgementsRequest, QueryUnreceivedPacketsRequest,
+};
+
+#[derive(Arbitrary, Debug)]
+struct Pagination {
+    key: Vec<u8>,
+    offset: u64,
+    limit: u64,
+    count_total: bool,
+    reverse: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    port_id: String,
+    channel_id: String,
+    client_id: String,
+    sequences: Vec<u64>,
+    pagination: Option<Pagination>,
+    revision_number: u64,
+    revision_height: u64,
+    path: String,
+}
+
+/// Encode the given Protobuf message and decode it back.
+fn reencode<M: Message + Default>(message: &M) -> M {
+    M::decode(message.encode_to_vec().as_slice()).expect("decoding an encoded message")
+}
+
+fuzz_target!(|input: Input| {
+    let pagination = input.pagination.map(|p| PageRequest {
+        key: p.key,
+        offset: p.offset,
+        limit: p.limit,
+        count_total: p.count_total,
+        reverse: p.reverse,
+    });
+
+    let sequences: Vec<Sequence> = input
+        .sequences
+        .iter()
+        .copied()
+        .map(Sequence::from)
+        .collect();
+
+    if let (Ok(port_id), Ok(channel_id)) = (
+        input.port_id.parse::<PortId>(),
+        input.channel_id.parse::<ChannelId>(),
+    ) {
+        let raw = RawQueryUnreceivedPacketsRequest::from(QueryUnreceivedPacketsRequest {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            packet_commitment_sequences: sequences.clone(),
+        });
+
+        assert_eq!(reencode(&raw), raw);
+        assert_eq!(raw.port_id.parse::<PortId>().unwrap(), port_id);
+        assert_eq!(raw.channel_id.parse::<ChannelId>().unwrap(), channel_id);
+        assert_eq!(raw.packet_commitment_sequences, input.sequences);
+
+        let raw = RawQueryPacketAcknowledgementsRequest::from(QueryPacketAcknowledgementsRequest {
+            port_id,
+            channel_id,
+            pagination: pagination.clone(),
+            packet_commitment_sequences: sequences,
+            height: QueryHeight::Latest,
+        });
+
+        assert_eq!(reencode(&raw), raw);
+        assert_eq!(raw.packet_commitment_sequences, input.sequences);
+        assert_eq!(
+            raw.pagination.map(|p| p.limit),
+            pagination.as_ref().map(|p| p.limit)
+        );
+    }
+
+    if let Ok(client_id) = input.client_id.parse::<ClientId>() {
+        let raw = RawQueryConsensusStatesRequest::from(QueryConsensusStatesRequest {
+            client_id: client_id.clone(),
+            pagination,
+            height: QueryHeight::Latest,
+        });
+
+        assert_eq!(reencode(&raw), raw);
+        assert_eq!(raw.client_id.parse::<ClientId>().unwrap(), client_id);
+    }
+
+    if let Ok(height) = Height::new(input.revision_number, input.revision_height) {
+        let query_height = QueryHeight::Specific(height);
+
+        if let Ok(tm_height) = TmHeight::try_from(query_height) {
+            assert_eq!(tm_height.value(), input.revision_height);
+        }
+
+        let metadata = AsciiMetadataValue::try_from(query_height).unwrap();
+        assert_eq!(
+            metadata.to_str().unwrap(),
+            input.revision_height.to_string()
+        );
+    }
+
+    let _ = CrossChainQueryRequest {
+        chain_id: String::new(),
+        id: String::new(),
+        path: input.path,
+        height: String::new(),
+    }
+    .decode_path_or_none();
+});
@@ -0,0 +1,34 @@
+//! Parsing of the JSON responses of the full nodes to the `tx_search` RPC queries into IBC events.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tendermint_rpc::endpoint::tx::Response as TxResponse;
+
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc::events::WithBlockDataType;
+use ibc::Height;
+use ibc_relayer::chain::cosmos::query::tx::{
+    all_ibc_events_from_tx_search_response, update_client_from_tx_search_response,
+};
+use ibc_relayer::chain::requests::{QueryClientEventRequest, QueryHeight};
+
+fuzz_target!(|data: &[u8]| {
+    let response: TxResponse = match serde_json::from_slice(data) {
+        Ok(response) => response,
+        Err(_) => return,
+    };
+
+    let chain_id = ChainId::new("ibc".to_string(), 1);
+
+    let _ = all_ibc_events_from_tx_search_response(&chain_id, response.clone());
+
+    let request = QueryClientEventRequest {
+        query_height: QueryHeight::Latest,
+        event_id: WithBlockDataType::UpdateClient,
+        client_id: ClientId::default(),
+        consensus_height: Height::new(1, 1).unwrap(),
+    };
+
+    let _ = update_client_from_tx_search_response(&chain_id, &request, response);
+});
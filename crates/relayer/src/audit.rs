@@ -0,0 +1,376 @@
+//! Cross-checking of the state of a channel on the chains at both of its ends, to report
+//! the inconsistencies which relaying alone cannot resolve, such as orphaned commitments,
+//! missing receipts and sequence gaps, along with the packets and acknowledgements which
+//! are merely waiting to be relayed.
+
+use alloc::collections::btree_set::BTreeSet;
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::{IdentifiedChannelEnd, State};
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+use crate::chain::counterparty::{
+    commitments_on_chain, packet_acknowledgements, unreceived_packets_sequences,
+};
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{IncludeProof, QueryHeight, QueryNextSequenceReceiveRequest};
+use crate::supervisor::Error;
+
+/// One end of a channel.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChannelEndId {
+    pub chain_id: ChainId,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+impl ChannelEndId {
+    fn new(chain_id: ChainId, channel: &IdentifiedChannelEnd) -> Self {
+        Self {
+            chain_id,
+            port_id: channel.port_id.clone(),
+            channel_id: channel.channel_id.clone(),
+        }
+    }
+
+    fn clear_packets_cmd(&self) -> String {
+        format!(
+            "hermes clear packets --chain {} --port {} --channel {}",
+            self.chain_id, self.port_id, self.channel_id
+        )
+    }
+}
+
+impl Display for ChannelEndId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "{}/{} on chain {}",
+            self.port_id, self.channel_id, self.chain_id
+        )
+    }
+}
+
+/// The state of the packets sent on one end of a channel to the other end.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DirectionState {
+    /// Whether the channel is ordered
+    pub ordered: bool,
+    /// Whether the channel end on the receiving chain is closed
+    pub counterparty_closed: bool,
+    /// The sequences of the packets with a commitment on the sending chain
+    pub commitments: Vec<Sequence>,
+    /// Out of the commitments, the sequences of the packets not received by the receiving chain
+    pub unreceived: Vec<Sequence>,
+    /// Out of the commitments, the sequences of the packets acknowledged on the receiving chain
+    pub acknowledged: Vec<Sequence>,
+    /// The sequence of the next packet expected by the receiving chain, on ordered channels
+    pub next_sequence_receive: Option<Sequence>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// Packets sent but not received yet
+    UnrelayedPackets,
+    /// Packets received and acknowledged, whose acknowledgements were not relayed back yet
+    UnrelayedAcknowledgements,
+    /// Packets received without being acknowledged, whose commitments cannot be cleared
+    OrphanedCommitments,
+    /// Packets acknowledged by the receiving chain which has no receipt for them
+    MissingReceipts,
+    /// Packets of an ordered channel which were neither received nor have a commitment,
+    /// blocking the reception of the packets after them
+    SequenceGap,
+}
+
+impl FindingKind {
+    /// Whether the finding is an inconsistency between the two chains,
+    /// rather than packets or acknowledgements waiting to be relayed.
+    pub fn is_inconsistency(&self) -> bool {
+        !matches!(
+            self,
+            Self::UnrelayedPackets | Self::UnrelayedAcknowledgements
+        )
+    }
+}
+
+/// Something found by cross-checking the packets sent on one end of the channel.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    pub inconsistency: bool,
+    /// The end of the channel the packets were sent on
+    pub src: ChannelEndId,
+    pub sequences: Vec<Sequence>,
+    pub remediation: String,
+}
+
+/// The outcome of the cross-checking of a channel.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChannelAudit {
+    pub a: ChannelEndId,
+    pub b: ChannelEndId,
+    /// Whether the two ends of the channel are consistent, even if some
+    /// packets or acknowledgements are still waiting to be relayed
+    pub consistent: bool,
+    pub findings: Vec<Finding>,
+}
+
+/// Cross-check the commitments, receipts, acknowledgements and next sequences
+/// of the given channel, on the chains at both of its ends.
+pub fn audit_channel(
+    chain: &impl ChainHandle,
+    counterparty_chain: &impl ChainHandle,
+    channel: &IdentifiedChannelEnd,
+    counterparty_channel: &IdentifiedChannelEnd,
+) -> Result<ChannelAudit, Error> {
+    let a = ChannelEndId::new(chain.id(), channel);
+    let b = ChannelEndId::new(counterparty_chain.id(), counterparty_channel);
+
+    let a_to_b = fetch_direction(chain, counterparty_chain, channel, counterparty_channel)?;
+    let b_to_a = fetch_direction(counterparty_chain, chain, counterparty_channel, channel)?;
+
+    let mut findings = check_direction(&a, &b, &a_to_b);
+    findings.extend(check_direction(&b, &a, &b_to_a));
+
+    Ok(ChannelAudit {
+        consistent: findings.iter().all(|finding| !finding.inconsistency),
+        a,
+        b,
+        findings,
+    })
+}
+
+/// Fetch the state of the packets sent on the given channel
+/// end of the source chain to the destination chain.
+fn fetch_direction(
+    src_chain: &impl ChainHandle,
+    dst_chain: &impl ChainHandle,
+    src_channel: &IdentifiedChannelEnd,
+    dst_channel: &IdentifiedChannelEnd,
+) -> Result<DirectionState, Error> {
+    let ordered = src_channel.channel_end.ordering().is_ordered();
+
+    let (commitments, _) =
+        commitments_on_chain(src_chain, &src_channel.port_id, &src_channel.channel_id)?;
+
+    let unreceived = unreceived_packets_sequences(
+        dst_chain,
+        &dst_channel.port_id,
+        &dst_channel.channel_id,
+        commitments.clone(),
+    )?;
+
+    let (acknowledged, _) = packet_acknowledgements(
+        dst_chain,
+        &dst_channel.port_id,
+        &dst_channel.channel_id,
+        commitments.clone(),
+    )?;
+
+    let next_sequence_receive = if ordered {
+        let (sequence, _) = dst_chain
+            .query_next_sequence_receive(
+                QueryNextSequenceReceiveRequest {
+                    port_id: dst_channel.port_id.clone(),
+                    channel_id: dst_channel.channel_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        Some(sequence)
+    } else {
+        None
+    };
+
+    Ok(DirectionState {
+        ordered,
+        counterparty_closed: dst_channel.channel_end.state_matches(&State::Closed),
+        commitments,
+        unreceived,
+        acknowledged,
+        next_sequence_receive,
+    })
+}
+
+/// Cross-check the state of the packets sent on the source channel end to the destination one.
+pub fn check_direction(
+    src: &ChannelEndId,
+    dst: &ChannelEndId,
+    state: &DirectionState,
+) -> Vec<Finding> {
+    let unreceived: BTreeSet<Sequence> = state.unreceived.iter().copied().collect();
+    let acknowledged: BTreeSet<Sequence> = state.acknowledged.iter().copied().collect();
+
+    let mut findings = Vec::new();
+
+    let mut report = |kind: FindingKind, sequences: Vec<Sequence>, remediation: String| {
+        if !sequences.is_empty() {
+            findings.push(Finding {
+                kind,
+                inconsistency: kind.is_inconsistency(),
+                src: src.clone(),
+                sequences,
+                remediation,
+            });
+        }
+    };
+
+    let unrelayed_packets = unreceived.difference(&acknowledged).copied().collect();
+    let remediation = if state.counterparty_closed {
+        format!(
+            "the channel end {} is closed, time the packets out with `{}`",
+            dst,
+            src.clear_packets_cmd()
+        )
+    } else {
+        format!("relay the packets with `{}`", src.clear_packets_cmd())
+    };
+    report(
+        FindingKind::UnrelayedPackets,
+        unrelayed_packets,
+        remediation,
+    );
+
+    let unrelayed_acks = acknowledged.difference(&unreceived).copied().collect();
+    report(
+        FindingKind::UnrelayedAcknowledgements,
+        unrelayed_acks,
+        format!(
+            "relay the acknowledgements with `{}`",
+            src.clear_packets_cmd()
+        ),
+    );
+
+    let orphaned = state
+        .commitments
+        .iter()
+        .filter(|seq| !unreceived.contains(seq) && !acknowledged.contains(seq))
+        .copied()
+        .collect();
+    report(
+        FindingKind::OrphanedCommitments,
+        orphaned,
+        format!(
+            "the packets were received on {} without being acknowledged: unless the application \
+            writes the acknowledgements asynchronously, their commitments on {} cannot be cleared \
+            by relaying and the applications at both ends must be inspected",
+            dst, src
+        ),
+    );
+
+    let missing_receipts = unreceived.intersection(&acknowledged).copied().collect();
+    report(
+        FindingKind::MissingReceipts,
+        missing_receipts,
+        format!(
+            "the packets are acknowledged on {} but have no receipt there: the state of the chain \
+            is corrupted, do not relay the packets again and report it to the chain developers",
+            dst
+        ),
+    );
+
+    if let (true, Some(next), Some(first)) = (
+        state.ordered,
+        state.next_sequence_receive,
+        unreceived.iter().next(),
+    ) {
+        let gap = (u64::from(next)..u64::from(*first))
+            .map(Sequence::from)
+            .collect();
+
+        report(
+            FindingKind::SequenceGap,
+            gap,
+            format!(
+                "the packets were never received on {} and have no commitment on {}, which blocks \
+                the ordered channel: the packets after them cannot be received until the channel \
+                is closed",
+                dst, src
+            ),
+        );
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn end(chain: &str) -> ChannelEndId {
+        ChannelEndId {
+            chain_id: ChainId::new(chain.to_string(), 0),
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::new(0),
+        }
+    }
+
+    fn seqs(sequences: &[u64]) -> Vec<Sequence> {
+        sequences.iter().copied().map(Sequence::from).collect()
+    }
+
+    fn kinds(findings: &[Finding]) -> Vec<(FindingKind, Vec<Sequence>)> {
+        findings
+            .iter()
+            .map(|finding| (finding.kind, finding.sequences.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn check_unordered_channel() {
+        let state = DirectionState {
+            commitments: seqs(&[1, 2, 3, 4, 5]),
+            unreceived: seqs(&[4, 5]),
+            acknowledged: seqs(&[1, 5]),
+            ..Default::default()
+        };
+
+        let findings = check_direction(&end("ibc-0"), &end("ibc-1"), &state);
+
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::UnrelayedPackets, seqs(&[4])),
+                (FindingKind::UnrelayedAcknowledgements, seqs(&[1])),
+                (FindingKind::OrphanedCommitments, seqs(&[2, 3])),
+                (FindingKind::MissingReceipts, seqs(&[5])),
+            ]
+        );
+
+        assert!(!findings[0].inconsistency);
+        assert!(findings[2].inconsistency);
+    }
+
+    #[test]
+    fn check_ordered_channel() {
+        let state = DirectionState {
+            ordered: true,
+            commitments: seqs(&[6, 7]),
+            unreceived: seqs(&[6, 7]),
+            next_sequence_receive: Some(Sequence::from(4)),
+            ..Default::default()
+        };
+
+        let findings = check_direction(&end("ibc-0"), &end("ibc-1"), &state);
+
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::UnrelayedPackets, seqs(&[6, 7])),
+                (FindingKind::SequenceGap, seqs(&[4, 5])),
+            ]
+        );
+
+        // Nothing is reported for a channel whose packets were all relayed
+        assert!(
+            check_direction(&end("ibc-0"), &end("ibc-1"), &DirectionState::default()).is_empty()
+        );
+    }
+}
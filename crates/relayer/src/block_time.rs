@@ -0,0 +1,202 @@
+//! Estimation of the time each chain takes to produce a block, from the heights and
+//! timestamps of the latest blocks of the chain observed by the relayer.
+//!
+//! The estimate is a moving average of the block times between the successive
+//! observations, so that it follows the chains whose block time changes, e.g. after
+//! an upgrade. It is used instead of fixed assumptions, which are either too slow
+//! for the chains producing a block every second or too eager for the ones
+//! producing a block every fifteen seconds, to derive:
+//!
+//! - the interval at which the confirmation of the transactions is polled,
+//!   see [`poll_interval`];
+//! - the timeout height of the transfers given a timeout duration, and
+//!   conversely, see [`blocks_in`] and [`duration_of`].
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use core::time::Duration;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use tracing::trace;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+
+use crate::chain::endpoint::ChainStatus;
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{QueryHeight, QueryHostConsensusStateRequest};
+use crate::error::Error;
+
+/// Weight of the latest block time in the moving average.
+const SMOOTHING: f64 = 0.2;
+
+/// Block times outside of these bounds are ignored, as they come from clock
+/// adjustments or halts of the chain rather than from its regular pace.
+const MIN_BLOCK_TIME: Duration = Duration::from_millis(100);
+const MAX_BLOCK_TIME: Duration = Duration::from_secs(120);
+
+/// How many blocks back the block time of a chain is sampled, when it was not estimated yet.
+const SAMPLED_BLOCKS: u64 = 20;
+
+/// Bounds of the interval at which the confirmation of the transactions is polled,
+/// one fourth of the block time, and the interval used when it is unknown.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+static ESTIMATES: Lazy<RwLock<HashMap<ChainId, Estimate>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Estimate {
+    /// The latest block observed
+    latest: Option<(u64, Timestamp)>,
+    block_time: Option<Duration>,
+}
+
+impl Estimate {
+    fn observe(&mut self, height: u64, timestamp: Timestamp) {
+        let latest = match self.latest {
+            Some(latest) if latest.0 < height => latest,
+            Some(_) => return,
+            None => {
+                self.latest = Some((height, timestamp));
+                return;
+            }
+        };
+
+        self.latest = Some((height, timestamp));
+
+        let block_time = match timestamp.duration_since(&latest.1) {
+            Some(elapsed) => elapsed / (height - latest.0) as u32,
+            None => return,
+        };
+
+        if !(MIN_BLOCK_TIME..=MAX_BLOCK_TIME).contains(&block_time) {
+            return;
+        }
+
+        self.block_time = Some(match self.block_time {
+            Some(average) => average.mul_f64(1.0 - SMOOTHING) + block_time.mul_f64(SMOOTHING),
+            None => block_time,
+        });
+    }
+}
+
+/// Record the height and timestamp of a block of the given chain.
+pub fn observe(chain_id: &ChainId, height: Height, timestamp: Timestamp) {
+    let mut estimates = ESTIMATES.write().unwrap_or_else(|e| e.into_inner());
+    let estimate = estimates.entry(chain_id.clone()).or_default();
+
+    estimate.observe(height.revision_height(), timestamp);
+
+    trace!(
+        chain = %chain_id,
+        "estimated block time: {:?}",
+        estimate.block_time
+    );
+}
+
+/// The estimated block time of the given chain, if enough of its blocks were observed.
+pub fn estimate(chain_id: &ChainId) -> Option<Duration> {
+    let estimates = ESTIMATES.read().unwrap_or_else(|e| e.into_inner());
+    estimates
+        .get(chain_id)
+        .and_then(|estimate| estimate.block_time)
+}
+
+/// The estimated block time of the given chain, sampling it from the blocks of the
+/// chain preceding the given latest one if it was not estimated yet.
+pub fn estimate_or_sample(
+    chain: &impl ChainHandle,
+    latest: &ChainStatus,
+) -> Result<Option<Duration>, Error> {
+    let chain_id = chain.id();
+
+    if let Some(block_time) = estimate(&chain_id) {
+        return Ok(Some(block_time));
+    }
+
+    let sampled_height = match latest
+        .height
+        .revision_height()
+        .checked_sub(SAMPLED_BLOCKS)
+        .filter(|&height| height > 0)
+    {
+        Some(height) => Height::new(latest.height.revision_number(), height)
+            .map_err(|_| Error::invalid_height_no_source())?,
+        None => return Ok(None),
+    };
+
+    let sampled = chain.query_host_consensus_state(QueryHostConsensusStateRequest {
+        height: QueryHeight::Specific(sampled_height),
+    })?;
+
+    observe(&chain_id, sampled_height, sampled.timestamp());
+    observe(&chain_id, latest.height, latest.timestamp);
+
+    Ok(estimate(&chain_id))
+}
+
+/// The interval at which to poll the given chain for the confirmation of transactions.
+pub fn poll_interval(chain_id: &ChainId) -> Duration {
+    estimate(chain_id).map_or(DEFAULT_POLL_INTERVAL, |block_time| {
+        (block_time / 4).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL)
+    })
+}
+
+/// The number of blocks produced in the given duration by a chain with the given block time, rounded up.
+pub fn blocks_in(duration: Duration, block_time: Duration) -> u64 {
+    let block_time = block_time.as_nanos().max(1);
+
+    ((duration.as_nanos() + block_time - 1) / block_time) as u64
+}
+
+/// The time taken to produce the given number of blocks by a chain with the given block time.
+pub fn duration_of(blocks: u64, block_time: Duration) -> Duration {
+    block_time.saturating_mul(blocks.min(u32::MAX as u64) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> Timestamp {
+        Timestamp::from_nanoseconds(secs * 1_000_000_000).unwrap()
+    }
+
+    #[test]
+    fn follows_the_block_time() {
+        let mut estimate = Estimate::default();
+
+        estimate.observe(100, at(1000));
+        assert_eq!(estimate.block_time, None);
+
+        // Five seconds per block, over four blocks
+        estimate.observe(104, at(1020));
+        assert_eq!(estimate.block_time, Some(Duration::from_secs(5)));
+
+        // Earlier blocks and halts are ignored
+        estimate.observe(90, at(900));
+        estimate.observe(105, at(2000));
+        assert_eq!(estimate.block_time, Some(Duration::from_secs(5)));
+
+        // Down to one second per block
+        for height in 106..150 {
+            estimate.observe(height, at(2000 + height - 105));
+        }
+
+        let block_time = estimate.block_time.unwrap();
+        assert!(block_time < Duration::from_millis(1010), "{:?}", block_time);
+
+        assert_eq!(
+            blocks_in(Duration::from_secs(61), Duration::from_secs(6)),
+            11
+        );
+        assert_eq!(
+            duration_of(10, Duration::from_secs(6)),
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -15,14 +15,14 @@ use ibc::core::ics24_host::identifier::{ClientId, ConnectionId, PortChannelId};
 
 use crate::client_state::AnyClientState;
 
+pub mod packet_events;
+
 const CHANNEL_CACHE_TTL: Duration = Duration::from_secs(60);
 const CONNECTION_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
 const CLIENT_STATE_CACHE_TTL: Duration = Duration::from_millis(500);
 const LATEST_HEIGHT_CACHE_TTL: Duration = Duration::from_millis(200);
 
-const CHANNEL_CACHE_CAPACITY: u64 = 10_000;
-const CONNECTION_CACHE_CAPACITY: u64 = 10_000;
-const CLIENT_STATE_CACHE_CAPACITY: u64 = 10_000;
+const DEFAULT_CACHE_CAPACITY: u64 = 10_000;
 
 /// Whether or not a result was in cache (ie. a cache hit)
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -59,19 +59,25 @@ impl Default for Cache {
 impl Cache {
     /// Initializes a new empty [`Cache`] with default time-to-live values.
     pub fn new() -> Cache {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Initializes a new empty [`Cache`] with default time-to-live values,
+    /// keeping at most `capacity` entries in each of its sub-caches.
+    pub fn with_capacity(capacity: u64) -> Cache {
         let channels = MokaCache::builder()
             .time_to_live(CHANNEL_CACHE_TTL)
-            .max_capacity(CHANNEL_CACHE_CAPACITY)
+            .max_capacity(capacity)
             .build();
 
         let connections = MokaCache::builder()
             .time_to_live(CONNECTION_CACHE_TTL)
-            .max_capacity(CONNECTION_CACHE_CAPACITY)
+            .max_capacity(capacity)
             .build();
 
         let client_states = MokaCache::builder()
             .time_to_live(CLIENT_STATE_CACHE_TTL)
-            .max_capacity(CLIENT_STATE_CACHE_CAPACITY)
+            .max_capacity(capacity)
             .build();
 
         let latest_height = MokaCache::builder()
@@ -0,0 +1,363 @@
+//! A cache of the `SendPacket` and `WriteAcknowledgement` events found on the chains,
+//! persisted on disk with [`sled`], so that restarting the relayer does not scan the
+//! blocks of the past again for the events of the packets it relays.
+//!
+//! The events are keyed by the channel and sequence of their packet, and by the height
+//! they were emitted at. The event of a packet never changes once committed, unless the
+//! chain is rolled back below its height, e.g. by a coordinated restart after a halt:
+//! the entries of a chain above its latest height are thus dropped when its cache is
+//! first used, see [`PacketEventCache::invalidate_above`].
+//!
+//! A chain may also be reset from a new genesis under the same chain identifier, in which
+//! case the cached events describe packets which never existed on the new chain. A cached
+//! event is thus only served after checking it against the packet commitment, or the
+//! acknowledgement, stored by the chain, see [`commitment`].
+//!
+//! The cache is best effort: an entry which cannot be read or written only means that
+//! the event is queried from the chain.
+
+use std::path::PathBuf;
+
+use flex_error::{define_error, TraceError};
+use once_cell::sync::OnceCell;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+
+use ibc::core::ics04_channel::events::{SendPacket, WriteAcknowledgement};
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::events::IbcEvent;
+use ibc::Height;
+
+use crate::chain::requests::{QueryHeight, QueryPacketEventDataRequest};
+use crate::config::packet_cache::PacketCacheConfig;
+use crate::event::IbcEventWithHeight;
+
+/// Location of the database of the cache, relative to the home directory.
+pub const DEFAULT_PACKET_CACHE_DIR: &str = ".hermes/cache/packets";
+
+static DB: OnceCell<sled::Db> = OnceCell::new();
+
+define_error! {
+    PacketCacheError {
+        Open
+            { path: PathBuf }
+            [ TraceError<sled::Error> ]
+            |e| { format!("failed to open the packet event cache at {}", e.path.display()) },
+
+        Db
+            [ TraceError<sled::Error> ]
+            |_| { "packet event cache database error" },
+
+        HomeLocationUnavailable
+            |_| { "home location is unavailable" },
+    }
+}
+
+/// Open the cache in the directory of the given configuration, to be used by the
+/// chains from now on, if the cache is enabled.
+pub fn open_with(config: &PacketCacheConfig) -> Result<(), PacketCacheError> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let path = match &config.path {
+        Some(path) => path.clone(),
+        None => dirs_next::home_dir()
+            .ok_or_else(PacketCacheError::home_location_unavailable)?
+            .join(DEFAULT_PACKET_CACHE_DIR),
+    };
+
+    let db = sled::open(&path).map_err(|e| PacketCacheError::open(path.clone(), e))?;
+
+    info!("caching the packet events in {}", path.display());
+
+    // The cache is only opened once, by `hermes start`
+    let _ = DB.set(db);
+
+    Ok(())
+}
+
+/// The cache of the given chain, if the cache was opened.
+pub fn for_chain(chain_id: &ChainId) -> Option<Result<PacketEventCache, PacketCacheError>> {
+    let db = DB.get()?;
+
+    Some(
+        db.open_tree(chain_id.as_str())
+            .map(|tree| PacketEventCache { tree })
+            .map_err(PacketCacheError::db),
+    )
+}
+
+/// An event as stored in the cache, with its packet encoded as Protobuf, as the
+/// events and packets do not roundtrip through serde.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEvent {
+    revision_number: u64,
+    revision_height: u64,
+    packet: Vec<u8>,
+    /// The acknowledgement, for the `WriteAcknowledgement` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ack: Option<Vec<u8>>,
+}
+
+impl CachedEvent {
+    fn new(packet: &Packet, ack: Option<&[u8]>, height: Height) -> Self {
+        Self {
+            revision_number: height.revision_number(),
+            revision_height: height.revision_height(),
+            packet: RawPacket::from(packet.clone()).encode_to_vec(),
+            ack: ack.map(<[u8]>::to_vec),
+        }
+    }
+
+    fn height(&self) -> Option<Height> {
+        Height::new(self.revision_number, self.revision_height).ok()
+    }
+
+    fn into_event(self) -> Option<IbcEventWithHeight> {
+        let height = self.height()?;
+
+        let packet = RawPacket::decode(self.packet.as_slice())
+            .ok()
+            .and_then(|raw| Packet::try_from(raw).ok())?;
+
+        let event = match self.ack {
+            Some(ack) => IbcEvent::WriteAcknowledgement(WriteAcknowledgement { packet, ack }),
+            None => IbcEvent::SendPacket(SendPacket { packet }),
+        };
+
+        Some(IbcEventWithHeight::new(event, height))
+    }
+}
+
+/// The cached packet events of a chain.
+#[derive(Clone, Debug)]
+pub struct PacketEventCache {
+    tree: sled::Tree,
+}
+
+impl PacketEventCache {
+    /// The cached event matching the given request for the packet with the given
+    /// sequence, emitted at or below the height of the request, and within its range
+    /// of heights if any.
+    pub fn get(
+        &self,
+        request: &QueryPacketEventDataRequest,
+        sequence: Sequence,
+    ) -> Option<IbcEventWithHeight> {
+        let max_height = match request.height {
+            QueryHeight::Latest => None,
+            QueryHeight::Specific(height) => Some(height),
+        };
+
+        self.tree
+            .scan_prefix(key_prefix(request, sequence))
+            .values()
+            .filter_map(|value| {
+                value
+                    .map_err(|e| warn!("failed to read the packet event cache: {}", e))
+                    .ok()
+            })
+            .filter_map(|value| serde_json::from_slice::<CachedEvent>(&value).ok())
+            .filter(|cached| {
+                cached.height().map_or(false, |height| {
+                    max_height.map_or(true, |max_height| height <= max_height)
+                        && request
+                            .height_range
+                            .map_or(true, |range| range.contains(height))
+                })
+            })
+            .last()
+            .and_then(CachedEvent::into_event)
+    }
+
+    /// Cache the given event, found with the given request.
+    pub fn insert(&self, request: &QueryPacketEventDataRequest, event: &IbcEventWithHeight) {
+        let (packet, ack) = match &event.event {
+            IbcEvent::SendPacket(send) => (&send.packet, None),
+            IbcEvent::WriteAcknowledgement(write_ack) => {
+                (&write_ack.packet, Some(write_ack.ack.as_slice()))
+            }
+            _ => return,
+        };
+
+        let key = event_key(request, packet.sequence, event.height);
+        let cached = CachedEvent::new(packet, ack, event.height);
+
+        let result = serde_json::to_vec(&cached)
+            .map_err(|e| e.to_string())
+            .and_then(|value| self.tree.insert(key, value).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            warn!(
+                "failed to cache the event of packet {}: {}",
+                packet.sequence, e
+            );
+        }
+    }
+
+    /// Drop the cached events of the packet with the given sequence, e.g. as they do
+    /// not match the commitment of the packet on the chain.
+    pub fn remove(&self, request: &QueryPacketEventDataRequest, sequence: Sequence) {
+        for key in self.tree.scan_prefix(key_prefix(request, sequence)).keys() {
+            let result = key.and_then(|key| self.tree.remove(key));
+
+            if let Err(e) = result {
+                warn!(
+                    "failed to drop the cached event of packet {}: {}",
+                    sequence, e
+                );
+            }
+        }
+    }
+
+    /// Drop the events emitted above the given height, as the chain was rolled back
+    /// below them. Returns the number of the events dropped.
+    pub fn invalidate_above(&self, latest_height: Height) -> Result<usize, PacketCacheError> {
+        let mut dropped = 0;
+
+        for entry in self.tree.iter() {
+            let (key, value) = entry.map_err(PacketCacheError::db)?;
+
+            let stale = serde_json::from_slice::<CachedEvent>(&value)
+                .ok()
+                .and_then(|cached| cached.height())
+                .map_or(true, |height| height > latest_height);
+
+            if stale {
+                self.tree.remove(key).map_err(PacketCacheError::db)?;
+                dropped += 1;
+            }
+        }
+
+        Ok(dropped)
+    }
+}
+
+/// The commitment stored by the chain for the packet of the given event, as computed by
+/// ibc-go: the hash of the packet timeouts and data for a `SendPacket` event, the hash of
+/// the acknowledgement for a `WriteAcknowledgement` event.
+pub fn commitment(event: &IbcEvent) -> Option<Vec<u8>> {
+    match event {
+        IbcEvent::SendPacket(send) => {
+            let packet = &send.packet;
+
+            let mut hasher = Sha256::new();
+            hasher.update(packet.timeout_timestamp.nanoseconds().to_be_bytes());
+            hasher.update(
+                packet
+                    .timeout_height
+                    .commitment_revision_number()
+                    .to_be_bytes(),
+            );
+            hasher.update(
+                packet
+                    .timeout_height
+                    .commitment_revision_height()
+                    .to_be_bytes(),
+            );
+            hasher.update(Sha256::digest(&packet.data));
+
+            Some(hasher.finalize().to_vec())
+        }
+        IbcEvent::WriteAcknowledgement(write_ack) => Some(Sha256::digest(&write_ack.ack).to_vec()),
+        _ => None,
+    }
+}
+
+fn key_prefix(request: &QueryPacketEventDataRequest, sequence: Sequence) -> String {
+    format!(
+        "{}/{}/{}/{}/{}/{:020}/",
+        request.event_id.as_str(),
+        request.source_port_id,
+        request.source_channel_id,
+        request.destination_port_id,
+        request.destination_channel_id,
+        u64::from(sequence)
+    )
+}
+
+/// The key of an event, sorted by height after the channel and sequence of its packet.
+fn event_key(request: &QueryPacketEventDataRequest, sequence: Sequence, height: Height) -> String {
+    format!(
+        "{}{:020}-{:020}",
+        key_prefix(request, sequence),
+        height.revision_number(),
+        height.revision_height()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+    use ibc::events::WithBlockDataType;
+
+    #[test]
+    fn cached_events_by_height() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let cache = PacketEventCache {
+            tree: db.open_tree("ibc-0").unwrap(),
+        };
+
+        let height = |h| Height::new(0, h).unwrap();
+
+        let mut request = QueryPacketEventDataRequest {
+            event_id: WithBlockDataType::SendPacket,
+            source_port_id: PortId::transfer(),
+            source_channel_id: ChannelId::from_str("channel-0").unwrap(),
+            destination_port_id: PortId::transfer(),
+            destination_channel_id: ChannelId::from_str("channel-1").unwrap(),
+            sequences: vec![Sequence::from(7)],
+            height: QueryHeight::Specific(height(100)),
+            height_range: None,
+        };
+
+        let packet = Packet {
+            sequence: Sequence::from(7),
+            data: b"data".to_vec(),
+            ..Packet::default()
+        };
+
+        let event = IbcEventWithHeight::new(
+            IbcEvent::SendPacket(SendPacket {
+                packet: packet.clone(),
+            }),
+            height(50),
+        );
+
+        cache.insert(&request, &event);
+
+        let cached = cache.get(&request, Sequence::from(7)).unwrap();
+        assert_eq!(cached.height, height(50));
+        assert_eq!(cached.event.packet(), Some(&packet));
+
+        assert!(cache.get(&request, Sequence::from(8)).is_none());
+
+        // The events above the height of the request are not returned
+        request.height = QueryHeight::Specific(height(40));
+        assert!(cache.get(&request, Sequence::from(7)).is_none());
+
+        // The events above the latest height are dropped after a rollback
+        assert_eq!(cache.invalidate_above(height(60)).unwrap(), 0);
+        assert_eq!(cache.invalidate_above(height(45)).unwrap(), 1);
+
+        request.height = QueryHeight::Latest;
+        assert!(cache.get(&request, Sequence::from(7)).is_none());
+
+        // The events not matching the chain are dropped
+        cache.insert(&request, &event);
+        assert!(cache.get(&request, Sequence::from(7)).is_some());
+
+        cache.remove(&request, Sequence::from(7));
+        assert!(cache.get(&request, Sequence::from(7)).is_none());
+    }
+}
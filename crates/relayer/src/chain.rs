@@ -1,8 +1,11 @@
 pub mod client;
+pub mod clock;
 pub mod cosmos;
 pub mod counterparty;
 pub mod endpoint;
 pub mod handle;
+pub mod priority;
+pub mod proof;
 pub mod requests;
 pub mod responses;
 pub mod runtime;
@@ -0,0 +1,178 @@
+//! Sanity checks of the block timestamps reported by the chains, so that a node
+//! whose clock is skewed, or which reports timestamps going back in time, does not
+//! lead the relayer into building timeouts that the counterparty rejects as premature.
+
+use core::time::Duration;
+use std::sync::Mutex;
+
+use tracing::warn;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+
+use crate::chain::endpoint::ChainStatus;
+use crate::util::pretty::PrettyDuration;
+
+/// Something wrong with the timestamp of the latest block of a chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Anomaly {
+    /// The timestamp is ahead of the local time by more than the clock drift allowed for the chain
+    FarFuture { ahead: Duration },
+    /// The timestamp is before the one of a lower height previously seen on the chain
+    NonMonotonic {
+        previous_height: Height,
+        previous_timestamp: Timestamp,
+    },
+}
+
+/// The latest block seen on a chain, to detect the timestamps going back in time.
+///
+/// Each user of the statuses of a chain, e.g. a path relaying to it, tracks them
+/// with its own tracker, which is therefore not shared with the other ones.
+#[derive(Debug, Default)]
+pub struct ClockTracker {
+    latest: Mutex<Option<(Height, Timestamp)>>,
+}
+
+impl ClockTracker {
+    /// Check the timestamp of the latest block of the chain against the previous
+    /// one and against the local time. Returns the timestamp to rely on, which is clamped
+    /// to the local time plus the given clock drift, along with the anomaly found, if any.
+    pub fn check(
+        &self,
+        status: &ChainStatus,
+        clock_drift: Duration,
+        now: Timestamp,
+    ) -> (Timestamp, Option<Anomaly>) {
+        let previous = self
+            .latest
+            .lock()
+            .unwrap()
+            .replace((status.height, status.timestamp));
+
+        let max_timestamp = match now + clock_drift {
+            Ok(max_timestamp) => max_timestamp,
+            Err(_) => return (status.timestamp, None),
+        };
+
+        if let Some(ahead) = status.timestamp.duration_since(&max_timestamp) {
+            if !ahead.is_zero() {
+                return (max_timestamp, Some(Anomaly::FarFuture { ahead }));
+            }
+        }
+
+        match previous {
+            Some((previous_height, previous_timestamp))
+                if previous_height < status.height
+                    && previous_timestamp.after(&status.timestamp) =>
+            {
+                let anomaly = Anomaly::NonMonotonic {
+                    previous_height,
+                    previous_timestamp,
+                };
+
+                // The earlier of the two timestamps is the one which
+                // cannot lead to building premature timeouts.
+                (status.timestamp, Some(anomaly))
+            }
+            _ => (status.timestamp, None),
+        }
+    }
+
+    /// Check the timestamp of the latest block of the given chain, warning about
+    /// any anomaly, and return the status with the timestamp to rely on.
+    pub fn sanitize_status(
+        &self,
+        chain_id: &ChainId,
+        status: ChainStatus,
+        clock_drift: Duration,
+    ) -> ChainStatus {
+        let (timestamp, anomaly) = self.check(&status, clock_drift, Timestamp::now());
+
+        match anomaly {
+            Some(Anomaly::FarFuture { ahead }) => warn!(
+                chain = %chain_id,
+                height = %status.height,
+                "the timestamp {} of the latest block is {} ahead of the local time, beyond the \
+                clock drift of {}: relying on {} instead",
+                status.timestamp,
+                PrettyDuration(&ahead),
+                PrettyDuration(&clock_drift),
+                timestamp,
+            ),
+            Some(Anomaly::NonMonotonic {
+                previous_height,
+                previous_timestamp,
+            }) => warn!(
+                chain = %chain_id,
+                height = %status.height,
+                "the timestamp {} of the latest block is before the timestamp {} seen at the lower \
+                height {}",
+                status.timestamp,
+                previous_timestamp,
+                previous_height,
+            ),
+            None => {}
+        }
+
+        ChainStatus {
+            height: status.height,
+            timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(height: u64, timestamp: Timestamp) -> ChainStatus {
+        ChainStatus {
+            height: Height::new(0, height).unwrap(),
+            timestamp,
+        }
+    }
+
+    fn secs(secs: u64) -> Timestamp {
+        Timestamp::from_nanoseconds(secs * 1_000_000_000).unwrap()
+    }
+
+    #[test]
+    fn clamp_and_detect_anomalies() {
+        let drift = Duration::from_secs(5);
+        let now = secs(1_000);
+
+        let tracker = ClockTracker::default();
+        let check = |height, timestamp| tracker.check(&status(height, timestamp), drift, now);
+
+        // Within the clock drift
+        assert_eq!(check(10, secs(1_004)), (secs(1_004), None));
+
+        // Beyond the clock drift
+        assert_eq!(
+            check(11, secs(1_065)),
+            (
+                secs(1_005),
+                Some(Anomaly::FarFuture {
+                    ahead: Duration::from_secs(60)
+                })
+            )
+        );
+
+        // Going back in time at a higher height
+        assert_eq!(
+            check(12, secs(990)),
+            (
+                secs(990),
+                Some(Anomaly::NonMonotonic {
+                    previous_height: Height::new(0, 11).unwrap(),
+                    previous_timestamp: secs(1_065),
+                })
+            )
+        );
+
+        // Same height queried again
+        assert_eq!(check(12, secs(990)), (secs(990), None));
+    }
+}
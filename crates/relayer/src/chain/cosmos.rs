@@ -4,14 +4,18 @@ use core::{
     convert::{TryFrom, TryInto},
     future::Future,
     str::FromStr,
+    sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
     time::Duration,
 };
 use num_bigint::BigInt;
+use std::collections::HashMap;
 use std::thread;
 
 use bitcoin::hashes::hex::ToHex;
-use futures::future::join_all;
+use futures::{stream, StreamExt};
 use ibc_proto::protobuf::Protobuf;
+use moka::sync::Cache as MokaCache;
+use once_cell::sync::OnceCell;
 use reqwest::Client as RestClient;
 use tendermint::block::Height as TmHeight;
 use tendermint::{
@@ -20,23 +24,26 @@ use tendermint::{
 };
 use tendermint_light_client_verifier::types::LightBlock as TmLightBlock;
 use tendermint_rpc::{
-    endpoint::broadcast::tx_sync::Response, endpoint::status, Client, HttpClient, Order,
+    endpoint::broadcast::tx_sync::Response, endpoint::status, Client, HttpClient, Order, Url,
 };
 use tokio::runtime::Runtime as TokioRuntime;
 use tonic::{codegen::http::Uri, metadata::AsciiMetadataValue};
-use tracing::{error, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::clients::ics07_tendermint::header::Header as TmHeader;
 use ibc::core::ics02_client::client_type::ClientType;
 use ibc::core::ics02_client::error::Error as ClientError;
 use ibc::core::ics03_connection::connection::{ConnectionEnd, IdentifiedConnectionEnd};
 use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd};
 use ibc::core::ics04_channel::packet::{Packet, Sequence};
-use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
+use ibc::core::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
 use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
 use ibc::core::ics24_host::path::{
-    AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath, CommitmentsPath,
-    ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+    AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+    ClientConsensusStatePath, ClientStatePath, CommitmentsPath, ConnectionsPath, ReceiptsPath,
+    SeqRecvsPath,
 };
 use ibc::core::ics24_host::{ClientUpgradePath, Path, IBC_QUERY_PATH, SDK_UPGRADE_QUERY_PATH};
 use ibc::events::IbcEvent;
@@ -50,43 +57,70 @@ use ibc::{
     clients::ics07_tendermint::consensus_state::ConsensusState as TMConsensusState,
     core::ics02_client::events::UpdateClient,
 };
+use ibc_proto::cosmos::base::query::v1beta1::PageRequest as RawPageRequest;
 use ibc_proto::cosmos::staking::v1beta1::Params as StakingParams;
+use ibc_proto::google::protobuf::Any;
 
 use crate::account::Balance;
+use crate::cache::packet_events::{self, PacketEventCache};
 use crate::chain::client::ClientSettings;
-use crate::chain::cosmos::batch::sequential_send_batched_messages_and_wait_commit;
+use crate::chain::cosmos::address::{check_address_prefix, diagnose_account_error};
+use crate::chain::cosmos::archive::{is_pruned_height_error, retained_height_after};
+use crate::chain::cosmos::batch::{
+    send_batched_messages, sequential_send_batched_messages, simulate_batched_messages,
+    wait_for_batched_messages_commit,
+};
+use crate::chain::cosmos::compatibility::Formats;
+use crate::chain::cosmos::deadline::with_deadline;
 use crate::chain::cosmos::gas::mul_ceil;
-use crate::chain::cosmos::query::account::get_or_fetch_account;
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
+use crate::chain::cosmos::memo::tx_memo;
+use crate::chain::cosmos::query::account::{get_or_fetch_account, query_account};
 use crate::chain::cosmos::query::balance::query_balance;
-use crate::chain::cosmos::query::custom_query::rest_query;
+use crate::chain::cosmos::query::batch::batch_query;
+use crate::chain::cosmos::query::block_results::{query_block_results, BlockResultsLayout};
+use crate::chain::cosmos::query::custom_query::cross_chain_query;
 use crate::chain::cosmos::query::denom_trace::query_denom_trace;
+use crate::chain::cosmos::query::fee::{query_counterparty_payee, query_incentivized_packet};
+use crate::chain::cosmos::query::gas_price::GasPriceFeed;
+use crate::chain::cosmos::query::pagination::{paginate, CheckpointFile, Page};
+use crate::chain::cosmos::query::proof::fetch_app_hash;
 use crate::chain::cosmos::query::status::query_status;
 use crate::chain::cosmos::query::tx::query_txs;
+use crate::chain::cosmos::query::wasm::query_wasm_checksums;
 use crate::chain::cosmos::query::{abci_query, fetch_version_specs, packet_query, QueryResponse};
+use crate::chain::cosmos::rotation::{replace_signer, KeyRotation};
 use crate::chain::cosmos::types::account::Account;
-use crate::chain::cosmos::types::config::TxConfig;
+use crate::chain::cosmos::types::config::{FeePayer, TxConfig};
 use crate::chain::cosmos::types::gas::{
     default_gas_from_config, gas_multiplier_from_config, max_gas_from_config,
 };
-use crate::chain::endpoint::{ChainEndpoint, ChainStatus, HealthCheck};
-use crate::chain::requests::CrossChainQueryRequest;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::cosmos::types::tx::TxSyncResult;
+use crate::chain::endpoint::{
+    ChainEndpoint, ChainStatus, DeferredCommit, HealthCheck, SimulatedTx,
+};
+use crate::chain::proof::QueryProof;
+use crate::chain::requests::{BatchQueryRequest, CrossChainQueryRequest};
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
-use crate::config::ChainConfig;
+use crate::config::event_source::EventSource;
+use crate::config::{AddressType, ChainConfig};
 use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
+use crate::denom::cache::DenomTraceCache;
 use crate::denom::DenomTrace;
 use crate::error::Error;
 use crate::event::monitor::{EventMonitor, EventReceiver, TxMonitorCmd};
+use crate::event::pull::EventPuller;
 use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
+use crate::keyring::errors::Error as KeyringError;
+use crate::keyring::remote_signer::RemoteSigner;
 use crate::keyring::{KeyEntry, KeyRing};
 use crate::light_client::tendermint::LightClient as TmLightClient;
 use crate::light_client::{LightClient, Verified};
 use crate::misbehaviour::MisbehaviourEvidence;
 use crate::{
-    chain::cosmos::batch::{
-        send_batched_messages_and_wait_check_tx, send_batched_messages_and_wait_commit,
-    },
+    chain::cosmos::batch::send_batched_messages_and_wait_check_tx,
     util::pretty::{PrettyIdentifiedClientState, PrettyIdentifiedConnection},
 };
 use crate::{
@@ -95,26 +129,35 @@ use crate::{
 };
 
 use super::requests::{
-    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryHeight,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
-    QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
-    QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketEventDataRequest,
-    QueryPacketReceiptRequest, QueryTxRequest, QueryUnreceivedAcksRequest,
-    QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
-    QueryUpgradedConsensusStateRequest,
+    IncludeProof, PageRequest, QueryBlockRequest, QueryChannelClientStateRequest,
+    QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+    QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+    QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+    QueryConsensusStatesRequest, QueryCounterpartyPayeeRequest, QueryHeight,
+    QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryPacketAcknowledgementRequest,
+    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentRequest,
+    QueryPacketCommitmentsRequest, QueryPacketEventDataRequest, QueryPacketReceiptRequest,
+    QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+    QueryUpgradeErrorRequest, QueryUpgradeRequest, QueryUpgradedClientStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWasmChecksumsRequest,
 };
 
+pub mod address;
+pub mod archive;
 pub mod batch;
 pub mod client;
 pub mod compatibility;
+pub mod deadline;
 pub mod encode;
 pub mod estimate;
 pub mod gas;
+pub mod grpc;
+pub mod memo;
+pub mod proxy;
 pub mod query;
 pub mod retry;
+pub mod rotation;
 pub mod simulate;
 pub mod tx;
 pub mod types;
@@ -123,19 +166,44 @@ pub mod wait;
 
 /// fraction of the maximum block size defined in the Tendermint core consensus parameters.
 pub const GENESIS_MAX_BYTES_MAX_FRACTION: f64 = 0.9;
+
+/// How many app hashes of the recent headers are kept to check the query proofs.
+const PROOF_ROOTS_CACHE_CAPACITY: u64 = 1_000;
+
 // https://github.com/cosmos/cosmos-sdk/blob/v0.44.0/types/errors/errors.go#L115-L117
 pub struct CosmosSdkChain {
     config: ChainConfig,
     tx_config: TxConfig,
     rpc_client: HttpClient,
+    /// The client of the archive endpoint of the chain, against which the
+    /// queries of the heights pruned by the full node are retried
+    archive_rpc_client: Option<HttpClient>,
+    /// The lowest height known to be retained by the full node, below which the
+    /// queries are sent directly to the archive endpoint, if any
+    retained_height: AtomicU64,
     rest_client: RestClient,
     grpc_addr: Uri,
+    grpc_settings: GrpcSettings,
     light_client: TmLightClient,
     rt: Arc<TokioRuntime>,
     query_rt: Arc<TokioRuntime>,
     keybase: KeyRing,
-    /// A cached copy of the account information
-    account: Option<Account>,
+    /// The key held by the remote signer of this chain, if any, in place of the key of `key_name`
+    remote_key: Option<KeyEntry>,
+    /// A cached copy of the account information of each of the signing keys, by key name
+    accounts: HashMap<String, Option<Account>>,
+    /// The rotation of the keys signing the transactions
+    key_rotation: KeyRotation,
+    /// The app hashes reported for the recent headers, against which the proofs are checked
+    proof_roots: MokaCache<TmHeight, Vec<u8>>,
+    /// The formats used by the chain, selected by the versions it runs on first use
+    formats: OnceCell<Formats>,
+    /// The denomination traces resolved on this chain, shared across runs
+    denom_traces: DenomTraceCache,
+    /// The packet events found on this chain, persisted across restarts if the cache is enabled
+    packet_events: OnceCell<Option<PacketEventCache>>,
+    /// The gas price of the fee market, if it is followed
+    gas_price_feed: Option<GasPriceFeed>,
 }
 
 impl CosmosSdkChain {
@@ -144,12 +212,24 @@ impl CosmosSdkChain {
         &self.config
     }
 
+    /// The size of the pages the given listing of the packets of a channel is fetched
+    /// by, if it lists all of them and a page size is configured for this chain.
+    fn listing_page_size(&self, pagination: &Option<PageRequest>) -> Option<u64> {
+        self.config
+            .query_page_size
+            .filter(|_| pagination.as_ref().map_or(false, PageRequest::is_all))
+    }
+
     /// The maximum size of any transaction sent by the relayer to this chain
     fn max_tx_size(&self) -> usize {
         self.config.max_tx_size.into()
     }
 
     fn key(&self) -> Result<KeyEntry, Error> {
+        if let Some(key) = &self.remote_key {
+            return Ok(key.clone());
+        }
+
         self.keybase()
             .get_key(&self.config.key_name)
             .map_err(Error::key_base)
@@ -218,7 +298,7 @@ impl CosmosSdkChain {
 
         // Check on the configured max_tx_size against the consensus parameters at latest height
         let result = self
-            .block_on(self.rpc_client.consensus_params(latest_height))
+            .block_on(self.rpc_client.consensus_params(latest_height))?
             .map_err(|e| {
                 Error::config_validation_json_rpc(
                     self.id().clone(),
@@ -272,24 +352,45 @@ impl CosmosSdkChain {
         Ok(())
     }
 
+    /// Check that the address of the key used on this chain has the prefix
+    /// of the chain, and that an account exists on the chain for it.
+    fn validate_key(&self) -> Result<(), Error> {
+        let key_entry = self.key()?;
+
+        check_address_prefix(
+            self.id(),
+            &self.config.key_name,
+            &key_entry.account,
+            &self.config.account_prefix,
+        )?;
+
+        self.block_on(query_account(
+            &self.grpc_addr,
+            &self.grpc_settings,
+            &key_entry.account,
+        ))?
+        .map_err(|e| {
+            diagnose_account_error(self.id(), &self.config.key_name, &key_entry.account, e)
+        })?;
+
+        Ok(())
+    }
+
     /// Query the chain staking parameters
     pub fn query_staking_params(&self) -> Result<StakingParams, Error> {
         crate::time!("query_staking_params");
         crate::telemetry!(query, self.id(), "query_staking_params");
 
         let mut client = self
-            .block_on(
-                ibc_proto::cosmos::staking::v1beta1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::cosmos::staking::v1beta1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request =
             tonic::Request::new(ibc_proto::cosmos::staking::v1beta1::QueryParamsRequest {});
 
         let response = self
-            .block_on(client.params(request))
+            .block_on(client.params(request))?
             .map_err(Error::grpc_status)?;
 
         let params = response
@@ -321,10 +422,234 @@ impl CosmosSdkChain {
         self.query_staking_params().map(|p| p.historical_entries)
     }
 
-    /// Run a future to completion on the Tokio runtime.
-    fn block_on<F: Future>(&self, f: F) -> F::Output {
+    /// Run a query to completion on the Tokio runtime, failing if it does
+    /// not complete within the `query_timeout` configured for the chain.
+    fn block_on<F: Future>(&self, f: F) -> Result<F::Output, Error> {
         crate::time!("block_on");
-        self.rt.block_on(f)
+        self.rt.block_on(with_deadline(
+            self.id(),
+            "query",
+            self.config.query_timeout,
+            f,
+        ))
+    }
+
+    /// Run the given query against the RPC endpoint of the chain and, if it fails because
+    /// the full node pruned the queried height, retry it against the archive endpoint
+    /// configured for the chain, so that the state and events of old heights, such as
+    /// the ones needed to clear old packets, can still be retrieved.
+    ///
+    /// The queries of a given height below the lowest one the full node was found to
+    /// retain are sent directly to the archive endpoint.
+    fn block_on_with_archive<'a, T, F, Fut>(
+        &'a self,
+        query: &str,
+        height: Option<TmHeight>,
+        f: F,
+    ) -> Result<T, Error>
+    where
+        F: Fn(&'a HttpClient, &'a Url) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let archive = self
+            .archive_rpc_client
+            .as_ref()
+            .zip(self.config.archive_rpc_addr.as_ref());
+
+        // A height of zero queries the latest height
+        let height = height.map(|height| height.value()).filter(|&h| h > 0);
+
+        if let (Some(height), Some((archive_rpc_client, archive_rpc_addr))) = (height, archive) {
+            if height < self.retained_height.load(AtomicOrdering::Relaxed) {
+                debug!(
+                    chain = %self.id(),
+                    "{} at height {} pruned by the full node, sending it to archive endpoint {}",
+                    query, height, archive_rpc_addr
+                );
+
+                crate::telemetry!(archive_query, self.id(), query);
+
+                return self.block_on(f(archive_rpc_client, archive_rpc_addr))?;
+            }
+        }
+
+        let result = self.block_on(f(&self.rpc_client, &self.config.rpc_addr))?;
+
+        match (result, archive) {
+            (Err(e), Some((archive_rpc_client, archive_rpc_addr)))
+                if is_pruned_height_error(&e) =>
+            {
+                if let Some(height) = height {
+                    self.retained_height
+                        .fetch_max(retained_height_after(&e, height), AtomicOrdering::Relaxed);
+                }
+
+                warn!(
+                    chain = %self.id(),
+                    "{} failed on pruned height, retrying against archive endpoint {}: {}",
+                    query, archive_rpc_addr, e
+                );
+
+                crate::telemetry!(archive_query, self.id(), query);
+
+                self.block_on(f(archive_rpc_client, archive_rpc_addr))?
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// The formats used by the chain, selected when first needed from the versions of the
+    /// modules it runs, logging whether these versions are outside of the supported ranges.
+    ///
+    /// If the versions cannot be queried, the formats are selected from the version
+    /// reported by the node, see [`compatibility::Formats`].
+    fn formats(&self) -> Result<Formats, Error> {
+        self.formats
+            .get_or_try_init(|| {
+                let specs = self
+                    .block_on(fetch_version_specs(
+                        self.id(),
+                        &self.grpc_addr,
+                        &self.grpc_settings,
+                    ))
+                    .and_then(|specs| specs);
+
+                let formats = match specs {
+                    Ok(specs) => {
+                        if let Err(diagnostic) = compatibility::run_diagnostic(&specs) {
+                            warn!(
+                                chain = %self.id(),
+                                "{}: some of the queries, events and messages of the chain may fail to decode",
+                                diagnostic
+                            );
+                        }
+
+                        let formats = Formats::from_specs(&specs);
+
+                        info!(
+                            chain = %self.id(),
+                            "chain runs {}, using the {:?} layout of block results",
+                            specs, formats.block_results_layout
+                        );
+
+                        formats
+                    }
+                    Err(e) => {
+                        warn!(
+                            chain = %self.id(),
+                            "failed to detect the versions run by the chain, \
+                            selecting its formats from the version of the node: {}",
+                            e
+                        );
+
+                        let status = self
+                            .block_on(self.rpc_client.status())?
+                            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+
+                        let formats =
+                            Formats::from_node_version(&status.node_info.version.to_string());
+
+                        debug!(
+                            chain = %self.id(),
+                            node_version = %status.node_info.version,
+                            "selected the {:?} layout of block results", formats.block_results_layout
+                        );
+
+                        formats
+                    }
+                };
+
+                Ok(formats)
+            })
+            .copied()
+    }
+
+    /// The layout of the block results of the node, selected by the version it runs,
+    /// as CometBFT 0.38 emits the events of BeginBlock and EndBlock under FinalizeBlock.
+    fn block_results_layout(&self) -> Result<BlockResultsLayout, Error> {
+        self.formats().map(|formats| formats.block_results_layout)
+    }
+
+    /// The cache of the packet events of this chain, if the cache is enabled. The events
+    /// emitted above the latest height of the chain are dropped on first use, as the chain
+    /// was rolled back below them while the relayer was stopped.
+    fn packet_event_cache(&self) -> Option<&PacketEventCache> {
+        self.packet_events
+            .get_or_init(|| {
+                let cache = packet_events::for_chain(self.id())?
+                    .map_err(|e| warn!(chain = %self.id(), "packet event cache unavailable: {}", e))
+                    .ok()?;
+
+                let latest_height = self
+                    .query_chain_latest_height()
+                    .map_err(|e| warn!(chain = %self.id(), "packet event cache unavailable: {}", e))
+                    .ok()?;
+
+                match cache.invalidate_above(latest_height) {
+                    Ok(0) => {}
+                    Ok(dropped) => warn!(
+                        chain = %self.id(),
+                        "dropped {} cached packet events above the latest height {}, \
+                        which the chain was rolled back below",
+                        dropped,
+                        latest_height
+                    ),
+                    Err(e) => {
+                        warn!(chain = %self.id(), "packet event cache unavailable: {}", e);
+                        return None;
+                    }
+                }
+
+                Some(cache)
+            })
+            .as_ref()
+    }
+
+    /// Whether the given cached event matches the commitment, or acknowledgement, stored
+    /// by the chain at the height of the request. A cached event may describe a packet
+    /// which does not exist anymore, if the chain was reset under the same identifier.
+    fn matches_commitment(
+        &self,
+        request: &QueryPacketEventDataRequest,
+        cached: &IbcEventWithHeight,
+    ) -> bool {
+        let packet = match cached.event.packet() {
+            Some(packet) => packet,
+            None => return false,
+        };
+
+        let stored = match &cached.event {
+            IbcEvent::SendPacket(_) => self.query_packet_commitment(
+                QueryPacketCommitmentRequest {
+                    port_id: packet.source_port.clone(),
+                    channel_id: packet.source_channel.clone(),
+                    sequence: packet.sequence,
+                    height: request.height,
+                },
+                IncludeProof::No,
+            ),
+            IbcEvent::WriteAcknowledgement(_) => self.query_packet_acknowledgement(
+                QueryPacketAcknowledgementRequest {
+                    port_id: packet.destination_port.clone(),
+                    channel_id: packet.destination_channel.clone(),
+                    sequence: packet.sequence,
+                    height: request.height,
+                },
+                IncludeProof::No,
+            ),
+            _ => return false,
+        };
+
+        match stored {
+            Ok((stored, _)) => packet_events::commitment(&cached.event) == Some(stored),
+            Err(e) => {
+                debug!(
+                    chain = %self.id(),
+                    "failed to check the cached event of packet {}: {}", packet.sequence, e
+                );
+                false
+            }
+        }
     }
 
     fn query(
@@ -346,21 +671,101 @@ impl CosmosSdkChain {
             return Err(Error::private_store());
         }
 
-        let response = self.block_on(abci_query(
-            &self.rpc_client,
-            &self.config.rpc_addr,
-            path,
-            data.to_string(),
-            height,
-            prove,
-        ))?;
-
-        // TODO - Verify response proof, if requested.
-        if prove {}
+        let response =
+            self.block_on_with_archive("abci_query", Some(height), |rpc_client, rpc_addr| {
+                abci_query(
+                    rpc_client,
+                    rpc_addr,
+                    path.clone(),
+                    data.to_string(),
+                    height,
+                    prove,
+                )
+            })?;
 
         Ok(response)
     }
 
+    /// Perform an ABCI query of the IBC store, returning the verified proof of
+    /// the resulting value along with it if `include_proof` is set.
+    fn query_with_proof(
+        &self,
+        data: impl Into<Path>,
+        height_query: QueryHeight,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
+        let data = data.into();
+        let path = data.to_string();
+
+        let response = self.query(
+            data,
+            height_query,
+            matches!(include_proof, IncludeProof::Yes),
+        )?;
+
+        match include_proof {
+            IncludeProof::Yes => {
+                let proof = response.proof.ok_or_else(Error::empty_response_proof)?;
+                let height = ICSHeight::new(self.id().version(), response.height.value())
+                    .map_err(|_| Error::invalid_height_no_source())?;
+
+                let proof = QueryProof::new(height, path, proof);
+
+                if self.config.check_query_proof_consistency {
+                    self.check_query_proof_consistency(&proof, &response.value)?;
+                }
+
+                Ok((response.value, Some(proof)))
+            }
+            IncludeProof::No => Ok((response.value, None)),
+        }
+    }
+
+    /// Check the given proof of the given value against the app hash of the header at
+    /// the proof height, as reported by the full node, before it is packed into messages,
+    /// see [`fetch_app_hash`]. This only catches the inconsistent responses of the node.
+    fn check_query_proof_consistency(&self, proof: &QueryProof, value: &[u8]) -> Result<(), Error> {
+        crate::time!("check_query_proof_consistency");
+
+        let root_height = TmHeight::try_from(proof.proof_height().revision_height())
+            .map_err(Error::invalid_height)?;
+
+        let root = match self.proof_roots.get(&root_height) {
+            Some(root) => root,
+            None => {
+                let root = self.block_on_with_archive(
+                    "proof_root",
+                    Some(root_height),
+                    |rpc_client, rpc_addr| {
+                        fetch_app_hash(
+                            rpc_client,
+                            rpc_addr,
+                            root_height,
+                            self.config.max_block_time,
+                        )
+                    },
+                )?;
+
+                self.proof_roots.insert(root_height, root.clone());
+                root
+            }
+        };
+
+        let prefix = self.query_commitment_prefix()?;
+        let specs = self.config.proof_specs.clone().unwrap_or_default();
+
+        proof
+            .verify(&specs, &prefix, &CommitmentRoot::from_bytes(&root), value)
+            .map_err(|e| {
+                Error::inconsistent_query_proof(
+                    self.id().clone(),
+                    proof.path.clone(),
+                    proof.height.revision_height(),
+                    e,
+                )
+            })
+    }
+
     /// Perform an ABCI query against the client upgrade sub-store.
     ///
     /// The data is returned in its raw format `Vec<u8>`, and is either the
@@ -394,7 +799,7 @@ impl CosmosSdkChain {
             Path::Upgrade(query_data).to_string(),
             TmHeight::try_from(query_height.revision_height()).map_err(Error::invalid_height)?,
             true,
-        ))?;
+        ))??;
 
         let proof = response.proof.ok_or_else(Error::empty_response_proof)?;
 
@@ -410,7 +815,7 @@ impl CosmosSdkChain {
         crate::telemetry!(query, self.id(), "status");
 
         let status = self
-            .block_on(self.rpc_client.status())
+            .block_on(self.rpc_client.status())?
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         if status.sync_info.catching_up {
@@ -428,15 +833,52 @@ impl CosmosSdkChain {
         crate::time!("query_latest_height");
         crate::telemetry!(query, self.id(), "query_latest_height");
 
-        let status = self.rt.block_on(query_status(
+        let status = self.block_on(query_status(
             self.id(),
             &self.rpc_client,
             &self.config.rpc_addr,
-        ))?;
+        ))??;
 
         Ok(status.height)
     }
 
+    /// Pay the last gas price queried from the fee market of the chain, if it is followed.
+    fn apply_gas_price(&mut self) {
+        let latest = self.gas_price_feed.as_ref().and_then(GasPriceFeed::latest);
+
+        if let Some(gas_price) = latest {
+            if gas_price != self.tx_config.gas_config.gas_price {
+                self.tx_config.gas_config.set_gas_price(gas_price);
+            }
+        }
+    }
+
+    /// The name and the entry of the key signing the transactions of the given messages,
+    /// along with the messages signed by it, whose signer is replaced by its address if it
+    /// is not the key of `key_name`, see [`KeyRotation`].
+    fn signing_key(&mut self, messages: Vec<Any>) -> Result<(String, KeyEntry, Vec<Any>), Error> {
+        let key_name = self.key_rotation.next_key(&messages).to_string();
+        let key_entry = self.key()?;
+
+        if key_name == self.config.key_name {
+            return Ok((key_name, key_entry, messages));
+        }
+
+        let signing_key = self
+            .keybase()
+            .get_key(&key_name)
+            .map_err(|e| Error::key_not_found(key_name.clone(), e))?;
+
+        let messages = messages
+            .iter()
+            .map(|message| replace_signer(message, &key_entry.account, &signing_key.account))
+            .collect::<Result<_, _>>()?;
+
+        debug!(key = %key_name, "signing the transactions with a rotated key");
+
+        Ok((key_name, signing_key, messages))
+    }
+
     #[instrument(
         name = "send_messages_and_wait_commit",
         level = "error",
@@ -446,38 +888,56 @@ impl CosmosSdkChain {
             tracking_id = %tracked_msgs.tracking_id()
         ),
     )]
-    async fn do_send_messages_and_wait_commit(
+    async fn do_send_messages_and_defer_commit(
         &mut self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<IbcEventWithHeight>, Error> {
+    ) -> Result<Vec<TxSyncResult>, Error> {
         crate::time!("send_messages_and_wait_commit");
 
-        let proto_msgs = tracked_msgs.msgs;
+        self.apply_gas_price();
 
-        let key_entry = self.key()?;
+        let (key_name, key_entry, proto_msgs) = self.signing_key(tracked_msgs.msgs)?;
 
-        let account =
-            get_or_fetch_account(&self.grpc_addr, &key_entry.account, &mut self.account).await?;
+        let account = with_deadline(
+            &self.config.id,
+            "account query",
+            self.config.query_timeout,
+            get_or_fetch_account(
+                &self.grpc_addr,
+                &self.grpc_settings,
+                &key_entry.account,
+                self.accounts.entry(key_name.clone()).or_default(),
+            ),
+        )
+        .await?
+        .map_err(|e| diagnose_account_error(&self.config.id, &key_name, &key_entry.account, e))?;
+
+        let tx_memo = tx_memo(
+            self.config.tx_memo_template.as_ref(),
+            self.config.id.as_str(),
+            &proto_msgs,
+            &self.config.memo_prefix,
+        );
 
         if self.config.sequential_batch_tx {
-            sequential_send_batched_messages_and_wait_commit(
+            sequential_send_batched_messages(
                 &self.tx_config,
                 self.config.max_msg_num,
                 self.config.max_tx_size,
                 &key_entry,
                 account,
-                &self.config.memo_prefix,
+                &tx_memo,
                 proto_msgs,
             )
             .await
         } else {
-            send_batched_messages_and_wait_commit(
+            send_batched_messages(
                 &self.tx_config,
                 self.config.max_msg_num,
                 self.config.max_tx_size,
                 &key_entry,
                 account,
-                &self.config.memo_prefix,
+                &tx_memo,
                 proto_msgs,
             )
             .await
@@ -499,12 +959,30 @@ impl CosmosSdkChain {
     ) -> Result<Vec<Response>, Error> {
         crate::time!("send_messages_and_wait_check_tx");
 
-        let proto_msgs = tracked_msgs.msgs;
+        self.apply_gas_price();
 
-        let key_entry = self.key()?;
+        let (key_name, key_entry, proto_msgs) = self.signing_key(tracked_msgs.msgs)?;
 
-        let account =
-            get_or_fetch_account(&self.grpc_addr, &key_entry.account, &mut self.account).await?;
+        let account = with_deadline(
+            &self.config.id,
+            "account query",
+            self.config.query_timeout,
+            get_or_fetch_account(
+                &self.grpc_addr,
+                &self.grpc_settings,
+                &key_entry.account,
+                self.accounts.entry(key_name.clone()).or_default(),
+            ),
+        )
+        .await?
+        .map_err(|e| diagnose_account_error(&self.config.id, &key_name, &key_entry.account, e))?;
+
+        let tx_memo = tx_memo(
+            self.config.tx_memo_template.as_ref(),
+            self.config.id.as_str(),
+            &proto_msgs,
+            &self.config.memo_prefix,
+        );
 
         send_batched_messages_and_wait_check_tx(
             &self.tx_config,
@@ -512,11 +990,57 @@ impl CosmosSdkChain {
             self.config.max_tx_size,
             &key_entry,
             account,
-            &self.config.memo_prefix,
+            &tx_memo,
             proto_msgs,
         )
         .await
     }
+
+    async fn do_simulate_messages(
+        &mut self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<SimulatedTx>, Error> {
+        crate::time!("simulate_messages");
+
+        self.apply_gas_price();
+
+        let key_entry = self.key()?;
+
+        // The account is queried anew, so that the simulations see its current sequence
+        let account = with_deadline(
+            &self.config.id,
+            "account query",
+            self.config.query_timeout,
+            query_account(&self.grpc_addr, &self.grpc_settings, &key_entry.account),
+        )
+        .await?
+        .map_err(|e| {
+            diagnose_account_error(
+                &self.config.id,
+                &self.config.key_name,
+                &key_entry.account,
+                e,
+            )
+        })?;
+
+        let tx_memo = tx_memo(
+            self.config.tx_memo_template.as_ref(),
+            self.config.id.as_str(),
+            &tracked_msgs.msgs,
+            &self.config.memo_prefix,
+        );
+
+        simulate_batched_messages(
+            &self.tx_config,
+            self.config.max_msg_num,
+            self.config.max_tx_size,
+            &key_entry,
+            &account.into(),
+            &tx_memo,
+            tracked_msgs.msgs,
+        )
+        .await
+    }
 }
 
 impl ChainEndpoint for CosmosSdkChain {
@@ -530,38 +1054,138 @@ impl ChainEndpoint for CosmosSdkChain {
         rt: Arc<TokioRuntime>,
         query_rt: Arc<TokioRuntime>,
     ) -> Result<Self, Error> {
-        let rpc_client = HttpClient::new(config.rpc_addr.clone())
-            .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))?;
+        let rpc_client = proxy::rpc_client(&config.rpc_addr, config.proxy.as_ref())?;
+
+        let archive_rpc_client = config
+            .archive_rpc_addr
+            .as_ref()
+            .map(|addr| proxy::rpc_client(addr, config.proxy.as_ref()))
+            .transpose()?;
 
         let rest_client = RestClient::builder()
             .timeout(Duration::from_secs(10))
             .build()
             .map_err(|_| Error::rest_client())?;
 
-        let light_client = rt.block_on(init_light_client(&rpc_client, &config))?;
+        let light_client = rt.block_on(with_deadline(
+            &config.id,
+            "light client initialization",
+            config.query_timeout,
+            init_light_client(&rpc_client, &config),
+        ))??;
 
         // Initialize key store and load key
-        let keybase = KeyRing::new(config.key_store_type, &config.account_prefix, &config.id)
-            .map_err(Error::key_base)?;
+        let keybase = KeyRing::new_namespaced(
+            config.key_store_type,
+            &config.account_prefix,
+            &config.id,
+            config.tenant.as_deref(),
+            config.key_store_folder.as_deref(),
+        )
+        .map_err(Error::key_base)?;
 
         let grpc_addr = Uri::from_str(&config.grpc_addr.to_string())
             .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
 
-        let tx_config = TxConfig::try_from(&config)?;
+        // Fail early if one of the keys signing in turn is missing
+        for key_name in &config.key_names {
+            keybase
+                .get_key(key_name)
+                .map_err(|e| Error::key_not_found(key_name.clone(), e))?;
+        }
+
+        let mut tx_config = TxConfig::try_from(&config)?;
+
+        if let Some(fee_payer) = &config.fee_payer {
+            let key_entry = keybase.get_key(fee_payer).map_err(Error::key_base)?;
+            tx_config.fee_payer = Some(FeePayer::new(key_entry));
+        }
+
+        let remote_key = match &config.remote_signer {
+            Some(remote_signer_config) => {
+                if config.address_type != AddressType::Cosmos {
+                    return Err(Error::key_base(KeyringError::remote_signer(
+                        remote_signer_config.url.to_string(),
+                        "only the chains of address type 'cosmos' are supported".to_string(),
+                    )));
+                }
+
+                let mut remote_signer =
+                    RemoteSigner::new(remote_signer_config, config.id.clone(), rt.clone())
+                        .map_err(Error::key_base)?;
+
+                let key = remote_signer
+                    .fetch_key(&config.account_prefix)
+                    .map_err(Error::key_base)?;
+
+                info!(
+                    chain = %config.id,
+                    account = %key.account,
+                    "signing the transactions with the remote signer at {}",
+                    remote_signer_config.url
+                );
+
+                tx_config.signing_provider = Arc::new(remote_signer);
+
+                Some(key)
+            }
+            None => None,
+        };
+
+        // The WebSocket client cannot be proxied, the events are then pulled over RPC
+        if let (Some(proxy), EventSource::Push) = (&config.proxy, config.event_source) {
+            return Err(Error::unsupported_proxy(
+                proxy.to_string(),
+                config.websocket_addr.to_string(),
+            ));
+        }
+
+        if config.client_tls.is_some() {
+            warn!(
+                chain = %config.id,
+                "the client certificate is only presented to the gRPC endpoint, \
+                not to the RPC and WebSocket endpoints"
+            );
+        }
 
         // Retrieve the version specification of this chain
 
+        let denom_traces = DenomTraceCache::for_chain(&config.id);
+
+        let key_rotation = KeyRotation::new(&config);
+
+        let gas_price_feed = config.dynamic_gas_price.clone().map(|dynamic| {
+            GasPriceFeed::spawn(
+                &rt,
+                config.id.clone(),
+                rpc_client.clone(),
+                config.rpc_addr.clone(),
+                dynamic,
+                config.gas_price.clone(),
+            )
+        });
+
         let chain = Self {
             config,
             rpc_client,
+            archive_rpc_client,
+            retained_height: AtomicU64::new(0),
             rest_client,
             grpc_addr,
+            grpc_settings: tx_config.grpc_settings.clone(),
             light_client,
             rt,
             query_rt,
             keybase,
-            account: None,
+            remote_key,
+            accounts: HashMap::new(),
+            key_rotation,
             tx_config,
+            proof_roots: MokaCache::new(PROOF_ROOTS_CACHE_CAPACITY),
+            formats: OnceCell::new(),
+            denom_traces,
+            packet_events: OnceCell::new(),
+            gas_price_feed,
         };
 
         Ok(chain)
@@ -573,6 +1197,21 @@ impl ChainEndpoint for CosmosSdkChain {
     ) -> Result<(EventReceiver, TxMonitorCmd), Error> {
         crate::time!("init_event_monitor");
 
+        if let EventSource::Pull { interval } = self.config.event_source {
+            let (event_puller, event_receiver, puller_tx) = EventPuller::new(
+                self.config.id.clone(),
+                self.rpc_client.clone(),
+                self.config.rpc_addr.clone(),
+                self.block_results_layout()?,
+                interval,
+                rt,
+            );
+
+            thread::spawn(move || event_puller.run());
+
+            return Ok((event_receiver, puller_tx));
+        }
+
         let (mut event_monitor, event_receiver, monitor_tx) = EventMonitor::new(
             self.config.id.clone(),
             self.config.websocket_addr.clone(),
@@ -634,6 +1273,21 @@ impl ChainEndpoint for CosmosSdkChain {
         Ok(HealthCheck::Healthy)
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        if let Err(e) = self.validate_key() {
+            warn!(
+                "the key of Hermes might be misconfigured for chain '{}'",
+                self.id()
+            );
+            warn!("    Reason: {}", e.detail());
+            warn!("    Hermes will not be able to submit transactions to this chain!");
+
+            return Ok(HealthCheck::Unhealthy(Box::new(e)));
+        }
+
+        Ok(HealthCheck::Healthy)
+    }
+
     /// Fetch a header from the chain at the given height and verify it.
     fn verify_header(
         &mut self,
@@ -670,9 +1324,22 @@ impl ChainEndpoint for CosmosSdkChain {
         &mut self,
         tracked_msgs: TrackedMsgs,
     ) -> Result<Vec<IbcEventWithHeight>, Error> {
+        self.send_messages_and_defer_commit(tracked_msgs)()
+    }
+
+    fn send_messages_and_defer_commit(&mut self, tracked_msgs: TrackedMsgs) -> DeferredCommit {
         let runtime = self.rt.clone();
+        let tx_config = self.tx_config.clone();
 
-        runtime.block_on(self.do_send_messages_and_wait_commit(tracked_msgs))
+        match runtime.block_on(self.do_send_messages_and_defer_commit(tracked_msgs)) {
+            Ok(tx_sync_results) => Box::new(move || {
+                runtime.block_on(wait_for_batched_messages_commit(
+                    &tx_config,
+                    tx_sync_results,
+                ))
+            }),
+            Err(e) => Box::new(move || Err(e)),
+        }
     }
 
     fn send_messages_and_wait_check_tx(
@@ -684,15 +1351,26 @@ impl ChainEndpoint for CosmosSdkChain {
         runtime.block_on(self.do_send_messages_and_wait_check_tx(tracked_msgs))
     }
 
+    fn simulate_messages(&mut self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        let runtime = self.rt.clone();
+
+        runtime.block_on(self.do_simulate_messages(tracked_msgs))
+    }
+
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        crate::time!("broadcast_evidence");
+
+        self.block_on(self.rpc_client.broadcast_evidence(evidence))?
+            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+
+        Ok(())
+    }
+
     /// Get the account for the signer
     fn get_signer(&mut self) -> Result<Signer, Error> {
         crate::time!("get_signer");
 
-        // Get the key from key seed file
-        let key = self
-            .keybase()
-            .get_key(&self.config.key_name)
-            .map_err(|e| Error::key_not_found(self.config.key_name.clone(), e))?;
+        let key = self.get_key()?;
 
         let bech32 = encode_to_bech32(&key.address.to_hex(), &self.config.account_prefix)?;
         bech32
@@ -709,6 +1387,10 @@ impl ChainEndpoint for CosmosSdkChain {
     fn get_key(&mut self) -> Result<KeyEntry, Error> {
         crate::time!("get_key");
 
+        if let Some(key) = &self.remote_key {
+            return Ok(key.clone());
+        }
+
         // Get the key from key seed file
         let key = self
             .keybase()
@@ -727,10 +1409,23 @@ impl ChainEndpoint for CosmosSdkChain {
     }
 
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
-        let version_specs = self.block_on(fetch_version_specs(self.id(), &self.grpc_addr))?;
+        let version_specs = self.block_on(fetch_version_specs(
+            self.id(),
+            &self.grpc_addr,
+            &self.grpc_settings,
+        ))??;
         Ok(version_specs.ibc_go)
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        let version_specs = self.block_on(fetch_version_specs(
+            self.id(),
+            &self.grpc_addr,
+            &self.grpc_settings,
+        ))??;
+        Ok(Some(version_specs.cosmos_sdk))
+    }
+
     fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
         // If a key_name is given, extract the account hash.
         // Else retrieve the account from the configuration file.
@@ -747,15 +1442,26 @@ impl ChainEndpoint for CosmosSdkChain {
 
         let balance = self.block_on(query_balance(
             &self.grpc_addr,
+            &self.grpc_settings,
             &account,
             &self.config.gas_price.denom,
-        ))?;
+        ))??;
 
         Ok(balance)
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
-        let denom_trace = self.block_on(query_denom_trace(&self.grpc_addr, &hash))?;
+        if let Some(denom_trace) = self.denom_traces.get(&hash) {
+            return Ok(denom_trace);
+        }
+
+        let denom_trace = self.block_on(query_denom_trace(
+            &self.grpc_addr,
+            &self.grpc_settings,
+            &hash,
+        ))??;
+
+        self.denom_traces.insert(&hash, denom_trace.clone());
 
         Ok(denom_trace)
     }
@@ -778,7 +1484,7 @@ impl ChainEndpoint for CosmosSdkChain {
         // Instead, we need to pull block height via `/abci_info` and then fetch block
         // metadata at the given height via `/blockchain` endpoint.
         let abci_info = self
-            .block_on(self.rpc_client.abci_info())
+            .block_on(self.rpc_client.abci_info())?
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         // Query `/blockchain` endpoint to pull the block metadata corresponding to
@@ -789,7 +1495,7 @@ impl ChainEndpoint for CosmosSdkChain {
             .block_on(
                 self.rpc_client
                     .blockchain(abci_info.last_block_height, abci_info.last_block_height),
-            )
+            )?
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?
             .block_metas;
 
@@ -801,6 +1507,8 @@ impl ChainEndpoint for CosmosSdkChain {
             .map_err(|_| Error::invalid_height_no_source())?;
             let timestamp = latest_app_block.header.time.into();
 
+            crate::block_time::observe(self.id(), height, timestamp);
+
             Ok(ChainStatus { height, timestamp })
         } else {
             // The `/blockchain` query failed to return the header we wanted
@@ -818,16 +1526,13 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_clients");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::client::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::client::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
         let response = self
-            .block_on(client.client_states(request))
+            .block_on(client.client_states(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -858,24 +1563,18 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
         crate::time!("query_client_state");
         crate::telemetry!(query, self.id(), "query_client_state");
 
-        let res = self.query(
+        let (value, proof) = self.query_with_proof(
             ClientStatePath(request.client_id.clone()),
             request.height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
-        let client_state = AnyClientState::decode_vec(&res.value).map_err(Error::decode)?;
+        let client_state = AnyClientState::decode_vec(&value).map_err(Error::decode)?;
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-                Ok((client_state, Some(proof)))
-            }
-            IncludeProof::No => Ok((client_state, None)),
-        }
+        Ok((client_state, proof))
     }
 
     fn query_upgraded_client_state(
@@ -935,16 +1634,17 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_consensus_states");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::client::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::client::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
-        let request = tonic::Request::new(request.into());
+        let height = specific_height(request.height);
+        let request = at_height(
+            ibc_proto::ibc::core::client::v1::QueryConsensusStatesRequest::from(request),
+            height,
+        )?;
         let response = self
-            .block_on(client.consensus_states(request))
+            .block_on(client.consensus_states(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -972,21 +1672,21 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
         crate::time!("query_consensus_state");
         crate::telemetry!(query, self.id(), "query_consensus_state");
 
-        let res = self.query(
+        let (value, proof) = self.query_with_proof(
             ClientConsensusStatePath {
                 client_id: request.client_id.clone(),
                 epoch: request.consensus_height.revision_number(),
                 height: request.consensus_height.revision_height(),
             },
             request.query_height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
 
-        let consensus_state = AnyConsensusState::decode_vec(&res.value).map_err(Error::decode)?;
+        let consensus_state = AnyConsensusState::decode_vec(&value).map_err(Error::decode)?;
 
         if !matches!(consensus_state, AnyConsensusState::Tendermint(_)) {
             return Err(Error::consensus_state_type_mismatch(
@@ -995,13 +1695,7 @@ impl ChainEndpoint for CosmosSdkChain {
             ));
         }
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-                Ok((consensus_state, Some(proof)))
-            }
-            IncludeProof::No => Ok((consensus_state, None)),
-        }
+        Ok((consensus_state, proof))
     }
 
     fn query_client_connections(
@@ -1012,16 +1706,13 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_client_connections");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::connection::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::connection::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
-        let response = match self.block_on(client.client_connections(request)) {
+        let response = match self.block_on(client.client_connections(request))? {
             Ok(res) => res.into_inner(),
             Err(e) if e.code() == tonic::Code::NotFound => return Ok(vec![]),
             Err(e) => return Err(Error::grpc_status(e)),
@@ -1048,17 +1739,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_connections");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::connection::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::connection::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.connections(request))
+            .block_on(client.connections(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1085,7 +1773,7 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         crate::time!("query_connection");
         crate::telemetry!(query, self.id(), "query_connection");
 
@@ -1097,10 +1785,10 @@ impl ChainEndpoint for CosmosSdkChain {
             use ibc_proto::ibc::core::connection::v1 as connection;
             use tonic::IntoRequest;
 
-            let mut client =
-                connection::query_client::QueryClient::connect(chain.grpc_addr.clone())
-                    .await
-                    .map_err(Error::grpc_transport)?;
+            let mut client = grpc::connect(&chain.grpc_addr, &chain.grpc_settings)
+                .await
+                .map(connection::query_client::QueryClient::new)
+                .map_err(Error::grpc_transport)?;
 
             let mut request = connection::QueryConnectionRequest {
                 connection_id: connection_id.to_string(),
@@ -1139,23 +1827,19 @@ impl ChainEndpoint for CosmosSdkChain {
 
         match include_proof {
             IncludeProof::Yes => {
-                let res = self.query(
+                let (value, proof) = self.query_with_proof(
                     ConnectionsPath(request.connection_id.clone()),
                     request.height,
-                    true,
+                    include_proof,
                 )?;
-                let connection_end =
-                    ConnectionEnd::decode_vec(&res.value).map_err(Error::decode)?;
+                let connection_end = ConnectionEnd::decode_vec(&value).map_err(Error::decode)?;
 
-                Ok((
-                    connection_end,
-                    Some(res.proof.ok_or_else(Error::empty_response_proof)?),
-                ))
+                Ok((connection_end, proof))
             }
             IncludeProof::No => self
                 .block_on(async {
                     do_query_connection(self, &request.connection_id, request.height).await
-                })
+                })?
                 .map(|conn_end| (conn_end, None)),
         }
     }
@@ -1168,17 +1852,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_connection_channels");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.connection_channels(request))
+            .block_on(client.connection_channels(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1208,17 +1889,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_channels");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.channels(request))
+            .block_on(client.channels(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1244,25 +1922,19 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         crate::time!("query_channel");
         crate::telemetry!(query, self.id(), "query_channel");
 
-        let res = self.query(
+        let (value, proof) = self.query_with_proof(
             ChannelEndsPath(request.port_id, request.channel_id),
             request.height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
 
-        let channel_end = ChannelEnd::decode_vec(&res.value).map_err(Error::decode)?;
+        let channel_end = ChannelEnd::decode_vec(&value).map_err(Error::decode)?;
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-                Ok((channel_end, Some(proof)))
-            }
-            IncludeProof::No => Ok((channel_end, None)),
-        }
+        Ok((channel_end, proof))
     }
 
     fn query_channel_client_state(
@@ -1273,17 +1945,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_channel_client_state");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.channel_client_state(request))
+            .block_on(client.channel_client_state(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1298,25 +1967,18 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
-        let res = self.query(
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
+        let (value, proof) = self.query_with_proof(
             CommitmentsPath {
                 port_id: request.port_id,
                 channel_id: request.channel_id,
                 sequence: request.sequence,
             },
             request.height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-
-                Ok((res.value, Some(proof)))
-            }
-            IncludeProof::No => Ok((res.value, None)),
-        }
+        Ok((value, proof))
     }
 
     /// Queries the packet commitment hashes associated with a channel.
@@ -1328,17 +1990,59 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_packet_commitments");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
-        let request = tonic::Request::new(request.into());
+        if let Some(page_size) = self.listing_page_size(&request.pagination) {
+            use ibc_proto::ibc::core::channel::v1::QueryPacketCommitmentsRequest as RawRequest;
+
+            let listed_at = specific_height(request.height);
+
+            let checkpoint_file = CheckpointFile::for_channel(
+                &self.config.id,
+                &listing_name("packet_commitments", listed_at),
+                &request.port_id,
+                &request.channel_id,
+            );
+
+            let raw_request = RawRequest::from(request);
+
+            let (mut sequences, height) =
+                paginate(&checkpoint_file, page_size, |key, limit, height| {
+                    let page_query = at_height(
+                        RawRequest {
+                            pagination: Some(page_request(key, limit)),
+                            ..raw_request.clone()
+                        },
+                        height.or(listed_at),
+                    )?;
+
+                    let response = self
+                        .block_on(client.packet_commitments(page_query))?
+                        .map_err(Error::grpc_status)?
+                        .into_inner();
+
+                    Ok(Page {
+                        entries: response.commitments.iter().map(|c| c.sequence).collect(),
+                        next_key: response.pagination.map(|p| p.next_key).unwrap_or_default(),
+                        height: response_height(response.height)?,
+                    })
+                })?;
+
+            sequences.sort_unstable();
+
+            return Ok((sequences.into_iter().map(Sequence::from).collect(), height));
+        }
+
+        let height = specific_height(request.height);
+        let request = at_height(
+            ibc_proto::ibc::core::channel::v1::QueryPacketCommitmentsRequest::from(request),
+            height,
+        )?;
 
         let response = self
-            .block_on(client.packet_commitments(request))
+            .block_on(client.packet_commitments(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1361,25 +2065,18 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
-        let res = self.query(
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
+        let (value, proof) = self.query_with_proof(
             ReceiptsPath {
                 port_id: request.port_id,
                 channel_id: request.channel_id,
                 sequence: request.sequence,
             },
             request.height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-
-                Ok((res.value, Some(proof)))
-            }
-            IncludeProof::No => Ok((res.value, None)),
-        }
+        Ok((value, proof))
     }
 
     /// Queries the unreceived packet sequences associated with a channel.
@@ -1391,17 +2088,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_unreceived_packets");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let mut response = self
-            .block_on(client.unreceived_packets(request))
+            .block_on(client.unreceived_packets(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1417,25 +2111,18 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
-        let res = self.query(
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
+        let (value, proof) = self.query_with_proof(
             AcksPath {
                 port_id: request.port_id,
                 channel_id: request.channel_id,
                 sequence: request.sequence,
             },
             request.height,
-            matches!(include_proof, IncludeProof::Yes),
+            include_proof,
         )?;
 
-        match include_proof {
-            IncludeProof::Yes => {
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
-
-                Ok((res.value, Some(proof)))
-            }
-            IncludeProof::No => Ok((res.value, None)),
-        }
+        Ok((value, proof))
     }
 
     /// Queries the packet acknowledgment hashes associated with a channel.
@@ -1447,25 +2134,77 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_packet_acknowledgements");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
-        let request = tonic::Request::new(request.into());
+        // The listings filtered by the sequences of the packet commitments are as
+        // short as their filter, and are not worth resuming
+        if let Some(page_size) = self
+            .listing_page_size(&request.pagination)
+            .filter(|_| request.packet_commitment_sequences.is_empty())
+        {
+            use ibc_proto::ibc::core::channel::v1::QueryPacketAcknowledgementsRequest as RawRequest;
+
+            let listed_at = specific_height(request.height);
+
+            let checkpoint_file = CheckpointFile::for_channel(
+                &self.config.id,
+                &listing_name("packet_acknowledgements", listed_at),
+                &request.port_id,
+                &request.channel_id,
+            );
+
+            let raw_request = RawRequest::from(request);
+
+            let (mut sequences, height) =
+                paginate(&checkpoint_file, page_size, |key, limit, height| {
+                    let page_query = at_height(
+                        RawRequest {
+                            pagination: Some(page_request(key, limit)),
+                            ..raw_request.clone()
+                        },
+                        height.or(listed_at),
+                    )?;
+
+                    let response = self
+                        .block_on(client.packet_acknowledgements(page_query))?
+                        .map_err(Error::grpc_status)?
+                        .into_inner();
+
+                    Ok(Page {
+                        entries: response
+                            .acknowledgements
+                            .iter()
+                            .map(|a| a.sequence)
+                            .collect(),
+                        next_key: response.pagination.map(|p| p.next_key).unwrap_or_default(),
+                        height: response_height(response.height)?,
+                    })
+                })?;
+
+            sequences.sort_unstable();
+
+            return Ok((sequences.into_iter().map(Sequence::from).collect(), height));
+        }
+
+        let height = specific_height(request.height);
+        let request = at_height(
+            ibc_proto::ibc::core::channel::v1::QueryPacketAcknowledgementsRequest::from(request),
+            height,
+        )?;
 
         let response = self
-            .block_on(client.packet_acknowledgements(request))
+            .block_on(client.packet_acknowledgements(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
-        let acks_sequences = response
+        let mut acks_sequences: Vec<Sequence> = response
             .acknowledgements
             .into_iter()
             .map(|v| v.sequence.into())
             .collect();
+        acks_sequences.sort_unstable();
 
         let height = response
             .height
@@ -1484,17 +2223,14 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::telemetry!(query, self.id(), "query_unreceived_acknowledgements");
 
         let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
+            .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+            .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
             .map_err(Error::grpc_transport)?;
 
         let request = tonic::Request::new(request.into());
 
         let mut response = self
-            .block_on(client.unreceived_acks(request))
+            .block_on(client.unreceived_acks(request))?
             .map_err(Error::grpc_status)?
             .into_inner();
 
@@ -1506,46 +2242,113 @@ impl ChainEndpoint for CosmosSdkChain {
             .collect())
     }
 
+    /// Queries the packets of several channels concurrently, over a single gRPC connection.
+    /// The listings fetched page by page, see `query_page_size`, are made one after the
+    /// other instead, so that they resume from their last page when interrupted.
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        crate::time!("batch_query");
+        crate::telemetry!(query, self.id(), "batch_query");
+
+        let (paginated, batched): (Vec<_>, Vec<_>) =
+            requests.into_iter().enumerate().partition(|(_, request)| {
+                let pagination = match request {
+                    BatchQueryRequest::PacketCommitments(request) => &request.pagination,
+                    BatchQueryRequest::PacketAcknowledgements(request) => &request.pagination,
+                    BatchQueryRequest::UnreceivedPackets(_) => return false,
+                };
+
+                self.listing_page_size(pagination).is_some()
+            });
+
+        let (batched_indices, batched): (Vec<_>, Vec<_>) = batched.into_iter().unzip();
+
+        let max_concurrent = self
+            .config
+            .quotas
+            .max_concurrent_queries
+            .unwrap_or(batched.len());
+
+        let batched_responses = if batched.is_empty() {
+            vec![]
+        } else {
+            self.rt.block_on(batch_query(
+                self.id(),
+                &self.grpc_addr,
+                &self.grpc_settings,
+                self.config.query_timeout,
+                max_concurrent,
+                batched,
+            ))?
+        };
+
+        let mut responses: Vec<_> = batched_indices.into_iter().zip(batched_responses).collect();
+
+        for (index, request) in paginated {
+            let response = match request {
+                BatchQueryRequest::PacketCommitments(request) => self
+                    .query_packet_commitments(request)
+                    .map(|(sequences, height)| {
+                        BatchQueryResponse::PacketCommitments(sequences, height)
+                    }),
+                BatchQueryRequest::PacketAcknowledgements(request) => self
+                    .query_packet_acknowledgements(request)
+                    .map(|(sequences, height)| {
+                        BatchQueryResponse::PacketAcknowledgements(sequences, height)
+                    }),
+                BatchQueryRequest::UnreceivedPackets(request) => self
+                    .query_unreceived_packets(request)
+                    .map(BatchQueryResponse::UnreceivedPackets),
+            };
+
+            responses.push((index, response));
+        }
+
+        responses.sort_by_key(|(index, _)| *index);
+
+        Ok(responses
+            .into_iter()
+            .map(|(_, response)| response)
+            .collect())
+    }
+
     fn query_next_sequence_receive(
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         crate::time!("query_next_sequence_receive");
         crate::telemetry!(query, self.id(), "query_next_sequence_receive");
 
         match include_proof {
             IncludeProof::Yes => {
-                let res = self.query(
+                let (value, proof) = self.query_with_proof(
                     SeqRecvsPath(request.port_id, request.channel_id),
                     request.height,
-                    true,
+                    include_proof,
                 )?;
 
                 // Note: We expect the return to be a u64 encoded in big-endian. Refer to ibc-go:
                 // https://github.com/cosmos/ibc-go/blob/25767f6bdb5bab2c2a116b41d92d753c93e18121/modules/core/04-channel/client/utils/utils.go#L191
-                if res.value.len() != 8 {
+                if value.len() != 8 {
                     return Err(Error::query("next_sequence_receive".into()));
                 }
-                let seq: Sequence = Bytes::from(res.value).get_u64().into();
-
-                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
+                let seq: Sequence = Bytes::from(value).get_u64().into();
 
-                Ok((seq, Some(proof)))
+                Ok((seq, proof))
             }
             IncludeProof::No => {
                 let mut client = self
-                    .block_on(
-                        ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                            self.grpc_addr.clone(),
-                        ),
-                    )
+                    .block_on(grpc::connect(&self.grpc_addr, &self.grpc_settings))?
+                    .map(ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new)
                     .map_err(Error::grpc_transport)?;
 
                 let request = tonic::Request::new(request.into());
 
                 let response = self
-                    .block_on(client.next_sequence_receive(request))
+                    .block_on(client.next_sequence_receive(request))?
                     .map_err(Error::grpc_status)?
                     .into_inner();
 
@@ -1569,12 +2372,59 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_txs");
         crate::telemetry!(query, self.id(), "query_txs");
 
-        self.block_on(query_txs(
-            self.id(),
-            &self.rpc_client,
-            &self.config.rpc_addr,
-            request,
-        ))
+        let layout = self.block_results_layout()?;
+
+        let (request, cache) = match (request, self.packet_event_cache()) {
+            (QueryTxRequest::Packet(request), Some(cache)) => (request, cache),
+            (request, _) => {
+                return self.block_on_with_archive("query_txs", None, |rpc_client, rpc_addr| {
+                    query_txs(self.id(), layout, rpc_client, rpc_addr, request.clone())
+                })
+            }
+        };
+
+        // Only the events of the packets missing from the cache are queried
+        let mut events = vec![];
+        let mut missing = vec![];
+
+        for seq in &request.sequences {
+            match cache.get(&request, *seq) {
+                Some(event) if self.matches_commitment(&request, &event) => events.push(event),
+                Some(_) => {
+                    cache.remove(&request, *seq);
+                    missing.push(*seq);
+                }
+                None => missing.push(*seq),
+            }
+        }
+
+        if !missing.is_empty() {
+            let request = QueryPacketEventDataRequest {
+                sequences: missing,
+                ..request
+            };
+
+            let queried =
+                self.block_on_with_archive("query_txs", None, |rpc_client, rpc_addr| {
+                    query_txs(
+                        self.id(),
+                        layout,
+                        rpc_client,
+                        rpc_addr,
+                        QueryTxRequest::Packet(request.clone()),
+                    )
+                })?;
+
+            for event in &queried {
+                cache.insert(&request, event);
+            }
+
+            events.extend(queried);
+        }
+
+        events.sort_by_key(|event| event.event.packet().map(|packet| packet.sequence));
+
+        Ok(events)
     }
 
     fn query_blocks(
@@ -1588,18 +2438,37 @@ impl ChainEndpoint for CosmosSdkChain {
             QueryBlockRequest::Packet(request) => {
                 crate::time!("query_blocks: query block packet events");
 
+                let layout = self.block_results_layout()?;
+
                 let mut begin_block_events: Vec<IbcEvent> = vec![];
                 let mut end_block_events: Vec<IbcEvent> = vec![];
 
                 for seq in &request.sequences {
-                    let response = self
-                        .block_on(self.rpc_client.block_search(
-                            packet_query(&request, *seq),
-                            1,
-                            1, // there should only be a single match for this query
-                            Order::Ascending,
-                        ))
-                        .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+                    let query = match request.height_range {
+                        Some(range) => packet_query(&request, *seq)
+                            .and_gte("block.height", range.start.revision_height())
+                            .and_lte("block.height", range.end.revision_height()),
+                        None => packet_query(&request, *seq),
+                    };
+
+                    let response = self.block_on_with_archive(
+                        "block_search",
+                        None,
+                        |rpc_client, rpc_addr| {
+                            let query = query.clone();
+                            async move {
+                                rpc_client
+                                    .block_search(
+                                        query,
+                                        1,
+                                        1, // there should only be a single match for this query
+                                        Order::Ascending,
+                                    )
+                                    .await
+                                    .map_err(|e| Error::rpc(rpc_addr.clone(), e))
+                            }
+                        },
+                    )?;
 
                     assert!(
                         response.blocks.len() <= 1,
@@ -1617,14 +2486,18 @@ impl ChainEndpoint for CosmosSdkChain {
                             }
                         }
 
-                        let response = self
-                            .block_on(self.rpc_client.block_results(block.header.height))
-                            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+                        let height = block.header.height;
+                        let response = self.block_on_with_archive(
+                            "block_results",
+                            Some(height),
+                            |rpc_client, rpc_addr| {
+                                query_block_results(layout, rpc_client, rpc_addr, height)
+                            },
+                        )?;
 
                         begin_block_events.append(
                             &mut response
                                 .begin_block_events
-                                .unwrap_or_default()
                                 .into_iter()
                                 .filter_map(|ev| filter_matching_event(ev, &request, *seq))
                                 .collect(),
@@ -1633,7 +2506,6 @@ impl ChainEndpoint for CosmosSdkChain {
                         end_block_events.append(
                             &mut response
                                 .end_block_events
-                                .unwrap_or_default()
                                 .into_iter()
                                 .filter_map(|ev| filter_matching_event(ev, &request, *seq))
                                 .collect(),
@@ -1662,7 +2534,7 @@ impl ChainEndpoint for CosmosSdkChain {
             _ => self.rpc_client.block(height),
         };
         let response = self
-            .block_on(rpc_call)
+            .block_on(rpc_call)?
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
         Ok(response.block.header.into())
     }
@@ -1731,21 +2603,139 @@ impl ChainEndpoint for CosmosSdkChain {
     ) -> Result<Vec<CrossChainQueryResponse>, Error> {
         let mut responses: Vec<CrossChainQueryResponse> = vec![];
 
+        let proof_specs = self.config.proof_specs.clone().unwrap_or_default();
+
         let tasks = requests
             .into_iter()
-            .map(|req| rest_query(&self.rest_client, req))
+            .map(|req| {
+                with_deadline(
+                    &self.config.id,
+                    "cross-chain query",
+                    self.config.query_timeout,
+                    cross_chain_query(
+                        &self.rpc_client,
+                        &self.config.rpc_addr,
+                        &self.rest_client,
+                        &proof_specs,
+                        req,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Run at most as many queries at once as allowed by the quotas of the chain
+        let max_concurrent = self
+            .config
+            .quotas
+            .max_concurrent_queries
+            .unwrap_or(tasks.len())
+            .max(1);
+
+        let joined_tasks = stream::iter(tasks)
+            .buffer_unordered(max_concurrent)
             .collect::<Vec<_>>();
 
-        let joined_tasks = join_all(tasks);
         let results: Vec<_> = self.query_rt.block_on(joined_tasks);
+
+        // The queries which could not be executed are left to be retried by the caller
         for result in results {
-            if let Ok(res) = result {
-                responses.push(res);
+            match result {
+                Ok(Ok(res)) => responses.push(res),
+                Ok(Err(e)) | Err(e) => warn!("cross-chain query failed: {}", e),
             }
         }
 
         Ok(responses)
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        crate::time!("query_incentivized_packet");
+        crate::telemetry!(query, self.id(), "query_incentivized_packet");
+
+        self.block_on(query_incentivized_packet(
+            &self.grpc_addr,
+            &self.grpc_settings,
+            request,
+        ))?
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        crate::time!("query_counterparty_payee");
+        crate::telemetry!(query, self.id(), "query_counterparty_payee");
+
+        self.block_on(query_counterparty_payee(
+            &self.grpc_addr,
+            &self.grpc_settings,
+            request,
+        ))?
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        crate::time!("query_wasm_checksums");
+        crate::telemetry!(query, self.id(), "query_wasm_checksums");
+
+        self.block_on(query_wasm_checksums(
+            &self.grpc_addr,
+            &self.grpc_settings,
+            request,
+        ))?
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        crate::time!("query_upgrade");
+        crate::telemetry!(query, self.id(), "query_upgrade");
+
+        let (value, proof) = self.query_with_proof(
+            ChannelUpgradePath(request.port_id, request.channel_id),
+            request.height,
+            include_proof,
+        )?;
+
+        // The upgrade is deleted once the channel is reopened or the upgrade aborted
+        if value.is_empty() {
+            return Err(Error::empty_response_value());
+        }
+
+        let upgrade = Upgrade::decode_vec(&value).map_err(Error::decode)?;
+
+        Ok((upgrade, proof))
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        crate::time!("query_upgrade_error");
+        crate::telemetry!(query, self.id(), "query_upgrade_error");
+
+        let (value, proof) = self.query_with_proof(
+            ChannelUpgradeErrorPath(request.port_id, request.channel_id),
+            request.height,
+            include_proof,
+        )?;
+
+        if value.is_empty() {
+            return Err(Error::empty_response_value());
+        }
+
+        let error_receipt = ErrorReceipt::decode_vec(&value).map_err(Error::decode)?;
+
+        Ok((error_receipt, proof))
+    }
 }
 
 /// Initialize the light client for the given chain using the given HTTP client
@@ -1804,6 +2794,57 @@ fn filter_matching_event(
     }
 }
 
+/// The request of the page of a listing starting at the given key.
+fn page_request(key: Vec<u8>, limit: u64) -> RawPageRequest {
+    PageRequest {
+        key,
+        limit,
+        ..Default::default()
+    }
+    .into()
+}
+
+/// The given gRPC query, made at the given height or at the latest one.
+fn at_height<T>(query: T, height: Option<ICSHeight>) -> Result<tonic::Request<T>, Error> {
+    let mut request = tonic::Request::new(query);
+
+    if let Some(height) = height {
+        let height_param = AsciiMetadataValue::try_from(QueryHeight::Specific(height))?;
+
+        request
+            .metadata_mut()
+            .insert("x-cosmos-block-height", height_param);
+    }
+
+    Ok(request)
+}
+
+/// The height a query is pinned to, `None` for the latest height.
+fn specific_height(height: QueryHeight) -> Option<ICSHeight> {
+    match height {
+        QueryHeight::Latest => None,
+        QueryHeight::Specific(height) => Some(height),
+    }
+}
+
+/// The name of the checkpoint file of a listing, which tells apart
+/// the listings pinned to a height from the listings at the latest height.
+fn listing_name(listing: &str, height: Option<ICSHeight>) -> String {
+    match height {
+        None => listing.to_string(),
+        Some(height) => format!("{}-at-{}", listing, height),
+    }
+}
+
+/// The height of the response to a gRPC query.
+fn response_height(
+    height: Option<ibc_proto::ibc::core::client::v1::Height>,
+) -> Result<ICSHeight, Error> {
+    height
+        .and_then(|raw_height| raw_height.try_into().ok())
+        .ok_or_else(|| Error::grpc_response_param("height".to_string()))
+}
+
 /// Returns the suffix counter for a CosmosSDK client id.
 /// Returns `None` if the client identifier is malformed
 /// and the suffix could not be parsed.
@@ -1821,7 +2862,7 @@ fn do_health_check(chain: &CosmosSdkChain) -> Result<(), Error> {
     let rpc_address = chain.config.rpc_addr.to_string();
 
     // Checkup on the self-reported health endpoint
-    chain.block_on(chain.rpc_client.health()).map_err(|e| {
+    chain.block_on(chain.rpc_client.health())?.map_err(|e| {
         Error::health_check_json_rpc(
             chain_id.clone(),
             rpc_address.clone(),
@@ -1854,7 +2895,11 @@ fn do_health_check(chain: &CosmosSdkChain) -> Result<(), Error> {
         );
     }
 
-    let version_specs = chain.block_on(fetch_version_specs(&chain.config.id, &chain.grpc_addr))?;
+    let version_specs = chain.block_on(fetch_version_specs(
+        &chain.config.id,
+        &chain.grpc_addr,
+        &chain.grpc_settings,
+    ))??;
 
     // Checkup on the underlying SDK & IBC-go versions
     if let Err(diagnostic) = compatibility::run_diagnostic(&version_specs) {
@@ -0,0 +1,77 @@
+//! Diagnostics of the misconfigurations of the address the relayer uses on a chain,
+//! which otherwise only surface as generic failures when broadcasting transactions.
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::error::{Error, ErrorDetail};
+
+/// Check that the given address has the bech32 prefix configured for the chain.
+pub fn check_address_prefix(
+    chain_id: &ChainId,
+    key_name: &str,
+    address: &str,
+    account_prefix: &str,
+) -> Result<(), Error> {
+    let prefix = address.rsplit_once('1').map_or("", |(prefix, _)| prefix);
+
+    if prefix == account_prefix {
+        Ok(())
+    } else {
+        Err(Error::address_prefix_mismatch(
+            chain_id.clone(),
+            key_name.to_string(),
+            address.to_string(),
+            prefix.to_string(),
+            account_prefix.to_string(),
+        ))
+    }
+}
+
+/// Turn the failure to find the account of the given address on the chain
+/// into an error explaining the likely misconfiguration of the key.
+pub fn diagnose_account_error(
+    chain_id: &ChainId,
+    key_name: &str,
+    address: &str,
+    e: Error,
+) -> Error {
+    match e.detail() {
+        ErrorDetail::EmptyQueryAccount(_) => {
+            Error::account_not_found(chain_id.clone(), key_name.to_string(), address.to_string())
+        }
+        _ => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_prefix_mismatch() {
+        let chain_id = ChainId::new("osmosis".to_string(), 1);
+        let address = "cosmos1xh2jvz9ecty8qdctlgscmys2dr5gz729k0l7x4";
+
+        assert!(check_address_prefix(&chain_id, "relayer", address, "cosmos").is_ok());
+
+        let e = check_address_prefix(&chain_id, "relayer", address, "osmo").unwrap_err();
+        assert!(e.to_string().contains("'cosmos'"));
+    }
+
+    #[test]
+    fn diagnose_missing_account() {
+        let chain_id = ChainId::new("ibc".to_string(), 0);
+        let address = "cosmos1xh2jvz9ecty8qdctlgscmys2dr5gz729k0l7x4";
+
+        let e = diagnose_account_error(
+            &chain_id,
+            "relayer",
+            address,
+            Error::empty_query_account(address.to_string()),
+        );
+        assert!(matches!(e.detail(), ErrorDetail::AccountNotFound(_)));
+
+        let e = diagnose_account_error(&chain_id, "relayer", address, Error::empty_base_account());
+        assert!(matches!(e.detail(), ErrorDetail::EmptyBaseAccount(_)));
+    }
+}
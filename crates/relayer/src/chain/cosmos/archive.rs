@@ -0,0 +1,85 @@
+//! Detection of the queries which fail because the full node pruned the queried height,
+//! so that they can be retried against the archive endpoint configured for the chain,
+//! and the queries of the heights below the ones it retains sent there directly.
+
+use crate::error::{Error, ErrorDetail};
+
+/// Fragments of the errors returned by Tendermint and the Cosmos SDK when the
+/// state, blocks or block results at the queried height were pruned.
+const PRUNED_HEIGHT_ERRORS: &[&str] = &[
+    "is not available, lowest height is",
+    "version does not exist",
+    "could not find results for height",
+];
+
+/// Whether the given error message reports that the queried height was pruned.
+pub fn is_pruned_height_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+
+    PRUNED_HEIGHT_ERRORS
+        .iter()
+        .any(|fragment| message.contains(fragment))
+}
+
+/// Whether the given query error reports that the queried height was pruned.
+pub fn is_pruned_height_error(e: &Error) -> bool {
+    error_message(e).map_or(false, |message| is_pruned_height_message(&message))
+}
+
+/// The lowest height retained by the node, as reported by the given error message, if any.
+pub fn lowest_height_from_message(message: &str) -> Option<u64> {
+    let (_, rest) = message.split_once("lowest height is ")?;
+
+    rest.split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// The height from which the node may retain its state, given that the query of
+/// the given height failed with the given error because the height was pruned.
+pub fn retained_height_after(e: &Error, queried_height: u64) -> u64 {
+    error_message(e)
+        .and_then(|message| lowest_height_from_message(&message))
+        .map_or(queried_height + 1, |lowest| lowest.max(queried_height + 1))
+}
+
+fn error_message(e: &Error) -> Option<String> {
+    match e.detail() {
+        ErrorDetail::Rpc(e) => Some(e.source.to_string()),
+        ErrorDetail::AbciQuery(e) => Some(format!("{:?}", e.query)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_pruned_heights() {
+        assert!(is_pruned_height_message(
+            "height 1200 is not available, lowest height is 51000"
+        ));
+        assert!(is_pruned_height_message(
+            "failed to load state at height 1200; version does not exist (latest height: 52000)"
+        ));
+        assert!(is_pruned_height_message(
+            "could not find results for height #1200"
+        ));
+
+        assert!(!is_pruned_height_message(
+            "height 60000 must be less than or equal to the current blockchain height 52000"
+        ));
+        assert!(!is_pruned_height_message("connection refused"));
+
+        assert_eq!(
+            lowest_height_from_message("height 1200 is not available, lowest height is 51000"),
+            Some(51000)
+        );
+        assert_eq!(
+            lowest_height_from_message("version does not exist (latest height: 52000)"),
+            None
+        );
+    }
+}
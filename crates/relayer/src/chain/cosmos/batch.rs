@@ -3,18 +3,23 @@ use core::mem;
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc::events::IbcEvent;
 use ibc::Height;
+use ibc_proto::cosmos::tx::v1beta1::Tx;
 use ibc_proto::google::protobuf::Any;
 use prost::Message;
 use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use tracing::debug;
 
-use crate::chain::cosmos::encode::encoded_tx_metrics;
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::encode::{encoded_tx_metrics, sign_tx};
 use crate::chain::cosmos::gas::gas_amount_to_fee;
+use crate::chain::cosmos::query::account::fetch_fee_payer_account;
 use crate::chain::cosmos::retry::send_tx_with_account_sequence_retry;
+use crate::chain::cosmos::simulate::send_tx_simulate;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
 use crate::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
 use crate::chain::cosmos::wait::wait_for_block_commits;
+use crate::chain::endpoint::SimulatedTx;
 use crate::config::types::{MaxMsgNum, MaxTxSize, Memo};
 use crate::error::Error;
 use crate::event::IbcEventWithHeight;
@@ -22,12 +27,13 @@ use crate::keyring::KeyEntry;
 
 /**
    Broadcast messages as multiple batched transactions to the chain all at once,
-   and then wait for all transactions to be committed.
+   without waiting for them to be committed, see [`wait_for_batched_messages_commit`].
    This may improve performance in case when multiple transactions are
-   committed into the same block. However this approach may not work if
+   committed into the same block, including the ones broadcast for other
+   messages in the meantime. However this approach may not work if
    priority mempool is enabled.
 */
-pub async fn send_batched_messages_and_wait_commit(
+pub async fn send_batched_messages(
     config: &TxConfig,
     max_msg_num: MaxMsgNum,
     max_tx_size: MaxTxSize,
@@ -35,12 +41,8 @@ pub async fn send_batched_messages_and_wait_commit(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Vec<IbcEventWithHeight>, Error> {
-    if messages.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let mut tx_sync_results = send_messages_as_batches(
+) -> Result<Vec<TxSyncResult>, Error> {
+    send_messages_as_batches(
         config,
         max_msg_num,
         max_tx_size,
@@ -49,13 +51,24 @@ pub async fn send_batched_messages_and_wait_commit(
         tx_memo,
         messages,
     )
-    .await?;
+    .await
+}
 
+/**
+   Wait for the transactions broadcast by [`send_batched_messages`] to be
+   committed, and return their events.
+*/
+pub async fn wait_for_batched_messages_commit(
+    config: &TxConfig,
+    mut tx_sync_results: Vec<TxSyncResult>,
+) -> Result<Vec<IbcEventWithHeight>, Error> {
     wait_for_block_commits(
         &config.chain_id,
         &config.rpc_client,
         &config.rpc_address,
         &config.rpc_timeout,
+        &config.query_timeout,
+        &config.in_flight,
         &mut tx_sync_results,
     )
     .await?;
@@ -72,8 +85,9 @@ pub async fn send_batched_messages_and_wait_commit(
    Send batched messages one after another, only after the previous one
    has been committed. This is only used in case if parallel transactions
    are committed in the wrong order due to interference from priority mempool.
+   The results returned are the ones of the committed transactions.
 */
-pub async fn sequential_send_batched_messages_and_wait_commit(
+pub async fn sequential_send_batched_messages(
     config: &TxConfig,
     max_msg_num: MaxMsgNum,
     max_tx_size: MaxTxSize,
@@ -81,12 +95,8 @@ pub async fn sequential_send_batched_messages_and_wait_commit(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Vec<IbcEventWithHeight>, Error> {
-    if messages.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let tx_sync_results = sequential_send_messages_as_batches(
+) -> Result<Vec<TxSyncResult>, Error> {
+    sequential_send_messages_as_batches(
         config,
         max_msg_num,
         max_tx_size,
@@ -95,14 +105,7 @@ pub async fn sequential_send_batched_messages_and_wait_commit(
         tx_memo,
         messages,
     )
-    .await?;
-
-    let events = tx_sync_results
-        .into_iter()
-        .flat_map(|el| el.events)
-        .collect();
-
-    Ok(events)
+    .await
 }
 
 pub async fn send_batched_messages_and_wait_check_tx(
@@ -140,6 +143,87 @@ pub async fn send_batched_messages_and_wait_check_tx(
     Ok(responses)
 }
 
+/**
+   Simulate the transactions the messages would be batched into, without
+   broadcasting them. The transactions are all simulated against the current
+   state of the chain, so that a transaction depending on the messages of a
+   previous one, e.g. on a client update, may be reported as failing.
+*/
+pub async fn simulate_batched_messages(
+    config: &TxConfig,
+    max_msg_num: MaxMsgNum,
+    max_tx_size: MaxTxSize,
+    key_entry: &KeyEntry,
+    account: &Account,
+    tx_memo: &Memo,
+    messages: Vec<Any>,
+) -> Result<Vec<SimulatedTx>, Error> {
+    if messages.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    fetch_fee_payer_account(config, false).await?;
+
+    let batches = batch_messages(
+        config,
+        max_msg_num,
+        max_tx_size,
+        key_entry,
+        account,
+        tx_memo,
+        messages,
+    )?;
+
+    let mut simulated = Vec::new();
+
+    for batch in batches {
+        let signed_tx = sign_tx(
+            config,
+            key_entry,
+            account,
+            tx_memo,
+            &batch,
+            &config.gas_config.max_fee,
+        )?;
+
+        let tx = Tx {
+            body: Some(signed_tx.body),
+            auth_info: Some(signed_tx.auth_info),
+            signatures: signed_tx.signatures,
+        };
+
+        let response = with_deadline(
+            &config.chain_id,
+            "tx simulation",
+            config.query_timeout,
+            send_tx_simulate(&config.grpc_address, &config.grpc_settings, tx),
+        )
+        .await?;
+
+        let gas_used = response
+            .as_ref()
+            .ok()
+            .and_then(|response| response.gas_info.as_ref())
+            .map(|gas_info| gas_info.gas_used);
+
+        let fee = gas_used.and_then(|gas_used| {
+            gas_amount_to_fee(&config.gas_config, gas_used)
+                .amount
+                .first()
+                .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        });
+
+        simulated.push(SimulatedTx {
+            messages: batch.len(),
+            gas_used,
+            fee,
+            error: response.err().map(|e| e.to_string()),
+        });
+    }
+
+    Ok(simulated)
+}
+
 async fn send_messages_as_batches(
     config: &TxConfig,
     max_msg_num: MaxMsgNum,
@@ -237,6 +321,8 @@ async fn sequential_send_messages_as_batches(
             &config.rpc_client,
             &config.rpc_address,
             &config.rpc_timeout,
+            &config.query_timeout,
+            &config.in_flight,
             &mut tx_sync_results,
         )
         .await?;
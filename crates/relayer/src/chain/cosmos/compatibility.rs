@@ -3,6 +3,7 @@
 use thiserror::Error;
 use tracing::debug;
 
+use super::query::block_results::BlockResultsLayout;
 use super::version;
 
 /// Specifies the SDK module version requirement.
@@ -87,3 +88,67 @@ fn ibc_go_diagnostic(version_info: Option<&semver::Version>) -> Result<(), Diagn
         },
     }
 }
+
+/// The formats of the queries and events of a chain which changed across the releases
+/// of its consensus engine, selected from the versions of the modules the chain runs, or
+/// from the version reported by its node if these cannot be queried:
+///
+/// | Module                | Versions    | Format                                        |
+/// |-----------------------|-------------|-----------------------------------------------|
+/// | Tendermint / CometBFT | < 0.38      | BeginBlock and EndBlock events                |
+/// | CometBFT              | >= 0.38     | FinalizeBlock events                          |
+///
+/// The flavour of the governance proposals accepted by a chain, which changed with
+/// Cosmos SDK v0.50, is detected when submitting one, see [`GovVersion::detect`].
+///
+/// [`GovVersion::detect`]: crate::gov::GovVersion::detect
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Formats {
+    pub block_results_layout: BlockResultsLayout,
+}
+
+impl Formats {
+    pub fn from_specs(v: &version::Specs) -> Self {
+        Self::from_node_version(&v.tendermint.to_string())
+    }
+
+    pub fn from_node_version(node_version: &str) -> Self {
+        Self {
+            block_results_layout: BlockResultsLayout::from_node_version(node_version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(cosmos_sdk: &str, ibc_go: Option<&str>, tendermint: &str) -> version::Specs {
+        version::Specs {
+            cosmos_sdk: semver::Version::parse(cosmos_sdk).unwrap(),
+            ibc_go: ibc_go.map(|v| semver::Version::parse(v).unwrap()),
+            tendermint: semver::Version::parse(tendermint).unwrap(),
+        }
+    }
+
+    #[test]
+    fn select_formats() {
+        let v = specs("0.45.9", Some("3.3.0"), "0.34.21");
+        assert!(run_diagnostic(&v).is_ok());
+        assert_eq!(
+            Formats::from_specs(&v),
+            Formats {
+                block_results_layout: BlockResultsLayout::BeginEndBlock,
+            }
+        );
+
+        let v = specs("0.50.3", Some("8.1.0"), "0.38.5");
+        assert!(run_diagnostic(&v).is_err());
+        assert_eq!(
+            Formats::from_specs(&v),
+            Formats {
+                block_results_layout: BlockResultsLayout::FinalizeBlock,
+            }
+        );
+    }
+}
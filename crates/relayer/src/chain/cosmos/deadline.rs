@@ -0,0 +1,45 @@
+//! Deadlines of the network calls to the chains, so that a hung gRPC stream or
+//! RPC connection cannot stall the runtime of a chain indefinitely.
+
+use core::future::Future;
+use core::time::Duration;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::error::Error;
+
+/// Await the given network call, failing with [`Error::call_timeout`]
+/// if it does not complete within the given deadline.
+pub async fn with_deadline<F: Future>(
+    chain_id: &ChainId,
+    call: &str,
+    deadline: Duration,
+    f: F,
+) -> Result<F::Output, Error> {
+    tokio::time::timeout(deadline, f)
+        .await
+        .map_err(|_| Error::call_timeout(chain_id.clone(), call.to_string(), deadline))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_times_out() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let chain_id = ChainId::new("ibc".to_string(), 0);
+        let deadline = Duration::from_millis(50);
+
+        let hung = rt.block_on(with_deadline(
+            &chain_id,
+            "query",
+            deadline,
+            core::future::pending::<()>(),
+        ));
+        assert!(hung.is_err());
+
+        let done = rt.block_on(with_deadline(&chain_id, "query", deadline, async { 42 }));
+        assert_eq!(done.unwrap(), 42);
+    }
+}
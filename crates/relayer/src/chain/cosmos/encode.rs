@@ -13,7 +13,7 @@ use crate::chain::cosmos::types::tx::SignedTx;
 use crate::config::types::Memo;
 use crate::config::AddressType;
 use crate::error::Error;
-use crate::keyring::{sign_message, KeyEntry};
+use crate::keyring::{KeyEntry, SigningProvider};
 
 pub fn sign_and_encode_tx(
     config: &TxConfig,
@@ -82,28 +82,65 @@ pub fn sign_tx(
 ) -> Result<SignedTx, Error> {
     let key_bytes = encode_key_bytes(key_entry)?;
 
-    let signer = encode_signer_info(&config.address_type, account.sequence, key_bytes)?;
+    let mut signer_infos = vec![encode_signer_info(
+        &config.address_type,
+        account.sequence,
+        key_bytes,
+    )?];
+
+    let mut fee = fee.clone();
+
+    // The fee payer signs the transaction along with the signer of its messages
+    let fee_payer = config
+        .fee_payer
+        .as_ref()
+        .map(|fee_payer| (fee_payer, fee_payer.number_and_sequence()));
+
+    if let Some((fee_payer, (_, sequence))) = fee_payer {
+        let key_bytes = encode_key_bytes(&fee_payer.key_entry)?;
+
+        signer_infos.push(encode_signer_info(
+            &config.address_type,
+            sequence,
+            key_bytes,
+        )?);
+
+        fee.payer = fee_payer.key_entry.account.clone();
+    }
 
     let (body, body_bytes) =
         tx_body_and_bytes(messages, tx_memo, config.extension_options.clone())?;
 
-    let (auth_info, auth_info_bytes) = auth_info_and_bytes(signer, fee.clone())?;
+    let (auth_info, auth_info_bytes) = auth_info_and_bytes(signer_infos, fee)?;
 
-    let signed_doc = encode_sign_doc(
+    let mut signatures = vec![encode_sign_doc(
+        config.signing_provider.as_ref(),
         &config.chain_id,
         key_entry,
         &config.address_type,
         account.number,
         auth_info_bytes.clone(),
         body_bytes.clone(),
-    )?;
+    )?];
+
+    if let Some((fee_payer, (number, _))) = fee_payer {
+        signatures.push(encode_sign_doc(
+            config.signing_provider.as_ref(),
+            &config.chain_id,
+            &fee_payer.key_entry,
+            &config.address_type,
+            number,
+            auth_info_bytes.clone(),
+            body_bytes.clone(),
+        )?);
+    }
 
     Ok(SignedTx {
         body,
         body_bytes,
         auth_info,
         auth_info_bytes,
-        signatures: vec![signed_doc],
+        signatures,
     })
 }
 
@@ -117,6 +154,7 @@ fn encode_key_bytes(key: &KeyEntry) -> Result<Vec<u8>, Error> {
 }
 
 fn encode_sign_doc(
+    signing_provider: &dyn SigningProvider,
     chain_id: &ChainId,
     key: &KeyEntry,
     address_type: &AddressType,
@@ -135,7 +173,9 @@ fn encode_sign_doc(
     let mut signdoc_buf = Vec::new();
     prost::Message::encode(&sign_doc, &mut signdoc_buf).unwrap();
 
-    let signed = sign_message(key, signdoc_buf, address_type).map_err(Error::key_base)?;
+    let signed = signing_provider
+        .sign(key, signdoc_buf, address_type)
+        .map_err(Error::key_base)?;
 
     Ok(signed)
 }
@@ -184,9 +224,12 @@ pub fn encode_to_bech32(address: &str, account_prefix: &str) -> Result<String, E
     Ok(encoded)
 }
 
-fn auth_info_and_bytes(signer_info: SignerInfo, fee: Fee) -> Result<(AuthInfo, Vec<u8>), Error> {
+fn auth_info_and_bytes(
+    signer_infos: Vec<SignerInfo>,
+    fee: Fee,
+) -> Result<(AuthInfo, Vec<u8>), Error> {
     let auth_info = AuthInfo {
-        signer_infos: vec![signer_info],
+        signer_infos,
         fee: Some(fee),
     };
 
@@ -4,8 +4,10 @@ use ibc_proto::google::protobuf::Any;
 use tonic::codegen::http::Uri;
 use tracing::{debug, error, span, warn, Level};
 
+use crate::chain::cosmos::deadline::with_deadline;
 use crate::chain::cosmos::encode::sign_tx;
 use crate::chain::cosmos::gas::gas_amount_to_fee;
+use crate::chain::cosmos::grpc::GrpcSettings;
 use crate::chain::cosmos::simulate::send_tx_simulate;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
@@ -44,8 +46,19 @@ pub async fn estimate_tx_fees(
         signatures: signed_tx.signatures,
     };
 
-    let estimated_fee =
-        estimate_fee_with_tx(gas_config, &config.grpc_address, &config.chain_id, tx).await?;
+    let estimated_fee = with_deadline(
+        &config.chain_id,
+        "tx simulation",
+        config.query_timeout,
+        estimate_fee_with_tx(
+            gas_config,
+            &config.grpc_address,
+            &config.grpc_settings,
+            &config.chain_id,
+            tx,
+        ),
+    )
+    .await??;
 
     Ok(estimated_fee)
 }
@@ -53,10 +66,11 @@ pub async fn estimate_tx_fees(
 async fn estimate_fee_with_tx(
     gas_config: &GasConfig,
     grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
     chain_id: &ChainId,
     tx: Tx,
 ) -> Result<Fee, Error> {
-    let estimated_gas = estimate_gas_with_tx(gas_config, grpc_address, tx).await?;
+    let estimated_gas = estimate_gas_with_tx(gas_config, grpc_address, grpc_settings, tx).await?;
 
     if estimated_gas > gas_config.max_gas {
         debug!(
@@ -94,9 +108,10 @@ async fn estimate_fee_with_tx(
 async fn estimate_gas_with_tx(
     gas_config: &GasConfig,
     grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
     tx: Tx,
 ) -> Result<u64, Error> {
-    let simulated_gas = send_tx_simulate(grpc_address, tx)
+    let simulated_gas = send_tx_simulate(grpc_address, grpc_settings, tx)
         .await
         .map(|sr| sr.gas_info);
 
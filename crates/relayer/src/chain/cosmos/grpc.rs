@@ -0,0 +1,373 @@
+//! Transports of the gRPC queries to the full nodes: native gRPC over HTTP/2,
+//! or gRPC-web over HTTP/1.1 for the nodes only exposed through web proxies,
+//! such as Envoy or Cloudflare.
+//!
+//! The gRPC clients are given a [`GrpcChannel`] for the transport configured for the chain,
+//! e.g. `QueryClient::new(connect(&grpc_addr, &grpc_settings).await?)`.
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures::future::BoxFuture;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, TE};
+use http::uri::Uri;
+use http::{Request, Response, Version};
+use http_body::Body;
+use hyper::client::HttpConnector;
+use hyper_rustls::HttpsConnector;
+use tonic::body::BoxBody;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+use tonic::Status;
+use tower::Service;
+
+use crate::chain::cosmos::proxy::ProxyConnector;
+use crate::config::proxy::Proxy;
+use crate::config::{ChainConfig, GrpcTransport};
+use crate::error::Error;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+const GRPC_WEB_CONTENT_TYPE: &str = "application/grpc-web+proto";
+
+/// Length of the header of a frame, made of its flags and the length of its payload
+const FRAME_HEADER_LEN: usize = 5;
+
+/// Flag of the frame carrying the trailers of a gRPC-web response
+const TRAILERS_FLAG: u8 = 0x80;
+
+/// The settings of the gRPC connections to a chain.
+#[derive(Clone, Debug, Default)]
+pub struct GrpcSettings {
+    pub transport: GrpcTransport,
+    /// The client certificate presented to the node, if any
+    pub tls: Option<ClientTlsConfig>,
+    /// The proxy through which the node is reached, if any
+    pub proxy: Option<Proxy>,
+}
+
+impl GrpcSettings {
+    /// Load the settings of the gRPC connections to the given chain,
+    /// reading the files of its client certificate, if any.
+    pub fn from_config(config: &ChainConfig) -> Result<Self, Error> {
+        if let (Some(proxy), GrpcTransport::Web) = (&config.proxy, config.grpc_transport) {
+            return Err(Error::unsupported_proxy(
+                proxy.to_string(),
+                config.grpc_addr.to_string(),
+            ));
+        }
+
+        let tls = match &config.client_tls {
+            None => None,
+            Some(_) if config.grpc_transport == GrpcTransport::Web => {
+                return Err(Error::client_tls(
+                    config.id.clone(),
+                    "client certificates are not supported over gRPC-web".to_string(),
+                ));
+            }
+            Some(client_tls) => {
+                let read = |path: &std::path::Path| {
+                    std::fs::read(path).map_err(|e| {
+                        Error::client_tls(
+                            config.id.clone(),
+                            format!("failed to read {}: {}", path.display(), e),
+                        )
+                    })
+                };
+
+                let identity = Identity::from_pem(read(&client_tls.cert)?, read(&client_tls.key)?);
+                let tls = ClientTlsConfig::new().identity(identity);
+
+                let tls = match &client_tls.ca {
+                    Some(ca) => tls.ca_certificate(Certificate::from_pem(read(ca)?)),
+                    None => tls,
+                };
+
+                Some(tls)
+            }
+        };
+
+        Ok(Self {
+            transport: config.grpc_transport,
+            tls,
+            proxy: config.proxy.clone(),
+        })
+    }
+}
+
+/// Connect to the gRPC endpoint at the given address, with the given settings.
+///
+/// Native gRPC connections are established right away. The gRPC-web ones are made
+/// anew for each request, as web proxies do not keep HTTP/1.1 connections open for long.
+pub async fn connect(
+    address: &Uri,
+    settings: &GrpcSettings,
+) -> Result<GrpcChannel, tonic::transport::Error> {
+    match settings.transport {
+        GrpcTransport::Native => {
+            let mut endpoint = Channel::builder(address.clone());
+
+            if let Some(tls) = &settings.tls {
+                endpoint = endpoint.tls_config(tls.clone())?;
+            }
+
+            let channel = match &settings.proxy {
+                Some(proxy) => {
+                    endpoint
+                        .connect_with_connector(ProxyConnector::new(proxy.clone()))
+                        .await?
+                }
+                None => endpoint.connect().await?,
+            };
+
+            Ok(GrpcChannel::Native(channel))
+        }
+        GrpcTransport::Web => Ok(GrpcChannel::Web(GrpcWebChannel::new(address.clone()))),
+    }
+}
+
+/// A channel to a gRPC endpoint, over either transport.
+#[derive(Clone)]
+pub enum GrpcChannel {
+    Native(Channel),
+    Web(GrpcWebChannel),
+}
+
+impl Service<Request<BoxBody>> for GrpcChannel {
+    type Response = Response<BoxBody>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            Self::Native(channel) => channel.poll_ready(cx).map_err(Into::into),
+            // A connection is made for each request
+            Self::Web(_) => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, request: Request<BoxBody>) -> Self::Future {
+        match self {
+            Self::Native(channel) => {
+                let response = channel.call(request);
+
+                Box::pin(async move {
+                    let response = response.await?;
+
+                    Ok(response.map(|body| {
+                        body.map_err(|e| Status::from_error(Box::new(e)))
+                            .boxed_unsync()
+                    }))
+                })
+            }
+            Self::Web(channel) => Box::pin(channel.clone().send(request)),
+        }
+    }
+}
+
+/// A channel to a gRPC-web endpoint.
+#[derive(Clone)]
+pub struct GrpcWebChannel {
+    address: Uri,
+    client: hyper::Client<HttpsConnector<HttpConnector>, BoxBody>,
+}
+
+impl GrpcWebChannel {
+    pub fn new(address: Uri) -> Self {
+        let client = hyper::Client::builder().build(HttpsConnector::with_native_roots());
+
+        Self { address, client }
+    }
+
+    /// Send the given gRPC request as a gRPC-web request, and turn the
+    /// gRPC-web response back into a gRPC response.
+    ///
+    /// The messages are framed the same way by both protocols, so only the
+    /// trailers, sent in the body of the gRPC-web responses, are translated.
+    async fn send(self, request: Request<BoxBody>) -> Result<Response<BoxBody>, BoxError> {
+        let (mut parts, body) = request.into_parts();
+
+        // The gRPC clients only set the path of the method called
+        let mut uri = self.address.into_parts();
+        uri.path_and_query = parts.uri.path_and_query().cloned();
+        parts.uri = Uri::from_parts(uri)?;
+
+        parts.version = Version::HTTP_11;
+        parts.headers.remove(TE);
+        parts.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(GRPC_WEB_CONTENT_TYPE),
+        );
+        parts
+            .headers
+            .insert("x-grpc-web", HeaderValue::from_static("1"));
+
+        let response = self
+            .client
+            .request(Request::from_parts(parts, body))
+            .await?;
+
+        let (mut parts, body) = response.into_parts();
+
+        parts
+            .headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static(GRPC_CONTENT_TYPE));
+
+        Ok(Response::from_parts(
+            parts,
+            GrpcWebBody::new(body).boxed_unsync(),
+        ))
+    }
+}
+
+enum Frame {
+    Data(Bytes),
+    Trailers(HeaderMap),
+}
+
+/// The body of a gRPC-web response, yielding its messages as data
+/// and its trailers frame as the trailers of the response.
+struct GrpcWebBody {
+    inner: hyper::Body,
+    buf: BytesMut,
+    trailers: Option<HeaderMap>,
+    finished: bool,
+}
+
+impl GrpcWebBody {
+    fn new(inner: hyper::Body) -> Self {
+        Self {
+            inner,
+            buf: BytesMut::new(),
+            trailers: None,
+            finished: false,
+        }
+    }
+
+    /// Split the next complete frame off the buffered body, if any.
+    fn next_frame(&mut self) -> Option<Frame> {
+        if self.buf.len() < FRAME_HEADER_LEN {
+            return None;
+        }
+
+        let flags = self.buf[0];
+        let len = u32::from_be_bytes([self.buf[1], self.buf[2], self.buf[3], self.buf[4]]) as usize;
+
+        if self.buf.len() < FRAME_HEADER_LEN + len {
+            return None;
+        }
+
+        let frame = self.buf.split_to(FRAME_HEADER_LEN + len).freeze();
+
+        if flags & TRAILERS_FLAG == 0 {
+            // The data frames are length-prefixed messages, as in gRPC
+            Some(Frame::Data(frame))
+        } else {
+            Some(Frame::Trailers(decode_trailers(&frame[FRAME_HEADER_LEN..])))
+        }
+    }
+}
+
+/// Decode the trailers of a gRPC-web response, sent as HTTP/1.1 header lines.
+fn decode_trailers(block: &[u8]) -> HeaderMap {
+    let block = String::from_utf8_lossy(block);
+    let mut trailers = HeaderMap::new();
+
+    for line in block.lines() {
+        if let Some((name, value)) = line.split_once(':') {
+            let name = HeaderName::from_bytes(name.trim().to_ascii_lowercase().as_bytes());
+            let value = HeaderValue::from_str(value.trim());
+
+            if let (Ok(name), Ok(value)) = (name, value) {
+                trailers.append(name, value);
+            }
+        }
+    }
+
+    trailers
+}
+
+impl Body for GrpcWebBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = &mut *self;
+
+        loop {
+            match this.next_frame() {
+                Some(Frame::Data(data)) => return Poll::Ready(Some(Ok(data))),
+                Some(Frame::Trailers(trailers)) => {
+                    this.trailers = Some(trailers);
+                    this.finished = true;
+
+                    return Poll::Ready(None);
+                }
+                None if this.finished => return Poll::Ready(None),
+                None => {}
+            }
+
+            match Pin::new(&mut this.inner).poll_data(cx) {
+                Poll::Ready(Some(Ok(chunk))) => this.buf.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(Some(Err(Status::from_error(Box::new(e)))))
+                }
+                Poll::Ready(None) => this.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(self.trailers.take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(flags: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![flags];
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn decode_grpc_web_frames() {
+        let message = frame(0, b"message");
+        let trailers = frame(TRAILERS_FLAG, b"grpc-status: 0\r\nGrpc-Message: OK\r\n");
+
+        let mut body = GrpcWebBody::new(hyper::Body::empty());
+
+        // An incomplete frame is buffered until the rest of it arrives
+        body.buf.extend_from_slice(&message[..6]);
+        assert!(body.next_frame().is_none());
+
+        body.buf.extend_from_slice(&message[6..]);
+        body.buf.extend_from_slice(&trailers);
+
+        match body.next_frame() {
+            Some(Frame::Data(data)) => assert_eq!(&data[..], &message[..]),
+            _ => panic!("expected a data frame"),
+        }
+
+        match body.next_frame() {
+            Some(Frame::Trailers(trailers)) => {
+                assert_eq!(trailers["grpc-status"], "0");
+                assert_eq!(trailers["grpc-message"], "OK");
+            }
+            _ => panic!("expected a trailers frame"),
+        }
+
+        assert!(body.next_frame().is_none());
+    }
+}
@@ -0,0 +1,146 @@
+//! The memo of the transactions relaying packets, rendered from the `tx_memo_template`
+//! of the chain, see [`TxMemoTemplate`].
+
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::channel::v1::{MsgAcknowledgement, MsgRecvPacket, Packet};
+use prost::Message;
+use tracing::warn;
+
+use crate::config::memo_template::{MemoVariables, TxMemoTemplate};
+use crate::config::types::Memo;
+
+const MSG_RECV_PACKET_TYPE_URL: &str = "/ibc.core.channel.v1.MsgRecvPacket";
+const MSG_ACKNOWLEDGEMENT_TYPE_URL: &str = "/ibc.core.channel.v1.MsgAcknowledgement";
+
+/// The memo of the transactions of the given messages: the memo rendered from the given
+/// template if they receive or acknowledge packets, and the given memo otherwise.
+///
+/// The port and channel of the memo are the ones, on the chain, of the first packet
+/// relayed by the messages, as the packets relayed together are those of one channel.
+pub fn tx_memo(
+    template: Option<&TxMemoTemplate>,
+    chain_id: &str,
+    messages: &[Any],
+    memo: &Memo,
+) -> Memo {
+    let template = match template {
+        Some(template) => template,
+        None => return memo.clone(),
+    };
+
+    let (port, channel) = match messages.iter().find_map(relayed_port_and_channel) {
+        Some(port_and_channel) => port_and_channel,
+        None => return memo.clone(),
+    };
+
+    let rendered = template.render(&MemoVariables {
+        chain_id,
+        port: &port,
+        channel: &channel,
+    });
+
+    if rendered.len() > TxMemoTemplate::MAX_RENDERED_LEN {
+        warn!(
+            "the memo rendered from the template '{}' is longer than {} characters, \
+            falling back to the memo of the chain",
+            template,
+            TxMemoTemplate::MAX_RENDERED_LEN
+        );
+
+        return memo.clone();
+    }
+
+    // The rendered memo is bounded by the limit of the chains, not the one of `memo_prefix`
+    let mut tx_memo = Memo::default();
+    tx_memo.apply_suffix(&rendered);
+    tx_memo
+}
+
+/// The port and channel, on the chain the message is submitted to, of the packet
+/// received or acknowledged by the given message, if any.
+fn relayed_port_and_channel(message: &Any) -> Option<(String, String)> {
+    let packet = match message.type_url.as_str() {
+        MSG_RECV_PACKET_TYPE_URL => {
+            MsgRecvPacket::decode(message.value.as_slice())
+                .ok()?
+                .packet?
+        }
+        MSG_ACKNOWLEDGEMENT_TYPE_URL => {
+            let packet = MsgAcknowledgement::decode(message.value.as_slice())
+                .ok()?
+                .packet?;
+
+            // The acknowledgement is submitted to the chain which sent the packet
+            Packet {
+                destination_port: packet.source_port,
+                destination_channel: packet.source_channel,
+                ..Default::default()
+            }
+        }
+        _ => return None,
+    };
+
+    Some((packet.destination_port, packet.destination_channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet() -> Packet {
+        Packet {
+            sequence: 1,
+            source_port: "transfer".to_string(),
+            source_channel: "channel-7".to_string(),
+            destination_port: "transfer".to_string(),
+            destination_channel: "channel-0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn memo_of_relayed_packets() {
+        let template = TxMemoTemplate::new("acme {chain_id} {port}/{channel}").unwrap();
+        let memo = Memo::new("hermes").unwrap();
+
+        let recv = Any {
+            type_url: MSG_RECV_PACKET_TYPE_URL.to_string(),
+            value: MsgRecvPacket {
+                packet: Some(packet()),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        };
+
+        let ack = Any {
+            type_url: MSG_ACKNOWLEDGEMENT_TYPE_URL.to_string(),
+            value: MsgAcknowledgement {
+                packet: Some(packet()),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        };
+
+        let update = Any {
+            type_url: "/ibc.core.client.v1.MsgUpdateClient".to_string(),
+            value: vec![],
+        };
+
+        let tx_memo_of = |messages: &[Any]| {
+            tx_memo(Some(&template), "osmosis-1", messages, &memo)
+                .as_str()
+                .to_string()
+        };
+
+        assert_eq!(
+            tx_memo_of(&[update.clone(), recv]),
+            "acme osmosis-1 transfer/channel-0"
+        );
+        assert_eq!(tx_memo_of(&[ack]), "acme osmosis-1 transfer/channel-7");
+        assert_eq!(tx_memo_of(&[update.clone()]), "hermes");
+        assert_eq!(
+            tx_memo(None, "osmosis-1", &[update], &memo).as_str(),
+            "hermes"
+        );
+    }
+}
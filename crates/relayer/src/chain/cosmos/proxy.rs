@@ -0,0 +1,144 @@
+//! Connections to the full nodes through the HTTP proxy configured for a chain.
+//!
+//! The RPC connections use the proxy support of `tendermint-rpc`, and the native gRPC
+//! ones are tunneled with `CONNECT` requests. The WebSocket client of `tendermint-rpc`
+//! and the gRPC-web transport cannot be proxied, which the validation of the config rejects.
+
+use core::str::FromStr;
+use core::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use http::uri::Uri;
+use tendermint_rpc::{HttpClient, Url};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tower::Service;
+
+use crate::config::proxy::Proxy;
+use crate::error::Error;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Maximum length of the response of an HTTP proxy to a `CONNECT` request
+const MAX_CONNECT_RESPONSE_LEN: usize = 8192;
+
+/// Build the RPC client of the given endpoint, reached through the given proxy, if any.
+pub fn rpc_client(address: &Url, proxy: Option<&Proxy>) -> Result<HttpClient, Error> {
+    match proxy {
+        None => HttpClient::new(address.clone()).map_err(|e| Error::rpc(address.clone(), e)),
+        Some(proxy) => {
+            let proxy_url =
+                Url::from_str(&proxy.to_string()).map_err(|e| Error::rpc(address.clone(), e))?;
+
+            HttpClient::new_with_proxy(address.clone(), proxy_url)
+                .map_err(|e| Error::rpc(address.clone(), e))
+        }
+    }
+}
+
+/// A connector tunneling the gRPC connections through an HTTP proxy.
+#[derive(Clone, Debug)]
+pub struct ProxyConnector {
+    proxy: Proxy,
+}
+
+impl ProxyConnector {
+    pub fn new(proxy: Proxy) -> Self {
+        Self { proxy }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = TcpStream;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        let proxy = self.proxy.clone();
+
+        Box::pin(async move { connect(&proxy, &target).await })
+    }
+}
+
+/// Open a connection to the given target through the given proxy, with a `CONNECT` request.
+async fn connect(proxy: &Proxy, target: &Uri) -> Result<TcpStream, BoxError> {
+    let host = target
+        .host()
+        .ok_or_else(|| format!("missing host in {}", target))?
+        .trim_matches(|c| c == '[' || c == ']');
+
+    let port = target
+        .port_u16()
+        .unwrap_or(if target.scheme_str() == Some("https") {
+            443
+        } else {
+            80
+        });
+
+    let mut stream = TcpStream::connect(proxy.address()).await?;
+
+    let authority = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+
+    let request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority);
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response byte by byte, not to consume any of the tunneled bytes
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE_LEN {
+            return Err("response of the HTTP proxy is too long".into());
+        }
+
+        response.push(stream.read_u8().await?);
+    }
+
+    if connect_succeeded(&response) {
+        Ok(stream)
+    } else {
+        let status = String::from_utf8_lossy(&response);
+        let status = status.lines().next().unwrap_or_default();
+
+        Err(format!("HTTP proxy refused to connect to {}: {}", authority, status).into())
+    }
+}
+
+/// Whether the given response of an HTTP proxy accepts a `CONNECT` request.
+fn connect_succeeded(response: &[u8]) -> bool {
+    let response = String::from_utf8_lossy(response);
+    let mut status_line = response
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+
+    matches!(
+        (status_line.next(), status_line.next()),
+        (Some(version), Some(code)) if version.starts_with("HTTP/1.") && code.starts_with('2')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_connect_response() {
+        assert!(connect_succeeded(
+            b"HTTP/1.1 200 Connection established\r\n\r\n"
+        ));
+        assert!(connect_succeeded(b"HTTP/1.0 200 OK\r\nVia: squid\r\n\r\n"));
+
+        assert!(!connect_succeeded(
+            b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n"
+        ));
+        assert!(!connect_succeeded(b"garbage\r\n\r\n"));
+    }
+}
@@ -1,3 +1,5 @@
+use core::str::FromStr;
+
 use http::uri::Uri;
 use ibc::core::ics04_channel::packet::Sequence;
 use ibc::core::ics23_commitment::merkle::{convert_tm_to_ics_merkle_proof, MerkleProof};
@@ -9,16 +11,27 @@ use tendermint::block::Height;
 use tendermint_rpc::query::Query;
 use tendermint_rpc::{Client, HttpClient, Url};
 
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
 use crate::chain::cosmos::version::Specs;
-use crate::chain::requests::{QueryClientEventRequest, QueryPacketEventDataRequest, QueryTxHash};
+use crate::chain::requests::{
+    QueryClientEventRequest, QueryHeightRange, QueryPacketEventDataRequest, QueryTxHash,
+};
 use crate::error::Error;
 
 pub mod account;
 pub mod balance;
+pub mod batch;
+pub mod block_results;
 pub mod custom_query;
 pub mod denom_trace;
+pub mod fee;
+pub mod fee_grant;
+pub mod gas_price;
+pub mod pagination;
+pub mod proof;
 pub mod status;
 pub mod tx;
+pub mod wasm;
 
 /// Generic query response type
 #[derive(Clone, Debug, PartialEq)]
@@ -29,6 +42,21 @@ pub struct QueryResponse {
 }
 
 pub fn packet_query(request: &QueryPacketEventDataRequest, seq: Sequence) -> Query {
+    packet_channel_query(request).and_eq(
+        format!("{}.packet_sequence", request.event_id.as_str()),
+        seq.to_string(),
+    )
+}
+
+/// The query for the events of the channel of the given request emitted by the transactions
+/// within the given range of heights, whatever the sequences of their packets.
+pub fn packet_range_query(request: &QueryPacketEventDataRequest, range: QueryHeightRange) -> Query {
+    packet_channel_query(request)
+        .and_gte("tx.height", range.start.revision_height())
+        .and_lte("tx.height", range.end.revision_height())
+}
+
+fn packet_channel_query(request: &QueryPacketEventDataRequest) -> Query {
     Query::eq(
         format!("{}.packet_src_channel", request.event_id.as_str()),
         request.source_channel_id.to_string(),
@@ -45,10 +73,6 @@ pub fn packet_query(request: &QueryPacketEventDataRequest, seq: Sequence) -> Que
         format!("{}.packet_dst_port", request.event_id.as_str()),
         request.destination_port_id.to_string(),
     )
-    .and_eq(
-        format!("{}.packet_sequence", request.event_id.as_str()),
-        seq.to_string(),
-    )
 }
 
 pub fn header_query(request: &QueryClientEventRequest) -> Query {
@@ -70,6 +94,30 @@ pub fn tx_hash_query(request: &QueryTxHash) -> Query {
     Query::eq("tx.hash", request.0.to_string())
 }
 
+/// Perform an ABCI query of the given gRPC method of the application, for the modules
+/// whose gRPC services are not part of the IBC protobuf definitions, and return the
+/// protobuf encoding of the response.
+pub async fn grpc_abci_query(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    path: &str,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    // SAFETY: Creating a Path from the method path of a gRPC service; this should never fail
+    let path = TendermintABCIPath::from_str(path).expect("gRPC method path is a valid ABCI path");
+
+    let response = rpc_client
+        .abci_query(Some(path), data, None, false)
+        .await
+        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+    if !response.code.is_ok() {
+        return Err(Error::abci_query(response));
+    }
+
+    Ok(response.value)
+}
+
 /// Perform a generic `abci_query`, and return the corresponding deserialized response data.
 pub async fn abci_query(
     rpc_client: &HttpClient,
@@ -117,12 +165,17 @@ pub async fn abci_query(
 }
 
 /// Queries the chain to obtain the version information.
-pub async fn fetch_version_specs(chain_id: &ChainId, grpc_address: &Uri) -> Result<Specs, Error> {
+pub async fn fetch_version_specs(
+    chain_id: &ChainId,
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+) -> Result<Specs, Error> {
     let grpc_addr_string = grpc_address.to_string();
 
     // Construct a gRPC client
-    let mut client = ServiceClient::connect(grpc_address.clone())
+    let mut client = grpc::connect(grpc_address, grpc_settings)
         .await
+        .map(ServiceClient::new)
         .map_err(|e| {
             Error::fetch_version_grpc_transport(
                 chain_id.clone(),
@@ -4,7 +4,10 @@ use ibc_proto::cosmos::auth::v1beta1::{BaseAccount, EthAccount, QueryAccountRequ
 use prost::Message;
 use tracing::info;
 
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
 use crate::chain::cosmos::types::account::Account;
+use crate::chain::cosmos::types::config::TxConfig;
 use crate::error::Error;
 
 /// Get a `&mut Account` from an `&mut Option<Account>` if it is `Some(Account)`.
@@ -12,13 +15,14 @@ use crate::error::Error;
 /// and return the underlying `&mut` reference.
 pub async fn get_or_fetch_account<'a>(
     grpc_address: &'a Uri,
+    grpc_settings: &GrpcSettings,
     account_address: &'a str,
     m_account: &'a mut Option<Account>,
 ) -> Result<&'a mut Account, Error> {
     match m_account {
         Some(account) => Ok(account),
         None => {
-            let account = query_account(grpc_address, account_address).await?;
+            let account = query_account(grpc_address, grpc_settings, account_address).await?;
             *m_account = Some(account.into());
 
             Ok(m_account
@@ -32,10 +36,11 @@ pub async fn get_or_fetch_account<'a>(
 /// account and updating the `&mut` reference.
 pub async fn refresh_account<'a>(
     grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
     account_address: &str,
     m_account: &'a mut Account,
 ) -> Result<(), Error> {
-    let account = query_account(grpc_address, account_address).await?;
+    let account = query_account(grpc_address, grpc_settings, account_address).await?;
 
     info!(
         sequence = %account.sequence,
@@ -48,20 +53,60 @@ pub async fn refresh_account<'a>(
     Ok(())
 }
 
+/// Query the account of the fee payer of the transactions, if any, unless it is already
+/// known and not to be refreshed, e.g. after a transaction was rejected because of a
+/// mismatched account sequence number.
+pub async fn fetch_fee_payer_account(config: &TxConfig, refresh: bool) -> Result<(), Error> {
+    let fee_payer = match &config.fee_payer {
+        Some(fee_payer) if refresh || fee_payer.account().is_none() => fee_payer,
+        _ => return Ok(()),
+    };
+
+    let account = with_deadline(
+        &config.chain_id,
+        "fee payer account query",
+        config.query_timeout,
+        query_account(
+            &config.grpc_address,
+            &config.grpc_settings,
+            &fee_payer.key_entry.account,
+        ),
+    )
+    .await??;
+
+    info!(
+        sequence = %account.sequence,
+        number = %account.account_number,
+        "retrieved fee payer account",
+    );
+
+    fee_payer.set_account(account.into());
+
+    Ok(())
+}
+
 /// Uses the GRPC client to retrieve the account sequence
 pub async fn query_account(
     grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
     account_address: &str,
 ) -> Result<BaseAccount, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+    let mut client = grpc::connect(grpc_address, grpc_settings)
         .await
+        .map(QueryClient::new)
         .map_err(Error::grpc_transport)?;
 
     let request = tonic::Request::new(QueryAccountRequest {
         address: account_address.to_string(),
     });
 
-    let response = client.account(request).await;
+    let response = match client.account(request).await {
+        // Recent versions of the SDK fail to query an account which does not exist
+        Err(status) if status.code() == tonic::Code::NotFound => {
+            return Err(Error::empty_query_account(account_address.to_string()))
+        }
+        response => response,
+    };
 
     // Querying for an account might fail, i.e. if the account doesn't actually exist
     let resp_account = match response.map_err(Error::grpc_status)?.into_inner().account {
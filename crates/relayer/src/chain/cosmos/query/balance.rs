@@ -2,16 +2,19 @@ use http::uri::Uri;
 
 use ibc_proto::cosmos::bank::v1beta1::{query_client::QueryClient, QueryBalanceRequest};
 
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
 use crate::{account::Balance, error::Error};
 
 /// Uses the GRPC client to retrieve the account balance for a specific denom
 pub async fn query_balance(
     grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
     account_address: &str,
     denom: &str,
 ) -> Result<Balance, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+    let mut client = grpc::connect(grpc_address, grpc_settings)
         .await
+        .map(QueryClient::new)
         .map_err(Error::grpc_transport)?;
 
     let request = tonic::Request::new(QueryBalanceRequest {
@@ -0,0 +1,143 @@
+//! Batches of queries about the packets of several channels, made concurrently over a
+//! single gRPC connection rather than one after the other, each over its own connection.
+//! The listings are fetched in a single query each, the paginated ones being left to
+//! the queries of the chain, see `CosmosSdkChain::batch_query`.
+
+use core::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use http::uri::Uri;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::Height;
+use ibc_proto::ibc::core::channel::v1::query_client::QueryClient;
+use ibc_proto::ibc::core::channel::v1::{
+    QueryPacketAcknowledgementsRequest as RawQueryPacketAcknowledgementsRequest,
+    QueryPacketCommitmentsRequest as RawQueryPacketCommitmentsRequest,
+};
+use ibc_proto::ibc::core::client::v1::Height as RawHeight;
+
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::grpc::{self, GrpcChannel, GrpcSettings};
+use crate::chain::cosmos::{at_height, specific_height};
+use crate::chain::requests::BatchQueryRequest;
+use crate::chain::responses::BatchQueryResponse;
+use crate::error::Error;
+
+/// Make the given queries over a single connection, up to `max_concurrent_queries`
+/// at a time, each of them within the given deadline. The responses are in the
+/// order of the requests.
+pub async fn batch_query(
+    chain_id: &ChainId,
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    query_timeout: Duration,
+    max_concurrent_queries: usize,
+    requests: Vec<BatchQueryRequest>,
+) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+    let client = with_deadline(
+        chain_id,
+        "gRPC connection",
+        query_timeout,
+        grpc::connect(grpc_address, grpc_settings),
+    )
+    .await?
+    .map(QueryClient::new)
+    .map_err(Error::grpc_transport)?;
+
+    let queries = requests.into_iter().map(|request| {
+        // The clones of the client share its connection, over which the queries are multiplexed
+        let client = client.clone();
+
+        async move {
+            with_deadline(chain_id, "query", query_timeout, query(client, request))
+                .await
+                .and_then(|response| response)
+        }
+    });
+
+    let responses = stream::iter(queries)
+        .buffered(max_concurrent_queries.max(1))
+        .collect()
+        .await;
+
+    Ok(responses)
+}
+
+async fn query(
+    mut client: QueryClient<GrpcChannel>,
+    request: BatchQueryRequest,
+) -> Result<BatchQueryResponse, Error> {
+    match request {
+        BatchQueryRequest::PacketCommitments(request) => {
+            let height = specific_height(request.height);
+
+            let response = client
+                .packet_commitments(at_height(
+                    RawQueryPacketCommitmentsRequest::from(request),
+                    height,
+                )?)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            let mut sequences: Vec<Sequence> = response
+                .commitments
+                .into_iter()
+                .map(|c| c.sequence.into())
+                .collect();
+            sequences.sort_unstable();
+
+            Ok(BatchQueryResponse::PacketCommitments(
+                sequences,
+                response_height(response.height)?,
+            ))
+        }
+
+        BatchQueryRequest::UnreceivedPackets(request) => {
+            let mut response = client
+                .unreceived_packets(tonic::Request::new(request.into()))
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            response.sequences.sort_unstable();
+
+            Ok(BatchQueryResponse::UnreceivedPackets(
+                response.sequences.into_iter().map(Sequence::from).collect(),
+            ))
+        }
+
+        BatchQueryRequest::PacketAcknowledgements(request) => {
+            let height = specific_height(request.height);
+
+            let response = client
+                .packet_acknowledgements(at_height(
+                    RawQueryPacketAcknowledgementsRequest::from(request),
+                    height,
+                )?)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            let mut sequences: Vec<Sequence> = response
+                .acknowledgements
+                .into_iter()
+                .map(|a| a.sequence.into())
+                .collect();
+            sequences.sort_unstable();
+
+            Ok(BatchQueryResponse::PacketAcknowledgements(
+                sequences,
+                response_height(response.height)?,
+            ))
+        }
+    }
+}
+
+fn response_height(height: Option<RawHeight>) -> Result<Height, Error> {
+    height
+        .and_then(|raw_height| raw_height.try_into().ok())
+        .ok_or_else(|| Error::grpc_response_param("height".to_string()))
+}
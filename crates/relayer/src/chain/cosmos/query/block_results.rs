@@ -0,0 +1,247 @@
+//! Queries of the results of the blocks of a chain, whose layout changed with CometBFT 0.38:
+//! the events of BeginBlock and EndBlock are emitted together under `finalize_block_events`,
+//! and the results of the transactions are the ones of their execution by FinalizeBlock.
+
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tendermint::abci::tag::{Key, Tag, Value};
+use tendermint::abci::Event;
+use tendermint::block::Height;
+use tendermint_rpc::{Client, HttpClient, Method, Url};
+
+use crate::error::Error;
+
+/// The layout of the results of the blocks returned by a node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlockResultsLayout {
+    /// Tendermint and CometBFT up to 0.37, with separate BeginBlock and EndBlock events
+    BeginEndBlock,
+    /// CometBFT 0.38 and above, with the block events under `finalize_block_events`
+    FinalizeBlock,
+}
+
+impl BlockResultsLayout {
+    /// Select the layout from the version reported by the node, e.g. `0.38.2` or `v0.34.24`.
+    ///
+    /// Versions which cannot be parsed are assumed to have the layout of the older nodes.
+    pub fn from_node_version(version: &str) -> Self {
+        match semver::Version::parse(version.trim_start_matches('v')) {
+            Ok(version) if (version.major, version.minor) >= (0, 38) => Self::FinalizeBlock,
+            _ => Self::BeginEndBlock,
+        }
+    }
+}
+
+/// The events and transaction results of a block.
+#[derive(Clone, Debug, Default)]
+pub struct BlockResults {
+    pub begin_block_events: Vec<Event>,
+    pub end_block_events: Vec<Event>,
+    /// The events emitted by each transaction of the block
+    pub txs_events: Vec<Vec<Event>>,
+}
+
+/// Query the results of the block at the given height, with the given layout.
+pub async fn query_block_results(
+    layout: BlockResultsLayout,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    height: Height,
+) -> Result<BlockResults, Error> {
+    match layout {
+        BlockResultsLayout::BeginEndBlock => {
+            let response = rpc_client
+                .block_results(height)
+                .await
+                .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+            Ok(BlockResults {
+                begin_block_events: response.begin_block_events.unwrap_or_default(),
+                end_block_events: response.end_block_events.unwrap_or_default(),
+                txs_events: response
+                    .txs_results
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|tx| tx.events)
+                    .collect(),
+            })
+        }
+        BlockResultsLayout::FinalizeBlock => {
+            let response = rpc_client
+                .perform(FinalizeBlockResultsRequest {
+                    height: Some(height),
+                })
+                .await
+                .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+            Ok(response.into())
+        }
+    }
+}
+
+/// The `block_results` request, answered with the layout of CometBFT 0.38.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FinalizeBlockResultsRequest {
+    height: Option<Height>,
+}
+
+impl tendermint_rpc::Request for FinalizeBlockResultsRequest {
+    type Response = FinalizeBlockResultsResponse;
+
+    fn method(&self) -> Method {
+        Method::BlockResults
+    }
+}
+
+impl tendermint_rpc::SimpleRequest for FinalizeBlockResultsRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FinalizeBlockResultsResponse {
+    height: Height,
+    #[serde(default)]
+    txs_results: Option<Vec<RawTxResult>>,
+    #[serde(default)]
+    finalize_block_events: Vec<RawEvent>,
+}
+
+impl tendermint_rpc::Response for FinalizeBlockResultsResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RawTxResult {
+    #[serde(default)]
+    events: Vec<RawEvent>,
+}
+
+/// An event of CometBFT 0.38, whose attributes are plain strings.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RawEvent {
+    #[serde(rename = "type")]
+    type_str: String,
+    #[serde(default)]
+    attributes: Vec<RawAttribute>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RawAttribute {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// The attribute set by the Cosmos SDK on the events emitted by FinalizeBlock,
+/// telling whether they were emitted by the BeginBlocker or the EndBlocker.
+const MODE_ATTRIBUTE_KEY: &str = "mode";
+
+impl From<RawEvent> for Event {
+    fn from(event: RawEvent) -> Self {
+        Event {
+            type_str: event.type_str,
+            // The parsing of `Key`s and `Value`s is infallible
+            attributes: event
+                .attributes
+                .into_iter()
+                .map(|attribute| Tag {
+                    key: Key::from_str(&attribute.key).unwrap(),
+                    value: Value::from_str(&attribute.value).unwrap(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<FinalizeBlockResultsResponse> for BlockResults {
+    fn from(response: FinalizeBlockResultsResponse) -> Self {
+        let (begin_block_events, end_block_events) = response
+            .finalize_block_events
+            .into_iter()
+            .partition::<Vec<_>, _>(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == MODE_ATTRIBUTE_KEY && attr.value == "BeginBlock")
+            });
+
+        BlockResults {
+            begin_block_events: begin_block_events.into_iter().map(Into::into).collect(),
+            end_block_events: end_block_events.into_iter().map(Into::into).collect(),
+            txs_events: response
+                .txs_results
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tx| tx.events.into_iter().map(Into::into).collect())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_layout() {
+        let layout = BlockResultsLayout::from_node_version;
+
+        assert_eq!(layout("0.38.2"), BlockResultsLayout::FinalizeBlock);
+        assert_eq!(layout("v1.0.0-rc1"), BlockResultsLayout::FinalizeBlock);
+        assert_eq!(layout("0.37.4"), BlockResultsLayout::BeginEndBlock);
+        assert_eq!(layout("v0.34.24"), BlockResultsLayout::BeginEndBlock);
+        assert_eq!(layout("unknown"), BlockResultsLayout::BeginEndBlock);
+    }
+
+    #[test]
+    fn parse_finalize_block_results() {
+        let json = r#"{
+            "height": "1200",
+            "txs_results": [
+                {
+                    "code": 0,
+                    "events": [
+                        {
+                            "type": "send_packet",
+                            "attributes": [
+                                { "key": "packet_sequence", "value": "7", "index": true }
+                            ]
+                        }
+                    ]
+                }
+            ],
+            "finalize_block_events": [
+                {
+                    "type": "send_packet",
+                    "attributes": [
+                        { "key": "packet_sequence", "value": "5", "index": true },
+                        { "key": "mode", "value": "BeginBlock", "index": true }
+                    ]
+                },
+                {
+                    "type": "write_acknowledgement",
+                    "attributes": [
+                        { "key": "packet_sequence", "value": "6", "index": true },
+                        { "key": "mode", "value": "EndBlock", "index": true }
+                    ]
+                }
+            ],
+            "validator_updates": [],
+            "app_hash": ""
+        }"#;
+
+        let response: FinalizeBlockResultsResponse = serde_json::from_str(json).unwrap();
+        let results = BlockResults::from(response);
+
+        assert_eq!(results.begin_block_events.len(), 1);
+        assert_eq!(results.begin_block_events[0].type_str, "send_packet");
+        assert_eq!(results.end_block_events.len(), 1);
+        assert_eq!(
+            results.end_block_events[0].type_str,
+            "write_acknowledgement"
+        );
+
+        assert_eq!(results.txs_events.len(), 1);
+        assert_eq!(
+            results.txs_events[0][0].attributes[0].value.to_string(),
+            "7"
+        );
+    }
+}
@@ -1,7 +1,20 @@
-use crate::chain::requests::CrossChainQueryRequest;
+use core::str::FromStr;
+
+use crate::chain::requests::{CrossChainQueryPath, CrossChainQueryRequest};
 use crate::chain::responses::CrossChainQueryResponse;
+use crate::error::Error as RelayerError;
+use ibc::core::ics23_commitment::specs::ProofSpecs;
+use ibc_proto::ibc::applications::query::v1::QueryResult;
 use reqwest::{Client, Error};
 use serde::{Deserialize, Serialize};
+use tendermint::abci::Path as TendermintABCIPath;
+use tendermint::block::Height;
+use tendermint_rpc::{Client as _, HttpClient, Url};
+
+/// The prefix of the ABCI paths querying the store of a module, whose results carry the
+/// proof specs of the queried chain. `MsgSubmitCrossChainQueryResult` has no field for
+/// the proof of the value itself, so none is requested from the full node.
+const STORE_QUERY_PATH_PREFIX: &str = "store/";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MsgTransfer {
@@ -11,6 +24,97 @@ pub struct MsgTransfer {
     pub sender: String,
 }
 
+/// Execute the given cross-chain query on the queried chain, either against its REST
+/// endpoint or as an ABCI query of its full node, depending on the path of the query.
+///
+/// The queries which are answered by the chain, successfully or not, have a response.
+/// The ones which could not be executed, e.g. as the endpoint is unreachable, fail,
+/// and are expected to be retried.
+pub async fn cross_chain_query(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    rest_client: &Client,
+    proof_specs: &ProofSpecs,
+    request: CrossChainQueryRequest,
+) -> Result<CrossChainQueryResponse, RelayerError> {
+    match request.decode_query() {
+        Some(CrossChainQueryPath::Rest(_)) => rest_query(rest_client, request.clone())
+            .await
+            .map_err(|e| RelayerError::invalid_cross_chain_query(request.id, e.to_string())),
+        Some(CrossChainQueryPath::Abci { path, data }) => {
+            abci_query(rpc_client, rpc_address, proof_specs, request, path, data).await
+        }
+        None => Ok(CrossChainQueryResponse::new(
+            request.id,
+            QueryResult::Failure as i32,
+            Vec::new(),
+            request.height,
+        )),
+    }
+}
+
+async fn abci_query(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    proof_specs: &ProofSpecs,
+    request: CrossChainQueryRequest,
+    path: String,
+    data: Vec<u8>,
+) -> Result<CrossChainQueryResponse, RelayerError> {
+    let failure = |request: CrossChainQueryRequest| {
+        Ok(CrossChainQueryResponse::new(
+            request.id,
+            QueryResult::Failure as i32,
+            Vec::new(),
+            request.height,
+        ))
+    };
+
+    let (abci_path, height) = match (
+        TendermintABCIPath::from_str(&path),
+        request.query_height().map(Height::try_from),
+    ) {
+        (Ok(abci_path), Some(Ok(height))) => (abci_path, height),
+        _ => return failure(request),
+    };
+
+    let store_query = path.starts_with(STORE_QUERY_PATH_PREFIX);
+
+    let response = rpc_client
+        .abci_query(
+            Some(abci_path),
+            data,
+            (height.value() != 0).then(|| height),
+            false,
+        )
+        .await
+        .map_err(|e| RelayerError::rpc(rpc_address.clone(), e))?;
+
+    if !response.code.is_ok() {
+        return failure(request);
+    }
+
+    // The queries of the latest height are answered at the height they were executed at
+    let query_height = if height.value() == 0 {
+        response.height.to_string()
+    } else {
+        request.height
+    };
+
+    let response = CrossChainQueryResponse::new(
+        request.id,
+        QueryResult::Success as i32,
+        response.value,
+        query_height,
+    );
+
+    if store_query {
+        Ok(response.with_proof_specs(proof_specs.clone()))
+    } else {
+        Ok(response)
+    }
+}
+
 pub async fn rest_query(
     client: &Client,
     request: CrossChainQueryRequest,
@@ -30,23 +134,72 @@ pub async fn rest_query(
             match data {
                 Ok(res) => Ok(CrossChainQueryResponse::new(
                     request.id,
-                    1,
-                    res,
+                    QueryResult::Success as i32,
+                    res.into_bytes(),
                     request.height,
                 )),
                 Err(e) => Ok(CrossChainQueryResponse::new(
                     request.id,
-                    2,
-                    e.to_string(),
+                    QueryResult::Failure as i32,
+                    e.to_string().into_bytes(),
                     request.height,
                 )),
             }
         }
         None => Ok(CrossChainQueryResponse::new(
             request.id,
-            2,
-            "".to_string(),
+            QueryResult::Failure as i32,
+            Vec::new(),
             request.height,
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str) -> CrossChainQueryRequest {
+        CrossChainQueryRequest {
+            chain_id: "osmosis-1".to_string(),
+            id: "query-1".to_string(),
+            path: hex::encode(path),
+            height: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn decode_query_paths() {
+        assert_eq!(
+            request("https://rest.example.org/cosmos/bank/v1beta1/balances/osmo1").decode_query(),
+            Some(CrossChainQueryPath::Rest(
+                "https://rest.example.org/cosmos/bank/v1beta1/balances/osmo1".to_string()
+            ))
+        );
+
+        assert_eq!(
+            request("store/bank/key?0201ff").decode_query(),
+            Some(CrossChainQueryPath::Abci {
+                path: "store/bank/key".to_string(),
+                data: vec![0x02, 0x01, 0xff],
+            })
+        );
+
+        assert_eq!(
+            request("/cosmos.bank.v1beta1.Query/Params").decode_query(),
+            Some(CrossChainQueryPath::Abci {
+                path: "/cosmos.bank.v1beta1.Query/Params".to_string(),
+                data: vec![],
+            })
+        );
+
+        assert_eq!(request("store/bank/key?zz").decode_query(), None);
+        assert_eq!(request("").decode_query(), None);
+
+        let malformed = CrossChainQueryRequest {
+            path: "not hex".to_string(),
+            ..request("store/bank/key")
+        };
+        assert_eq!(malformed.decode_query(), None);
+    }
+}
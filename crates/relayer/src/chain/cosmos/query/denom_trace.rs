@@ -4,12 +4,18 @@ use ibc_proto::ibc::applications::transfer::v1::{
     query_client::QueryClient, QueryDenomTraceRequest,
 };
 
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
 use crate::{denom::DenomTrace, error::Error};
 
 // Uses the GRPC client to retrieve the denom trace for a specific hash
-pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTrace, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+pub async fn query_denom_trace(
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    hash: &str,
+) -> Result<DenomTrace, Error> {
+    let mut client = grpc::connect(grpc_address, grpc_settings)
         .await
+        .map(QueryClient::new)
         .map_err(Error::grpc_transport)?;
 
     let request = tonic::Request::new(QueryDenomTraceRequest {
@@ -0,0 +1,60 @@
+use http::uri::Uri;
+use tonic::Code;
+
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
+use ibc::signer::Signer;
+use ibc_proto::ibc::applications::fee::v1::query_client::QueryClient;
+
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
+use crate::chain::requests::{QueryCounterpartyPayeeRequest, QueryIncentivizedPacketRequest};
+use crate::error::Error;
+
+/// Uses the GRPC client to retrieve the fees escrowed for a packet,
+/// if the packet is incentivized.
+pub async fn query_incentivized_packet(
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    request: QueryIncentivizedPacketRequest,
+) -> Result<Option<IdentifiedPacketFees>, Error> {
+    let mut client = grpc::connect(grpc_address, grpc_settings)
+        .await
+        .map(QueryClient::new)
+        .map_err(Error::grpc_transport)?;
+
+    let request = tonic::Request::new(request.into());
+
+    let response = match client.incentivized_packet(request).await {
+        Ok(response) => response.into_inner(),
+        // The packets which are not incentivized are not found
+        Err(status) if status.code() == Code::NotFound => return Ok(None),
+        Err(status) => return Err(Error::grpc_status(status)),
+    };
+
+    response
+        .incentivized_packet
+        .map(|fees| fees.try_into().map_err(Error::ics29))
+        .transpose()
+}
+
+/// Uses the GRPC client to retrieve the counterparty payee registered by a relayer
+/// for a channel, if any.
+pub async fn query_counterparty_payee(
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    request: QueryCounterpartyPayeeRequest,
+) -> Result<Option<Signer>, Error> {
+    let mut client = grpc::connect(grpc_address, grpc_settings)
+        .await
+        .map(QueryClient::new)
+        .map_err(Error::grpc_transport)?;
+
+    let request = tonic::Request::new(request.into());
+
+    let response = match client.counterparty_payee(request).await {
+        Ok(response) => response.into_inner(),
+        Err(status) if status.code() == Code::NotFound => return Ok(None),
+        Err(status) => return Err(Error::grpc_status(status)),
+    };
+
+    Ok(response.counterparty_payee.parse().ok())
+}
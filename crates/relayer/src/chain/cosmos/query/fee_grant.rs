@@ -0,0 +1,439 @@
+//! Queries of the allowances granted with the `feegrant` module of the Cosmos SDK, by
+//! which the fees of the transactions of the relayer are paid by the `fee_granter` of
+//! the chain, see [`check_fee_grant`].
+//!
+//! The query is an ABCI query of the gRPC method of the module, whose messages are
+//! defined here as they are not part of the IBC protobuf definitions. The allowances
+//! are cached for a short time, see [`FeeAllowanceCache`].
+
+use core::fmt;
+use core::time::Duration as StdDuration;
+
+use ibc::timestamp::Timestamp;
+use ibc_proto::cosmos::base::v1beta1::Coin;
+use ibc_proto::cosmos::tx::v1beta1::Fee;
+use ibc_proto::google::protobuf::{Any, Duration, Timestamp as RawTimestamp};
+use moka::sync::Cache as MokaCache;
+use prost::Message;
+use tendermint_rpc::{HttpClient, Url};
+use tracing::debug;
+
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::query::grpc_abci_query;
+use crate::chain::cosmos::types::config::TxConfig;
+use crate::error::Error;
+use crate::keyring::KeyEntry;
+
+const FEE_ALLOWANCE_PATH: &str = "/cosmos.feegrant.v1beta1.Query/Allowance";
+
+const BASIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+const PERIODIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.PeriodicAllowance";
+const ALLOWED_MSG_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.AllowedMsgAllowance";
+
+/// How long the allowance of a grantee is cached before it is queried again.
+const FEE_ALLOWANCE_TTL: StdDuration = StdDuration::from_secs(60);
+
+/// The allowances granted to the grantees of a chain, cached so that they are not queried
+/// before each transaction. As the amount left to spend of a cached allowance is only an
+/// upper bound, the allowance is queried again before a transaction is failed for it.
+#[derive(Clone)]
+pub struct FeeAllowanceCache(MokaCache<String, FeeAllowance>);
+
+impl Default for FeeAllowanceCache {
+    fn default() -> Self {
+        Self(
+            MokaCache::builder()
+                .time_to_live(FEE_ALLOWANCE_TTL)
+                .max_capacity(16)
+                .build(),
+        )
+    }
+}
+
+impl fmt::Debug for FeeAllowanceCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeeAllowanceCache").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryAllowanceRequest {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryAllowanceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub allowance: Option<Grant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Grant {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub allowance: Option<Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct BasicAllowance {
+    #[prost(message, repeated, tag = "1")]
+    pub spend_limit: Vec<Coin>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<RawTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PeriodicAllowance {
+    #[prost(message, optional, tag = "1")]
+    pub basic: Option<BasicAllowance>,
+    #[prost(message, optional, tag = "2")]
+    pub period: Option<Duration>,
+    #[prost(message, repeated, tag = "3")]
+    pub period_spend_limit: Vec<Coin>,
+    #[prost(message, repeated, tag = "4")]
+    pub period_can_spend: Vec<Coin>,
+    #[prost(message, optional, tag = "5")]
+    pub period_reset: Option<RawTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct AllowedMsgAllowance {
+    #[prost(message, optional, tag = "1")]
+    pub allowance: Option<Any>,
+    #[prost(string, repeated, tag = "2")]
+    pub allowed_messages: Vec<String>,
+}
+
+/// The conditions of an allowance under which a granter pays the fees of a grantee.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeeAllowance {
+    /// The limits which the fee of a transaction must be within, none of which
+    /// applies to the denominations it does not list unless it is empty.
+    pub spend_limits: Vec<Vec<Coin>>,
+    /// The time after which the allowance cannot be used anymore, if any
+    pub expiration: Option<Timestamp>,
+    /// The type URLs of the only messages whose transactions the allowance pays for, if any
+    pub allowed_messages: Option<Vec<String>>,
+}
+
+impl FeeAllowance {
+    fn decode(allowance: &Any, now: Timestamp) -> Result<Self, Error> {
+        match allowance.type_url.as_str() {
+            BASIC_ALLOWANCE_TYPE_URL => {
+                let basic = BasicAllowance::decode(allowance.value.as_slice())
+                    .map_err(|e| Error::protobuf_decode("BasicAllowance".to_string(), e))?;
+
+                Ok(Self::from_basic(basic))
+            }
+            PERIODIC_ALLOWANCE_TYPE_URL => {
+                let periodic = PeriodicAllowance::decode(allowance.value.as_slice())
+                    .map_err(|e| Error::protobuf_decode("PeriodicAllowance".to_string(), e))?;
+
+                let mut fee_allowance = Self::from_basic(periodic.basic.unwrap_or_default());
+
+                // The amount left to spend is reset to the limit of the period once it is over
+                let period_over = periodic
+                    .period_reset
+                    .map_or(true, |reset| is_past(&reset, now));
+
+                fee_allowance.spend_limits.push(if period_over {
+                    periodic.period_spend_limit
+                } else {
+                    periodic.period_can_spend
+                });
+
+                Ok(fee_allowance)
+            }
+            ALLOWED_MSG_ALLOWANCE_TYPE_URL => {
+                let allowed = AllowedMsgAllowance::decode(allowance.value.as_slice())
+                    .map_err(|e| Error::protobuf_decode("AllowedMsgAllowance".to_string(), e))?;
+
+                let inner = allowed.allowance.ok_or_else(Error::empty_response_value)?;
+
+                Ok(Self {
+                    allowed_messages: Some(allowed.allowed_messages),
+                    ..Self::decode(&inner, now)?
+                })
+            }
+            type_url => Err(Error::invalid_fee_grant_type(type_url.to_string())),
+        }
+    }
+
+    fn from_basic(basic: BasicAllowance) -> Self {
+        Self {
+            spend_limits: vec![basic.spend_limit],
+            expiration: basic
+                .expiration
+                .and_then(|expiration| to_timestamp(&expiration)),
+            allowed_messages: None,
+        }
+    }
+
+    /// Check that the allowance pays the given fee of a transaction of the given messages
+    /// at the given time, and return why it does not otherwise.
+    pub fn check(&self, fee: &Fee, messages: &[Any], now: Timestamp) -> Result<(), String> {
+        if let Some(expiration) = self.expiration {
+            if now.after(&expiration) {
+                return Err(format!("the allowance expired at {}", expiration));
+            }
+        }
+
+        if let Some(allowed_messages) = &self.allowed_messages {
+            if let Some(message) = messages
+                .iter()
+                .find(|message| !allowed_messages.contains(&message.type_url))
+            {
+                return Err(format!(
+                    "the allowance does not pay for messages of type {}",
+                    message.type_url
+                ));
+            }
+        }
+
+        for limit in self.spend_limits.iter().filter(|limit| !limit.is_empty()) {
+            for coin in &fee.amount {
+                let left = limit
+                    .iter()
+                    .find(|left| left.denom == coin.denom)
+                    .map_or(0, |left| parse_amount(&left.amount));
+
+                if parse_amount(&coin.amount) > left {
+                    return Err(format!(
+                        "the fee of {}{} exceeds the {}{} left to spend",
+                        coin.amount, coin.denom, left, coin.denom
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Query the allowance granted by the given granter to the given grantee.
+pub async fn query_fee_allowance(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    granter: &str,
+    grantee: &str,
+) -> Result<FeeAllowance, Error> {
+    let request = QueryAllowanceRequest {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+    };
+
+    let value = grpc_abci_query(
+        rpc_client,
+        rpc_address,
+        FEE_ALLOWANCE_PATH,
+        request.encode_to_vec(),
+    )
+    .await?;
+
+    let response = QueryAllowanceResponse::decode(value.as_slice())
+        .map_err(|e| Error::protobuf_decode("QueryAllowanceResponse".to_string(), e))?;
+
+    let allowance = response
+        .allowance
+        .and_then(|grant| grant.allowance)
+        .ok_or_else(Error::empty_response_value)?;
+
+    FeeAllowance::decode(&allowance, Timestamp::now())
+}
+
+/// Check that the fee granter of the chain, if any, still pays the given fee of a transaction
+/// of the given messages, so that the transaction is not broadcast to be rejected for it.
+///
+/// The grantee of the allowance is the fee payer of the transactions if the chain has one,
+/// and the account of the given key otherwise. The allowance is only queried when it is
+/// not cached, or when the cached allowance does not pay the fee.
+pub async fn check_fee_grant(
+    config: &TxConfig,
+    key_entry: &KeyEntry,
+    messages: &[Any],
+    fee: &Fee,
+) -> Result<(), Error> {
+    let granter = &config.gas_config.fee_granter;
+
+    if granter.is_empty() {
+        return Ok(());
+    }
+
+    let grantee = match &config.fee_payer {
+        Some(fee_payer) => &fee_payer.key_entry.account,
+        None => &key_entry.account,
+    };
+
+    if let Some(allowance) = config.fee_allowances.0.get(grantee) {
+        if allowance.check(fee, messages, Timestamp::now()).is_ok() {
+            return Ok(());
+        }
+    }
+
+    let allowance = with_deadline(
+        &config.chain_id,
+        "fee allowance query",
+        config.query_timeout,
+        query_fee_allowance(&config.rpc_client, &config.rpc_address, granter, grantee),
+    )
+    .await??;
+
+    debug!(%granter, %grantee, ?allowance, "fee allowance");
+
+    config
+        .fee_allowances
+        .0
+        .insert(grantee.clone(), allowance.clone());
+
+    allowance
+        .check(fee, messages, Timestamp::now())
+        .map_err(|reason| Error::invalid_fee_grant(granter.clone(), grantee.clone(), reason))
+}
+
+fn to_timestamp(timestamp: &RawTimestamp) -> Option<Timestamp> {
+    let nanos = u64::try_from(timestamp.seconds)
+        .ok()?
+        .checked_mul(1_000_000_000)?
+        .checked_add(u64::try_from(timestamp.nanos).ok()?)?;
+
+    Timestamp::from_nanoseconds(nanos).ok()
+}
+
+fn is_past(timestamp: &RawTimestamp, now: Timestamp) -> bool {
+    to_timestamp(timestamp).map_or(true, |timestamp| now.after(&timestamp))
+}
+
+fn parse_amount(amount: &str) -> u128 {
+    amount.parse().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coins(amount: u64) -> Vec<Coin> {
+        vec![Coin {
+            denom: "stake".to_string(),
+            amount: amount.to_string(),
+        }]
+    }
+
+    fn fee(amount: u64) -> Fee {
+        Fee {
+            amount: coins(amount),
+            gas_limit: 100_000,
+            payer: String::new(),
+            granter: "cosmos1granter".to_string(),
+        }
+    }
+
+    fn raw_timestamp(timestamp: Timestamp) -> RawTimestamp {
+        let nanos = timestamp.nanoseconds();
+
+        RawTimestamp {
+            seconds: (nanos / 1_000_000_000) as i64,
+            nanos: (nanos % 1_000_000_000) as i32,
+        }
+    }
+
+    fn msg(type_url: &str) -> Any {
+        Any {
+            type_url: type_url.to_string(),
+            value: vec![],
+        }
+    }
+
+    #[test]
+    fn basic_allowances() {
+        let now = Timestamp::now();
+        let later = (now + core::time::Duration::from_secs(60)).unwrap();
+
+        let basic = BasicAllowance {
+            spend_limit: coins(1000),
+            expiration: Some(raw_timestamp(later)),
+        };
+
+        let allowance = FeeAllowance::decode(
+            &Any {
+                type_url: BASIC_ALLOWANCE_TYPE_URL.to_string(),
+                value: basic.encode_to_vec(),
+            },
+            now,
+        )
+        .unwrap();
+
+        let messages = [msg("/ibc.core.client.v1.MsgUpdateClient")];
+
+        assert!(allowance.check(&fee(1000), &messages, now).is_ok());
+        assert!(allowance.check(&fee(1001), &messages, now).is_err());
+
+        let expired = (later + core::time::Duration::from_secs(1)).unwrap();
+        assert!(allowance.check(&fee(10), &messages, expired).is_err());
+
+        let other_denom = Fee {
+            amount: vec![Coin {
+                denom: "uatom".to_string(),
+                amount: "1".to_string(),
+            }],
+            ..fee(0)
+        };
+        assert!(allowance.check(&other_denom, &messages, now).is_err());
+
+        assert!(FeeAllowance::default()
+            .check(&fee(u64::MAX), &messages, now)
+            .is_ok());
+    }
+
+    #[test]
+    fn periodic_and_allowed_msg_allowances() {
+        let now = Timestamp::now();
+        let later = (now + core::time::Duration::from_secs(60)).unwrap();
+
+        let periodic = PeriodicAllowance {
+            basic: Some(BasicAllowance {
+                spend_limit: coins(1000),
+                expiration: None,
+            }),
+            period: Some(Duration {
+                seconds: 3600,
+                nanos: 0,
+            }),
+            period_spend_limit: coins(100),
+            period_can_spend: coins(10),
+            period_reset: Some(raw_timestamp(later)),
+        };
+
+        let allowed = AllowedMsgAllowance {
+            allowance: Some(Any {
+                type_url: PERIODIC_ALLOWANCE_TYPE_URL.to_string(),
+                value: periodic.encode_to_vec(),
+            }),
+            allowed_messages: vec!["/ibc.core.channel.v1.MsgRecvPacket".to_string()],
+        };
+
+        let allowed = Any {
+            type_url: ALLOWED_MSG_ALLOWANCE_TYPE_URL.to_string(),
+            value: allowed.encode_to_vec(),
+        };
+
+        let allowance = FeeAllowance::decode(&allowed, now).unwrap();
+        let recv = [msg("/ibc.core.channel.v1.MsgRecvPacket")];
+
+        assert!(allowance.check(&fee(10), &recv, now).is_ok());
+        assert!(allowance.check(&fee(11), &recv, now).is_err());
+
+        let transfer = [msg("/ibc.applications.transfer.v1.MsgTransfer")];
+        assert!(allowance.check(&fee(10), &transfer, now).is_err());
+
+        // Once the period is over, the whole limit of the next period can be spent
+        let next_period = (later + core::time::Duration::from_secs(1)).unwrap();
+        let allowance = FeeAllowance::decode(&allowed, next_period).unwrap();
+        assert!(allowance.check(&fee(100), &recv, next_period).is_ok());
+    }
+}
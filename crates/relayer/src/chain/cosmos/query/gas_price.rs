@@ -0,0 +1,266 @@
+//! Queries of the gas price set by the fee market module of a chain, see
+//! [`DynamicGasPrice`](crate::config::dynamic_gas_price::DynamicGasPrice), refreshed
+//! in the background by a [`GasPriceFeed`].
+//!
+//! The queries are ABCI queries of the gRPC methods of the modules, whose messages
+//! are defined here as they are not part of the IBC protobuf definitions.
+
+use alloc::sync::Arc;
+use std::sync::RwLock;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_proto::cosmos::base::v1beta1::DecCoin;
+use prost::Message;
+use tendermint_rpc::{HttpClient, Url};
+use tokio::runtime::Runtime as TokioRuntime;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, warn};
+
+use crate::chain::cosmos::query::grpc_abci_query;
+use crate::config::dynamic_gas_price::{DynamicGasPrice, GasPriceSource};
+use crate::config::GasPrice;
+use crate::error::Error;
+
+const FEE_MARKET_GAS_PRICE_PATH: &str = "/feemarket.feemarket.v1.Query/GasPrice";
+const OSMOSIS_EIP_BASE_FEE_PATH: &str = "/osmosis.txfees.v1beta1.Query/GetEipBaseFee";
+const OSMOSIS_BASE_DENOM_PATH: &str = "/osmosis.txfees.v1beta1.Query/BaseDenom";
+const OSMOSIS_DENOM_SPOT_PRICE_PATH: &str = "/osmosis.txfees.v1beta1.Query/DenomSpotPrice";
+
+/// The number of decimals of the `Dec` type of the Cosmos SDK.
+const SDK_DEC_PRECISION: i32 = 18;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GasPriceRequest {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GasPriceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub price: Option<DecCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryEipBaseFeeRequest {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryEipBaseFeeResponse {
+    #[prost(string, tag = "1")]
+    pub base_fee: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryBaseDenomRequest {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryBaseDenomResponse {
+    #[prost(string, tag = "1")]
+    pub base_denom: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryDenomSpotPriceRequest {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryDenomSpotPriceResponse {
+    #[prost(uint64, tag = "1")]
+    pub pool_id: u64,
+    /// The amount of the base denomination worth one unit of the denomination
+    #[prost(string, tag = "2")]
+    pub spot_price: String,
+}
+
+/// The gas price of a chain following its fee market, queried every refresh interval
+/// in the background, so that the transactions never wait for the fee market.
+#[derive(Clone, Debug, Default)]
+pub struct GasPriceFeed {
+    price: Arc<RwLock<Option<GasPrice>>>,
+}
+
+impl GasPriceFeed {
+    /// Start querying the gas price of the denomination of the given static gas price
+    /// as configured, on the given runtime, until the runtime is shut down.
+    pub fn spawn(
+        rt: &TokioRuntime,
+        chain_id: ChainId,
+        rpc_client: HttpClient,
+        rpc_address: Url,
+        dynamic: DynamicGasPrice,
+        static_price: GasPrice,
+    ) -> Self {
+        let feed = Self::default();
+        let price = feed.price.clone();
+
+        rt.spawn(async move {
+            let mut interval = tokio::time::interval(dynamic.refresh_interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                let denom = &static_price.denom;
+
+                match query_gas_price(&rpc_client, &rpc_address, dynamic.source, denom).await {
+                    Ok(queried_price) => {
+                        let paid = dynamic.bounded_price(queried_price, static_price.price);
+
+                        debug!(
+                            chain = %chain_id,
+                            "gas price of the fee market is {}{}, paying {}{}",
+                            queried_price, denom, paid, denom
+                        );
+
+                        *price.write().unwrap() = Some(GasPrice::new(paid, denom.clone()));
+                    }
+                    Err(e) => warn!(
+                        chain = %chain_id,
+                        "failed to query the gas price of the fee market, keeping the last price: {}",
+                        e
+                    ),
+                }
+            }
+        });
+
+        feed
+    }
+
+    /// The last gas price queried, if any query succeeded yet.
+    pub fn latest(&self) -> Option<GasPrice> {
+        self.price.read().unwrap().clone()
+    }
+}
+
+/// Query the current gas price of the given denomination from the given module.
+pub async fn query_gas_price(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    source: GasPriceSource,
+    denom: &str,
+) -> Result<f64, Error> {
+    match source {
+        GasPriceSource::FeeMarket => {
+            let request = GasPriceRequest {
+                denom: denom.to_string(),
+            };
+
+            let value = grpc_abci_query(
+                rpc_client,
+                rpc_address,
+                FEE_MARKET_GAS_PRICE_PATH,
+                request.encode_to_vec(),
+            )
+            .await?;
+
+            let response = GasPriceResponse::decode(value.as_slice())
+                .map_err(|e| Error::protobuf_decode("GasPriceResponse".to_string(), e))?;
+
+            let price = response.price.ok_or_else(Error::empty_response_value)?;
+            parse_sdk_dec(&price.amount)
+        }
+        GasPriceSource::OsmosisTxFees => {
+            let value = grpc_abci_query(
+                rpc_client,
+                rpc_address,
+                OSMOSIS_EIP_BASE_FEE_PATH,
+                QueryEipBaseFeeRequest {}.encode_to_vec(),
+            )
+            .await?;
+
+            let response = QueryEipBaseFeeResponse::decode(value.as_slice())
+                .map_err(|e| Error::protobuf_decode("QueryEipBaseFeeResponse".to_string(), e))?;
+
+            let base_fee = parse_sdk_dec(&response.base_fee)?;
+
+            // The base fee is in the base denomination of the chain, and the fees paid in
+            // any other denomination are converted at its spot price by the module
+            if query_osmosis_base_denom(rpc_client, rpc_address).await? == denom {
+                Ok(base_fee)
+            } else {
+                let spot_price = query_osmosis_spot_price(rpc_client, rpc_address, denom).await?;
+                Ok(base_fee / spot_price)
+            }
+        }
+    }
+}
+
+async fn query_osmosis_base_denom(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+) -> Result<String, Error> {
+    let value = grpc_abci_query(
+        rpc_client,
+        rpc_address,
+        OSMOSIS_BASE_DENOM_PATH,
+        QueryBaseDenomRequest {}.encode_to_vec(),
+    )
+    .await?;
+
+    let response = QueryBaseDenomResponse::decode(value.as_slice())
+        .map_err(|e| Error::protobuf_decode("QueryBaseDenomResponse".to_string(), e))?;
+
+    Ok(response.base_denom)
+}
+
+async fn query_osmosis_spot_price(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    denom: &str,
+) -> Result<f64, Error> {
+    let request = QueryDenomSpotPriceRequest {
+        denom: denom.to_string(),
+    };
+
+    let value = grpc_abci_query(
+        rpc_client,
+        rpc_address,
+        OSMOSIS_DENOM_SPOT_PRICE_PATH,
+        request.encode_to_vec(),
+    )
+    .await?;
+
+    let response = QueryDenomSpotPriceResponse::decode(value.as_slice())
+        .map_err(|e| Error::protobuf_decode("QueryDenomSpotPriceResponse".to_string(), e))?;
+
+    parse_sdk_dec(&response.spot_price)
+}
+
+/// Parse a `Dec` of the Cosmos SDK, which is encoded in protobuf as the integer of its
+/// value scaled by 10^18, but may also be written with its decimal point.
+fn parse_sdk_dec(dec: &str) -> Result<f64, Error> {
+    let invalid = || Error::invalid_gas_price(dec.to_string());
+
+    let value = if dec.contains('.') {
+        dec.parse::<f64>().map_err(|_| invalid())?
+    } else {
+        dec.parse::<f64>().map_err(|_| invalid())? / 10f64.powi(SDK_DEC_PRECISION)
+    };
+
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdk_decimals() {
+        let close =
+            |dec: &str, expected: f64| (parse_sdk_dec(dec).unwrap() - expected).abs() < 1e-12;
+
+        assert!(close("25000000000000000", 0.025));
+        assert!(close("0.0025", 0.0025));
+        assert!(close("1000000000000000000", 1.0));
+
+        assert!(parse_sdk_dec("").is_err());
+        assert!(parse_sdk_dec("0").is_err());
+        assert!(parse_sdk_dec("-1.5").is_err());
+    }
+}
@@ -0,0 +1,454 @@
+//! Listing of the packet commitments and acknowledgements of a channel page by page,
+//! with checkpoints which let an interrupted listing resume from its last page.
+//!
+//! After each page, the entries listed so far are saved along with the `next_key` of
+//! the page and the height the listing is made at, to which the next pages are pinned.
+//! A listing which fails, or whose process is stopped, thus resumes where it left off
+//! on its next attempt, instead of fetching again the hundreds of thousands of entries
+//! of an old channel from the first page. The checkpoint is removed once the last page
+//! is fetched.
+//!
+//! A listing holds the lock file of its checkpoint while it runs, so that a listing
+//! of the same channel made meanwhile, by another worker or another process, neither
+//! resumes from a checkpoint it did not save nor overwrites it: such a listing is made
+//! without checkpoint instead.
+//!
+//! The checkpoints are best effort: a checkpoint which cannot be saved, which is
+//! older than [`MAX_CHECKPOINT_AGE`], or whose height cannot be queried anymore
+//! only means that the listing starts over from the first page.
+
+use core::time::Duration;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flex_error::{define_error, TraceError};
+use serde_derive::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::Height;
+
+use crate::util::state_file;
+
+/// Location of the checkpoint files, relative to the home directory.
+pub const DEFAULT_CHECKPOINT_DIR: &str = ".hermes/cache/pagination";
+
+/// How long a checkpoint is resumed from after it is saved. A resumed listing is made
+/// at the height of its checkpoint, and thus misses the entries added since, so past
+/// that the listing starts over from the first page. A lock file which was not
+/// refreshed for as long is left over by a listing which was stopped, and is taken over.
+pub const MAX_CHECKPOINT_AGE: Duration = Duration::from_secs(10 * 60);
+
+define_error! {
+    Error {
+        Io
+            { path: PathBuf }
+            [ TraceError<std::io::Error> ]
+            |e| { format!("I/O error on pagination checkpoint file {}", e.path.display()) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode the pagination checkpoint" },
+
+        Decode
+            { path: PathBuf }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode the pagination checkpoint file {}", e.path.display()) },
+
+        Corrupted
+            { path: PathBuf, reason: String }
+            |e| { format!("pagination checkpoint file {} is corrupted: {}", e.path.display(), e.reason) },
+    }
+}
+
+/// A page of a listing, as returned by the chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page {
+    pub entries: Vec<u64>,
+    /// The key of the next page, empty on the last page
+    pub next_key: Vec<u8>,
+    /// The height the page was listed at
+    pub height: Height,
+}
+
+/// The progress of a listing, saved after each page.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Checkpoint {
+    height: Height,
+    next_key: Vec<u8>,
+    entries: Vec<u64>,
+    /// When the checkpoint was saved, in seconds since the Unix epoch
+    saved_at: u64,
+}
+
+/// The file the checkpoints of a listing are saved to.
+#[derive(Clone, Debug)]
+pub struct CheckpointFile {
+    /// `None` if the home directory is unknown, in which case no checkpoint is saved
+    path: Option<PathBuf>,
+}
+
+impl CheckpointFile {
+    /// The checkpoint file of the given listing of the given channel, in [`DEFAULT_CHECKPOINT_DIR`].
+    pub fn for_channel(
+        chain_id: &ChainId,
+        listing: &str,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Self {
+        let path = dirs_next::home_dir().map(|home| {
+            home.join(DEFAULT_CHECKPOINT_DIR)
+                .join(chain_id.as_str())
+                .join(format!("{}-{}-{}.json", listing, port_id, channel_id))
+        });
+
+        Self { path }
+    }
+
+    /// The checkpoint file at the given path.
+    pub fn at(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    /// The checkpoint to resume from, if there is one recent enough.
+    fn load(&self) -> Option<Checkpoint> {
+        let path = self.path.as_ref()?;
+
+        match load(path) {
+            Ok(Some(checkpoint))
+                if now().saturating_sub(checkpoint.saved_at) <= MAX_CHECKPOINT_AGE.as_secs() =>
+            {
+                Some(checkpoint)
+            }
+            Ok(Some(_)) => {
+                debug!(
+                    "ignoring the outdated pagination checkpoint {}",
+                    path.display()
+                );
+                self.remove();
+                None
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("ignoring the pagination checkpoint: {}", e);
+
+                if let ErrorDetail::Corrupted(_) | ErrorDetail::Decode(_) = e.detail() {
+                    let _ = state_file::move_aside(path);
+                }
+
+                None
+            }
+        }
+    }
+
+    fn save(&self, checkpoint: &Checkpoint) {
+        if let Some(path) = &self.path {
+            if let Err(e) = save(path, checkpoint) {
+                warn!("failed to save the pagination checkpoint: {}", e);
+            }
+        }
+    }
+
+    fn remove(&self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Take the lock of the checkpoint, unless another listing holds it.
+    fn lock(&self) -> Option<CheckpointLock> {
+        let path = self.path.as_ref()?.with_extension("lock");
+
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        for _ in 0..2 {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => {
+                    let lock = CheckpointLock { path };
+                    lock.refresh();
+                    return Some(lock);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if !is_stale(&path) {
+                        return None;
+                    }
+
+                    debug!("taking over the stale pagination lock {}", path.display());
+                    let _ = fs::remove_file(&path);
+                }
+                Err(e) => {
+                    // Without lock, the listing is made without checkpoint
+                    warn!(
+                        "failed to lock the pagination checkpoint {}: {}",
+                        path.display(),
+                        e
+                    );
+                    return None;
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// The lock of a checkpoint file held by a listing, released when dropped.
+struct CheckpointLock {
+    path: PathBuf,
+}
+
+impl CheckpointLock {
+    /// Mark the lock as still held, so that it is not taken over.
+    fn refresh(&self) {
+        let _ = fs::write(&self.path, std::process::id().to_string());
+    }
+}
+
+impl Drop for CheckpointLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Whether the lock file at the given path was not refreshed for [`MAX_CHECKPOINT_AGE`].
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| {
+            SystemTime::now()
+                .duration_since(modified)
+                .map_or(false, |age| age > MAX_CHECKPOINT_AGE)
+        })
+        .unwrap_or(true)
+}
+
+/// List all the entries of a listing, by querying its pages of the given size with
+/// `query_page`, given the key of the page and the height to list it at, if any.
+///
+/// The listing resumes from the checkpoint saved in the given file by a previous
+/// attempt, if there is one, unless another listing holds the lock of the file, in
+/// which case it is made without checkpoint. Returns the entries along with the
+/// height they were listed at.
+pub fn paginate<E: core::fmt::Display>(
+    checkpoint_file: &CheckpointFile,
+    page_size: u64,
+    mut query_page: impl FnMut(Vec<u8>, u64, Option<Height>) -> Result<Page, E>,
+) -> Result<(Vec<u64>, Height), E> {
+    let lock = checkpoint_file.lock();
+
+    let unlocked = CheckpointFile { path: None };
+    let checkpoint_file = match (&lock, &checkpoint_file.path) {
+        (None, Some(path)) => {
+            debug!(
+                "the listing of {} is already in progress, listing without checkpoint",
+                path.display()
+            );
+            &unlocked
+        }
+        _ => checkpoint_file,
+    };
+
+    let checkpoint = checkpoint_file.load();
+    let mut resuming = checkpoint.is_some();
+
+    let (mut entries, mut key, mut height) = match checkpoint {
+        Some(checkpoint) => {
+            info!(
+                "resuming the listing of {} entries at height {} from its checkpoint",
+                checkpoint.entries.len(),
+                checkpoint.height
+            );
+
+            (
+                checkpoint.entries,
+                checkpoint.next_key,
+                Some(checkpoint.height),
+            )
+        }
+        None => (Vec::new(), Vec::new(), None),
+    };
+
+    loop {
+        let page = match query_page(key.clone(), page_size, height) {
+            Ok(page) => page,
+            Err(e) if resuming => {
+                // The height of the checkpoint may have been pruned meanwhile
+                warn!(
+                    "failed to resume the listing from its checkpoint, starting over: {}",
+                    e
+                );
+
+                checkpoint_file.remove();
+                resuming = false;
+
+                entries.clear();
+                key.clear();
+                height = None;
+
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        resuming = false;
+
+        let listed_at = *height.get_or_insert(page.height);
+        entries.extend(page.entries);
+
+        if page.next_key.is_empty() {
+            checkpoint_file.remove();
+
+            return Ok((entries, listed_at));
+        }
+
+        checkpoint_file.save(&Checkpoint {
+            height: listed_at,
+            next_key: page.next_key.clone(),
+            entries: entries.clone(),
+            saved_at: now(),
+        });
+
+        if let Some(lock) = &lock {
+            lock.refresh();
+        }
+
+        key = page.next_key;
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn load(path: &Path) -> Result<Option<Checkpoint>, Error> {
+    let file = match fs::read(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::io(path.to_path_buf(), e)),
+    };
+
+    let json =
+        state_file::unseal(&file).map_err(|reason| Error::corrupted(path.to_path_buf(), reason))?;
+
+    serde_json::from_slice(json).map_err(|e| Error::decode(path.to_path_buf(), e))
+}
+
+fn save(path: &Path, checkpoint: &Checkpoint) -> Result<(), Error> {
+    let json = serde_json::to_vec(checkpoint).map_err(Error::encode)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io(path.to_path_buf(), e))?;
+    }
+
+    // Write to a temporary file first, so that an interruption
+    // never leaves a partially written checkpoint behind.
+    let tmp_path = path.with_extension(format!("tmp-{}", std::process::id()));
+    fs::write(&tmp_path, state_file::seal(&json)).map_err(|e| Error::io(tmp_path.clone(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupted_listing_resumes_from_its_last_page() {
+        let path = std::env::temp_dir()
+            .join(format!("hermes-pagination-{}", std::process::id()))
+            .join("packet_commitments-transfer-channel-0.json");
+
+        let _ = fs::remove_file(&path);
+        let checkpoint_file = CheckpointFile::at(path.clone());
+
+        let height = Height::new(0, 42).unwrap();
+
+        // Pages of two entries, whose key is the first entry of the page
+        let list = |key: &[u8], limit: u64| {
+            let start = key.first().copied().unwrap_or(1);
+            let end = (start + limit as u8).min(8);
+
+            Page {
+                entries: (start..end).map(u64::from).collect(),
+                next_key: if end < 8 { vec![end] } else { Vec::new() },
+                height,
+            }
+        };
+
+        let mut queried = Vec::new();
+        let result = paginate(&checkpoint_file, 2, |key, limit, _| {
+            queried.push(key.clone());
+
+            if key == vec![5] {
+                Err("timed out")
+            } else {
+                Ok(list(&key, limit))
+            }
+        });
+
+        assert_eq!(result, Err("timed out"));
+        assert_eq!(queried, vec![vec![], vec![3], vec![5]]);
+
+        let mut queried = Vec::new();
+        let result = paginate(&checkpoint_file, 2, |key, limit, at| {
+            assert_eq!(at, Some(height));
+            queried.push(key.clone());
+
+            Ok::<_, &str>(list(&key, limit))
+        });
+
+        assert_eq!(result, Ok(((1..8).collect(), height)));
+        assert_eq!(queried, vec![vec![5], vec![7]]);
+
+        // The checkpoint is removed once the listing completes
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn concurrent_listing_is_made_without_checkpoint() {
+        let path = std::env::temp_dir()
+            .join(format!("hermes-pagination-lock-{}", std::process::id()))
+            .join("packet_commitments-transfer-channel-0.json");
+
+        let _ = fs::remove_file(&path);
+        let checkpoint_file = CheckpointFile::at(path.clone());
+
+        let height = Height::new(0, 42).unwrap();
+        let lock = checkpoint_file
+            .lock()
+            .expect("the checkpoint is not locked");
+
+        let result = paginate(&checkpoint_file, 2, |key, _, _| {
+            if key.is_empty() {
+                Ok(Page {
+                    entries: vec![1, 2],
+                    next_key: vec![3],
+                    height,
+                })
+            } else {
+                Err("timed out")
+            }
+        });
+
+        // The interrupted listing saved no checkpoint over the one of the lock holder
+        assert_eq!(result, Err("timed out"));
+        assert!(!path.exists());
+
+        drop(lock);
+        assert!(checkpoint_file.lock().is_some());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
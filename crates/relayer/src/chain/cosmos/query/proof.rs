@@ -0,0 +1,46 @@
+//! Consistency check of the proofs returned by the full node along with the results of
+//! the queries, so that a buggy or inconsistent node, e.g. behind a load balancer which
+//! spreads the queries over nodes at different heights, does not get the relayer to
+//! submit messages with proofs which the counterparty chain would reject after paying
+//! for gas.
+//!
+//! This is not a verification of the proofs: the app hash they are checked against is
+//! reported by the same node, without being verified by the light client, so a malicious
+//! node can forge both the results and the app hash.
+
+use core::time::Duration;
+use std::time::Instant;
+
+use tendermint::block::Height;
+use tendermint_rpc::{Client, HttpClient, Url};
+
+use crate::error::Error;
+
+/// The fragment of the error returned by Tendermint when the queried block is not committed yet.
+const FUTURE_HEIGHT_ERROR: &str = "must be less than or equal to the current blockchain height";
+
+/// Fetch the app hash of the header at the given height, ie. the root of the application
+/// state at the previous height, against which the proofs of the queries made at that
+/// height are checked. The header is not verified by the light client. Waits up to
+/// `max_block_time` for the block to be committed, failing right away on any other error.
+pub async fn fetch_app_hash(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    height: Height,
+    max_block_time: Duration,
+) -> Result<Vec<u8>, Error> {
+    let start = Instant::now();
+
+    loop {
+        match rpc_client.commit(height).await {
+            Ok(response) => return Ok(response.signed_header.header.app_hash.as_ref().to_vec()),
+            Err(e)
+                if e.to_string().contains(FUTURE_HEIGHT_ERROR)
+                    && start.elapsed() < max_block_time =>
+            {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Err(e) => return Err(Error::rpc(rpc_address.clone(), e)),
+        }
+    }
+}
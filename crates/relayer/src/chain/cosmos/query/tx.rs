@@ -8,13 +8,16 @@ use tendermint::abci::Event;
 use tendermint_rpc::endpoint::tx::Response as TxResponse;
 use tendermint_rpc::{Client, HttpClient, Order, Url};
 
-use crate::chain::cosmos::query::{header_query, packet_query, tx_hash_query};
+use crate::chain::cosmos::query::block_results::{query_block_results, BlockResultsLayout};
+use crate::chain::cosmos::query::{header_query, packet_query, packet_range_query, tx_hash_query};
 use crate::chain::cosmos::types::events;
 use crate::chain::requests::{
-    QueryClientEventRequest, QueryHeight, QueryPacketEventDataRequest, QueryTxHash, QueryTxRequest,
+    QueryClientEventRequest, QueryHeight, QueryHeightRange, QueryPacketEventDataRequest,
+    QueryTxHash, QueryTxRequest,
 };
 use crate::error::Error;
 use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
+use crate::stats;
 
 /// This function queries transactions for events matching certain criteria.
 /// 1. Client Update request - returns a vector with at most one update client event
@@ -27,8 +30,11 @@ use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
 ///    Therefore, for packets we perform one tx_search for each sequence.
 ///    Alternatively, a single query for all packets could be performed but it would return all
 ///    packets ever sent.
+///    When the request is bounded to a range of heights, the transactions of the channel within
+///    the range are scanned instead, whatever the sequences of their packets.
 pub async fn query_txs(
     chain_id: &ChainId,
+    block_results_layout: BlockResultsLayout,
     rpc_client: &HttpClient,
     rpc_address: &Url,
     request: QueryTxRequest,
@@ -40,6 +46,17 @@ pub async fn query_txs(
         QueryTxRequest::Packet(request) => {
             crate::time!("query_txs: query packet events");
 
+            if let Some(range) = request.height_range {
+                return query_packet_txs_in_range(
+                    chain_id,
+                    rpc_client,
+                    rpc_address,
+                    &request,
+                    range,
+                )
+                .await;
+            }
+
             let mut result: Vec<IbcEventWithHeight> = vec![];
 
             let tm_height = match request.height {
@@ -50,24 +67,18 @@ pub async fn query_txs(
             };
             let height = Height::new(chain_id.version(), u64::from(tm_height))
                 .map_err(|_| Error::invalid_height_no_source())?;
-            let exact_block_results = rpc_client
-                .block_results(tm_height)
-                .await
-                .map_err(|e| Error::rpc(rpc_address.clone(), e))?
-                .txs_results;
-
-            if let Some(txs) = exact_block_results {
-                for tx in txs.iter() {
-                    let tx_copy = tx.clone();
-                    result.append(
-                        &mut tx_copy
-                            .events
-                            .into_iter()
-                            .filter_map(|e| filter_matching_event(e, &request, &request.sequences))
-                            .map(|e| IbcEventWithHeight::new(e, height))
-                            .collect(),
-                    )
-                }
+            let exact_block_results =
+                query_block_results(block_results_layout, rpc_client, rpc_address, tm_height)
+                    .await?;
+
+            for tx_events in exact_block_results.txs_events {
+                result.append(
+                    &mut tx_events
+                        .into_iter()
+                        .filter_map(|e| filter_matching_event(e, &request, &request.sequences))
+                        .map(|e| IbcEventWithHeight::new(e, height))
+                        .collect(),
+                )
             }
 
             // Call to /block_results doesn't get SendPacket events, so an additional tx_search is required.
@@ -160,10 +171,75 @@ pub async fn query_txs(
                 Ok(vec![])
             } else {
                 let tx = response.txs.remove(0);
-                Ok(all_ibc_events_from_tx_search_response(chain_id, tx))
+                all_ibc_events_from_tx_search_response(chain_id, tx)
+            }
+        }
+    }
+}
+
+/// The number of transactions per page when scanning a range of heights.
+const RANGE_SCAN_PAGE_SIZE: u8 = 100;
+
+/// Scan the transactions of the channel of the given request within the given range of
+/// heights, page by page, until the events of all the sequences of the request are found.
+async fn query_packet_txs_in_range(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: &QueryPacketEventDataRequest,
+    range: QueryHeightRange,
+) -> Result<Vec<IbcEventWithHeight>, Error> {
+    crate::time!("query_txs: scan packet events in range");
+
+    let mut result = vec![];
+    let mut remaining = request.sequences.clone();
+    let mut page = 1;
+
+    while !remaining.is_empty() {
+        let response = rpc_client
+            .tx_search(
+                packet_range_query(request, range),
+                false,
+                page,
+                RANGE_SCAN_PAGE_SIZE,
+                Order::Ascending,
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+        let scanned = response.txs.len();
+
+        for tx in response.txs {
+            let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            if let QueryHeight::Specific(query_height) = request.height {
+                if height > query_height {
+                    continue;
+                }
+            }
+
+            for event in tx.tx_result.events {
+                if let Some(event) = filter_matching_event(event, request, &remaining) {
+                    if let Some(packet) = event.packet() {
+                        remaining.retain(|seq| *seq != packet.sequence);
+                    }
+
+                    result.push(IbcEventWithHeight::new(event, height));
+                }
             }
         }
+
+        let scanned_total = (page as usize - 1) * RANGE_SCAN_PAGE_SIZE as usize + scanned;
+
+        if scanned == 0 || scanned_total >= response.total_count as usize {
+            break;
+        }
+
+        page += 1;
     }
+
+    Ok(result)
 }
 
 // Extracts from the Tx the update client event for the requested client and height.
@@ -174,7 +250,7 @@ pub async fn query_txs(
 // for client Y at consensus height H'. This is the reason the code iterates all event fields in the
 // returned Tx to retrieve the relevant ones.
 // Returns `None` if no matching event was found.
-fn update_client_from_tx_search_response(
+pub fn update_client_from_tx_search_response(
     chain_id: &ChainId,
     request: &QueryClientEventRequest,
     response: TxResponse,
@@ -212,7 +288,7 @@ fn update_client_from_tx_search_response(
 // was committed in some Tx along with the packet with sequence 4, the response
 // will include both packets. For this reason, we iterate all packets in the Tx,
 // searching for those that match (which must be a single one).
-fn packet_from_tx_search_response(
+pub fn packet_from_tx_search_response(
     chain_id: &ChainId,
     request: &QueryPacketEventDataRequest,
     seq: Sequence,
@@ -291,23 +367,29 @@ pub async fn query_tx_response(
     Ok(response.txs.into_iter().next())
 }
 
-fn all_ibc_events_from_tx_search_response(
+pub fn all_ibc_events_from_tx_search_response(
     chain_id: &ChainId,
     response: TxResponse,
-) -> Vec<IbcEventWithHeight> {
-    let height = ICSHeight::new(chain_id.version(), u64::from(response.height)).unwrap();
+) -> Result<Vec<IbcEventWithHeight>, Error> {
+    let height = ICSHeight::new(chain_id.version(), u64::from(response.height))
+        .map_err(|_| Error::invalid_height_no_source())?;
     let deliver_tx_result = response.tx_result;
 
+    stats::note_gas_used(&response.hash, u64::from(deliver_tx_result.gas_used));
+
     if deliver_tx_result.code.is_err() {
         // We can only return a single ChainError here because at this point
         // we have lost information about how many messages were in the transaction
-        vec![IbcEventWithHeight::new(
+        Ok(vec![IbcEventWithHeight::new(
             IbcEvent::ChainError(format!(
-                "deliver_tx for {} reports error: code={:?}, log={:?}",
-                response.hash, deliver_tx_result.code, deliver_tx_result.log
+                "deliver_tx for {} reports error: codespace={}, code={:?}, log={:?}",
+                response.hash,
+                deliver_tx_result.codespace,
+                deliver_tx_result.code,
+                deliver_tx_result.log
             )),
             height,
-        )]
+        )])
     } else {
         let result = deliver_tx_result
             .events
@@ -315,6 +397,6 @@ fn all_ibc_events_from_tx_search_response(
             .flat_map(|event| events::from_tx_response_event(height, event).into_iter())
             .collect::<Vec<_>>();
 
-        result
+        Ok(result)
     }
 }
@@ -0,0 +1,35 @@
+use http::uri::Uri;
+
+use ibc_proto::ibc::lightclients::wasm::v1::query_client::QueryClient;
+
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
+use crate::chain::requests::QueryWasmChecksumsRequest;
+use crate::error::Error;
+
+/// Uses the GRPC client to retrieve the checksums of the Wasm contracts
+/// stored for the light clients of the chain.
+pub async fn query_wasm_checksums(
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    request: QueryWasmChecksumsRequest,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let mut client = grpc::connect(grpc_address, grpc_settings)
+        .await
+        .map(QueryClient::new)
+        .map_err(Error::grpc_transport)?;
+
+    let request = tonic::Request::new(request.into());
+
+    let response = client
+        .checksums(request)
+        .await
+        .map_err(Error::grpc_status)?
+        .into_inner();
+
+    // The checksums which are not hex-encoded cannot be the ones of a contract
+    Ok(response
+        .checksums
+        .iter()
+        .filter_map(|checksum| hex::decode(checksum).ok())
+        .collect())
+}
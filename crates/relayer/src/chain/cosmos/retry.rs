@@ -1,18 +1,22 @@
 use core::time::Duration;
 use std::thread;
+use std::time::Instant;
 
+use itertools::Itertools;
 use tracing::{debug, error, instrument, warn};
 
 use ibc_proto::google::protobuf::Any;
 use tendermint::abci::Code;
 use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 
-use crate::chain::cosmos::query::account::refresh_account;
-use crate::chain::cosmos::tx::estimate_fee_and_send_tx;
-use crate::chain::cosmos::types::account::Account;
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::query::account::{fetch_fee_payer_account, refresh_account};
+use crate::chain::cosmos::query::status::query_status;
+use crate::chain::cosmos::tx::{estimate_fee_and_send_bundle, estimate_fee_and_send_tx};
+use crate::chain::cosmos::types::account::{Account, AccountSequence};
 use crate::chain::cosmos::types::config::TxConfig;
 use crate::config::types::Memo;
-use crate::error::Error;
+use crate::error::{parse_sequences_in_mismatch_error_message, Error};
 use crate::keyring::KeyEntry;
 use crate::sdk_error::sdk_error_from_tx_sync_error_code;
 use crate::{telemetry, time};
@@ -24,6 +28,10 @@ const ACCOUNT_SEQUENCE_RETRY_DELAY: u64 = 300;
 // https://github.com/cosmos/cosmos-sdk/blob/v0.44.0/types/errors/errors.go#L115-L117
 const INCORRECT_ACCOUNT_SEQUENCE_ERR: u32 = 32;
 
+/// How many times at most a transaction is signed again with a refreshed account
+/// sequence number, when it is rejected because of a mismatched sequence number.
+const MAX_ACCOUNT_SEQUENCE_RETRIES: u32 = 3;
+
 /// Try to `send_tx` and retry on account sequence error with re-cached account s.n.
 /// An account sequence error can occur if the account sequence that
 /// the relayer caches becomes outdated.
@@ -33,8 +41,12 @@ const INCORRECT_ACCOUNT_SEQUENCE_ERR: u32 = 32;
 ///   2. as an Ok variant, with an Code::Err response, propagated from
 ///     the `broadcast_tx_sync` step.
 ///
-/// We treat both cases by re-fetching the account sequence number
-/// from the full node and retrying once with the new account s.n.
+/// We treat both cases by signing the transaction again with the account sequence number
+/// expected by the chain, as reported in the error, or re-fetched from the full node if it
+/// is not reported, up to `MAX_ACCOUNT_SEQUENCE_RETRIES` times. The transactions in flight
+/// are then not stalled by a query of the account whenever one of them is dropped from
+/// the mempool. If the sequence number keeps being advanced behind our back, the key is
+/// used by another process, and we fail with a diagnostic instead of retrying.
 #[instrument(
     name = "send_tx_with_account_sequence_retry",
     level = "error",
@@ -72,37 +84,150 @@ async fn do_send_tx_with_account_sequence_retry(
     tx_memo: &Memo,
     messages: Vec<Any>,
 ) -> Result<Response, Error> {
-    match estimate_fee_and_send_tx(config, key_entry, account, tx_memo, &messages).await {
-        // Gas estimation failed with acct. s.n. mismatch at estimate gas step.
-        // It indicates that the account sequence cached by hermes is stale (got < expected).
-        // This can happen when the same account is used by another agent.
-        Err(ref e) if mismatch_account_sequence_number_error_requires_refresh(e) => {
-            warn!(
-                error = %e,
-                "failed to estimate gas because of a mismatched account sequence number, \
-                refreshing account sequence number and retrying once",
+    let mut conflict = SequenceConflict::default();
+    let mut retries = 0;
+
+    // A transaction bid for top-of-block placement is bundled in its bid
+    let tip = top_of_block_tip(config, &messages).await;
+
+    fetch_fee_payer_account(config, false).await?;
+
+    loop {
+        let result = match tip {
+            Some(tip) => {
+                estimate_fee_and_send_bundle(config, key_entry, account, tx_memo, &messages, tip)
+                    .await
+            }
+            None => estimate_fee_and_send_tx(config, key_entry, account, tx_memo, &messages).await,
+        };
+
+        let mismatch = match &result {
+            // Gas estimation failed with acct. s.n. mismatch at estimate gas step.
+            // It indicates that the account sequence cached by hermes is stale (got < expected).
+            // This can happen when the same account is used by another agent.
+            Err(e) => mismatch_account_sequence_number_error_requires_refresh(e),
+            // Gas estimation succeeded but broadcast_tx_sync failed with a retry-able error.
+            Ok(response) => response.code == Code::Err(INCORRECT_ACCOUNT_SEQUENCE_ERR),
+        };
+
+        if !mismatch {
+            return handle_send_result(config, result, account);
+        }
+
+        if retries == MAX_ACCOUNT_SEQUENCE_RETRIES {
+            error!(
+                "transaction was still rejected because of a mismatched account sequence number \
+                after {} retries, giving up",
+                retries
             );
 
-            refresh_account_and_retry_send_tx_with_account_sequence(
-                config, key_entry, account, tx_memo, messages,
-            )
-            .await
+            return handle_send_result(config, result, account);
         }
 
-        // Gas estimation succeeded but broadcast_tx_sync failed with a retry-able error.
-        Ok(ref response) if response.code == Code::Err(INCORRECT_ACCOUNT_SEQUENCE_ERR) => {
-            warn!(
-                ?response,
-                "failed to broadcast tx because of a mismatched account sequence number, \
-                refreshing account sequence number and retrying once"
+        retries += 1;
+
+        warn!(
+            retry = retries,
+            max_retries = MAX_ACCOUNT_SEQUENCE_RETRIES,
+            "failed to send tx because of a mismatched account sequence number, \
+            refreshing account sequence number and retrying",
+        );
+
+        let used = account.sequence;
+
+        // The sequence number reported is the one of the fee payer if it was mismatched
+        let expected = config
+            .fee_payer
+            .is_none()
+            .then(|| expected_account_sequence(&result))
+            .flatten();
+
+        match expected {
+            // Sign the transaction again with the sequence number expected by the chain,
+            // e.g. after transactions in flight were dropped from the mempool
+            Some(expected) => {
+                debug!(
+                    account.sequence.used = %used,
+                    account.sequence.expected = %expected,
+                    "using the account sequence number expected by the chain"
+                );
+
+                // The chain checks the transactions against its mempool, so the ones in flight
+                // from the sequence number it expects on were dropped from it
+                if expected < used {
+                    let dropped = config.in_flight.dropped_from(&account.address, expected);
+
+                    if !dropped.is_empty() {
+                        warn!(
+                            "{} transactions in flight were dropped from the mempool, \
+                            their messages are to be relayed again: {}",
+                            dropped.len(),
+                            dropped.iter().join(", ")
+                        );
+                    }
+                }
+
+                account.sequence = expected;
+            }
+            None => {
+                // Re-fetch the account s.n.
+                with_deadline(
+                    &config.chain_id,
+                    "account query",
+                    config.query_timeout,
+                    refresh_account(
+                        &config.grpc_address,
+                        &config.grpc_settings,
+                        &key_entry.account,
+                        account,
+                    ),
+                )
+                .await??;
+
+                // The transaction may have been rejected for the sequence number of the fee payer
+                fetch_fee_payer_account(config, true).await?;
+
+                // Retry after delay.
+                thread::sleep(Duration::from_millis(ACCOUNT_SEQUENCE_RETRY_DELAY));
+            }
+        }
+
+        if conflict.record(used, account.sequence) {
+            error!(
+                account.sequence.used = %used,
+                account.sequence.chain = %account.sequence,
+                "the account sequence number keeps being advanced by transactions which \
+                were not submitted by this relayer, is the key used by another process?"
             );
 
-            refresh_account_and_retry_send_tx_with_account_sequence(
-                config, key_entry, account, tx_memo, messages,
-            )
-            .await
+            return Err(Error::account_sequence_conflict(
+                config.chain_id.clone(),
+                key_entry.account.clone(),
+            ));
         }
+    }
+}
 
+/// The account sequence number expected by the chain, as reported along with the rejection
+/// of a transaction because of a mismatched account sequence number.
+fn expected_account_sequence(result: &Result<Response, Error>) -> Option<AccountSequence> {
+    let message = match result {
+        Err(e) => e.to_string(),
+        Ok(response) => response.log.to_string(),
+    };
+
+    parse_sequences_in_mismatch_error_message(&message)
+        .map(|(expected, _)| AccountSequence::new(expected))
+}
+
+/// Increase the cached account s.n., and the one of the fee payer if any, if the
+/// transaction was broadcast successfully, and log the unrecoverable errors.
+fn handle_send_result(
+    config: &TxConfig,
+    result: Result<Response, Error>,
+    account: &mut Account,
+) -> Result<Response, Error> {
+    match result {
         // Gas estimation succeeded and broadcast_tx_sync was either successful or has failed with
         // an unrecoverable error.
         Ok(response) => {
@@ -113,9 +238,19 @@ async fn do_send_tx_with_account_sequence_retry(
                 Code::Ok => {
                     let old_account_sequence = account.sequence;
 
+                    config.in_flight.broadcast(
+                        &account.address,
+                        old_account_sequence,
+                        response.hash,
+                    );
+
                     // Increase account s.n.
                     account.sequence.increment_mut();
 
+                    if let Some(fee_payer) = &config.fee_payer {
+                        fee_payer.increment_sequence();
+                    }
+
                     debug!(
                         ?response,
                         account.sequence.old = %old_account_sequence,
@@ -151,18 +286,71 @@ async fn do_send_tx_with_account_sequence_retry(
     }
 }
 
-async fn refresh_account_and_retry_send_tx_with_account_sequence(
-    config: &TxConfig,
-    key_entry: &KeyEntry,
-    account: &mut Account,
-    tx_memo: &Memo,
-    messages: Vec<Any>,
-) -> Result<Response, Error> {
-    // Re-fetch the account s.n.
-    refresh_account(&config.grpc_address, &key_entry.account, account).await?;
-    // Retry after delay.
-    thread::sleep(Duration::from_millis(ACCOUNT_SEQUENCE_RETRY_DELAY));
-    estimate_fee_and_send_tx(config, key_entry, account, tx_memo, &messages).await
+/// Detects the account sequence number being advanced by another process signing
+/// transactions with the same key. The cached sequence number only lags behind the
+/// one of the chain if transactions were signed elsewhere, as it is increased for each
+/// transaction broadcast by the relayer. This can happen once, e.g. if the key was used
+/// before the relayer started, but happening again while retrying means that another
+/// process is competing for the sequence numbers.
+#[derive(Debug, Default)]
+struct SequenceConflict {
+    advanced: u32,
+}
+
+impl SequenceConflict {
+    /// Record the sequence number used for a rejected transaction and the one fetched
+    /// from the chain afterwards. Returns whether another process uses the key.
+    fn record(&mut self, used: AccountSequence, fetched: AccountSequence) -> bool {
+        if fetched > used {
+            self.advanced += 1;
+        }
+
+        self.advanced >= 2
+    }
+}
+
+/// The tip to bid for the top-of-block placement of a transaction with the given messages,
+/// within the budget of the chain. The timeouts of the packets are checked against the
+/// latest block of the chain, so that no tip is bid if its status cannot be queried.
+async fn top_of_block_tip(config: &TxConfig, messages: &[Any]) -> Option<u64> {
+    if !config.top_of_block.enabled {
+        return None;
+    }
+
+    let status = with_deadline(
+        &config.chain_id,
+        "status query",
+        config.query_timeout,
+        query_status(&config.chain_id, &config.rpc_client, &config.rpc_address),
+    )
+    .await
+    .and_then(|status| status);
+
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => {
+            warn!(
+                "failed to query the chain status, \
+                not bidding for the top-of-block placement of tx: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    let tip = config.top_of_block.tip_for(messages, &status)?;
+    let granted = config
+        .tip_budget
+        .spend(&config.top_of_block, tip, Instant::now());
+
+    if granted.is_none() {
+        debug!(
+            %tip,
+            "top-of-block tips budget exhausted for the current period, not bidding for tx"
+        );
+    }
+
+    granted
 }
 
 /// Determine whether the given error yielded by `tx_simulate`
@@ -177,3 +365,38 @@ fn mismatch_account_sequence_number_error_requires_refresh(e: &Error) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_sequence_conflict() {
+        let seq = AccountSequence::new;
+        let mut conflict = SequenceConflict::default();
+
+        // The cached sequence number was ahead, e.g. as a transaction was dropped from the mempool
+        assert!(!conflict.record(seq(10), seq(8)));
+
+        // The key was used elsewhere once, e.g. before the relayer started
+        assert!(!conflict.record(seq(8), seq(9)));
+
+        // The key is used elsewhere again while retrying
+        assert!(conflict.record(seq(9), seq(11)));
+    }
+
+    #[test]
+    fn expected_sequence_of_mismatch() {
+        let mismatch = Err(Error::grpc_status(tonic::Status::unknown(
+            "account sequence mismatch, expected 5, got 7: incorrect account sequence",
+        )));
+
+        assert_eq!(
+            expected_account_sequence(&mismatch),
+            Some(AccountSequence::new(5))
+        );
+
+        let other = Err(Error::grpc_status(tonic::Status::unknown("out of gas")));
+        assert_eq!(expected_account_sequence(&other), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Rotation of the keys signing the transactions of a chain among the one of its
+//! `key_name` and the ones of its `key_names`, so that consecutive transactions are
+//! signed by different accounts, whose sequence numbers are tracked independently,
+//! instead of all waiting on the sequence number of a single account.
+//!
+//! The messages are built with the address of the key of `key_name` as their signer,
+//! see [`ChainEndpoint::get_signer`](crate::chain::endpoint::ChainEndpoint::get_signer),
+//! which is replaced by the address of the key signing their transaction. Only the
+//! messages of the IBC core modules are signed in turn by the keys, as the signer of the
+//! other messages, e.g. the sender of a token transfer, is the account they act upon.
+
+use ibc_proto::google::protobuf::Any;
+use prost::encoding::{decode_key, decode_varint, encode_key, encode_varint, WireType};
+use prost::DecodeError;
+
+use crate::config::ChainConfig;
+use crate::error::Error;
+
+const IBC_CORE_TYPE_URL_PREFIX: &str = "/ibc.core.";
+
+#[derive(Clone, Debug)]
+pub struct KeyRotation {
+    /// The names of the keys signing in turn, starting with the one of `key_name`
+    key_names: Vec<String>,
+    next: usize,
+}
+
+impl KeyRotation {
+    pub fn new(config: &ChainConfig) -> Self {
+        let mut key_names = vec![config.key_name.clone()];
+
+        for key_name in &config.key_names {
+            if !key_names.contains(key_name) {
+                key_names.push(key_name.clone());
+            }
+        }
+
+        Self { key_names, next: 0 }
+    }
+
+    /// The name of the key signing the transactions of the given messages, which is the
+    /// next key in turn if they are all messages of the IBC core modules, and the key of
+    /// `key_name` otherwise.
+    pub fn next_key(&mut self, messages: &[Any]) -> &str {
+        if !messages.iter().all(is_rotated) {
+            return &self.key_names[0];
+        }
+
+        let index = self.next;
+        self.next = (self.next + 1) % self.key_names.len();
+
+        &self.key_names[index]
+    }
+}
+
+fn is_rotated(message: &Any) -> bool {
+    message.type_url.starts_with(IBC_CORE_TYPE_URL_PREFIX)
+}
+
+/// Replace the given signer of the given message by the given one, i.e. the top-level
+/// string fields of the message equal to the address of the signer. The nested messages,
+/// e.g. the packet of a `MsgRecvPacket`, are left untouched.
+pub fn replace_signer(message: &Any, signer: &str, new_signer: &str) -> Result<Any, Error> {
+    let decode_error = |e| Error::protobuf_decode(message.type_url.clone(), e);
+
+    let mut buf = message.value.as_slice();
+    let mut value = Vec::with_capacity(message.value.len() + new_signer.len());
+
+    while !buf.is_empty() {
+        let (tag, wire_type) = decode_key(&mut buf).map_err(decode_error)?;
+        encode_key(tag, wire_type, &mut value);
+
+        let len = match wire_type {
+            WireType::Varint => {
+                let varint = decode_varint(&mut buf).map_err(decode_error)?;
+                encode_varint(varint, &mut value);
+                continue;
+            }
+            WireType::SixtyFourBit => 8,
+            WireType::ThirtyTwoBit => 4,
+            WireType::LengthDelimited => decode_varint(&mut buf).map_err(decode_error)? as usize,
+            WireType::StartGroup | WireType::EndGroup => {
+                return Err(decode_error(DecodeError::new("unsupported group field")));
+            }
+        };
+
+        if buf.len() < len {
+            return Err(decode_error(DecodeError::new("buffer underflow")));
+        }
+
+        let (field, rest) = buf.split_at(len);
+        buf = rest;
+
+        if wire_type == WireType::LengthDelimited {
+            let field = if field == signer.as_bytes() {
+                new_signer.as_bytes()
+            } else {
+                field
+            };
+
+            encode_varint(field.len() as u64, &mut value);
+            value.extend_from_slice(field);
+        } else {
+            value.extend_from_slice(field);
+        }
+    }
+
+    Ok(Any {
+        type_url: message.type_url.clone(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_proto::ibc::core::channel::v1::{MsgRecvPacket, Packet};
+    use ibc_proto::ibc::core::client::v1::Height;
+    use prost::Message;
+
+    const SIGNER: &str = "cosmos1primary";
+    const NEW_SIGNER: &str = "cosmos1anotherkeysigning";
+
+    fn recv_packet() -> MsgRecvPacket {
+        MsgRecvPacket {
+            packet: Some(Packet {
+                sequence: 7,
+                source_port: "transfer".to_string(),
+                source_channel: "channel-0".to_string(),
+                destination_port: "transfer".to_string(),
+                destination_channel: "channel-1".to_string(),
+                // The address of the signer within a nested message is left untouched
+                data: SIGNER.as_bytes().to_vec(),
+                timeout_height: Some(Height {
+                    revision_number: 0,
+                    revision_height: 100,
+                }),
+                timeout_timestamp: 0,
+            }),
+            proof_commitment: vec![1, 2, 3],
+            proof_height: Some(Height {
+                revision_number: 0,
+                revision_height: 10,
+            }),
+            signer: SIGNER.to_string(),
+        }
+    }
+
+    #[test]
+    fn replace_message_signer() {
+        let msg = recv_packet();
+        let any = Any {
+            type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+            value: msg.encode_to_vec(),
+        };
+
+        let replaced = replace_signer(&any, SIGNER, NEW_SIGNER).unwrap();
+        let replaced_msg = MsgRecvPacket::decode(replaced.value.as_slice()).unwrap();
+
+        assert_eq!(
+            replaced_msg,
+            MsgRecvPacket {
+                signer: NEW_SIGNER.to_string(),
+                ..msg
+            }
+        );
+
+        let malformed = Any {
+            value: any.value[..any.value.len() - 1].to_vec(),
+            ..any
+        };
+        assert!(replace_signer(&malformed, SIGNER, NEW_SIGNER).is_err());
+    }
+
+    #[test]
+    fn rotate_keys() {
+        let mut rotation = KeyRotation {
+            key_names: vec!["primary".to_string(), "secondary".to_string()],
+            next: 0,
+        };
+
+        let core = [Any {
+            type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+            value: vec![],
+        }];
+
+        let transfer = [Any {
+            type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+            value: vec![],
+        }];
+
+        assert_eq!(rotation.next_key(&core), "primary");
+        assert_eq!(rotation.next_key(&transfer), "primary");
+        assert_eq!(rotation.next_key(&core), "secondary");
+        assert_eq!(rotation.next_key(&core), "primary");
+    }
+}
@@ -2,9 +2,14 @@ use ibc_proto::cosmos::tx::v1beta1::service_client::ServiceClient;
 use ibc_proto::cosmos::tx::v1beta1::{SimulateRequest, SimulateResponse, Tx};
 use tonic::codegen::http::Uri;
 
+use crate::chain::cosmos::grpc::{self, GrpcSettings};
 use crate::error::Error;
 
-pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResponse, Error> {
+pub async fn send_tx_simulate(
+    grpc_address: &Uri,
+    grpc_settings: &GrpcSettings,
+    tx: Tx,
+) -> Result<SimulateResponse, Error> {
     crate::time!("send_tx_simulate");
 
     // The `tx` field of `SimulateRequest` was deprecated in Cosmos SDK 0.43 in favor of `tx_bytes`.
@@ -18,8 +23,9 @@ pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResp
         tx_bytes,     // needed for simulation to go through with Cosmos SDk >= 0.43
     };
 
-    let mut client = ServiceClient::connect(grpc_address.clone())
+    let mut client = grpc::connect(grpc_address, grpc_settings)
         .await
+        .map(ServiceClient::new)
         .map_err(Error::grpc_transport)?;
 
     let request = tonic::Request::new(req);
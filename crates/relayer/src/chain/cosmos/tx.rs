@@ -1,10 +1,18 @@
+use ibc_proto::cosmos::base::v1beta1::Coin;
 use ibc_proto::cosmos::tx::v1beta1::Fee;
 use ibc_proto::google::protobuf::Any;
+use ibc_proto::sdk::auction::v1::MsgAuctionBid;
+use prost::Message;
+use sha2::{Digest, Sha256};
+use tendermint::abci::transaction::Hash;
 use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use tendermint_rpc::{Client, HttpClient, Url};
+use tracing::debug;
 
+use crate::chain::cosmos::deadline::with_deadline;
 use crate::chain::cosmos::encode::sign_and_encode_tx;
 use crate::chain::cosmos::estimate::estimate_tx_fees;
+use crate::chain::cosmos::query::fee_grant::check_fee_grant;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
 use crate::config::types::Memo;
@@ -20,9 +28,79 @@ pub async fn estimate_fee_and_send_tx(
 ) -> Result<Response, Error> {
     let fee = estimate_tx_fees(config, key_entry, account, tx_memo, messages).await?;
 
+    check_fee_grant(config, key_entry, messages, &fee).await?;
+
     send_tx_with_fee(config, key_entry, account, tx_memo, messages, &fee).await
 }
 
+/// The type URL of the bid for the top-of-block placement of a bundle of
+/// transactions, in the auction module of the Skip block SDK.
+const AUCTION_BID_TYPE_URL: &str = "/sdk.auction.v1.MsgAuctionBid";
+
+/// Send the given messages in a transaction bundled in a bid of the given tip
+/// for its placement at the top of the next block.
+///
+/// The bid is signed with the sequence number of the account, and the bundled
+/// transaction with the next one. As the bundled transaction is only included if
+/// the bid wins the auction, the cached account sequence number only accounts for
+/// the bid, and is refreshed on the sequence mismatch of the next transaction
+/// once the bundled transaction is included. The hash of the bundled transaction is returned
+/// in the response in place of the one of the bid, so that the events of the
+/// messages are found once the bundle is committed.
+pub async fn estimate_fee_and_send_bundle(
+    config: &TxConfig,
+    key_entry: &KeyEntry,
+    account: &Account,
+    tx_memo: &Memo,
+    messages: &[Any],
+    tip: u64,
+) -> Result<Response, Error> {
+    let fee = estimate_tx_fees(config, key_entry, account, tx_memo, messages).await?;
+
+    check_fee_grant(config, key_entry, messages, &fee).await?;
+
+    let bundled_account = Account {
+        sequence: account.sequence.increment(),
+        ..account.clone()
+    };
+
+    let bundled_tx =
+        sign_and_encode_tx(config, key_entry, &bundled_account, tx_memo, messages, &fee)?;
+    let bundled_tx_hash = Hash::new(Sha256::digest(&bundled_tx).into());
+
+    let bid = MsgAuctionBid {
+        bidder: key_entry.account.clone(),
+        bid: Some(Coin {
+            denom: config.top_of_block.denom.clone(),
+            amount: tip.to_string(),
+        }),
+        transactions: vec![bundled_tx],
+    };
+
+    let bid_msg = Any {
+        type_url: AUCTION_BID_TYPE_URL.to_string(),
+        value: bid.encode_to_vec(),
+    };
+
+    debug!(
+        %tip,
+        denom = %config.top_of_block.denom,
+        tx_hash = %bundled_tx_hash,
+        "bidding for the top-of-block placement of tx"
+    );
+
+    let bid_fee = estimate_tx_fees(config, key_entry, account, tx_memo, &[bid_msg.clone()]).await?;
+
+    check_fee_grant(config, key_entry, &[bid_msg.clone()], &bid_fee).await?;
+
+    let mut response =
+        send_tx_with_fee(config, key_entry, account, tx_memo, &[bid_msg], &bid_fee).await?;
+
+    response.hash = bundled_tx_hash;
+
+    Ok(response)
+}
+
 async fn send_tx_with_fee(
     config: &TxConfig,
     key_entry: &KeyEntry,
@@ -33,7 +111,13 @@ async fn send_tx_with_fee(
 ) -> Result<Response, Error> {
     let tx_bytes = sign_and_encode_tx(config, key_entry, account, tx_memo, messages, fee)?;
 
-    let response = broadcast_tx_sync(&config.rpc_client, &config.rpc_address, tx_bytes).await?;
+    let response = with_deadline(
+        &config.chain_id,
+        "broadcast_tx_sync",
+        config.broadcast_timeout,
+        broadcast_tx_sync(&config.rpc_client, &config.rpc_address, tx_bytes),
+    )
+    .await??;
 
     Ok(response)
 }
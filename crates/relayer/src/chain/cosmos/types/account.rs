@@ -1,5 +1,12 @@
 use core::fmt::{Display, Error as FmtError, Formatter};
 use ibc_proto::cosmos::auth::v1beta1::BaseAccount;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tendermint::abci::transaction::Hash as TxHash;
+
+/// How many transactions of an account are tracked at most as in flight, and how many
+/// of the ones dropped from the mempool are remembered.
+const MAX_IN_FLIGHT_TXS: usize = 256;
 
 /// Wrapper for account number and sequence number.
 ///
@@ -23,7 +30,7 @@ impl From<BaseAccount> for Account {
 }
 
 /// Newtype for account address
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct AccountAddress(String);
 
 impl AccountAddress {
@@ -93,3 +100,102 @@ impl Display for AccountSequence {
         write!(f, "{}", self.0)
     }
 }
+
+/// The transactions broadcast by the accounts of a chain which are not known to be
+/// committed yet, shared by the clones of the configuration which wait for their commit.
+///
+/// When the chain reports that it expects an account sequence number lower than the one
+/// of a transaction, the transactions in flight from that sequence number on were dropped
+/// from the mempool, and are not waited for anymore.
+#[derive(Clone, Debug, Default)]
+pub struct InFlightTxs(Arc<Mutex<InFlightState>>);
+
+#[derive(Debug, Default)]
+struct InFlightState {
+    txs: HashMap<AccountAddress, BTreeMap<AccountSequence, TxHash>>,
+    dropped: VecDeque<TxHash>,
+}
+
+impl InFlightTxs {
+    /// Record the transaction broadcast by the given account with the given sequence number.
+    pub fn broadcast(&self, address: &AccountAddress, sequence: AccountSequence, hash: TxHash) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let txs = state.txs.entry(address.clone()).or_default();
+
+        txs.insert(sequence, hash);
+
+        // The transactions which were never waited for are forgotten eventually
+        while txs.len() > MAX_IN_FLIGHT_TXS {
+            let oldest = *txs.keys().next().unwrap();
+            txs.remove(&oldest);
+        }
+    }
+
+    /// Forget the transaction with the given hash, which was committed.
+    pub fn committed(&self, hash: &TxHash) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        for txs in state.txs.values_mut() {
+            txs.retain(|_, tx_hash| tx_hash != hash);
+        }
+    }
+
+    /// Record that the transactions of the given account in flight from the given sequence
+    /// number on were dropped from the mempool, and return their hashes.
+    pub fn dropped_from(&self, address: &AccountAddress, sequence: AccountSequence) -> Vec<TxHash> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        let dropped: Vec<TxHash> = match state.txs.get_mut(address) {
+            Some(txs) => txs.split_off(&sequence).into_values().collect(),
+            None => Vec::new(),
+        };
+
+        state.dropped.extend(dropped.iter().copied());
+
+        while state.dropped.len() > MAX_IN_FLIGHT_TXS {
+            state.dropped.pop_front();
+        }
+
+        dropped
+    }
+
+    /// Whether the transaction with the given hash was dropped from the mempool.
+    pub fn is_dropped(&self, hash: &TxHash) -> bool {
+        let state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        state.dropped.contains(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_flight_txs_dropped_from_expected_sequence() {
+        let in_flight = InFlightTxs::default();
+        let address = AccountAddress::new("cosmos1relayer".to_string());
+        let hash = |n: u8| TxHash::new([n; 32]);
+
+        for n in 1..=4 {
+            in_flight.broadcast(&address, AccountSequence::new(n as u64), hash(n));
+        }
+
+        in_flight.committed(&hash(1));
+
+        // The chain expects the sequence number 3 again
+        assert_eq!(
+            in_flight.dropped_from(&address, AccountSequence::new(3)),
+            vec![hash(3), hash(4)]
+        );
+
+        assert!(!in_flight.is_dropped(&hash(2)));
+        assert!(in_flight.is_dropped(&hash(3)));
+        assert!(in_flight.is_dropped(&hash(4)));
+
+        // Only the transaction still in flight is dropped from there
+        assert_eq!(
+            in_flight.dropped_from(&address, AccountSequence::new(1)),
+            vec![hash(2)]
+        );
+    }
+}
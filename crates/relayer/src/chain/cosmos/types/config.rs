@@ -3,11 +3,19 @@ use core::time::Duration;
 use http::Uri;
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc_proto::google::protobuf::Any;
+use std::sync::Arc;
 use tendermint_rpc::{HttpClient, Url};
 
+use crate::chain::cosmos::grpc::GrpcSettings;
+use crate::chain::cosmos::proxy;
+use crate::chain::cosmos::query::fee_grant::FeeAllowanceCache;
+use crate::chain::cosmos::types::account::{Account, AccountNumber, AccountSequence, InFlightTxs};
 use crate::chain::cosmos::types::gas::GasConfig;
+use crate::config::top_of_block::{TipBudget, TopOfBlock};
 use crate::config::{AddressType, ChainConfig};
 use crate::error::Error;
+use crate::keyring::{KeyEntry, LocalSigner, SigningProvider};
+use crate::util::lock::{LockExt, RwArc};
 
 #[derive(Debug, Clone)]
 pub struct TxConfig {
@@ -16,21 +24,75 @@ pub struct TxConfig {
     pub rpc_client: HttpClient,
     pub rpc_address: Url,
     pub grpc_address: Uri,
+    pub grpc_settings: GrpcSettings,
     pub rpc_timeout: Duration,
+    pub query_timeout: Duration,
+    pub broadcast_timeout: Duration,
     pub address_type: AddressType,
     pub extension_options: Vec<Any>,
+    pub top_of_block: TopOfBlock,
+    pub tip_budget: TipBudget,
+    pub fee_payer: Option<FeePayer>,
+    pub signing_provider: Arc<dyn SigningProvider>,
+    pub in_flight: InFlightTxs,
+    pub fee_allowances: FeeAllowanceCache,
+}
+
+/// The account which pays the fees of the transactions in place of the account signing
+/// their messages, and which therefore signs the transactions along with it.
+///
+/// The account of the fee payer is shared by the clones of the configuration, as its
+/// sequence number is advanced by each transaction broadcast.
+#[derive(Debug, Clone)]
+pub struct FeePayer {
+    pub key_entry: KeyEntry,
+    account: RwArc<Option<Account>>,
+}
+
+impl FeePayer {
+    pub fn new(key_entry: KeyEntry) -> Self {
+        Self {
+            key_entry,
+            account: RwArc::new_lock(None),
+        }
+    }
+
+    pub fn account(&self) -> Option<Account> {
+        self.account.acquire_read().clone()
+    }
+
+    pub fn set_account(&self, account: Account) {
+        *self.account.acquire_write() = Some(account);
+    }
+
+    /// The number and sequence number of the account of the fee payer, which are only
+    /// unknown when a transaction is signed to compute its size before it is sent.
+    pub fn number_and_sequence(&self) -> (AccountNumber, AccountSequence) {
+        self.account.acquire_read().as_ref().map_or(
+            (AccountNumber::new(0), AccountSequence::new(0)),
+            |account| (account.number, account.sequence),
+        )
+    }
+
+    /// Increase the sequence number of the account once a transaction is broadcast.
+    pub fn increment_sequence(&self) {
+        if let Some(account) = self.account.acquire_write().as_mut() {
+            account.sequence.increment_mut();
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
     type Error = Error;
 
     fn try_from(config: &'a ChainConfig) -> Result<Self, Error> {
-        let rpc_client = HttpClient::new(config.rpc_addr.clone())
-            .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))?;
+        let rpc_client = proxy::rpc_client(&config.rpc_addr, config.proxy.as_ref())?;
 
         let grpc_address = Uri::from_str(&config.grpc_addr.to_string())
             .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
 
+        let grpc_settings = GrpcSettings::from_config(config)?;
+
         let gas_config = GasConfig::from(config);
 
         let extension_options = config
@@ -45,9 +107,20 @@ impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
             rpc_client,
             rpc_address: config.rpc_addr.clone(),
             grpc_address,
+            grpc_settings,
             rpc_timeout: config.rpc_timeout,
+            query_timeout: config.query_timeout,
+            broadcast_timeout: config.broadcast_timeout,
             address_type: config.address_type.clone(),
             extension_options,
+            top_of_block: config.top_of_block.clone(),
+            tip_budget: TipBudget::default(),
+            // Set from the keyring of the chain, see `CosmosSdkChain::bootstrap`
+            fee_payer: None,
+            // Replaced by a remote signer, see `CosmosSdkChain::bootstrap`
+            signing_provider: Arc::new(LocalSigner),
+            in_flight: InFlightTxs::default(),
+            fee_allowances: FeeAllowanceCache::default(),
         })
     }
 }
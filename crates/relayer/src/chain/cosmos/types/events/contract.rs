@@ -0,0 +1,258 @@
+//! Events of the CosmWasm contracts called by the IBC applications while handling
+//! the packets relayed to a chain, eg. the contracts executed by `ibc-hooks` when
+//! receiving a packet and called back on its acknowledgement or timeout, or the
+//! IBC-enabled contracts bound to the port of the channel.
+//!
+//! These events are not IBC events: they are only logged along with the packet
+//! whose relaying triggered them, and counted in the telemetry.
+
+use tendermint::abci::Event as AbciEvent;
+use tracing::{debug, info};
+
+use ibc::core::ics04_channel::packet::Packet;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::events::IbcEventType;
+
+use crate::event::{
+    acknowledge_packet_try_from_abci_event, receive_packet_try_from_abci_event,
+    timeout_packet_try_from_abci_event,
+};
+
+/// Types of the events emitted by `wasmd` when calling a contract: `execute` for the
+/// contracts executed by `ibc-hooks` on receive, `sudo` for its acknowledgement and
+/// timeout callbacks, and `reply` for the replies to the sub-messages of a contract.
+const CONTRACT_CALL_EVENT_TYPES: &[&str] = &["execute", "sudo", "reply"];
+
+/// Type of the event holding the attributes set by a contract, and prefix of
+/// the types of the custom events emitted by a contract, as in `wasm-<type>`.
+const CONTRACT_EVENT_TYPE: &str = "wasm";
+
+/// Attribute holding the address of the contract in all the events of `wasmd`.
+const CONTRACT_ADDRESS_KEY: &str = "_contract_address";
+
+/// Type of the events starting the events of each message of a transaction.
+const MESSAGE_EVENT_TYPE: &str = "message";
+
+/// An event of a contract called while handling a packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// The type of the event, eg. `sudo`, `wasm` or `wasm-<type>`
+    pub kind: String,
+    /// The address of the contract
+    pub contract: String,
+}
+
+/// The events of the contracts called while handling a packet.
+#[derive(Clone, Debug)]
+pub struct PacketContractEvents {
+    /// The IBC event of the packet, the contracts being called after it
+    pub packet_event: IbcEventType,
+    pub packet: Packet,
+    pub events: Vec<ContractEvent>,
+    /// Whether the acknowledgement of a received packet was written by the
+    /// transaction, or will be written later on by the application
+    pub ack_written: bool,
+}
+
+/// The packets received, acknowledged or timed out in the transaction with the given
+/// events, along with the events of the contracts called while handling them.
+///
+/// The events of the modules handling a message follow the event of the message,
+/// and the events of the applications follow the IBC event of the packet.
+pub fn packet_contract_events(events: &[AbciEvent]) -> Vec<PacketContractEvents> {
+    let mut packets: Vec<PacketContractEvents> = Vec::new();
+
+    // Whether the contract events belong to the last packet, ie. are in the same message
+    let mut in_packet_message = false;
+
+    for event in events {
+        if let Some((packet_event, packet)) = packet_of(event) {
+            packets.push(PacketContractEvents {
+                packet_event,
+                packet,
+                events: vec![],
+                ack_written: false,
+            });
+
+            in_packet_message = true;
+            continue;
+        }
+
+        let last = match packets.last_mut() {
+            Some(last) if in_packet_message => last,
+            _ => continue,
+        };
+
+        if event.type_str == MESSAGE_EVENT_TYPE && has_attribute(event, "action") {
+            in_packet_message = false;
+        } else if event.type_str == IbcEventType::WriteAck.as_str() {
+            last.ack_written = true;
+        } else if let Some(contract_event) = contract_event(event) {
+            last.events.push(contract_event);
+        }
+    }
+
+    packets
+}
+
+/// Log the events of the contracts called while handling the packets relayed by
+/// the transaction with the given events, and the packets whose acknowledgement
+/// will be written asynchronously.
+pub fn log_contract_events(chain_id: &ChainId, events: &[AbciEvent]) {
+    for packet_events in packet_contract_events(events) {
+        let packet = &packet_events.packet;
+
+        for event in &packet_events.events {
+            info!(
+                chain = %chain_id,
+                packet = %packet,
+                "{} called contract {} ({} event)",
+                packet_events.packet_event.as_str(),
+                event.contract,
+                event.kind,
+            );
+
+            // The channel end on this chain
+            let (port_id, channel_id) = match packet_events.packet_event {
+                IbcEventType::ReceivePacket => {
+                    (&packet.destination_port, &packet.destination_channel)
+                }
+                _ => (&packet.source_port, &packet.source_channel),
+            };
+
+            crate::telemetry!(contract_event, chain_id, channel_id, port_id, &event.kind);
+        }
+
+        if packet_events.packet_event == IbcEventType::ReceivePacket && !packet_events.ack_written {
+            debug!(
+                chain = %chain_id,
+                packet = %packet,
+                "packet was received without an acknowledgement, which the application will write asynchronously"
+            );
+        }
+    }
+}
+
+fn packet_of(event: &AbciEvent) -> Option<(IbcEventType, Packet)> {
+    let event_type = event.type_str.parse().ok()?;
+
+    let packet = match event_type {
+        IbcEventType::ReceivePacket => receive_packet_try_from_abci_event(event).ok()?.packet,
+        IbcEventType::AckPacket => acknowledge_packet_try_from_abci_event(event).ok()?.packet,
+        IbcEventType::Timeout => timeout_packet_try_from_abci_event(event).ok()?.packet,
+        _ => return None,
+    };
+
+    Some((event_type, packet))
+}
+
+fn contract_event(event: &AbciEvent) -> Option<ContractEvent> {
+    let kind = event.type_str.as_str();
+
+    let is_contract_event = CONTRACT_CALL_EVENT_TYPES.contains(&kind)
+        || kind == CONTRACT_EVENT_TYPE
+        || kind.starts_with(&format!("{}-", CONTRACT_EVENT_TYPE));
+
+    if !is_contract_event {
+        return None;
+    }
+
+    let contract = event
+        .attributes
+        .iter()
+        .find(|attr| attr.key.as_ref() == CONTRACT_ADDRESS_KEY)?
+        .value
+        .as_ref()
+        .to_string();
+
+    Some(ContractEvent {
+        kind: kind.to_string(),
+        contract,
+    })
+}
+
+fn has_attribute(event: &AbciEvent, key: &str) -> bool {
+    event.attributes.iter().any(|attr| attr.key.as_ref() == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::convert::TryFrom;
+
+    use ibc::core::ics04_channel::events::{ReceivePacket, WriteAcknowledgement};
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::events::ModuleEventAttribute;
+
+    fn event(kind: &str, attributes: &[(&str, &str)]) -> AbciEvent {
+        AbciEvent {
+            type_str: kind.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|attr| ModuleEventAttribute::from(*attr).into())
+                .collect(),
+        }
+    }
+
+    fn packet(sequence: u64) -> Packet {
+        Packet {
+            sequence: sequence.into(),
+            ..Packet::default()
+        }
+    }
+
+    #[test]
+    fn attach_contract_events_to_packets() {
+        let contract = "osmo1contract";
+
+        let events = vec![
+            event(
+                "message",
+                &[("action", "/ibc.core.channel.v1.MsgRecvPacket")],
+            ),
+            AbciEvent::try_from(ReceivePacket { packet: packet(1) }).unwrap(),
+            event("execute", &[("_contract_address", contract)]),
+            event("wasm", &[("_contract_address", contract), ("swap", "ok")]),
+            AbciEvent::try_from(WriteAcknowledgement {
+                packet: packet(1),
+                ack: b"{}".to_vec(),
+            })
+            .unwrap(),
+            event(
+                "message",
+                &[("action", "/ibc.core.channel.v1.MsgRecvPacket")],
+            ),
+            AbciEvent::try_from(ReceivePacket { packet: packet(2) }).unwrap(),
+            event(
+                "message",
+                &[("action", "/cosmwasm.wasm.v1.MsgExecuteContract")],
+            ),
+            event("execute", &[("_contract_address", contract)]),
+        ];
+
+        let packets = packet_contract_events(&events);
+        assert_eq!(packets.len(), 2);
+
+        assert_eq!(packets[0].packet.sequence, Sequence::from(1));
+        assert!(packets[0].ack_written);
+        assert_eq!(
+            packets[0].events,
+            vec![
+                ContractEvent {
+                    kind: "execute".to_string(),
+                    contract: contract.to_string()
+                },
+                ContractEvent {
+                    kind: "wasm".to_string(),
+                    contract: contract.to_string()
+                },
+            ]
+        );
+
+        // Acknowledged asynchronously, and the contract executed by another message
+        assert_eq!(packets[1].packet.sequence, Sequence::from(2));
+        assert!(!packets[1].ack_written);
+        assert!(packets[1].events.is_empty());
+    }
+}
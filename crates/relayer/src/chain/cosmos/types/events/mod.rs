@@ -1,12 +1,23 @@
+use ibc::events::{IbcEvent, IbcEventType};
 use ibc::Height;
 use tendermint::abci::Event as AbciEvent;
 
-use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
+use crate::event::{
+    ibc_event_try_from_abci_event, receive_packet_try_from_abci_event, IbcEventWithHeight,
+};
 
 pub mod channel;
+pub mod contract;
 
 pub fn from_tx_response_event(height: Height, event: &AbciEvent) -> Option<IbcEventWithHeight> {
-    ibc_event_try_from_abci_event(event)
-        .ok()
-        .map(|ibc_event| IbcEventWithHeight::new(ibc_event, height))
+    // The `recv_packet` events are only of interest in the results of the transactions,
+    // e.g. to count the packets received when their acknowledgement is written later on
+    let ibc_event = match event.type_str.parse() {
+        Ok(IbcEventType::ReceivePacket) => receive_packet_try_from_abci_event(event)
+            .map(IbcEvent::ReceivePacket)
+            .ok(),
+        _ => ibc_event_try_from_abci_event(event).ok(),
+    };
+
+    ibc_event.map(|ibc_event| IbcEventWithHeight::new(ibc_event, height))
 }
@@ -18,6 +18,14 @@ pub struct GasConfig {
     pub fee_granter: String,
 }
 
+impl GasConfig {
+    /// Pay the given gas price from now on, for up to the same maximum amount of gas.
+    pub fn set_gas_price(&mut self, gas_price: GasPrice) {
+        self.max_fee.amount = vec![calculate_fee(self.max_gas, &gas_price)];
+        self.gas_price = gas_price;
+    }
+}
+
 impl<'a> From<&'a ChainConfig> for GasConfig {
     fn from(config: &'a ChainConfig) -> Self {
         Self {
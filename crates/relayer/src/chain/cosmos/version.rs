@@ -25,6 +25,7 @@ use ibc_proto::cosmos::base::tendermint::v1beta1::VersionInfo;
 const SDK_MODULE_NAME: &str = "cosmos/cosmos-sdk";
 const IBC_GO_MODULE_NAME: &str = "cosmos/ibc-go";
 const TENDERMINT_MODULE_NAME: &str = "tendermint/tendermint";
+const COMETBFT_MODULE_NAME: &str = "cometbft/cometbft";
 
 /// Captures the version(s) specification of different
 /// modules of a network.
@@ -177,7 +178,10 @@ fn parse_tendermint_version(version_info: &VersionInfo) -> Result<semver::Versio
     let module = version_info
         .build_deps
         .iter()
-        .find(|&m| m.path.contains(TENDERMINT_MODULE_NAME))
+        .find(|&m| {
+            // Chains on CometBFT depend on its module instead of the Tendermint one
+            m.path.contains(TENDERMINT_MODULE_NAME) || m.path.contains(COMETBFT_MODULE_NAME)
+        })
         .ok_or_else(|| {
             Error::tendermint_module_not_found(
                 TENDERMINT_MODULE_NAME.to_string(),
@@ -8,22 +8,32 @@ use std::time::Instant;
 use tendermint_rpc::{HttpClient, Url};
 use tracing::{debug, trace};
 
+use crate::block_time;
+use crate::chain::cosmos::deadline::with_deadline;
+use crate::chain::cosmos::query::status::query_status;
 use crate::chain::cosmos::query::tx::query_tx_response;
+use crate::chain::cosmos::types::account::InFlightTxs;
+use crate::chain::cosmos::types::events::contract::log_contract_events;
 use crate::chain::cosmos::types::events::from_tx_response_event;
 use crate::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
 use crate::error::Error;
 use crate::event::IbcEventWithHeight;
 
-const WAIT_BACKOFF: Duration = Duration::from_millis(300);
-
 /// Given a vector of `TxSyncResult` elements,
 /// each including a transaction response hash for one or more messages, periodically queries the chain
 /// with the transaction hashes to get the list of IbcEvents included in those transactions.
+///
+/// Gives up once `rpc_timeout` has elapsed, each query being bounded by `query_timeout`.
+/// The queries are repeated at an interval derived from the block time of the chain.
+/// The transactions dropped from the mempool in the meantime are not waited for, see
+/// [`InFlightTxs`].
 pub async fn wait_for_block_commits(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
     rpc_timeout: &Duration,
+    query_timeout: &Duration,
+    in_flight: &InFlightTxs,
     tx_sync_results: &mut [TxSyncResult],
 ) -> Result<(), Error> {
     if all_tx_results_found(tx_sync_results) {
@@ -58,12 +68,50 @@ pub async fn wait_for_block_commits(
         } else if &elapsed > rpc_timeout {
             return Err(Error::tx_no_confirmation());
         } else {
-            thread::sleep(WAIT_BACKOFF);
+            thread::sleep(block_time::poll_interval(chain_id));
+
+            // The latest height of the chain, queried once per round if a transaction was dropped
+            let mut latest_height = None;
 
             for tx_sync_result in tx_sync_results.iter_mut() {
+                if !matches!(tx_sync_result.status, TxStatus::Pending { .. }) {
+                    continue;
+                }
+
+                if in_flight.is_dropped(&tx_sync_result.response.hash) {
+                    if latest_height.is_none() {
+                        latest_height = with_deadline(
+                            chain_id,
+                            "status query",
+                            *query_timeout,
+                            query_status(chain_id, rpc_client, rpc_address),
+                        )
+                        .await
+                        .and_then(|status| status)
+                        .map(|status| status.height)
+                        .ok();
+                    }
+
+                    // Otherwise the transaction is reported as dropped on the next round
+                    if let Some(height) = latest_height {
+                        mark_dropped(chain_id, height, tx_sync_result);
+                    }
+
+                    continue;
+                }
+
                 // ignore error
-                let _ =
-                    update_tx_sync_result(chain_id, rpc_client, rpc_address, tx_sync_result).await;
+                let _ = with_deadline(
+                    chain_id,
+                    "tx confirmation query",
+                    *query_timeout,
+                    update_tx_sync_result(chain_id, rpc_client, rpc_address, tx_sync_result),
+                )
+                .await;
+
+                if let TxStatus::ReceivedResponse = tx_sync_result.status {
+                    in_flight.committed(&tx_sync_result.response.hash);
+                }
             }
         }
     }
@@ -87,14 +135,19 @@ async fn update_tx_sync_result(
                 tx_sync_result.events = vec![
                     IbcEventWithHeight::new(
                         IbcEvent::ChainError(format!(
-                            "deliver_tx for {} reports error: code={:?}, log={:?}",
-                            response.hash, response.tx_result.code, response.tx_result.log
+                            "deliver_tx for {} reports error: codespace={}, code={:?}, log={:?}",
+                            response.hash,
+                            response.tx_result.codespace,
+                            response.tx_result.code,
+                            response.tx_result.log
                         )),
                         height
                     );
                     message_count
                 ];
             } else {
+                log_contract_events(chain_id, &response.tx_result.events);
+
                 tx_sync_result.events = response
                     .tx_result
                     .events
@@ -108,6 +161,26 @@ async fn update_tx_sync_result(
     Ok(())
 }
 
+/// Report the messages of a pending transaction dropped from the mempool as failed,
+/// so that they are relayed again rather than waited for until the timeout.
+/// The failure is reported at the given height, the latest one of the chain
+/// when the transaction was found to be missing from its mempool.
+fn mark_dropped(chain_id: &ChainId, height: Height, tx_sync_result: &mut TxSyncResult) {
+    if let TxStatus::Pending { message_count } = tx_sync_result.status {
+        tx_sync_result.status = TxStatus::ReceivedResponse;
+        tx_sync_result.events = vec![
+            IbcEventWithHeight::new(
+                IbcEvent::ChainError(format!(
+                    "tx {} was dropped from the mempool of chain {}",
+                    tx_sync_result.response.hash, chain_id
+                )),
+                height
+            );
+            message_count
+        ];
+    }
+}
+
 fn all_tx_results_found(tx_sync_results: &[TxSyncResult]) -> bool {
     tx_sync_results
         .iter()
@@ -20,13 +20,14 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, trace};
 
 use super::requests::{
-    IncludeProof, PageRequest, QueryChannelRequest, QueryClientConnectionsRequest,
-    QueryClientStateRequest, QueryConnectionRequest, QueryPacketAcknowledgementsRequest,
-    QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+    BatchQueryRequest, IncludeProof, PageRequest, QueryChannelRequest,
+    QueryClientConnectionsRequest, QueryClientStateRequest, QueryConnectionRequest,
+    QueryPacketAcknowledgementsRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
 };
 use super::{
     handle::ChainHandle,
     requests::{QueryConnectionChannelsRequest, QueryPacketCommitmentsRequest},
+    responses::BatchQueryResponse,
 };
 use crate::chain::requests::QueryHeight;
 use crate::channel::ChannelError;
@@ -367,6 +368,7 @@ pub fn commitments_on_chain(
             port_id: port_id.clone(),
             channel_id: channel_id.clone(),
             pagination: Some(PageRequest::all()),
+            height: QueryHeight::Latest,
         })
         .map_err(Error::relayer)?;
 
@@ -413,6 +415,7 @@ pub fn packet_acknowledgements(
             channel_id: channel_id.clone(),
             pagination: Some(PageRequest::all()),
             packet_commitment_sequences: commit_sequences,
+            height: QueryHeight::Latest,
         })
         .map_err(Error::relayer)?;
 
@@ -485,6 +488,85 @@ pub fn unreceived_packets(
     Ok((packet_seq_nrs, h))
 }
 
+/// The sequences of the unreceived packets of each of the given paths, as with
+/// [`unreceived_packets`], the queries about all the paths being batched together,
+/// see [`ChainHandle::batch_query`]. Answers `None` for a path which could not be queried.
+pub fn unreceived_packets_of_paths(
+    chain: &impl ChainHandle,
+    counterparty_chain: &impl ChainHandle,
+    paths: &[PathIdentifiers],
+) -> Result<Vec<Option<Vec<Sequence>>>, Error> {
+    let commitments_requests = paths
+        .iter()
+        .map(|path| {
+            BatchQueryRequest::PacketCommitments(QueryPacketCommitmentsRequest {
+                port_id: path.counterparty_port_id.clone(),
+                channel_id: path.counterparty_channel_id.clone(),
+                pagination: Some(PageRequest::all()),
+                height: QueryHeight::Latest,
+            })
+        })
+        .collect();
+
+    let commitments = counterparty_chain
+        .batch_query(commitments_requests)
+        .map_err(Error::relayer)?;
+
+    let mut unreceived: Vec<Option<Vec<Sequence>>> = Vec::with_capacity(paths.len());
+    let mut unreceived_requests = vec![];
+    let mut queried = vec![];
+
+    for (index, (path, commitments)) in paths.iter().zip(commitments).enumerate() {
+        match commitments {
+            Ok(BatchQueryResponse::PacketCommitments(sequences, _)) if sequences.is_empty() => {
+                unreceived.push(Some(vec![]));
+            }
+            Ok(BatchQueryResponse::PacketCommitments(sequences, _)) => {
+                unreceived.push(None);
+                queried.push(index);
+                unreceived_requests.push(BatchQueryRequest::UnreceivedPackets(
+                    QueryUnreceivedPacketsRequest {
+                        port_id: path.port_id.clone(),
+                        channel_id: path.channel_id.clone(),
+                        packet_commitment_sequences: sequences,
+                    },
+                ));
+            }
+            Ok(_) => unreceived.push(None),
+            Err(e) => {
+                error!(
+                    "failed to query the packet commitments of {}/{}: {}",
+                    path.counterparty_port_id, path.counterparty_channel_id, e
+                );
+                unreceived.push(None);
+            }
+        }
+    }
+
+    if unreceived_requests.is_empty() {
+        return Ok(unreceived);
+    }
+
+    let responses = chain
+        .batch_query(unreceived_requests)
+        .map_err(Error::relayer)?;
+
+    for (index, response) in queried.into_iter().zip(responses) {
+        match response {
+            Ok(BatchQueryResponse::UnreceivedPackets(sequences)) => {
+                unreceived[index] = Some(sequences);
+            }
+            Ok(_) => {}
+            Err(e) => error!(
+                "failed to query the unreceived packets of {}/{}: {}",
+                paths[index].port_id, paths[index].channel_id, e
+            ),
+        }
+    }
+
+    Ok(unreceived)
+}
+
 pub fn acknowledgements_on_chain(
     chain: &impl ChainHandle,
     counterparty_chain: &impl ChainHandle,
@@ -1,8 +1,9 @@
 use alloc::sync::Arc;
-use core::convert::TryFrom;
 
+use serde::Serialize;
 use tokio::runtime::Runtime as TokioRuntime;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics02_client::consensus_state::ConsensusState;
 use ibc::core::ics02_client::events::UpdateClient;
@@ -11,7 +12,8 @@ use ibc::core::ics03_connection::connection::{ConnectionEnd, IdentifiedConnectio
 use ibc::core::ics03_connection::version::{get_compatible_versions, Version};
 use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd};
 use ibc::core::ics04_channel::packet::{PacketMsgType, Sequence};
-use ibc::core::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
+use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
 use ibc::events::IbcEvent;
@@ -23,24 +25,26 @@ use tendermint_rpc::endpoint::broadcast::tx_sync::Response as TxResponse;
 
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
+use crate::chain::proof::{require_proof, QueryProof};
 use crate::chain::requests::{
-    CrossChainQueryRequest, QueryChannelClientStateRequest, QueryChannelRequest,
+    BatchQueryRequest, CrossChainQueryRequest, QueryChannelClientStateRequest, QueryChannelRequest,
     QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
     QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
     QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
-    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest, QueryUnreceivedAcksRequest,
-    QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
-    QueryUpgradedConsensusStateRequest,
+    QueryCounterpartyPayeeRequest, QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryPacketAcknowledgementsRequest,
+    QueryPacketCommitmentsRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+    QueryUpgradeErrorRequest, QueryUpgradeRequest, QueryUpgradedClientStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWasmChecksumsRequest,
 };
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
 use crate::config::ChainConfig;
 use crate::connection::ConnectionMsgType;
 use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
 use crate::denom::DenomTrace;
-use crate::error::{Error, QUERY_PROOF_EXPECT_MSG};
+use crate::error::Error;
 use crate::event::monitor::{EventReceiver, TxMonitorCmd};
 use crate::event::IbcEventWithHeight;
 use crate::keyring::{KeyEntry, KeyRing};
@@ -66,6 +70,23 @@ pub struct ChainStatus {
     pub timestamp: Timestamp,
 }
 
+/// The outcome of the simulation of a transaction, see [`ChainEndpoint::simulate_messages`].
+#[derive(Clone, Debug, Serialize)]
+pub struct SimulatedTx {
+    /// The number of messages in the transaction
+    pub messages: usize,
+    /// The gas used by the transaction, if it would succeed
+    pub gas_used: Option<u64>,
+    /// The fee paid for the gas used, with the gas price and multiplier of the chain
+    pub fee: Option<String>,
+    /// Why the transaction would fail, if it would
+    pub error: Option<String>,
+}
+
+/// The wait for the commit of the transactions broadcast by
+/// [`ChainEndpoint::send_messages_and_defer_commit`], returning their events.
+pub type DeferredCommit = Box<dyn FnOnce() -> Result<Vec<IbcEventWithHeight>, Error> + Send>;
+
 /// Defines a blockchain as understood by the relayer
 pub trait ChainEndpoint: Sized {
     /// Type of light blocks for this chain
@@ -107,6 +128,9 @@ pub trait ChainEndpoint: Sized {
     /// Perform a health check
     fn health_check(&self) -> Result<HealthCheck, Error>;
 
+    /// Check that the key of the relayer can sign the transactions submitted to the chain
+    fn check_key(&self) -> Result<HealthCheck, Error>;
+
     // Keyring
 
     /// Returns the chain's keybase
@@ -126,6 +150,9 @@ pub trait ChainEndpoint: Sized {
     /// Return the version of the IBC protocol that this chain is running, if known.
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error>;
 
+    /// Return the version of the Cosmos SDK that this chain is running, if known.
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error>;
+
     // Send transactions
 
     /// Sends one or more transactions with `msgs` to chain and
@@ -135,6 +162,14 @@ pub trait ChainEndpoint: Sized {
         tracked_msgs: TrackedMsgs,
     ) -> Result<Vec<IbcEventWithHeight>, Error>;
 
+    /// Sends one or more transactions with `msgs` to chain, and returns the wait for them
+    /// to be committed, so that the transactions of other requests can be broadcast in the
+    /// meantime and committed in the same block. Waits for the commit right away by default.
+    fn send_messages_and_defer_commit(&mut self, tracked_msgs: TrackedMsgs) -> DeferredCommit {
+        let result = self.send_messages_and_wait_commit(tracked_msgs);
+        Box::new(move || result)
+    }
+
     /// Sends one or more transactions with `msgs` to chain.
     /// Non-blocking alternative to `send_messages_and_wait_commit` interface.
     fn send_messages_and_wait_check_tx(
@@ -142,6 +177,14 @@ pub trait ChainEndpoint: Sized {
         tracked_msgs: TrackedMsgs,
     ) -> Result<Vec<TxResponse>, Error>;
 
+    /// Simulates the transactions which would be sent with `msgs`, batched as
+    /// `send_messages_and_wait_commit` does, without broadcasting them.
+    fn simulate_messages(&mut self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error>;
+
+    /// Broadcast the given evidence of the misbehaviour of validators of the chain to its
+    /// consensus, without waiting for the evidence to be committed.
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error>;
+
     // Light client
 
     /// Fetch a header from the chain at the given height and verify it.
@@ -191,7 +234,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error>;
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve the consensus state for a specified height
     /// `consensus_height` that the specified light client stores.
@@ -199,7 +242,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error>;
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the consensus states that the specified
     /// light client stores.
@@ -237,7 +280,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error>;
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all channels associated with a connection.
     fn query_connection_channels(
@@ -257,7 +300,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error>;
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve the client state for the channel associated
     /// with a given channel identifier.
@@ -273,7 +316,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the packet commitments hashes
     /// associated with a channel. Returns the corresponding packet sequence
@@ -289,7 +332,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query about which IBC packets in the specified list has not
     /// been received. Returns the sequence numbers of the packets that were not
@@ -310,7 +353,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the packet acknowledgements associated
     /// with a channel. Returns the corresponding packet sequence numbers and
@@ -332,6 +375,35 @@ pub trait ChainEndpoint: Sized {
         request: QueryUnreceivedAcksRequest,
     ) -> Result<Vec<Sequence>, Error>;
 
+    /// Performs the given queries about the packets of several channels, answering each
+    /// of them in the order of the requests. The queries are made one after the other,
+    /// unless the chain is able to make them concurrently.
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        let responses = requests
+            .into_iter()
+            .map(|request| match request {
+                BatchQueryRequest::PacketCommitments(request) => self
+                    .query_packet_commitments(request)
+                    .map(|(sequences, height)| {
+                        BatchQueryResponse::PacketCommitments(sequences, height)
+                    }),
+                BatchQueryRequest::UnreceivedPackets(request) => self
+                    .query_unreceived_packets(request)
+                    .map(BatchQueryResponse::UnreceivedPackets),
+                BatchQueryRequest::PacketAcknowledgements(request) => self
+                    .query_packet_acknowledgements(request)
+                    .map(|(sequences, height)| {
+                        BatchQueryResponse::PacketAcknowledgements(sequences, height)
+                    }),
+            })
+            .collect();
+
+        Ok(responses)
+    }
+
     /// Performs a query to retrieve `nextSequenceRecv` stored at path
     /// `path::SeqRecvsPath` as defined in ICS-4. A proof can optionally be
     /// returned along with the result.
@@ -339,7 +411,7 @@ pub trait ChainEndpoint: Sized {
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error>;
+    ) -> Result<(Sequence, Option<QueryProof>), Error>;
 
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error>;
 
@@ -358,6 +430,41 @@ pub trait ChainEndpoint: Sized {
         requests: Vec<CrossChainQueryRequest>,
     ) -> Result<Vec<CrossChainQueryResponse>, Error>;
 
+    /// Query the fees escrowed for the relaying of a packet, none if the packet is not
+    /// incentivized, see ICS-29.
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error>;
+
+    /// Query the address to which the receive fees of a relayer are paid out on the
+    /// counterparty chain of a channel, if registered, see ICS-29.
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error>;
+
+    /// Query the checksums of the Wasm contracts stored on the chain, see ICS-08.
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Query the upgrade proposed by the given channel end, which only exists while
+    /// the channel is being upgraded.
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error>;
+
+    /// Query the error receipt of the latest aborted upgrade of the given channel end.
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error>;
+
     fn build_client_state(
         &self,
         height: ICSHeight,
@@ -397,7 +504,7 @@ pub trait ChainEndpoint: Sized {
             },
             IncludeProof::Yes,
         )?;
-        let connection_proof = maybe_connection_proof.expect(QUERY_PROOF_EXPECT_MSG);
+        let connection_proof = require_proof(maybe_connection_proof)?;
 
         // Check that the connection state is compatible with the message
         match message_type {
@@ -435,12 +542,9 @@ pub trait ChainEndpoint: Sized {
                     },
                     IncludeProof::Yes,
                 )?;
-                let client_state_proof = maybe_client_state_proof.expect(QUERY_PROOF_EXPECT_MSG);
+                let client_state_proof = require_proof(maybe_client_state_proof)?;
 
-                client_proof = Some(
-                    CommitmentProofBytes::try_from(client_state_proof)
-                        .map_err(Error::malformed_proof)?,
-                );
+                client_proof = Some(client_state_proof.to_bytes()?);
 
                 let consensus_state_proof = {
                     let (_, maybe_consensus_state_proof) = self.query_consensus_state(
@@ -452,13 +556,12 @@ pub trait ChainEndpoint: Sized {
                         IncludeProof::Yes,
                     )?;
 
-                    maybe_consensus_state_proof.expect(QUERY_PROOF_EXPECT_MSG)
+                    require_proof(maybe_consensus_state_proof)?
                 };
 
                 consensus_proof = Option::from(
                     ConsensusProof::new(
-                        CommitmentProofBytes::try_from(consensus_state_proof)
-                            .map_err(Error::malformed_proof)?,
+                        consensus_state_proof.to_bytes()?,
                         client_state_value.latest_height(),
                     )
                     .map_err(Error::consensus_proof)?,
@@ -472,7 +575,7 @@ pub trait ChainEndpoint: Sized {
         Ok((
             client_state,
             Proofs::new(
-                CommitmentProofBytes::try_from(connection_proof).map_err(Error::malformed_proof)?,
+                connection_proof.to_bytes()?,
                 client_proof,
                 consensus_proof,
                 None,
@@ -498,9 +601,7 @@ pub trait ChainEndpoint: Sized {
             },
             IncludeProof::Yes,
         )?;
-        let channel_proof = maybe_channel_proof.expect(QUERY_PROOF_EXPECT_MSG);
-        let channel_proof_bytes =
-            CommitmentProofBytes::try_from(channel_proof).map_err(Error::malformed_proof)?;
+        let channel_proof_bytes = require_proof(maybe_channel_proof)?.to_bytes()?;
 
         Proofs::new(channel_proof_bytes, None, None, None, height.increment())
             .map_err(Error::malformed_proof)
@@ -577,11 +678,7 @@ pub trait ChainEndpoint: Sized {
                         },
                         IncludeProof::Yes,
                     )?;
-                    let channel_merkle_proof = maybe_channel_proof.expect(QUERY_PROOF_EXPECT_MSG);
-                    Some(
-                        CommitmentProofBytes::try_from(channel_merkle_proof)
-                            .map_err(Error::malformed_proof)?,
-                    )
+                    Some(require_proof(maybe_channel_proof)?.to_bytes()?)
                 };
                 let (_, maybe_packet_proof) = self.query_packet_receipt(
                     QueryPacketReceiptRequest {
@@ -597,10 +694,10 @@ pub trait ChainEndpoint: Sized {
             }
         };
 
-        let packet_proof = maybe_packet_proof.expect(QUERY_PROOF_EXPECT_MSG);
+        let packet_proof = require_proof(maybe_packet_proof)?;
 
         let proofs = Proofs::new(
-            CommitmentProofBytes::try_from(packet_proof).map_err(Error::malformed_proof)?,
+            packet_proof.to_bytes()?,
             None,
             None,
             channel_proof,
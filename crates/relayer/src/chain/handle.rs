@@ -4,6 +4,7 @@ use core::fmt::{self, Debug, Display};
 use crossbeam_channel as channel;
 use tracing::Span;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::{
     core::{
         ics02_client::events::UpdateClient,
@@ -14,6 +15,7 @@ use ibc::{
         ics04_channel::{
             channel::{ChannelEnd, IdentifiedChannelEnd},
             packet::{PacketMsgType, Sequence},
+            upgrade::{ErrorReceipt, Upgrade},
         },
         ics23_commitment::{commitment::CommitmentPrefix, merkle::MerkleProof},
         ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
@@ -27,7 +29,7 @@ use ibc::{
 use crate::{
     account::Balance,
     client_state::{AnyClientState, IdentifiedAnyClientState},
-    config::ChainConfig,
+    config::{priority::Priority, ChainConfig},
     connection::ConnectionMsgType,
     consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight},
     denom::DenomTrace,
@@ -43,7 +45,7 @@ use crate::{
 
 use super::{
     client::ClientSettings,
-    endpoint::{ChainStatus, HealthCheck},
+    endpoint::{ChainStatus, HealthCheck, SimulatedTx},
     requests::*,
     tracking::TrackedMsgs,
 };
@@ -52,8 +54,9 @@ mod base;
 mod cache;
 mod counting;
 
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::CrossChainQueryRequest;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 pub use base::BaseChainHandle;
 pub use counting::CountingChainHandle;
 
@@ -108,6 +111,10 @@ pub enum ChainRequest {
         reply_to: ReplyTo<HealthCheck>,
     },
 
+    CheckKey {
+        reply_to: ReplyTo<HealthCheck>,
+    },
+
     Subscribe {
         reply_to: ReplyTo<Subscription>,
     },
@@ -122,6 +129,16 @@ pub enum ChainRequest {
         reply_to: ReplyTo<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>>,
     },
 
+    SimulateMessages {
+        tracked_msgs: TrackedMsgs,
+        reply_to: ReplyTo<Vec<SimulatedTx>>,
+    },
+
+    BroadcastEvidence {
+        evidence: tendermint::evidence::Evidence,
+        reply_to: ReplyTo<()>,
+    },
+
     Config {
         reply_to: ReplyTo<ChainConfig>,
     },
@@ -144,6 +161,10 @@ pub enum ChainRequest {
         reply_to: ReplyTo<Option<semver::Version>>,
     },
 
+    SdkVersion {
+        reply_to: ReplyTo<Option<semver::Version>>,
+    },
+
     QueryBalance {
         key_name: Option<String>,
         reply_to: ReplyTo<Balance>,
@@ -200,7 +221,7 @@ pub enum ChainRequest {
     QueryClientState {
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(AnyClientState, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(AnyClientState, Option<QueryProof>)>,
     },
 
     QueryClientConnections {
@@ -211,7 +232,7 @@ pub enum ChainRequest {
     QueryConsensusState {
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(AnyConsensusState, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(AnyConsensusState, Option<QueryProof>)>,
     },
 
     QueryConsensusStates {
@@ -240,7 +261,7 @@ pub enum ChainRequest {
     QueryConnection {
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(ConnectionEnd, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(ConnectionEnd, Option<QueryProof>)>,
     },
 
     QueryConnections {
@@ -261,7 +282,7 @@ pub enum ChainRequest {
     QueryChannel {
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(ChannelEnd, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(ChannelEnd, Option<QueryProof>)>,
     },
 
     QueryChannelClientState {
@@ -272,7 +293,7 @@ pub enum ChainRequest {
     QueryNextSequenceReceive {
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Sequence, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Sequence, Option<QueryProof>)>,
     },
 
     BuildChannelProofs {
@@ -294,7 +315,7 @@ pub enum ChainRequest {
     QueryPacketCommitment {
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     },
 
     QueryPacketCommitments {
@@ -305,7 +326,7 @@ pub enum ChainRequest {
     QueryPacketReceipt {
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     },
 
     QueryUnreceivedPackets {
@@ -316,7 +337,7 @@ pub enum ChainRequest {
     QueryPacketAcknowledgement {
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     },
 
     QueryPacketAcknowledgements {
@@ -329,6 +350,11 @@ pub enum ChainRequest {
         reply_to: ReplyTo<Vec<Sequence>>,
     },
 
+    BatchQuery {
+        requests: Vec<BatchQueryRequest>,
+        reply_to: ReplyTo<Vec<Result<BatchQueryResponse, Error>>>,
+    },
+
     QueryPacketEventDataFromTxs {
         request: QueryTxRequest,
         reply_to: ReplyTo<Vec<IbcEventWithHeight>>,
@@ -348,10 +374,37 @@ pub enum ChainRequest {
         request: Vec<CrossChainQueryRequest>,
         reply_to: ReplyTo<Vec<CrossChainQueryResponse>>,
     },
+
+    QueryIncentivizedPacket {
+        request: QueryIncentivizedPacketRequest,
+        reply_to: ReplyTo<Option<IdentifiedPacketFees>>,
+    },
+
+    QueryCounterpartyPayee {
+        request: QueryCounterpartyPayeeRequest,
+        reply_to: ReplyTo<Option<Signer>>,
+    },
+
+    QueryWasmChecksums {
+        request: QueryWasmChecksumsRequest,
+        reply_to: ReplyTo<Vec<Vec<u8>>>,
+    },
+
+    QueryUpgrade {
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(Upgrade, Option<QueryProof>)>,
+    },
+
+    QueryUpgradeError {
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(ErrorReceipt, Option<QueryProof>)>,
+    },
 }
 
 pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
-    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self;
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self;
 
     /// Get the [`ChainId`] of this chain.
     fn id(&self) -> ChainId;
@@ -362,6 +415,9 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
     /// Perform a health check
     fn health_check(&self) -> Result<HealthCheck, Error>;
 
+    /// Check that the key of the relayer can sign the transactions submitted to the chain
+    fn check_key(&self) -> Result<HealthCheck, Error>;
+
     /// Subscribe to the events emitted by the chain.
     fn subscribe(&self) -> Result<Subscription, Error>;
 
@@ -381,6 +437,14 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         tracked_msgs: TrackedMsgs,
     ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error>;
 
+    /// Simulates the transactions which would be sent with the given messages, without
+    /// broadcasting them, returning the gas they would use or why they would fail.
+    fn simulate_messages(&self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error>;
+
+    /// Broadcast the given evidence of the misbehaviour of validators of the chain to its
+    /// consensus, without waiting for the evidence to be committed.
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error>;
+
     fn get_signer(&self) -> Result<Signer, Error>;
 
     fn config(&self) -> Result<ChainConfig, Error>;
@@ -392,6 +456,9 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
     /// Return the version of the IBC protocol that this chain is running, if known.
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error>;
 
+    /// Return the version of the Cosmos SDK that this chain is running, if known.
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error>;
+
     /// Query the balance of the given account for the denom used to pay tx fees.
     /// If no account is given, behavior must be specified, e.g. retrieve it from configuration file.
     fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error>;
@@ -418,7 +485,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error>;
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve the identifiers of all connections.
     fn query_client_connections(
@@ -432,7 +499,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error>;
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the consensus states that the specified
     /// light client stores.
@@ -462,7 +529,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error>;
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve the identifiers of all connections.
     fn query_connections(
@@ -483,7 +550,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error>;
+    ) -> Result<(Sequence, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the channels of a chain.
     fn query_channels(
@@ -497,7 +564,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error>;
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve the client state for the channel associated
     /// with a given channel identifier.
@@ -565,7 +632,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the packet commitments hashes
     /// associated with a channel. Returns the corresponding packet sequence
@@ -581,7 +648,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query about which IBC packets in the specified list has not
     /// been received. Returns the sequence numbers of the packets that were not
@@ -602,7 +669,7 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error>;
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error>;
 
     /// Performs a query to retrieve all the packet acknowledgements associated
     /// with a channel. Returns the corresponding packet sequence numbers and
@@ -624,6 +691,15 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         request: QueryUnreceivedAcksRequest,
     ) -> Result<Vec<Sequence>, Error>;
 
+    /// Performs the given queries about the packets of several channels, concurrently
+    /// on the chains which support it, up to `quotas.max_concurrent_queries` at a time.
+    /// Answers each of the queries in the order of the requests, failing as a whole
+    /// only if none of them could be made.
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error>;
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error>;
 
     fn query_blocks(
@@ -640,4 +716,39 @@ pub trait ChainHandle: Clone + Display + Send + Sync + Debug + 'static {
         &self,
         request: Vec<CrossChainQueryRequest>,
     ) -> Result<Vec<CrossChainQueryResponse>, Error>;
+
+    /// Query the fees escrowed for the relaying of a packet, none if the packet is not
+    /// incentivized, see ICS-29.
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error>;
+
+    /// Query the address to which the receive fees of a relayer are paid out on the
+    /// counterparty chain of a channel, if registered, see ICS-29.
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error>;
+
+    /// Query the checksums of the Wasm contracts stored on the chain, see ICS-08.
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Query the upgrade proposed by the given channel end, which only exists while
+    /// the channel is being upgraded.
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error>;
+
+    /// Query the error receipt of the latest aborted upgrade of the given channel end.
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error>;
 }
@@ -3,6 +3,7 @@ use core::fmt::{Debug, Display, Error as FmtError, Formatter};
 use crossbeam_channel as channel;
 use tracing::Span;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::{
     core::{
         ics02_client::events::UpdateClient,
@@ -10,6 +11,7 @@ use ibc::{
         ics03_connection::version::Version,
         ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd},
         ics04_channel::packet::{PacketMsgType, Sequence},
+        ics04_channel::upgrade::{ErrorReceipt, Upgrade},
         ics23_commitment::{commitment::CommitmentPrefix, merkle::MerkleProof},
         ics24_host::identifier::ChainId,
         ics24_host::identifier::ChannelId,
@@ -21,11 +23,17 @@ use ibc::{
     Height,
 };
 
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::CrossChainQueryRequest;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::{
     account::Balance,
-    chain::{client::ClientSettings, endpoint::ChainStatus, requests::*, tracking::TrackedMsgs},
+    chain::{
+        client::ClientSettings,
+        endpoint::{ChainStatus, SimulatedTx},
+        requests::*,
+        tracking::TrackedMsgs,
+    },
     client_state::{AnyClientState, IdentifiedAnyClientState},
     config::ChainConfig,
     connection::ConnectionMsgType,
@@ -39,6 +47,8 @@ use crate::{
 };
 
 use super::{reply_channel, ChainHandle, ChainRequest, HealthCheck, ReplyTo, Subscription};
+use crate::chain::priority;
+use crate::config::priority::Priority;
 
 /// A basic chain handle implementation.
 /// For use in interactive CLIs, e.g., `query`, `tx`, etc.
@@ -48,11 +58,11 @@ pub struct BaseChainHandle {
     chain_id: ChainId,
 
     /// The handle's channel for sending requests to the runtime
-    runtime_sender: channel::Sender<(Span, ChainRequest)>,
+    runtime_sender: channel::Sender<(Span, Priority, ChainRequest)>,
 }
 
 impl BaseChainHandle {
-    pub fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+    pub fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self {
         Self {
             chain_id,
             runtime_sender: sender,
@@ -70,7 +80,7 @@ impl BaseChainHandle {
         let input = f(sender);
 
         self.runtime_sender
-            .send((span, input))
+            .send((span, priority::current(), input))
             .map_err(Error::send)?;
 
         receiver.recv().map_err(Error::channel_receive)?
@@ -84,7 +94,7 @@ impl Display for BaseChainHandle {
 }
 
 impl ChainHandle for BaseChainHandle {
-    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self {
         Self::new(chain_id, sender)
     }
 
@@ -96,6 +106,10 @@ impl ChainHandle for BaseChainHandle {
         self.send(|reply_to| ChainRequest::HealthCheck { reply_to })
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        self.send(|reply_to| ChainRequest::CheckKey { reply_to })
+    }
+
     fn shutdown(&self) -> Result<(), Error> {
         self.send(|reply_to| ChainRequest::Shutdown { reply_to })
     }
@@ -124,6 +138,17 @@ impl ChainHandle for BaseChainHandle {
         })
     }
 
+    fn simulate_messages(&self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        self.send(|reply_to| ChainRequest::SimulateMessages {
+            tracked_msgs,
+            reply_to,
+        })
+    }
+
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        self.send(|reply_to| ChainRequest::BroadcastEvidence { evidence, reply_to })
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.send(|reply_to| ChainRequest::Signer { reply_to })
     }
@@ -148,6 +173,10 @@ impl ChainHandle for BaseChainHandle {
         self.send(|reply_to| ChainRequest::IbcVersion { reply_to })
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.send(|reply_to| ChainRequest::SdkVersion { reply_to })
+    }
+
     fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
         self.send(|reply_to| ChainRequest::QueryBalance { key_name, reply_to })
     }
@@ -171,7 +200,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryClientState {
             request,
             include_proof,
@@ -197,7 +226,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryConsensusState {
             request,
             include_proof,
@@ -231,7 +260,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryConnection {
             request,
             include_proof,
@@ -257,7 +286,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryNextSequenceReceive {
             request,
             include_proof,
@@ -276,7 +305,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryChannel {
             request,
             include_proof,
@@ -397,7 +426,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryPacketCommitment {
             request,
             include_proof,
@@ -416,7 +445,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryPacketReceipt {
             request,
             include_proof,
@@ -435,7 +464,7 @@ impl ChainHandle for BaseChainHandle {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.send(|reply_to| ChainRequest::QueryPacketAcknowledgement {
             request,
             include_proof,
@@ -457,6 +486,13 @@ impl ChainHandle for BaseChainHandle {
         self.send(|reply_to| ChainRequest::QueryUnreceivedAcknowledgement { request, reply_to })
     }
 
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        self.send(|reply_to| ChainRequest::BatchQuery { requests, reply_to })
+    }
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error> {
         self.send(|reply_to| ChainRequest::QueryPacketEventDataFromTxs { request, reply_to })
     }
@@ -481,4 +517,49 @@ impl ChainHandle for BaseChainHandle {
     ) -> Result<Vec<CrossChainQueryResponse>, Error> {
         self.send(|reply_to| ChainRequest::CrossChainQuery { request, reply_to })
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        self.send(|reply_to| ChainRequest::QueryIncentivizedPacket { request, reply_to })
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        self.send(|reply_to| ChainRequest::QueryCounterpartyPayee { request, reply_to })
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.send(|reply_to| ChainRequest::QueryWasmChecksums { request, reply_to })
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        self.send(|reply_to| ChainRequest::QueryUpgrade {
+            request,
+            include_proof,
+            reply_to,
+        })
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        self.send(|reply_to| ChainRequest::QueryUpgradeError {
+            request,
+            include_proof,
+            reply_to,
+        })
+    }
 }
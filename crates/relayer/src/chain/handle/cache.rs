@@ -1,11 +1,15 @@
+use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
 use crossbeam_channel as channel;
+use once_cell::sync::OnceCell;
 use tracing::Span;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::core::ics02_client::events::UpdateClient;
 use ibc::core::ics03_connection::connection::IdentifiedConnectionEnd;
 use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
 use ibc::core::ics04_channel::packet::{PacketMsgType, Sequence};
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 use ibc::{
     core::ics03_connection::connection::ConnectionEnd,
@@ -24,12 +28,14 @@ use ibc::{
 use crate::account::Balance;
 use crate::cache::{Cache, CacheStatus};
 use crate::chain::client::ClientSettings;
-use crate::chain::endpoint::{ChainStatus, HealthCheck};
+use crate::chain::endpoint::{ChainStatus, HealthCheck, SimulatedTx};
 use crate::chain::handle::{ChainHandle, ChainRequest, Subscription};
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::*;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
+use crate::config::priority::Priority;
 use crate::config::ChainConfig;
 use crate::connection::ConnectionMsgType;
 use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
@@ -46,14 +52,15 @@ use crate::telemetry;
 #[derive(Debug, Clone)]
 pub struct CachingChainHandle<Handle> {
     inner: Handle,
-    cache: Cache,
+    /// Created on first use, with the capacity set in the quotas of the chain
+    cache: Arc<OnceCell<Cache>>,
 }
 
 impl<Handle> CachingChainHandle<Handle> {
     pub fn new(handle: Handle) -> Self {
         Self {
             inner: handle,
-            cache: Cache::new(),
+            cache: Arc::new(OnceCell::new()),
         }
     }
 
@@ -62,6 +69,15 @@ impl<Handle> CachingChainHandle<Handle> {
     }
 }
 
+impl<Handle: ChainHandle> CachingChainHandle<Handle> {
+    fn cache(&self) -> &Cache {
+        self.cache.get_or_init(|| match self.inner().config() {
+            Ok(config) => Cache::with_capacity(config.quotas.max_cache_entries),
+            Err(_) => Cache::new(),
+        })
+    }
+}
+
 impl<Handle: ChainHandle> Display for CachingChainHandle<Handle> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         write!(
@@ -73,7 +89,7 @@ impl<Handle: ChainHandle> Display for CachingChainHandle<Handle> {
 }
 
 impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
-    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self {
         Self::new(Handle::new(chain_id, sender))
     }
 
@@ -89,6 +105,10 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         self.inner().health_check()
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        self.inner().check_key()
+    }
+
     fn subscribe(&self) -> Result<Subscription, Error> {
         self.inner().subscribe()
     }
@@ -107,6 +127,14 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         self.inner().send_messages_and_wait_check_tx(tracked_msgs)
     }
 
+    fn simulate_messages(&self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        self.inner().simulate_messages(tracked_msgs)
+    }
+
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        self.inner().broadcast_evidence(evidence)
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.inner().get_signer()
     }
@@ -127,6 +155,10 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         self.inner().ibc_version()
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.inner().sdk_version()
+    }
+
     fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
         self.inner().query_balance(key_name)
     }
@@ -164,13 +196,13 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
         let handle = self.inner();
         match include_proof {
             IncludeProof::Yes => handle.query_client_state(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_client_state_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_client_state_with(
                         &request.client_id,
                         || {
                             handle
@@ -209,7 +241,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
         self.inner().query_consensus_state(request, include_proof)
     }
 
@@ -239,13 +271,13 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         let handle = self.inner();
         match include_proof {
             IncludeProof::Yes => handle.query_connection(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_connection_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_connection_with(
                         &request.connection_id,
                         || {
                             handle
@@ -284,7 +316,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         self.inner()
             .query_next_sequence_receive(request, include_proof)
     }
@@ -300,13 +332,13 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         let handle = self.inner();
         match include_proof {
             IncludeProof::Yes => handle.query_channel(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_channel_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_channel_with(
                         &PortChannelId::new(request.channel_id.clone(), request.port_id.clone()),
                         || {
                             handle
@@ -413,7 +445,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inner().query_packet_commitment(request, include_proof)
     }
 
@@ -428,7 +460,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inner().query_packet_receipt(request, include_proof)
     }
 
@@ -443,7 +475,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inner()
             .query_packet_acknowledgement(request, include_proof)
     }
@@ -462,6 +494,13 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
         self.inner().query_unreceived_acknowledgements(request)
     }
 
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        self.inner().batch_query(requests)
+    }
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error> {
         self.inner().query_txs(request)
     }
@@ -486,4 +525,41 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
     ) -> Result<Vec<CrossChainQueryResponse>, Error> {
         self.inner.cross_chain_query(request)
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        self.inner.query_incentivized_packet(request)
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        self.inner.query_counterparty_payee(request)
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.inner.query_wasm_checksums(request)
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        self.inner.query_upgrade(request, include_proof)
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        self.inner.query_upgrade_error(request, include_proof)
+    }
 }
@@ -7,12 +7,14 @@ use tracing::{debug, Span};
 
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
-use crate::chain::endpoint::{ChainStatus, HealthCheck};
+use crate::chain::endpoint::{ChainStatus, HealthCheck, SimulatedTx};
 use crate::chain::handle::{ChainHandle, ChainRequest, Subscription};
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::*;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
+use crate::config::priority::Priority;
 use crate::config::ChainConfig;
 use crate::connection::ConnectionMsgType;
 use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
@@ -23,10 +25,12 @@ use crate::keyring::KeyEntry;
 use crate::light_client::AnyHeader;
 use crate::misbehaviour::MisbehaviourEvidence;
 use crate::util::lock::LockExt;
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::core::ics02_client::events::UpdateClient;
 use ibc::core::ics03_connection::connection::IdentifiedConnectionEnd;
 use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
 use ibc::core::ics04_channel::packet::{PacketMsgType, Sequence};
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 use ibc::{
     core::ics03_connection::connection::ConnectionEnd,
@@ -83,7 +87,7 @@ impl<Handle: ChainHandle> Display for CountingChainHandle<Handle> {
 }
 
 impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
-    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self {
         Self::new(Handle::new(chain_id, sender))
     }
 
@@ -106,6 +110,11 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().health_check()
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        self.inc_metric("check_key");
+        self.inner().check_key()
+    }
+
     fn subscribe(&self) -> Result<Subscription, Error> {
         self.inc_metric("subscribe");
         self.inner().subscribe()
@@ -127,6 +136,16 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().send_messages_and_wait_check_tx(tracked_msgs)
     }
 
+    fn simulate_messages(&self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        self.inc_metric("simulate_messages");
+        self.inner().simulate_messages(tracked_msgs)
+    }
+
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        self.inc_metric("broadcast_evidence");
+        self.inner().broadcast_evidence(evidence)
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.inc_metric("get_signer");
         self.inner().get_signer()
@@ -152,6 +171,11 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().ibc_version()
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.inc_metric("sdk_version");
+        self.inner().sdk_version()
+    }
+
     fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
         self.inc_metric("query_balance");
         self.inner().query_balance(key_name)
@@ -184,7 +208,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
         self.inc_metric(&format!(
             "query_client_state({}, {})",
             request.client_id, request.height
@@ -212,7 +236,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
         self.inc_metric("query_consensus_state");
         self.inner().query_consensus_state(request, include_proof)
     }
@@ -247,7 +271,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         self.inc_metric("query_connection");
         self.inner().query_connection(request, include_proof)
     }
@@ -272,7 +296,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         self.inc_metric("query_next_sequence_receive");
         self.inner()
             .query_next_sequence_receive(request, include_proof)
@@ -290,7 +314,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         self.inc_metric("query_channel");
         self.inner().query_channel(request, include_proof)
     }
@@ -389,7 +413,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inc_metric("query_packet_commitment");
         self.inner().query_packet_commitment(request, include_proof)
     }
@@ -406,7 +430,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inc_metric("query_packet_receipt");
         self.inner().query_packet_receipt(request, include_proof)
     }
@@ -423,7 +447,7 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.inc_metric("query_packet_acknowledgement");
         self.inner()
             .query_packet_acknowledgement(request, include_proof)
@@ -445,6 +469,14 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().query_unreceived_acknowledgements(request)
     }
 
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        self.inc_metric("batch_query");
+        self.inner().batch_query(requests)
+    }
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error> {
         self.inc_metric("query_txs");
         self.inner().query_txs(request)
@@ -472,4 +504,46 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
     ) -> Result<Vec<CrossChainQueryResponse>, Error> {
         self.inner.cross_chain_query(request)
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        self.inc_metric("query_incentivized_packet");
+        self.inner.query_incentivized_packet(request)
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        self.inc_metric("query_counterparty_payee");
+        self.inner.query_counterparty_payee(request)
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.inc_metric("query_wasm_checksums");
+        self.inner.query_wasm_checksums(request)
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        self.inc_metric("query_upgrade");
+        self.inner.query_upgrade(request, include_proof)
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        self.inc_metric("query_upgrade_error");
+        self.inner.query_upgrade_error(request, include_proof)
+    }
 }
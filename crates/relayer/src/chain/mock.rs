@@ -9,6 +9,7 @@ use tendermint::abci::transaction::Hash;
 use tendermint_testgen::light_block::TmLightBlock;
 use tokio::runtime::Runtime;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState as TmClientState};
 use ibc::clients::ics07_tendermint::consensus_state::ConsensusState as TendermintConsensusState;
 use ibc::clients::ics07_tendermint::header::Header as TendermintHeader;
@@ -17,6 +18,7 @@ use ibc::core::ics03_connection::connection::{ConnectionEnd, IdentifiedConnectio
 use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd};
 use ibc::core::ics04_channel::context::ChannelReader;
 use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 use ibc::core::ics23_commitment::{commitment::CommitmentPrefix, specs::ProofSpecs};
 use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
@@ -27,14 +29,18 @@ use ibc::relayer::ics18_relayer::context::Ics18Context;
 use ibc::signer::Signer;
 use ibc::test_utils::get_dummy_account_id;
 use ibc::Height;
+use ibc_proto::ibc::applications::query::v1::QueryResult;
 
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
 use crate::chain::cosmos::query::custom_query::rest_query;
-use crate::chain::endpoint::{ChainEndpoint, ChainStatus, HealthCheck};
+use crate::chain::endpoint::{ChainEndpoint, ChainStatus, HealthCheck, SimulatedTx};
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::{
-    CrossChainQueryRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-    QueryClientStatesRequest,
+    CrossChainQueryPath, CrossChainQueryRequest, QueryChannelClientStateRequest,
+    QueryChannelRequest, QueryClientStatesRequest, QueryCounterpartyPayeeRequest,
+    QueryIncentivizedPacketRequest, QueryUpgradeErrorRequest, QueryUpgradeRequest,
+    QueryWasmChecksumsRequest,
 };
 use crate::chain::responses::CrossChainQueryResponse;
 use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
@@ -131,6 +137,10 @@ impl ChainEndpoint for MockChain {
         Ok(HealthCheck::Healthy)
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        Ok(HealthCheck::Healthy)
+    }
+
     fn shutdown(self) -> Result<(), Error> {
         Ok(())
     }
@@ -194,6 +204,19 @@ impl ChainEndpoint for MockChain {
         ])
     }
 
+    fn simulate_messages(&mut self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        Ok(vec![SimulatedTx {
+            messages: tracked_msgs.messages().len(),
+            gas_used: Some(0),
+            fee: None,
+            error: None,
+        }])
+    }
+
+    fn broadcast_evidence(&self, _evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn get_signer(&mut self) -> Result<Signer, Error> {
         Ok(get_dummy_account_id())
     }
@@ -214,6 +237,10 @@ impl ChainEndpoint for MockChain {
         Ok(Some(semver::Version::new(3, 0, 0)))
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        Ok(Some(semver::Version::new(0, 45, 0)))
+    }
+
     fn query_balance(&self, _key_name: Option<String>) -> Result<Balance, Error> {
         unimplemented!()
     }
@@ -243,8 +270,13 @@ impl ChainEndpoint for MockChain {
     fn query_client_state(
         &self,
         request: QueryClientStateRequest,
-        _include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+        include_proof: IncludeProof,
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
+        // The mock context does not produce proofs
+        if matches!(include_proof, IncludeProof::Yes) {
+            return Err(Error::empty_response_proof());
+        }
+
         // TODO: unclear what are the scenarios where we need to take height into account.
         let client_state = self
             .context
@@ -267,7 +299,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryConnectionRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -303,7 +335,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryChannelRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -318,7 +350,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryPacketCommitmentRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -333,7 +365,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryPacketReceiptRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -348,7 +380,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryPacketAcknowledgementRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -370,7 +402,7 @@ impl ChainEndpoint for MockChain {
         &self,
         _request: QueryNextSequenceReceiveRequest,
         _include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         unimplemented!()
     }
 
@@ -471,9 +503,11 @@ impl ChainEndpoint for MockChain {
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
-        // IncludeProof::Yes not implemented
-        assert!(matches!(include_proof, IncludeProof::No));
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
+        // The mock context does not produce proofs
+        if matches!(include_proof, IncludeProof::Yes) {
+            return Err(Error::empty_response_proof());
+        }
 
         let consensus_states = consensus_states(&self.context, &request.client_id);
         let consensus_state = consensus_states
@@ -503,7 +537,17 @@ impl ChainEndpoint for MockChain {
         let query_rt = Runtime::new().unwrap();
 
         let mut responses: Vec<CrossChainQueryResponse> = vec![];
-        let tasks = requests
+
+        // The mock chain has no store to query, its ABCI queries succeed with no value
+        let (abci_requests, rest_requests): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .partition(|req| matches!(req.decode_query(), Some(CrossChainQueryPath::Abci { .. })));
+
+        responses.extend(abci_requests.into_iter().map(|req| {
+            CrossChainQueryResponse::new(req.id, QueryResult::Success as i32, vec![], req.height)
+        }));
+
+        let tasks = rest_requests
             .into_iter()
             .map(|req| rest_query(&rest_client, req))
             .collect::<Vec<_>>();
@@ -518,6 +562,43 @@ impl ChainEndpoint for MockChain {
 
         Ok(responses)
     }
+
+    fn query_incentivized_packet(
+        &self,
+        _request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        unimplemented!()
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        _request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        unimplemented!()
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        _request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        unimplemented!()
+    }
+
+    fn query_upgrade(
+        &self,
+        _request: QueryUpgradeRequest,
+        _include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        unimplemented!()
+    }
+
+    fn query_upgrade_error(
+        &self,
+        _request: QueryUpgradeErrorRequest,
+        _include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        unimplemented!()
+    }
 }
 
 pub fn consensus_states(
@@ -557,19 +638,30 @@ pub mod test_utils {
             id: ChainId::from_str(id).unwrap(),
             r#type: ChainType::Mock,
             rpc_addr: "http://127.0.0.1:26656".parse().unwrap(),
+            secondary_rpc_addr: None,
+            archive_rpc_addr: None,
             grpc_addr: "http://127.0.0.1:9090".parse().unwrap(),
+            grpc_transport: Default::default(),
+            proxy: None,
             websocket_addr: "ws://127.0.0.1:26656/websocket".parse().unwrap(),
             rpc_timeout: crate::config::default::rpc_timeout(),
+            query_timeout: crate::config::default::query_timeout(),
+            query_page_size: None,
+            broadcast_timeout: crate::config::default::broadcast_timeout(),
             account_prefix: "".to_string(),
             key_name: "".to_string(),
+            key_names: Vec::new(),
             store_prefix: "".to_string(),
             default_gas: None,
             key_store_type: Default::default(),
+            key_store_folder: None,
+            tenant: None,
             max_gas: None,
             gas_price: GasPrice::new(0.001, "uatom".to_string()),
             gas_adjustment: None,
             gas_multiplier: None,
             fee_granter: None,
+            fee_payer: None,
             max_msg_num: Default::default(),
             max_tx_size: Default::default(),
             clock_drift: Duration::from_secs(5),
@@ -577,11 +669,27 @@ pub mod test_utils {
             trusting_period: Some(Duration::from_secs(14 * 24 * 60 * 60)), // 14 days
             trust_threshold: Default::default(),
             packet_filter: PacketFilter::default(),
+            quotas: Default::default(),
+            top_of_block: Default::default(),
+            event_source: Default::default(),
+            fee_replenishment: None,
+            dynamic_gas_price: None,
+            remote_signer: None,
+            client_tls: None,
+            priorities: Vec::new(),
+            packet_limits: Vec::new(),
+            schedules: Vec::new(),
+            min_fees: Vec::new(),
+            channel_versions: Vec::new(),
+            pre_send_check: Default::default(),
             address_type: AddressType::default(),
             memo_prefix: Default::default(),
+            tx_memo_template: None,
             proof_specs: Default::default(),
             extension_options: Default::default(),
+            check_query_proof_consistency: false,
             sequential_batch_tx: false,
+            client_wrapper: None,
         }
     }
 }
@@ -0,0 +1,256 @@
+//! Scheduling of the requests made to a chain runtime according to the
+//! [`Priority`] of the path on behalf of which they are made.
+//!
+//! The priority of a request is the one of the thread making it, which
+//! is set for the threads of a worker when the worker is spawned, and
+//! inherited by the threads spawned with
+//! [`spawn_background_task`](crate::util::task::spawn_background_task).
+//! The threads of a worker share their priority, which can be raised for
+//! all of them at once after they are spawned.
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::priority::Priority;
+
+std::thread_local! {
+    static CURRENT: RefCell<SharedPriority> = RefCell::new(SharedPriority::default());
+}
+
+/// A priority shared by several threads, e.g. the ones of a worker.
+#[derive(Clone, Debug)]
+pub struct SharedPriority(Arc<AtomicU8>);
+
+impl Default for SharedPriority {
+    fn default() -> Self {
+        Self::new(Priority::default())
+    }
+}
+
+impl SharedPriority {
+    pub fn new(priority: Priority) -> Self {
+        Self(Arc::new(AtomicU8::new(to_u8(priority))))
+    }
+
+    pub fn get(&self) -> Priority {
+        from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Raise the priority to the given one, if it is higher.
+    pub fn raise(&self, priority: Priority) {
+        self.0.fetch_max(to_u8(priority), Ordering::Relaxed);
+    }
+}
+
+fn to_u8(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 0,
+        Priority::Normal => 1,
+        Priority::High => 2,
+    }
+}
+
+fn from_u8(priority: u8) -> Priority {
+    match priority {
+        0 => Priority::Low,
+        1 => Priority::Normal,
+        _ => Priority::High,
+    }
+}
+
+/// The priority of the requests made by the current thread.
+pub fn current() -> Priority {
+    CURRENT.with(|current| current.borrow().get())
+}
+
+/// The priority of the requests made by the current thread, as shared with the
+/// threads it was inherited from or handed down to.
+pub fn current_shared() -> SharedPriority {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Set the priority of the requests made by the current thread.
+pub fn set_current(priority: SharedPriority) {
+    CURRENT.with(|current| *current.borrow_mut() = priority);
+}
+
+/// Set the priority of the requests made by the current thread
+/// until the returned guard is dropped.
+pub fn scoped(priority: SharedPriority) -> PriorityGuard {
+    let previous = current_shared();
+    set_current(priority);
+    PriorityGuard { previous }
+}
+
+/// Restores the previous priority of the current thread when dropped.
+pub struct PriorityGuard {
+    previous: SharedPriority,
+}
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        set_current(self.previous.clone());
+    }
+}
+
+/// The order in which the priorities take turns to have a request handled, which
+/// hands out four requests of high priority and two of normal priority for each one
+/// of low priority, so that no priority is starved by the higher ones.
+const TURNS: [Priority; 7] = [
+    Priority::High,
+    Priority::High,
+    Priority::Normal,
+    Priority::High,
+    Priority::High,
+    Priority::Normal,
+    Priority::Low,
+];
+
+/// The requests received by a chain runtime and not handled yet, handed out
+/// by weighted round-robin between the priorities, see [`TURNS`], and in the
+/// order they were received for a given priority.
+///
+/// The urgent requests, e.g. to shut the runtime down, are handed out before
+/// all the others.
+#[derive(Debug)]
+pub struct PendingRequests<T> {
+    urgent: VecDeque<T>,
+    high: VecDeque<T>,
+    normal: VecDeque<T>,
+    low: VecDeque<T>,
+    turn: usize,
+}
+
+impl<T> Default for PendingRequests<T> {
+    fn default() -> Self {
+        Self {
+            urgent: VecDeque::new(),
+            high: VecDeque::new(),
+            normal: VecDeque::new(),
+            low: VecDeque::new(),
+            turn: 0,
+        }
+    }
+}
+
+impl<T> PendingRequests<T> {
+    pub fn push(&mut self, priority: Priority, request: T) {
+        match priority {
+            Priority::High => self.high.push_back(request),
+            Priority::Normal => self.normal.push_back(request),
+            Priority::Low => self.low.push_back(request),
+        }
+    }
+
+    pub fn push_urgent(&mut self, request: T) {
+        self.urgent.push_back(request);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if let Some(request) = self.urgent.pop_front() {
+            return Some(request);
+        }
+
+        for offset in 0..TURNS.len() {
+            let turn = (self.turn + offset) % TURNS.len();
+
+            let queue = match TURNS[turn] {
+                Priority::High => &mut self.high,
+                Priority::Normal => &mut self.normal,
+                Priority::Low => &mut self.low,
+            };
+
+            if let Some(request) = queue.pop_front() {
+                self.turn = (turn + 1) % TURNS.len();
+                return Some(request);
+            }
+        }
+
+        None
+    }
+
+    pub fn len(&self) -> usize {
+        self.urgent.len() + self.high.len() + self.normal.len() + self.low.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_requests_order() {
+        let mut pending = PendingRequests::default();
+
+        pending.push(Priority::Low, 1);
+        pending.push(Priority::Normal, 2);
+        pending.push(Priority::High, 3);
+        pending.push(Priority::Normal, 4);
+        pending.push(Priority::High, 5);
+
+        let order: Vec<_> = core::iter::from_fn(|| pending.pop()).collect();
+        assert_eq!(order, vec![3, 5, 2, 4, 1]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn urgent_requests_first() {
+        let mut pending = PendingRequests::default();
+
+        pending.push(Priority::High, 1);
+        pending.push_urgent(2);
+        pending.push(Priority::High, 3);
+
+        let order: Vec<_> = core::iter::from_fn(|| pending.pop()).collect();
+        assert_eq!(order, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn low_priority_not_starved() {
+        let mut pending = PendingRequests::default();
+
+        pending.push(Priority::Low, 0);
+
+        // Keep two requests of high priority waiting while handling them one by one
+        for i in 1..=TURNS.len() {
+            pending.push(Priority::High, i);
+            pending.push(Priority::High, i);
+
+            if pending.pop() == Some(0) {
+                return;
+            }
+        }
+
+        panic!("the request of low priority was never handed out");
+    }
+
+    #[test]
+    fn scoped_priority() {
+        assert_eq!(current(), Priority::Normal);
+
+        {
+            let _guard = scoped(SharedPriority::new(Priority::High));
+            assert_eq!(current(), Priority::High);
+        }
+
+        assert_eq!(current(), Priority::Normal);
+    }
+
+    #[test]
+    fn raise_shared_priority() {
+        let priority = SharedPriority::new(Priority::Normal);
+        let _guard = scoped(priority.clone());
+
+        priority.raise(Priority::Low);
+        assert_eq!(current(), Priority::Normal);
+
+        priority.raise(Priority::High);
+        assert_eq!(current(), Priority::High);
+    }
+}
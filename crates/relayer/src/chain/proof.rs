@@ -0,0 +1,107 @@
+//! Proofs returned by the queries of the chain endpoints made with [`IncludeProof::Yes`].
+//!
+//! [`IncludeProof::Yes`]: crate::chain::requests::IncludeProof::Yes
+
+use core::convert::TryFrom;
+
+use ibc::core::ics23_commitment::commitment::{
+    CommitmentPrefix, CommitmentProofBytes, CommitmentRoot,
+};
+use ibc::core::ics23_commitment::error::Error as CommitmentError;
+use ibc::core::ics23_commitment::merkle::{apply_prefix, MerkleProof};
+use ibc::core::ics23_commitment::specs::ProofSpecs;
+use ibc::Height;
+use ibc_proto::ibc::core::commitment::v1::{MerklePath, MerkleRoot};
+
+use crate::error::Error;
+
+/// The proof of the value returned by a query, or of its absence if the value is empty.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryProof {
+    /// The height at which the value was queried
+    pub height: Height,
+    /// The path of the value in the IBC store
+    pub path: String,
+    /// The merkle proof of the value at that path
+    pub proof: MerkleProof,
+}
+
+impl QueryProof {
+    pub fn new(height: Height, path: String, proof: MerkleProof) -> Self {
+        Self {
+            height,
+            path,
+            proof,
+        }
+    }
+
+    /// The height of the header committing to the root the proof is verified against,
+    /// ie. the proof height of the messages carrying the proof.
+    pub fn proof_height(&self) -> Height {
+        self.height.increment()
+    }
+
+    /// Encode the proof to be carried by a message.
+    pub fn to_bytes(&self) -> Result<CommitmentProofBytes, Error> {
+        CommitmentProofBytes::try_from(self.proof.clone()).map_err(Error::malformed_proof)
+    }
+
+    /// Verify that the proof proves the given value, or its absence if empty, in the
+    /// store with the given prefix, against the given root.
+    pub fn verify(
+        &self,
+        specs: &ProofSpecs,
+        prefix: &CommitmentPrefix,
+        root: &CommitmentRoot,
+        value: &[u8],
+    ) -> Result<(), CommitmentError> {
+        if value.is_empty() {
+            self.verify_non_membership(specs, prefix, root)
+        } else {
+            self.verify_membership(specs, prefix, root, value.to_vec())
+        }
+    }
+
+    /// Verify that the proof proves the given value, in the store with the given prefix,
+    /// against the given root.
+    pub fn verify_membership(
+        &self,
+        specs: &ProofSpecs,
+        prefix: &CommitmentPrefix,
+        root: &CommitmentRoot,
+        value: Vec<u8>,
+    ) -> Result<(), CommitmentError> {
+        self.proof
+            .verify_membership(specs, merkle_root(root), self.keys(prefix), value, 0)
+    }
+
+    /// Verify that the proof proves the absence of any value, in the store with the given
+    /// prefix, against the given root.
+    pub fn verify_non_membership(
+        &self,
+        specs: &ProofSpecs,
+        prefix: &CommitmentPrefix,
+        root: &CommitmentRoot,
+    ) -> Result<(), CommitmentError> {
+        self.proof
+            .verify_non_membership(specs, merkle_root(root), self.keys(prefix))
+    }
+
+    fn keys(&self, prefix: &CommitmentPrefix) -> MerklePath {
+        apply_prefix(prefix, vec![self.path.clone()])
+    }
+}
+
+/// The proof returned by a query made with [`IncludeProof::Yes`], which any chain
+/// endpoint must return along with the result.
+///
+/// [`IncludeProof::Yes`]: crate::chain::requests::IncludeProof::Yes
+pub fn require_proof(proof: Option<QueryProof>) -> Result<QueryProof, Error> {
+    proof.ok_or_else(Error::empty_response_proof)
+}
+
+fn merkle_root(root: &CommitmentRoot) -> MerkleRoot {
+    MerkleRoot {
+        hash: root.as_bytes().to_vec(),
+    }
+}
@@ -4,10 +4,15 @@ use core::fmt::Display;
 use ibc::core::ics04_channel::packet::Sequence;
 use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use ibc::events::WithBlockDataType;
+use ibc::signer::Signer;
 use ibc::Height;
 use ibc_proto::cosmos::base::query::v1beta1::PageRequest as RawPageRequest;
+use ibc_proto::ibc::applications::fee::v1::{
+    QueryCounterpartyPayeeRequest as RawQueryCounterpartyPayeeRequest,
+    QueryIncentivizedPacketRequest as RawQueryIncentivizedPacketRequest,
+};
 use ibc_proto::ibc::core::channel::v1::{
-    QueryChannelClientStateRequest as RawQueryChannelClientStateRequest,
+    PacketId, QueryChannelClientStateRequest as RawQueryChannelClientStateRequest,
     QueryChannelsRequest as RawQueryChannelsRequest,
     QueryConnectionChannelsRequest as RawQueryConnectionChannelsRequest,
     QueryNextSequenceReceiveRequest as RawQueryNextSequenceReceiveRequest,
@@ -24,6 +29,7 @@ use ibc_proto::ibc::core::connection::v1::{
     QueryClientConnectionsRequest as RawQueryClientConnectionsRequest,
     QueryConnectionsRequest as RawQueryConnectionsRequest,
 };
+use ibc_proto::ibc::lightclients::wasm::v1::QueryChecksumsRequest as RawQueryChecksumsRequest;
 
 use crate::event::IbcEventWithHeight;
 use serde::{Deserialize, Serialize};
@@ -75,6 +81,26 @@ impl Display for QueryHeight {
     }
 }
 
+/// A range of heights, both inclusive, to scan for events, as opposed to searching
+/// the whole history of the chain up to some height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryHeightRange {
+    pub start: Height,
+    pub end: Height,
+}
+
+impl QueryHeightRange {
+    pub fn contains(&self, height: Height) -> bool {
+        self.start <= height && height <= self.end
+    }
+}
+
+impl Display for QueryHeightRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
 /// Defines a type to be used in select requests to specify whether or not a proof should be
 /// returned along with the response.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -112,6 +138,11 @@ impl PageRequest {
             ..Default::default()
         }
     }
+
+    /// Whether this request asks for all the entries at once, as with [`PageRequest::all`].
+    pub fn is_all(&self) -> bool {
+        self.limit == u64::MAX && self.key.is_empty() && self.offset == 0 && !self.reverse
+    }
 }
 
 impl From<PageRequest> for RawPageRequest {
@@ -168,6 +199,7 @@ pub struct QueryUpgradedConsensusStateRequest {
 pub struct QueryConsensusStatesRequest {
     pub client_id: ClientId,
     pub pagination: Option<PageRequest>,
+    pub height: QueryHeight,
 }
 
 impl From<QueryConsensusStatesRequest> for RawQueryConsensusStatesRequest {
@@ -274,6 +306,7 @@ pub struct QueryPacketCommitmentsRequest {
     pub port_id: PortId,
     pub channel_id: ChannelId,
     pub pagination: Option<PageRequest>,
+    pub height: QueryHeight,
 }
 
 impl From<QueryPacketCommitmentsRequest> for RawQueryPacketCommitmentsRequest {
@@ -323,12 +356,22 @@ pub struct QueryPacketAcknowledgementRequest {
     pub height: QueryHeight,
 }
 
+/// One of the queries about the packets of a channel which can be batched with
+/// the queries about other channels, see [`crate::chain::handle::ChainHandle::batch_query`].
+#[derive(Clone, Debug)]
+pub enum BatchQueryRequest {
+    PacketCommitments(QueryPacketCommitmentsRequest),
+    UnreceivedPackets(QueryUnreceivedPacketsRequest),
+    PacketAcknowledgements(QueryPacketAcknowledgementsRequest),
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QueryPacketAcknowledgementsRequest {
     pub port_id: PortId,
     pub channel_id: ChannelId,
     pub pagination: Option<PageRequest>,
     pub packet_commitment_sequences: Vec<Sequence>,
+    pub height: QueryHeight,
 }
 
 impl From<QueryPacketAcknowledgementsRequest> for RawQueryPacketAcknowledgementsRequest {
@@ -419,6 +462,42 @@ impl CrossChainQueryRequest {
             Err(_) => None,
         }
     }
+
+    /// The query to execute on the queried chain, from the decoded path of the request,
+    /// or none if the path is malformed.
+    pub fn decode_query(&self) -> Option<CrossChainQueryPath> {
+        let path = self.decode_path_or_none()?;
+
+        if path.starts_with("http://") || path.starts_with("https://") {
+            return Some(CrossChainQueryPath::Rest(path));
+        }
+
+        let (path, data) = match path.split_once('?') {
+            Some((path, data)) => (path.to_string(), hex::decode(data).ok()?),
+            None => (path, Vec::new()),
+        };
+
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(CrossChainQueryPath::Abci { path, data })
+    }
+
+    /// The height the query is executed at, zero meaning the latest height.
+    pub fn query_height(&self) -> Option<u64> {
+        self.height.parse().ok()
+    }
+}
+
+/// The query of a [`CrossChainQueryRequest`], decoded from its hex-encoded path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CrossChainQueryPath {
+    /// A GET request to the given URL of a REST endpoint of the queried chain
+    Rest(String),
+    /// An ABCI query of the given path, e.g. `store/bank/key` or the method of a gRPC
+    /// service, with the given data, which is hex-encoded after a `?` in the path
+    Abci { path: String, data: Vec<u8> },
 }
 
 /// Used for queries and not yet standardized in channel's query.proto
@@ -432,8 +511,83 @@ pub enum QueryTxRequest {
 #[derive(Clone, Debug)]
 pub struct QueryTxHash(pub TxHash);
 
+/// Query the fees escrowed on the chain for the relaying of the packet sent with the given
+/// sequence on the given channel, see ICS-29.
+#[derive(Clone, Debug)]
+pub struct QueryIncentivizedPacketRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequence: Sequence,
+    pub height: QueryHeight,
+}
+
+impl From<QueryIncentivizedPacketRequest> for RawQueryIncentivizedPacketRequest {
+    fn from(request: QueryIncentivizedPacketRequest) -> Self {
+        RawQueryIncentivizedPacketRequest {
+            packet_id: Some(PacketId {
+                port_id: request.port_id.to_string(),
+                channel_id: request.channel_id.to_string(),
+                sequence: request.sequence.into(),
+            }),
+            query_height: match request.height {
+                QueryHeight::Latest => 0,
+                QueryHeight::Specific(height) => height.revision_height(),
+            },
+        }
+    }
+}
+
+/// Query the address on the counterparty chain of the given channel to which the fees
+/// of the packets received by the given relayer are paid out, see ICS-29.
+#[derive(Clone, Debug)]
+pub struct QueryCounterpartyPayeeRequest {
+    pub channel_id: ChannelId,
+    pub relayer: Signer,
+}
+
+impl From<QueryCounterpartyPayeeRequest> for RawQueryCounterpartyPayeeRequest {
+    fn from(request: QueryCounterpartyPayeeRequest) -> Self {
+        RawQueryCounterpartyPayeeRequest {
+            channel_id: request.channel_id.to_string(),
+            relayer: request.relayer.to_string(),
+        }
+    }
+}
+
+/// Query the checksums of the Wasm contracts stored on a chain, which implement
+/// the light clients run by its `08-wasm` clients.
+#[derive(Clone, Debug)]
+pub struct QueryWasmChecksumsRequest {
+    pub pagination: Option<PageRequest>,
+}
+
+impl From<QueryWasmChecksumsRequest> for RawQueryChecksumsRequest {
+    fn from(request: QueryWasmChecksumsRequest) -> Self {
+        RawQueryChecksumsRequest {
+            pagination: request.pagination.map(|pagination| pagination.into()),
+        }
+    }
+}
+
+/// Query the upgrade proposed by a channel end, during its upgrade handshake.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryUpgradeRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub height: QueryHeight,
+}
+
+/// Query the error receipt written by a channel end when it aborted its latest upgrade.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryUpgradeErrorRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub height: QueryHeight,
+}
+
 /// Used to query a packet event, identified by `event_id`, for specific channel and sequences.
-/// The query is preformed for the chain context at `height`.
+/// The query is preformed for the chain context at `height`, and only scans the blocks
+/// within `height_range` if set.
 #[derive(Clone, Debug)]
 pub struct QueryPacketEventDataRequest {
     pub event_id: WithBlockDataType,
@@ -443,6 +597,7 @@ pub struct QueryPacketEventDataRequest {
     pub destination_port_id: PortId,
     pub sequences: Vec<Sequence>,
     pub height: QueryHeight,
+    pub height_range: Option<QueryHeightRange>,
 }
 
 /// Query request for a single client event, identified by `event_id`, for `client_id`.
@@ -1,7 +1,11 @@
 use crate::chain::handle::ChainHandle;
+use crate::error::Error;
 use core::fmt::{Display, Formatter};
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics23_commitment::specs::ProofSpecs;
+use ibc::Height;
 use ibc_proto::google::protobuf::Any;
-use ibc_proto::ibc::applications::query::v1::MsgSubmitCrossChainQueryResult;
+use ibc_proto::ibc::applications::query::v1::{MsgSubmitCrossChainQueryResult, QueryResult};
 use prost;
 use serde::{Deserialize, Serialize};
 
@@ -9,36 +13,57 @@ use serde::{Deserialize, Serialize};
 pub struct CrossChainQueryResponse {
     pub id: String,
     pub result: i32,
-    pub data: String,
+    pub data: Vec<u8>,
     pub height: String,
+    /// The specifications of the proofs of the state of the queried chain, which the
+    /// querying chain verifies them against, for the queries of its store
+    pub proof_specs: Option<ProofSpecs>,
 }
 
 impl CrossChainQueryResponse {
-    pub fn new(id: String, result: i32, data: String, height: String) -> Self {
+    pub fn new(id: String, result: i32, data: Vec<u8>, height: String) -> Self {
         Self {
             id,
             result,
             data,
             height,
+            proof_specs: None,
         }
     }
 
-    pub fn to_any<QueryingChain: ChainHandle>(&self, handle: &QueryingChain) -> Any {
-        let mut encoded = Vec::new();
+    /// The response to a query which could not be answered in time.
+    pub fn timeout(id: String, height: String) -> Self {
+        Self::new(id, QueryResult::Timeout as i32, Vec::new(), height)
+    }
+
+    pub fn with_proof_specs(self, proof_specs: ProofSpecs) -> Self {
+        Self {
+            proof_specs: Some(proof_specs),
+            ..self
+        }
+    }
+
+    pub fn to_any<QueryingChain: ChainHandle>(&self, handle: &QueryingChain) -> Result<Any, Error> {
+        let query_height = self.height.parse().map_err(|_| {
+            Error::invalid_cross_chain_query(
+                self.id.clone(),
+                format!("invalid query height '{}'", self.height),
+            )
+        })?;
 
         let msg_submit_cross_chain_query_result = MsgSubmitCrossChainQueryResult {
             id: self.id.to_string(),
-            query_height: self.height.parse().unwrap(),
+            query_height,
             result: self.result,
-            data: self.data.as_bytes().to_vec(),
-            sender: handle.get_signer().unwrap().to_string(),
-            proof_specs: vec![],
+            data: self.data.clone(),
+            sender: handle.get_signer()?.to_string(),
+            proof_specs: self.proof_specs.clone().map(Into::into).unwrap_or_default(),
         };
-        prost::Message::encode(&msg_submit_cross_chain_query_result, &mut encoded).unwrap();
-        Any {
+
+        Ok(Any {
             type_url: "/ibc.applications.ibc_query.v1.MsgSubmitCrossChainQueryResult".to_string(),
-            value: encoded,
-        }
+            value: prost::Message::encode_to_vec(&msg_submit_cross_chain_query_result),
+        })
     }
 }
 
@@ -47,7 +72,20 @@ impl Display for CrossChainQueryResponse {
         write!(
             f,
             "id: {}, data: {}, height: {}",
-            self.id, self.data, self.height
+            self.id,
+            String::from_utf8_lossy(&self.data),
+            self.height
         )
     }
 }
+
+/// The answer to a [`BatchQueryRequest`](crate::chain::requests::BatchQueryRequest), of the same kind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchQueryResponse {
+    /// The sequences of the packet commitments, and the height they were queried at
+    PacketCommitments(Vec<Sequence>, Height),
+    /// The sequences of the unreceived packets
+    UnreceivedPackets(Vec<Sequence>),
+    /// The sequences of the packet acknowledgements, and the height they were queried at
+    PacketAcknowledgements(Vec<Sequence>, Height),
+}
@@ -1,10 +1,12 @@
 use alloc::sync::Arc;
+use core::time::Duration;
 use std::thread;
 
 use crossbeam_channel as channel;
 use tokio::runtime::Runtime as TokioRuntime;
-use tracing::{error, Span};
+use tracing::{error, warn, Span};
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::{
     core::{
         ics02_client::events::UpdateClient,
@@ -15,6 +17,7 @@ use ibc::{
         ics04_channel::{
             channel::{ChannelEnd, IdentifiedChannelEnd},
             packet::{PacketMsgType, Sequence},
+            upgrade::{ErrorReceipt, Upgrade},
         },
         ics23_commitment::{commitment::CommitmentPrefix, merkle::MerkleProof},
         ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -25,12 +28,13 @@ use ibc::{
     Height,
 };
 
+use crate::chain::proof::QueryProof;
 use crate::chain::requests::CrossChainQueryRequest;
-use crate::chain::responses::CrossChainQueryResponse;
+use crate::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use crate::{
     account::Balance,
     client_state::{AnyClientState, IdentifiedAnyClientState},
-    config::ChainConfig,
+    config::{priority::Priority, quota::RuntimeQuotas, ChainConfig},
     connection::ConnectionMsgType,
     consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight},
     denom::DenomTrace,
@@ -47,22 +51,36 @@ use crate::{
 
 use super::{
     client::ClientSettings,
-    endpoint::{ChainEndpoint, ChainStatus, HealthCheck},
+    endpoint::{ChainEndpoint, ChainStatus, DeferredCommit, HealthCheck, SimulatedTx},
     handle::{ChainHandle, ChainRequest, ReplyTo, Subscription},
+    priority::PendingRequests,
     requests::{
-        IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-        QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-        QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-        QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-        QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+        BatchQueryRequest, IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest,
+        QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+        QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+        QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+        QueryConsensusStatesRequest, QueryCounterpartyPayeeRequest, QueryHostConsensusStateRequest,
+        QueryIncentivizedPacketRequest, QueryNextSequenceReceiveRequest,
         QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
         QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
         QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-        QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+        QueryUpgradeErrorRequest, QueryUpgradeRequest, QueryUpgradedClientStateRequest,
+        QueryUpgradedConsensusStateRequest, QueryWasmChecksumsRequest,
     },
     tracking::TrackedMsgs,
 };
 
+/// How long the runtime waits for a request or an event before checking
+/// again for pending requests, when there are none.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many threads of each runtime wait for the commit of the transactions
+/// sent by the runtime, while it handles the next requests.
+const COMMIT_WAITERS: usize = 4;
+
+/// A transaction whose commit is to be waited for, and where to reply once it is.
+type PendingCommit = (DeferredCommit, ReplyTo<Vec<IbcEventWithHeight>>);
+
 pub struct Threads {
     pub chain_runtime: thread::JoinHandle<()>,
     pub event_monitor: Option<thread::JoinHandle<()>>,
@@ -132,11 +150,11 @@ pub struct ChainRuntime<Endpoint: ChainEndpoint> {
 
     /// The sender side of a channel to this runtime. Any `ChainHandle` can use this to send
     /// chain requests to this runtime
-    request_sender: channel::Sender<(Span, ChainRequest)>,
+    request_sender: channel::Sender<(Span, Priority, ChainRequest)>,
 
     /// The receiving side of a channel to this runtime. The runtime consumes chain requests coming
     /// in through this channel.
-    request_receiver: channel::Receiver<(Span, ChainRequest)>,
+    request_receiver: channel::Receiver<(Span, Priority, ChainRequest)>,
 
     /// An event bus, for broadcasting events that this runtime receives (via `event_receiver`) to subscribers
     event_bus: EventBus<Arc<MonitorResult<EventBatch>>>,
@@ -144,6 +162,13 @@ pub struct ChainRuntime<Endpoint: ChainEndpoint> {
     /// Interface to the event monitor
     event_monitor_ctrl: EventMonitorCtrl,
 
+    /// Limits on the resources used by this runtime
+    quotas: RuntimeQuotas,
+
+    /// The sender side of the channel feeding the threads waiting for the commit
+    /// of the transactions, which stop once it is dropped along with the runtime
+    commit_sender: channel::Sender<PendingCommit>,
+
     #[allow(dead_code)]
     rt: Arc<TokioRuntime>, // Making this future-proof, so we keep the runtime around.
 }
@@ -190,15 +215,40 @@ where
 
     /// Basic constructor
     fn new(chain: Endpoint, rt: Arc<TokioRuntime>) -> Self {
-        let (request_sender, request_receiver) = channel::unbounded();
+        let quotas = chain.config().quotas;
+
+        // Make the callers wait once the runtime has as many requests to handle as allowed
+        let (request_sender, request_receiver) = match quotas.request_queue_bound() {
+            Some(max) => channel::bounded(max),
+            None => channel::unbounded(),
+        };
+
+        // The transactions waiting for a commit are bounded as the requests are, so that
+        // the runtime stops sending transactions once too many of them are in flight
+        let (commit_sender, commit_receiver) = match quotas.request_queue_bound() {
+            Some(max) => channel::bounded(max),
+            None => channel::unbounded(),
+        };
+
+        for _ in 0..COMMIT_WAITERS {
+            let commit_receiver = commit_receiver.clone();
+
+            thread::spawn(move || {
+                for (wait_for_commit, reply_to) in commit_receiver {
+                    let _ = reply_to.send(wait_for_commit());
+                }
+            });
+        }
 
         Self {
             rt,
             chain,
             request_sender,
             request_receiver,
-            event_bus: EventBus::new(),
+            event_bus: EventBus::with_capacity(quotas.max_queued_event_batches),
             event_monitor_ctrl: EventMonitorCtrl::none(),
+            quotas,
+            commit_sender,
         }
     }
 
@@ -210,13 +260,41 @@ where
     }
 
     fn run(mut self) -> Result<(), Error> {
+        let mut pending = PendingRequests::default();
+        let no_requests = channel::never();
+
         loop {
+            // Do not block on the channels while there are requests to handle
+            let idle_timeout = if pending.is_empty() {
+                IDLE_TIMEOUT
+            } else {
+                Duration::ZERO
+            };
+
+            // Leave the requests in the channel while the runtime has too many of them
+            // to handle, so that the callers wait instead of queuing up more requests
+            let saturated = self.quotas.requests_exhausted(pending.len());
+            let requests = if saturated {
+                &no_requests
+            } else {
+                &self.request_receiver
+            };
+
             channel::select! {
                 recv(self.event_monitor_ctrl.recv()) -> event_batch => {
                     match event_batch {
                         Ok(event_batch) => {
-                            self.event_bus
+                            let dropped = self.event_bus
                                 .broadcast(Arc::new(event_batch));
+
+                            if dropped > 0 {
+                                warn!(
+                                    chain = %self.chain.id(),
+                                    "dropped a batch of events for {} subscribers which are lagging behind, \
+                                    the packets it refers to will be relayed on the next packet clearing",
+                                    dropped
+                                );
+                            }
                         },
                         Err(e) => {
                             error!("received error via event bus: {}", e);
@@ -224,213 +302,311 @@ where
                         },
                     }
                 },
-                recv(self.request_receiver) -> event => {
-                    let (span, event) = match event {
-                        Ok((span, event)) => (span, event),
+                recv(requests) -> request => {
+                    match request {
+                        Ok((span, priority, request)) => enqueue(&mut pending, span, priority, request),
                         Err(e) => {
                             error!("received error via chain request channel: {}", e);
                             continue;
                         }
-                    };
-
-                    let _span = span.entered();
-
-                    match event {
-                        ChainRequest::Shutdown { reply_to } => {
-                            self.event_monitor_ctrl.shutdown()?;
-
-                            let res = self.chain.shutdown();
-                            reply_to.send(res)
-                                .map_err(Error::send)?;
-
-                            break;
-                        },
-
-                        ChainRequest::HealthCheck { reply_to } => {
-                            self.health_check(reply_to)?
-                        },
-
-                        ChainRequest::Subscribe { reply_to } => {
-                            self.subscribe(reply_to)?
-                        },
-
-                        ChainRequest::SendMessagesAndWaitCommit { tracked_msgs, reply_to } => {
-                            self.send_messages_and_wait_commit(tracked_msgs, reply_to)?
-                        },
-
-                        ChainRequest::SendMessagesAndWaitCheckTx { tracked_msgs, reply_to } => {
-                            self.send_messages_and_wait_check_tx(tracked_msgs, reply_to)?
-                        },
-
-                        ChainRequest::Signer { reply_to } => {
-                            self.get_signer(reply_to)?
-                        },
-
-                        ChainRequest::Config { reply_to } => {
-                            self.get_config(reply_to)?
-                        },
-
-                        ChainRequest::GetKey { reply_to } => {
-                            self.get_key(reply_to)?
-                        },
-
-                        ChainRequest::AddKey { key_name, key, reply_to } => {
-                            self.add_key(key_name, key, reply_to)?
-                        },
-
-                        ChainRequest::IbcVersion { reply_to } => {
-                            self.ibc_version(reply_to)?
-                        },
-
-                        ChainRequest::BuildHeader { trusted_height, target_height, client_state, reply_to } => {
-                            self.build_header(trusted_height, target_height, client_state, reply_to)?
-                        },
-
-                        ChainRequest::BuildClientState { height, settings, reply_to } => {
-                            self.build_client_state(height, settings, reply_to)?
-                        },
-
-                        ChainRequest::BuildConsensusState { trusted, target, client_state, reply_to } => {
-                            self.build_consensus_state(trusted, target, client_state, reply_to)?
-                        },
-
-                        ChainRequest::BuildMisbehaviour { client_state, update_event, reply_to } => {
-                            self.check_misbehaviour(update_event, client_state, reply_to)?
-                        },
-
-                        ChainRequest::BuildConnectionProofsAndClientState { message_type, connection_id, client_id, height, reply_to } => {
-                            self.build_connection_proofs_and_client_state(message_type, connection_id, client_id, height, reply_to)?
-                        },
-
-                        ChainRequest::BuildChannelProofs { port_id, channel_id, height, reply_to } => {
-                            self.build_channel_proofs(port_id, channel_id, height, reply_to)?
-                        },
-
-                        ChainRequest::QueryBalance { key_name, reply_to } => {
-                            self.query_balance(key_name, reply_to)?
-                        },
-
-                        ChainRequest::QueryDenomTrace { hash, reply_to } => {
-                            self.query_denom_trace(hash, reply_to)?
-                        },
-
-                        ChainRequest::QueryApplicationStatus { reply_to } => {
-                            self.query_application_status(reply_to)?
-                        },
-
-                        ChainRequest::QueryClients { request, reply_to } => {
-                            self.query_clients(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryClientConnections { request, reply_to } => {
-                            self.query_client_connections(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryClientState { request, include_proof, reply_to } => {
-                            self.query_client_state(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryConsensusStates { request, reply_to } => {
-                            self.query_consensus_states(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryConsensusState { request, include_proof, reply_to } => {
-                            self.query_consensus_state(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryUpgradedClientState { request, reply_to } => {
-                            self.query_upgraded_client_state(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryUpgradedConsensusState { request, reply_to } => {
-                            self.query_upgraded_consensus_state(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryCommitmentPrefix { reply_to } => {
-                            self.query_commitment_prefix(reply_to)?
-                        },
-
-                        ChainRequest::QueryCompatibleVersions { reply_to } => {
-                            self.query_compatible_versions(reply_to)?
-                        },
-
-                        ChainRequest::QueryConnection { request, include_proof, reply_to } => {
-                            self.query_connection(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryConnections { request, reply_to } => {
-                            self.query_connections(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryConnectionChannels { request, reply_to } => {
-                            self.query_connection_channels(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryChannels { request, reply_to } => {
-                            self.query_channels(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryChannel { request, include_proof, reply_to } => {
-                            self.query_channel(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryChannelClientState { request, reply_to } => {
-                            self.query_channel_client_state(request, reply_to)?
-                        },
-
-                        ChainRequest::BuildPacketProofs { packet_type, port_id, channel_id, sequence, height, reply_to } => {
-                            self.build_packet_proofs(packet_type, port_id, channel_id, sequence, height, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketCommitment { request, include_proof, reply_to } => {
-                            self.query_packet_commitment(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketCommitments { request, reply_to } => {
-                            self.query_packet_commitments(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketReceipt { request, include_proof, reply_to } => {
-                            self.query_packet_receipt(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryUnreceivedPackets { request, reply_to } => {
-                            self.query_unreceived_packets(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketAcknowledgement { request, include_proof, reply_to } => {
-                            self.query_packet_acknowledgement(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketAcknowledgements { request, reply_to } => {
-                            self.query_packet_acknowledgements(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryUnreceivedAcknowledgement { request, reply_to } => {
-                            self.query_unreceived_acknowledgement(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryNextSequenceReceive { request, include_proof, reply_to } => {
-                            self.query_next_sequence_receive(request, include_proof, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketEventDataFromTxs { request, reply_to } => {
-                            self.query_txs(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryPacketEventDataFromBlocks { request, reply_to } => {
-                            self.query_blocks(request, reply_to)?
-                        },
-
-                        ChainRequest::QueryHostConsensusState { request, reply_to } => {
-                            self.query_host_consensus_state(request, reply_to)?
-                        },
+                    }
 
-                        ChainRequest::CrossChainQuery { request, reply_to } => {
-                            self.cross_chain_query(request, reply_to)?
+                    // Pick up all the requests which are already waiting, so that
+                    // the ones with a higher priority are handled first
+                    while !self.quotas.requests_exhausted(pending.len()) {
+                        match self.request_receiver.try_recv() {
+                            Ok((span, priority, request)) => enqueue(&mut pending, span, priority, request),
+                            Err(_) => break,
                         }
                     }
                 },
+                default(idle_timeout) => {},
+            }
+
+            let (span, event) = match pending.pop() {
+                Some(request) => request,
+                None => continue,
+            };
+
+            let _span = span.entered();
+
+            match event {
+                ChainRequest::Shutdown { reply_to } => {
+                    self.event_monitor_ctrl.shutdown()?;
+
+                    let res = self.chain.shutdown();
+                    reply_to.send(res).map_err(Error::send)?;
+
+                    break;
+                }
+
+                ChainRequest::HealthCheck { reply_to } => self.health_check(reply_to)?,
+
+                ChainRequest::CheckKey { reply_to } => self.check_key(reply_to)?,
+
+                ChainRequest::Subscribe { reply_to } => self.subscribe(reply_to)?,
+
+                ChainRequest::SendMessagesAndWaitCommit {
+                    tracked_msgs,
+                    reply_to,
+                } => self.send_messages_and_wait_commit(tracked_msgs, reply_to)?,
+
+                ChainRequest::SendMessagesAndWaitCheckTx {
+                    tracked_msgs,
+                    reply_to,
+                } => self.send_messages_and_wait_check_tx(tracked_msgs, reply_to)?,
+
+                ChainRequest::SimulateMessages {
+                    tracked_msgs,
+                    reply_to,
+                } => self.simulate_messages(tracked_msgs, reply_to)?,
+
+                ChainRequest::BroadcastEvidence { evidence, reply_to } => {
+                    self.broadcast_evidence(evidence, reply_to)?
+                }
+
+                ChainRequest::Signer { reply_to } => self.get_signer(reply_to)?,
+
+                ChainRequest::Config { reply_to } => self.get_config(reply_to)?,
+
+                ChainRequest::GetKey { reply_to } => self.get_key(reply_to)?,
+
+                ChainRequest::AddKey {
+                    key_name,
+                    key,
+                    reply_to,
+                } => self.add_key(key_name, key, reply_to)?,
+
+                ChainRequest::IbcVersion { reply_to } => self.ibc_version(reply_to)?,
+
+                ChainRequest::SdkVersion { reply_to } => self.sdk_version(reply_to)?,
+
+                ChainRequest::BuildHeader {
+                    trusted_height,
+                    target_height,
+                    client_state,
+                    reply_to,
+                } => self.build_header(trusted_height, target_height, client_state, reply_to)?,
+
+                ChainRequest::BuildClientState {
+                    height,
+                    settings,
+                    reply_to,
+                } => self.build_client_state(height, settings, reply_to)?,
+
+                ChainRequest::BuildConsensusState {
+                    trusted,
+                    target,
+                    client_state,
+                    reply_to,
+                } => self.build_consensus_state(trusted, target, client_state, reply_to)?,
+
+                ChainRequest::BuildMisbehaviour {
+                    client_state,
+                    update_event,
+                    reply_to,
+                } => self.check_misbehaviour(update_event, client_state, reply_to)?,
+
+                ChainRequest::BuildConnectionProofsAndClientState {
+                    message_type,
+                    connection_id,
+                    client_id,
+                    height,
+                    reply_to,
+                } => self.build_connection_proofs_and_client_state(
+                    message_type,
+                    connection_id,
+                    client_id,
+                    height,
+                    reply_to,
+                )?,
+
+                ChainRequest::BuildChannelProofs {
+                    port_id,
+                    channel_id,
+                    height,
+                    reply_to,
+                } => self.build_channel_proofs(port_id, channel_id, height, reply_to)?,
+
+                ChainRequest::QueryBalance { key_name, reply_to } => {
+                    self.query_balance(key_name, reply_to)?
+                }
+
+                ChainRequest::QueryDenomTrace { hash, reply_to } => {
+                    self.query_denom_trace(hash, reply_to)?
+                }
+
+                ChainRequest::QueryApplicationStatus { reply_to } => {
+                    self.query_application_status(reply_to)?
+                }
+
+                ChainRequest::QueryClients { request, reply_to } => {
+                    self.query_clients(request, reply_to)?
+                }
+
+                ChainRequest::QueryClientConnections { request, reply_to } => {
+                    self.query_client_connections(request, reply_to)?
+                }
+
+                ChainRequest::QueryClientState {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_client_state(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryConsensusStates { request, reply_to } => {
+                    self.query_consensus_states(request, reply_to)?
+                }
+
+                ChainRequest::QueryConsensusState {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_consensus_state(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryUpgradedClientState { request, reply_to } => {
+                    self.query_upgraded_client_state(request, reply_to)?
+                }
+
+                ChainRequest::QueryUpgradedConsensusState { request, reply_to } => {
+                    self.query_upgraded_consensus_state(request, reply_to)?
+                }
+
+                ChainRequest::QueryCommitmentPrefix { reply_to } => {
+                    self.query_commitment_prefix(reply_to)?
+                }
+
+                ChainRequest::QueryCompatibleVersions { reply_to } => {
+                    self.query_compatible_versions(reply_to)?
+                }
+
+                ChainRequest::QueryConnection {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_connection(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryConnections { request, reply_to } => {
+                    self.query_connections(request, reply_to)?
+                }
+
+                ChainRequest::QueryConnectionChannels { request, reply_to } => {
+                    self.query_connection_channels(request, reply_to)?
+                }
+
+                ChainRequest::QueryChannels { request, reply_to } => {
+                    self.query_channels(request, reply_to)?
+                }
+
+                ChainRequest::QueryChannel {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_channel(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryChannelClientState { request, reply_to } => {
+                    self.query_channel_client_state(request, reply_to)?
+                }
+
+                ChainRequest::BuildPacketProofs {
+                    packet_type,
+                    port_id,
+                    channel_id,
+                    sequence,
+                    height,
+                    reply_to,
+                } => self.build_packet_proofs(
+                    packet_type,
+                    port_id,
+                    channel_id,
+                    sequence,
+                    height,
+                    reply_to,
+                )?,
+
+                ChainRequest::QueryPacketCommitment {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_packet_commitment(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryPacketCommitments { request, reply_to } => {
+                    self.query_packet_commitments(request, reply_to)?
+                }
+
+                ChainRequest::QueryPacketReceipt {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_packet_receipt(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryUnreceivedPackets { request, reply_to } => {
+                    self.query_unreceived_packets(request, reply_to)?
+                }
+
+                ChainRequest::QueryPacketAcknowledgement {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_packet_acknowledgement(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryPacketAcknowledgements { request, reply_to } => {
+                    self.query_packet_acknowledgements(request, reply_to)?
+                }
+
+                ChainRequest::QueryUnreceivedAcknowledgement { request, reply_to } => {
+                    self.query_unreceived_acknowledgement(request, reply_to)?
+                }
+
+                ChainRequest::BatchQuery { requests, reply_to } => {
+                    self.batch_query(requests, reply_to)?
+                }
+
+                ChainRequest::QueryNextSequenceReceive {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_next_sequence_receive(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryPacketEventDataFromTxs { request, reply_to } => {
+                    self.query_txs(request, reply_to)?
+                }
+
+                ChainRequest::QueryPacketEventDataFromBlocks { request, reply_to } => {
+                    self.query_blocks(request, reply_to)?
+                }
+
+                ChainRequest::QueryHostConsensusState { request, reply_to } => {
+                    self.query_host_consensus_state(request, reply_to)?
+                }
+
+                ChainRequest::CrossChainQuery { request, reply_to } => {
+                    self.cross_chain_query(request, reply_to)?
+                }
+
+                ChainRequest::QueryIncentivizedPacket { request, reply_to } => {
+                    self.query_incentivized_packet(request, reply_to)?
+                }
+
+                ChainRequest::QueryCounterpartyPayee { request, reply_to } => {
+                    self.query_counterparty_payee(request, reply_to)?
+                }
+
+                ChainRequest::QueryWasmChecksums { request, reply_to } => {
+                    self.query_wasm_checksums(request, reply_to)?
+                }
+
+                ChainRequest::QueryUpgrade {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_upgrade(request, include_proof, reply_to)?,
+
+                ChainRequest::QueryUpgradeError {
+                    request,
+                    include_proof,
+                    reply_to,
+                } => self.query_upgrade_error(request, include_proof, reply_to)?,
             }
         }
 
@@ -442,11 +618,23 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn check_key(&mut self, reply_to: ReplyTo<HealthCheck>) -> Result<(), Error> {
+        let result = self.chain.check_key();
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn subscribe(&mut self, reply_to: ReplyTo<Subscription>) -> Result<(), Error> {
         if !self.event_monitor_ctrl.is_live() {
             self.enable_event_monitor()?;
         }
 
+        if let Some(max) = self.quotas.max_subscriptions {
+            if self.event_bus.subscribers() >= max {
+                let error = Error::max_subscriptions(self.chain.id().clone(), max);
+                return reply_to.send(Err(error)).map_err(Error::send);
+            }
+        }
+
         let subscription = self.event_bus.subscribe();
         reply_to.send(Ok(subscription)).map_err(Error::send)
     }
@@ -465,8 +653,13 @@ where
         tracked_msgs: TrackedMsgs,
         reply_to: ReplyTo<Vec<IbcEventWithHeight>>,
     ) -> Result<(), Error> {
-        let result = self.chain.send_messages_and_wait_commit(tracked_msgs);
-        reply_to.send(result).map_err(Error::send)
+        let wait_for_commit = self.chain.send_messages_and_defer_commit(tracked_msgs);
+
+        // The next requests are handled while waiting for the commit, so that the transactions
+        // of the other workers are broadcast and committed in the same block
+        self.commit_sender
+            .send((wait_for_commit, reply_to))
+            .map_err(Error::send)
     }
 
     fn send_messages_and_wait_check_tx(
@@ -478,6 +671,24 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn simulate_messages(
+        &mut self,
+        tracked_msgs: TrackedMsgs,
+        reply_to: ReplyTo<Vec<SimulatedTx>>,
+    ) -> Result<(), Error> {
+        let result = self.chain.simulate_messages(tracked_msgs);
+        reply_to.send(result).map_err(Error::send)
+    }
+
+    fn broadcast_evidence(
+        &self,
+        evidence: tendermint::evidence::Evidence,
+        reply_to: ReplyTo<()>,
+    ) -> Result<(), Error> {
+        let result = self.chain.broadcast_evidence(evidence);
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn query_balance(
         &self,
         key_name: Option<String>,
@@ -527,6 +738,11 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn sdk_version(&mut self, reply_to: ReplyTo<Option<semver::Version>>) -> Result<(), Error> {
+        let result = self.chain.sdk_version();
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn build_header(
         &mut self,
         trusted_height: Height,
@@ -631,7 +847,7 @@ where
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(AnyClientState, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(AnyClientState, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let res = self.chain.query_client_state(request, include_proof);
 
@@ -661,7 +877,7 @@ where
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(AnyConsensusState, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(AnyConsensusState, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let res = self.chain.query_consensus_state(request, include_proof);
 
@@ -692,7 +908,7 @@ where
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(ConnectionEnd, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(ConnectionEnd, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let connection_end = self.chain.query_connection(request, include_proof);
         reply_to.send(connection_end).map_err(Error::send)
@@ -729,7 +945,7 @@ where
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(ChannelEnd, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(ChannelEnd, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let result = self.chain.query_channel(request, include_proof);
         reply_to.send(result).map_err(Error::send)
@@ -778,7 +994,7 @@ where
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let result = self.chain.query_packet_commitment(request, include_proof);
         reply_to.send(result).map_err(Error::send)
@@ -797,7 +1013,7 @@ where
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let result = self.chain.query_packet_receipt(request, include_proof);
         reply_to.send(result).map_err(Error::send)
@@ -816,7 +1032,7 @@ where
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Vec<u8>, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Vec<u8>, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let result = self
             .chain
@@ -842,11 +1058,20 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+        reply_to: ReplyTo<Vec<Result<BatchQueryResponse, Error>>>,
+    ) -> Result<(), Error> {
+        let result = self.chain.batch_query(requests);
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn query_next_sequence_receive(
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-        reply_to: ReplyTo<(Sequence, Option<MerkleProof>)>,
+        reply_to: ReplyTo<(Sequence, Option<QueryProof>)>,
     ) -> Result<(), Error> {
         let result = self
             .chain
@@ -900,4 +1125,77 @@ where
 
         Ok(())
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+        reply_to: ReplyTo<Option<IdentifiedPacketFees>>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_incentivized_packet(request);
+        reply_to.send(result).map_err(Error::send)?;
+
+        Ok(())
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+        reply_to: ReplyTo<Option<Signer>>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_counterparty_payee(request);
+        reply_to.send(result).map_err(Error::send)?;
+
+        Ok(())
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+        reply_to: ReplyTo<Vec<Vec<u8>>>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_wasm_checksums(request);
+        reply_to.send(result).map_err(Error::send)?;
+
+        Ok(())
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(Upgrade, Option<QueryProof>)>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_upgrade(request, include_proof);
+        reply_to.send(result).map_err(Error::send)?;
+
+        Ok(())
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(ErrorReceipt, Option<QueryProof>)>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_upgrade_error(request, include_proof);
+        reply_to.send(result).map_err(Error::send)?;
+
+        Ok(())
+    }
+}
+
+/// Queue the given request according to its priority, except for the requests to shut
+/// the runtime down and to check its health, which are handled before all the others.
+fn enqueue(
+    pending: &mut PendingRequests<(Span, ChainRequest)>,
+    span: Span,
+    priority: Priority,
+    request: ChainRequest,
+) {
+    match request {
+        ChainRequest::Shutdown { .. } | ChainRequest::HealthCheck { .. } => {
+            pending.push_urgent((span, request))
+        }
+        _ => pending.push(priority, (span, request)),
+    }
 }
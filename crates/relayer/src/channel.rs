@@ -6,6 +6,7 @@ use serde::Serialize;
 use tracing::{debug, error, info, warn};
 
 pub use error::ChannelError;
+use ibc::applications::ics27_ica;
 use ibc::core::ics04_channel::channel::{
     ChannelEnd, Counterparty, IdentifiedChannelEnd, Order, State,
 };
@@ -38,6 +39,7 @@ use crate::util::retry::{retry_count, RetryResult};
 use crate::util::task::Next;
 
 pub mod error;
+pub mod upgrade;
 pub mod version;
 
 mod handshake_retry {
@@ -763,6 +765,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
             // return anyway as the final step is to be done by the counterparty worker.
             (State::TryOpen, State::Open) => return Ok((None, Next::Abort)),
 
+            // The channel was closed on this end, the close handshake is completed by
+            // the packet worker once the packets in flight are flushed to the counterparty.
+            (State::Closed, _) => return Ok((None, Next::Abort)),
+
             _ => None,
         };
 
@@ -797,6 +803,8 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
             IbcEvent::OpenTryChannel(_) => State::TryOpen,
             IbcEvent::OpenAckChannel(_) => State::Open,
             IbcEvent::OpenConfirmChannel(_) => State::Open,
+            IbcEvent::CloseInitChannel(_) => State::Closed,
+            IbcEvent::CloseConfirmChannel(_) => State::Closed,
             _ => State::Uninitialized,
         };
 
@@ -815,6 +823,38 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         })
     }
 
+    /// The version resolved for the destination channel end, by a registered resolver
+    /// or from the `channel_versions` configured for the destination chain.
+    fn resolve_dst_version(&self, counterparty_version: Option<&Version>) -> Option<Version> {
+        let configured = self
+            .dst_chain()
+            .config()
+            .map(|config| config.channel_versions)
+            .unwrap_or_else(|e| {
+                warn!(
+                    chain = %self.dst_chain().id(),
+                    "failed to get the config of the chain, ignoring its channel versions: {}",
+                    e
+                );
+
+                Vec::new()
+            });
+
+        version::resolve(
+            &configured,
+            &version::VersionContext {
+                chain_id: &self.dst_chain().id(),
+                port_id: self.dst_port_id(),
+                connection_id: self.dst_connection_id(),
+                counterparty_chain_id: &self.src_chain().id(),
+                counterparty_port_id: self.src_port_id(),
+                counterparty_connection_id: self.src_connection_id(),
+                ordering: self.ordering,
+                counterparty_version,
+            },
+        )
+    }
+
     pub fn build_chan_open_init(&self) -> Result<Vec<Any>, ChannelError> {
         let signer = self
             .dst_chain()
@@ -824,11 +864,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         let counterparty = Counterparty::new(self.src_port_id().clone(), None);
 
         // If the user supplied a version, use that.
-        // Otherwise, either use the version defined for the `transfer`
-        // or an empty version if the port is non-standard.
+        // Otherwise, either use the version resolved for the application of the port,
+        // the version defined for the `transfer` port, or an empty version if the
+        // port is non-standard.
         let version = self
             .dst_version()
             .cloned()
+            .or_else(|| self.resolve_dst_version(None))
             .or_else(|| version::default_by_port(self.dst_port_id()))
             .unwrap_or_else(|| {
                 warn!(
@@ -841,6 +883,15 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                 Version::empty()
             });
 
+        if ics27_ica::is_controller_port(self.dst_port_id()) && !self.ordering.is_ordered() {
+            warn!(
+                chain = %self.dst_chain().id(),
+                port = %self.dst_port_id(),
+                "the channels of the interchain accounts must be ordered, the controller will reject an {} channel",
+                self.ordering
+            );
+        }
+
         let channel = ChannelEnd::new(
             State::Init,
             self.ordering,
@@ -1008,8 +1059,11 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         let counterparty =
             Counterparty::new(self.src_port_id().clone(), self.src_channel_id().cloned());
 
-        // Re-use the version that was either set on ChanOpenInit or overwritten by the application.
-        let version = src_channel.version().clone();
+        // Propose the version resolved for the application of the port, if any, otherwise
+        // re-use the version that was either set on ChanOpenInit or overwritten by the application.
+        let version = self
+            .resolve_dst_version(Some(src_channel.version()))
+            .unwrap_or_else(|| src_channel.version().clone());
 
         let channel = ChannelEnd::new(
             State::TryOpen,
@@ -0,0 +1,589 @@
+//! Relaying of the channel upgrade handshake, by which the two ends of an open channel
+//! move to the parameters proposed by one of them.
+//!
+//! The upgrade is initialized on one of the chains by its authority, after which each
+//! step of the handshake is proven to the counterparty end, as for the opening handshake:
+//!
+//! ```text
+//!  a                     b
+//!  Init (OPEN)  ------>  Try (FLUSHING)
+//!  Ack (FLUSHING) <----
+//!               ------>  Confirm (FLUSHING or FLUSHCOMPLETE)
+//! ```
+//!
+//! Each end is reopened with the new parameters once both ends flushed their in-flight
+//! packets, i.e. are in the `FLUSHCOMPLETE` state, or once its counterparty is reopened.
+//! An upgrade aborted on one end, e.g. because it failed or timed out, is cancelled on the
+//! other end by proving the error receipt written by the end which aborted it.
+
+use ibc_proto::google::protobuf::Any;
+use tracing::{debug, error, info};
+
+use ibc::core::ics04_channel::channel::{ChannelEnd, State};
+use ibc::core::ics04_channel::events::{UpgradeChannel, UpgradeStep};
+use ibc::core::ics04_channel::msgs::chan_upgrade_ack::MsgChannelUpgradeAck;
+use ibc::core::ics04_channel::msgs::chan_upgrade_cancel::MsgChannelUpgradeCancel;
+use ibc::core::ics04_channel::msgs::chan_upgrade_confirm::MsgChannelUpgradeConfirm;
+use ibc::core::ics04_channel::msgs::chan_upgrade_open::MsgChannelUpgradeOpen;
+use ibc::core::ics04_channel::msgs::chan_upgrade_timeout::MsgChannelUpgradeTimeout;
+use ibc::core::ics04_channel::msgs::chan_upgrade_try::MsgChannelUpgradeTry;
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::events::IbcEvent;
+use ibc::proofs::Proofs;
+use ibc::tx_msg::Msg;
+use ibc::Height;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::proof::require_proof;
+use crate::chain::requests::{
+    IncludeProof, QueryChannelRequest, QueryHeight, QueryUpgradeErrorRequest, QueryUpgradeRequest,
+};
+use crate::chain::tracking::TrackedMsgs;
+use crate::error::{Error as RelayerError, ErrorDetail as RelayerErrorDetail};
+use crate::util::retry::RetryResult;
+use crate::util::task::Next;
+
+use super::{Channel, ChannelError};
+
+/// The ends of a channel being upgraded, queried at the latest height of their chains.
+struct UpgradeEnds {
+    dst_channel_id: ChannelId,
+    src_channel: ChannelEnd,
+    src_upgrade: Option<Upgrade>,
+    dst_channel: ChannelEnd,
+    dst_upgrade: Option<Upgrade>,
+}
+
+impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
+    /// Relays the step of the upgrade handshake following the one which emitted the
+    /// given event on the source chain, by submitting its datagram to the destination chain.
+    pub fn step_upgrade_event(&self, event: &UpgradeChannel, index: u64) -> RetryResult<Next, u64> {
+        let result = match event.step {
+            UpgradeStep::Init => self.build_chan_upgrade_try_and_send(),
+            UpgradeStep::Try => self.build_chan_upgrade_ack_and_send(),
+            UpgradeStep::Ack => self.build_chan_upgrade_confirm_and_send(),
+            UpgradeStep::Confirm | UpgradeStep::Open => self.build_chan_upgrade_open_and_send(),
+            UpgradeStep::Timeout | UpgradeStep::Error => self.build_chan_upgrade_cancel_and_send(),
+            UpgradeStep::Cancel => Ok(None),
+        };
+
+        upgrade_step_result(event.step, result, index)
+    }
+
+    /// Relays the steps of an upgrade in progress which are not triggered by an event
+    /// of the counterparty, i.e. the opening of an end once both ends are flushed, and
+    /// the timeout of an upgrade which the counterparty did not complete in time.
+    ///
+    /// Returns whether an upgrade is still in progress on either end.
+    pub fn step_upgrade_state(&self) -> Result<bool, ChannelError> {
+        let flipped = self.flipped();
+
+        for result in [
+            self.build_chan_upgrade_open_and_send(),
+            flipped.build_chan_upgrade_open_and_send(),
+            self.build_chan_upgrade_timeout_and_send(),
+            flipped.build_chan_upgrade_timeout_and_send(),
+        ] {
+            if let Some(event) = result? {
+                info!("channel upgrade step completed with event: {}", event);
+            }
+        }
+
+        let ends = self.query_upgrade_ends()?;
+
+        Ok(ends.src_channel.is_upgrading()
+            || ends.dst_channel.is_upgrading()
+            || ends.src_upgrade.is_some()
+            || ends.dst_upgrade.is_some())
+    }
+
+    fn query_upgrade_ends(&self) -> Result<UpgradeEnds, ChannelError> {
+        let src_channel_id = self
+            .src_channel_id()
+            .ok_or_else(ChannelError::missing_local_channel_id)?;
+        let dst_channel_id = self
+            .dst_channel_id()
+            .ok_or_else(ChannelError::missing_counterparty_channel_id)?;
+
+        let (src_channel, _) = self
+            .src_chain()
+            .query_channel(
+                QueryChannelRequest {
+                    port_id: self.src_port_id().clone(),
+                    channel_id: src_channel_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        let (dst_channel, _) = self
+            .dst_chain()
+            .query_channel(
+                QueryChannelRequest {
+                    port_id: self.dst_port_id().clone(),
+                    channel_id: dst_channel_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| ChannelError::query(self.dst_chain().id(), e))?;
+
+        let src_upgrade =
+            query_latest_upgrade(self.src_chain(), self.src_port_id(), src_channel_id)?;
+        let dst_upgrade =
+            query_latest_upgrade(self.dst_chain(), self.dst_port_id(), dst_channel_id)?;
+
+        Ok(UpgradeEnds {
+            dst_channel_id: dst_channel_id.clone(),
+            src_channel,
+            src_upgrade,
+            dst_channel,
+            dst_upgrade,
+        })
+    }
+
+    /// Builds the proofs of the source channel end and, if `with_upgrade` is set, of its
+    /// upgrade, at the latest height of the source chain, along with the proven values.
+    fn build_upgrade_proofs(
+        &self,
+        with_upgrade: bool,
+    ) -> Result<(ChannelEnd, Option<Upgrade>, Proofs), ChannelError> {
+        let src_channel_id = self
+            .src_channel_id()
+            .ok_or_else(ChannelError::missing_local_channel_id)?;
+
+        let query_height = self
+            .src_chain()
+            .query_latest_height()
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        let (channel, maybe_channel_proof) = self
+            .src_chain()
+            .query_channel(
+                QueryChannelRequest {
+                    port_id: self.src_port_id().clone(),
+                    channel_id: src_channel_id.clone(),
+                    height: QueryHeight::Specific(query_height),
+                },
+                IncludeProof::Yes,
+            )
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        let channel_proof = require_proof(maybe_channel_proof)
+            .and_then(|proof| proof.to_bytes())
+            .map_err(ChannelError::channel_proof)?;
+
+        let (upgrade, upgrade_proof) = if with_upgrade {
+            let (upgrade, maybe_upgrade_proof) = self
+                .src_chain()
+                .query_upgrade(
+                    QueryUpgradeRequest {
+                        port_id: self.src_port_id().clone(),
+                        channel_id: src_channel_id.clone(),
+                        height: QueryHeight::Specific(query_height),
+                    },
+                    IncludeProof::Yes,
+                )
+                .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+            let upgrade_proof = require_proof(maybe_upgrade_proof)
+                .and_then(|proof| proof.to_bytes())
+                .map_err(ChannelError::channel_proof)?;
+
+            (Some(upgrade), Some(upgrade_proof))
+        } else {
+            (None, None)
+        };
+
+        let proofs = Proofs::new(
+            channel_proof,
+            None,
+            None,
+            upgrade_proof,
+            query_height.increment(),
+        )
+        .map_err(|e| ChannelError::channel_proof(RelayerError::malformed_proof(e)))?;
+
+        Ok((channel, upgrade, proofs))
+    }
+
+    /// Wraps the given upgrade datagram for the destination chain, built along with
+    /// proofs at the given height, with the client update it requires.
+    fn upgrade_msgs(&self, msg: impl Msg, proof_height: Height) -> Result<Vec<Any>, ChannelError> {
+        let mut msgs = self.build_update_client_on_dst(proof_height)?;
+        msgs.push(msg.to_any());
+        Ok(msgs)
+    }
+
+    pub fn build_chan_upgrade_try(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        // The destination end answers the upgrade proposed by the source end,
+        // unless it already did, or is upgrading to another one
+        if ends.src_upgrade.is_none()
+            || ends.dst_channel.state_matches(&State::Flushing)
+            || ends.dst_channel.state_matches(&State::FlushComplete)
+        {
+            return Ok(None);
+        }
+
+        let (src_channel, src_upgrade, proofs) = self.build_upgrade_proofs(true)?;
+        let src_upgrade = src_upgrade.ok_or_else(|| {
+            ChannelError::missing_event("no upgrade was proven on the source chain".to_string())
+        })?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeTry {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            // The upgrade is relayed over the connection of the destination end
+            proposed_upgrade_connection_hops: ends.dst_channel.connection_hops().clone(),
+            counterparty_upgrade_fields: src_upgrade.fields,
+            counterparty_upgrade_sequence: src_channel.upgrade_sequence(),
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_try_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_try()?, UpgradeStep::Try)
+    }
+
+    pub fn build_chan_upgrade_ack(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        // The destination end, which proposed the upgrade, acknowledges the source
+        // end flushing its packets before moving to the upgrade
+        if ends.dst_upgrade.is_none()
+            || !ends.dst_channel.state_matches(&State::Open)
+            || !ends.src_channel.state_matches(&State::Flushing)
+        {
+            return Ok(None);
+        }
+
+        let (_, src_upgrade, proofs) = self.build_upgrade_proofs(true)?;
+        let src_upgrade = src_upgrade.ok_or_else(|| {
+            ChannelError::missing_event("no upgrade was proven on the source chain".to_string())
+        })?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeAck {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            counterparty_upgrade: src_upgrade,
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_ack_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_ack()?, UpgradeStep::Ack)
+    }
+
+    pub fn build_chan_upgrade_confirm(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        if ends.src_upgrade.is_none()
+            || !ends.dst_channel.state_matches(&State::Flushing)
+            || !ends.src_channel.is_upgrading()
+        {
+            return Ok(None);
+        }
+
+        let (src_channel, src_upgrade, proofs) = self.build_upgrade_proofs(true)?;
+        let src_upgrade = src_upgrade.ok_or_else(|| {
+            ChannelError::missing_event("no upgrade was proven on the source chain".to_string())
+        })?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeConfirm {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            counterparty_channel_state: *src_channel.state(),
+            counterparty_upgrade: src_upgrade,
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_confirm_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_confirm()?, UpgradeStep::Confirm)
+    }
+
+    pub fn build_chan_upgrade_open(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        // The destination end is reopened once both ends are flushed,
+        // or once the source end was reopened with the same upgrade
+        let src_flushed = ends.src_channel.state_matches(&State::FlushComplete)
+            || (ends.src_channel.state_matches(&State::Open)
+                && ends.src_channel.upgrade_sequence() == ends.dst_channel.upgrade_sequence());
+
+        if !ends.dst_channel.state_matches(&State::FlushComplete) || !src_flushed {
+            return Ok(None);
+        }
+
+        let (src_channel, _, proofs) = self.build_upgrade_proofs(false)?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeOpen {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            counterparty_channel_state: *src_channel.state(),
+            counterparty_upgrade_sequence: src_channel.upgrade_sequence(),
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_open_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_open()?, UpgradeStep::Open)
+    }
+
+    pub fn build_chan_upgrade_timeout(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        // The upgrade of the source end sets the deadline by which the destination end,
+        // which is flushing its packets, must be reopened with it
+        let timeout = match &ends.src_upgrade {
+            Some(upgrade) if ends.dst_channel.is_upgrading() => upgrade.timeout.clone(),
+            _ => return Ok(None),
+        };
+
+        if ends.src_channel.state_matches(&State::FlushComplete) {
+            return Ok(None);
+        }
+
+        let status = self
+            .src_chain()
+            .query_application_status()
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        if !timeout.has_expired(status.height, status.timestamp) {
+            return Ok(None);
+        }
+
+        let (src_channel, _, proofs) = self.build_upgrade_proofs(false)?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeTimeout {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            counterparty_channel: src_channel,
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_timeout_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_timeout()?, UpgradeStep::Timeout)
+    }
+
+    pub fn build_chan_upgrade_cancel(&self) -> Result<Option<Vec<Any>>, ChannelError> {
+        let ends = self.query_upgrade_ends()?;
+
+        // Nothing to cancel if the destination end is not upgrading
+        if ends.dst_upgrade.is_none() {
+            return Ok(None);
+        }
+
+        let src_channel_id = self
+            .src_channel_id()
+            .ok_or_else(ChannelError::missing_local_channel_id)?;
+
+        let query_height = self
+            .src_chain()
+            .query_latest_height()
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        let (error_receipt, maybe_proof): (ErrorReceipt, _) = self
+            .src_chain()
+            .query_upgrade_error(
+                QueryUpgradeErrorRequest {
+                    port_id: self.src_port_id().clone(),
+                    channel_id: src_channel_id.clone(),
+                    height: QueryHeight::Specific(query_height),
+                },
+                IncludeProof::Yes,
+            )
+            .map_err(|e| ChannelError::query(self.src_chain().id(), e))?;
+
+        // The receipt may be the one of an earlier upgrade of the channel
+        if error_receipt.sequence < ends.dst_channel.upgrade_sequence() {
+            debug!(
+                "error receipt of upgrade {} is older than upgrade {}, not cancelling it",
+                error_receipt.sequence,
+                ends.dst_channel.upgrade_sequence()
+            );
+            return Ok(None);
+        }
+
+        let error_receipt_proof = require_proof(maybe_proof)
+            .and_then(|proof| proof.to_bytes())
+            .map_err(ChannelError::channel_proof)?;
+
+        let proofs = Proofs::new(
+            error_receipt_proof,
+            None,
+            None,
+            None,
+            query_height.increment(),
+        )
+        .map_err(|e| ChannelError::channel_proof(RelayerError::malformed_proof(e)))?;
+
+        let signer = self
+            .dst_chain()
+            .get_signer()
+            .map_err(|e| ChannelError::fetch_signer(self.dst_chain().id(), e))?;
+
+        let proof_height = proofs.height();
+        let new_msg = MsgChannelUpgradeCancel {
+            port_id: self.dst_port_id().clone(),
+            channel_id: ends.dst_channel_id,
+            error_receipt,
+            proofs,
+            signer,
+        };
+
+        self.upgrade_msgs(new_msg, proof_height).map(Some)
+    }
+
+    pub fn build_chan_upgrade_cancel_and_send(&self) -> Result<Option<IbcEvent>, ChannelError> {
+        self.send_upgrade_msgs(self.build_chan_upgrade_cancel()?, UpgradeStep::Cancel)
+    }
+
+    /// Submits the given messages of an upgrade step, if any, to the destination chain,
+    /// and returns the event of the step, which may also be the error of an aborted upgrade.
+    fn send_upgrade_msgs(
+        &self,
+        dst_msgs: Option<Vec<Any>>,
+        step: UpgradeStep,
+    ) -> Result<Option<IbcEvent>, ChannelError> {
+        let dst_msgs = match dst_msgs {
+            Some(dst_msgs) => dst_msgs,
+            None => return Ok(None),
+        };
+
+        let tm = TrackedMsgs::new_static(dst_msgs, step.event_type().as_str());
+
+        let events = self
+            .dst_chain()
+            .send_messages_and_wait_commit(tm)
+            .map_err(|e| ChannelError::submit(self.dst_chain().id(), e))?;
+
+        let result = events
+            .into_iter()
+            .find(|event_with_height| {
+                matches!(&event_with_height.event, IbcEvent::UpgradeChannel(ev)
+                    if ev.step == step || ev.step == UpgradeStep::Error)
+                    || matches!(event_with_height.event, IbcEvent::ChainError(_))
+            })
+            .ok_or_else(|| {
+                ChannelError::missing_event(format!(
+                    "no {} event was in the response",
+                    step.event_type().as_str()
+                ))
+            })?;
+
+        match &result.event {
+            IbcEvent::UpgradeChannel(_) => {
+                info!("🥂 {} => {}", self.dst_chain().id(), result);
+                Ok(Some(result.event))
+            }
+            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.clone())),
+            _ => Err(ChannelError::invalid_event(result.event)),
+        }
+    }
+}
+
+/// The upgrade of the given channel end at the latest height of its chain, if any.
+/// An end has no upgrade stored unless an upgrade is in progress, while the other
+/// errors of the query are returned, so that a transient failure is not taken
+/// for the end of the upgrade.
+fn query_latest_upgrade<Chain: ChainHandle>(
+    chain: &Chain,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<Option<Upgrade>, ChannelError> {
+    let result = chain.query_upgrade(
+        QueryUpgradeRequest {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            height: QueryHeight::Latest,
+        },
+        IncludeProof::No,
+    );
+
+    match result {
+        Ok((upgrade, _)) => Ok(Some(upgrade)),
+        Err(e) => match e.detail() {
+            RelayerErrorDetail::EmptyResponseValue(_) => Ok(None),
+            _ => Err(ChannelError::query(chain.id(), e)),
+        },
+    }
+}
+
+fn upgrade_step_result(
+    step: UpgradeStep,
+    result: Result<Option<IbcEvent>, ChannelError>,
+    index: u64,
+) -> RetryResult<Next, u64> {
+    match result {
+        Ok(Some(event)) => {
+            info!("channel upgrade step completed with event: {}", event);
+            RetryResult::Ok(Next::Continue)
+        }
+        Ok(None) => {
+            debug!(
+                "no channel upgrade step follows {}",
+                step.event_type().as_str()
+            );
+            RetryResult::Ok(Next::Continue)
+        }
+        Err(e) => {
+            error!(
+                "failed to relay the step following {}: {}",
+                step.event_type().as_str(),
+                e
+            );
+            RetryResult::Retry(index)
+        }
+    }
+}
@@ -3,12 +3,60 @@
 //! Provides support for resolving the appropriate
 //! channel version to be used in a channel open
 //! handshake.
+//!
+//! The version proposed for a channel end is, in order of precedence:
+//! the version given by the user, e.g. with `create channel --channel-version`,
+//! the one computed by the first resolver registered for the port of the channel end
+//! with [`register_resolver`], the one configured for the port in the `channel_versions`
+//! of the chain, the metadata of the interchain accounts for their ports, the version of
+//! the interchain queries for the channels of their hosts, and the default one for the port,
+//! see [`default_by_port`].
+
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use tracing::warn;
 
 use ibc::{
-    applications::transfer,
-    core::{ics04_channel::Version, ics24_host::identifier::PortId},
+    applications::{async_icq, ics27_ica, ics27_ica::metadata::Metadata, transfer},
+    core::{
+        ics04_channel::{channel::Order, Version},
+        ics24_host::identifier::{ChainId, ConnectionId, PortId},
+    },
 };
 
+use crate::config::channel_version::{channel_version, ChannelVersion};
+use crate::config::filter::PortFilterMatch;
+
+/// Computes the version to propose for a channel end, if it knows about its application.
+pub type VersionResolver = Box<dyn Fn(&VersionContext<'_>) -> Option<Version> + Send + Sync>;
+
+/// The resolvers registered by the applications embedding the relayer, with the ports they handle.
+static RESOLVERS: Lazy<RwLock<Vec<(PortFilterMatch, VersionResolver)>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
+
+/// The channel end whose version is resolved.
+#[derive(Clone, Debug)]
+pub struct VersionContext<'a> {
+    /// The chain the channel end is opened on
+    pub chain_id: &'a ChainId,
+    pub port_id: &'a PortId,
+    pub connection_id: &'a ConnectionId,
+    pub counterparty_chain_id: &'a ChainId,
+    pub counterparty_port_id: &'a PortId,
+    pub counterparty_connection_id: &'a ConnectionId,
+    pub ordering: Order,
+    /// The version of the counterparty channel end, when the channel end is opened
+    /// with `ChanOpenTry`, or `None` with `ChanOpenInit`
+    pub counterparty_version: Option<&'a Version>,
+}
+
+/// Register a resolver of the versions of the channel ends opened on the ports
+/// matching the given pattern, taking precedence over the ones registered before.
+pub fn register_resolver(port_id: PortFilterMatch, resolver: VersionResolver) {
+    RESOLVERS.write().unwrap().insert(0, (port_id, resolver));
+}
+
 /// Returns the default channel version, depending on the the given [`PortId`].
 pub fn default_by_port(port_id: &PortId) -> Option<Version> {
     if port_id.as_str() == transfer::PORT_ID_STR {
@@ -18,3 +66,200 @@ pub fn default_by_port(port_id: &PortId) -> Option<Version> {
         None
     }
 }
+
+/// Returns the version computed for the given channel end by a registered resolver,
+/// the one configured for its port in the given list, the metadata of the interchain
+/// accounts if the channel end is on one of their ports, or the version of the
+/// interchain queries if the channel end is on their host or its counterparty, if any.
+pub fn resolve(configured: &[ChannelVersion], ctx: &VersionContext<'_>) -> Option<Version> {
+    let resolved = RESOLVERS
+        .read()
+        .unwrap()
+        .iter()
+        .filter(|(port_id, _)| port_id.matches(ctx.port_id))
+        .find_map(|(_, resolver)| resolver(ctx));
+
+    resolved
+        .or_else(|| channel_version(configured, ctx.port_id).map(|template| render(template, ctx)))
+        .or_else(|| ics27_version(ctx))
+        .or_else(|| async_icq_version(ctx))
+}
+
+/// The version of the channels of the interchain queries, proposed by both their
+/// controller, whose port is application-specific, and their host.
+fn async_icq_version(ctx: &VersionContext<'_>) -> Option<Version> {
+    (async_icq::is_host_port(ctx.port_id) || async_icq::is_host_port(ctx.counterparty_port_id))
+        .then(|| Version::new(async_icq::VERSION.to_string()))
+}
+
+/// The version of the channel ends of the interchain accounts, whose metadata is derived
+/// from the connections of the channel. The controller proposes it on `ChanOpenInit`,
+/// and the host accepts it on `ChanOpenTry`, filling in the address of the account.
+fn ics27_version(ctx: &VersionContext<'_>) -> Option<Version> {
+    match ctx.counterparty_version {
+        None if ics27_ica::is_controller_port(ctx.port_id)
+            && ics27_ica::is_host_port(ctx.counterparty_port_id) =>
+        {
+            let metadata = Metadata::for_init(
+                ctx.connection_id.clone(),
+                ctx.counterparty_connection_id.clone(),
+            );
+
+            Some(metadata.into())
+        }
+        Some(version) if ics27_ica::is_host_port(ctx.port_id) => {
+            let metadata = Metadata::from_version(version)?;
+
+            if &metadata.host_connection_id != ctx.connection_id
+                || &metadata.controller_connection_id != ctx.counterparty_connection_id
+            {
+                warn!(
+                    chain = %ctx.chain_id,
+                    port = %ctx.port_id,
+                    "the metadata proposed by the controller of the interchain account does not \
+                     match the connections of the channel, the host will reject it: {}",
+                    version
+                );
+            }
+
+            Some(version.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Substitute the placeholders of the given template of a version.
+fn render(template: &str, ctx: &VersionContext<'_>) -> Version {
+    let counterparty_version = ctx
+        .counterparty_version
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    let version = template
+        .replace("{port_id}", ctx.port_id.as_str())
+        .replace("{connection_id}", ctx.connection_id.as_str())
+        .replace("{counterparty_port_id}", ctx.counterparty_port_id.as_str())
+        .replace(
+            "{counterparty_connection_id}",
+            ctx.counterparty_connection_id.as_str(),
+        )
+        .replace("{counterparty_version}", &counterparty_version);
+
+    Version::new(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    use crate::config::filter::{FilterPattern, Wildcard};
+
+    #[test]
+    fn resolve_version() {
+        let chain_id = ChainId::from_string("ibc-0");
+        let counterparty_chain_id = ChainId::from_string("ibc-1");
+        let connection_id = ConnectionId::from_str("connection-0").unwrap();
+        let counterparty_connection_id = ConnectionId::from_str("connection-7").unwrap();
+        let counterparty_port_id = PortId::from_str("icahost").unwrap();
+
+        let controller = PortId::from_str("icacontroller-owner").unwrap();
+        let custom = PortId::from_str("resolved").unwrap();
+
+        let ctx = VersionContext {
+            chain_id: &chain_id,
+            port_id: &controller,
+            connection_id: &connection_id,
+            counterparty_chain_id: &counterparty_chain_id,
+            counterparty_port_id: &counterparty_port_id,
+            counterparty_connection_id: &counterparty_connection_id,
+            ordering: Order::Ordered,
+            counterparty_version: None,
+        };
+
+        let controllers = Wildcard::new("icacontroller-*".to_string()).unwrap();
+        let configured = vec![ChannelVersion {
+            port_id: FilterPattern::Wildcard(controllers),
+            version: r#"{"version":"ics27-1","controller_connection_id":"{connection_id}","host_connection_id":"{counterparty_connection_id}"}"#.to_string(),
+        }];
+
+        assert_eq!(
+            resolve(&configured, &ctx),
+            Some(Version::new(
+                r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-7"}"#.to_string()
+            ))
+        );
+
+        let ctx = VersionContext {
+            port_id: &custom,
+            ..ctx
+        };
+
+        assert_eq!(resolve(&configured, &ctx), None);
+
+        // The metadata of the interchain accounts is proposed without being configured
+        let ctx = VersionContext {
+            port_id: &controller,
+            ..ctx
+        };
+
+        let metadata = resolve(&[], &ctx).unwrap();
+        assert_eq!(
+            metadata.to_string(),
+            r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-7","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}"#
+        );
+
+        let host_ctx = VersionContext {
+            chain_id: &counterparty_chain_id,
+            port_id: &counterparty_port_id,
+            connection_id: &counterparty_connection_id,
+            counterparty_chain_id: &chain_id,
+            counterparty_port_id: &controller,
+            counterparty_connection_id: &connection_id,
+            ordering: Order::Ordered,
+            counterparty_version: Some(&metadata),
+        };
+
+        assert_eq!(resolve(&[], &host_ctx), Some(metadata.clone()));
+
+        let ctx = VersionContext {
+            port_id: &custom,
+            ..ctx
+        };
+
+        register_resolver(
+            FilterPattern::Exact(custom.clone()),
+            Box::new(|ctx| Some(Version::new(format!("custom-{}", ctx.connection_id)))),
+        );
+
+        assert_eq!(
+            resolve(&configured, &ctx),
+            Some(Version::new("custom-connection-0".to_string()))
+        );
+
+        // The channels of the interchain queries are versioned from either end
+        let icq_host = PortId::from_str("icqhost").unwrap();
+        let icq_controller = PortId::from_str("interquery").unwrap();
+
+        let ctx = VersionContext {
+            port_id: &icq_controller,
+            counterparty_port_id: &icq_host,
+            ordering: Order::Unordered,
+            counterparty_version: None,
+            ..ctx
+        };
+
+        let icq_version = Some(Version::new("icq-1".to_string()));
+        assert_eq!(resolve(&[], &ctx), icq_version);
+
+        let ctx = VersionContext {
+            port_id: &icq_host,
+            counterparty_port_id: &icq_controller,
+            counterparty_version: icq_version.as_ref(),
+            ..ctx
+        };
+
+        assert_eq!(resolve(&[], &ctx), icq_version);
+    }
+}
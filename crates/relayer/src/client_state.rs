@@ -12,6 +12,7 @@ use ibc::clients::ics07_tendermint::client_state::{
     ClientState as TmClientState, UpgradeOptions as TmUpgradeOptions,
     TENDERMINT_CLIENT_STATE_TYPE_URL,
 };
+use ibc::clients::ics08_wasm::{self, WASM_CLIENT_STATE_TYPE_URL};
 use ibc::core::ics02_client::client_state::{
     downcast_client_state, ClientState, UpdatedState, UpgradeOptions,
 };
@@ -113,6 +114,15 @@ impl AnyClientState {
         }
     }
 
+    pub fn trusting_period(&self) -> Option<Duration> {
+        match self {
+            AnyClientState::Tendermint(tm_state) => Some(tm_state.trusting_period),
+
+            #[cfg(test)]
+            AnyClientState::Mock(_) => None,
+        }
+    }
+
     pub fn refresh_period(&self) -> Option<Duration> {
         match self {
             AnyClientState::Tendermint(tm_state) => tm_state.refresh_time(),
@@ -143,6 +153,11 @@ impl TryFrom<Any> for AnyClientState {
                     .map_err(Error::decode_raw_client_state)?,
             )),
 
+            // The client state of a wasm client is the one of the light client of its contract
+            WASM_CLIENT_STATE_TYPE_URL => {
+                AnyClientState::try_from(ics08_wasm::unwrap(raw).map_err(Error::decode_wasm_state)?)
+            }
+
             _ => Err(Error::unknown_client_state_type(raw.type_url)),
         }
     }
@@ -1,9 +1,33 @@
 //! Relayer configuration
 
+pub mod channel_version;
+pub mod claims;
+pub mod client_wrapper;
+pub mod dynamic_gas_price;
 pub mod error;
+pub mod event_source;
+pub mod fee_replenishment;
 pub mod filter;
 pub mod gas_multiplier;
+pub mod halt;
+pub mod memo_guard;
+pub mod memo_template;
+pub mod min_fee;
+pub mod packet_cache;
+pub mod packet_limit;
+pub mod packet_policy;
+pub mod priority;
 pub mod proof_specs;
+pub mod proxy;
+pub mod quarantine;
+pub mod quota;
+pub mod remote_signer;
+pub mod restart;
+pub mod schedule;
+pub mod sequence_gap;
+pub mod stats;
+pub mod tenant;
+pub mod top_of_block;
 pub mod types;
 
 use alloc::collections::BTreeMap;
@@ -11,7 +35,12 @@ use core::{
     fmt::{Display, Error as FmtError, Formatter},
     time::Duration,
 };
-use std::{fs, fs::File, io::Write, path::Path};
+use std::{
+    fs,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use ibc_proto::google::protobuf::Any;
 use serde_derive::{Deserialize, Serialize};
@@ -22,7 +51,31 @@ use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::timestamp::ZERO_DURATION;
 
 use crate::chain::ChainType;
+use crate::config::channel_version::ChannelVersion;
+use crate::config::claims::ClaimsConfig;
+use crate::config::client_wrapper::ClientWrapper;
+use crate::config::dynamic_gas_price::DynamicGasPrice;
+use crate::config::event_source::EventSource;
+use crate::config::fee_replenishment::FeeReplenishment;
+use crate::config::filter::ChannelFilters;
 use crate::config::gas_multiplier::GasMultiplier;
+use crate::config::halt::HaltConfig;
+use crate::config::memo_template::TxMemoTemplate;
+use crate::config::min_fee::MinFee;
+use crate::config::packet_cache::PacketCacheConfig;
+use crate::config::packet_limit::{packet_limit, PacketLimit};
+use crate::config::packet_policy::PacketPolicyConfig;
+use crate::config::priority::{channel_priority, ChannelPriority, Priority};
+use crate::config::proxy::Proxy;
+use crate::config::quarantine::QuarantineConfig;
+use crate::config::quota::RuntimeQuotas;
+use crate::config::remote_signer::RemoteSignerConfig;
+use crate::config::restart::RestartConfig;
+use crate::config::schedule::RelaySchedule;
+use crate::config::sequence_gap::SequenceGapConfig;
+use crate::config::stats::StatsConfig;
+use crate::config::tenant::{find_tenant, Tenant};
+use crate::config::top_of_block::TopOfBlock;
 use crate::config::types::{MaxMsgNum, MaxTxSize, Memo};
 use crate::error::Error as RelayerError;
 use crate::extension_options::ExtensionOptionDynamicFeeTx;
@@ -98,14 +151,34 @@ pub mod default {
         false
     }
 
+    pub fn check_query_proof_consistency() -> bool {
+        false
+    }
+
     pub fn clear_packets_interval() -> u64 {
         100
     }
 
+    pub fn client_refresh_rate() -> f64 {
+        0.66
+    }
+
+    pub fn client_expiry_warning_rate() -> f64 {
+        0.1
+    }
+
     pub fn rpc_timeout() -> Duration {
         Duration::from_secs(10)
     }
 
+    pub fn query_timeout() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    pub fn broadcast_timeout() -> Duration {
+        Duration::from_secs(30)
+    }
+
     pub fn clock_drift() -> Duration {
         Duration::from_secs(5)
     }
@@ -130,6 +203,28 @@ pub struct Config {
     pub rest: RestConfig,
     #[serde(default)]
     pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    #[serde(default)]
+    pub packet_cache: PacketCacheConfig,
+    #[serde(default)]
+    pub ha: HaConfig,
+    #[serde(default)]
+    pub claims: ClaimsConfig,
+    #[serde(default)]
+    pub restart: RestartConfig,
+    #[serde(default)]
+    pub quarantine: QuarantineConfig,
+    #[serde(default)]
+    pub halt: HaltConfig,
+    #[serde(default)]
+    pub sequence_gaps: SequenceGapConfig,
+    #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub packet_policy: PacketPolicyConfig,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub tenants: Vec<Tenant>,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
     pub chains: Vec<ChainConfig>,
 }
@@ -148,7 +243,8 @@ impl Config {
     }
 
     /// Returns true if filtering is disabled or if packets are allowed on
-    /// the channel [`PortId`] [`ChannelId`] on [`ChainId`].
+    /// the channel [`PortId`] [`ChannelId`] on [`ChainId`], by the packet
+    /// filter reloaded for the chain if any.
     /// Returns false otherwise.
     pub fn packets_on_channel_allowed(
         &self,
@@ -156,12 +252,33 @@ impl Config {
         port_id: &PortId,
         channel_id: &ChannelId,
     ) -> bool {
+        if let Some(allowed) = filter::with_reloaded(chain_id, |packet_filter| {
+            packet_filter.is_allowed(port_id, channel_id)
+        }) {
+            return allowed;
+        }
+
         match self.find_chain(chain_id) {
             Some(chain_config) => chain_config.packet_filter.is_allowed(port_id, channel_id),
             None => false,
         }
     }
 
+    /// Returns the priority configured for the channel [`PortId`] [`ChannelId`]
+    /// on [`ChainId`], see [`ChainConfig::channel_priority`], or the default
+    /// priority if the chain is not configured.
+    pub fn channel_priority(
+        &self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Priority {
+        self.find_chain(chain_id)
+            .map_or_else(Priority::default, |chain_config| {
+                chain_config.channel_priority(port_id, channel_id)
+            })
+    }
+
     pub fn chains_map(&self) -> BTreeMap<&ChainId, &ChainConfig> {
         self.chains.iter().map(|c| (&c.id, c)).collect()
     }
@@ -170,6 +287,9 @@ impl Config {
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ModeConfig {
+    /// Only monitor the chains, without ever signing nor broadcasting a transaction
+    #[serde(default)]
+    pub watch_only: bool,
     pub clients: Clients,
     pub connections: Connections,
     pub channels: Channels,
@@ -189,9 +309,12 @@ impl ModeConfig {
 impl Default for ModeConfig {
     fn default() -> Self {
         Self {
+            watch_only: false,
             clients: Clients {
                 enabled: true,
                 refresh: true,
+                refresh_rate: default::client_refresh_rate(),
+                expiry_warning_rate: default::client_expiry_warning_rate(),
                 misbehaviour: false,
             },
             connections: Connections { enabled: false },
@@ -206,12 +329,20 @@ impl Default for ModeConfig {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Clients {
     pub enabled: bool,
     #[serde(default)]
     pub refresh: bool,
+    /// The fraction of the trusting period of the clients after their latest update
+    /// at which they are refreshed
+    #[serde(default = "default::client_refresh_rate")]
+    pub refresh_rate: f64,
+    /// The fraction of the trusting period of the clients left before they expire
+    /// below which the relayer warns that they are about to expire
+    #[serde(default = "default::client_expiry_warning_rate")]
+    pub expiry_warning_rate: f64,
     #[serde(default)]
     pub misbehaviour: bool,
 }
@@ -282,10 +413,28 @@ impl Display for LogLevel {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct GlobalConfig {
     pub log_level: LogLevel,
+    #[serde(with = "humantime_serde")]
+    pub shutdown_grace_period: Duration,
+    /// The proxy through which the nodes of the chains without a proxy of their own are reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Proxy>,
+}
+
+/// Default values for the global configuration.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self {
+            log_level: LogLevel::default(),
+            shutdown_grace_period: Duration::from_secs(30),
+            proxy: None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -294,6 +443,8 @@ pub struct TelemetryConfig {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub tracing: TracingExportConfig,
 }
 
 /// Default values for the telemetry configuration.
@@ -305,6 +456,30 @@ impl Default for TelemetryConfig {
             enabled: false,
             host: "127.0.0.1".to_string(),
             port: 3001,
+            tracing: TracingExportConfig::default(),
+        }
+    }
+}
+
+/// Export of the spans of the relaying of the packets, channels and connections
+/// to an OpenTelemetry collector, over OTLP/HTTP.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TracingExportConfig {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+/// Default values for the tracing export configuration.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for TracingExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:4318/v1/traces".to_string(),
+            service_name: "hermes".to_string(),
         }
     }
 }
@@ -327,6 +502,71 @@ impl Default for RestConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PersistenceConfig {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(with = "humantime_serde")]
+    pub max_age: Duration,
+}
+
+/// Default values for the persistence configuration.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_age: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HaConfig {
+    pub enabled: bool,
+    pub backend: HaBackend,
+    pub lock_key: String,
+    #[serde(with = "humantime_serde")]
+    pub lease_ttl: Duration,
+    #[serde(with = "humantime_serde")]
+    pub renew_interval: Duration,
+}
+
+/// Default values for the high-availability configuration.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for HaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: HaBackend::default(),
+            lock_key: "hermes/leader".to_string(),
+            lease_ttl: Duration::from_secs(10),
+            renew_interval: Duration::from_secs(3),
+        }
+    }
+}
+
+/// The backend holding the leadership lock shared by the relayer instances.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum HaBackend {
+    Redis { url: String },
+}
+
+impl Default for HaBackend {
+    fn default() -> Self {
+        Self::Redis {
+            url: "redis://127.0.0.1:6379".to_string(),
+        }
+    }
+}
+
 /// It defines the address generation method
 /// TODO: Ethermint `pk_type` to be restricted
 /// after the Cosmos SDK release with ethsecp256k1
@@ -358,6 +598,39 @@ impl Display for AddressType {
     }
 }
 
+/// The transport of the gRPC queries to a chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrpcTransport {
+    /// Native gRPC, over HTTP/2
+    Native,
+    /// gRPC-web, over HTTP/1.1, for the endpoints behind web proxies
+    Web,
+}
+
+impl Default for GrpcTransport {
+    fn default() -> Self {
+        GrpcTransport::Native
+    }
+}
+
+/// The client certificate presented to the gRPC endpoint of a chain sitting behind
+/// a gateway which requires mutual TLS. The certificate is only presented over the
+/// native gRPC transport, and never to the RPC and WebSocket endpoints, whose clients
+/// do not accept a custom TLS configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientTls {
+    /// Path to the PEM-encoded client certificate
+    pub cert: PathBuf,
+    /// Path to the PEM-encoded private key of the client certificate
+    pub key: PathBuf,
+    /// Path to the PEM-encoded certificate of the authority which signed the
+    /// certificates of the nodes, if not one of the system roots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca: Option<PathBuf>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ChainConfig {
@@ -365,14 +638,50 @@ pub struct ChainConfig {
     #[serde(default = "default::chain_type")]
     pub r#type: ChainType,
     pub rpc_addr: tendermint_rpc::Url,
+    /// A second RPC endpoint, run independently from the one at `rpc_addr`, from which the
+    /// headers of the client updates are fetched again and required to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary_rpc_addr: Option<tendermint_rpc::Url>,
+    /// The RPC endpoint of an archive node of the chain, against which the
+    /// queries of the heights pruned by the node at `rpc_addr` are retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_rpc_addr: Option<tendermint_rpc::Url>,
     pub websocket_addr: tendermint_rpc::Url,
     pub grpc_addr: tendermint_rpc::Url,
+    /// Whether `grpc_addr` serves native gRPC or gRPC-web
+    #[serde(default)]
+    pub grpc_transport: GrpcTransport,
+    /// The proxy through which the nodes of this chain are reached,
+    /// in place of the global one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Proxy>,
     #[serde(default = "default::rpc_timeout", with = "humantime_serde")]
     pub rpc_timeout: Duration,
+    /// Deadline of each gRPC and RPC query to the chain
+    #[serde(default = "default::query_timeout", with = "humantime_serde")]
+    pub query_timeout: Duration,
+    /// Number of entries fetched per page by the queries listing the packet commitments
+    /// and acknowledgements of a channel, which are then resumed from the last page
+    /// fetched when interrupted. Unset, each listing is fetched in a single query.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_page_size: Option<u64>,
+    /// Deadline of the broadcast of each transaction to the chain
+    #[serde(default = "default::broadcast_timeout", with = "humantime_serde")]
+    pub broadcast_timeout: Duration,
     pub account_prefix: String,
     pub key_name: String,
+    /// The names of other keys signing the transactions of the IBC messages in turn with
+    /// the key of `key_name`, see [`crate::chain::cosmos::rotation`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_names: Vec<String>,
     #[serde(default)]
     pub key_store_type: Store,
+    /// The folder holding the keys of the keyring, in place of `$HOME/.hermes/keys`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_store_folder: Option<PathBuf>,
+    /// The tenant this chain belongs to, whose settings it inherits, see [`tenant::Tenant`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
     pub store_prefix: String,
     pub default_gas: Option<u64>,
     pub max_gas: Option<u64>,
@@ -382,6 +691,10 @@ pub struct ChainConfig {
     pub gas_multiplier: Option<GasMultiplier>,
 
     pub fee_granter: Option<String>,
+    /// The name of the key in the keyring of the chain whose account pays the fees of the
+    /// transactions in place of the account of `key_name`, and signs them along with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<String>,
     #[serde(default)]
     pub max_msg_num: MaxMsgNum,
     #[serde(default)]
@@ -407,6 +720,11 @@ pub struct ChainConfig {
     #[serde(default)]
     pub memo_prefix: Memo,
 
+    /// The template of the memo of the transactions receiving and acknowledging packets,
+    /// in place of `memo_prefix`, see [`memo_template::TxMemoTemplate`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_memo_template: Option<TxMemoTemplate>,
+
     // Note: These last few need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML.
     //       That's because these are all tables and have to come last when serializing.
     #[serde(
@@ -416,6 +734,12 @@ pub struct ChainConfig {
     )]
     pub proof_specs: Option<ProofSpecs>,
 
+    /// Whether to check the proofs returned along with the results of the queries against
+    /// the app hash reported by the same node, which detects inconsistent responses but,
+    /// the app hash not being verified by the light client, not forged ones
+    #[serde(default = "default::check_query_proof_consistency")]
+    pub check_query_proof_consistency: bool,
+
     // This is an undocumented and hidden config to make the relayer wait for
     // DeliverTX before sending the next transaction when sending messages in
     // multiple batches. We will instruct relayer operators to turn this on
@@ -424,6 +748,11 @@ pub struct ChainConfig {
     #[serde(default)]
     pub sequential_batch_tx: bool,
 
+    /// How the states and headers of the clients created on this chain are wrapped,
+    /// for chains which only accept wrapped light clients, e.g. `wasm:<checksum>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_wrapper: Option<ClientWrapper>,
+
     // these two need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML
     /// The trust threshold defines what fraction of the total voting power of a known
     /// and trusted validator set is sufficient for a commit to be accepted going forward.
@@ -435,17 +764,120 @@ pub struct ChainConfig {
     #[serde(default)]
     pub packet_filter: PacketFilter,
 
+    /// Limits on the resources used by the runtime of this chain
+    #[serde(default)]
+    pub quotas: RuntimeQuotas,
+
+    /// Bidding for the top-of-block placement of the transactions receiving packets
+    #[serde(default)]
+    pub top_of_block: TopOfBlock,
+
+    /// Whether the events of this chain are pushed over a WebSocket subscription
+    /// or pulled from the results of its blocks
+    #[serde(default)]
+    pub event_source: EventSource,
+
+    /// Swapping a reserve token into the fee token when its balance runs low
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_replenishment: Option<FeeReplenishment>,
+
+    /// Following the gas price of the fee market of this chain instead of `gas_price`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dynamic_gas_price: Option<DynamicGasPrice>,
+
+    /// The remote signer holding the key signing the transactions of this chain,
+    /// in place of the key of `key_name` in the keyring
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_signer: Option<RemoteSignerConfig>,
+
+    /// The client certificate presented to the native gRPC endpoint of this chain,
+    /// see [`ClientTls`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_tls: Option<ClientTls>,
+
+    /// Priorities of the channels relayed on this chain, see [`priority::ChannelPriority`]
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub priorities: Vec<ChannelPriority>,
+
+    /// Priorities and quotas of the packets relayed over the channels of this chain,
+    /// see [`packet_limit::PacketLimit`]
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub packet_limits: Vec<PacketLimit>,
+
+    /// Windows of time restricting the relaying of the channels of this chain,
+    /// see [`schedule::RelaySchedule`]
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<RelaySchedule>,
+
+    /// Minimum fees to be escrowed for the packets sent on the channels of this chain
+    /// for them to be relayed, see [`min_fee::MinFee`]
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub min_fees: Vec<MinFee>,
+
+    /// Versions proposed when opening the channel ends of custom applications on this chain,
+    /// see [`channel_version::ChannelVersion`]
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub channel_versions: Vec<ChannelVersion>,
+
+    /// The channels of this chain whose messages are checked for having already been
+    /// relayed on the paths from them, right before broadcasting them
+    #[serde(default, skip_serializing_if = "ChannelFilters::is_empty")]
+    pub pre_send_check: ChannelFilters,
+
     #[serde(default)]
     pub address_type: AddressType,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
     pub extension_options: Vec<ExtensionOption>,
 }
 
+impl ChainConfig {
+    /// Returns the priority configured for the channel [`PortId`] [`ChannelId`],
+    /// in `priorities` or else in `packet_limits`, or the default priority if
+    /// there is none.
+    pub fn channel_priority(&self, port_id: &PortId, channel_id: &ChannelId) -> Priority {
+        let prioritized = self
+            .priorities
+            .iter()
+            .any(|p| p.matches(port_id, channel_id));
+
+        match packet_limit(&self.packet_limits, port_id, channel_id)
+            .and_then(|limit| limit.priority)
+        {
+            Some(priority) if !prioritized => priority,
+            _ => channel_priority(&self.priorities, port_id, channel_id),
+        }
+    }
+
+    /// Whether a priority is configured for any channel of the chain.
+    pub fn has_channel_priorities(&self) -> bool {
+        !self.priorities.is_empty() || self.packet_limits.iter().any(|l| l.priority.is_some())
+    }
+}
+
 /// Attempt to load and parse the TOML config file as a `Config`.
 pub fn load(path: impl AsRef<Path>) -> Result<Config, Error> {
     let config_toml = std::fs::read_to_string(&path).map_err(Error::io)?;
 
-    let config = toml::from_str::<Config>(&config_toml[..]).map_err(Error::decode)?;
+    let mut config = toml::from_str::<Config>(&config_toml[..]).map_err(Error::decode)?;
+
+    // The chains without a proxy of their own are reached through the global one
+    if let Some(proxy) = &config.global.proxy {
+        for chain in config.chains.iter_mut().filter(|c| c.proxy.is_none()) {
+            chain.proxy = Some(proxy.clone());
+        }
+    }
+
+    // The chains of a tenant inherit the settings of the tenant they do not override
+    for chain in config.chains.iter_mut() {
+        let tenant = chain
+            .tenant
+            .as_deref()
+            .and_then(|id| find_tenant(&config.tenants, id));
+
+        if let Some(tenant) = tenant {
+            tenant.apply_to(chain);
+        }
+    }
 
     Ok(config)
 }
@@ -473,7 +905,9 @@ pub(crate) fn store_writer(config: &Config, mut writer: impl Write) -> Result<()
 
 #[cfg(test)]
 mod tests {
-    use super::{load, store_writer};
+    use core::str::FromStr;
+
+    use super::{load, store_writer, ChainConfig};
     use test_log::test;
 
     #[test]
@@ -500,4 +934,29 @@ mod tests {
         let mut buffer = Vec::new();
         store_writer(&config, &mut buffer).unwrap();
     }
+
+    #[test]
+    fn parse_ipv6_endpoints() {
+        let toml_content = r#"
+            id = 'chain_A'
+            rpc_addr = 'http://[::1]:26657'
+            grpc_addr = 'http://[fd00::2]:9090'
+            websocket_addr = 'ws://[::1]:26657/websocket'
+            account_prefix = 'cosmos'
+            key_name = 'testkey'
+            store_prefix = 'ibc'
+            gas_price = { price = 0.001, denom = 'stake' }
+            trust_threshold = { numerator = '1', denominator = '3' }
+            "#;
+
+        let config: ChainConfig = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.rpc_addr.host(), "[::1]");
+        assert_eq!(config.rpc_addr.port(), 26657);
+
+        // The gRPC clients are given the address as an `http::Uri`
+        let grpc_addr = http::Uri::from_str(&config.grpc_addr.to_string()).unwrap();
+        assert_eq!(grpc_addr.host(), Some("[fd00::2]"));
+        assert_eq!(grpc_addr.port_u16(), Some(9090));
+    }
 }
@@ -0,0 +1,70 @@
+//! Versions proposed by the relayer when opening the ends of the channels of the custom
+//! applications, whose version cannot be inferred from their port like for ICS20.
+
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics24_host::identifier::PortId;
+
+use super::filter::PortFilterMatch;
+
+/// The version to propose for the channel ends opened on the ports matching the given pattern.
+///
+/// The version is a template, in which the following placeholders are substituted:
+/// `{port_id}`, `{connection_id}`, `{counterparty_port_id}`, `{counterparty_connection_id}`
+/// and `{counterparty_version}`, the last one being empty when the channel end is opened
+/// with `ChanOpenInit`. This allows proposing the JSON-encoded versions of the applications
+/// which negotiate metadata in the version, e.g. those of ICS27 and ICS29.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelVersion {
+    pub port_id: PortFilterMatch,
+    pub version: String,
+}
+
+impl ChannelVersion {
+    pub fn matches(&self, port_id: &PortId) -> bool {
+        self.port_id.matches(port_id)
+    }
+}
+
+/// The template of the version for the given port according to the given list,
+/// in which the first matching entry wins.
+pub fn channel_version<'a>(versions: &'a [ChannelVersion], port_id: &PortId) -> Option<&'a str> {
+    versions
+        .iter()
+        .find(|v| v.matches(port_id))
+        .map(|v| v.version.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[derive(Debug, Deserialize)]
+    struct Versions {
+        channel_versions: Vec<ChannelVersion>,
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let toml_content = r#"
+            channel_versions = [
+                { port_id = 'icacontroller-*', version = '{"version":"ics27-1","controller_connection_id":"{connection_id}"}' },
+                { port_id = '*', version = 'custom-1' },
+            ]
+            "#;
+
+        let Versions { channel_versions } = toml::from_str(toml_content).unwrap();
+
+        let version =
+            |port: &str| channel_version(&channel_versions, &PortId::from_str(port).unwrap());
+
+        assert_eq!(
+            version("icacontroller-owner"),
+            Some(r#"{"version":"ics27-1","controller_connection_id":"{connection_id}"}"#)
+        );
+        assert_eq!(version("custom"), Some("custom-1"));
+    }
+}
@@ -0,0 +1,36 @@
+//! Coordination of cooperating relayer instances relaying the same paths, which
+//! claim the packets in a shared backend before relaying them, see [`crate::ha::claims`].
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::HaBackend;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClaimsConfig {
+    pub enabled: bool,
+    /// The backend holding the claims, shared by the instances
+    pub backend: HaBackend,
+    /// The prefix of the keys of the claims
+    pub key_prefix: String,
+    /// How long a claim is held for, after which another instance relays
+    /// the packet if the claiming instance did not
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+}
+
+/// Default values for the packet claims.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for ClaimsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: HaBackend::default(),
+            key_prefix: "hermes/claims".to_string(),
+            ttl: Duration::from_secs(300),
+        }
+    }
+}
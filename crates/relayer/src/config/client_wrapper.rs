@@ -0,0 +1,122 @@
+//! Wrappers of the clients created on the chains which only accept the light clients
+//! wrapped in another client type, e.g. the Tendermint light client run by an `08-wasm`
+//! client, written `wasm:<checksum>` with the hex-encoded checksum of its contract.
+
+use core::fmt;
+use core::str::FromStr;
+
+use flex_error::define_error;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use ibc::clients::ics08_wasm;
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+
+define_error! {
+    ClientWrapperError {
+        Invalid
+            { wrapper: String, reason: String }
+            |e| { format_args!("invalid client wrapper '{}': {}", e.wrapper, e.reason) },
+    }
+}
+
+/// How the states and headers of the clients created on a chain are wrapped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientWrapper {
+    /// Wrapped in `08-wasm` clients running the contract of the given checksum
+    Wasm { checksum: Vec<u8> },
+}
+
+impl ClientWrapper {
+    pub fn wrap_client_state(&self, client_state: Any, latest_height: Height) -> Any {
+        match self {
+            Self::Wasm { checksum } => {
+                ics08_wasm::wrap_client_state(client_state, checksum.clone(), latest_height)
+            }
+        }
+    }
+
+    pub fn wrap_consensus_state(&self, consensus_state: Any) -> Any {
+        match self {
+            Self::Wasm { .. } => ics08_wasm::wrap_consensus_state(consensus_state),
+        }
+    }
+
+    /// Wrap a header or a misbehaviour.
+    pub fn wrap_client_message(&self, message: Any) -> Any {
+        match self {
+            Self::Wasm { .. } => ics08_wasm::wrap_client_message(message),
+        }
+    }
+}
+
+impl FromStr for ClientWrapper {
+    type Err = ClientWrapperError;
+
+    fn from_str(wrapper: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| ClientWrapperError::invalid(wrapper.to_string(), reason.to_string());
+
+        match wrapper.split_once(':') {
+            Some(("wasm", checksum)) => {
+                let checksum = hex::decode(checksum)
+                    .map_err(|_| invalid("the checksum is not hex-encoded"))?;
+
+                if checksum.is_empty() {
+                    return Err(invalid("the checksum is empty"));
+                }
+
+                Ok(Self::Wasm { checksum })
+            }
+            _ => Err(invalid("expected 'wasm:<checksum>'")),
+        }
+    }
+}
+
+impl fmt::Display for ClientWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wasm { checksum } => write!(f, "wasm:{}", hex::encode(checksum)),
+        }
+    }
+}
+
+impl Serialize for ClientWrapper {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClientWrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ClientWrapper, D::Error> {
+        let wrapper = String::deserialize(deserializer)?;
+        wrapper.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_client_wrapper() {
+        let wrapper: ClientWrapper = "wasm:0aff".parse().unwrap();
+
+        assert_eq!(
+            wrapper,
+            ClientWrapper::Wasm {
+                checksum: vec![0x0a, 0xff]
+            }
+        );
+        assert_eq!(wrapper.to_string(), "wasm:0aff");
+
+        for wrapper in ["wasm:", "wasm:xyz", "0aff", "solo:0aff"] {
+            assert!(wrapper.parse::<ClientWrapper>().is_err(), "{wrapper}");
+        }
+    }
+}
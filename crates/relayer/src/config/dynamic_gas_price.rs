@@ -0,0 +1,114 @@
+//! Gas prices following the fee market of a chain, queried periodically from the module
+//! setting the minimum gas price of the chain from its recent load, so that the transactions
+//! of the relayer are not stuck during congestion, nor overpay once the load drops.
+//!
+//! The price queried is multiplied by a margin and bounded, so that a faulty or manipulated
+//! fee market cannot make the relayer pay an arbitrary price. The configured `gas_price`
+//! is used until the first query succeeds, and the last price is kept when a query fails.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicGasPrice {
+    /// The module the gas price is queried from.
+    #[serde(default)]
+    pub source: GasPriceSource,
+
+    /// The factor the queried gas price is multiplied by, so that the transactions still
+    /// pay enough if the price rises before they are included in a block.
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+
+    /// The lowest gas price paid, whatever the fee market. Default: the price of `gas_price`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+
+    /// The highest gas price paid, whatever the fee market.
+    pub max: f64,
+
+    /// How often the gas price is queried.
+    #[serde(default = "default_refresh_interval", with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+impl DynamicGasPrice {
+    /// The gas price paid for the given price queried from the fee market, given the
+    /// static gas price configured for the chain.
+    pub fn bounded_price(&self, queried_price: f64, static_price: f64) -> f64 {
+        let min = self.min.unwrap_or(static_price);
+
+        // Not using `f64::clamp`, which panics if the bounds are inverted
+        (queried_price * self.multiplier).max(min).min(self.max)
+    }
+}
+
+/// The module setting the minimum gas price of a chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GasPriceSource {
+    /// The `feemarket` module of Skip, implementing an EIP-1559 base fee
+    #[serde(rename = "feemarket")]
+    FeeMarket,
+    /// The `txfees` module of Osmosis, whose EIP-1559 base fee is in the native denomination
+    #[serde(rename = "osmosis-txfees")]
+    OsmosisTxFees,
+}
+
+impl Default for GasPriceSource {
+    fn default() -> Self {
+        Self::FeeMarket
+    }
+}
+
+fn default_multiplier() -> f64 {
+    1.1
+}
+
+fn default_refresh_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dynamic_gas_price() {
+        let toml_content = r#"
+            source = 'osmosis-txfees'
+            max = 0.1
+            "#;
+
+        let dynamic: DynamicGasPrice = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(dynamic.source, GasPriceSource::OsmosisTxFees);
+        assert_eq!(dynamic.multiplier, default_multiplier());
+        assert_eq!(dynamic.refresh_interval, default_refresh_interval());
+
+        assert!(toml::from_str::<DynamicGasPrice>("multiplier = 1.2").is_err());
+    }
+
+    #[test]
+    fn bounded_prices() {
+        let dynamic = DynamicGasPrice {
+            source: GasPriceSource::FeeMarket,
+            multiplier: 2.0,
+            min: None,
+            max: 1.0,
+            refresh_interval: default_refresh_interval(),
+        };
+
+        assert_eq!(dynamic.bounded_price(0.1, 0.05), 0.2);
+        assert_eq!(dynamic.bounded_price(0.01, 0.05), 0.05);
+        assert_eq!(dynamic.bounded_price(10.0, 0.05), 1.0);
+
+        let dynamic = DynamicGasPrice {
+            min: Some(0.001),
+            ..dynamic
+        };
+
+        assert_eq!(dynamic.bounded_price(0.0001, 0.05), 0.001);
+    }
+}
@@ -0,0 +1,62 @@
+//! The source of the events of a chain: pushed by its node over a WebSocket subscription,
+//! or pulled by polling the results of its new blocks over RPC, for the nodes whose
+//! providers throttle or drop the WebSocket subscriptions.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum EventSource {
+    /// The events are pushed by the node over a subscription to its `websocket_addr`
+    Push,
+    /// The events are pulled from the results of the new blocks, queried at `rpc_addr`
+    Pull {
+        /// How often the node is polled for new blocks
+        #[serde(default = "default_interval", with = "humantime_serde")]
+        interval: Duration,
+    },
+}
+
+impl Default for EventSource {
+    fn default() -> Self {
+        Self::Push
+    }
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Chain {
+        #[serde(default)]
+        event_source: EventSource,
+    }
+
+    #[test]
+    fn event_source_modes() {
+        let parse =
+            |toml_content: &str| toml::from_str::<Chain>(toml_content).unwrap().event_source;
+
+        assert_eq!(parse(""), EventSource::Push);
+        assert_eq!(parse("event_source = { mode = 'push' }"), EventSource::Push);
+        assert_eq!(
+            parse("event_source = { mode = 'pull' }"),
+            EventSource::Pull {
+                interval: default_interval()
+            }
+        );
+        assert_eq!(
+            parse("event_source = { mode = 'pull', interval = '500ms' }"),
+            EventSource::Pull {
+                interval: Duration::from_millis(500)
+            }
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Replenishment of the balance of the fee token of the relayer account on a chain, by
+//! swapping a reserve token held by the same account on a DEX of the chain whenever the
+//! balance runs low, so that unattended relayers keep paying their fees.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeReplenishment {
+    /// The balance of the fee token, i.e. the denomination of the gas price,
+    /// below which the reserve token is swapped into the fee token.
+    pub threshold: u64,
+
+    /// The denomination of the reserve token, held by the relayer account.
+    pub reserve_denom: String,
+
+    /// The module of the DEX the swaps are made with.
+    #[serde(default)]
+    pub module: SwapModule,
+
+    /// The pool the swaps are made in, which must trade the reserve token for the fee token.
+    pub pool_id: u64,
+
+    /// The amount of the reserve token swapped at once.
+    pub swap_amount: u64,
+
+    /// The minimum amount of the fee token received by a swap, which fails otherwise.
+    /// Bounds the price paid for the fee token, whatever the state of the pool.
+    pub min_amount_out: u64,
+
+    /// How many swaps at most are made over any period of 24 hours.
+    #[serde(default = "default_max_swaps_per_day")]
+    pub max_swaps_per_day: u32,
+}
+
+/// The module of the DEX making the swaps, which determines the message swapping the tokens.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapModule {
+    /// The `poolmanager` module of Osmosis, which routes the swaps to all the types of pools
+    PoolManager,
+    /// The `gamm` module of Osmosis, used by its older releases
+    Gamm,
+}
+
+impl Default for SwapModule {
+    fn default() -> Self {
+        Self::PoolManager
+    }
+}
+
+fn default_max_swaps_per_day() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fee_replenishment() {
+        let toml_content = r#"
+            threshold = 1000000
+            reserve_denom = 'ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2'
+            pool_id = 1
+            swap_amount = 5000000
+            min_amount_out = 2000000
+            "#;
+
+        let replenishment: FeeReplenishment = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(replenishment.module, SwapModule::PoolManager);
+        assert_eq!(replenishment.max_swaps_per_day, 1);
+
+        let toml_content = format!("{}\nmodule = 'gamm'\nmax_swaps_per_day = 3", toml_content);
+        let replenishment: FeeReplenishment = toml::from_str(&toml_content).unwrap();
+
+        assert_eq!(replenishment.module, SwapModule::Gamm);
+        assert_eq!(replenishment.max_swaps_per_day, 3);
+    }
+}
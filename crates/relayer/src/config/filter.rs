@@ -1,12 +1,33 @@
 //! Custom `serde` deserializer for `FilterMatch`
 
+use alloc::collections::BTreeMap;
 use core::fmt;
 use core::str::FromStr;
+use std::sync::RwLock;
 
-use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::applications::transfer::amount::Amount;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use super::memo_guard::MemoGuard;
+
+/// The packet filters reloaded at runtime, e.g. on `SIGHUP`, which take precedence
+/// over the ones the chains were configured with when Hermes started.
+static RELOADED: Lazy<RwLock<BTreeMap<ChainId, PacketFilter>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// Replace the packet filters of the chains with the given ones.
+pub fn reload(filters: impl IntoIterator<Item = (ChainId, PacketFilter)>) {
+    *RELOADED.write().unwrap() = filters.into_iter().collect();
+}
+
+/// Apply the given function to the packet filter reloaded for the given chain, if any.
+pub fn with_reloaded<R>(chain_id: &ChainId, f: impl FnOnce(&PacketFilter) -> R) -> Option<R> {
+    RELOADED.read().unwrap().get(chain_id).map(f)
+}
+
 /// Represents the ways in which packets can be filtered.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(
@@ -22,6 +43,9 @@ pub enum PacketFilter {
     Deny(ChannelFilters),
     /// Allow any & all packets.
     AllowAll,
+    /// Allow or deny the messages relayed from the specified channels as decided by the
+    /// first rule matching them, denying the ones of the channels no rule matches.
+    Rules(Vec<FilterRule>),
 }
 
 impl Default for PacketFilter {
@@ -39,6 +63,133 @@ impl PacketFilter {
             PacketFilter::Allow(filters) => filters.matches((port_id, channel_id)),
             PacketFilter::Deny(filters) => !filters.matches((port_id, channel_id)),
             PacketFilter::AllowAll => true,
+            PacketFilter::Rules(_) => RelayKind::ALL
+                .iter()
+                .any(|kind| self.allows(port_id, channel_id, *kind)),
+        }
+    }
+
+    /// Returns true if the given kind of messages can be relayed from the channel with
+    /// [`PortId`] and [`ChannelId`], false otherwise.
+    pub fn allows(&self, port_id: &PortId, channel_id: &ChannelId, kind: RelayKind) -> bool {
+        match self {
+            PacketFilter::Rules(rules) => rules
+                .iter()
+                .find(|rule| rule.matches(port_id, channel_id))
+                .map_or(false, |rule| rule.allows(kind)),
+            _ => self.is_allowed(port_id, channel_id),
+        }
+    }
+
+    /// Returns true if an ICS-20 transfer of the given amount of the given denomination,
+    /// as written in its packet, can be received from the channel with [`PortId`] and
+    /// [`ChannelId`], i.e. if it is not below the minimum amount of the denomination in
+    /// the rule matching the channel, false otherwise.
+    pub fn allows_transfer(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        denom: &str,
+        amount: Amount,
+    ) -> bool {
+        let min_amount = match self {
+            PacketFilter::Rules(rules) => rules
+                .iter()
+                .find(|rule| rule.matches(port_id, channel_id))
+                .and_then(|rule| rule.min_amounts.iter().find(|min| min.denom == denom)),
+            _ => None,
+        };
+
+        min_amount.map_or(true, |min| amount >= Amount::from(min.amount))
+    }
+
+    /// The validation of the memos of the ICS-20 transfers received from the channel
+    /// with [`PortId`] and [`ChannelId`], in the rule matching the channel, if any.
+    pub fn memo_guard(&self, port_id: &PortId, channel_id: &ChannelId) -> Option<&MemoGuard> {
+        match self {
+            PacketFilter::Rules(rules) => rules
+                .iter()
+                .find(|rule| rule.matches(port_id, channel_id))
+                .and_then(|rule| rule.memo_guard.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// The messages relayed from a channel end, on behalf of the packets sent from it and of
+/// the ones it received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayKind {
+    /// The `MsgRecvPacket`s of the packets sent from the channel end
+    Recv,
+    /// The `MsgAcknowledgement`s of the packets it received
+    Ack,
+    /// The `MsgTimeout`s of the packets sent from it
+    Timeout,
+}
+
+impl RelayKind {
+    pub const ALL: [RelayKind; 3] = [RelayKind::Recv, RelayKind::Ack, RelayKind::Timeout];
+}
+
+impl fmt::Display for RelayKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Recv => write!(f, "recv"),
+            Self::Ack => write!(f, "ack"),
+            Self::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    Allow,
+    Deny,
+}
+
+/// A rule of a [`PacketFilter::Rules`] filter, for the channels matching the given port
+/// and channel patterns. An `allow` rule allows only the given kinds of messages to be
+/// relayed from them, and a `deny` rule all but those, the kinds being all of them if none
+/// is given.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterRule {
+    pub action: FilterAction,
+    pub port_id: PortFilterMatch,
+    pub channel_id: ChannelFilterMatch,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relay: Vec<RelayKind>,
+    /// The minimum amounts of the ICS-20 transfers received from the channels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub min_amounts: Vec<MinAmount>,
+    /// The validation of the memos of the ICS-20 transfers received from the channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo_guard: Option<MemoGuard>,
+}
+
+/// The minimum amount of the ICS-20 transfers of a denomination, as written in their
+/// packets, e.g. `uatom` or `transfer/channel-0/uosmo`, for them to be relayed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MinAmount {
+    pub denom: String,
+    pub amount: u64,
+}
+
+impl FilterRule {
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port_id.matches(port_id) && self.channel_id.matches(channel_id)
+    }
+
+    pub fn allows(&self, kind: RelayKind) -> bool {
+        let applies = self.relay.is_empty() || self.relay.contains(&kind);
+
+        match self.action {
+            FilterAction::Allow => applies,
+            FilterAction::Deny => !applies,
         }
     }
 }
@@ -503,6 +654,122 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn packet_filter_rules() {
+        let rules_policy = r#"
+            policy = 'rules'
+            list = [
+              { action = 'deny', port_id = 'transfer', channel_id = 'channel-9' },
+              { action = 'allow', port_id = 'transfer', channel_id = 'channel-1', relay = ['recv'] },
+              { action = 'deny', port_id = 'ica*', channel_id = '*', relay = ['ack'] },
+              { action = 'allow', port_id = 'transfer', channel_id = '*' },
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(rules_policy).expect("could not parse filter policy");
+
+        let allows = |port: &str, channel: &str, kind| {
+            pf.allows(
+                &PortId::from_str(port).unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+                kind,
+            )
+        };
+
+        // The first matching rule wins
+        assert!(!allows("transfer", "channel-9", RelayKind::Recv));
+        assert!(allows("transfer", "channel-8", RelayKind::Ack));
+
+        // The rules restricted to some messages
+        assert!(allows("transfer", "channel-1", RelayKind::Recv));
+        assert!(!allows("transfer", "channel-1", RelayKind::Ack));
+        assert!(!allows("icahost", "channel-2", RelayKind::Ack));
+        assert!(allows("icahost", "channel-2", RelayKind::Timeout));
+
+        // The channels no rule matches are denied
+        assert!(!allows("nft-transfer", "channel-0", RelayKind::Recv));
+
+        assert!(!pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-9").unwrap()
+        ));
+        assert!(pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_min_amounts() {
+        let rules_policy = r#"
+            policy = 'rules'
+
+            [[list]]
+            action = 'allow'
+            port_id = 'transfer'
+            channel_id = 'channel-0'
+            min_amounts = [
+              { denom = 'uatom', amount = 1000 },
+              { denom = 'transfer/channel-0/uosmo', amount = 5000 },
+            ]
+
+            [[list]]
+            action = 'allow'
+            port_id = 'transfer'
+            channel_id = '*'
+            "#;
+
+        let pf: PacketFilter = toml::from_str(rules_policy).expect("could not parse filter policy");
+
+        let allows = |channel: &str, denom: &str, amount: u64| {
+            pf.allows_transfer(
+                &PortId::from_str("transfer").unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+                denom,
+                Amount::from(amount),
+            )
+        };
+
+        assert!(!allows("channel-0", "uatom", 999));
+        assert!(allows("channel-0", "uatom", 1000));
+        assert!(!allows("channel-0", "transfer/channel-0/uosmo", 10));
+        assert!(allows("channel-0", "ujuno", 1));
+        assert!(allows("channel-1", "uatom", 1));
+    }
+
+    #[test]
+    fn packet_filter_memo_guard() {
+        let rules_policy = r#"
+            policy = 'rules'
+
+            [[list]]
+            action = 'allow'
+            port_id = 'transfer'
+            channel_id = 'channel-0'
+            memo_guard = { max_memo_bytes = 4096 }
+
+            [[list]]
+            action = 'allow'
+            port_id = 'transfer'
+            channel_id = '*'
+            "#;
+
+        let pf: PacketFilter = toml::from_str(rules_policy).expect("could not parse filter policy");
+
+        let memo_guard = |channel: &str| {
+            pf.memo_guard(
+                &PortId::from_str("transfer").unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+            )
+            .cloned()
+        };
+
+        let guard = memo_guard("channel-0").expect("channel-0 should have a memo guard");
+        assert_eq!(guard.max_memo_bytes, Some(4096));
+        assert!(guard.denied_contracts.is_empty());
+        assert!(memo_guard("channel-1").is_none());
+    }
+
     #[test]
     fn to_string_wildcards() {
         let wildcard = "ica*".parse::<Wildcard>().unwrap();
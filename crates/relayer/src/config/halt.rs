@@ -0,0 +1,52 @@
+//! Detection of the chains which stop producing blocks, be it for a planned upgrade
+//! or because of an outage, so that the workers relaying to and from such a chain
+//! are paused until new blocks appear, instead of building messages and client
+//! updates from its stale state.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HaltConfig {
+    pub enabled: bool,
+    /// How often the latest height of the chains is checked
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+    /// How long a chain must go without producing a block to be considered halted
+    #[serde(with = "humantime_serde")]
+    pub halt_threshold: Duration,
+}
+
+/// Default values for the detection of the halted chains.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for HaltConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval: Duration::from_secs(5),
+            halt_threshold: Duration::from_secs(60),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_halt() {
+        let toml_content = r#"
+            enabled = true
+            halt_threshold = '2m'
+            "#;
+
+        let config: HaltConfig = toml::from_str(toml_content).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.check_interval, Duration::from_secs(5));
+        assert_eq!(config.halt_threshold, Duration::from_secs(120));
+    }
+}
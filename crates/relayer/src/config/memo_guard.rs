@@ -0,0 +1,160 @@
+//! Validation of the memos of the ICS-20 transfers sent over the channels of a chain,
+//! before receiving them on the counterparty chain, as configured in the rules of its
+//! packet filter, see [`FilterRule`](super::filter::FilterRule).
+//!
+//! The memos of the transfers may trigger the execution of CosmWasm contracts on the
+//! chains running the ibc-hooks middleware, either on reception of the transfer, with a
+//! `{"wasm": {"contract": ..., "msg": ...}}` memo, or on its acknowledgement or timeout,
+//! with a `{"ibc_callback": ...}` memo. The huge memos, and the ones calling into known
+//! malicious contracts, regularly blow past the gas estimated for receiving them and
+//! wedge the workers relaying them, so that they can be rejected beforehand.
+//!
+//! A rejected transfer is not received, and is timed out as usual.
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Validates the memos of the transfers sent over the channels matched by a rule
+/// of the packet filter.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoGuard {
+    /// The maximum length in bytes of the memos, unlimited if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memo_bytes: Option<usize>,
+
+    /// The addresses of the contracts which the ibc-hooks memos must not call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_contracts: Vec<String>,
+}
+
+/// Why the memo of a transfer was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemoViolation {
+    TooLong { bytes: usize, max: usize },
+    DeniedContract(String),
+}
+
+impl Display for MemoViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::TooLong { bytes, max } => write!(
+                f,
+                "memo of {} bytes exceeds the maximum of {} bytes",
+                bytes, max
+            ),
+            Self::DeniedContract(contract) => {
+                write!(f, "memo calls the denied contract '{}'", contract)
+            }
+        }
+    }
+}
+
+impl MemoGuard {
+    /// Check the memo of the given packet data, which passes if it is not the JSON
+    /// data of a transfer with a memo.
+    pub fn check(&self, data: &[u8]) -> Result<(), MemoViolation> {
+        let memo = match packet_memo(data) {
+            Some(memo) => memo,
+            None => return Ok(()),
+        };
+
+        if let Some(max) = self.max_memo_bytes {
+            if memo.len() > max {
+                return Err(MemoViolation::TooLong {
+                    bytes: memo.len(),
+                    max,
+                });
+            }
+        }
+
+        if self.denied_contracts.is_empty() {
+            return Ok(());
+        }
+
+        // Memos which are not JSON objects do not call any contract
+        let memo: Value = match serde_json::from_str(&memo) {
+            Ok(memo) => memo,
+            Err(_) => return Ok(()),
+        };
+
+        let called = [memo.pointer("/wasm/contract"), memo.get("ibc_callback")];
+
+        match called
+            .iter()
+            .flatten()
+            .filter_map(|contract| contract.as_str())
+            .find(|contract| self.denied_contracts.iter().any(|c| c == contract))
+        {
+            Some(contract) => Err(MemoViolation::DeniedContract(contract.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The memo of the given ICS-20 packet data, if any.
+fn packet_memo(data: &[u8]) -> Option<String> {
+    let data: Value = serde_json::from_slice(data).ok()?;
+
+    data.get("memo")?
+        .as_str()
+        .filter(|memo| !memo.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn transfer(memo: &str) -> Vec<u8> {
+        json!({
+            "denom": "uosmo",
+            "amount": "100",
+            "sender": "osmo1sender",
+            "receiver": "juno1receiver",
+            "memo": memo,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn check_transfer_memos() {
+        let guard: MemoGuard = toml::from_str(
+            r#"
+            max_memo_bytes = 128
+            denied_contracts = ['juno1evil']
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(guard.check(&transfer("")), Ok(()));
+        assert_eq!(guard.check(&transfer("gm")), Ok(()));
+        assert_eq!(guard.check(b"not a transfer"), Ok(()));
+
+        assert_eq!(
+            guard.check(&transfer(&"a".repeat(129))),
+            Err(MemoViolation::TooLong {
+                bytes: 129,
+                max: 128
+            })
+        );
+
+        let wasm =
+            |contract: &str| json!({ "wasm": { "contract": contract, "msg": {} } }).to_string();
+
+        assert_eq!(guard.check(&transfer(&wasm("juno1good"))), Ok(()));
+        assert_eq!(
+            guard.check(&transfer(&wasm("juno1evil"))),
+            Err(MemoViolation::DeniedContract("juno1evil".to_string()))
+        );
+        assert_eq!(
+            guard.check(&transfer(r#"{"ibc_callback":"juno1evil"}"#)),
+            Err(MemoViolation::DeniedContract("juno1evil".to_string()))
+        );
+    }
+}
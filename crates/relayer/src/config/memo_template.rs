@@ -0,0 +1,120 @@
+//! The template of the memo of the transactions relaying packets, i.e. receiving and
+//! acknowledging them, for the attribution of the packets relayed to their relayer,
+//! e.g. by the name of its operator, by the analytics of the chains.
+//!
+//! The template may contain the following variables, which are replaced in the memo of
+//! each transaction by their value:
+//!
+//! - `{chain_id}`: the identifier of the chain the transaction is submitted to
+//! - `{port}`: the port of the channel of the packets relayed, on the chain
+//! - `{channel}`: the channel of the packets relayed, on the chain
+//! - `{version}`: the version of the relayer
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+const VARIABLES: [&str; 4] = ["chain_id", "port", "channel", "version"];
+
+/// The values of the variables of a memo template.
+#[derive(Clone, Debug)]
+pub struct MemoVariables<'a> {
+    pub chain_id: &'a str,
+    pub port: &'a str,
+    pub channel: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxMemoTemplate(String);
+
+impl TxMemoTemplate {
+    /// The maximum length of a rendered memo, which is the default maximum length
+    /// of the memo of the transactions accepted by the Cosmos SDK.
+    pub const MAX_RENDERED_LEN: usize = 256;
+
+    pub fn new(template: impl Into<String>) -> Result<Self, String> {
+        let template = template.into();
+
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            let (variable, after) = rest[start + 1..]
+                .split_once('}')
+                .ok_or_else(|| format!("unclosed variable in memo template '{}'", template))?;
+
+            if !VARIABLES.contains(&variable) {
+                return Err(format!(
+                    "unknown variable '{{{}}}' in memo template '{}', expected one of: {}",
+                    variable,
+                    template,
+                    VARIABLES.join(", ")
+                ));
+            }
+
+            rest = after;
+        }
+
+        Ok(Self(template))
+    }
+
+    /// Render the memo of the template with the given values of its variables.
+    pub fn render(&self, variables: &MemoVariables<'_>) -> String {
+        self.0
+            .replace("{chain_id}", variables.chain_id)
+            .replace("{port}", variables.port)
+            .replace("{channel}", variables.channel)
+            .replace("{version}", env!("CARGO_PKG_VERSION"))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for TxMemoTemplate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        TxMemoTemplate::new(value).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for TxMemoTemplate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl Display for TxMemoTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_memo_template() {
+        let template = TxMemoTemplate::new("relayed by acme on {chain_id}/{channel}").unwrap();
+
+        let memo = template.render(&MemoVariables {
+            chain_id: "osmosis-1",
+            port: "transfer",
+            channel: "channel-0",
+        });
+
+        assert_eq!(memo, "relayed by acme on osmosis-1/channel-0");
+
+        assert!(TxMemoTemplate::new("no variables").is_ok());
+        assert!(TxMemoTemplate::new("{version} on {port}").is_ok());
+        assert!(TxMemoTemplate::new("{sequence}").is_err());
+        assert!(TxMemoTemplate::new("{chain_id").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Minimum fees which must be escrowed for the packets of a channel, on chains running
+//! the ICS-29 fee middleware, for them to be relayed, so that the relayer is compensated
+//! at least for the gas it spends receiving them.
+//!
+//! A packet whose fees fall short is not received until more fees are escrowed for it,
+//! and is otherwise timed out as usual.
+
+use serde::{Deserialize, Serialize};
+
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
+use ibc::applications::transfer::amount::Amount;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::signer::Signer;
+
+use super::filter::{ChannelFilterMatch, PortFilterMatch};
+
+/// Requires the packets sent over the channels matching the given port and channel
+/// patterns to escrow receive fees of at least the given amount of the given denomination.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MinFee {
+    pub port_id: PortFilterMatch,
+    pub channel_id: ChannelFilterMatch,
+    pub denom: String,
+    pub amount: u64,
+}
+
+impl MinFee {
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port_id.matches(port_id) && self.channel_id.matches(channel_id)
+    }
+
+    /// Whether the given fees, escrowed for a packet, pay the given relayer enough
+    /// for receiving the packet.
+    pub fn is_met_by(&self, fees: Option<&IdentifiedPacketFees>, relayer: &Signer) -> bool {
+        let total = fees.map_or(Amount::from(0), |fees| {
+            fees.total_recv_fee(&self.denom, relayer)
+        });
+
+        total >= Amount::from(self.amount)
+    }
+}
+
+/// The minimum fee of the given channel according to the given list,
+/// in which the first matching entry wins.
+pub fn min_fee<'a>(
+    min_fees: &'a [MinFee],
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Option<&'a MinFee> {
+    min_fees.iter().find(|m| m.matches(port_id, channel_id))
+}
@@ -0,0 +1,92 @@
+//! Limits on the packets relayed over the channels of a chain, so that a channel
+//! flooded with packets cannot starve the other channels relayed with the same key,
+//! neither of the capacity of the chain runtime nor of the space in the blocks.
+
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+use super::filter::{ChannelFilterMatch, PortFilterMatch};
+use super::priority::Priority;
+
+/// Limits the packets sent over the channels matching the given port and channel patterns.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacketLimit {
+    pub port_id: PortFilterMatch,
+    pub channel_id: ChannelFilterMatch,
+
+    /// The priority of the requests made on behalf of the paths over the channels,
+    /// if not given by an entry of the `priorities` of the chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+
+    /// How many packets at most are received on the counterparty chain for each of
+    /// its blocks, the others being held until the next ones. Unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_packets_per_block: Option<u64>,
+}
+
+impl PacketLimit {
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port_id.matches(port_id) && self.channel_id.matches(channel_id)
+    }
+}
+
+/// The limits of the given channel according to the given list,
+/// in which the first matching entry wins.
+pub fn packet_limit<'a>(
+    limits: &'a [PacketLimit],
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Option<&'a PacketLimit> {
+    limits.iter().find(|l| l.matches(port_id, channel_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[derive(Debug, Deserialize)]
+    struct Limits {
+        packet_limits: Vec<PacketLimit>,
+    }
+
+    #[test]
+    fn parse_packet_limits() {
+        let toml_content = r#"
+            [[packet_limits]]
+            port_id = 'transfer'
+            channel_id = 'channel-0'
+            priority = 'high'
+
+            [[packet_limits]]
+            port_id = 'transfer'
+            channel_id = 'channel-*'
+            priority = 'low'
+            max_packets_per_block = 10
+            "#;
+
+        let Limits { packet_limits } = toml::from_str(toml_content).unwrap();
+
+        let limit = |port: &str, channel: &str| {
+            packet_limit(
+                &packet_limits,
+                &PortId::from_str(port).unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+            )
+        };
+
+        let high = limit("transfer", "channel-0").unwrap();
+        assert_eq!(high.priority, Some(Priority::High));
+        assert_eq!(high.max_packets_per_block, None);
+
+        let spammy = limit("transfer", "channel-7").unwrap();
+        assert_eq!(spammy.priority, Some(Priority::Low));
+        assert_eq!(spammy.max_packets_per_block, Some(10));
+
+        assert!(limit("icahost", "channel-0").is_none());
+    }
+}
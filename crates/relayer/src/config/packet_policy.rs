@@ -0,0 +1,39 @@
+//! An external policy service deciding whether the packets are relayed from their
+//! data, e.g. to filter out the transfers of sanctioned addresses or tokens, see
+//! [`crate::link::policy`].
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PacketPolicyConfig {
+    pub enabled: bool,
+    /// The endpoint the packets are posted to, which answers whether to relay them
+    pub url: String,
+    /// How long to wait for the answer of the service
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// Whether the packets are relayed when the service fails to answer,
+    /// instead of being held until it answers
+    pub fail_open: bool,
+    /// Whether the service is also asked about the packets of ordered channels,
+    /// a packet it denies holding the channel rather than being skipped
+    pub ordered_channels: bool,
+}
+
+/// Default values for the packet policy service.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for PacketPolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://127.0.0.1:8080/packets".to_string(),
+            timeout: Duration::from_secs(5),
+            fail_open: false,
+            ordered_channels: false,
+        }
+    }
+}
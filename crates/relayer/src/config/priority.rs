@@ -0,0 +1,100 @@
+//! Priorities of the paths relayed on a chain, so that the capacity of the chain
+//! runtime is used for the paths with the highest priority first when it is contended.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+use super::filter::{ChannelFilterMatch, PortFilterMatch};
+
+/// The priority of the requests made to the chain runtimes on behalf of a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Assigns a priority to the channels matching the given port and channel patterns.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelPriority {
+    pub port_id: PortFilterMatch,
+    pub channel_id: ChannelFilterMatch,
+    pub priority: Priority,
+}
+
+impl ChannelPriority {
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port_id.matches(port_id) && self.channel_id.matches(channel_id)
+    }
+}
+
+/// The priority of the given channel according to the given list,
+/// in which the first matching entry wins.
+pub fn channel_priority(
+    priorities: &[ChannelPriority],
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Priority {
+    priorities
+        .iter()
+        .find(|p| p.matches(port_id, channel_id))
+        .map(|p| p.priority)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[derive(Debug, Deserialize)]
+    struct Priorities {
+        priorities: Vec<ChannelPriority>,
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let toml_content = r#"
+            priorities = [
+                { port_id = 'transfer', channel_id = 'channel-0', priority = 'high' },
+                { port_id = 'transfer', channel_id = 'channel-*', priority = 'low' },
+            ]
+            "#;
+
+        let Priorities { priorities } = toml::from_str(toml_content).unwrap();
+
+        let priority = |port: &str, channel: &str| {
+            channel_priority(
+                &priorities,
+                &PortId::from_str(port).unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+            )
+        };
+
+        assert_eq!(priority("transfer", "channel-0"), Priority::High);
+        assert_eq!(priority("transfer", "channel-1"), Priority::Low);
+        assert_eq!(priority("icahost", "channel-1"), Priority::Normal);
+    }
+}
@@ -0,0 +1,108 @@
+//! The HTTP proxies through which the connections to the full nodes are made,
+//! for the relayers running in restricted networks or routing their traffic
+//! through a VPN egress.
+//!
+//! The proxies are only reached with HTTP, as the RPC client of `tendermint-rpc`
+//! does not support SOCKS5 proxies.
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+use core::str::FromStr;
+
+use http::uri::Uri;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+flex_error::define_error! {
+    Error {
+        InvalidUrl
+            { url: String }
+            |e| {
+                format_args!("invalid proxy URL `{}`, expected `http://host:port`", e.url)
+            },
+    }
+}
+
+/// An HTTP proxy, given by its URL, e.g. `http://127.0.0.1:3128`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proxy {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Proxy {
+    /// The address of the proxy, as `host:port`.
+    pub fn address(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl FromStr for Proxy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let uri = Uri::from_str(s).map_err(|_| Error::invalid_url(s.to_string()))?;
+
+        if uri.scheme_str() != Some("http") {
+            return Err(Error::invalid_url(s.to_string()));
+        }
+
+        match (uri.host(), uri.port_u16()) {
+            (Some(host), Some(port)) => Ok(Self {
+                host: host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string(),
+                port,
+            }),
+            _ => Err(Error::invalid_url(s.to_string())),
+        }
+    }
+}
+
+impl Display for Proxy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "http://{}", self.address())
+    }
+}
+
+impl<'de> Deserialize<'de> for Proxy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let url = String::deserialize(deserializer)?;
+
+        Proxy::from_str(&url).map_err(|e| D::Error::custom(e.to_string()))
+    }
+}
+
+impl Serialize for Proxy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proxy() {
+        let proxy = Proxy::from_str("http://127.0.0.1:3128").unwrap();
+        assert_eq!(proxy.address(), "127.0.0.1:3128");
+
+        let proxy = Proxy::from_str("http://[::1]:3128").unwrap();
+        assert_eq!(proxy.address(), "[::1]:3128");
+        assert_eq!(proxy.to_string(), "http://[::1]:3128");
+
+        assert!(Proxy::from_str("https://proxy:3128").is_err());
+        assert!(Proxy::from_str("socks5://127.0.0.1:9050").is_err());
+        assert!(Proxy::from_str("http://proxy").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Quarantine of the chains whose endpoints keep failing, so that the workers
+//! relaying to and from such a chain are stopped until it is healthy again,
+//! while relaying on all the other chains goes on.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuarantineConfig {
+    pub enabled: bool,
+    /// How often the endpoints of the chains which are not quarantined are checked
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+    /// How many consecutive checks must fail for a chain to be quarantined
+    pub failure_threshold: u32,
+    /// How often the endpoints of the quarantined chains are checked
+    #[serde(with = "humantime_serde")]
+    pub retry_interval: Duration,
+    /// How many consecutive checks must succeed for a chain to be released from quarantine
+    pub recovery_threshold: u32,
+    /// How long a check waits for the endpoints of a chain to respond,
+    /// beyond which the check of that chain counts as failed
+    #[serde(with = "humantime_serde")]
+    pub check_timeout: Duration,
+}
+
+/// Default values for the quarantine of the chains.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for QuarantineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval: Duration::from_secs(30),
+            failure_threshold: 3,
+            retry_interval: Duration::from_secs(10),
+            recovery_threshold: 2,
+            check_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_quarantine() {
+        let toml_content = r#"
+            enabled = true
+            check_interval = '1m'
+            failure_threshold = 5
+            "#;
+
+        let config: QuarantineConfig = toml::from_str(toml_content).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.check_interval, Duration::from_secs(60));
+        assert_eq!(config.failure_threshold, 5);
+        assert_eq!(config.retry_interval, Duration::from_secs(10));
+        assert_eq!(config.recovery_threshold, 2);
+        assert_eq!(config.check_timeout, Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,118 @@
+//! Limits on the resources used by the runtime of a chain, so that a chain
+//! producing huge blocks or floods of events cannot exhaust the resources
+//! of the process and degrade relaying on all the other chains.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeQuotas {
+    /// How many requests of any kind (queries, transaction submissions, etc.) at most
+    /// can be queued for the chain runtime. Beyond that, the callers wait for the
+    /// runtime to catch up before their requests are accepted.
+    ///
+    /// The bound applies twice: to the requests the runtime has picked up and is
+    /// ordering by priority, and to the requests still waiting to be picked up,
+    /// so up to twice that many requests can be queued at once.
+    /// Unlimited if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pending_requests: Option<usize>,
+
+    /// How many queries at most are made concurrently by a single request which
+    /// fans out into several of them, i.e. cross-chain queries and batched packet
+    /// queries. Does not limit the other requests. Unlimited if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_queries: Option<usize>,
+
+    /// How many subscriptions at most can be made to the events of the chain.
+    /// Subscribing beyond that fails. Unlimited if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_subscriptions: Option<usize>,
+
+    /// How many batches of events at most are queued for each subscriber.
+    /// This is a number of batches, not of events nor of bytes: a batch holds
+    /// all the events of a block, however many there are. The batches arriving while the queue of a subscriber is full are dropped
+    /// for that subscriber, and the packets they refer to are left to be relayed
+    /// on the next packet clearing. Unlimited if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_queued_event_batches: Option<usize>,
+
+    /// How many entries at most are kept in each of the caches of the chain
+    /// (channels, connections and client states). This is a number of entries,
+    /// not of bytes, whatever the size of the entries.
+    pub max_cache_entries: u64,
+}
+
+/// Default values for the runtime quotas.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for RuntimeQuotas {
+    fn default() -> Self {
+        Self {
+            max_pending_requests: None,
+            max_concurrent_queries: None,
+            max_subscriptions: None,
+            max_queued_event_batches: None,
+            max_cache_entries: 10_000,
+        }
+    }
+}
+
+impl RuntimeQuotas {
+    /// How many requests at most the chain runtime accepts to handle at once, if limited.
+    /// The runtime always accepts at least one request, so that it makes progress.
+    pub fn request_queue_bound(&self) -> Option<usize> {
+        self.max_pending_requests.map(|max| max.max(1))
+    }
+
+    /// Whether the chain runtime cannot accept more requests
+    /// while it has the given number of them to handle.
+    pub fn requests_exhausted(&self, pending: usize) -> bool {
+        self.request_queue_bound()
+            .map_or(false, |max| pending >= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_quotas() {
+        let toml_content = r#"
+            max_subscriptions = 2
+            max_cache_entries = 100
+            "#;
+
+        let quotas: RuntimeQuotas = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(quotas.max_pending_requests, None);
+        assert_eq!(quotas.max_concurrent_queries, None);
+        assert_eq!(quotas.max_subscriptions, Some(2));
+        assert_eq!(quotas.max_cache_entries, 100);
+        assert!(!quotas.requests_exhausted(usize::MAX));
+
+        let quotas = RuntimeQuotas {
+            max_concurrent_queries: Some(1),
+            ..Default::default()
+        };
+
+        assert!(!quotas.requests_exhausted(usize::MAX));
+
+        let quotas = RuntimeQuotas {
+            max_pending_requests: Some(10),
+            ..Default::default()
+        };
+
+        assert!(!quotas.requests_exhausted(9));
+        assert!(quotas.requests_exhausted(10));
+
+        let quotas = RuntimeQuotas {
+            max_pending_requests: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(quotas.request_queue_bound(), Some(1));
+        assert!(!quotas.requests_exhausted(0));
+    }
+}
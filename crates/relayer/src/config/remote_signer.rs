@@ -0,0 +1,49 @@
+//! Signing of the transactions of a chain by a remote signer, e.g. a threshold signer or
+//! the transit engine of HashiCorp Vault behind a gRPC gateway, so that the private key of
+//! the relayer account never reaches the host of the relayer.
+//!
+//! The remote signer holds the key identified by `key_id`, whose public key it exposes,
+//! and signs the `SignDoc` of the transactions of the chain with it, see
+//! [`RemoteSigner`](crate::keyring::remote_signer::RemoteSigner).
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteSignerConfig {
+    /// The gRPC endpoint of the remote signer.
+    pub url: tendermint_rpc::Url,
+
+    /// The identifier of the key held by the remote signer which signs the transactions.
+    pub key_id: String,
+
+    /// How long a request to the remote signer may take before it fails.
+    #[serde(default = "default_timeout", with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_remote_signer() {
+        let toml_content = r#"
+            url = 'http://127.0.0.1:9091'
+            key_id = 'hermes-osmosis'
+            "#;
+
+        let config: RemoteSignerConfig = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.key_id, "hermes-osmosis");
+        assert_eq!(config.timeout, default_timeout());
+
+        assert!(toml::from_str::<RemoteSignerConfig>("key_id = 'hermes-osmosis'").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Policies for restarting the workers which crashed, i.e. whose tasks
+//! stopped after encountering a fatal error or panicking.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::object::ObjectType;
+
+/// What to do once a crashed worker has exhausted its restarts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GiveUp {
+    /// Log an error and record it in the telemetry, leaving the worker stopped
+    Alert,
+    /// Shut the relayer down gracefully and exit with an error,
+    /// for the process manager to restart it
+    Exit,
+}
+
+impl Default for GiveUp {
+    fn default() -> Self {
+        Self::Alert
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RestartPolicy {
+    /// How many times a crashed worker is restarted before giving up on it
+    pub max_restarts: u32,
+    /// How long to wait before the first restart
+    #[serde(with = "humantime_serde")]
+    pub initial_backoff: Duration,
+    /// How long to wait at most before a restart
+    #[serde(with = "humantime_serde")]
+    pub max_backoff: Duration,
+    /// By how much the backoff is multiplied after each restart
+    pub backoff_multiplier: u32,
+    /// How long a restarted worker must run without crashing
+    /// for its count of restarts to be reset
+    #[serde(with = "humantime_serde")]
+    pub reset_after: Duration,
+    pub on_give_up: GiveUp,
+}
+
+/// Default values for the restart policy.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+            backoff_multiplier: 2,
+            reset_after: Duration::from_secs(10 * 60),
+            on_give_up: GiveUp::Alert,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// How long to wait before restarting a worker which was already restarted
+    /// the given number of times: `initial_backoff * backoff_multiplier ^ restarts`,
+    /// capped by `max_backoff`.
+    pub fn backoff(&self, restarts: u32) -> Duration {
+        let factor = self.backoff_multiplier.saturating_pow(restarts);
+
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RestartConfig {
+    pub enabled: bool,
+    /// The policy for the workers of the types which do not override it
+    pub policy: RestartPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packet: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<RestartPolicy>,
+}
+
+impl RestartConfig {
+    /// The restart policy for the workers of the given type.
+    pub fn policy_for(&self, object_type: ObjectType) -> &RestartPolicy {
+        let policy = match object_type {
+            ObjectType::Client => &self.client,
+            ObjectType::Connection => &self.connection,
+            ObjectType::Channel => &self.channel,
+            ObjectType::Packet => &self.packet,
+            ObjectType::Wallet => &self.wallet,
+            ObjectType::CrossChainQueryPacket => &None,
+        };
+
+        policy.as_ref().unwrap_or(&self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff() {
+        let policy = RestartPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            backoff_multiplier: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(5), Duration::from_secs(32));
+        assert_eq!(policy.backoff(6), Duration::from_secs(60));
+        assert_eq!(policy.backoff(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn overrides() {
+        let toml_content = r#"
+            enabled = true
+            policy = { max_restarts = 3, initial_backoff = '2s' }
+            packet = { max_restarts = 10, on_give_up = 'exit' }
+            "#;
+
+        let config: RestartConfig = toml::from_str(toml_content).unwrap();
+
+        let client = config.policy_for(ObjectType::Client);
+        assert_eq!(client.max_restarts, 3);
+        assert_eq!(client.initial_backoff, Duration::from_secs(2));
+        assert_eq!(client.on_give_up, GiveUp::Alert);
+
+        let packet = config.policy_for(ObjectType::Packet);
+        assert_eq!(packet.max_restarts, 10);
+        assert_eq!(packet.initial_backoff, Duration::from_secs(1));
+        assert_eq!(packet.on_give_up, GiveUp::Exit);
+    }
+}
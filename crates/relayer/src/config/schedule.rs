@@ -0,0 +1,280 @@
+//! Windows of time during which the packets of a path are relayed, so that the
+//! operators of low-traffic paths can relay them in batches at the times of their
+//! choosing, e.g. outside the hours when the fees are the highest.
+//!
+//! Outside of its windows, a path only relays the timeouts of its packets, and
+//! receives those of its packets which are about to time out.
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flex_error::define_error;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+use super::filter::{ChannelFilterMatch, PortFilterMatch};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+define_error! {
+    TimeWindowError {
+        Invalid
+            { window: String, reason: String }
+            |e| { format_args!("invalid time window '{}': {}", e.window, e.reason) },
+    }
+}
+
+/// Restricts the relaying of the packets over the channels matching the given port
+/// and channel patterns to the given windows of time.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelaySchedule {
+    pub port_id: PortFilterMatch,
+    pub channel_id: ChannelFilterMatch,
+
+    /// The windows of time, in UTC, during which the packets are relayed
+    pub windows: Vec<TimeWindow>,
+
+    /// How long before their timeout the packets are received outside of the windows
+    #[serde(default = "default_timeout_margin", with = "humantime_serde")]
+    pub timeout_margin: Duration,
+}
+
+impl RelaySchedule {
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port_id.matches(port_id) && self.channel_id.matches(channel_id)
+    }
+
+    /// Whether the given time falls within one of the windows.
+    pub fn is_active_at(&self, time: SystemTime) -> bool {
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        // The 1st of January 1970 was a Thursday
+        let weekday = ((secs / SECS_PER_DAY + 3) % 7) as u8;
+        let minute = ((secs % SECS_PER_DAY) / 60) as u16;
+
+        self.windows
+            .iter()
+            .any(|window| window.contains(weekday, minute))
+    }
+}
+
+fn default_timeout_margin() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
+/// The schedule of the given channel according to the given list,
+/// in which the first matching entry wins.
+pub fn relay_schedule<'a>(
+    schedules: &'a [RelaySchedule],
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Option<&'a RelaySchedule> {
+    schedules.iter().find(|s| s.matches(port_id, channel_id))
+}
+
+/// A window of time repeated on some days of the week, written `[<days>] <start>-<end>`,
+/// e.g. `mon-fri 08:00-18:00`, `sat,sun 00:00-24:00` or `22:00-06:00`. The days are
+/// every day if omitted, and are the ones the window starts on when it spans midnight.
+#[derive(Clone, Debug)]
+pub struct TimeWindow {
+    spec: String,
+    /// The days of the week the window starts on, Monday being the lowest bit
+    days: u8,
+    /// The minutes of the day the window starts and ends at
+    start: u16,
+    end: u16,
+}
+
+impl TimeWindow {
+    /// Whether the window contains the given minute of the given day of the week, Monday being 0.
+    fn contains(&self, weekday: u8, minute: u16) -> bool {
+        let starts_on = |day: u8| self.days & (1 << day) != 0;
+
+        if self.start < self.end {
+            starts_on(weekday) && self.start <= minute && minute < self.end
+        } else {
+            (starts_on(weekday) && minute >= self.start)
+                || (starts_on((weekday + 6) % 7) && minute < self.end)
+        }
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = TimeWindowError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| TimeWindowError::invalid(spec.to_string(), reason.to_string());
+
+        let (days, times) = match spec.trim().rsplit_once(' ') {
+            Some((days, times)) => (
+                parse_days(days.trim()).ok_or_else(|| invalid("invalid days"))?,
+                times,
+            ),
+            None => (0b111_1111, spec.trim()),
+        };
+
+        let (start, end) = times
+            .split_once('-')
+            .ok_or_else(|| invalid("expected '<start>-<end>'"))?;
+
+        let start = parse_minute(start).ok_or_else(|| invalid("invalid start time"))?;
+        let end = parse_minute(end).ok_or_else(|| invalid("invalid end time"))?;
+
+        if start == MINUTES_PER_DAY {
+            return Err(invalid("the window cannot start at 24:00"));
+        }
+
+        if start == end {
+            return Err(invalid("the window is empty"));
+        }
+
+        Ok(Self {
+            spec: spec.to_string(),
+            days,
+            start,
+            end: end % MINUTES_PER_DAY,
+        })
+    }
+}
+
+/// Parse a list of days or ranges of days, e.g. `mon-fri,sun`, into a set of days.
+fn parse_days(days: &str) -> Option<u8> {
+    let weekday = |day: &str| {
+        WEEKDAYS
+            .iter()
+            .position(|d| d.eq_ignore_ascii_case(day.trim()))
+    };
+
+    days.split(',').try_fold(0, |set, range| {
+        let (first, last) = match range.split_once('-') {
+            Some((first, last)) => (weekday(first)?, weekday(last)?),
+            None => (weekday(range)?, weekday(range)?),
+        };
+
+        // The range wraps around the end of the week when it ends before it starts
+        let len = (last + 7 - first) % 7 + 1;
+
+        Some((0..len).fold(set, |set, i| set | (1 << ((first + i) % 7))))
+    })
+}
+
+/// Parse a time of the day `HH:MM` into the minutes elapsed since midnight, up to `24:00`.
+fn parse_minute(time: &str) -> Option<u16> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u16, u16) = (hours.parse().ok()?, minutes.parse().ok()?);
+
+    let minute = hours.checked_mul(60)?.checked_add(minutes)?;
+    (minutes < 60 && minute <= MINUTES_PER_DAY).then(|| minute)
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec)
+    }
+}
+
+impl PartialEq for TimeWindow {
+    fn eq(&self, other: &Self) -> bool {
+        self.spec == other.spec
+    }
+}
+
+impl Serialize for TimeWindow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.spec)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeWindow {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TimeWindow, D::Error> {
+        let spec = String::deserialize(deserializer)?;
+        spec.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Schedules {
+        schedules: Vec<RelaySchedule>,
+    }
+
+    /// The given time of the first week of 1970, which started on Thursday the 1st of January.
+    fn at(weekday: &str, hours: u64, minutes: u64) -> SystemTime {
+        let day = (WEEKDAYS.iter().position(|d| *d == weekday).unwrap() as u64 + 4) % 7;
+        UNIX_EPOCH + Duration::from_secs(day * SECS_PER_DAY + hours * 3600 + minutes * 60)
+    }
+
+    #[test]
+    fn windows_of_time() {
+        let toml_content = r#"
+            schedules = [
+                { port_id = 'transfer', channel_id = 'channel-0', windows = ['mon-fri 08:00-18:00', 'sat 22:00-02:00'] },
+                { port_id = 'transfer', channel_id = 'channel-*', windows = ['23:00-24:00'], timeout_margin = '30m' },
+            ]
+            "#;
+
+        let Schedules { schedules } = toml::from_str(toml_content).unwrap();
+
+        let weekdays = &schedules[0];
+        assert_eq!(weekdays.timeout_margin, default_timeout_margin());
+
+        assert!(weekdays.is_active_at(at("mon", 8, 0)));
+        assert!(weekdays.is_active_at(at("fri", 17, 59)));
+        assert!(!weekdays.is_active_at(at("fri", 18, 0)));
+        assert!(!weekdays.is_active_at(at("sat", 12, 0)));
+
+        // The window starting on Saturday spans midnight
+        assert!(weekdays.is_active_at(at("sat", 23, 0)));
+        assert!(weekdays.is_active_at(at("sun", 1, 59)));
+        assert!(!weekdays.is_active_at(at("sun", 2, 0)));
+        assert!(!weekdays.is_active_at(at("sat", 1, 0)));
+
+        let nightly = &schedules[1];
+        assert!(nightly.is_active_at(at("wed", 23, 30)));
+        assert!(!nightly.is_active_at(at("thu", 0, 0)));
+
+        let schedule = |channel: &str| {
+            relay_schedule(
+                &schedules,
+                &PortId::transfer(),
+                &ChannelId::from_str(channel).unwrap(),
+            )
+        };
+
+        assert_eq!(schedule("channel-0"), Some(weekdays));
+        assert_eq!(schedule("channel-1"), Some(nightly));
+    }
+
+    #[test]
+    fn invalid_windows() {
+        for spec in [
+            "08:00",
+            "mon-fry 08:00-18:00",
+            "08:00-25:00",
+            "9999:00-10:00",
+            "24:00-06:00",
+            "08:00-08:00",
+        ] {
+            assert!(spec.parse::<TimeWindow>().is_err(), "{spec}");
+        }
+
+        assert_eq!(parse_days("fri-mon"), Some(0b111_0001));
+    }
+}
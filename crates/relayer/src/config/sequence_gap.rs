@@ -0,0 +1,57 @@
+//! Detection of the gaps between the packets sent on the ordered channels and the
+//! packets received by their counterparty, as an ordered channel whose gap keeps
+//! growing or whose counterparty stops receiving is effectively dead: none of its
+//! packets after the first one which was not received can be received either.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SequenceGapConfig {
+    pub enabled: bool,
+    /// How often the gaps of the ordered channels are checked
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+    /// Number of packets committed but not received above which a gap is reported
+    pub max_gap: u64,
+    /// How long the counterparty must go without receiving any packet for a gap to be reported
+    #[serde(with = "humantime_serde")]
+    pub stall_threshold: Duration,
+}
+
+/// Default values for the detection of the sequence gaps.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for SequenceGapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval: Duration::from_secs(60),
+            max_gap: 100,
+            stall_threshold: Duration::from_secs(600),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sequence_gap() {
+        let toml_content = r#"
+            enabled = true
+            max_gap = 10
+            stall_threshold = '30m'
+            "#;
+
+        let config: SequenceGapConfig = toml::from_str(toml_content).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.check_interval, Duration::from_secs(60));
+        assert_eq!(config.max_gap, 10);
+        assert_eq!(config.stall_threshold, Duration::from_secs(1800));
+    }
+}
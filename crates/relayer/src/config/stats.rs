@@ -0,0 +1,51 @@
+//! Log of the packet messages submitted by the relayer, from which the historical
+//! performance of the channels is reported with `hermes stats channel`.
+
+use core::time::Duration;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsConfig {
+    pub enabled: bool,
+    /// The file the submissions are logged to, by default `$HOME/.hermes/stats/submissions.jsonl`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// How long the submissions are kept in the log, older ones being pruned at startup
+    #[serde(with = "humantime_serde")]
+    pub retention: Duration,
+}
+
+/// Default values for the log of the submissions.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            retention: Duration::from_secs(30 * 24 * 3600),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stats() {
+        let toml_content = r#"
+            enabled = true
+            path = '/tmp/submissions.jsonl'
+            "#;
+
+        let config: StatsConfig = toml::from_str(toml_content).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.path, Some(PathBuf::from("/tmp/submissions.jsonl")));
+        assert_eq!(config.retention, Duration::from_secs(30 * 24 * 3600));
+    }
+}
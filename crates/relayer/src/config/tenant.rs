@@ -0,0 +1,89 @@
+//! Tenants of a relayer process shared by several operators, e.g. the customers of a
+//! relaying service, each of them relaying on their own chains.
+//!
+//! The chains of a tenant are the ones whose `tenant` setting is its identifier. They
+//! inherit the packet filter and fee settings of the tenant when they do not specify
+//! their own, their keys are stored apart from the ones of the other tenants, and their
+//! metrics are labelled with the identifier of the tenant.
+
+use serde::{Deserialize, Serialize};
+
+use super::filter::PacketFilter;
+use super::gas_multiplier::GasMultiplier;
+use super::ChainConfig;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tenant {
+    pub id: String,
+    /// The packet filter of the chains of the tenant which allow all the packets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packet_filter: Option<PacketFilter>,
+    /// The fee granter of the chains of the tenant which have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_granter: Option<String>,
+    /// The gas multiplier of the chains of the tenant which have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_multiplier: Option<GasMultiplier>,
+}
+
+impl Tenant {
+    /// Apply the settings of this tenant to the given chain of the tenant,
+    /// where they are not overridden by the chain itself.
+    pub fn apply_to(&self, chain: &mut ChainConfig) {
+        if let (PacketFilter::AllowAll, Some(packet_filter)) =
+            (&chain.packet_filter, &self.packet_filter)
+        {
+            chain.packet_filter = packet_filter.clone();
+        }
+
+        if chain.fee_granter.is_none() {
+            chain.fee_granter = self.fee_granter.clone();
+        }
+
+        if chain.gas_multiplier.is_none() {
+            chain.gas_multiplier = self.gas_multiplier;
+        }
+    }
+}
+
+/// The tenant with the given identifier in the given list.
+pub fn find_tenant<'a>(tenants: &'a [Tenant], id: &str) -> Option<&'a Tenant> {
+    tenants.iter().find(|tenant| tenant.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Tenants {
+        tenants: Vec<Tenant>,
+    }
+
+    #[test]
+    fn parse_tenants() {
+        let toml_content = r#"
+            [[tenants]]
+            id = 'acme'
+            fee_granter = 'cosmos1granter'
+            packet_filter = { policy = 'allow', list = [['transfer', 'channel-0']] }
+
+            [[tenants]]
+            id = 'globex'
+            gas_multiplier = 1.3
+            "#;
+
+        let Tenants { tenants } = toml::from_str(toml_content).unwrap();
+
+        let acme = find_tenant(&tenants, "acme").unwrap();
+        assert_eq!(acme.fee_granter.as_deref(), Some("cosmos1granter"));
+        assert!(matches!(acme.packet_filter, Some(PacketFilter::Allow(_))));
+
+        let globex = find_tenant(&tenants, "globex").unwrap();
+        assert!(globex.packet_filter.is_none());
+        assert!(globex.gas_multiplier.is_some());
+
+        assert!(find_tenant(&tenants, "initech").is_none());
+    }
+}
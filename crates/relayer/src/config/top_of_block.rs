@@ -0,0 +1,284 @@
+//! Submission of the transactions relaying the packets which can time out
+//! at the top of the next block, on the chains whose block builder auctions
+//! this placement, such as the top-of-block lane of the Skip block SDK.
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use ibc::core::ics04_channel::msgs::recv_packet;
+use ibc::core::ics04_channel::timeout::TimeoutHeight;
+use ibc::timestamp::{Expiry, Timestamp};
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::channel::v1::{MsgRecvPacket, Packet};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::endpoint::ChainStatus;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopOfBlock {
+    /// Whether the transactions receiving packets are bid for top-of-block placement.
+    pub enabled: bool,
+
+    /// The tip bid for each packet received by a transaction.
+    pub tip_per_packet: u64,
+
+    /// The maximum tip bid for a single transaction, whatever the number of its packets.
+    pub max_tip: u64,
+
+    /// The denomination of the tips.
+    pub denom: String,
+
+    /// Only the packets whose timeout height is at most that many blocks past
+    /// the latest height of the chain are tipped for.
+    pub timeout_window_blocks: u64,
+
+    /// Only the packets whose timeout timestamp is at most that long after
+    /// the time of the latest block of the chain are tipped for.
+    #[serde(with = "humantime_serde")]
+    pub timeout_window: Duration,
+
+    /// The total tips bid at most over each `budget_period`, whether the bids
+    /// win their auction or not. Beyond that, the transactions are sent without
+    /// a bid until the next period.
+    pub budget: u64,
+
+    /// The period over which the tips bid are limited by `budget`.
+    #[serde(with = "humantime_serde")]
+    pub budget_period: Duration,
+}
+
+/// Default values for the top-of-block placement.
+///
+/// # IMPORTANT: Remember to update the Hermes guide & the default config.toml whenever these values change.
+impl Default for TopOfBlock {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tip_per_packet: 0,
+            max_tip: 0,
+            denom: String::new(),
+            timeout_window_blocks: 100,
+            timeout_window: Duration::from_secs(10 * 60),
+            budget: 0,
+            budget_period: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl TopOfBlock {
+    /// The tip to bid for the top-of-block placement of a transaction with the given
+    /// messages, if it receives packets which are about to time out on the chain
+    /// with the given status.
+    pub fn tip_for(&self, messages: &[Any], status: &ChainStatus) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+
+        let packets = messages
+            .iter()
+            .filter(|msg| msg.type_url == recv_packet::TYPE_URL)
+            .filter_map(|msg| MsgRecvPacket::decode(msg.value.as_slice()).ok()?.packet)
+            .filter(|packet| self.times_out_soon(packet, status))
+            .count() as u64;
+
+        let tip = self
+            .tip_per_packet
+            .saturating_mul(packets)
+            .min(self.max_tip);
+
+        (tip > 0).then(|| tip)
+    }
+
+    /// Whether the given packet times out within the window of the latest block
+    /// of the chain with the given status, by height or by timestamp.
+    fn times_out_soon(&self, packet: &Packet, status: &ChainStatus) -> bool {
+        let timeout_height =
+            TimeoutHeight::try_from(packet.timeout_height.clone()).unwrap_or_default();
+        let timeout_timestamp = Timestamp::from_nanoseconds(packet.timeout_timestamp)
+            .unwrap_or_else(|_| Timestamp::none());
+
+        let window_height = status.height.add(self.timeout_window_blocks);
+        let height_soon = timeout_height.has_expired(window_height);
+
+        let timestamp_soon = timeout_timestamp != Timestamp::none()
+            && (status.timestamp + self.timeout_window).map_or(true, |window_timestamp| {
+                window_timestamp.check_expiry(&timeout_timestamp) == Expiry::Expired
+            });
+
+        height_soon || timestamp_soon
+    }
+}
+
+/// The tips bid by the transactions of a chain over the current budget period,
+/// shared by the clones of the budget.
+#[derive(Clone, Debug)]
+pub struct TipBudget(Arc<Mutex<TipBudgetState>>);
+
+#[derive(Debug)]
+struct TipBudgetState {
+    period_start: Instant,
+    spent: u64,
+}
+
+impl Default for TipBudget {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(TipBudgetState {
+            period_start: Instant::now(),
+            spent: 0,
+        })))
+    }
+}
+
+impl TipBudget {
+    /// Spend the given tip out of the budget of the current period at the given time,
+    /// starting a new period if the current one is over. Returns the tip to bid,
+    /// which is reduced to what is left of the budget, if anything.
+    pub fn spend(&self, config: &TopOfBlock, tip: u64, now: Instant) -> Option<u64> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        if now.saturating_duration_since(state.period_start) >= config.budget_period {
+            state.period_start = now;
+            state.spent = 0;
+        }
+
+        let tip = tip.min(config.budget.saturating_sub(state.spent));
+
+        if tip == 0 {
+            return None;
+        }
+
+        state.spent += tip;
+
+        Some(tip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics02_client::height::Height;
+    use ibc_proto::ibc::core::client::v1::Height as RawHeight;
+
+    fn msg(type_url: &str) -> Any {
+        Any {
+            type_url: type_url.to_string(),
+            value: vec![],
+        }
+    }
+
+    /// A message receiving a packet timing out at the given height and timestamp
+    fn recv(timeout_height: u64, timeout_timestamp: Timestamp) -> Any {
+        let msg = MsgRecvPacket {
+            packet: Some(Packet {
+                timeout_height: Some(RawHeight {
+                    revision_number: 0,
+                    revision_height: timeout_height,
+                }),
+                timeout_timestamp: timeout_timestamp.nanoseconds(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        Any {
+            type_url: recv_packet::TYPE_URL.to_string(),
+            value: msg.encode_to_vec(),
+        }
+    }
+
+    fn status(height: u64, timestamp: Timestamp) -> ChainStatus {
+        ChainStatus {
+            height: Height::new(0, height).unwrap(),
+            timestamp,
+        }
+    }
+
+    fn top_of_block() -> TopOfBlock {
+        toml::from_str(
+            r#"
+            enabled = true
+            tip_per_packet = 100
+            max_tip = 250
+            denom = 'uatom'
+            timeout_window_blocks = 10
+            timeout_window = '1m'
+            budget = 1000
+            budget_period = '1h'
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn tip_for_packets() {
+        let top_of_block = top_of_block();
+        let now = Timestamp::now();
+        let status = status(100, now);
+
+        let recv = recv(105, Timestamp::none());
+        let update = msg("/ibc.core.client.v1.MsgUpdateClient");
+
+        assert_eq!(top_of_block.tip_for(&[update.clone()], &status), None);
+        assert_eq!(
+            top_of_block.tip_for(&[update.clone(), recv.clone()], &status),
+            Some(100)
+        );
+        assert_eq!(
+            top_of_block.tip_for(&[recv.clone(), recv.clone(), recv.clone()], &status),
+            Some(250)
+        );
+
+        let disabled = TopOfBlock {
+            enabled: false,
+            ..top_of_block
+        };
+        assert_eq!(disabled.tip_for(&[update, recv], &status), None);
+    }
+
+    #[test]
+    fn tip_for_packets_timing_out_soon() {
+        let top_of_block = top_of_block();
+        let now = Timestamp::now();
+        let status = status(100, now);
+
+        let later = |secs| (now + Duration::from_secs(secs)).unwrap();
+
+        // Timing out within the window, by height or by timestamp
+        let by_height = recv(110, Timestamp::none());
+        let by_timestamp = recv(1000, later(30));
+
+        // Timing out after the window, or never
+        let late_height = recv(111, Timestamp::none());
+        let late_timestamp = recv(0, later(120));
+        let never = recv(0, Timestamp::none());
+
+        assert_eq!(top_of_block.tip_for(&[by_height], &status), Some(100));
+        assert_eq!(top_of_block.tip_for(&[by_timestamp], &status), Some(100));
+        assert_eq!(
+            top_of_block.tip_for(&[late_height, late_timestamp, never], &status),
+            None
+        );
+    }
+
+    #[test]
+    fn tip_budget_per_period() {
+        let top_of_block = top_of_block();
+        let budget = TipBudget::default();
+        let start = Instant::now();
+
+        assert_eq!(budget.spend(&top_of_block, 250, start), Some(250));
+        assert_eq!(budget.spend(&top_of_block, 250, start), Some(250));
+        assert_eq!(budget.spend(&top_of_block, 250, start), Some(250));
+
+        // Only what is left of the budget is bid, then nothing until the next period
+        assert_eq!(budget.spend(&top_of_block, 400, start), Some(250));
+        assert_eq!(budget.spend(&top_of_block, 100, start), None);
+
+        let next_period = start + Duration::from_secs(60 * 60);
+        assert_eq!(budget.spend(&top_of_block, 100, next_period), Some(100));
+    }
+}
@@ -25,6 +25,7 @@ use crate::chain::requests::{
     IncludeProof, PageRequest, QueryConnectionRequest, QueryConnectionsRequest, QueryHeight,
 };
 use crate::chain::tracking::TrackedMsgs;
+use crate::client_state::AnyClientState;
 use crate::foreign_client::{ForeignClient, HasExpiredOrFrozenError};
 use crate::object::Connection as WorkerConnectionObject;
 use crate::util::pretty::{PrettyDuration, PrettyOption};
@@ -866,6 +867,32 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         })
     }
 
+    /// The client state of the client on the source chain, wrapped as it is stored on the
+    /// source chain if it only accepts wrapped clients, so that it matches the bytes covered
+    /// by the proof of the client state.
+    fn wrap_src_client_state(
+        &self,
+        client_state: Option<AnyClientState>,
+    ) -> Result<Option<Any>, ConnectionError> {
+        let client_state = match client_state {
+            Some(client_state) => client_state,
+            None => return Ok(None),
+        };
+
+        let wrapper = self
+            .src_chain()
+            .config()
+            .map_err(|e| ConnectionError::chain_query(self.src_chain().id(), e))?
+            .client_wrapper;
+
+        let height = client_state.latest_height();
+
+        Ok(Some(match wrapper {
+            Some(wrapper) => wrapper.wrap_client_state(client_state.into(), height),
+            None => client_state.into(),
+        }))
+    }
+
     pub fn build_conn_init(&self) -> Result<Vec<Any>, ConnectionError> {
         // Get signer
         let signer = self
@@ -1053,7 +1080,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
 
         let new_msg = MsgConnectionOpenTry {
             client_id: self.dst_client_id().clone(),
-            client_state: client_state.map(Into::into),
+            client_state: self.wrap_src_client_state(client_state)?,
             previous_connection_id,
             counterparty,
             counterparty_versions,
@@ -1171,7 +1198,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         let new_msg = MsgConnectionOpenAck {
             connection_id: dst_connection_id.clone(),
             counterparty_connection_id: src_connection_id.clone(),
-            client_state: client_state.map(Into::into),
+            client_state: self.wrap_src_client_state(client_state)?,
             proofs,
             version: src_connection.versions()[0].clone(),
             signer,
@@ -1,6 +1,7 @@
 use ibc::clients::ics07_tendermint::consensus_state::{
     ConsensusState as TmConsensusState, TENDERMINT_CONSENSUS_STATE_TYPE_URL,
 };
+use ibc::clients::ics08_wasm::{self, WASM_CONSENSUS_STATE_TYPE_URL};
 use ibc::core::ics02_client::client_type::ClientType;
 use ibc::core::ics02_client::consensus_state::{downcast_consensus_state, ConsensusState};
 use ibc::core::ics02_client::error::Error;
@@ -68,6 +69,10 @@ impl TryFrom<Any> for AnyConsensusState {
                     .map_err(Error::decode_raw_client_state)?,
             )),
 
+            WASM_CONSENSUS_STATE_TYPE_URL => AnyConsensusState::try_from(
+                ics08_wasm::unwrap(value).map_err(Error::decode_wasm_state)?,
+            ),
+
             _ => Err(Error::unknown_consensus_state_type(value.type_url)),
         }
     }
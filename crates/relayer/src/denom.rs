@@ -2,8 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod cache;
+
 /// The denom trace
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DenomTrace {
     /// The chain of port/channel identifiers used for tracing the source of the coin.
     pub path: String,
@@ -0,0 +1,198 @@
+//! On-disk cache of the denomination traces resolved by the relayer.
+//!
+//! The trace behind the hash of an IBC denomination never changes once the
+//! denomination exists on a chain, so the traces resolved over gRPC are saved in
+//! a file per chain and reused by later runs, instead of being queried again.
+//!
+//! The cache is best effort: a cache file which cannot be read or written only
+//! means that the traces are queried from the chain, as if it were not there.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use flex_error::{define_error, TraceError};
+use tracing::{debug, warn};
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use super::DenomTrace;
+use crate::util::state_file;
+
+/// Location of the cache files, relative to the home directory.
+pub const DEFAULT_CACHE_DIR: &str = ".hermes/cache/denom_traces";
+
+/// Prefix of the denominations of tokens received over IBC.
+const IBC_DENOM_PREFIX: &str = "ibc/";
+
+define_error! {
+    Error {
+        Io
+            { path: PathBuf }
+            [ TraceError<std::io::Error> ]
+            |e| { format!("I/O error on denom trace cache file {}", e.path.display()) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode the denom trace cache" },
+
+        Decode
+            { path: PathBuf }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode the denom trace cache file {}", e.path.display()) },
+
+        Corrupted
+            { path: PathBuf, reason: String }
+            |e| { format!("denom trace cache file {} is corrupted: {}", e.path.display(), e.reason) },
+    }
+}
+
+/// The denomination traces resolved on a chain, keyed by their hash.
+#[derive(Debug)]
+pub struct DenomTraceCache {
+    /// The file the cache is saved to, if the home directory is known
+    path: Option<PathBuf>,
+    traces: Mutex<HashMap<String, DenomTrace>>,
+}
+
+impl DenomTraceCache {
+    /// The cache of the given chain, in [`DEFAULT_CACHE_DIR`].
+    pub fn for_chain(chain_id: &ChainId) -> Self {
+        match dirs_next::home_dir() {
+            Some(home) => Self::open(
+                home.join(DEFAULT_CACHE_DIR)
+                    .join(format!("{}.json", chain_id)),
+            ),
+            None => {
+                warn!(
+                    "home location is unavailable, denom traces of chain {} are not cached on disk",
+                    chain_id
+                );
+
+                Self {
+                    path: None,
+                    traces: Mutex::new(HashMap::new()),
+                }
+            }
+        }
+    }
+
+    /// The cache saved in the given file, empty if the file does not exist or cannot be loaded.
+    pub fn open(path: PathBuf) -> Self {
+        let traces = match load(&path) {
+            Ok(traces) => traces,
+            Err(e) => {
+                warn!("ignoring the denom trace cache: {}", e);
+
+                if let ErrorDetail::Corrupted(_) | ErrorDetail::Decode(_) = e.detail() {
+                    let _ = state_file::move_aside(&path);
+                }
+
+                HashMap::new()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            traces: Mutex::new(traces),
+        }
+    }
+
+    /// The cached trace of the given hash, with or without the `ibc/` prefix.
+    pub fn get(&self, hash: &str) -> Option<DenomTrace> {
+        let traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        traces.get(&cache_key(hash)).cloned()
+    }
+
+    /// Cache the trace of the given hash, and save the cache to its file.
+    pub fn insert(&self, hash: &str, trace: DenomTrace) {
+        let mut traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        traces.insert(cache_key(hash), trace);
+
+        if let Some(path) = &self.path {
+            // Merge in the traces saved meanwhile by other instances of the relayer
+            if let Ok(saved) = load(path) {
+                for (hash, trace) in saved {
+                    traces.entry(hash).or_insert(trace);
+                }
+            }
+
+            match save(path, &traces) {
+                Ok(()) => debug!("saved {} denom traces to {}", traces.len(), path.display()),
+                Err(e) => warn!("failed to save the denom trace cache: {}", e),
+            }
+        }
+    }
+}
+
+/// Hashes are hex encoded, and looked up regardless of their case.
+fn cache_key(hash: &str) -> String {
+    hash.strip_prefix(IBC_DENOM_PREFIX)
+        .unwrap_or(hash)
+        .to_uppercase()
+}
+
+fn load(path: &Path) -> Result<HashMap<String, DenomTrace>, Error> {
+    let file = match fs::read(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(Error::io(path.to_path_buf(), e)),
+    };
+
+    let json =
+        state_file::unseal(&file).map_err(|reason| Error::corrupted(path.to_path_buf(), reason))?;
+
+    serde_json::from_slice(json).map_err(|e| Error::decode(path.to_path_buf(), e))
+}
+
+fn save(path: &Path, traces: &HashMap<String, DenomTrace>) -> Result<(), Error> {
+    let json = serde_json::to_vec(traces).map_err(Error::encode)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io(path.to_path_buf(), e))?;
+    }
+
+    // Write to a temporary file first, so that a concurrent
+    // reader never sees a partially written cache file.
+    let tmp_path = path.with_extension(format!("tmp-{}", std::process::id()));
+    fs::write(&tmp_path, state_file::seal(&json)).map_err(|e| Error::io(tmp_path.clone(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traces_are_reused_across_runs() {
+        let path = std::env::temp_dir()
+            .join(format!("hermes-denom-traces-{}", std::process::id()))
+            .join("ibc-0.json");
+
+        let _ = fs::remove_file(&path);
+
+        let trace = DenomTrace {
+            path: "transfer/channel-0".to_string(),
+            base_denom: "uatom".to_string(),
+        };
+
+        let hash = "27394fb092d2eccd56123c74f36e4c1f926001ceada9ca97ea622b25f41e5eb2";
+
+        let cache = DenomTraceCache::open(path.clone());
+        assert_eq!(cache.get(hash), None);
+
+        cache.insert(hash, trace.clone());
+
+        let reopened = DenomTraceCache::open(path.clone());
+        assert_eq!(reopened.get(hash), Some(trace.clone()));
+        assert_eq!(
+            reopened.get(&format!("ibc/{}", hash.to_uppercase())),
+            Some(trace)
+        );
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -6,6 +6,7 @@ use flex_error::{define_error, DisplayOnly, TraceClone, TraceError};
 use http::uri::InvalidUri;
 use humantime::format_duration;
 use ibc_proto::protobuf::Error as TendermintProtoError;
+use once_cell::sync::Lazy;
 use prost::{DecodeError, EncodeError};
 use regex::Regex;
 use tendermint::Error as TendermintError;
@@ -22,6 +23,7 @@ use tonic::{
 };
 
 use ibc::{
+    applications::ics29_fee::error as fee_error,
     clients::ics07_tendermint::error as tendermint_error,
     core::{
         ics02_client::{client_type::ClientType, error as client_error},
@@ -239,6 +241,10 @@ define_error! {
             { reason: String }
             |e| { format!("message transaction failure: {0}", e.reason) },
 
+        InvalidCrossChainQuery
+            { id: String, reason: String }
+            |e| { format!("invalid cross-chain query '{}': {}", e.id, e.reason) },
+
         Query
             { query: String }
             |e| { format!("query error occurred (failed to query for {0})", e.query) },
@@ -272,6 +278,10 @@ define_error! {
             [ commitment_error::Error ]
             |_| { "ICS 23 error" },
 
+        Ics29
+            [ fee_error::Error ]
+            |_| { "ICS 29 error" },
+
         InvalidUri
             { uri: String }
             [ TraceError<InvalidUri> ]
@@ -297,6 +307,13 @@ define_error! {
         TxNoConfirmation
             |_| { "failed tx: no confirmation" },
 
+        CallTimeout
+            { chain_id: ChainId, call: String, timeout: Duration }
+            |e| {
+                format!("{} call to chain {} did not complete within {:?}",
+                    e.call, e.chain_id, e.timeout)
+            },
+
         Misbehaviour
             { reason: String }
             |e| { format!("error raised while submitting the misbehaviour evidence: {0}", e.reason) },
@@ -325,6 +342,21 @@ define_error! {
             [ TraceError<DecodeError> ]
             |e| { format!("error decoding protocol buffer for {}", e.payload_type) },
 
+        InvalidGasPrice
+            { price: String }
+            |e| { format!("invalid gas price '{}' returned by the fee market", e.price) },
+
+        InvalidFeeGrant
+            { granter: String, grantee: String, reason: String }
+            |e| {
+                format!("the fees of {} are not paid by the fee granter {}: {}",
+                    e.grantee, e.granter, e.reason)
+            },
+
+        InvalidFeeGrantType
+            { type_url: String }
+            |e| { format!("unknown type of fee allowance '{}'", e.type_url) },
+
         ProtobufEncode
             { payload_type: String }
             [ TraceError<EncodeError> ]
@@ -500,6 +532,31 @@ define_error! {
             { address: String }
             |e| { format!("Query/Account RPC returned an empty account for address: {}", e.address) },
 
+        AddressPrefixMismatch
+            {
+                chain_id: ChainId,
+                key_name: String,
+                address: String,
+                prefix: String,
+                account_prefix: String,
+            }
+            |e| {
+                format!("the address {} of key '{}' has the prefix '{}' but the `account_prefix` of chain {} is '{}': \
+                    check the `account_prefix` configured for the chain, or add the key again with `hermes keys add` \
+                    if it was added while the `account_prefix` was wrong",
+                    e.address, e.key_name, e.prefix, e.chain_id, e.account_prefix)
+            },
+
+        AccountNotFound
+            { chain_id: ChainId, key_name: String, address: String }
+            |e| {
+                format!("there is no account on chain {} for the address {} of key '{}': fund the address if it is new, \
+                    otherwise the key was likely derived for another chain, check that it was added with the coin type \
+                    of the chain (e.g. `--hd-path \"m/44'/60'/0'/0/0\"` for Ethermint-based chains), and that the \
+                    `address_type` configured for the chain is right",
+                    e.chain_id, e.address, e.key_name)
+            },
+
         NoHistoricalEntries
             { chain_id: ChainId }
             |e| {
@@ -531,6 +588,82 @@ define_error! {
             { len: usize }
             |e| {
                 format_args!("message with length {} is too large for a transaction", e.len)
+            },
+
+        MaxSubscriptions
+            { chain_id: ChainId, max: usize }
+            |e| {
+                format_args!(
+                    "chain '{}' reached its maximum number of subscriptions ({}), \
+                    see `max_subscriptions` in its `quotas` configuration",
+                    e.chain_id, e.max
+                )
+            },
+
+        MissingFixture
+            { chain_id: ChainId, description: String }
+            |e| {
+                format_args!("no fixture to replay {} on chain '{}'", e.description, e.chain_id)
+            },
+
+        AccountSequenceConflict
+            { chain_id: ChainId, address: String }
+            |e| {
+                format_args!(
+                    "the sequence number of account {} on chain '{}' keeps being advanced \
+                    by another process: make sure that its key is not used by another relayer \
+                    or wallet at the same time",
+                    e.address, e.chain_id
+                )
+            },
+
+        InconsistentQueryProof
+            { chain_id: ChainId, path: String, height: u64 }
+            [ commitment_error::Error ]
+            |e| {
+                format!(
+                    "the proof returned by the full node of chain '{}' for '{}' at height {} \
+                    does not match the app hash reported by that node",
+                    e.chain_id, e.path, e.height
+                )
+            },
+
+        HeaderMismatch
+            {
+                chain_id: ChainId,
+                height: u64,
+                primary_hash: String,
+                secondary_address: String,
+                secondary_hash: String,
+            }
+            |e| {
+                format_args!(
+                    "the header at height {} of chain '{}' has hash {} on the primary RPC endpoint \
+                    but {} on the secondary one at {}: the primary node may be on a fork or \
+                    compromised, refusing to build a client update with it",
+                    e.height, e.chain_id, e.primary_hash, e.secondary_hash, e.secondary_address
+                )
+            },
+
+        ClientTls
+            {
+                chain_id: ChainId,
+                reason: String,
+            }
+            |e| {
+                format!("invalid client TLS configuration for chain '{}': {}",
+                    e.chain_id, e.reason)
+            },
+
+        UnsupportedProxy
+            {
+                proxy: String,
+                endpoint: String,
+            }
+            |e| {
+                format!("the proxy {} cannot be used to reach the endpoint {}: \
+                    the WebSocket and gRPC-web endpoints cannot be reached through a proxy",
+                    e.proxy, e.endpoint)
             }
     }
 }
@@ -614,11 +747,13 @@ impl GrpcStatusSubdetail {
 /// during simulating or broadcasting a transaction, includes the following pattern:
 /// "account sequence mismatch, expected E, got G".
 /// If a match is found it extracts and returns (E, G).
-fn parse_sequences_in_mismatch_error_message(message: &str) -> Option<(u64, u64)> {
-    let re =
+pub fn parse_sequences_in_mismatch_error_message(message: &str) -> Option<(u64, u64)> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r#"account sequence mismatch, expected (?P<expected>\d+), got (?P<got>\d+)"#)
-            .unwrap();
-    match re.captures(message) {
+            .unwrap()
+    });
+
+    match RE.captures(message) {
         None => None,
         Some(captures) => match (captures["expected"].parse(), captures["got"].parse()) {
             (Ok(e), Ok(g)) => Some((e, g)),
@@ -627,9 +762,6 @@ fn parse_sequences_in_mismatch_error_message(message: &str) -> Option<(u64, u64)
     }
 }
 
-pub const QUERY_PROOF_EXPECT_MSG: &str =
-    "Internal error. Requested proof with query but no proof was returned.";
-
 #[cfg(test)]
 mod tests {
     use super::*;
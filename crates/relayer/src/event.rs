@@ -18,7 +18,7 @@ use ibc::{
     core::ics04_channel::{
         error::Error as ChannelError,
         events::{self as channel_events, Attributes as ChannelAttributes},
-        packet::Packet,
+        packet::{Packet, Sequence},
         timeout::TimeoutHeight,
     },
     events::{Error as IbcEventError, IbcEvent, IbcEventType},
@@ -31,6 +31,7 @@ use crate::light_client::AnyHeader;
 
 pub mod bus;
 pub mod monitor;
+pub mod pull;
 pub mod rpc;
 
 #[derive(Clone, Debug, Serialize)]
@@ -123,6 +124,16 @@ pub fn ibc_event_try_from_abci_event(abci_event: &AbciEvent) -> Result<IbcEvent,
                 .map_err(IbcEventError::cross_chain_query)?,
         )),
 
+        Ok(event_type) => match channel_events::UpgradeStep::from_event_type(&event_type) {
+            Some(step) => Ok(IbcEvent::UpgradeChannel(
+                channel_upgrade_try_from_abci_event(abci_event, step)
+                    .map_err(IbcEventError::channel)?,
+            )),
+            None => Err(IbcEventError::unsupported_abci_event(
+                abci_event.type_str.to_owned(),
+            )),
+        },
+
         _ => Err(IbcEventError::unsupported_abci_event(
             abci_event.type_str.to_owned(),
         )),
@@ -240,6 +251,31 @@ pub fn channel_close_confirm_try_from_abci_event(
     }
 }
 
+pub fn channel_upgrade_try_from_abci_event(
+    abci_event: &AbciEvent,
+    step: channel_events::UpgradeStep,
+) -> Result<channel_events::UpgradeChannel, ChannelError> {
+    let attrs = channel_extract_attributes_from_tx(abci_event)?;
+
+    let mut upgrade_sequence = Sequence::default();
+    for tag in &abci_event.attributes {
+        if tag.key.as_ref() == channel_events::UPGRADE_SEQUENCE_ATTRIBUTE_KEY {
+            upgrade_sequence = tag.value.as_ref().parse()?;
+        }
+    }
+
+    Ok(channel_events::UpgradeChannel {
+        step,
+        port_id: attrs.port_id,
+        channel_id: attrs
+            .channel_id
+            .ok_or_else(ChannelError::missing_channel_id)?,
+        counterparty_port_id: attrs.counterparty_port_id,
+        counterparty_channel_id: attrs.counterparty_channel_id,
+        upgrade_sequence,
+    })
+}
+
 pub fn send_packet_try_from_abci_event(
     abci_event: &AbciEvent,
 ) -> Result<channel_events::SendPacket, ChannelError> {
@@ -272,6 +308,18 @@ pub fn write_acknowledgement_try_from_abci_event(
         .map_err(|_| ChannelError::abci_conversion_failed(abci_event.type_str.to_owned()))
 }
 
+pub fn receive_packet_try_from_abci_event(
+    abci_event: &AbciEvent,
+) -> Result<channel_events::ReceivePacket, ChannelError> {
+    extract_packet_and_write_ack_from_tx(abci_event)
+        .map(|(packet, write_ack)| {
+            // This event should not have a write ack.
+            debug_assert_eq!(write_ack.len(), 0);
+            channel_events::ReceivePacket { packet }
+        })
+        .map_err(|_| ChannelError::abci_conversion_failed(abci_event.type_str.to_owned()))
+}
+
 pub fn acknowledge_packet_try_from_abci_event(
     abci_event: &AbciEvent,
 ) -> Result<channel_events::AcknowledgePacket, ChannelError> {
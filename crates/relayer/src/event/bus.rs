@@ -4,6 +4,9 @@ use crossbeam_channel as channel;
 
 pub struct EventBus<T> {
     txs: VecDeque<channel::Sender<T>>,
+    /// How many values (e.g. event batches, whatever their size) at most
+    /// are queued for each subscriber, if bounded
+    capacity: Option<usize>,
 }
 
 impl<T> Default for EventBus<T> {
@@ -14,33 +17,57 @@ impl<T> Default for EventBus<T> {
 
 impl<T> EventBus<T> {
     pub fn new() -> Self {
+        Self::with_capacity(None)
+    }
+
+    /// Create a bus queueing at most `capacity` values for each subscriber, if set.
+    /// The capacity is a number of values, not of bytes.
+    pub fn with_capacity(capacity: Option<usize>) -> Self {
         Self {
             txs: VecDeque::new(),
+            capacity,
         }
     }
 
+    /// The number of subscribers to the bus.
+    pub fn subscribers(&self) -> usize {
+        self.txs.len()
+    }
+
     pub fn subscribe(&mut self) -> channel::Receiver<T> {
-        let (tx, rx) = channel::unbounded();
+        let (tx, rx) = match self.capacity {
+            Some(capacity) => channel::bounded(capacity),
+            None => channel::unbounded(),
+        };
+
         self.txs.push_back(tx);
         rx
     }
 
-    pub fn broadcast(&mut self, value: T)
+    /// Send the value to all the subscribers, returning the number of
+    /// subscribers whose queue was full, and which did not get it.
+    pub fn broadcast(&mut self, value: T) -> usize
     where
         T: Clone,
     {
         let mut disconnected = Vec::new();
+        let mut dropped = 0;
+
         for (idx, tx) in self.txs.iter().enumerate() {
             // TODO: Avoid cloning when sending to last subscriber
-            if let Err(channel::SendError(_)) = tx.send(value.clone()) {
-                disconnected.push(idx);
+            match tx.try_send(value.clone()) {
+                Ok(()) => {}
+                Err(channel::TrySendError::Full(_)) => dropped += 1,
+                Err(channel::TrySendError::Disconnected(_)) => disconnected.push(idx),
             }
         }
 
         // Remove all disconnected subscribers
-        for idx in disconnected {
+        for idx in disconnected.into_iter().rev() {
             self.txs.remove(idx);
         }
+
+        dropped
     }
 }
 
@@ -116,4 +143,27 @@ mod tests {
 
         assert_eq!(counter(), 20);
     }
+
+    #[test]
+    #[serial]
+    fn bounded_subscribers() {
+        reset_counter();
+
+        let mut bus = EventBus::with_capacity(Some(1));
+        let rx = bus.subscribe();
+        let lagging = bus.subscribe();
+
+        assert_eq!(bus.broadcast(Value(42)), 0);
+        assert_eq!(rx.recv(), Ok(Value(42)));
+
+        // The queue of the lagging subscriber is full
+        assert_eq!(bus.broadcast(Value(113)), 1);
+        assert_eq!(rx.recv(), Ok(Value(113)));
+        assert_eq!(lagging.recv(), Ok(Value(42)));
+        assert!(lagging.try_recv().is_err());
+
+        drop(lagging);
+        assert_eq!(bus.broadcast(Value(7)), 0);
+        assert_eq!(bus.subscribers(), 1);
+    }
 }
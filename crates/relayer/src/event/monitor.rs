@@ -22,6 +22,7 @@ use ibc::{
 
 use crate::{
     chain::tracking::TrackingId,
+    replay::journal,
     telemetry,
     util::{
         retry::{retry_count, retry_with_index, RetryResult},
@@ -460,7 +461,10 @@ fn stream_batches(
 
     // Collect IBC events from each RPC event
     let events = subscriptions
-        .map_ok(move |rpc_event| collect_events(&id, rpc_event))
+        .map_ok(move |rpc_event| {
+            journal::record(&id, &rpc_event);
+            collect_events(&id, rpc_event)
+        })
         .map_err(Error::canceled_or_generic)
         .try_flatten();
 
@@ -487,7 +491,7 @@ fn stream_batches(
 
 /// Sort the given events by putting the NewBlock event first,
 /// and leaving the other events as is.
-fn sort_events(events: &mut [IbcEventWithHeight]) {
+pub(crate) fn sort_events(events: &mut [IbcEventWithHeight]) {
     events.sort_by(|a, b| match (&a.event, &b.event) {
         (IbcEvent::NewBlock(_), _) => Ordering::Less,
         _ => Ordering::Equal,
@@ -0,0 +1,183 @@
+//! Pulling the events of a chain from the results of its blocks, queried over RPC as
+//! they are committed, instead of having them pushed over a WebSocket subscription.
+//!
+//! The [`EventPuller`] keeps a cursor on the next block whose events it pulls, so that
+//! a query which fails, e.g. because the RPC provider throttled it, is retried at the
+//! next poll and no block is skipped. It produces the same event batches as the
+//! [`EventMonitor`](super::monitor::EventMonitor), one per block.
+
+use alloc::sync::Arc;
+use core::time::Duration;
+
+use crossbeam_channel as channel;
+use tendermint::block::Height as TmHeight;
+use tendermint_rpc::{Client, HttpClient, Url};
+use tokio::runtime::Runtime as TokioRuntime;
+use tracing::{debug, instrument, trace, warn};
+
+use ibc::core::ics02_client::{events as ClientEvents, height::Height};
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::chain::cosmos::query::block_results::{
+    query_block_results, BlockResults, BlockResultsLayout,
+};
+use crate::chain::tracking::TrackingId;
+use crate::error::Error;
+
+use super::monitor::{sort_events, EventBatch, EventReceiver, MonitorCmd, TxMonitorCmd};
+use super::{ibc_event_try_from_abci_event, IbcEventWithHeight};
+
+/// The maximum number of blocks pulled at each poll, when catching up with the chain.
+const MAX_BLOCKS_PER_POLL: u64 = 100;
+
+/// Poll a Tendermint node for its new blocks, and send the IBC events of
+/// their results to the runtime of the chain.
+pub struct EventPuller {
+    chain_id: ChainId,
+    rpc_client: HttpClient,
+    rpc_addr: Url,
+    layout: BlockResultsLayout,
+    /// How often the node is polled for new blocks
+    interval: Duration,
+    /// Channel to handler where the puller for this chain sends the events
+    tx_batch: channel::Sender<super::monitor::Result<EventBatch>>,
+    /// Channel where to receive commands
+    rx_cmd: channel::Receiver<MonitorCmd>,
+    /// The height of the next block whose events are pulled, once the first poll is made
+    next_height: Option<u64>,
+    rt: Arc<TokioRuntime>,
+}
+
+impl EventPuller {
+    pub fn new(
+        chain_id: ChainId,
+        rpc_client: HttpClient,
+        rpc_addr: Url,
+        layout: BlockResultsLayout,
+        interval: Duration,
+        rt: Arc<TokioRuntime>,
+    ) -> (Self, EventReceiver, TxMonitorCmd) {
+        let (tx_batch, rx_batch) = channel::unbounded();
+        let (tx_cmd, rx_cmd) = channel::unbounded();
+
+        let puller = Self {
+            chain_id,
+            rpc_client,
+            rpc_addr,
+            layout,
+            interval,
+            tx_batch,
+            rx_cmd,
+            next_height: None,
+            rt,
+        };
+
+        (puller, rx_batch, tx_cmd)
+    }
+
+    /// Event puller loop
+    #[instrument(
+        name = "event_puller",
+        level = "error",
+        skip_all,
+        fields(chain = %self.chain_id)
+    )]
+    pub fn run(mut self) {
+        debug!(
+            "starting event puller, polling {} every {:?}",
+            self.rpc_addr, self.interval
+        );
+
+        loop {
+            if let Err(e) = self.pull_new_blocks() {
+                warn!(
+                    "failed to pull the events of the new blocks, retrying: {}",
+                    e
+                );
+            }
+
+            match self.rx_cmd.recv_timeout(self.interval) {
+                Err(channel::RecvTimeoutError::Timeout) => continue,
+                Ok(MonitorCmd::Shutdown) | Err(channel::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        debug!("event puller is shutting down");
+    }
+
+    /// Pull the events of the blocks committed since the last poll, up to `MAX_BLOCKS_PER_POLL`.
+    fn pull_new_blocks(&mut self) -> Result<(), Error> {
+        let status = self
+            .rt
+            .block_on(self.rpc_client.status())
+            .map_err(|e| Error::rpc(self.rpc_addr.clone(), e))?;
+
+        let latest_height = u64::from(status.sync_info.latest_block_height);
+
+        // The events of the blocks before the first poll are recovered by
+        // the clearing of the packets when the workers start
+        let from = *self.next_height.get_or_insert(latest_height);
+        let to = latest_height.min(from.saturating_add(MAX_BLOCKS_PER_POLL - 1));
+
+        for height in from..=to {
+            let tm_height = TmHeight::try_from(height).map_err(Error::invalid_height)?;
+
+            let results = self.rt.block_on(query_block_results(
+                self.layout,
+                &self.rpc_client,
+                &self.rpc_addr,
+                tm_height,
+            ))?;
+
+            let height = Height::new(self.chain_id.version(), height)
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            let batch = event_batch(&self.chain_id, height, results);
+
+            trace!(
+                height = %batch.height,
+                "pulled {} events",
+                batch.events.len()
+            );
+
+            if self.tx_batch.send(Ok(batch)).is_err() {
+                // The runtime of the chain is shutting down
+                return Ok(());
+            }
+
+            self.next_height = Some(height.revision_height() + 1);
+        }
+
+        Ok(())
+    }
+}
+
+/// The batch of the IBC events of the block at the given height, in the order they were
+/// emitted: by BeginBlock, then by the transactions, then by EndBlock, after its `NewBlock` event.
+fn event_batch(chain_id: &ChainId, height: Height, results: BlockResults) -> EventBatch {
+    let abci_events = results
+        .begin_block_events
+        .iter()
+        .chain(results.txs_events.iter().flatten())
+        .chain(results.end_block_events.iter());
+
+    let mut events = vec![IbcEventWithHeight::new(
+        ClientEvents::NewBlock::new(height).into(),
+        height,
+    )];
+
+    events.extend(
+        abci_events
+            .filter_map(|abci_event| ibc_event_try_from_abci_event(abci_event).ok())
+            .map(|event| IbcEventWithHeight::new(event, height)),
+    );
+
+    sort_events(&mut events);
+
+    EventBatch {
+        chain_id: chain_id.clone(),
+        tracking_id: TrackingId::new_uuid(),
+        height,
+        events,
+    }
+}
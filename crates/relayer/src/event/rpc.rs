@@ -112,7 +112,14 @@ use super::{ibc_event_try_from_abci_event, IbcEventWithHeight};
 /// ```
 /// {Begin,End}Block events however do not have any such `message.action` associated with them, so
 /// this doesn't work. For this reason, we extract block events in the following order ->
-/// OpenInit -> OpenTry -> OpenAck -> OpenConfirm -> SendPacket -> CloseInit -> CloseConfirm.
+/// OpenInit -> OpenTry -> OpenAck -> OpenConfirm -> SendPacket -> WriteAcknowledgement ->
+/// CloseInit -> CloseConfirm.
+///
+/// The block events are read from the `RpcEvent::events` field rather than from the results
+/// of the block carried by the `NewBlock` event, so that the events of the nodes on
+/// CometBFT 0.38 and above are extracted as well: there, the BeginBlock and EndBlock events
+/// are emitted together by FinalizeBlock, which the `NewBlock` event data has no field for,
+/// but they are still flattened into `RpcEvent::events` like the ones of the older nodes.
 pub fn get_all_events(
     chain_id: &ChainId,
     result: RpcEvent,
@@ -217,6 +224,7 @@ fn event_is_type_channel(ev: &IbcEvent) -> bool {
             | IbcEvent::OpenConfirmChannel(_)
             | IbcEvent::CloseInitChannel(_)
             | IbcEvent::CloseConfirmChannel(_)
+            | IbcEvent::UpgradeChannel(_)
             | IbcEvent::SendPacket(_)
             | IbcEvent::ReceivePacket(_)
             | IbcEvent::WriteAcknowledgement(_)
@@ -293,6 +301,11 @@ fn extract_block_events(
         extract_events(height, block_events, "send_packet", "packet_data"),
         height,
     );
+    append_events::<ChannelEvents::WriteAcknowledgement>(
+        &mut events,
+        extract_events(height, block_events, "write_acknowledgement", "packet_data"),
+        height,
+    );
     append_events::<ChannelEvents::CloseInit>(
         &mut events,
         extract_events(height, block_events, "channel_close_init", "channel_id"),
@@ -10,9 +10,12 @@ use std::time::Instant;
 
 use ibc_proto::google::protobuf::Any;
 use itertools::Itertools;
+use tendermint::abci::transaction::Hash as TxHash;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use flex_error::define_error;
+use ibc::clients::ics07_tendermint::header::Header as TmHeader;
+use ibc::clients::ics07_tendermint::misbehaviour::Misbehaviour as TmMisbehaviour;
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics02_client::error::Error as ClientError;
 use ibc::core::ics02_client::events::UpdateClient;
@@ -34,18 +37,28 @@ use crate::chain::handle::ChainHandle;
 use crate::chain::requests::{
     IncludeProof, PageRequest, QueryClientEventRequest, QueryClientStateRequest,
     QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryHeight, QueryTxRequest,
-    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest, QueryWasmChecksumsRequest,
 };
 use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::AnyClientState;
+use crate::config::client_wrapper::ClientWrapper;
 use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
 use crate::error::Error as RelayerError;
 use crate::event::IbcEventWithHeight;
+use crate::foreign_client::expiry::ExpiryForecast;
+use crate::foreign_client::hooks::{ClientUpdate, UpdateClientHookError};
 use crate::light_client::AnyHeader;
-use crate::misbehaviour::MisbehaviourEvidence;
+use crate::misbehaviour::broadcast::{self, DetectedMisbehaviour};
+use crate::misbehaviour::{AnyMisbehaviour, MisbehaviourEvidence};
+use crate::recover_client::{
+    build_and_send_recover_client_proposal, RecoverClientError, RecoverClientOptions,
+};
 use crate::telemetry;
 use crate::util::pretty::{PrettyDuration, PrettySlice};
 
+pub mod expiry;
+pub mod hooks;
+
 const MAX_MISBEHAVIOUR_CHECK_DURATION: Duration = Duration::from_secs(120);
 
 const MAX_RETRIES: usize = 5;
@@ -67,6 +80,16 @@ define_error! {
             [ ClientError ]
             |_| { "ICS02 client error" },
 
+        WasmContractNotFound
+            {
+                chain_id: ChainId,
+                checksum: String,
+            }
+            |e| {
+                format_args!("no wasm contract with checksum {0} is stored on chain {1} to run the client",
+                    e.checksum, e.chain_id)
+            },
+
         HeaderInTheFuture
             {
                 src_chain_id: ChainId,
@@ -82,6 +105,19 @@ define_error! {
                  e.src_chain_id, e.src_header_height, e.src_header_time, e.dst_chain_id, e.dst_latest_header_height, e.dst_latest_header_time, e.max_drift)
             },
 
+        NonMonotonicHeaderTime
+            {
+                src_chain_id: ChainId,
+                header_height: Height,
+                header_time: Timestamp,
+                previous_height: Height,
+                previous_time: Timestamp
+            }
+            |e| {
+                format_args!("update header from {} with height {} and time {} is not after the header with height {} and time {}",
+                 e.src_chain_id, e.header_height, e.header_time, e.previous_height, e.previous_time)
+            },
+
         ClientUpdate
             {
                 chain_id: ChainId,
@@ -191,6 +227,17 @@ define_error! {
                     e.client_id, e.chain_id, e.description)
         },
 
+        ClientRecovery
+            {
+                client_id: ClientId,
+                chain_id: ChainId,
+            }
+            [ RecoverClientError ]
+            |e| {
+                format_args!("failed while trying to recover client id {0} on chain {1}",
+                    e.client_id, e.chain_id)
+            },
+
         ClientEventQuery
             {
                 client_id: ClientId,
@@ -285,6 +332,17 @@ define_error! {
                 format_args!("failed to update client on destination {} because of error event: {}",
                     e.chain_id, e.event)
             },
+
+        UpdateHook
+            {
+                client_id: ClientId,
+                hook: String,
+            }
+            [ UpdateClientHookError ]
+            |e| {
+                format_args!("hook {} failed on update of client {}",
+                    e.hook, e.client_id)
+            },
     }
 }
 
@@ -539,6 +597,63 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
             .collect())
     }
 
+    /// Submit a governance proposal to the chain hosting this client, typically expired or
+    /// frozen, for replacing its state with the one of the given substitute client.
+    ///
+    /// The substitute client, hosted by the same chain and tracking the same source chain,
+    /// must be active, and its parameters must match the ones of this client.
+    /// The proposal is deposited the given amount of the given denomination.
+    #[instrument(
+        name = "foreign_client.recover",
+        level = "error",
+        skip(self, deposit_denom),
+        fields(client = %self)
+    )]
+    pub fn recover(
+        &self,
+        substitute_client_id: &ClientId,
+        deposit_amount: u64,
+        deposit_denom: &str,
+    ) -> Result<TxHash, ForeignClientError> {
+        let substitute = ForeignClient::restore(
+            substitute_client_id.clone(),
+            self.dst_chain.clone(),
+            self.src_chain.clone(),
+        );
+
+        // Fails if the substitute client is itself expired or frozen
+        substitute.validated_client_state()?;
+
+        let chain_config = self.dst_chain.config().map_err(|e| {
+            ForeignClientError::client_recovery(
+                self.id.clone(),
+                self.dst_chain.id(),
+                RecoverClientError::query(e),
+            )
+        })?;
+
+        let opts = RecoverClientOptions {
+            chain_config,
+            subject_client_id: self.id.clone(),
+            substitute_client_id: substitute_client_id.clone(),
+            amount: deposit_amount,
+            denom: deposit_denom.to_string(),
+        };
+
+        let tx_hash = build_and_send_recover_client_proposal(self.dst_chain.clone(), &opts)
+            .map_err(|e| {
+                ForeignClientError::client_recovery(self.id.clone(), self.dst_chain.id(), e)
+            })?;
+
+        info!(
+            substitute = %substitute_client_id,
+            "submitted the proposal recovering the client in tx {}",
+            tx_hash
+        );
+
+        Ok(tx_hash)
+    }
+
     /// Returns a handle to the chain hosting this client.
     pub fn dst_chain(&self) -> DstChain {
         self.dst_chain.clone()
@@ -623,13 +738,81 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                 )
             })?;
 
+        // Wrap the states of the client if the destination chain only accepts wrapped clients
+        let (client_state, consensus_state) = match &dst_config.client_wrapper {
+            Some(wrapper) => {
+                self.check_client_wrapper(wrapper)?;
+
+                (
+                    wrapper.wrap_client_state(
+                        client_state.clone().into(),
+                        client_state.latest_height(),
+                    ),
+                    wrapper.wrap_consensus_state(consensus_state.into()),
+                )
+            }
+            None => (client_state.into(), consensus_state.into()),
+        };
+
         //TODO Get acct_prefix
-        let msg = MsgCreateClient::new(client_state.into(), consensus_state.into(), signer)
+        let msg = MsgCreateClient::new(client_state, consensus_state, signer)
             .map_err(ForeignClientError::client)?;
 
         Ok(msg)
     }
 
+    /// Check that the destination chain can run the clients wrapped with the given wrapper.
+    fn check_client_wrapper(&self, wrapper: &ClientWrapper) -> Result<(), ForeignClientError> {
+        match wrapper {
+            ClientWrapper::Wasm { checksum } => {
+                let checksums = self
+                    .dst_chain
+                    .query_wasm_checksums(QueryWasmChecksumsRequest {
+                        pagination: Some(PageRequest::all()),
+                    })
+                    .map_err(|e| {
+                        ForeignClientError::client_create(
+                            self.dst_chain.id(),
+                            "failed while querying the checksums of the wasm contracts".to_string(),
+                            e,
+                        )
+                    })?;
+
+                if !checksums.contains(checksum) {
+                    return Err(ForeignClientError::wasm_contract_not_found(
+                        self.dst_chain.id(),
+                        hex::encode(checksum),
+                    ));
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// The given header or misbehaviour, wrapped if the destination chain
+    /// only accepts wrapped clients.
+    fn wrap_client_message(&self, message: Any) -> Any {
+        let wrapper = self
+            .dst_chain
+            .config()
+            .map(|config| config.client_wrapper)
+            .unwrap_or_else(|e| {
+                warn!(
+                    chain = %self.dst_chain.id(),
+                    "failed to get the config of the chain, not wrapping the client message: {}",
+                    e
+                );
+
+                None
+            });
+
+        match wrapper {
+            Some(wrapper) => wrapper.wrap_client_message(message),
+            None => message,
+        }
+    }
+
     /// Returns the identifier of the newly created client.
     pub fn build_create_client_and_send(
         &self,
@@ -804,25 +987,6 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         fields(client = %self)
     )]
     pub fn refresh(&mut self) -> Result<Option<Vec<IbcEvent>>, ForeignClientError> {
-        fn check_no_errors(
-            ibc_events: &[IbcEvent],
-            dst_chain_id: ChainId,
-        ) -> Result<(), ForeignClientError> {
-            // The assumption is that only one IbcEventType::ChainError will be
-            // in the resulting Vec<IbcEvent> if an error occurred.
-            let chain_error = ibc_events
-                .iter()
-                .find(|&e| e.event_type() == IbcEventType::ChainError);
-
-            match chain_error {
-                None => Ok(()),
-                Some(ev) => Err(ForeignClientError::chain_error_event(
-                    dst_chain_id,
-                    ev.to_owned(),
-                )),
-            }
-        }
-
         // If elapsed < refresh_window for the client, `try_refresh()` will
         // be successful with an empty vector.
         if let Some(events) = self.try_refresh()? {
@@ -833,6 +997,36 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         }
     }
 
+    /// Refresh the client now, whatever the time elapsed since its latest update.
+    #[instrument(
+        name = "foreign_client.refresh_now",
+        level = "error",
+        skip_all,
+        fields(client = %self)
+    )]
+    pub fn refresh_now(&mut self) -> Result<Vec<IbcEvent>, ForeignClientError> {
+        let events = self.build_latest_update_client_and_send()?;
+        check_no_errors(&events, self.dst_chain().id())?;
+        Ok(events)
+    }
+
+    /// Forecast the expiry of the client, from its trusting period and the time elapsed
+    /// since its latest update. There is none for the clients without a trusting period.
+    pub fn expiry_forecast(&self) -> Result<Option<ExpiryForecast>, ForeignClientError> {
+        let (client_state, elapsed) = self.validated_client_state()?;
+
+        let forecast =
+            client_state
+                .trusting_period()
+                .zip(elapsed)
+                .map(|(trusting_period, elapsed)| ExpiryForecast {
+                    trusting_period,
+                    elapsed,
+                });
+
+        Ok(forecast)
+    }
+
     fn try_refresh(&mut self) -> Result<Option<Vec<IbcEvent>>, ForeignClientError> {
         let (client_state, elapsed) = self.validated_client_state()?;
 
@@ -1038,6 +1232,45 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         }
     }
 
+    /// Check that the timestamps of the headers of a client update strictly increase from
+    /// the timestamp of the trusted consensus state, which the destination chain would
+    /// otherwise reject after the relayer paid for the transaction.
+    fn check_headers_monotonic_time(
+        &self,
+        trusted_height: Height,
+        support: &[AnyHeader],
+        header: &AnyHeader,
+    ) -> Result<(), ForeignClientError> {
+        let mut previous_height = trusted_height;
+        let mut previous_time = self.fetch_consensus_state(trusted_height)?.timestamp();
+
+        for header in support.iter().chain(core::iter::once(header)) {
+            if !header.timestamp().after(&previous_time) {
+                warn!(
+                    header_height = %header.height(),
+                    header_time = %header.timestamp(),
+                    %previous_height,
+                    %previous_time,
+                    "the timestamp of the update header of {} is not after the previous one",
+                    self.src_chain.id(),
+                );
+
+                return Err(ForeignClientError::non_monotonic_header_time(
+                    self.src_chain.id(),
+                    header.height(),
+                    header.timestamp(),
+                    previous_height,
+                    previous_time,
+                ));
+            }
+
+            previous_height = header.height();
+            previous_time = header.timestamp();
+        }
+
+        Ok(())
+    }
+
     /// Wait for the source chain application to reach height `target_height`
     /// before building the update client messages.
     ///
@@ -1147,6 +1380,7 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
             )
         })?;
 
+        self.check_headers_monotonic_time(trusted_height, &support, &header)?;
         self.wait_for_header_validation_delay(&client_state, &header)?;
 
         let mut msgs = vec![];
@@ -1159,7 +1393,7 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
 
             msgs.push(
                 MsgUpdateClient {
-                    header: header.into(),
+                    header: self.wrap_client_message(header.into()),
                     client_id: self.id.clone(),
                     signer: signer.clone(),
                 }
@@ -1175,13 +1409,26 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
 
         msgs.push(
             MsgUpdateClient {
-                header: header.into(),
-                signer,
+                header: self.wrap_client_message(header.into()),
+                signer: signer.clone(),
                 client_id: self.id.clone(),
             }
             .to_any(),
         );
 
+        let (src_chain_id, dst_chain_id) = (self.src_chain.id(), self.dst_chain.id());
+        let update = ClientUpdate {
+            src_chain: &src_chain_id,
+            dst_chain: &dst_chain_id,
+            client_id: &self.id,
+            trusted_height,
+            target_height,
+            signer: &signer,
+        };
+
+        hooks::run_hooks(&hooks::registered(), &update, &mut msgs)
+            .map_err(|(hook, e)| ForeignClientError::update_hook(self.id.clone(), hook, e))?;
+
         telemetry!(
             client_updates_submitted,
             &self.src_chain.id(),
@@ -1353,6 +1600,7 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
             .query_consensus_states(QueryConsensusStatesRequest {
                 client_id: self.id.clone(),
                 pagination: Some(PageRequest::all()),
+                height: QueryHeight::Latest,
             })
             .map_err(|e| {
                 ForeignClientError::client_query(self.id().clone(), self.src_chain.id(), e)
@@ -1606,13 +1854,88 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         Ok(None)
     }
 
+    /// Build the evidence of the given misbehaviour of the source chain, detected on
+    /// another client of the chain, for this client.
+    ///
+    /// The conflicting header is re-anchored on a consensus state of this client, from
+    /// which the header of the chain at the height of the misbehaviour is built, along
+    /// with its supporting headers.
+    #[instrument(
+        name = "foreign_client.build_evidence_from",
+        level = "error",
+        skip_all,
+        fields(client = %self)
+    )]
+    pub fn build_evidence_from(
+        &self,
+        evidence: &MisbehaviourEvidence,
+    ) -> Result<MisbehaviourEvidence, ForeignClientError> {
+        let misbehaviour = match &evidence.misbehaviour {
+            AnyMisbehaviour::Tendermint(misbehaviour) => misbehaviour,
+
+            #[cfg(test)]
+            AnyMisbehaviour::Mock(_) => {
+                return Err(ForeignClientError::misbehaviour_exit(
+                    "cannot re-anchor the evidence of a mock misbehaviour".to_string(),
+                ))
+            }
+        };
+
+        let (client_state, _) = self.validated_client_state()?;
+
+        let target_height = misbehaviour.header2.height();
+        let trusted_height = self.solve_trusted_height(target_height, &client_state)?;
+
+        let (header, supporting_headers) = self
+            .src_chain()
+            .build_header(trusted_height, target_height, client_state)
+            .map_err(|e| {
+                ForeignClientError::misbehaviour(
+                    format!(
+                        "failed building the header of chain {} at height {}",
+                        self.src_chain.id(),
+                        target_height
+                    ),
+                    e,
+                )
+            })?;
+
+        let header2 = match header {
+            AnyHeader::Tendermint(header) => header,
+
+            #[cfg(test)]
+            AnyHeader::Mock(_) => {
+                return Err(ForeignClientError::misbehaviour_exit(
+                    "cannot re-anchor the evidence on a mock header".to_string(),
+                ))
+            }
+        };
+
+        // The trusted height and validators of a header are not signed
+        let header1 = TmHeader {
+            trusted_height: header2.trusted_height,
+            trusted_validator_set: header2.trusted_validator_set.clone(),
+            ..misbehaviour.header1.clone()
+        };
+
+        Ok(MisbehaviourEvidence {
+            misbehaviour: TmMisbehaviour {
+                client_id: self.id.clone(),
+                header1,
+                header2,
+            }
+            .into(),
+            supporting_headers,
+        })
+    }
+
     #[instrument(
         name = "foreign_client.submit_evidence",
         level = "error",
         skip(self),
         fields(client = %self)
     )]
-    fn submit_evidence(
+    pub fn submit_evidence(
         &self,
         evidence: MisbehaviourEvidence,
     ) -> Result<Vec<IbcEvent>, ForeignClientError> {
@@ -1631,7 +1954,7 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         for header in evidence.supporting_headers {
             msgs.push(
                 MsgUpdateClient {
-                    header: header.into(),
+                    header: self.wrap_client_message(header.into()),
                     client_id: self.id.clone(),
                     signer: signer.clone(),
                 }
@@ -1641,7 +1964,7 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
 
         msgs.push(
             MsgSubmitMisbehaviour {
-                misbehaviour: evidence.misbehaviour.into(),
+                misbehaviour: self.wrap_client_message(evidence.misbehaviour.into()),
                 client_id: self.id.clone(),
                 signer,
             }
@@ -1675,11 +1998,69 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
     pub fn detect_misbehaviour_and_submit_evidence(
         &self,
         update_event: Option<&UpdateClient>,
+    ) -> MisbehaviourResults {
+        self.handle_misbehaviour(update_event, true, None)
+    }
+
+    /// Same as [`ForeignClient::detect_misbehaviour_and_submit_evidence`], except that
+    /// the misbehaviour detected is also sent with the given sender, for its evidence
+    /// to be broadcast to the faulty chain and to its other clients.
+    #[instrument(
+        name = "foreign_client.detect_misbehaviour_and_broadcast_evidence",
+        level = "error",
+        skip(self, broadcast),
+        fields(client = %self)
+    )]
+    pub fn detect_misbehaviour_and_broadcast_evidence(
+        &self,
+        update_event: Option<&UpdateClient>,
+        broadcast: &broadcast::Sender,
+    ) -> MisbehaviourResults {
+        self.handle_misbehaviour(update_event, true, Some(broadcast))
+    }
+
+    /// Same as [`ForeignClient::detect_misbehaviour_and_submit_evidence`], except that
+    /// the misbehaviour detected is only reported and its evidence is never submitted,
+    /// as done by a watch-only relayer.
+    #[instrument(
+        name = "foreign_client.detect_misbehaviour_and_report",
+        level = "error",
+        skip(self),
+        fields(client = %self)
+    )]
+    pub fn detect_misbehaviour_and_report(
+        &self,
+        update_event: Option<&UpdateClient>,
+    ) -> MisbehaviourResults {
+        self.handle_misbehaviour(update_event, false, None)
+    }
+
+    fn handle_misbehaviour(
+        &self,
+        update_event: Option<&UpdateClient>,
+        submit: bool,
+        broadcast: Option<&broadcast::Sender>,
     ) -> MisbehaviourResults {
         // check evidence of misbehaviour for all updates or one
         let result = match self.detect_misbehaviour(update_event) {
             Err(e) => Err(e),
             Ok(None) => Ok(vec![]), // no evidence found
+            Ok(Some(detected)) if !submit => {
+                error!(
+                    misbehaviour = %detected.misbehaviour,
+                    "misbehaviour detected, not sending evidence in watch-only mode"
+                );
+
+                telemetry!(
+                    client_misbehaviours_detected,
+                    &self.src_chain.id(),
+                    &self.dst_chain.id(),
+                    &self.id,
+                    1
+                );
+
+                return MisbehaviourResults::EvidenceDetected;
+            }
             Ok(Some(detected)) => {
                 error!(
                     misbehaviour = %detected.misbehaviour,
@@ -1694,7 +2075,23 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                     1
                 );
 
-                self.submit_evidence(detected)
+                match broadcast {
+                    None => self.submit_evidence(detected),
+                    Some(broadcast) => {
+                        let result = self.submit_evidence(detected.clone());
+
+                        // The faulty chain and its other clients are sent the
+                        // evidence by the supervisor
+                        let _ = broadcast.send(DetectedMisbehaviour {
+                            faulty_chain: self.src_chain.id(),
+                            host_chain: self.dst_chain.id(),
+                            client_id: self.id.clone(),
+                            evidence: detected,
+                        });
+
+                        result
+                    }
+                }
             }
         };
 
@@ -1763,9 +2160,30 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
     }
 }
 
+fn check_no_errors(
+    ibc_events: &[IbcEvent],
+    dst_chain_id: ChainId,
+) -> Result<(), ForeignClientError> {
+    // The assumption is that only one IbcEventType::ChainError will be
+    // in the resulting Vec<IbcEvent> if an error occurred.
+    let chain_error = ibc_events
+        .iter()
+        .find(|&e| e.event_type() == IbcEventType::ChainError);
+
+    match chain_error {
+        None => Ok(()),
+        Some(ev) => Err(ForeignClientError::chain_error_event(
+            dst_chain_id,
+            ev.to_owned(),
+        )),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum MisbehaviourResults {
     CannotExecute,
+    /// Misbehaviour was detected, but its evidence was not submitted
+    EvidenceDetected,
     EvidenceSubmitted(Vec<IbcEvent>),
     ValidClient,
     VerificationError,
@@ -0,0 +1,103 @@
+//! Forecast of the expiry of the clients, from their trusting period and the time
+//! elapsed since their latest update, from which the refresh worker of the clients
+//! decides when to refresh them.
+
+use core::time::Duration;
+
+use crate::config::{default, Clients};
+
+/// When the clients are refreshed, and when their coming expiry is warned about,
+/// as fractions of their trusting period.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RefreshPolicy {
+    /// The fraction of the trusting period elapsed since the latest update of
+    /// a client at which it is refreshed
+    pub refresh_rate: f64,
+    /// The fraction of the trusting period left below which the coming expiry
+    /// of a client is warned about
+    pub expiry_warning_rate: f64,
+}
+
+impl Default for RefreshPolicy {
+    fn default() -> Self {
+        Self {
+            refresh_rate: default::client_refresh_rate(),
+            expiry_warning_rate: default::client_expiry_warning_rate(),
+        }
+    }
+}
+
+impl From<&Clients> for RefreshPolicy {
+    fn from(clients: &Clients) -> Self {
+        Self {
+            refresh_rate: clients.refresh_rate,
+            expiry_warning_rate: clients.expiry_warning_rate,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ExpiryForecast {
+    pub trusting_period: Duration,
+    /// The time elapsed since the latest update of the client
+    pub elapsed: Duration,
+}
+
+impl ExpiryForecast {
+    /// The time left before the client expires, unless it is updated.
+    pub fn time_to_expiry(&self) -> Duration {
+        self.trusting_period.saturating_sub(self.elapsed)
+    }
+
+    /// How long to wait before refreshing the client, zero if it must be refreshed now.
+    pub fn refresh_in(&self, policy: &RefreshPolicy) -> Duration {
+        self.trusting_period
+            .mul_f64(policy.refresh_rate)
+            .saturating_sub(self.elapsed)
+    }
+
+    /// Whether the expiry of the client is close enough to be warned about.
+    pub fn is_expiring(&self, policy: &RefreshPolicy) -> bool {
+        self.time_to_expiry() <= self.trusting_period.mul_f64(policy.expiry_warning_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn forecast_client_expiry() {
+        let policy = RefreshPolicy {
+            refresh_rate: 0.5,
+            expiry_warning_rate: 0.1,
+        };
+
+        let fresh = ExpiryForecast {
+            trusting_period: 10 * DAY,
+            elapsed: DAY,
+        };
+
+        assert_eq!(fresh.time_to_expiry(), 9 * DAY);
+        assert_eq!(fresh.refresh_in(&policy), 4 * DAY);
+        assert!(!fresh.is_expiring(&policy));
+
+        let quiet = ExpiryForecast {
+            trusting_period: 10 * DAY,
+            elapsed: 9 * DAY + DAY / 2,
+        };
+
+        assert_eq!(quiet.time_to_expiry(), DAY / 2);
+        assert_eq!(quiet.refresh_in(&policy), Duration::ZERO);
+        assert!(quiet.is_expiring(&policy));
+
+        let expired = ExpiryForecast {
+            trusting_period: 10 * DAY,
+            elapsed: 11 * DAY,
+        };
+
+        assert_eq!(expired.time_to_expiry(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,207 @@
+//! Hooks attaching additional data to the client updates built by the relayer,
+//! for the chains whose light clients require more than the headers to
+//! update, e.g. Bitcoin-timestamped checkpoints or the proofs of an
+//! alternative finality gadget.
+//!
+//! Hooks are compiled in: a binary embedding the relayer registers its hooks
+//! with [`register`] before spawning the supervisor. Each hook which
+//! [applies to](UpdateClientHook::applies_to) a client is then given the
+//! messages of each of its updates, to which it can add its own messages,
+//! which are submitted in the same transaction.
+//!
+//! ```rust,ignore
+//! struct CheckpointHook { checkpoints: CheckpointSource }
+//!
+//! impl UpdateClientHook for CheckpointHook {
+//!     fn name(&self) -> &str {
+//!         "checkpoint"
+//!     }
+//!
+//!     fn applies_to(&self, src_chain: &ChainId, _dst_chain: &ChainId) -> bool {
+//!         self.checkpoints.tracks(src_chain)
+//!     }
+//!
+//!     fn on_update_client(
+//!         &self,
+//!         update: &ClientUpdate<'_>,
+//!         msgs: &mut Vec<Any>,
+//!     ) -> Result<(), UpdateClientHookError> {
+//!         let checkpoint = self
+//!             .checkpoints
+//!             .checkpoint_of(update.src_chain, update.target_height)
+//!             .map_err(|e| UpdateClientHookError::failed(e.to_string()))?;
+//!
+//!         msgs.push(checkpoint.to_msg(update.client_id, update.signer));
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! hooks::register(CheckpointHook { checkpoints });
+//! ```
+
+use alloc::sync::Arc;
+use std::sync::RwLock;
+
+use flex_error::define_error;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc::signer::Signer;
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+use once_cell::sync::Lazy;
+
+define_error! {
+    UpdateClientHookError {
+        Failed
+            { reason: String }
+            | e | { format_args!("update client hook failed: {}", e.reason) },
+    }
+}
+
+/// The client update being built, given to the hooks.
+#[derive(Clone, Debug)]
+pub struct ClientUpdate<'a> {
+    /// The chain tracked by the client
+    pub src_chain: &'a ChainId,
+    /// The chain hosting the client
+    pub dst_chain: &'a ChainId,
+    pub client_id: &'a ClientId,
+    pub trusted_height: Height,
+    pub target_height: Height,
+    /// The signer of the update on the destination chain
+    pub signer: &'a Signer,
+}
+
+/// Additional data attached to the updates of the clients.
+pub trait UpdateClientHook: Send + Sync {
+    /// The name of the hook, which identifies it in the logs and errors.
+    fn name(&self) -> &str;
+
+    /// Whether the hook runs for the clients of the source chain hosted on
+    /// the destination chain. All clients by default.
+    fn applies_to(&self, _src_chain: &ChainId, _dst_chain: &ChainId) -> bool {
+        true
+    }
+
+    /// Add messages to the given messages of the client update, in the order
+    /// in which they must be submitted, e.g. before the `MsgUpdateClient`s.
+    ///
+    /// An error fails the client update.
+    fn on_update_client(
+        &self,
+        update: &ClientUpdate<'_>,
+        msgs: &mut Vec<Any>,
+    ) -> Result<(), UpdateClientHookError>;
+}
+
+static HOOKS: Lazy<RwLock<Vec<Arc<dyn UpdateClientHook>>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Register a hook, to be run on all the client updates built from now on.
+pub fn register(hook: impl UpdateClientHook + 'static) {
+    HOOKS.write().unwrap().push(Arc::new(hook));
+}
+
+/// The registered hooks.
+pub fn registered() -> Vec<Arc<dyn UpdateClientHook>> {
+    HOOKS.read().unwrap().clone()
+}
+
+/// Run the given hooks which apply to the client of the given update on its messages.
+/// Returns the name of the first hook which failed along with its error.
+pub fn run_hooks(
+    hooks: &[Arc<dyn UpdateClientHook>],
+    update: &ClientUpdate<'_>,
+    msgs: &mut Vec<Any>,
+) -> Result<(), (String, UpdateClientHookError)> {
+    hooks
+        .iter()
+        .filter(|hook| hook.applies_to(update.src_chain, update.dst_chain))
+        .try_for_each(|hook| {
+            hook.on_update_client(update, msgs)
+                .map_err(|e| (hook.name().to_string(), e))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    struct CheckpointHook {
+        src_chain: ChainId,
+    }
+
+    impl UpdateClientHook for CheckpointHook {
+        fn name(&self) -> &str {
+            "checkpoint"
+        }
+
+        fn applies_to(&self, src_chain: &ChainId, _dst_chain: &ChainId) -> bool {
+            src_chain == &self.src_chain
+        }
+
+        fn on_update_client(
+            &self,
+            update: &ClientUpdate<'_>,
+            msgs: &mut Vec<Any>,
+        ) -> Result<(), UpdateClientHookError> {
+            if update.target_height.revision_height() > 100 {
+                return Err(UpdateClientHookError::failed("no checkpoint".to_string()));
+            }
+
+            msgs.insert(
+                0,
+                Any {
+                    type_url: "/checkpoint.MsgCheckpoint".to_string(),
+                    value: vec![],
+                },
+            );
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn hooks_attach_messages() {
+        let hooks: Vec<Arc<dyn UpdateClientHook>> = vec![Arc::new(CheckpointHook {
+            src_chain: ChainId::new("ibc".to_string(), 0),
+        })];
+
+        let src_chain = ChainId::new("ibc".to_string(), 0);
+        let dst_chain = ChainId::new("ibc".to_string(), 1);
+        let client_id = ClientId::default();
+        let signer = Signer::from_str("cosmos1xh2jvz9ecty8qdctlgscmys2dr5gz729k0l7x4").unwrap();
+
+        let update_msg = Any {
+            type_url: "/ibc.core.client.v1.MsgUpdateClient".to_string(),
+            value: vec![],
+        };
+
+        let mut update = ClientUpdate {
+            src_chain: &src_chain,
+            dst_chain: &dst_chain,
+            client_id: &client_id,
+            trusted_height: Height::new(0, 10).unwrap(),
+            target_height: Height::new(0, 20).unwrap(),
+            signer: &signer,
+        };
+
+        let mut msgs = vec![update_msg.clone()];
+        run_hooks(&hooks, &update, &mut msgs).unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].type_url, "/checkpoint.MsgCheckpoint");
+        assert_eq!(msgs[1], update_msg);
+
+        // The hook does not apply to the clients of other chains
+        update.src_chain = &dst_chain;
+        let mut msgs = vec![update_msg.clone()];
+        run_hooks(&hooks, &update, &mut msgs).unwrap();
+        assert_eq!(msgs, vec![update_msg.clone()]);
+
+        update.src_chain = &src_chain;
+        update.target_height = Height::new(0, 200).unwrap();
+        let (name, _) = run_hooks(&hooks, &update, &mut vec![update_msg]).unwrap_err();
+        assert_eq!(name, "checkpoint");
+    }
+}
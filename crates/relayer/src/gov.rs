@@ -0,0 +1,152 @@
+//! Submission of governance proposals carrying IBC actions, such as
+//! IBC-breaking chain upgrades and the recovery of frozen or expired clients.
+//!
+//! Chains running Cosmos SDK v0.50 or later expect these actions as messages
+//! executed by the governance module, submitted with a `cosmos.gov.v1.MsgSubmitProposal`.
+//! Older chains expect them as legacy proposal content, submitted with a
+//! `cosmos.gov.v1beta1.MsgSubmitProposal`.
+
+use bech32::{ToBase32, Variant};
+use sha2::{Digest, Sha256};
+
+use ibc_proto::cosmos::base::v1beta1::Coin;
+use ibc_proto::cosmos::gov::v1beta1::MsgSubmitProposal as LegacyMsgSubmitProposal;
+use ibc_proto::google::protobuf::Any;
+
+use crate::chain::handle::ChainHandle;
+use crate::error::Error;
+
+pub const MSG_SUBMIT_PROPOSAL_TYPE_URL: &str = "/cosmos.gov.v1.MsgSubmitProposal";
+pub const LEGACY_MSG_SUBMIT_PROPOSAL_TYPE_URL: &str = "/cosmos.gov.v1beta1.MsgSubmitProposal";
+
+/// The `cosmos.gov.v1.MsgSubmitProposal` of Cosmos SDK v0.50, which the Cosmos SDK
+/// version the definitions of `ibc-proto` are generated from predates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSubmitProposal {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<Coin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+    #[prost(string, tag = "5")]
+    pub title: String,
+    #[prost(string, tag = "6")]
+    pub summary: String,
+    #[prost(bool, tag = "7")]
+    pub expedited: bool,
+}
+
+/// The flavour of governance proposals accepted by a chain for IBC actions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GovVersion {
+    /// Legacy proposal content, e.g. `UpgradeProposal` or `ClientUpdateProposal`
+    Legacy,
+    /// `x/ibc` messages executed by the governance module, e.g. `MsgIBCSoftwareUpgrade`
+    /// or `MsgRecoverClient`
+    V1,
+}
+
+impl GovVersion {
+    /// Chains whose Cosmos SDK version is unknown are assumed to accept legacy proposals.
+    pub fn from_sdk_version(sdk_version: Option<&semver::Version>) -> Self {
+        match sdk_version {
+            Some(version) if (version.major, version.minor) >= (0, 50) => Self::V1,
+            _ => Self::Legacy,
+        }
+    }
+
+    /// Detect the flavour of governance proposals accepted by the given chain
+    /// from the version of the Cosmos SDK it is running.
+    pub fn detect(chain: &impl ChainHandle) -> Result<Self, Error> {
+        let sdk_version = chain.sdk_version()?;
+        Ok(Self::from_sdk_version(sdk_version.as_ref()))
+    }
+}
+
+/// Address of the governance module account, which is the authority expected
+/// as signer of the messages executed by the governance module.
+pub fn gov_authority(account_prefix: &str) -> Result<String, Error> {
+    let hash = Sha256::digest(b"gov");
+
+    bech32::encode(account_prefix, hash[..20].to_base32(), Variant::Bech32)
+        .map_err(Error::bech32_encoding)
+}
+
+/// Wrap the given messages into a proposal for chains using [`GovVersion::V1`].
+pub fn submit_proposal(
+    messages: Vec<Any>,
+    deposit: Coin,
+    proposer: String,
+    title: String,
+    summary: String,
+) -> Any {
+    let msg = MsgSubmitProposal {
+        messages,
+        initial_deposit: vec![deposit],
+        proposer,
+        metadata: String::new(),
+        title,
+        summary,
+        expedited: false,
+    };
+
+    let mut buf_msg = Vec::new();
+    prost::Message::encode(&msg, &mut buf_msg).unwrap();
+
+    Any {
+        type_url: MSG_SUBMIT_PROPOSAL_TYPE_URL.to_string(),
+        value: buf_msg,
+    }
+}
+
+/// Wrap the given proposal content into a proposal for chains using [`GovVersion::Legacy`].
+pub fn submit_legacy_proposal(content: Any, deposit: Coin, proposer: String) -> Any {
+    let msg = LegacyMsgSubmitProposal {
+        content: Some(content),
+        initial_deposit: vec![deposit],
+        proposer,
+    };
+
+    let mut buf_msg = Vec::new();
+    prost::Message::encode(&msg, &mut buf_msg).unwrap();
+
+    Any {
+        type_url: LEGACY_MSG_SUBMIT_PROPOSAL_TYPE_URL.to_string(),
+        value: buf_msg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gov_version_from_sdk_version() {
+        let version = |v: &str| semver::Version::parse(v).unwrap();
+
+        assert_eq!(GovVersion::from_sdk_version(None), GovVersion::Legacy);
+        assert_eq!(
+            GovVersion::from_sdk_version(Some(&version("0.47.5"))),
+            GovVersion::Legacy
+        );
+        assert_eq!(
+            GovVersion::from_sdk_version(Some(&version("0.50.0-rc.1"))),
+            GovVersion::V1
+        );
+        assert_eq!(
+            GovVersion::from_sdk_version(Some(&version("0.50.3"))),
+            GovVersion::V1
+        );
+    }
+
+    #[test]
+    fn gov_authority_address() {
+        assert_eq!(
+            gov_authority("cosmos").unwrap(),
+            "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! High-availability mode, in which several identically configured relayer
+//! instances coordinate through a shared lock so that only one of them,
+//! the leader, actively relays at any given time.
+//!
+//! The leader holds the lock under a lease which it renews periodically.
+//! If the leader fails, its lease expires and one of the standby instances,
+//! which keep trying to acquire the lock, takes over.
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use flex_error::{define_error, TraceError};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::{HaBackend, HaConfig};
+
+pub mod claims;
+pub mod redis;
+
+use self::redis::RedisLock;
+
+define_error! {
+    Error {
+        Io
+            [ TraceError<std::io::Error> ]
+            |_| { "I/O error while talking to the lock backend" },
+
+        InvalidUrl
+            { url: String, reason: String }
+            |e| { format!("invalid lock backend URL '{}': {}", e.url, e.reason) },
+
+        Backend
+            { reason: String }
+            |e| { format!("lock backend returned an error: {}", e.reason) },
+
+        UnexpectedReply
+            { reply: String }
+            |e| { format!("unexpected reply from the lock backend: {}", e.reply) },
+
+        LeadershipLost
+            { key: String }
+            |e| { format!("lost the leadership lock '{}'", e.key) },
+    }
+}
+
+/// A lock with an expiring lease, shared by the relayer instances.
+pub trait LockBackend: Send {
+    /// Try to acquire the lock for the given holder, returning whether it was acquired.
+    fn try_acquire(&mut self, key: &str, holder: &str, ttl: Duration) -> Result<bool, Error>;
+
+    /// Extend the lease of the lock, returning `false` if it is not held by the given holder.
+    fn renew(&mut self, key: &str, holder: &str, ttl: Duration) -> Result<bool, Error>;
+
+    /// Release the lock, if it is held by the given holder.
+    fn release(&mut self, key: &str, holder: &str) -> Result<(), Error>;
+
+    /// Acquire each of the given locks for the given holder, or extend its lease if it already
+    /// holds it, returning whether it holds each of them.
+    fn claim_all(&mut self, keys: &[&str], holder: &str, ttl: Duration)
+        -> Result<Vec<bool>, Error>;
+}
+
+/// Handles the election of the leader among the relayer instances.
+pub struct LeaderElector {
+    backend: Box<dyn LockBackend>,
+    key: String,
+    holder: String,
+    lease_ttl: Duration,
+    renew_interval: Duration,
+}
+
+impl LeaderElector {
+    pub fn new(config: &HaConfig) -> Result<Self, Error> {
+        let backend: Box<dyn LockBackend> = match &config.backend {
+            HaBackend::Redis { url } => Box::new(RedisLock::connect(url)?),
+        };
+
+        Ok(Self {
+            backend,
+            key: config.lock_key.clone(),
+            holder: holder_id(),
+            lease_ttl: config.lease_ttl,
+            renew_interval: config.renew_interval,
+        })
+    }
+
+    /// Identifier of this instance as the holder of the lock.
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Block until this instance becomes the leader.
+    pub fn wait_for_leadership(&mut self) {
+        info!(
+            holder = %self.holder,
+            "waiting to acquire the leadership lock '{}'",
+            self.key
+        );
+
+        loop {
+            match self
+                .backend
+                .try_acquire(&self.key, &self.holder, self.lease_ttl)
+            {
+                Ok(true) => {
+                    info!(holder = %self.holder, "acquired the leadership lock, now leader");
+                    return;
+                }
+                Ok(false) => debug!("leadership lock is held by another instance, standing by"),
+                Err(e) => warn!("failed to acquire the leadership lock: {}", e),
+            }
+
+            thread::sleep(self.renew_interval);
+        }
+    }
+
+    /// Keep renewing the lease of the leadership lock in the background from now on,
+    /// until it is released or lost, i.e. held by another instance or not renewed before
+    /// the lease expired, in which case the given function is called.
+    pub fn hold_leadership(self, on_lost: impl FnOnce(Error) + Send + 'static) -> Leadership {
+        let renew_interval = self.renew_interval;
+        let lease_ttl = self.lease_ttl;
+
+        let leadership = Leadership {
+            elector: Arc::new(Mutex::new(Some(self))),
+        };
+
+        let elector = leadership.elector.clone();
+
+        thread::spawn(move || {
+            let mut failures = 0;
+
+            loop {
+                thread::sleep(renew_interval);
+
+                let mut elector = elector.lock().unwrap_or_else(|e| e.into_inner());
+
+                // The leadership was released
+                let elector = match elector.as_mut() {
+                    Some(elector) => elector,
+                    None => return,
+                };
+
+                match elector
+                    .backend
+                    .renew(&elector.key, &elector.holder, lease_ttl)
+                {
+                    Ok(true) => failures = 0,
+                    Ok(false) => return on_lost(Error::leadership_lost(elector.key.clone())),
+                    Err(e) => {
+                        failures += 1;
+                        warn!("failed to renew the leadership lock: {}", e);
+
+                        // Stop before the lease may have expired, as another instance may
+                        // then have taken over the lock without this one noticing.
+                        if renew_interval * (failures + 1) >= lease_ttl {
+                            return on_lost(Error::leadership_lost(elector.key.clone()));
+                        }
+                    }
+                }
+            }
+        });
+
+        leadership
+    }
+}
+
+/// The leadership of this instance, whose lease is renewed in the background.
+#[derive(Clone)]
+pub struct Leadership {
+    elector: Arc<Mutex<Option<LeaderElector>>>,
+}
+
+impl Leadership {
+    /// Stop renewing the lease and release the leadership lock, so that a standby
+    /// instance can take over right away.
+    pub fn release(&self) {
+        let elector = self
+            .elector
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        if let Some(mut elector) = elector {
+            match elector.backend.release(&elector.key, &elector.holder) {
+                Ok(()) => info!("released the leadership lock"),
+                Err(e) => warn!("failed to release the leadership lock: {}", e),
+            }
+        }
+    }
+}
+
+fn holder_id() -> String {
+    format!("{}-{}", std::process::id(), Uuid::new_v4())
+}
@@ -0,0 +1,238 @@
+//! Claims of the packets by cooperating relayer instances, which all relay the same
+//! paths and split their packets between them, as opposed to the high-availability
+//! mode where only the leader relays.
+//!
+//! Before building the message relaying a packet, or its acknowledgement, an instance
+//! claims it in the backend shared by the instances, under a key identifying the
+//! packet and held for a limited time. The instances which fail to claim it skip it,
+//! so that its message is not submitted by several instances, each spending gas on it
+//! and all but one failing. A packet claimed by an instance which then failed to relay
+//! it is claimed, and relayed, by another instance once the claim expired.
+
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use once_cell::sync::OnceCell;
+use tracing::{info, warn};
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+use crate::config::claims::ClaimsConfig;
+use crate::config::HaBackend;
+
+use super::redis::RedisLock;
+use super::{holder_id, Error, LockBackend};
+
+static SETTINGS: OnceCell<ClaimsSettings> = OnceCell::new();
+
+/// What is claimed about a packet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClaimKind {
+    /// Receiving the packet on the destination chain, or timing it out on the source chain
+    Packet,
+    /// Acknowledging the packet on the source chain
+    Ack,
+}
+
+impl ClaimKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Packet => "packet",
+            Self::Ack => "ack",
+        }
+    }
+}
+
+struct ClaimsSettings {
+    backend: HaBackend,
+    key_prefix: String,
+    holder: String,
+    ttl: Duration,
+}
+
+fn connect(backend: &HaBackend) -> Result<Box<dyn LockBackend>, Error> {
+    match backend {
+        HaBackend::Redis { url } => Ok(Box::new(RedisLock::connect(url)?)),
+    }
+}
+
+/// Claim the packets in the backend of the given configuration before relaying
+/// them from now on, if the claims are enabled.
+pub fn claim_with(config: &ClaimsConfig) -> Result<(), Error> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    // Fail right away if the backend cannot be reached
+    connect(&config.backend)?;
+
+    let settings = ClaimsSettings {
+        backend: config.backend.clone(),
+        key_prefix: config.key_prefix.clone(),
+        holder: holder_id(),
+        ttl: config.ttl,
+    };
+
+    info!(holder = %settings.holder, "claiming the packets before relaying them");
+
+    // The claims are only set up once, by `hermes start`
+    let _ = SETTINGS.set(settings);
+
+    Ok(())
+}
+
+/// The claims of the packets relayed on a path, made over a connection to the backend
+/// of its own, so that the paths do not wait on each other.
+pub struct PathClaims {
+    settings: &'static ClaimsSettings,
+    state: Mutex<ClaimsState>,
+}
+
+struct ClaimsState {
+    /// The connection to the backend, made anew after it failed to be made
+    backend: Option<Box<dyn LockBackend>>,
+    /// The claims acquired by this instance, with the time at which they expire
+    held: HashMap<String, Instant>,
+}
+
+impl PathClaims {
+    /// The claims of the packets of a path, if the claims are enabled.
+    pub fn new() -> Option<Self> {
+        let settings = SETTINGS.get()?;
+
+        Some(Self {
+            settings,
+            state: Mutex::new(ClaimsState {
+                backend: None,
+                held: HashMap::new(),
+            }),
+        })
+    }
+
+    /// The key of the claim of the given packet, sent from the given chain.
+    pub fn key(
+        &self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+        kind: ClaimKind,
+    ) -> String {
+        claim_key(
+            &self.settings.key_prefix,
+            chain_id,
+            port_id,
+            channel_id,
+            sequence,
+            kind,
+        )
+    }
+
+    /// Whether this instance may relay each of the packets of the given claims, i.e. whether
+    /// it claimed the packet, or already held the claim. All the claims which are not held
+    /// for at least half of their time anymore are made in a single round trip to the backend.
+    /// The packets may be relayed if the backend failed, in which case they may also be relayed
+    /// by another instance rather than by none.
+    pub fn claim_all(&self, keys: &[String]) -> Vec<bool> {
+        let settings = self.settings;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let now = Instant::now();
+        state.held.retain(|_, expiry| *expiry > now);
+
+        let renewed_before = now + settings.ttl / 2;
+        let to_claim: Vec<&str> = keys
+            .iter()
+            .filter(|key| {
+                state
+                    .held
+                    .get(key.as_str())
+                    .map_or(true, |expiry| *expiry <= renewed_before)
+            })
+            .map(String::as_str)
+            .collect();
+
+        if !to_claim.is_empty() {
+            match state.claim(settings, &to_claim) {
+                Ok(claimed) => {
+                    let expiry = now + settings.ttl;
+
+                    for (key, claimed) in to_claim.iter().zip(claimed) {
+                        if claimed {
+                            state.held.insert(key.to_string(), expiry);
+                        } else {
+                            state.held.remove(*key);
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "failed to claim {} packets, relaying them regardless: {}",
+                        to_claim.len(),
+                        e
+                    );
+
+                    return vec![true; keys.len()];
+                }
+            }
+        }
+
+        keys.iter()
+            .map(|key| state.held.contains_key(key.as_str()))
+            .collect()
+    }
+}
+
+impl ClaimsState {
+    fn claim(&mut self, settings: &ClaimsSettings, keys: &[&str]) -> Result<Vec<bool>, Error> {
+        let backend = match &mut self.backend {
+            Some(backend) => backend,
+            None => self.backend.insert(connect(&settings.backend)?),
+        };
+
+        backend.claim_all(keys, &settings.holder, settings.ttl)
+    }
+}
+
+fn claim_key(
+    key_prefix: &str,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: Sequence,
+    kind: ClaimKind,
+) -> String {
+    format!(
+        "{}/{}/{}/{}/{}/{}",
+        key_prefix,
+        chain_id,
+        port_id,
+        channel_id,
+        sequence,
+        kind.as_str()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn keys_of_claims() {
+        let key = claim_key(
+            "hermes/claims",
+            &ChainId::from_string("ibc-0"),
+            &PortId::transfer(),
+            &ChannelId::from_str("channel-0").unwrap(),
+            Sequence::from(42),
+            ClaimKind::Ack,
+        );
+
+        assert_eq!(key, "hermes/claims/ibc-0/transfer/channel-0/42/ack");
+    }
+}
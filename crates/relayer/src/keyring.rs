@@ -8,8 +8,8 @@ use bech32::{ToBase32, Variant};
 use bip39::{Language, Mnemonic, Seed};
 use bitcoin::{
     network::constants::Network,
-    secp256k1::{Message, Secp256k1, SecretKey},
-    util::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey},
+    secp256k1::{Message, PublicKey, Secp256k1, SecretKey},
+    util::bip32::{ChainCode, ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey},
 };
 use hdpath::StandardHDPath;
 use ibc::core::ics24_host::identifier::ChainId;
@@ -21,14 +21,19 @@ use tiny_keccak::{Hasher, Keccak};
 
 use errors::Error;
 pub use pub_key::EncodedPubKey;
+pub use signing_provider::{LocalSigner, SigningProvider};
 
 pub mod errors;
 mod pub_key;
+pub mod remote_signer;
+pub mod signing_provider;
 
 pub type HDPath = StandardHDPath;
 
 pub const KEYSTORE_DEFAULT_FOLDER: &str = ".hermes/keys/";
 pub const KEYSTORE_DISK_BACKEND: &str = "keyring-test";
+/// Folder of the keys of the namespaced keyrings, relative to [`KEYSTORE_DEFAULT_FOLDER`]
+pub const KEYSTORE_NAMESPACES_FOLDER: &str = "tenants";
 pub const KEYSTORE_FILE_EXTENSION: &str = "json";
 
 // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
@@ -41,8 +46,9 @@ pub struct KeyEntry {
     /// Public key
     pub public_key: ExtendedPubKey,
 
-    /// Private key
-    pub private_key: ExtendedPrivKey,
+    /// Private key, unless the key is held by a remote signer, see [`remote_signer`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<ExtendedPrivKey>,
 
     /// Account Bech32 format - TODO allow hrp
     pub account: String,
@@ -91,12 +97,41 @@ impl KeyEntry {
         } else {
             Ok(Self {
                 public_key: derived_pubkey,
-                private_key,
+                private_key: Some(private_key),
                 account: key_file.address,
                 address: keyfile_address_bytes,
             })
         }
     }
+
+    /// The entry of a key whose private key is held by a remote signer, from its public key.
+    pub fn from_public_key(
+        public_key: PublicKey,
+        account_prefix: &str,
+        at: &AddressType,
+    ) -> Result<Self, Error> {
+        // Only the public key is used, the key is not derived from
+        let public_key = ExtendedPubKey {
+            network: Network::Bitcoin,
+            depth: 0,
+            parent_fingerprint: Default::default(),
+            child_number: ChildNumber::Normal { index: 0 },
+            public_key,
+            chain_code: ChainCode::from(&[0; 32][..]),
+        };
+
+        let address = get_address(public_key, at);
+
+        let account = bech32::encode(account_prefix, address.to_base32(), Variant::Bech32)
+            .map_err(Error::bech32)?;
+
+        Ok(Self {
+            public_key,
+            private_key: None,
+            account,
+            address,
+        })
+    }
 }
 
 pub trait KeyStore {
@@ -261,11 +296,24 @@ pub enum KeyRing {
 
 impl KeyRing {
     pub fn new(store: Store, account_prefix: &str, chain_id: &ChainId) -> Result<Self, Error> {
+        Self::new_namespaced(store, account_prefix, chain_id, None, None)
+    }
+
+    /// The keyring of the given chain, whose keys are stored in the given folder, or else
+    /// in `$HOME/.hermes/keys`, within the folder of the given namespace, e.g. the tenant
+    /// of the chain, apart from the keys of the other namespaces.
+    pub fn new_namespaced(
+        store: Store,
+        account_prefix: &str,
+        chain_id: &ChainId,
+        namespace: Option<&str>,
+        key_store_folder: Option<&Path>,
+    ) -> Result<Self, Error> {
         match store {
             Store::Memory => Ok(Self::Memory(Memory::new(account_prefix.to_string()))),
 
             Store::Test => {
-                let keys_folder = disk_store_path(chain_id.as_str())?;
+                let keys_folder = disk_store_path(key_store_folder, namespace, chain_id.as_str())?;
 
                 // Create keys folder if it does not exist
                 fs::create_dir_all(&keys_folder).map_err(|e| {
@@ -345,7 +393,7 @@ impl KeyRing {
 
         Ok(KeyEntry {
             public_key,
-            private_key,
+            private_key: Some(private_key),
             account,
             address,
         })
@@ -377,7 +425,12 @@ pub fn sign_message(
     msg: Vec<u8>,
     address_type: &AddressType,
 ) -> Result<Vec<u8>, Error> {
-    let private_key_bytes = key.private_key.to_priv().to_bytes();
+    let private_key = key
+        .private_key
+        .as_ref()
+        .ok_or_else(|| Error::missing_private_key(key.account.clone()))?;
+
+    let private_key_bytes = private_key.to_priv().to_bytes();
     match address_type {
         AddressType::Ethermint { ref pk_type } if pk_type.ends_with(".ethsecp256k1.PubKey") => {
             let hash = keccak256_hash(msg.as_slice());
@@ -463,13 +516,24 @@ fn decode_bech32(input: &str) -> Result<Vec<u8>, Error> {
     Ok(bytes)
 }
 
-fn disk_store_path(folder_name: &str) -> Result<PathBuf, Error> {
-    let home = dirs_next::home_dir().ok_or_else(Error::home_location_unavailable)?;
+fn disk_store_path(
+    key_store_folder: Option<&Path>,
+    namespace: Option<&str>,
+    folder_name: &str,
+) -> Result<PathBuf, Error> {
+    let mut folder = match key_store_folder {
+        Some(folder) => folder.to_path_buf(),
+        None => {
+            let home = dirs_next::home_dir().ok_or_else(Error::home_location_unavailable)?;
+            home.join(KEYSTORE_DEFAULT_FOLDER)
+        }
+    };
 
-    let folder = Path::new(home.as_path())
-        .join(KEYSTORE_DEFAULT_FOLDER)
-        .join(folder_name)
-        .join(KEYSTORE_DISK_BACKEND);
+    if let Some(namespace) = namespace {
+        folder = folder.join(KEYSTORE_NAMESPACES_FOLDER).join(namespace);
+    }
+
+    let folder = folder.join(folder_name).join(KEYSTORE_DISK_BACKEND);
 
     Ok(folder)
 }
@@ -483,8 +547,6 @@ fn keccak256_hash(bytes: &[u8]) -> Vec<u8> {
 }
 
 fn standard_path_to_derivation_path(path: &StandardHDPath) -> DerivationPath {
-    use bitcoin::util::bip32::ChildNumber;
-
     let child_numbers = vec![
         ChildNumber::from_hardened_idx(path.purpose().as_value().as_number())
             .expect("Purpose is not Hardened"),
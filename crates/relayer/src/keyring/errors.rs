@@ -105,6 +105,19 @@ define_error! {
                     e.file_path)
             },
 
+        MissingPrivateKey
+            { account: String }
+            |e| {
+                format!("the private key of {} is not in the keyring, it is held by a remote signer",
+                    e.account)
+            },
+
+        RemoteSigner
+            { url: String, reason: String }
+            |e| {
+                format!("the remote signer at {} failed: {}", e.url, e.reason)
+            },
+
         InvalidHdPath
             {
                 path: String,
@@ -0,0 +1,248 @@
+//! Client of a remote signer, holding the private key of the account of a chain and
+//! signing its transactions over gRPC, see
+//! [`RemoteSignerConfig`](crate::config::remote_signer::RemoteSignerConfig).
+//!
+//! The remote signer implements the `signer.v1.Signer` service, whose messages are defined
+//! here, which exposes the compressed secp256k1 public key of the keys it holds, and signs
+//! the given bytes with them, returning the compact `r || s` encoding of the signature.
+
+use std::sync::Arc;
+use std::thread;
+
+use bitcoin::secp256k1::PublicKey;
+use http::uri::PathAndQuery;
+use ibc::core::ics24_host::identifier::ChainId;
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use prost::Message;
+use tendermint_rpc::Url;
+use tokio::runtime::Runtime as TokioRuntime;
+use tonic::codec::ProstCodec;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use crate::config::remote_signer::RemoteSignerConfig;
+use crate::config::AddressType;
+
+use super::errors::Error;
+use super::signing_provider::{LocalSigner, SigningProvider};
+use super::KeyEntry;
+
+const PUBLIC_KEY_PATH: &str = "/signer.v1.Signer/PublicKey";
+const SIGN_PATH: &str = "/signer.v1.Signer/Sign";
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PublicKeyRequest {
+    #[prost(string, tag = "1")]
+    pub key_id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PublicKeyResponse {
+    /// The compressed secp256k1 public key, of 33 bytes
+    #[prost(bytes = "vec", tag = "1")]
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SignRequest {
+    #[prost(string, tag = "1")]
+    pub key_id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub sign_bytes: Vec<u8>,
+    #[prost(string, tag = "3")]
+    pub chain_id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SignResponse {
+    /// The `r || s` encoding of the signature, of 64 bytes
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: Vec<u8>,
+}
+
+/// Signs with the key held by a remote signer the messages of its account, and with the
+/// private keys of the keyring the ones of the other accounts, e.g. the one of a fee payer.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    url: Url,
+    key_id: String,
+    chain_id: ChainId,
+    channel: Channel,
+    rt: Arc<TokioRuntime>,
+    /// The key held by the remote signer, once its public key is fetched
+    key: Option<KeyEntry>,
+}
+
+impl RemoteSigner {
+    pub fn new(
+        config: &RemoteSignerConfig,
+        chain_id: ChainId,
+        rt: Arc<TokioRuntime>,
+    ) -> Result<Self, Error> {
+        let remote_error = |e: tonic::transport::Error| {
+            Error::remote_signer(config.url.to_string(), e.to_string())
+        };
+
+        let mut endpoint = Endpoint::from_shared(config.url.to_string())
+            .map_err(remote_error)?
+            .connect_timeout(config.timeout)
+            .timeout(config.timeout);
+
+        if config.url.to_string().starts_with("https") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new())
+                .map_err(remote_error)?;
+        }
+
+        // The connection is established by the first request
+        let channel = {
+            let _guard = rt.enter();
+            endpoint.connect_lazy()
+        };
+
+        Ok(Self {
+            url: config.url.clone(),
+            key_id: config.key_id.clone(),
+            chain_id,
+            channel,
+            rt,
+            key: None,
+        })
+    }
+
+    /// Fetch the public key of the key held by the remote signer, returning its key entry.
+    pub fn fetch_key(&mut self, account_prefix: &str) -> Result<KeyEntry, Error> {
+        let request = PublicKeyRequest {
+            key_id: self.key_id.clone(),
+        };
+
+        let response: PublicKeyResponse = self.unary(PUBLIC_KEY_PATH, request)?;
+
+        let public_key = PublicKey::from_slice(&response.public_key).map_err(|e| {
+            Error::remote_signer(self.url.to_string(), format!("invalid public key: {}", e))
+        })?;
+
+        let key = KeyEntry::from_public_key(public_key, account_prefix, &AddressType::Cosmos)?;
+        self.key = Some(key.clone());
+
+        Ok(key)
+    }
+
+    fn sign_remotely(&self, key: &KeyEntry, message: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let invalid = |reason: String| Error::remote_signer(self.url.to_string(), reason);
+
+        let request = SignRequest {
+            key_id: self.key_id.clone(),
+            sign_bytes: message.clone(),
+            chain_id: self.chain_id.to_string(),
+        };
+
+        let response: SignResponse = self.unary(SIGN_PATH, request)?;
+
+        let signature = Signature::try_from(response.signature.as_slice())
+            .map_err(|e| invalid(format!("invalid signature: {}", e)))?;
+
+        // The chains only accept the signatures whose `s` is in the lower half of the order
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&key.public_key.public_key.serialize())
+            .map_err(|e| invalid(format!("invalid public key: {}", e)))?;
+
+        verifying_key.verify(&message, &signature).map_err(|_| {
+            invalid(format!(
+                "the signature does not match the public key of {}",
+                key.account
+            ))
+        })?;
+
+        Ok(signature.as_ref().to_vec())
+    }
+
+    /// Make the given request to the remote signer, from a thread of its own, as the
+    /// transactions are signed from within the runtime of the chain, which cannot be
+    /// blocked on from one of its tasks.
+    fn unary<Req, Res>(&self, path: &'static str, request: Req) -> Result<Res, Error>
+    where
+        Req: Message + Send + 'static,
+        Res: Message + Default + Send + 'static,
+    {
+        let rt = self.rt.clone();
+        let channel = self.channel.clone();
+
+        let result = thread::spawn(move || {
+            rt.block_on(async move {
+                let mut client = tonic::client::Grpc::new(channel);
+
+                client.ready().await.map_err(|e| e.to_string())?;
+
+                let response = client
+                    .unary(
+                        tonic::Request::new(request),
+                        PathAndQuery::from_static(path),
+                        ProstCodec::<Req, Res>::default(),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                Ok(response.into_inner())
+            })
+        })
+        .join()
+        .unwrap_or_else(|_| Err("the request to the remote signer panicked".to_string()));
+
+        result.map_err(|reason| Error::remote_signer(self.url.to_string(), reason))
+    }
+}
+
+impl SigningProvider for RemoteSigner {
+    fn sign(
+        &self,
+        key: &KeyEntry,
+        message: Vec<u8>,
+        address_type: &AddressType,
+    ) -> Result<Vec<u8>, Error> {
+        match &self.key {
+            Some(remote_key) if remote_key.account == key.account => {
+                self.sign_remotely(key, message)
+            }
+            _ => LocalSigner.sign(key, message, address_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    #[test]
+    fn key_of_public_key() {
+        let secret = [7u8; 32];
+
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&secret).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+
+        let key = KeyEntry::from_public_key(public_key, "cosmos", &AddressType::Cosmos).unwrap();
+
+        assert!(key.private_key.is_none());
+        assert!(key.account.starts_with("cosmos1"));
+        assert_eq!(key.address.len(), 20);
+
+        // The signatures of the remote signer are verified against the fetched public key
+        let message = b"sign doc".to_vec();
+        let signature: Signature = SigningKey::from_bytes(&secret).unwrap().sign(&message);
+
+        let verifying_key =
+            VerifyingKey::from_sec1_bytes(&key.public_key.public_key.serialize()).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+
+        // Without its private key, the key cannot be used to sign locally
+        assert!(LocalSigner
+            .sign(&key, message, &AddressType::Cosmos)
+            .is_err());
+    }
+}
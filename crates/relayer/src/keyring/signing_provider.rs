@@ -0,0 +1,36 @@
+use core::fmt::Debug;
+
+use crate::config::AddressType;
+
+use super::errors::Error;
+use super::{sign_message, KeyEntry};
+
+/// Signs the messages of the keys of a chain, e.g. the `SignDoc` of its transactions.
+///
+/// The signatures are made with the private key of the key entries in the keyring
+/// by default, see [`LocalSigner`], or delegated to a service holding the private keys,
+/// see [`RemoteSigner`](super::remote_signer::RemoteSigner).
+pub trait SigningProvider: Debug + Send + Sync {
+    /// Sign the given message with the given key, returning the encoded signature.
+    fn sign(
+        &self,
+        key: &KeyEntry,
+        message: Vec<u8>,
+        address_type: &AddressType,
+    ) -> Result<Vec<u8>, Error>;
+}
+
+/// Signs with the private keys of the keyring.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LocalSigner;
+
+impl SigningProvider for LocalSigner {
+    fn sign(
+        &self,
+        key: &KeyEntry,
+        message: Vec<u8>,
+        address_type: &AddressType,
+    ) -> Result<Vec<u8>, Error> {
+        sign_message(key, message, address_type)
+    }
+}
@@ -21,6 +21,8 @@
 extern crate alloc;
 
 pub mod account;
+pub mod audit;
+pub mod block_time;
 pub mod cache;
 pub mod chain;
 pub mod channel;
@@ -33,6 +35,8 @@ pub mod error;
 pub mod event;
 pub mod extension_options;
 pub mod foreign_client;
+pub mod gov;
+pub mod ha;
 pub mod keyring;
 pub mod light_client;
 pub mod link;
@@ -40,10 +44,16 @@ pub mod macros;
 pub mod misbehaviour;
 pub mod object;
 pub mod path;
+pub mod recover_client;
 pub mod registry;
+pub mod replay;
+pub mod replenish;
 pub mod rest;
+pub mod sdk;
 pub mod sdk_error;
+pub mod snapshot;
 pub mod spawn;
+pub mod stats;
 pub mod supervisor;
 pub mod telemetry;
 pub mod transfer;
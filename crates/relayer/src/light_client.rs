@@ -7,6 +7,7 @@ use core::ops::Deref;
 use ibc::clients::ics07_tendermint::header::{
     decode_header, Header as TendermintHeader, TENDERMINT_HEADER_TYPE_URL,
 };
+use ibc::clients::ics08_wasm::{self, WASM_CLIENT_MESSAGE_TYPE_URL};
 use ibc::core::ics02_client::client_type::ClientType;
 use ibc::core::ics02_client::error::Error;
 use ibc::core::ics02_client::events::UpdateClient;
@@ -140,6 +141,11 @@ impl TryFrom<Any> for AnyHeader {
                     .map_err(Error::invalid_raw_header)?,
             )),
 
+            // The client message of a wasm client wraps a header of the light client of its contract
+            WASM_CLIENT_MESSAGE_TYPE_URL => {
+                AnyHeader::try_from(ics08_wasm::unwrap(raw).map_err(Error::decode_wasm_state)?)
+            }
+
             _ => Err(Error::unknown_header_type(raw.type_url)),
         }
     }
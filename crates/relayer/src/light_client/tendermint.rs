@@ -23,10 +23,13 @@ use ibc::{
     },
     downcast,
 };
-use tracing::trace;
+use tracing::{error, trace};
 
 use crate::{
-    chain::cosmos::CosmosSdkChain, client_state::AnyClientState, config::ChainConfig, error::Error,
+    chain::cosmos::{proxy, CosmosSdkChain},
+    client_state::AnyClientState,
+    config::ChainConfig,
+    error::Error,
     misbehaviour::MisbehaviourEvidence,
 };
 
@@ -36,6 +39,8 @@ pub struct LightClient {
     chain_id: ChainId,
     peer_id: PeerId,
     io: components::io::ProdIo,
+    /// The secondary RPC endpoint the headers are cross-verified against, if any
+    secondary: Option<(rpc::Url, components::io::ProdIo)>,
 }
 
 impl super::LightClient<CosmosSdkChain> for LightClient {
@@ -46,6 +51,11 @@ impl super::LightClient<CosmosSdkChain> for LightClient {
         client_state: &AnyClientState,
     ) -> Result<Verified<TmHeader>, Error> {
         let Verified { target, supporting } = self.verify(trusted, target, client_state)?;
+
+        for block in supporting.iter().chain(core::iter::once(&target)) {
+            self.cross_verify(block)?;
+        }
+
         let (target, supporting) = self.adjust_headers(trusted, target, supporting)?;
         Ok(Verified { target, supporting })
     }
@@ -165,18 +175,73 @@ impl super::LightClient<CosmosSdkChain> for LightClient {
 
 impl LightClient {
     pub fn from_config(config: &ChainConfig, peer_id: PeerId) -> Result<Self, Error> {
-        let rpc_client = rpc::HttpClient::new(config.rpc_addr.clone())
-            .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))?;
+        let rpc_client = proxy::rpc_client(&config.rpc_addr, config.proxy.as_ref())?;
 
         let io = components::io::ProdIo::new(peer_id, rpc_client, Some(config.rpc_timeout));
 
+        // Only the hashes of the headers fetched from the secondary endpoint are looked at,
+        // so the light blocks it provides are attributed to the primary peer.
+        let secondary = match &config.secondary_rpc_addr {
+            Some(addr) => {
+                let rpc_client = proxy::rpc_client(addr, config.proxy.as_ref())?;
+
+                let io = components::io::ProdIo::new(peer_id, rpc_client, Some(config.rpc_timeout));
+
+                Some((addr.clone(), io))
+            }
+            None => None,
+        };
+
         Ok(Self {
             chain_id: config.id.clone(),
             peer_id,
             io,
+            secondary,
         })
     }
 
+    /// Fetch the header of the given light block again from the secondary RPC endpoint,
+    /// if any, and require both hashes to match, so that a header from a node on a fork
+    /// or compromised is never submitted in a client update.
+    fn cross_verify(&self, block: &LightBlock) -> Result<(), Error> {
+        use tendermint_light_client::components::io::Io;
+
+        let (addr, io) = match &self.secondary {
+            Some(secondary) => secondary,
+            None => return Ok(()),
+        };
+
+        let witness = io
+            .fetch_light_block(AtHeight::At(block.height()))
+            .map_err(|e| Error::light_client_io(addr.to_string(), e))?;
+
+        let primary_hash = block.signed_header.header.hash();
+        let secondary_hash = witness.signed_header.header.hash();
+
+        if primary_hash != secondary_hash {
+            error!(
+                height = %block.height(),
+                %primary_hash,
+                %secondary_hash,
+                secondary = %addr,
+                "header differs between the primary and the secondary RPC endpoints, \
+                aborting the client update"
+            );
+
+            crate::telemetry!(header_mismatch, &self.chain_id);
+
+            return Err(Error::header_mismatch(
+                self.chain_id.clone(),
+                block.height().value(),
+                primary_hash.to_string(),
+                addr.to_string(),
+                secondary_hash.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     fn prepare_client(&self, client_state: &AnyClientState) -> Result<TmLightClient, Error> {
         let clock = components::clock::SystemClock;
         let hasher = operations::hasher::ProdHasher;
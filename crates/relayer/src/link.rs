@@ -11,14 +11,20 @@ use crate::channel::{Channel, ChannelSide};
 use crate::link::error::LinkError;
 
 pub mod cli;
+pub mod dry_run;
 pub mod error;
 pub mod operational_data;
+pub mod policy;
 
+mod block_quota;
+mod client_updates;
 mod packet_events;
 mod pending;
+mod rate_limit;
 mod relay_path;
 mod relay_sender;
 mod relay_summary;
+mod routing;
 mod tx_hashes;
 
 use tx_hashes::TxHashes;
@@ -26,6 +32,7 @@ use tx_hashes::TxHashes;
 // Re-export the telemetries summary
 pub use relay_summary::RelaySummary;
 
+pub use pending::{InFlightTxs, InFlightView};
 pub use relay_path::{RelayPath, Resubmit};
 
 #[derive(Clone, Debug)]
@@ -75,8 +82,11 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
                 )
             })?;
 
+        // A channel being upgraded still relays its in-flight packets, which it must
+        // flush before it is reopened
         if !a_channel.state_matches(&ChannelState::Open)
             && !a_channel.state_matches(&ChannelState::Closed)
+            && !a_channel.is_upgrading()
         {
             return Err(LinkError::invalid_channel_state(
                 a_channel_id.clone(),
@@ -0,0 +1,62 @@
+//! Quota of the packets received on the destination chain of a path in each of its
+//! blocks, see [`PacketLimit`](crate::config::packet_limit::PacketLimit).
+//!
+//! The packets are counted against the latest height of the destination chain when
+//! their messages are submitted, the ones beyond the quota of a height being held
+//! until the chain reaches the next one.
+
+use ibc::Height;
+
+#[derive(Debug)]
+pub struct BlockQuota {
+    max_packets: u64,
+    /// The height the packets submitted are counted against, and how many were
+    height: Option<Height>,
+    used: u64,
+}
+
+impl BlockQuota {
+    pub fn new(max_packets: u64) -> Self {
+        Self {
+            max_packets,
+            height: None,
+            used: 0,
+        }
+    }
+
+    /// How many more packets can be submitted at the given latest height.
+    pub fn remaining(&mut self, height: Height) -> u64 {
+        if self.height != Some(height) {
+            self.height = Some(height);
+            self.used = 0;
+        }
+
+        self.max_packets.saturating_sub(self.used)
+    }
+
+    /// Count the given number of packets as submitted at the last height checked.
+    pub fn consume(&mut self, packets: u64) {
+        self.used = self.used.saturating_add(packets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_resets_every_block() {
+        let mut quota = BlockQuota::new(10);
+
+        let height = Height::new(0, 100).unwrap();
+        assert_eq!(quota.remaining(height), 10);
+
+        quota.consume(7);
+        assert_eq!(quota.remaining(height), 3);
+
+        quota.consume(3);
+        assert_eq!(quota.remaining(height), 0);
+
+        assert_eq!(quota.remaining(height.increment()), 10);
+    }
+}
@@ -10,6 +10,7 @@ use ibc::Height;
 
 use crate::chain::counterparty::{unreceived_acknowledgements, unreceived_packets};
 use crate::chain::handle::ChainHandle;
+use crate::chain::requests::QueryHeightRange;
 use crate::chain::tracking::TrackingId;
 use crate::event::IbcEventWithHeight;
 use crate::link::error::LinkError;
@@ -70,10 +71,21 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
     pub fn relay_recv_packet_and_timeout_messages(&self) -> Result<Vec<IbcEvent>, LinkError> {
         self.relay_recv_packet_and_timeout_messages_with_packet_data_query_height(None)
     }
+
     /// Implements the `packet-recv` CLI
     pub fn relay_recv_packet_and_timeout_messages_with_packet_data_query_height(
         &self,
         packet_data_query_height: Option<Height>,
+    ) -> Result<Vec<IbcEvent>, LinkError> {
+        self.relay_recv_packet_and_timeout_messages_from(packet_data_query_height, None)
+    }
+
+    /// Relays the unreceived packets, only scanning the blocks of the source chain from
+    /// the given height up to the query height for their events, if any.
+    pub fn relay_recv_packet_and_timeout_messages_from(
+        &self,
+        packet_data_query_height: Option<Height>,
+        scan_from: Option<Height>,
     ) -> Result<Vec<IbcEvent>, LinkError> {
         let _span = error_span!(
             "PacketRecvCmd",
@@ -106,6 +118,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
             sequences,
             src_response_height,
             packet_data_query_height,
+            scan_from,
             query_send_packet_events,
             TrackingId::new_static("packet-recv"),
         )
@@ -113,6 +126,15 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
 
     /// Implements the `packet-ack` CLI
     pub fn relay_ack_packet_messages(&self) -> Result<Vec<IbcEvent>, LinkError> {
+        self.relay_ack_packet_messages_from(None)
+    }
+
+    /// Relays the unreceived acknowledgements, only scanning the blocks of the source chain
+    /// from the given height up to the query height for their events, if any.
+    pub fn relay_ack_packet_messages_from(
+        &self,
+        scan_from: Option<Height>,
+    ) -> Result<Vec<IbcEvent>, LinkError> {
         let _span = error_span!(
             "PacketAckCmd",
             src_chain = %self.a_to_b.src_chain().id(),
@@ -143,6 +165,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
             sequences,
             src_response_height,
             Some(src_response_height),
+            scan_from,
             query_write_ack_events,
             TrackingId::new_static("packet-ack"),
         )
@@ -153,11 +176,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
         sequences: Vec<Sequence>,
         src_response_height: Height,
         packet_data_query_height: Option<Height>,
+        scan_from: Option<Height>,
         query_fn: impl Fn(
             &ChainA,
             &PathIdentifiers,
             Vec<Sequence>,
             Height,
+            Option<QueryHeightRange>,
         ) -> Result<Vec<IbcEvent>, LinkError>,
         tracking_id: TrackingId,
     ) -> Result<Vec<IbcEvent>, LinkError> {
@@ -167,9 +192,16 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
             Some(height) => height,
             None => src_response_height,
         };
+
+        let height_range = scan_from.map(|start| QueryHeightRange {
+            start,
+            end: solved_query_height,
+        });
+
         for events_chunk in query_packet_events_with(
             &sequences,
             solved_query_height,
+            height_range,
             self.a_to_b.src_chain(),
             &self.a_to_b.path_id,
             query_fn,
@@ -0,0 +1,378 @@
+//! Coordination of the updates of a client shared by several paths, e.g. the
+//! channels over the same connection, so that the client is updated once for a
+//! given height instead of each packet worker racing to update it independently.
+//!
+//! The first path which needs the update at a given height claims it, and prepends
+//! the update to its batch of messages. The other paths needing the update at that
+//! height hold off on their messages while the update is in flight, i.e. until the
+//! transaction carrying it is committed, and then submit them without the update.
+//! As their transactions are signed by the same key on the same chain, they are
+//! included after the transaction updating the client.
+//!
+//! The paths never wait for each other: a path whose update is in flight for another
+//! path leaves its messages in its schedule until the next time it is executed.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+use tendermint::abci::transaction;
+use tracing::{debug, warn};
+
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc::Height;
+
+/// How long the update of a client claimed by a path is assumed to be in flight,
+/// from when it was claimed or its transaction passed `CheckTx`, after which the
+/// other paths needing it submit the update themselves.
+const MAX_IN_FLIGHT: Duration = Duration::from_secs(60);
+
+/// How long the update of a client committed at a given height is remembered, after
+/// which the paths needing it build it again. As building a client update yields no
+/// message if the client was already updated at the requested height, this only needs
+/// to cover the time for the paths relying on it to submit their messages.
+const COMMITTED_TTL: Duration = Duration::from_secs(60);
+
+static CLIENT_UPDATES: Lazy<ClientUpdates> = Lazy::new(ClientUpdates::default);
+
+/// The coordinator of the client updates shared by all the paths in the process.
+pub fn client_updates() -> &'static ClientUpdates {
+    &CLIENT_UPDATES
+}
+
+type Key = (ChainId, ClientId);
+
+#[derive(Debug)]
+struct InFlight {
+    /// Tells apart the successive claims of the update at the same height
+    id: u64,
+    height: Height,
+    since: Instant,
+    /// The transaction carrying the update, once it passed `CheckTx`
+    tx: Option<transaction::Hash>,
+}
+
+#[derive(Debug, Default)]
+struct UpdateState {
+    /// The updates claimed by some path, being submitted or not committed yet
+    in_flight: Vec<InFlight>,
+    /// The heights at which an update was committed, and when
+    committed: Vec<(Height, Instant)>,
+}
+
+impl UpdateState {
+    fn prune(&mut self, now: Instant) {
+        self.in_flight
+            .retain(|update| now.duration_since(update.since) < MAX_IN_FLIGHT);
+        self.committed
+            .retain(|(_, at)| now.duration_since(*at) < COMMITTED_TTL);
+    }
+
+    fn is_committed(&self, height: Height) -> bool {
+        self.committed.iter().any(|(h, _)| *h == height)
+    }
+
+    fn is_in_flight(&self, height: Height) -> bool {
+        self.in_flight.iter().any(|update| update.height == height)
+    }
+
+    fn is_unused(&self) -> bool {
+        self.in_flight.is_empty() && self.committed.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ClientUpdates {
+    states: Mutex<HashMap<Key, UpdateState>>,
+    next_id: AtomicU64,
+}
+
+/// Whether a path must include the update of a client in its batch of messages.
+#[derive(Debug)]
+pub enum ClientUpdateTicket<'a> {
+    /// The path must build and submit the update
+    Update(ClientUpdateClaim<'a>),
+    /// The update is in flight for another path
+    InFlight,
+    /// The update was already committed for another path
+    Skip,
+}
+
+impl ClientUpdates {
+    /// Claim the update of the given client at the given height, unless another
+    /// path already claimed it and it is still in flight, or it was committed.
+    pub fn claim(
+        &self,
+        chain_id: &ChainId,
+        client_id: &ClientId,
+        height: Height,
+    ) -> ClientUpdateTicket<'_> {
+        let key = (chain_id.clone(), client_id.clone());
+        let now = Instant::now();
+
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(key.clone()).or_default();
+        state.prune(now);
+
+        if state.is_committed(height) {
+            debug!(
+                chain = %chain_id, client = %client_id, %height,
+                "client update was already committed for another path"
+            );
+
+            return ClientUpdateTicket::Skip;
+        }
+
+        if state.is_in_flight(height) {
+            return ClientUpdateTicket::InFlight;
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        state.in_flight.push(InFlight {
+            id,
+            height,
+            since: now,
+            tx: None,
+        });
+
+        ClientUpdateTicket::Update(ClientUpdateClaim {
+            updates: self,
+            key,
+            id,
+            height,
+            resolved: false,
+        })
+    }
+
+    /// Whether the update of the given client at the given height is in flight for some path.
+    pub fn is_in_flight(&self, chain_id: &ChainId, client_id: &ClientId, height: Height) -> bool {
+        let key = (chain_id.clone(), client_id.clone());
+        let mut states = self.states.lock().unwrap();
+
+        states.get_mut(&key).map_or(false, |state| {
+            state.prune(Instant::now());
+            state.is_in_flight(height)
+        })
+    }
+
+    /// Record the outcome of the given transaction, once it is either committed
+    /// or given up on, resolving the client update it carries, if any.
+    pub fn confirmed(&self, tx: &transaction::Hash, committed: bool) {
+        let mut states = self.states.lock().unwrap();
+        let now = Instant::now();
+
+        for ((chain_id, client_id), state) in states.iter_mut() {
+            let (resolved, in_flight): (Vec<_>, Vec<_>) = state
+                .in_flight
+                .drain(..)
+                .partition(|update| update.tx.as_ref() == Some(tx));
+
+            state.in_flight = in_flight;
+
+            for update in resolved {
+                if committed {
+                    state.committed.push((update.height, now));
+                } else {
+                    warn!(
+                        chain = %chain_id, client = %client_id, height = %update.height,
+                        "client update was not committed, the paths needing it will submit it"
+                    );
+                }
+            }
+        }
+
+        states.retain(|_, state| !state.is_unused());
+    }
+
+    /// Forget that the update of the given client at the given height was committed,
+    /// e.g. because the messages relying on it failed, so that it is built again.
+    pub fn forget(&self, chain_id: &ChainId, client_id: &ClientId, height: Height) {
+        let key = (chain_id.clone(), client_id.clone());
+        let mut states = self.states.lock().unwrap();
+
+        if let Some(state) = states.get_mut(&key) {
+            state.committed.retain(|(h, _)| *h != height);
+
+            if state.is_unused() {
+                states.remove(&key);
+            }
+        }
+    }
+
+    fn resolve(&self, key: &Key, id: u64, outcome: ClaimOutcome) {
+        let mut states = self.states.lock().unwrap();
+        let now = Instant::now();
+        let state = states.entry(key.clone()).or_default();
+
+        match outcome {
+            ClaimOutcome::GivenUp => state.in_flight.retain(|update| update.id != id),
+            ClaimOutcome::Submitted(tx) => {
+                if let Some(update) = state.in_flight.iter_mut().find(|update| update.id == id) {
+                    update.tx = Some(tx);
+                    update.since = now;
+                }
+            }
+            ClaimOutcome::Committed(height) => {
+                state.in_flight.retain(|update| update.id != id);
+                state.committed.push((height, now));
+            }
+        }
+
+        state.prune(now);
+
+        if state.is_unused() {
+            states.remove(key);
+        }
+    }
+}
+
+enum ClaimOutcome {
+    GivenUp,
+    Submitted(transaction::Hash),
+    Committed(Height),
+}
+
+/// The claim of a path on the update of a client at a given height,
+/// given up on when dropped unless the update was submitted.
+#[derive(Debug)]
+pub struct ClientUpdateClaim<'a> {
+    updates: &'a ClientUpdates,
+    key: Key,
+    id: u64,
+    height: Height,
+    resolved: bool,
+}
+
+impl ClientUpdateClaim<'_> {
+    /// Record that the update passed `CheckTx` in the given transaction, for the
+    /// other paths to hold off until it is confirmed, see [`ClientUpdates::confirmed`].
+    pub fn submitted(mut self, tx: transaction::Hash) {
+        self.resolved = true;
+        self.updates
+            .resolve(&self.key, self.id, ClaimOutcome::Submitted(tx));
+    }
+
+    /// Record that the update was committed, for the other paths to skip it.
+    pub fn committed(mut self) {
+        self.resolved = true;
+        self.updates
+            .resolve(&self.key, self.id, ClaimOutcome::Committed(self.height));
+    }
+}
+
+impl Drop for ClientUpdateClaim<'_> {
+    fn drop(&mut self) {
+        if !self.resolved {
+            self.updates
+                .resolve(&self.key, self.id, ClaimOutcome::GivenUp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics02_client::client_type::ClientType;
+
+    fn ids() -> (ChainId, ClientId) {
+        (
+            ChainId::new("ibc".to_string(), 0),
+            ClientId::new(ClientType::Tendermint, 0).unwrap(),
+        )
+    }
+
+    fn height(h: u64) -> Height {
+        Height::new(0, h).unwrap()
+    }
+
+    fn tx(byte: u8) -> transaction::Hash {
+        transaction::Hash::new([byte; 32])
+    }
+
+    #[test]
+    fn skip_committed_update() {
+        let (chain_id, client_id) = ids();
+        let updates = ClientUpdates::default();
+
+        match updates.claim(&chain_id, &client_id, height(10)) {
+            ClientUpdateTicket::Update(claim) => claim.committed(),
+            _ => panic!("update at height 10 should be claimed"),
+        }
+
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(10)),
+            ClientUpdateTicket::Skip
+        ));
+
+        // An update at another height is claimed independently
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(11)),
+            ClientUpdateTicket::Update(_)
+        ));
+
+        updates.forget(&chain_id, &client_id, height(10));
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(10)),
+            ClientUpdateTicket::Update(_)
+        ));
+    }
+
+    #[test]
+    fn claim_again_when_given_up() {
+        let (chain_id, client_id) = ids();
+        let updates = ClientUpdates::default();
+
+        // Dropped without being submitted, eg. because the transaction failed
+        drop(updates.claim(&chain_id, &client_id, height(10)));
+
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(10)),
+            ClientUpdateTicket::Update(_)
+        ));
+
+        assert!(updates.states.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn hold_off_until_confirmed() {
+        let (chain_id, client_id) = ids();
+        let updates = ClientUpdates::default();
+
+        let claim = match updates.claim(&chain_id, &client_id, height(10)) {
+            ClientUpdateTicket::Update(claim) => claim,
+            _ => panic!("update at height 10 should be claimed"),
+        };
+
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(10)),
+            ClientUpdateTicket::InFlight
+        ));
+
+        // Passing `CheckTx` is not enough for the other paths to skip the update
+        claim.submitted(tx(1));
+        assert!(updates.is_in_flight(&chain_id, &client_id, height(10)));
+
+        updates.confirmed(&tx(1), true);
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(10)),
+            ClientUpdateTicket::Skip
+        ));
+
+        // An update whose transaction fails is claimed again
+        match updates.claim(&chain_id, &client_id, height(11)) {
+            ClientUpdateTicket::Update(claim) => claim.submitted(tx(2)),
+            _ => panic!("update at height 11 should be claimed"),
+        }
+
+        updates.confirmed(&tx(2), false);
+        assert!(matches!(
+            updates.claim(&chain_id, &client_id, height(11)),
+            ClientUpdateTicket::Update(_)
+        ));
+    }
+}
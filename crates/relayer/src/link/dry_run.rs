@@ -0,0 +1,207 @@
+//! Dry runs of a link, which build the messages relaying all the pending packets,
+//! acknowledgements and timeouts of its path, and simulate them on the chains they
+//! target instead of submitting them.
+
+use serde::Serialize;
+use tracing::{error_span, info};
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::events::IbcEvent;
+use ibc::Height;
+
+use crate::chain::counterparty::{unreceived_acknowledgements, unreceived_packets};
+use crate::chain::endpoint::SimulatedTx;
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::QueryHeightRange;
+use crate::chain::tracking::TrackingId;
+use crate::event::IbcEventWithHeight;
+use crate::link::error::LinkError;
+use crate::link::operational_data::{OperationalData, OperationalDataTarget, TrackedEvents};
+use crate::link::packet_events::{
+    query_packet_events_with, query_send_packet_events, query_write_ack_events,
+};
+use crate::link::Link;
+use crate::path::PathIdentifiers;
+use crate::util::pretty::PrettySlice;
+
+/// The messages a link would submit to relay the pending packets of its path at the
+/// time of the dry run, and the outcome of their simulation, batch by batch.
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunReport {
+    pub src_chain: ChainId,
+    pub dst_chain: ChainId,
+    pub batches: Vec<DryRunBatch>,
+}
+
+/// A batch of messages which would be submitted to the given chain in one go, and the
+/// transactions it would be split into, with their expected gas, fee and failures.
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunBatch {
+    pub chain: ChainId,
+    pub messages: Vec<DryRunMessage>,
+    pub simulations: Vec<SimulatedTx>,
+}
+
+/// A message of a batch, along with the event it relays, or none for the client updates.
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunMessage {
+    pub type_url: String,
+    pub event: Option<IbcEvent>,
+}
+
+impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
+    /// Build the messages relaying the unreceived packets and acknowledgements of the
+    /// path, and the timeouts of its expired packets, and simulate them without
+    /// broadcasting anything.
+    ///
+    /// All the messages are simulated against the current state of the chains, so that
+    /// those depending on messages of an earlier batch, or on a client update which is
+    /// only submitted once the connection delay elapsed, may be reported as failing.
+    pub fn dry_run(&self) -> Result<DryRunReport, LinkError> {
+        let _span = error_span!(
+            "DryRun",
+            src_chain = %self.a_to_b.src_chain().id(),
+            src_port = %self.a_to_b.src_port_id(),
+            src_channel = %self.a_to_b.src_channel_id(),
+            dst_chain = %self.a_to_b.dst_chain().id(),
+        )
+        .entered();
+
+        let mut batches = vec![];
+
+        let (sequences, src_response_height) = unreceived_packets(
+            self.a_to_b.dst_chain(),
+            self.a_to_b.src_chain(),
+            &self.a_to_b.path_id,
+        )
+        .map_err(LinkError::supervisor)?;
+
+        info!(
+            "{} unreceived packets found: {} ",
+            sequences.len(),
+            PrettySlice(&sequences)
+        );
+
+        self.dry_run_packet_messages(
+            &sequences,
+            src_response_height,
+            query_send_packet_events,
+            &mut batches,
+        )?;
+
+        let (sequences, src_response_height) = unreceived_acknowledgements(
+            self.a_to_b.dst_chain(),
+            self.a_to_b.src_chain(),
+            &self.a_to_b.path_id,
+        )
+        .map_err(LinkError::supervisor)?;
+
+        info!(
+            "{} unreceived acknowledgements found: {} ",
+            sequences.len(),
+            PrettySlice(&sequences)
+        );
+
+        self.dry_run_packet_messages(
+            &sequences,
+            src_response_height,
+            query_write_ack_events,
+            &mut batches,
+        )?;
+
+        Ok(DryRunReport {
+            src_chain: self.a_to_b.src_chain().id(),
+            dst_chain: self.a_to_b.dst_chain().id(),
+            batches,
+        })
+    }
+
+    fn dry_run_packet_messages(
+        &self,
+        sequences: &[Sequence],
+        src_response_height: Height,
+        query_fn: impl Fn(
+            &ChainA,
+            &PathIdentifiers,
+            Vec<Sequence>,
+            Height,
+            Option<QueryHeightRange>,
+        ) -> Result<Vec<IbcEvent>, LinkError>,
+        batches: &mut Vec<DryRunBatch>,
+    ) -> Result<(), LinkError> {
+        if sequences.is_empty() {
+            return Ok(());
+        }
+
+        for events_chunk in query_packet_events_with(
+            sequences,
+            src_response_height,
+            None,
+            self.a_to_b.src_chain(),
+            &self.a_to_b.path_id,
+            query_fn,
+        ) {
+            let events_chunk = events_chunk
+                .iter()
+                .map(|e| IbcEventWithHeight::new(e.event.clone(), src_response_height))
+                .collect();
+
+            let tracked_events =
+                TrackedEvents::new(events_chunk, TrackingId::new_static("dry-run"));
+            self.a_to_b.events_to_operational_data(tracked_events)?;
+        }
+
+        // The operational data are simulated right away, without waiting for the connection delay
+        let src_ods = self.a_to_b.src_operational_data.take();
+        let dst_ods = self.a_to_b.dst_operational_data.take();
+
+        for od in src_ods.into_iter().chain(dst_ods) {
+            batches.push(self.dry_run_operational_data(od)?);
+        }
+
+        Ok(())
+    }
+
+    fn dry_run_operational_data(&self, od: OperationalData) -> Result<DryRunBatch, LinkError> {
+        let tracked_msgs = od.assemble_msgs(&self.a_to_b, true)?;
+
+        // The client update, if any, is prepended to the messages of the batch
+        let client_updates = tracked_msgs.msgs.len().saturating_sub(od.batch.len());
+
+        let messages = tracked_msgs
+            .msgs
+            .iter()
+            .enumerate()
+            .map(|(i, msg)| DryRunMessage {
+                type_url: msg.type_url.clone(),
+                event: i
+                    .checked_sub(client_updates)
+                    .map(|i| od.batch[i].event_with_height.event.clone()),
+            })
+            .collect();
+
+        let (chain, simulations) = match od.target {
+            OperationalDataTarget::Source => (
+                self.a_to_b.src_chain().id(),
+                self.a_to_b
+                    .src_chain()
+                    .simulate_messages(tracked_msgs)
+                    .map_err(LinkError::relayer)?,
+            ),
+            OperationalDataTarget::Destination => (
+                self.a_to_b.dst_chain().id(),
+                self.a_to_b
+                    .dst_chain()
+                    .simulate_messages(tracked_msgs)
+                    .map_err(LinkError::relayer)?,
+            ),
+        };
+
+        Ok(DryRunBatch {
+            chain,
+            messages,
+            simulations,
+        })
+    }
+}
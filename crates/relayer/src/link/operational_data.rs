@@ -152,13 +152,23 @@ impl OperationalData {
 
     /// Returns all the messages in this operational
     /// data, plus prepending the client update message
-    /// if necessary.
+    /// if necessary, unless `with_client_update` is false because
+    /// the update was already submitted on behalf of another path.
     pub fn assemble_msgs<ChainA: ChainHandle, ChainB: ChainHandle>(
         &self,
         relay_path: &RelayPath<ChainA, ChainB>,
+        with_client_update: bool,
     ) -> Result<TrackedMsgs, LinkError> {
         // For zero delay we prepend the client update msgs.
-        let client_update_msg = if !self.conn_delay_needed() {
+        let client_update_msg = if !self.conn_delay_needed() && !with_client_update {
+            debug!(
+                "not prepending {} client update at height {}, already submitted",
+                self.target,
+                self.proofs_height.increment()
+            );
+
+            None
+        } else if !self.conn_delay_needed() {
             let update_height = self.proofs_height.increment();
 
             debug!(
@@ -8,7 +8,7 @@ use ibc::Height;
 
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::{
-    QueryBlockRequest, QueryHeight, QueryPacketEventDataRequest, QueryTxRequest,
+    QueryBlockRequest, QueryHeight, QueryHeightRange, QueryPacketEventDataRequest, QueryTxRequest,
 };
 use crate::event::IbcEventWithHeight;
 use crate::link::error::LinkError;
@@ -18,13 +18,21 @@ use crate::util::pretty::PrettySlice;
 /// Limit on how many query results should be expected.
 pub const QUERY_RESULT_LIMIT: usize = 50;
 
-/// Returns an iterator on batches of packet events.
+/// Returns an iterator on batches of packet events, only scanning the blocks within
+/// the given range of heights for them if any.
 pub fn query_packet_events_with<'a, ChainA>(
     sequence_nrs: &'a [Sequence],
     query_height: Height,
+    height_range: Option<QueryHeightRange>,
     src_chain: &'a ChainA,
     path: &'a PathIdentifiers,
-    query_fn: impl Fn(&ChainA, &PathIdentifiers, Vec<Sequence>, Height) -> Result<Vec<IbcEvent>, LinkError>
+    query_fn: impl Fn(
+            &ChainA,
+            &PathIdentifiers,
+            Vec<Sequence>,
+            Height,
+            Option<QueryHeightRange>,
+        ) -> Result<Vec<IbcEvent>, LinkError>
         + 'a,
 ) -> impl Iterator<Item = Vec<IbcEventWithHeight>> + 'a
 where
@@ -37,7 +45,7 @@ where
         .chunks(QUERY_RESULT_LIMIT)
         .map_while(move |chunk| {
             let sequences_nrs_chunk = chunk.to_vec();
-            match query_fn(src_chain, path, sequences_nrs_chunk, query_height) {
+            match query_fn(src_chain, path, sequences_nrs_chunk, query_height, height_range) {
                 Ok(events) => {
                     events_left_count -= chunk.len();
                     info!(events_total = %events_total_count, events_left = %events_left_count, "pulled packet data for {} events: {};", events.len(), PrettySlice(chunk));
@@ -59,6 +67,7 @@ pub fn query_send_packet_events<ChainA: ChainHandle>(
     path: &PathIdentifiers,
     sequences: Vec<Sequence>,
     src_query_height: Height,
+    height_range: Option<QueryHeightRange>,
 ) -> Result<Vec<IbcEvent>, LinkError> {
     let mut events_result = vec![];
     let _span = span!(Level::DEBUG, "query_send_packet_events", h = %src_query_height).entered();
@@ -71,6 +80,7 @@ pub fn query_send_packet_events<ChainA: ChainHandle>(
         destination_channel_id: path.channel_id.clone(),
         sequences,
         height: QueryHeight::Specific(src_query_height),
+        height_range,
     };
 
     let tx_events: Vec<IbcEvent> = src_chain
@@ -114,24 +124,63 @@ pub fn query_send_packet_events<ChainA: ChainHandle>(
 
 /// Returns packet event data for building ack messages for the
 /// given list of [`Sequence`] numbers.
+///
+/// Applications which acknowledge packets asynchronously write the acknowledgement
+/// in a later transaction or in a `BeginBlock`/`EndBlock` handler, so the block
+/// events are searched as well for the acknowledgements not found in transactions.
 pub fn query_write_ack_events<ChainA: ChainHandle>(
     src_chain: &ChainA,
     path: &PathIdentifiers,
     sequences: Vec<Sequence>,
     src_query_height: Height,
+    height_range: Option<QueryHeightRange>,
 ) -> Result<Vec<IbcEvent>, LinkError> {
+    let _span = span!(Level::DEBUG, "query_write_ack_events", h = %src_query_height).entered();
+
+    let mut query = QueryPacketEventDataRequest {
+        event_id: WithBlockDataType::WriteAck,
+        source_port_id: path.port_id.clone(),
+        source_channel_id: path.channel_id.clone(),
+        destination_port_id: path.counterparty_port_id.clone(),
+        destination_channel_id: path.counterparty_channel_id.clone(),
+        sequences,
+        height: QueryHeight::Specific(src_query_height),
+        height_range,
+    };
+
     // TODO(Adi): Would be good to make use of generics.
-    let events_result = src_chain
-        .query_txs(QueryTxRequest::Packet(QueryPacketEventDataRequest {
-            event_id: WithBlockDataType::WriteAck,
-            source_port_id: path.port_id.clone(),
-            source_channel_id: path.channel_id.clone(),
-            destination_port_id: path.counterparty_port_id.clone(),
-            destination_channel_id: path.counterparty_channel_id.clone(),
-            sequences,
-            height: QueryHeight::Specific(src_query_height),
-        }))
-        .map_err(|e| LinkError::query(src_chain.id(), e))?;
-
-    Ok(events_result.into_iter().map(|ev| ev.event).collect())
+    let tx_events: Vec<IbcEvent> = src_chain
+        .query_txs(QueryTxRequest::Packet(query.clone()))
+        .map_err(|e| LinkError::query(src_chain.id(), e))?
+        .into_iter()
+        .map(|ev| ev.event)
+        .collect();
+
+    let acked_sequences: Vec<Sequence> = tx_events
+        .iter()
+        .filter_map(|ev| match ev {
+            IbcEvent::WriteAcknowledgement(ref ack_ev) => Some(ack_ev.packet.sequence),
+            _ => None,
+        })
+        .collect();
+
+    query.sequences.retain(|seq| !acked_sequences.contains(seq));
+
+    let (start_block_events, end_block_events) = if !query.sequences.is_empty() {
+        src_chain
+            .query_blocks(QueryBlockRequest::Packet(query))
+            .map_err(|e| LinkError::query(src_chain.id(), e))?
+    } else {
+        Default::default()
+    };
+
+    trace!("start_block_events {:?}", start_block_events);
+    trace!("tx_events {:?}", tx_events);
+    trace!("end_block_events {:?}", end_block_events);
+
+    let mut events_result = start_block_events;
+    events_result.extend(tx_events);
+    events_result.extend(end_block_events);
+
+    Ok(events_result)
 }
@@ -1,7 +1,9 @@
 use core::iter::Iterator;
 use core::time::Duration;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, trace, trace_span};
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
@@ -10,8 +12,11 @@ use ibc::events::IbcEvent;
 use crate::chain::requests::{QueryTxHash, QueryTxRequest};
 use crate::chain::tracking::TrackingId;
 use crate::error::Error as RelayerError;
+use crate::link::client_updates::client_updates;
 use crate::link::{error::LinkError, RelayPath};
+use crate::stats::{self, SubmissionEntry};
 use crate::telemetry;
+use crate::util::lock::LockExt;
 use crate::util::queue::Queue;
 use crate::{
     chain::handle::ChainHandle,
@@ -41,6 +46,15 @@ impl PendingData {
     }
 }
 
+/// The transactions of a pending operational data,
+/// submitted to the given chain but not confirmed yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightTxs {
+    pub chain_id: ChainId,
+    pub tracking_id: String,
+    pub tx_hashes: Vec<String>,
+}
+
 /// Stores all pending data
 /// and tries to confirm them asynchronously.
 pub struct PendingTxs<Chain> {
@@ -49,6 +63,8 @@ pub struct PendingTxs<Chain> {
     pub port_id: PortId,
     pub counterparty_chain_id: ChainId,
     pub pending_queue: Queue<PendingData>,
+    /// The pending transaction taken out of the queue while its confirmation is checked
+    checking: Arc<RwLock<Option<InFlightTxs>>>,
 }
 
 impl<Chain> PendingTxs<Chain> {
@@ -64,7 +80,65 @@ impl<Chain> PendingTxs<Chain> {
             port_id,
             counterparty_chain_id,
             pending_queue: Queue::new(),
+            checking: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+/// The transactions of a [`PendingTxs`] which are not confirmed yet, which can be
+/// listed from another thread without holding the link relaying them.
+#[derive(Clone)]
+pub struct InFlightView {
+    chain_id: ChainId,
+    pending_queue: Queue<PendingData>,
+    checking: Arc<RwLock<Option<InFlightTxs>>>,
+}
+
+impl InFlightView {
+    /// The transactions which are still waiting to be confirmed.
+    pub fn txs(&self) -> Vec<InFlightTxs> {
+        // The pending transaction being checked leaves the queue while this lock is
+        // held, and is only cleared once it is back in the queue or resolved
+        let checking = self.checking.acquire_read();
+
+        let mut txs: Vec<InFlightTxs> = self
+            .pending_queue
+            .clone_vec()
+            .iter()
+            .map(|pending| InFlightTxs::new(&self.chain_id, pending))
+            .collect();
+
+        if let Some(checked) = &*checking {
+            if !txs.iter().any(|tx| tx.tracking_id == checked.tracking_id) {
+                txs.insert(0, checked.clone());
+            }
         }
+
+        txs
+    }
+}
+
+impl InFlightTxs {
+    fn new(chain_id: &ChainId, pending: &PendingData) -> Self {
+        Self {
+            chain_id: chain_id.clone(),
+            tracking_id: pending.tracking_id().to_string(),
+            tx_hashes: pending
+                .tx_hashes
+                .0
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Clears the pending transaction being checked when dropped.
+struct Checked<'a>(&'a RwLock<Option<InFlightTxs>>);
+
+impl Drop for Checked<'_> {
+    fn drop(&mut self) {
+        *self.0.acquire_write() = None;
     }
 }
 
@@ -73,6 +147,15 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         self.chain.id()
     }
 
+    /// The view of the transactions which are still waiting to be confirmed.
+    pub fn in_flight(&self) -> InFlightView {
+        InFlightView {
+            chain_id: self.chain_id(),
+            pending_queue: self.pending_queue.clone(),
+            checking: self.checking.clone(),
+        }
+    }
+
     /// Insert a new pending transaction to the back of the queue.
     pub fn insert_new_pending_tx(&self, r: AsyncReply, od: OperationalData) {
         let mut tx_hashes = Vec::new();
@@ -142,6 +225,52 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         Ok(Some(all_events.into_iter().map(|ev| ev.event).collect()))
     }
 
+    /// Log the outcome of a pending transaction in the submission log, given the
+    /// events of its confirmed transactions, or `None` if they timed out.
+    fn record_submission(&self, pending: &PendingData, confirmed_events: Option<&[IbcEvent]>) {
+        if !stats::is_recording() {
+            return;
+        }
+
+        let txs = pending.tx_hashes.0.len() + pending.error_events.len();
+
+        let (failed_txs, packets) = match confirmed_events {
+            Some(events) => (
+                pending.error_events.len()
+                    + events
+                        .iter()
+                        .filter(|event| matches!(event, IbcEvent::ChainError(_)))
+                        .count(),
+                events
+                    .iter()
+                    .filter(|event| {
+                        matches!(
+                            event,
+                            IbcEvent::ReceivePacket(_)
+                                | IbcEvent::AcknowledgePacket(_)
+                                | IbcEvent::TimeoutPacket(_)
+                                | IbcEvent::TimeoutOnClosePacket(_)
+                        )
+                    })
+                    .count(),
+            ),
+            None => (txs, 0),
+        };
+
+        stats::record(&SubmissionEntry {
+            time: stats::now(),
+            chain_id: self.chain_id(),
+            counterparty_chain_id: self.counterparty_chain_id.clone(),
+            port_id: self.port_id.clone(),
+            channel_id: self.channel_id.clone(),
+            txs,
+            failed_txs,
+            packets,
+            latency_ms: pending.submit_time.elapsed().as_millis() as u64,
+            gas_used: stats::take_gas_used(&pending.tx_hashes.0),
+        });
+    }
+
     /// Try and process one pending transaction within the given timeout duration if one
     /// is available.
     ///
@@ -155,12 +284,26 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         resubmit: Option<impl FnOnce(OperationalData) -> Result<AsyncReply, LinkError>>,
     ) -> Result<Option<RelaySummary>, LinkError> {
         // We process pending transactions in a FIFO manner, so take from
-        // the front of the queue.
-        if let Some(pending) = self.pending_queue.pop_front() {
+        // the front of the queue. It is listed as in flight until checked.
+        let chain_id = self.chain_id();
+        let pending = {
+            let mut checking = self.checking.acquire_write();
+            let pending = self.pending_queue.pop_front();
+            *checking = pending
+                .as_ref()
+                .map(|pending| InFlightTxs::new(&chain_id, pending));
+            pending
+        };
+
+        let _checked = Checked(&self.checking);
+
+        if let Some(pending) = pending {
             let tx_hashes = &pending.tx_hashes;
             let submit_time = &pending.submit_time;
 
             if tx_hashes.0.is_empty() {
+                self.record_submission(&pending, Some(&[]));
+
                 return Ok(Some(RelaySummary::from_events(pending.error_events)));
             }
 
@@ -191,6 +334,9 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         // relayer to resubmit the transaction to the chain again.
                         error!("timed out while confirming {}", tx_hashes);
 
+                        self.record_submission(&pending, None);
+                        client_updates().confirmed(&tx_hashes.0[0], false);
+
                         match resubmit {
                             Some(f) => {
                                 // The pending tx needs to be resubmitted. This involves replacing the tx's
@@ -251,6 +397,14 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         &self.counterparty_chain_id
                     );
 
+                    self.record_submission(&pending, Some(&events));
+
+                    // The first transaction carries the client update prepended to the messages, if any
+                    let committed = !events
+                        .iter()
+                        .any(|ev| matches!(ev, IbcEvent::ChainError(_)));
+                    client_updates().confirmed(&tx_hashes.0[0], committed);
+
                     // Append the events corresponding to errors from the pending tx.
                     events.extend(pending.error_events);
 
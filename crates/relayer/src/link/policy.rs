@@ -0,0 +1,434 @@
+//! Policies inspecting the data of the packets before they are relayed, and deciding
+//! whether to relay or deny them, e.g. to enforce compliance rules such as blocklists
+//! of addresses or tokens without forking the workers.
+//!
+//! Policies are compiled in: a binary embedding the relayer registers its policies
+//! with [`register`] before spawning the supervisor. Alternatively, the policy can be
+//! delegated to an external HTTP service with the `[packet_policy]` section of the
+//! configuration, see [`HttpPolicy`]. Each policy which [applies to](PacketPolicy::applies_to)
+//! the path of a packet is then asked about it before its `MsgRecvPacket` is built.
+//!
+//! A denied packet is not received on the destination chain, and its timeout is relayed
+//! back once it expires. A packet for which a policy fails is held, i.e. skipped until it
+//! is relayed again by the next packet clearing. The policies are asked once per packet
+//! about the packets they relay or deny.
+//!
+//! As the packets of an ordered channel are received in order, denying one of them would
+//! block all the packets sent after it on the channel. The policies therefore only decide
+//! about the packets of ordered channels if they
+//! [opt in](PacketPolicy::applies_to_ordered_channels), in which case a packet they deny
+//! is held, along with the channel, rather than skipped. The policies which do not opt in
+//! are reported once for each ordered path they are bypassed on.
+//!
+//! ```rust,ignore
+//! struct Blocklist { addresses: BTreeSet<String> }
+//!
+//! impl PacketPolicy for Blocklist {
+//!     fn name(&self) -> &str {
+//!         "blocklist"
+//!     }
+//!
+//!     fn check(&self, packet: &PacketInfo<'_>) -> Result<Verdict, PacketPolicyError> {
+//!         match packet.transfer_data() {
+//!             Some(data) if self.addresses.contains(&data.sender.to_string()) => {
+//!                 Ok(Verdict::deny("sender is blocklisted"))
+//!             }
+//!             _ => Ok(Verdict::Relay),
+//!         }
+//!     }
+//! }
+//!
+//! policy::register(Blocklist { addresses });
+//! ```
+
+use alloc::sync::Arc;
+use std::sync::RwLock;
+
+use flex_error::{define_error, TraceError};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use ibc::applications::transfer::packet::PacketData;
+use ibc::core::ics04_channel::packet::Packet;
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::config::packet_policy::PacketPolicyConfig;
+
+define_error! {
+    PacketPolicyError {
+        Failed
+            { reason: String }
+            | e | { format_args!("packet policy failed: {}", e.reason) },
+
+        Http
+            { url: String }
+            [ TraceError<reqwest::Error> ]
+            | e | { format_args!("request to the packet policy service at {} failed", e.url) },
+    }
+}
+
+/// A packet about to be relayed, given to the policies.
+#[derive(Clone, Debug)]
+pub struct PacketInfo<'a> {
+    pub src_chain: &'a ChainId,
+    pub dst_chain: &'a ChainId,
+    pub packet: &'a Packet,
+}
+
+impl<'a> PacketInfo<'a> {
+    /// The data of the packet decoded as JSON, as encoded by most applications, e.g. ICS-20 and ICS-721.
+    pub fn json_data(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.packet.data).ok()
+    }
+
+    /// The data of the packet decoded as the data of an ICS-20 fungible token transfer.
+    pub fn transfer_data(&self) -> Option<PacketData> {
+        serde_json::from_slice(&self.packet.data).ok()
+    }
+}
+
+/// The decision of a policy about a packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Relay,
+    Deny { reason: String },
+}
+
+impl Verdict {
+    pub fn deny(reason: impl ToString) -> Self {
+        Self::Deny {
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// A policy deciding whether the packets are relayed.
+pub trait PacketPolicy: Send + Sync {
+    /// The name of the policy, which identifies it in the logs and errors.
+    fn name(&self) -> &str;
+
+    /// Whether the policy decides about the packets sent from the source chain
+    /// to the destination chain. All packets by default.
+    fn applies_to(&self, _src_chain: &ChainId, _dst_chain: &ChainId) -> bool {
+        true
+    }
+
+    /// Decide whether the given packet is relayed.
+    ///
+    /// An error holds the packet, unless the policy [fails open](PacketPolicy::fails_open).
+    fn check(&self, packet: &PacketInfo<'_>) -> Result<Verdict, PacketPolicyError>;
+
+    /// Whether the packets are relayed when the policy fails to decide about them.
+    fn fails_open(&self) -> bool {
+        false
+    }
+
+    /// Whether the policy also decides about the packets of ordered channels, which are
+    /// relayed regardless of it otherwise. A packet of an ordered channel which the policy
+    /// denies is held, blocking the packets sent after it, until the policy relays it or
+    /// it times out, which closes the channel if it is `ORDERED`.
+    fn applies_to_ordered_channels(&self) -> bool {
+        false
+    }
+}
+
+static POLICIES: Lazy<RwLock<Vec<Arc<dyn PacketPolicy>>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Register a policy, to decide about all the packets relayed from now on.
+pub fn register(policy: impl PacketPolicy + 'static) {
+    POLICIES.write().unwrap().push(Arc::new(policy));
+}
+
+/// The registered policies.
+pub fn registered() -> Vec<Arc<dyn PacketPolicy>> {
+    POLICIES.read().unwrap().clone()
+}
+
+/// What becomes of a packet according to the policies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// All the policies relay the packet
+    Relay,
+    /// A policy denied the packet
+    Deny,
+    /// A policy failed to decide about the packet, which is held until it is relayed again
+    Hold,
+}
+
+/// The names of those of the given policies which apply to the packets sent from the source
+/// chain to the destination chain, but not to the ones of ordered channels.
+pub fn bypassed_on_ordered_channels<'a>(
+    policies: &'a [Arc<dyn PacketPolicy>],
+    src_chain: &ChainId,
+    dst_chain: &ChainId,
+) -> Vec<&'a str> {
+    policies
+        .iter()
+        .filter(|policy| policy.applies_to(src_chain, dst_chain))
+        .filter(|policy| !policy.applies_to_ordered_channels())
+        .map(|policy| policy.name())
+        .collect()
+}
+
+/// Decide about the given packet with those of the given policies which apply to it,
+/// the first one denying or holding it deciding. Logs the policy deciding so, if any.
+/// On an ordered channel, only the policies which apply to ordered channels decide,
+/// and a packet they deny is held instead.
+pub fn decide(
+    policies: &[Arc<dyn PacketPolicy>],
+    packet: &PacketInfo<'_>,
+    ordered: bool,
+) -> Decision {
+    let applicable = policies
+        .iter()
+        .filter(|policy| policy.applies_to(packet.src_chain, packet.dst_chain))
+        .filter(|policy| !ordered || policy.applies_to_ordered_channels());
+
+    for policy in applicable {
+        match policy.check(packet) {
+            Ok(Verdict::Relay) => {}
+            Ok(Verdict::Deny { reason }) if ordered => {
+                info!(
+                    policy = policy.name(),
+                    packet = %packet.packet,
+                    "holding packet of ordered channel, and the packets sent after it, \
+                    as denied by policy: {}",
+                    reason
+                );
+
+                return Decision::Hold;
+            }
+            Ok(Verdict::Deny { reason }) => {
+                info!(
+                    policy = policy.name(),
+                    packet = %packet.packet,
+                    "packet denied by policy: {}",
+                    reason
+                );
+
+                return Decision::Deny;
+            }
+            Err(e) if policy.fails_open() => {
+                warn!(
+                    policy = policy.name(),
+                    packet = %packet.packet,
+                    "relaying packet although the policy failed: {}",
+                    e
+                );
+            }
+            Err(e) => {
+                warn!(
+                    policy = policy.name(),
+                    packet = %packet.packet,
+                    "holding packet as the policy failed: {}",
+                    e
+                );
+
+                return Decision::Hold;
+            }
+        }
+    }
+
+    Decision::Relay
+}
+
+/// The request posted to the policy service for each packet.
+#[derive(Debug, Serialize)]
+struct PolicyRequest<'a> {
+    src_chain: &'a ChainId,
+    dst_chain: &'a ChainId,
+    packet: &'a Packet,
+    /// The data of the packet decoded as JSON, if it is JSON
+    data: Option<serde_json::Value>,
+}
+
+/// The answer of the policy service.
+#[derive(Debug, Deserialize)]
+struct PolicyResponse {
+    relay: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// A policy delegating its decisions to an external HTTP service, configured in the
+/// `[packet_policy]` section. Each packet is posted to the service as JSON, along with
+/// its decoded data, and the service answers with `{ "relay": <bool>, "reason": <string> }`.
+pub struct HttpPolicy {
+    config: PacketPolicyConfig,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpPolicy {
+    pub fn new(config: PacketPolicyConfig) -> Result<Self, PacketPolicyError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| PacketPolicyError::http(config.url.clone(), e))?;
+
+        Ok(Self { config, client })
+    }
+}
+
+impl PacketPolicy for HttpPolicy {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn check(&self, packet: &PacketInfo<'_>) -> Result<Verdict, PacketPolicyError> {
+        let request = PolicyRequest {
+            src_chain: packet.src_chain,
+            dst_chain: packet.dst_chain,
+            packet: packet.packet,
+            data: packet.json_data(),
+        };
+
+        let response: PolicyResponse = self
+            .client
+            .post(&self.config.url)
+            .json(&request)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|e| PacketPolicyError::http(self.config.url.clone(), e))?;
+
+        if response.relay {
+            Ok(Verdict::Relay)
+        } else {
+            Ok(Verdict::deny(response.reason.unwrap_or_else(|| {
+                "denied by the policy service".to_string()
+            })))
+        }
+    }
+
+    fn fails_open(&self) -> bool {
+        self.config.fail_open
+    }
+
+    fn applies_to_ordered_channels(&self) -> bool {
+        self.config.ordered_channels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Blocklist {
+        sender: String,
+    }
+
+    impl PacketPolicy for Blocklist {
+        fn name(&self) -> &str {
+            "blocklist"
+        }
+
+        fn check(&self, packet: &PacketInfo<'_>) -> Result<Verdict, PacketPolicyError> {
+            match packet.transfer_data() {
+                Some(data) if data.sender.to_string() == self.sender => {
+                    Ok(Verdict::deny("sender is blocklisted"))
+                }
+                Some(_) => Ok(Verdict::Relay),
+                None => Err(PacketPolicyError::failed("not a transfer".to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn policies_deny_and_hold_packets() {
+        let policies: Vec<Arc<dyn PacketPolicy>> = vec![Arc::new(Blocklist {
+            sender: "cosmos1sanctioned".to_string(),
+        })];
+
+        let src_chain = ChainId::new("ibc".to_string(), 0);
+        let dst_chain = ChainId::new("ibc".to_string(), 1);
+
+        let transfer = |sender: &str| Packet {
+            data: serde_json::to_vec(&serde_json::json!({
+                "denom": "uatom",
+                "amount": "100",
+                "sender": sender,
+                "receiver": "cosmos1receiver",
+            }))
+            .unwrap(),
+            ..Packet::default()
+        };
+
+        let decide = |packet: &Packet| {
+            decide(
+                &policies,
+                &PacketInfo {
+                    src_chain: &src_chain,
+                    dst_chain: &dst_chain,
+                    packet,
+                },
+                false,
+            )
+        };
+
+        assert_eq!(decide(&transfer("cosmos1sender")), Decision::Relay);
+        assert_eq!(decide(&transfer("cosmos1sanctioned")), Decision::Deny);
+
+        // The packets the policy fails to decide about are held
+        assert_eq!(decide(&Packet::default()), Decision::Hold);
+    }
+
+    #[test]
+    fn policies_opt_in_to_ordered_channels() {
+        struct Ordered(Blocklist);
+
+        impl PacketPolicy for Ordered {
+            fn name(&self) -> &str {
+                "ordered-blocklist"
+            }
+
+            fn check(&self, packet: &PacketInfo<'_>) -> Result<Verdict, PacketPolicyError> {
+                self.0.check(packet)
+            }
+
+            fn applies_to_ordered_channels(&self) -> bool {
+                true
+            }
+        }
+
+        let blocklist = || Blocklist {
+            sender: "cosmos1sanctioned".to_string(),
+        };
+
+        let src_chain = ChainId::new("ibc".to_string(), 0);
+        let dst_chain = ChainId::new("ibc".to_string(), 1);
+
+        let packet = Packet {
+            data: serde_json::to_vec(&serde_json::json!({
+                "denom": "uatom",
+                "amount": "100",
+                "sender": "cosmos1sanctioned",
+                "receiver": "cosmos1receiver",
+            }))
+            .unwrap(),
+            ..Packet::default()
+        };
+
+        let info = PacketInfo {
+            src_chain: &src_chain,
+            dst_chain: &dst_chain,
+            packet: &packet,
+        };
+
+        // The policies which do not opt in are bypassed on ordered channels
+        let policies: Vec<Arc<dyn PacketPolicy>> = vec![Arc::new(blocklist())];
+
+        assert_eq!(decide(&policies, &info, true), Decision::Relay);
+        assert_eq!(
+            bypassed_on_ordered_channels(&policies, &src_chain, &dst_chain),
+            vec!["blocklist"]
+        );
+
+        // The packets denied by the policies which opt in are held on ordered channels
+        let policies: Vec<Arc<dyn PacketPolicy>> = vec![Arc::new(Ordered(blocklist()))];
+
+        assert_eq!(decide(&policies, &info, true), Decision::Hold);
+        assert_eq!(decide(&policies, &info, false), Decision::Deny);
+        assert!(bypassed_on_ordered_channels(&policies, &src_chain, &dst_chain).is_empty());
+    }
+}
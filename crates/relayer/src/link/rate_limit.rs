@@ -0,0 +1,159 @@
+//! Detection of packets rejected by a rate-limiting module on the destination chain,
+//! such as the Osmosis `ibc-rate-limit` middleware or the Stride `ratelimit` module.
+//!
+//! Resubmitting a `MsgRecvPacket` rejected because a quota is exhausted keeps
+//! failing until the quota window resets, so relaying packets on the channel
+//! is paused until then.
+
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// How long to back off for when the rejection does not say when the quota resets.
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Longest backoff, so that an unexpected reset time cannot stall a channel indefinitely.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A rejection by a rate-limiting module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimited {
+    /// When the exhausted quota resets, if reported by the module
+    pub reset_at: Option<SystemTime>,
+}
+
+impl RateLimited {
+    /// How long to wait from `now` before relaying on the channel again.
+    pub fn backoff(&self, now: SystemTime) -> Duration {
+        let backoff = match self.reset_at {
+            Some(reset_at) => reset_at.duration_since(now).unwrap_or(Duration::ZERO),
+            None => DEFAULT_BACKOFF,
+        };
+
+        backoff.min(MAX_BACKOFF)
+    }
+}
+
+/// Checks whether the given error message reports a rate limit rejection,
+/// and extracts the time at which the quota resets if available.
+pub fn parse_rate_limit_error(message: &str) -> Option<RateLimited> {
+    let lowercase = message.to_lowercase();
+
+    if !lowercase.contains("rate limit exceeded") && !lowercase.contains("quota exceeded") {
+        return None;
+    }
+
+    Some(RateLimited {
+        reset_at: parse_reset_time(message),
+    })
+}
+
+/// Assumes that the reset time is either reported as by the Osmosis rate limit contract,
+/// i.e. "try again after Timestamp(Uint64(N))" with `N` in nanoseconds since the epoch,
+/// or as "resets at N" with `N` in seconds since the epoch.
+fn parse_reset_time(message: &str) -> Option<SystemTime> {
+    static NANOS_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?i)try again after Timestamp\(Uint64\((?P<nanos>\d+)\)\)"#).unwrap()
+    });
+
+    static SECS_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"(?i)resets? at:? (?P<secs>\d+)"#).unwrap());
+
+    if let Some(captures) = NANOS_RE.captures(message) {
+        let nanos = captures["nanos"].parse().ok()?;
+        return Some(UNIX_EPOCH + Duration::from_nanos(nanos));
+    }
+
+    if let Some(captures) = SECS_RE.captures(message) {
+        let secs = captures["secs"].parse().ok()?;
+        return Some(UNIX_EPOCH + Duration::from_secs(secs));
+    }
+
+    None
+}
+
+/// Tracks until when relaying packets on a channel is paused.
+#[derive(Debug, Default)]
+pub struct RateLimitBackoff {
+    until: Mutex<Option<Instant>>,
+}
+
+impl RateLimitBackoff {
+    /// Pauses relaying until the quota of the given rejection resets,
+    /// returning how long relaying is paused for.
+    pub fn back_off(&self, rate_limited: &RateLimited) -> Duration {
+        let backoff = rate_limited.backoff(SystemTime::now());
+        *self.until.lock().unwrap() = Some(Instant::now() + backoff);
+        backoff
+    }
+
+    /// Whether relaying is currently paused.
+    pub fn is_active(&self) -> bool {
+        matches!(*self.until.lock().unwrap(), Some(until) if Instant::now() < until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_osmosis_rate_limit_error() {
+        let message = "failed to execute message; message index: 1: IBC Rate Limit exceeded for \
+            channel-0/uosmo. Tried to transfer 100 which exceeds capacity on the 'weekly' quota \
+            (90/95). Try again after Timestamp(Uint64(1667758080000000000)): \
+            execute wasm contract failed";
+
+        assert_eq!(
+            parse_rate_limit_error(message),
+            Some(RateLimited {
+                reset_at: Some(UNIX_EPOCH + Duration::from_secs(1667758080)),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_quota_exceeded_error() {
+        let message = "Outflow exceeds quota - Net Outflow: 100, Channel Value: 1000, \
+            Threshold: 5%: Quota exceeded";
+
+        assert_eq!(
+            parse_rate_limit_error(message),
+            Some(RateLimited { reset_at: None })
+        );
+    }
+
+    #[test]
+    fn parse_unrelated_error() {
+        assert_eq!(
+            parse_rate_limit_error("account sequence mismatch, expected 1, got 2"),
+            None
+        );
+    }
+
+    #[test]
+    fn backoff_duration() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+
+        let unknown = RateLimited { reset_at: None };
+        assert_eq!(unknown.backoff(now), DEFAULT_BACKOFF);
+
+        let future = RateLimited {
+            reset_at: Some(UNIX_EPOCH + Duration::from_secs(1030)),
+        };
+        assert_eq!(future.backoff(now), Duration::from_secs(30));
+
+        let past = RateLimited {
+            reset_at: Some(UNIX_EPOCH + Duration::from_secs(10)),
+        };
+        assert_eq!(past.backoff(now), Duration::ZERO);
+
+        let far = RateLimited {
+            reset_at: Some(now + 2 * MAX_BACKOFF),
+        };
+        assert_eq!(far.backoff(now), MAX_BACKOFF);
+    }
+}
@@ -1,12 +1,17 @@
 use alloc::collections::BTreeMap as HashMap;
 use alloc::collections::VecDeque;
+use std::collections::HashSet;
 use std::ops::Sub;
-use std::time::{Duration, Instant};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant, SystemTime};
 
 use ibc_proto::google::protobuf::Any;
 use itertools::Itertools;
-use tracing::{debug, error, info, span, trace, warn, Level};
+use moka::sync::Cache as MokaCache;
+use tracing::{debug, error, error_span, info, span, trace, warn, Level, Span};
 
+use crate::block_time;
+use crate::chain::clock::ClockTracker;
 use crate::chain::counterparty::unreceived_acknowledgements;
 use crate::chain::counterparty::unreceived_packets;
 use crate::chain::endpoint::ChainStatus;
@@ -16,6 +21,7 @@ use crate::chain::requests::QueryChannelRequest;
 use crate::chain::requests::QueryClientEventRequest;
 use crate::chain::requests::QueryHeight;
 use crate::chain::requests::QueryHostConsensusStateRequest;
+use crate::chain::requests::QueryIncentivizedPacketRequest;
 use crate::chain::requests::QueryNextSequenceReceiveRequest;
 use crate::chain::requests::QueryPacketCommitmentRequest;
 use crate::chain::requests::QueryTxRequest;
@@ -25,9 +31,16 @@ use crate::chain::tracking::TrackedMsgs;
 use crate::chain::tracking::TrackingId;
 use crate::channel::error::ChannelError;
 use crate::channel::Channel;
+use crate::config::filter::{self, PacketFilter, RelayKind};
+use crate::config::min_fee::{min_fee, MinFee};
+use crate::config::packet_limit::packet_limit;
+use crate::config::schedule::{relay_schedule, RelaySchedule};
 use crate::event::monitor::EventBatch;
 use crate::event::IbcEventWithHeight;
 use crate::foreign_client::{ForeignClient, ForeignClientError};
+use crate::ha::claims::{ClaimKind, PathClaims};
+use crate::link::block_quota::BlockQuota;
+use crate::link::client_updates::{client_updates, ClientUpdateTicket};
 use crate::link::error::{self, LinkError};
 use crate::link::operational_data::{
     OperationalData, OperationalDataTarget, TrackedEvents, TransitMessage,
@@ -35,28 +48,36 @@ use crate::link::operational_data::{
 use crate::link::packet_events::query_packet_events_with;
 use crate::link::packet_events::query_send_packet_events;
 use crate::link::packet_events::query_write_ack_events;
-use crate::link::pending::PendingTxs;
-use crate::link::relay_sender::{AsyncReply, SubmitReply};
+use crate::link::pending::{InFlightView, PendingTxs};
+use crate::link::policy::{self, Decision, PacketInfo};
+use crate::link::rate_limit::{parse_rate_limit_error, RateLimitBackoff};
+use crate::link::relay_sender::{AsyncReply, SubmitReply, TxStatus};
 use crate::link::relay_summary::RelaySummary;
+use crate::link::routing::{check_channel_route, check_packet_route, End, Misrouted};
 use crate::link::{pending, relay_sender};
 use crate::path::PathIdentifiers;
+use crate::sdk_error::{chain_error_class, ErrorClass};
 use crate::telemetry;
 use crate::util::pretty::PrettyEvents;
 use crate::util::queue::Queue;
 use ibc::{
+    applications::{async_icq, transfer::packet::PacketData},
     core::{
         ics02_client::events::ClientMisbehaviour as ClientMisbehaviourEvent,
         ics04_channel::{
-            channel::{ChannelEnd, Order, State as ChannelState},
-            events::{SendPacket, WriteAcknowledgement},
+            channel::{ChannelEnd, State as ChannelState},
+            events::{self as ChannelEvents, SendPacket, WriteAcknowledgement},
             msgs::{
-                acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
-                recv_packet::MsgRecvPacket, timeout::MsgTimeout,
+                acknowledgement::MsgAcknowledgement,
+                chan_close_confirm::MsgChannelCloseConfirm,
+                recv_packet::{self, MsgRecvPacket},
+                timeout::MsgTimeout,
                 timeout_on_close::MsgTimeoutOnClose,
             },
-            packet::{Packet, PacketMsgType},
+            packet::{Packet, PacketMsgType, Sequence},
+            timeout::TimeoutHeight,
         },
-        ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+        ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
     },
     events::{IbcEvent, WithBlockDataType},
     signer::Signer,
@@ -67,6 +88,10 @@ use ibc::{
 
 const MAX_RETRIES: usize = 5;
 
+/// How many skipped packets are remembered per path, to only report them once,
+/// and how many decisions of the packet policies, to only ask them once per packet.
+const SKIPPED_PACKETS_CAPACITY: u64 = 10_000;
+
 /// Whether or not to resubmit packets when pending transactions
 /// fail to process within the given timeout duration.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -114,6 +139,50 @@ pub struct RelayPath<ChainA: ChainHandle, ChainB: ChainHandle> {
     // transactions if [`confirm_txes`] is true.
     pending_txs_src: PendingTxs<ChainA>,
     pending_txs_dst: PendingTxs<ChainB>,
+
+    // Pauses relaying `MsgRecvPacket`s after the destination chain
+    // rejected some due to a rate limit quota being exhausted.
+    rate_limit: RateLimitBackoff,
+
+    // Whether to check right before broadcasting the messages
+    // whether their packets were already relayed.
+    pre_send_check: bool,
+
+    // The windows of time outside of which only the packets about
+    // to time out are received, and whether the path was within
+    // them when last checked.
+    schedule: Option<RelaySchedule>,
+    in_window: bool,
+
+    // The minimum fees to be escrowed for the packets to be received.
+    min_fee: Option<MinFee>,
+
+    // How many packets at most are received on the destination chain for each of its blocks.
+    block_quota: Option<BlockQuota>,
+
+    // The packet filter of the source chain, unless one was reloaded for it since.
+    packet_filter: PacketFilter,
+
+    // The claims of the packets of the path, if they are claimed from the other instances.
+    claims: Option<PathClaims>,
+
+    // The packets which were skipped already, so that they are only reported once
+    // although they are skipped again at each clearing pass until they time out.
+    skipped_packets: MokaCache<Sequence, ()>,
+
+    // The decisions of the packet policies to relay or deny the packets, so that they are
+    // not asked again at each clearing pass. The packets held are not remembered.
+    policy_decisions: MokaCache<Sequence, Decision>,
+
+    // Reports once the packet policies bypassed on the path, if its channel is ordered.
+    ordered_policies_warning: Once,
+
+    // The lowest sequence number of the packets of the ordered channel held by a packet
+    // policy in the batch of events being handled, which holds the packets sent after it.
+    ordered_held_from: Mutex<Option<Sequence>>,
+
+    // The timestamps of the latest blocks of the destination chain seen by the path.
+    dst_clock: ClockTracker,
 }
 
 impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
@@ -140,6 +209,21 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         let src_port_id = channel.src_port_id().clone();
         let dst_port_id = channel.dst_port_id().clone();
 
+        let src_config = src_chain.config().map_err(LinkError::relayer)?;
+
+        let pre_send_check = src_config
+            .pre_send_check
+            .matches((&src_port_id, &src_channel_id));
+
+        let schedule =
+            relay_schedule(&src_config.schedules, &src_port_id, &src_channel_id).cloned();
+
+        let min_fee = min_fee(&src_config.min_fees, &src_port_id, &src_channel_id).cloned();
+
+        let block_quota = packet_limit(&src_config.packet_limits, &src_port_id, &src_channel_id)
+            .and_then(|limit| limit.max_packets_per_block)
+            .map(BlockQuota::new);
+
         let path = PathIdentifiers {
             port_id: dst_port_id.clone(),
             channel_id: dst_channel_id.clone(),
@@ -159,6 +243,19 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             confirm_txes: with_tx_confirmation,
             pending_txs_src: PendingTxs::new(src_chain, src_channel_id, src_port_id, dst_chain_id),
             pending_txs_dst: PendingTxs::new(dst_chain, dst_channel_id, dst_port_id, src_chain_id),
+            rate_limit: RateLimitBackoff::default(),
+            pre_send_check,
+            schedule,
+            in_window: true,
+            min_fee,
+            block_quota,
+            packet_filter: src_config.packet_filter.clone(),
+            claims: PathClaims::new(),
+            skipped_packets: MokaCache::new(SKIPPED_PACKETS_CAPACITY),
+            policy_decisions: MokaCache::new(SKIPPED_PACKETS_CAPACITY),
+            ordered_policies_warning: Once::new(),
+            ordered_held_from: Mutex::new(None),
+            dst_clock: ClockTracker::default(),
         })
     }
 
@@ -294,6 +391,114 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(Instant::now().sub(elapsed))
     }
 
+    /// The latest status of the destination chain, with its timestamp clamped to the local
+    /// time plus the clock drift of the chain, so that timeouts are not built from a skewed
+    /// timestamp which the source chain would reject as premature.
+    fn dst_status(&self) -> Result<ChainStatus, LinkError> {
+        let status = self
+            .dst_chain()
+            .query_application_status()
+            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+
+        let clock_drift = self
+            .dst_chain()
+            .config()
+            .map_err(LinkError::relayer)?
+            .clock_drift;
+
+        Ok(self
+            .dst_clock
+            .sanitize_status(&self.dst_chain().id(), status, clock_drift))
+    }
+
+    /// Whether the packets of the path are relayed now, i.e. whether the path
+    /// has no schedule or the current time falls within one of its windows.
+    pub fn in_relay_window(&self) -> bool {
+        self.schedule
+            .as_ref()
+            .map_or(true, |schedule| schedule.is_active_at(SystemTime::now()))
+    }
+
+    /// Whether a window of the schedule of the path opened since the last call,
+    /// upon which the packets deferred outside of the windows are cleared.
+    pub fn relay_window_opened(&mut self) -> bool {
+        let in_window = self.in_relay_window();
+        let opened = in_window && !self.in_window;
+        self.in_window = in_window;
+
+        opened
+    }
+
+    /// The packets of the given events, or their acknowledgements, which are claimed by another
+    /// of the instances relaying the path, see [`PathClaims`]. The packets of all the events are
+    /// claimed at once.
+    fn claimed_by_others(&self, events: &[IbcEventWithHeight]) -> HashSet<(Sequence, ClaimKind)> {
+        let claims = match &self.claims {
+            Some(claims) => claims,
+            None => return HashSet::new(),
+        };
+
+        let src_chain_id = self.src_chain().id();
+        let dst_chain_id = self.dst_chain().id();
+
+        // The packets are identified on the chain they were sent from
+        let packets: Vec<(&Packet, ClaimKind, &ChainId)> = events
+            .iter()
+            .filter_map(|event_with_height| match &event_with_height.event {
+                IbcEvent::SendPacket(event) => {
+                    Some((&event.packet, ClaimKind::Packet, &src_chain_id))
+                }
+                IbcEvent::WriteAcknowledgement(event) => {
+                    Some((&event.packet, ClaimKind::Ack, &dst_chain_id))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let keys: Vec<String> = packets
+            .iter()
+            .map(|(packet, kind, chain_id)| {
+                claims.key(
+                    chain_id,
+                    &packet.source_port,
+                    &packet.source_channel,
+                    packet.sequence,
+                    *kind,
+                )
+            })
+            .collect();
+
+        packets
+            .iter()
+            .zip(claims.claim_all(&keys))
+            .filter(|(_, claimed)| !claimed)
+            .map(|((packet, kind, _), _)| (packet.sequence, *kind))
+            .collect()
+    }
+
+    /// Whether the given packet times out on the destination chain within
+    /// the timeout margin of the schedule of the path.
+    fn about_to_time_out(&self, packet: &Packet, dst_info: &ChainStatus) -> bool {
+        let margin = match &self.schedule {
+            Some(schedule) => schedule.timeout_margin,
+            None => return false,
+        };
+
+        let timestamp = (dst_info.timestamp + margin).unwrap_or(dst_info.timestamp);
+
+        // The timeout height cannot be told apart from a close one
+        // until the block time of the destination chain is estimated
+        let height = match block_time::estimate(&self.dst_chain().id()) {
+            Some(block_time) => dst_info
+                .height
+                .add(block_time::blocks_in(margin, block_time)),
+            None if packet.timeout_height == TimeoutHeight::Never => dst_info.height,
+            None => return true,
+        };
+
+        packet.timed_out(&timestamp, height)
+    }
+
     pub(crate) fn src_max_block_time(&self) -> Result<Duration, LinkError> {
         // TODO(hu55a1n1): Ideally, we should get the `max_expected_time_per_block` using the
         // `/genesis` endpoint once it is working in tendermint-rs.
@@ -312,12 +517,33 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .max_block_time)
     }
 
-    fn unordered_channel(&self) -> bool {
-        self.channel.ordering == Order::Unordered
+    /// Whether the packets of the channel are received in the order they were sent,
+    /// including on the `ORDERED_ALLOW_TIMEOUT` channels.
+    fn ordered_channel(&self) -> bool {
+        self.channel.ordering.is_ordered()
     }
 
-    fn ordered_channel(&self) -> bool {
-        self.channel.ordering == Order::Ordered
+    /// Whether the timeout of a packet closes the channel, i.e. whether it is `ORDERED`.
+    fn closes_on_timeout(&self) -> bool {
+        self.channel.ordering.closes_on_timeout()
+    }
+
+    /// Whether the destination skips the packets which timed out on their reception,
+    /// i.e. whether the channel is `ORDERED_ALLOW_TIMEOUT`.
+    fn skips_timed_out_packets(&self) -> bool {
+        self.ordered_channel() && !self.closes_on_timeout()
+    }
+
+    /// Whether the packet filter of the source chain allows relaying the given kind
+    /// of messages from the source channel.
+    fn relay_allowed(&self, kind: RelayKind) -> bool {
+        let src_chain_id = self.src_chain().id();
+        let (port_id, channel_id) = (self.src_port_id(), self.src_channel_id());
+
+        filter::with_reloaded(&src_chain_id, |packet_filter| {
+            packet_filter.allows(port_id, channel_id, kind)
+        })
+        .unwrap_or_else(|| self.packet_filter.allows(port_id, channel_id, kind))
     }
 
     pub fn build_update_client_on_dst(&self, height: Height) -> Result<Vec<Any>, LinkError> {
@@ -404,6 +630,30 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         TrackedEvents::new(result, tracking_id)
     }
 
+    /// Check that the packet or channel of the given event resolves to the channel ends
+    /// of the path, so that spoofed or misrouted events, e.g. emitted by a malicious
+    /// contract on the source chain, are not relayed.
+    fn check_event_route(&self, event: &IbcEvent) -> Result<(), Misrouted> {
+        let src = End::new(self.src_port_id(), self.src_channel_id());
+        let dst = End::new(self.dst_port_id(), self.dst_channel_id());
+
+        match event {
+            IbcEvent::SendPacket(ev) => check_packet_route(&ev.packet, src, dst),
+            IbcEvent::TimeoutPacket(ev) => check_packet_route(&ev.packet, src, dst),
+            IbcEvent::WriteAcknowledgement(ev) => check_packet_route(&ev.packet, dst, src),
+            IbcEvent::CloseInitChannel(ev) => check_channel_route(
+                End::new(&ev.port_id, &ev.channel_id),
+                &ev.connection_id,
+                &ev.counterparty_port_id,
+                ev.counterparty_channel_id.as_ref(),
+                src,
+                self.src_connection_id(),
+                dst,
+            ),
+            _ => Ok(()),
+        }
+    }
+
     fn relay_pending_packets(&self, height: Option<Height>) -> Result<(), LinkError> {
         let _span = span!(Level::ERROR, "relay_pending_packets", ?height).entered();
 
@@ -443,6 +693,75 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(())
     }
 
+    /// Whether the channel is closed on the source chain but not yet on the
+    /// destination chain, i.e. a `ChanCloseConfirm` still has to be relayed.
+    pub fn close_confirm_pending(&self) -> Result<bool, LinkError> {
+        if !self
+            .src_channel(QueryHeight::Latest)?
+            .state_matches(&ChannelState::Closed)
+        {
+            return Ok(false);
+        }
+
+        Ok(!self
+            .dst_channel(QueryHeight::Latest)?
+            .state_matches(&ChannelState::Closed))
+    }
+
+    /// Whether all the packets sent on the source chain and their acknowledgements were
+    /// relayed to the destination chain, with no message to it scheduled nor in flight.
+    /// Until then, the channel must be kept open on the destination chain, as the packets
+    /// cannot be received on it once it is closed.
+    fn is_flushed(&self) -> Result<bool, LinkError> {
+        if !self.dst_operational_data.is_empty()
+            || !self.pending_txs_dst.in_flight().txs().is_empty()
+        {
+            return Ok(false);
+        }
+
+        let (packets, _) = unreceived_packets(self.dst_chain(), self.src_chain(), &self.path_id)
+            .map_err(LinkError::supervisor)?;
+
+        if !packets.is_empty() {
+            return Ok(false);
+        }
+
+        let (acks, _) =
+            unreceived_acknowledgements(self.dst_chain(), self.src_chain(), &self.path_id)
+                .map_err(LinkError::supervisor)?;
+
+        Ok(acks.is_empty())
+    }
+
+    /// Schedules a `ChanCloseConfirm` for the destination chain if the channel
+    /// was closed on the source chain without the counterparty following up,
+    /// e.g. because the `CloseInit` event was emitted while no relayer was running,
+    /// or because the path was not flushed yet when it was.
+    pub fn schedule_close_confirm(&self) -> Result<(), LinkError> {
+        let _span = span!(Level::ERROR, "schedule_close_confirm").entered();
+
+        if !self.close_confirm_pending()? {
+            return Ok(());
+        }
+
+        let close_init = ChannelEvents::CloseInit {
+            port_id: self.src_port_id().clone(),
+            channel_id: self.src_channel_id().clone(),
+            connection_id: self.src_connection_id().clone(),
+            counterparty_port_id: self.dst_port_id().clone(),
+            counterparty_channel_id: Some(self.dst_channel_id().clone()),
+        };
+
+        let event = IbcEventWithHeight::new(
+            IbcEvent::CloseInitChannel(close_init),
+            self.src_latest_height()?,
+        );
+
+        debug!("channel is closed on source chain, scheduling close confirm");
+
+        self.events_to_operational_data(TrackedEvents::new(vec![event], TrackingId::new_uuid()))
+    }
+
     /// Generate & schedule operational data from the input `batch` of IBC events.
     pub fn update_schedule(&self, batch: EventBatch) -> Result<(), LinkError> {
         let _span = span!(
@@ -505,16 +824,17 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         )
         .entered();
 
+        // The packets held by a policy on an ordered channel are asked about again
+        // with each batch, along with the packets sent after them
+        *self.ordered_held_from.lock().unwrap() = None;
+
         let input = events.events();
         let src_height = match input.get(0) {
             None => return Ok((None, None)),
             Some(ev) => ev.height,
         };
 
-        let dst_latest_info = self
-            .dst_chain()
-            .query_application_status()
-            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+        let dst_latest_info = self.dst_status()?;
 
         let dst_latest_height = dst_latest_info.height;
 
@@ -534,21 +854,46 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             self.channel.connection_delay,
         );
 
+        let claimed_by_others = self.claimed_by_others(input);
+
         for event_with_height in input {
+            let _span = self.packet_span(&event_with_height.event).entered();
+
             trace!(event = %event_with_height, "processing event");
 
+            if let Err(misrouted) = self.check_event_route(&event_with_height.event) {
+                warn!(
+                    event = %event_with_height,
+                    "rejecting event which does not belong to the path: {}",
+                    misrouted
+                );
+
+                continue;
+            }
+
             let (dst_msg, src_msg) = match &event_with_height.event {
-                IbcEvent::CloseInitChannel(_) => (
-                    Some(self.build_chan_close_confirm_from_event(event_with_height)?),
-                    None,
-                ),
+                IbcEvent::CloseInitChannel(_) => {
+                    if self.is_flushed()? {
+                        (
+                            Some(self.build_chan_close_confirm_from_event(event_with_height)?),
+                            None,
+                        )
+                    } else {
+                        debug!(
+                            event = %event_with_height,
+                            "deferring the close confirm until the packets in flight are flushed"
+                        );
+
+                        (None, None)
+                    }
+                }
                 IbcEvent::TimeoutPacket(_) => {
                     // When a timeout packet for an ordered channel is processed on-chain (src here)
                     // the chain closes the channel but no close init event is emitted, instead
                     // we get a timeout packet event (this happens for both unordered and ordered channels)
                     // Here we check that the channel is closed on src and send a channel close confirm
-                    // to the counterparty.
-                    if self.ordered_channel()
+                    // to the counterparty. The `ORDERED_ALLOW_TIMEOUT` channels are left open.
+                    if self.closes_on_timeout()
                         && self
                             .src_channel(QueryHeight::Specific(event_with_height.height))?
                             .state_matches(&ChannelState::Closed)
@@ -565,13 +910,31 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     if self.send_packet_event_handled(event)? {
                         debug!(?event, "SendPacket event has already been handled");
 
+                        (None, None)
+                    } else if !self.relay_allowed(RelayKind::Recv)
+                        && !self.relay_allowed(RelayKind::Timeout)
+                    {
+                        debug!(?event, "SendPacket event is denied by the packet filter");
+
+                        (None, None)
+                    } else if claimed_by_others
+                        .contains(&(event.packet.sequence, ClaimKind::Packet))
+                    {
+                        debug!(?event, "SendPacket event is claimed by another instance");
+
                         (None, None)
                     } else {
-                        self.build_recv_or_timeout_from_send_packet_event(
-                            event,
-                            &dst_latest_info,
-                            event_with_height.height,
-                        )?
+                        let (dst_msg, src_msg) = self
+                            .build_recv_or_timeout_from_send_packet_event(
+                                event,
+                                &dst_latest_info,
+                                event_with_height.height,
+                            )?;
+
+                        (
+                            dst_msg.filter(|_| self.relay_allowed(RelayKind::Recv)),
+                            src_msg.filter(|_| self.relay_allowed(RelayKind::Timeout)),
+                        )
                     }
                 }
                 IbcEvent::WriteAcknowledgement(ref event) => {
@@ -586,6 +949,27 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                             "WriteAcknowledgement event has already been handled"
                         );
 
+                        (None, None)
+                    } else if !self.relay_allowed(RelayKind::Ack) {
+                        debug!(
+                            ?event,
+                            "WriteAcknowledgement event is denied by the packet filter"
+                        );
+
+                        (None, None)
+                    } else if !self.in_relay_window() {
+                        debug!(
+                            ?event,
+                            "deferring WriteAcknowledgement event until the next relay window"
+                        );
+
+                        (None, None)
+                    } else if claimed_by_others.contains(&(event.packet.sequence, ClaimKind::Ack)) {
+                        debug!(
+                            ?event,
+                            "WriteAcknowledgement event is claimed by another instance"
+                        );
+
                         (None, None)
                     } else {
                         (
@@ -597,6 +981,26 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                 _ => (None, None),
             };
 
+            // On ordered channels, e.g. those of the interchain accounts, the packets sent after
+            // a packet which timed out cannot be received, as its timeout closes the channel.
+            // They are timed out on close instead, once the channel is closed. On the
+            // `ORDERED_ALLOW_TIMEOUT` channels, they are received after the timed out packets,
+            // which the destination skips.
+            let dst_msg = dst_msg.filter(|msg| {
+                let blocked = self.closes_on_timeout()
+                    && !src_od.batch.is_empty()
+                    && msg.type_url == recv_packet::TYPE_URL;
+
+                if blocked {
+                    debug!(
+                        event = %event_with_height,
+                        "skipping packet sent after a packet which timed out on the ordered channel"
+                    );
+                }
+
+                !blocked
+            });
+
             // Collect messages to be sent to the destination chain (e.g., RecvPacket)
             if let Some(msg) = dst_msg {
                 trace!(%msg.type_url, event = %event_with_height, "collected event");
@@ -611,7 +1015,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             if let Some(msg) = src_msg {
                 // For Ordered channels a single timeout event should be sent as this closes the channel.
                 // Otherwise a multi message transaction will fail.
-                if self.unordered_channel() || src_od.batch.is_empty() {
+                if !self.closes_on_timeout() || src_od.batch.is_empty() {
                     trace!(%msg.type_url, event = %event_with_height, "collected event");
 
                     src_od.batch.push(TransitMessage {
@@ -639,6 +1043,22 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         let mut odata = initial_od;
 
+        if self.pre_send_check {
+            match self.leave_out_relayed(&mut odata) {
+                Ok(0) => {}
+                Ok(left_out) => info!(
+                    "left out {} messages whose packets were already relayed",
+                    left_out
+                ),
+                Err(e) => warn!("failed to check which packets were already relayed: {}", e),
+            }
+
+            if odata.batch.is_empty() {
+                info!("all the packets were already relayed, nothing to submit");
+                return Ok(S::Reply::empty());
+            }
+        }
+
         for i in 0..MAX_RETRIES {
             debug!(retry.current = i + 1, retry.max = MAX_RETRIES, "retrying");
 
@@ -665,14 +1085,35 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     return Ok(reply);
                 }
                 Err(LinkError(error::LinkErrorDetail::Send(e), _)) => {
-                    // This error means we could retry
                     error!("error {}", e.event);
+
+                    let class = match &e.event {
+                        IbcEvent::ChainError(message) => chain_error_class(message),
+                        _ => ErrorClass::Retryable,
+                    };
+
+                    if class == ErrorClass::FatalForBatch {
+                        error!("the messages cannot succeed without intervention, giving up");
+                        break;
+                    }
+
                     if i + 1 == MAX_RETRIES {
                         error!("{}/{} retries exhausted. giving up", i + 1, MAX_RETRIES)
                     } else {
                         // If we haven't exhausted all retries, regenerate the op. data & retry
                         match self.regenerate_operational_data(odata.clone()) {
                             None => return Ok(S::Reply::empty()), // Nothing to retry
+                            // The failed message is still in the batch, it would fail again
+                            Some(new_od)
+                                if class == ErrorClass::FatalForMessage
+                                    && new_od.batch.len() >= odata.batch.len() =>
+                            {
+                                error!(
+                                    "a message of the batch cannot succeed and was not left out \
+                                    when regenerating it, giving up"
+                                );
+                                break;
+                            }
                             Some(new_od) => odata = new_od,
                         }
                     }
@@ -687,6 +1128,106 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(S::Reply::empty())
     }
 
+    /// Leaves out of the operational data the messages whose packets were relayed since
+    /// it was built, e.g. by another relayer, querying the destination chain for the
+    /// receipts and acknowledgements and the source chain for the packet commitments.
+    /// Returns how many messages were left out.
+    fn leave_out_relayed(&self, odata: &mut OperationalData) -> Result<usize, LinkError> {
+        let mut recv_sequences = vec![];
+        let mut ack_sequences = vec![];
+        let mut timeout_sequences = vec![];
+
+        for gm in odata.batch.iter() {
+            match (&gm.event_with_height.event, odata.target) {
+                (IbcEvent::SendPacket(ev), OperationalDataTarget::Destination) => {
+                    recv_sequences.push(ev.packet.sequence)
+                }
+                (IbcEvent::SendPacket(ev), OperationalDataTarget::Source) => {
+                    timeout_sequences.push(ev.packet.sequence)
+                }
+                (IbcEvent::WriteAcknowledgement(ev), _) => ack_sequences.push(ev.packet.sequence),
+                _ => {}
+            }
+        }
+
+        // The packets whose `MsgRecvPacket`, `MsgAcknowledgement` or `MsgTimeout`
+        // is no longer needed, the sequences of each kind being unrelated
+        let (mut received, mut acknowledged, mut cleared) = (vec![], vec![], vec![]);
+
+        if !recv_sequences.is_empty() {
+            let unreceived = self
+                .dst_chain()
+                .query_unreceived_packets(QueryUnreceivedPacketsRequest {
+                    port_id: self.dst_port_id().clone(),
+                    channel_id: self.dst_channel_id().clone(),
+                    packet_commitment_sequences: recv_sequences.clone(),
+                })
+                .map_err(LinkError::relayer)?;
+
+            received.extend(
+                recv_sequences
+                    .into_iter()
+                    .filter(|s| !unreceived.contains(s)),
+            );
+        }
+
+        if !ack_sequences.is_empty() {
+            let unacknowledged = self
+                .dst_chain()
+                .query_unreceived_acknowledgements(QueryUnreceivedAcksRequest {
+                    port_id: self.dst_port_id().clone(),
+                    channel_id: self.dst_channel_id().clone(),
+                    packet_ack_sequences: ack_sequences.clone(),
+                })
+                .map_err(LinkError::relayer)?;
+
+            acknowledged.extend(
+                ack_sequences
+                    .into_iter()
+                    .filter(|s| !unacknowledged.contains(s)),
+            );
+        }
+
+        for sequence in timeout_sequences {
+            let (commitment, _) = self
+                .src_chain()
+                .query_packet_commitment(
+                    QueryPacketCommitmentRequest {
+                        port_id: self.src_port_id().clone(),
+                        channel_id: self.src_channel_id().clone(),
+                        sequence,
+                        height: QueryHeight::Latest,
+                    },
+                    IncludeProof::No,
+                )
+                .map_err(LinkError::relayer)?;
+
+            if commitment.is_empty() {
+                cleared.push(sequence);
+            }
+        }
+
+        let initial_len = odata.batch.len();
+
+        let target = odata.target;
+        odata
+            .batch
+            .retain(|gm| match (&gm.event_with_height.event, target) {
+                (IbcEvent::SendPacket(ev), OperationalDataTarget::Destination) => {
+                    !received.contains(&ev.packet.sequence)
+                }
+                (IbcEvent::SendPacket(ev), OperationalDataTarget::Source) => {
+                    !cleared.contains(&ev.packet.sequence)
+                }
+                (IbcEvent::WriteAcknowledgement(ev), _) => {
+                    !acknowledged.contains(&ev.packet.sequence)
+                }
+                _ => true,
+            });
+
+        Ok(initial_len - odata.batch.len())
+    }
+
     /// Generates fresh operational data for a tx given the initial operational data
     /// that failed to send.
     ///
@@ -782,12 +1323,63 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             return Ok(S::Reply::empty());
         }
 
-        let msgs = odata.assemble_msgs(self)?;
+        let (chain_id, client_id) = match odata.target {
+            OperationalDataTarget::Source => (self.src_chain().id(), self.src_client_id()),
+            OperationalDataTarget::Destination => (self.dst_chain().id(), self.dst_client_id()),
+        };
 
-        match odata.target {
+        // Coordinate with the other paths over the same client, for the client
+        // to be updated only once at the height required by their messages. If the
+        // update got in flight for another path since the schedule was executed,
+        // include it anyway rather than waiting for it.
+        let update_height = odata.proofs_height.increment();
+        let ticket = if odata.conn_delay_needed() {
+            None
+        } else {
+            Some(client_updates().claim(&chain_id, client_id, update_height))
+        };
+
+        let with_client_update = !matches!(ticket, Some(ClientUpdateTicket::Skip));
+        let msgs = odata.assemble_msgs(self, with_client_update)?;
+        let has_client_update = msgs.messages().len() > odata.batch.len();
+
+        let result = match odata.target {
             OperationalDataTarget::Source => S::submit(self.src_chain(), msgs),
             OperationalDataTarget::Destination => S::submit(self.dst_chain(), msgs),
+        };
+
+        match (ticket, &result) {
+            (Some(ClientUpdateTicket::Update(claim)), Ok(reply)) if has_client_update => {
+                match reply.first_tx() {
+                    TxStatus::Committed => claim.committed(),
+                    TxStatus::Checked(tx) => claim.submitted(tx),
+                    TxStatus::Failed => drop(claim),
+                }
+            }
+            // The messages may have failed because the update submitted
+            // for another path did not make it, build it next time
+            (Some(ClientUpdateTicket::Skip), Err(_)) => {
+                client_updates().forget(&chain_id, client_id, update_height)
+            }
+            _ => {}
         }
+
+        result
+    }
+
+    /// Whether the client update needed by the given operational data is
+    /// in flight for another path, see [`client_updates`].
+    fn client_update_in_flight(&self, odata: &OperationalData) -> bool {
+        if odata.conn_delay_needed() {
+            return false;
+        }
+
+        let (chain_id, client_id) = match odata.target {
+            OperationalDataTarget::Source => (self.src_chain().id(), self.src_client_id()),
+            OperationalDataTarget::Destination => (self.dst_chain().id(), self.dst_client_id()),
+        };
+
+        client_updates().is_in_flight(&chain_id, client_id, odata.proofs_height.increment())
     }
 
     fn enqueue_pending_tx(&self, reply: AsyncReply, odata: OperationalData) {
@@ -1121,6 +1713,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         for events_chunk in query_packet_events_with(
             &sequences,
             query_height,
+            None,
             self.src_chain(),
             &self.path_id,
             query_send_packet_events,
@@ -1178,10 +1771,18 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         for events_chunk in query_packet_events_with(
             &sequences,
             query_height,
+            None,
             self.src_chain(),
             &self.path_id,
             query_write_ack_events,
         ) {
+            // An acknowledgement written asynchronously may not be found yet, it will
+            // be relayed when its `WriteAcknowledgement` event is seen or on a later clearing.
+            if events_chunk.is_empty() {
+                debug!("no acknowledgement events found yet for the queried sequences");
+                continue;
+            }
+
             telemetry!(self.record_cleared_acknowledgments(events_chunk.iter()));
             self.events_to_operational_data(TrackedEvents::new(events_chunk, tracking_id))?;
         }
@@ -1190,6 +1791,44 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     }
 
     fn build_recv_packet(&self, packet: &Packet, height: Height) -> Result<Option<Any>, LinkError> {
+        match self.policy_decision(packet) {
+            Decision::Relay => {}
+            Decision::Deny => {
+                self.skip_packet(packet, "packet is denied by a packet policy");
+                return Ok(None);
+            }
+            Decision::Hold => return Ok(None),
+        }
+
+        if let Some(reason) = self.rejected_transfer(packet) {
+            self.skip_packet(packet, &reason);
+            return Ok(None);
+        }
+
+        if let Some(min_fee) = &self.min_fee {
+            if !self.pays_min_fee(min_fee, packet)? {
+                return Ok(None);
+            }
+        }
+
+        let msg = self.build_recv_packet_msg(packet, height)?;
+
+        // The host of the interchain queries executes them on reception of the packet,
+        // which are only decoded to be logged
+        if tracing::enabled!(Level::DEBUG) && async_icq::is_host_port(&packet.destination_port) {
+            if let Some(queries) = async_icq::packet::decode_queries(&packet.data) {
+                debug!(
+                    packet = %packet,
+                    paths = %queries.iter().map(|query| &query.path).join(", "),
+                    "relaying interchain queries to their host"
+                );
+            }
+        }
+
+        Ok(Some(msg))
+    }
+
+    fn build_recv_packet_msg(&self, packet: &Packet, height: Height) -> Result<Any, LinkError> {
         let proofs = self
             .src_chain()
             .build_packet_proofs(
@@ -1205,7 +1844,160 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         trace!(packet = %packet, height = %proofs.height(), "built recv_packet msg");
 
-        Ok(Some(msg.to_any()))
+        Ok(msg.to_any())
+    }
+
+    /// The decision of the packet policies about the given packet, asked once per packet
+    /// unless they hold it. On ordered channels, only the policies applying to them decide,
+    /// and the other ones are reported once for the path. A packet held on an ordered
+    /// channel holds the packets sent after it in the same batch, which could not be
+    /// received before it anyway.
+    fn policy_decision(&self, packet: &Packet) -> Decision {
+        let held_from = *self.ordered_held_from.lock().unwrap();
+
+        if matches!(held_from, Some(sequence) if packet.sequence > sequence) {
+            trace!(%packet, "holding packet sent after a packet held on the ordered channel");
+            return Decision::Hold;
+        }
+
+        let decision = self.ask_policies(packet);
+
+        if decision == Decision::Hold && self.ordered_channel() {
+            *self.ordered_held_from.lock().unwrap() = Some(packet.sequence);
+        }
+
+        decision
+    }
+
+    /// Ask the registered packet policies about the given packet, or reuse their decision.
+    fn ask_policies(&self, packet: &Packet) -> Decision {
+        if let Some(decision) = self.policy_decisions.get(&packet.sequence) {
+            return decision;
+        }
+
+        let policies = policy::registered();
+
+        if policies.is_empty() {
+            return Decision::Relay;
+        }
+
+        let (src_chain_id, dst_chain_id) = (self.src_chain().id(), self.dst_chain().id());
+        let ordered = self.ordered_channel();
+
+        if ordered {
+            self.ordered_policies_warning.call_once(|| {
+                let bypassed =
+                    policy::bypassed_on_ordered_channels(&policies, &src_chain_id, &dst_chain_id);
+
+                if !bypassed.is_empty() {
+                    warn!(
+                        "the packets of this ordered channel are relayed regardless of the \
+                        packet policies which do not apply to ordered channels: {}",
+                        bypassed.join(", ")
+                    );
+                }
+            });
+        }
+
+        let decision = policy::decide(
+            &policies,
+            &PacketInfo {
+                src_chain: &src_chain_id,
+                dst_chain: &dst_chain_id,
+                packet,
+            },
+            ordered,
+        );
+
+        if decision != Decision::Hold {
+            self.policy_decisions.insert(packet.sequence, decision);
+        }
+
+        decision
+    }
+
+    /// Why the packet filter of the source chain rejects receiving the given packet, if it
+    /// does: an ICS-20 transfer below the minimum amount of its denomination, or whose memo
+    /// is rejected by the memo guard of its channel.
+    fn rejected_transfer(&self, packet: &Packet) -> Option<String> {
+        let data: PacketData = serde_json::from_slice(&packet.data).ok()?;
+
+        let denom = data.token.denom.to_string();
+        let amount = data.token.amount;
+        let (port_id, channel_id) = (self.src_port_id(), self.src_channel_id());
+
+        let check = |packet_filter: &PacketFilter| {
+            if !packet_filter.allows_transfer(port_id, channel_id, &denom, amount) {
+                return Some(format!(
+                    "transfer is below the minimum amount of {} of the packet filter",
+                    denom
+                ));
+            }
+
+            packet_filter
+                .memo_guard(port_id, channel_id)
+                .and_then(|memo_guard| memo_guard.check(&packet.data).err())
+                .map(|violation| format!("transfer is rejected by the memo guard: {}", violation))
+        };
+
+        filter::with_reloaded(&self.src_chain().id(), check)
+            .unwrap_or_else(|| check(&self.packet_filter))
+    }
+
+    /// Skip receiving the given packet for the given reason, which is then timed out.
+    /// As the packet is skipped again at each clearing pass, it is only logged and
+    /// counted the first time.
+    fn skip_packet(&self, packet: &Packet, reason: &str) {
+        if self.skipped_packets.contains_key(&packet.sequence) {
+            trace!(%packet, "skipping packet again: {}", reason);
+            return;
+        }
+
+        self.skipped_packets.insert(packet.sequence, ());
+
+        warn!(%packet, "skipping packet: {}", reason);
+
+        telemetry!(
+            packets_denied,
+            &self.src_chain().id(),
+            self.src_channel_id(),
+            self.src_port_id(),
+            &self.dst_chain().id()
+        );
+    }
+
+    /// Whether the receive fees escrowed for the given packet on the source chain pay the
+    /// relayer at least the given minimum fee. Holds the packet if its fees cannot be queried.
+    fn pays_min_fee(&self, min_fee: &MinFee, packet: &Packet) -> Result<bool, LinkError> {
+        let fees = self
+            .src_chain()
+            .query_incentivized_packet(QueryIncentivizedPacketRequest {
+                port_id: packet.source_port.clone(),
+                channel_id: packet.source_channel.clone(),
+                sequence: packet.sequence,
+                height: QueryHeight::Latest,
+            });
+
+        let fees = match fees {
+            Ok(fees) => fees,
+            Err(e) => {
+                warn!(%packet, "holding packet as its fees could not be queried: {}", e);
+                return Ok(false);
+            }
+        };
+
+        if min_fee.is_met_by(fees.as_ref(), &self.dst_signer()?) {
+            return Ok(true);
+        }
+
+        debug!(
+            %packet,
+            "holding packet as its receive fees are below the minimum of {}{}",
+            min_fee.amount,
+            min_fee.denom
+        );
+
+        Ok(false)
     }
 
     fn build_ack_from_recv_event(
@@ -1341,8 +2133,21 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         height: Height,
     ) -> Result<(Option<Any>, Option<Any>), LinkError> {
         let timeout = self.build_timeout_from_send_packet_event(event, dst_info)?;
-        if timeout.is_some() {
+        if timeout.is_some() && self.skips_timed_out_packets() {
+            // The destination skips the packet on reception, so that the next ones can be received
+            Ok((
+                Some(self.build_recv_packet_msg(&event.packet, height)?),
+                timeout,
+            ))
+        } else if timeout.is_some() {
             Ok((None, timeout))
+        } else if !self.in_relay_window() && !self.about_to_time_out(&event.packet, dst_info) {
+            debug!(
+                packet = %event.packet,
+                "deferring packet until the next relay window"
+            );
+
+            Ok((None, None))
         } else {
             Ok((self.build_recv_packet(&event.packet, height)?, None))
         }
@@ -1374,12 +2179,74 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     /// also placed in the 'unprocessed' bucket.
     fn execute_schedule_for_target_chain<I: Iterator<Item = OperationalData>>(
         &mut self,
-        mut operations: I,
+        operations: I,
         target_chain: OperationalDataTarget,
     ) -> Result<VecDeque<OperationalData>, (VecDeque<OperationalData>, LinkError)> {
+        let mut operations: VecDeque<_> = operations.collect();
         let mut unprocessed = VecDeque::new();
 
-        while let Some(od) = operations.next() {
+        while let Some(mut od) = operations.pop_front() {
+            // Hold off on packets which would be rejected again by the rate limit on destination.
+            if target_chain == OperationalDataTarget::Destination
+                && self.rate_limit.is_active()
+                && od
+                    .batch
+                    .iter()
+                    .any(|gm| gm.msg.type_url == recv_packet::TYPE_URL)
+            {
+                unprocessed.push_back(od);
+                continue;
+            }
+
+            // Hold off on the packets beyond the quota of the channel for the current block
+            // of the destination, the messages after them being processed in order.
+            if target_chain == OperationalDataTarget::Destination && self.block_quota.is_some() {
+                let height = match self.dst_latest_height() {
+                    Ok(height) => height,
+                    Err(e) => {
+                        unprocessed.push_back(od);
+                        unprocessed.extend(operations);
+
+                        return Err((unprocessed, e));
+                    }
+                };
+
+                match self.split_at_block_quota(od, height) {
+                    (Some(within), held) => {
+                        if let Some(held) = held {
+                            operations.push_front(held);
+                        }
+
+                        od = within;
+                    }
+                    (None, Some(held)) => {
+                        debug!(
+                            %height,
+                            "holding {} messages beyond the packet quota of the channel for the current block",
+                            held.batch.len()
+                        );
+
+                        unprocessed.push_back(held);
+                        continue;
+                    }
+                    (None, None) => continue,
+                }
+            }
+
+            // Hold off while the client update needed by the messages is in flight for
+            // another path, the messages after them being processed in order.
+            if self.client_update_in_flight(&od) {
+                debug!(
+                    "holding {} messages until the client update in flight for another path is confirmed",
+                    od.batch.len()
+                );
+
+                unprocessed.push_back(od);
+                unprocessed.extend(operations);
+
+                return Ok(unprocessed);
+            }
+
             let elapsed_result = match target_chain {
                 OperationalDataTarget::Source => od.has_conn_delay_elapsed(
                     &|| self.src_time_latest(),
@@ -1406,6 +2273,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                             // The relaying process failed; return all of the subsequent pieces of operational
                             // data along with the underlying error that occurred.
                             Err(e) => {
+                                if target_chain == OperationalDataTarget::Destination
+                                    && self.handle_rate_limit_error(&e.to_string())
+                                {
+                                    unprocessed.push_back(od);
+                                }
+
                                 unprocessed.extend(operations);
 
                                 return Err((unprocessed, e));
@@ -1434,6 +2307,44 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(unprocessed)
     }
 
+    /// Split the given operational data before its first packet beyond the quota of the
+    /// channel for the given height of the destination, returning the messages within the
+    /// quota, counted against it, and the ones from that packet on, if any.
+    fn split_at_block_quota(
+        &mut self,
+        mut od: OperationalData,
+        height: Height,
+    ) -> (Option<OperationalData>, Option<OperationalData>) {
+        let quota = match &mut self.block_quota {
+            Some(quota) => quota,
+            None => return (Some(od), None),
+        };
+
+        let is_recv = |msg: &TransitMessage| msg.msg.type_url == recv_packet::TYPE_URL;
+
+        let split_at = od
+            .batch
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| is_recv(msg))
+            .nth(quota.remaining(height) as usize)
+            .map(|(index, _)| index);
+
+        let held = split_at.map(|index| {
+            let mut held = od.clone();
+            held.batch = od.batch.split_off(index);
+            held
+        });
+
+        quota.consume(od.batch.iter().filter(|msg| is_recv(msg)).count() as u64);
+
+        if od.batch.is_empty() {
+            (None, held)
+        } else {
+            (Some(od), held)
+        }
+    }
+
     // TODO: handle cross-chain query
     // Get response from query data, send tx to src chain
     fn execute_local_bound_event(&self, events: Vec<IbcEventWithHeight>) {
@@ -1497,6 +2408,15 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(())
     }
 
+    /// The views of the transactions submitted to the source and destination
+    /// chains which are not confirmed yet.
+    pub fn in_flight(&self) -> [InFlightView; 2] {
+        [
+            self.pending_txs_src.in_flight(),
+            self.pending_txs_dst.in_flight(),
+        ]
+    }
+
     /// Kicks off the process of relaying pending txs to the source and destination chains.
     ///
     /// See [`Resubmit::from_clear_interval`] for more info about the `resubmit` parameter.
@@ -1552,9 +2472,34 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .process_pending(pending::TIMEOUT, self, do_resubmit)?
             .unwrap_or_else(RelaySummary::empty);
 
+        for event in &res.events {
+            if let IbcEvent::ChainError(message) = event {
+                self.handle_rate_limit_error(message);
+            }
+        }
+
         Ok(res)
     }
 
+    /// Pauses relaying packets if the given error is a rejection by a rate limit
+    /// on the destination chain. Returns whether that is the case.
+    fn handle_rate_limit_error(&self, message: &str) -> bool {
+        match parse_rate_limit_error(message) {
+            Some(rate_limited) => {
+                let backoff = self.rate_limit.back_off(&rate_limited);
+
+                warn!(
+                    "packets rejected by rate limit on chain {}, pausing relaying for {:?}",
+                    self.dst_chain().id(),
+                    backoff
+                );
+
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Refreshes the scheduled batches.
     /// Verifies if any sendPacket messages timed-out. If so, moves them from destination op. data
     /// to source operational data, and adjusts the events and messages accordingly.
@@ -1566,10 +2511,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             return Ok(());
         }
 
-        let dst_status = self
-            .dst_chain()
-            .query_application_status()
-            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+        let dst_status = self.dst_status()?;
 
         let dst_current_height = dst_status.height;
 
@@ -1757,6 +2699,33 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok((elapsed_src_ods, elapsed_dst_ods))
     }
 
+    /// The span of the relaying of the packet of the given event, if any, identified by
+    /// the chain the packet was sent from, and the sequence, ports and channels of the
+    /// packet. The spans of all the events of a packet, on both chains, are exported
+    /// in the same trace, whose identifier is derived from these fields.
+    fn packet_span(&self, event: &IbcEvent) -> Span {
+        // The acknowledgements are written on the chain the packets were sent to
+        let packet_chain = match event {
+            IbcEvent::WriteAcknowledgement(_) => self.dst_chain().id(),
+            _ => self.src_chain().id(),
+        };
+
+        match event.packet() {
+            Some(packet) => error_span!(
+                "relay.packet",
+                event = event.event_type().as_str(),
+                chain = %self.src_chain().id(),
+                packet_chain = %packet_chain,
+                sequence = %packet.sequence,
+                src_port = %packet.source_port,
+                src_channel = %packet.source_channel,
+                dst_port = %packet.destination_port,
+                dst_channel = %packet.destination_channel,
+            ),
+            None => Span::none(),
+        }
+    }
+
     fn restore_src_client(&self) -> ForeignClient<ChainA, ChainB> {
         ForeignClient::restore(
             self.src_client_id().clone(),
@@ -1811,6 +2780,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     self.src_port_id(),
                     &self.dst_chain().id(),
                 );
+                ibc_telemetry::global().packet_sent(
+                    send_packet_ev.packet.sequence.into(),
+                    &self.src_chain().id(),
+                    self.src_channel_id(),
+                    self.src_port_id(),
+                );
             }
             IbcEvent::WriteAcknowledgement(write_ack_ev) => {
                 ibc_telemetry::global().backlog_remove(
@@ -1820,6 +2795,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     self.dst_port_id(),
                     &self.src_chain().id(),
                 );
+                ibc_telemetry::global().packet_acknowledged(
+                    write_ack_ev.packet.sequence.into(),
+                    &self.dst_chain().id(),
+                    self.dst_channel_id(),
+                    self.dst_port_id(),
+                    &self.src_chain().id(),
+                );
             }
             IbcEvent::TimeoutPacket(timeout_packet) => {
                 ibc_telemetry::global().backlog_remove(
@@ -1,5 +1,6 @@
 use core::fmt::{Display, Error as FmtError, Formatter};
 
+use tendermint::abci::transaction;
 use tendermint_rpc::endpoint::broadcast::tx_sync;
 use tracing::info;
 
@@ -17,6 +18,20 @@ pub trait SubmitReply {
 
     /// Counts the number of replies that this instance contains.
     fn len(&self) -> usize;
+
+    /// The status of the first transaction submitted, which carries the
+    /// client update prepended to the messages, if any.
+    fn first_tx(&self) -> TxStatus;
+}
+
+/// The status of a transaction once submitted.
+pub enum TxStatus {
+    /// The transaction was committed
+    Committed,
+    /// The transaction passed `CheckTx`, and is to be confirmed
+    Checked(transaction::Hash),
+    /// The transaction failed, or none was submitted
+    Failed,
 }
 
 impl SubmitReply for RelaySummary {
@@ -27,6 +42,15 @@ impl SubmitReply for RelaySummary {
     fn len(&self) -> usize {
         self.events.len()
     }
+
+    fn first_tx(&self) -> TxStatus {
+        // The synchronous sender fails on the first error event
+        if self.events.is_empty() {
+            TxStatus::Failed
+        } else {
+            TxStatus::Committed
+        }
+    }
 }
 
 /// Captures the ability to submit messages to a chain.
@@ -85,6 +109,13 @@ impl SubmitReply for AsyncReply {
     fn len(&self) -> usize {
         self.responses.len()
     }
+
+    fn first_tx(&self) -> TxStatus {
+        match self.responses.first() {
+            Some(response) if response.code.is_ok() => TxStatus::Checked(response.hash),
+            _ => TxStatus::Failed,
+        }
+    }
 }
 
 // TODO(Adi): Consider removing the senders and keep only a generic
@@ -0,0 +1,184 @@
+//! Validation of the routing of the events relayed on a path, so that the events whose
+//! packet or channel does not resolve to the two channel ends of the path, such as the
+//! spoofed events emitted by a malicious contract, are rejected before building messages.
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use ibc::core::ics04_channel::packet::Packet;
+use ibc::core::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+
+/// A channel end, identified by its port and channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct End<'a> {
+    pub port_id: &'a PortId,
+    pub channel_id: &'a ChannelId,
+}
+
+impl<'a> End<'a> {
+    pub fn new(port_id: &'a PortId, channel_id: &'a ChannelId) -> Self {
+        Self {
+            port_id,
+            channel_id,
+        }
+    }
+}
+
+impl Display for End<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "{}/{}", self.port_id, self.channel_id)
+    }
+}
+
+/// Why an event does not belong to the path it was received for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Misrouted {
+    /// The packet goes between other channel ends than the ones of the path
+    Packet {
+        sequence: u64,
+        sender: String,
+        receiver: String,
+        expected_sender: String,
+        expected_receiver: String,
+    },
+    /// The channel closed is not the one of the path
+    Channel { found: String, expected: String },
+}
+
+impl Display for Misrouted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Packet {
+                sequence,
+                sender,
+                receiver,
+                expected_sender,
+                expected_receiver,
+            } => write!(
+                f,
+                "packet {} goes from {} to {} instead of from {} to {}",
+                sequence, sender, receiver, expected_sender, expected_receiver
+            ),
+            Self::Channel { found, expected } => {
+                write!(f, "channel {} is not the channel {}", found, expected)
+            }
+        }
+    }
+}
+
+/// Check that the given packet goes from the `sender` channel end to the `receiver` one.
+pub fn check_packet_route(
+    packet: &Packet,
+    sender: End<'_>,
+    receiver: End<'_>,
+) -> Result<(), Misrouted> {
+    let packet_sender = End::new(&packet.source_port, &packet.source_channel);
+    let packet_receiver = End::new(&packet.destination_port, &packet.destination_channel);
+
+    if packet_sender == sender && packet_receiver == receiver {
+        Ok(())
+    } else {
+        Err(Misrouted::Packet {
+            sequence: packet.sequence.into(),
+            sender: packet_sender.to_string(),
+            receiver: packet_receiver.to_string(),
+            expected_sender: sender.to_string(),
+            expected_receiver: receiver.to_string(),
+        })
+    }
+}
+
+/// Check that the given channel end, along with its connection and its counterparty
+/// if known, is the `expected` channel end of the path.
+pub fn check_channel_route(
+    channel: End<'_>,
+    connection_id: &ConnectionId,
+    counterparty_port_id: &PortId,
+    counterparty_channel_id: Option<&ChannelId>,
+    expected: End<'_>,
+    expected_connection_id: &ConnectionId,
+    expected_counterparty: End<'_>,
+) -> Result<(), Misrouted> {
+    let counterparty_matches = counterparty_port_id == expected_counterparty.port_id
+        && counterparty_channel_id.map_or(true, |id| id == expected_counterparty.channel_id);
+
+    if channel == expected && connection_id == expected_connection_id && counterparty_matches {
+        Ok(())
+    } else {
+        let counterparty_channel =
+            counterparty_channel_id.map_or_else(|| "?".to_string(), |id| id.to_string());
+
+        Err(Misrouted::Channel {
+            found: format!(
+                "{} on {} with counterparty {}/{}",
+                channel, connection_id, counterparty_port_id, counterparty_channel
+            ),
+            expected: format!(
+                "{} on {} with counterparty {}",
+                expected, expected_connection_id, expected_counterparty
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics04_channel::packet::Sequence;
+
+    fn packet(dst_port: &str, dst_channel: u64) -> Packet {
+        Packet {
+            sequence: Sequence::from(1),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: dst_port.parse().unwrap(),
+            destination_channel: ChannelId::new(dst_channel),
+            data: vec![],
+            timeout_height: Default::default(),
+            timeout_timestamp: Default::default(),
+        }
+    }
+
+    #[test]
+    fn reject_misrouted_packets() {
+        let port = PortId::transfer();
+        let (channel_0, channel_1) = (ChannelId::new(0), ChannelId::new(1));
+        let src = End::new(&port, &channel_0);
+        let dst = End::new(&port, &channel_1);
+
+        assert_eq!(check_packet_route(&packet("transfer", 1), src, dst), Ok(()));
+
+        // The receiving end is not the counterparty of the sending end
+        assert!(check_packet_route(&packet("transfer", 7), src, dst).is_err());
+        assert!(check_packet_route(&packet("wasm.contract", 1), src, dst).is_err());
+
+        // The packet goes the other way
+        assert!(check_packet_route(&packet("transfer", 1), dst, src).is_err());
+    }
+
+    #[test]
+    fn reject_misrouted_channels() {
+        let port = PortId::transfer();
+        let (channel_0, channel_1) = (ChannelId::new(0), ChannelId::new(1));
+        let (connection_0, connection_1) = (ConnectionId::new(0), ConnectionId::new(1));
+        let src = End::new(&port, &channel_0);
+        let dst = End::new(&port, &channel_1);
+
+        let check = |connection_id, counterparty_channel_id| {
+            check_channel_route(
+                src,
+                connection_id,
+                &port,
+                counterparty_channel_id,
+                src,
+                &connection_0,
+                dst,
+            )
+        };
+
+        assert_eq!(check(&connection_0, Some(&channel_1)), Ok(()));
+        assert_eq!(check(&connection_0, None), Ok(()));
+        assert!(check(&connection_1, Some(&channel_1)).is_err());
+        assert!(check(&connection_0, Some(&channel_0)).is_err());
+    }
+}
@@ -15,6 +15,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::light_client::AnyHeader;
 
+pub mod broadcast;
+pub mod equivocation;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MisbehaviourEvidence {
     pub misbehaviour: AnyMisbehaviour,
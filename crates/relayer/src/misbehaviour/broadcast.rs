@@ -0,0 +1,27 @@
+//! The misbehaviours detected by the misbehaviour tasks of the clients, sent to the
+//! supervisor for their evidence to be broadcast to the faulty chain itself and to its
+//! other clients, on all the chains of the configuration, which the tasks have no handle to.
+
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+
+use crate::misbehaviour::MisbehaviourEvidence;
+
+pub type Sender = crossbeam_channel::Sender<DetectedMisbehaviour>;
+pub type Receiver = crossbeam_channel::Receiver<DetectedMisbehaviour>;
+
+/// A misbehaviour of the faulty chain, whose evidence was submitted to
+/// the given client of the chain hosting it.
+#[derive(Clone, Debug)]
+pub struct DetectedMisbehaviour {
+    pub faulty_chain: ChainId,
+    pub host_chain: ChainId,
+    pub client_id: ClientId,
+    pub evidence: MisbehaviourEvidence,
+}
+
+impl DetectedMisbehaviour {
+    /// Whether the evidence of the misbehaviour was already submitted to the given client.
+    pub fn submitted_to(&self, host_chain: &ChainId, client_id: &ClientId) -> bool {
+        &self.host_chain == host_chain && &self.client_id == client_id
+    }
+}
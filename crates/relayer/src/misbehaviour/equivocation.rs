@@ -0,0 +1,143 @@
+//! The evidence of the equivocation of the validators of a chain, i.e. of their
+//! duplicate votes, broadcast to the chain itself so that they are slashed and jailed.
+//!
+//! The validators which signed both of the conflicting headers of a misbehaviour at
+//! the same height and round voted for two different blocks. Their precommits, rebuilt
+//! from the signatures of both commits, are the `DuplicateVoteEvidence` of CometBFT,
+//! which is submitted with the `broadcast_evidence` RPC and handed over by CometBFT
+//! to the `evidence` module of the chain once committed.
+
+use ibc::clients::ics07_tendermint::header::Header as TmHeader;
+use prost::Message;
+use tendermint::block::{Commit, CommitSig};
+use tendermint::evidence::{DuplicateVoteEvidence, Evidence};
+use tendermint::vote::{self, ValidatorIndex, Vote};
+use tendermint_proto::types::PartSetHeader as RawPartSetHeader;
+
+/// The evidence of the duplicate votes of the validators which signed both of the given
+/// conflicting headers, the second one being the header of the chain.
+///
+/// There is none if the headers are not at the same height and round, e.g. for a
+/// BFT time violation, or if they are not conflicting.
+pub fn duplicate_votes(header1: &TmHeader, header2: &TmHeader) -> Vec<Evidence> {
+    let (commit1, commit2) = (&header1.signed_header.commit, &header2.signed_header.commit);
+
+    if commit1.height != commit2.height
+        || commit1.round != commit2.round
+        || commit1.block_id == commit2.block_id
+    {
+        return vec![];
+    }
+
+    let validators = &header2.validator_set;
+
+    precommits(commit1)
+        .filter_map(|vote1| {
+            let vote2 = precommits(commit2)
+                .find(|vote| vote.validator_address == vote1.validator_address)?;
+
+            let validator = validators.validator(vote1.validator_address)?;
+
+            // CometBFT only accepts the votes ordered by the key of their block
+            let (vote_a, vote_b) = if block_key(&vote1) < block_key(&vote2) {
+                (vote1, vote2)
+            } else {
+                (vote2, vote1)
+            };
+
+            Some(Evidence::DuplicateVote(DuplicateVoteEvidence {
+                vote_a,
+                vote_b,
+                total_voting_power: validators.total_voting_power(),
+                validator_power: validator.power,
+                timestamp: header2.signed_header.header.time,
+            }))
+        })
+        .collect()
+}
+
+/// The precommits for the block of the given commit, rebuilt from its signatures.
+fn precommits(commit: &Commit) -> impl Iterator<Item = Vote> + '_ {
+    commit
+        .signatures
+        .iter()
+        .enumerate()
+        .filter_map(move |(index, signature)| match signature {
+            CommitSig::BlockIdFlagCommit {
+                validator_address,
+                timestamp,
+                signature,
+            } => Some(Vote {
+                vote_type: vote::Type::Precommit,
+                height: commit.height,
+                round: commit.round,
+                block_id: Some(commit.block_id),
+                timestamp: Some(*timestamp),
+                validator_address: *validator_address,
+                validator_index: ValidatorIndex::try_from(index).ok()?,
+                signature: signature.clone(),
+            }),
+            _ => None,
+        })
+}
+
+/// The key of the block of the given vote, i.e. its hash followed by the encoding of
+/// its part set header, by which CometBFT orders the votes of a duplicate vote evidence.
+fn block_key(vote: &Vote) -> Vec<u8> {
+    vote.block_id.map_or_else(Vec::new, |block_id| {
+        let mut key = block_id.hash.as_bytes().to_vec();
+        key.extend(RawPartSetHeader::from(block_id.part_set_header).encode_to_vec());
+        key
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::Height;
+    use tendermint::Time;
+    use tendermint_testgen::{Generator, LightBlock};
+
+    fn header(time: Time) -> TmHeader {
+        let block = LightBlock::new_default_with_time_and_chain_id("ibc-0".to_string(), time, 10)
+            .generate()
+            .unwrap();
+
+        TmHeader {
+            signed_header: block.signed_header,
+            validator_set: block.validators.clone(),
+            trusted_height: Height::new(0, 9).unwrap(),
+            trusted_validator_set: block.validators,
+        }
+    }
+
+    #[test]
+    fn duplicate_votes_of_conflicting_headers() {
+        let honest = header(Time::from_unix_timestamp(1_600_000_000, 0).unwrap());
+        let forged = header(Time::from_unix_timestamp(1_600_000_001, 0).unwrap());
+
+        let found = duplicate_votes(&forged, &honest);
+        assert_eq!(found.len(), honest.validator_set.validators().len());
+
+        for evidence in found {
+            let evidence = match evidence {
+                Evidence::DuplicateVote(evidence) => evidence,
+                _ => panic!("expected the evidence of a duplicate vote"),
+            };
+
+            assert_eq!(evidence.vote_a.height.value(), 10);
+            assert_eq!(
+                evidence.vote_a.validator_address,
+                evidence.vote_b.validator_address
+            );
+            assert!(block_key(&evidence.vote_a) < block_key(&evidence.vote_b));
+            assert!(evidence.vote_a.signature.is_some() && evidence.vote_b.signature.is_some());
+            assert!(evidence.validator_power.value() > 0);
+            assert_eq!(evidence.timestamp, honest.signed_header.header.time);
+        }
+
+        // The same header is no evidence of a duplicate vote
+        assert!(duplicate_votes(&honest, &honest).is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use ibc::core::{
     ics02_client::{client_state::ClientState, events::UpdateClient},
     ics03_connection::events::Attributes as ConnectionAttributes,
     ics04_channel::events::{
-        Attributes, CloseInit, SendPacket, TimeoutPacket, WriteAcknowledgement,
+        Attributes, CloseInit, SendPacket, TimeoutPacket, UpgradeChannel, WriteAcknowledgement,
     },
     ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 };
@@ -121,7 +121,15 @@ impl Packet {
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CrossChainQueryPacket {
+    /// Source chain identifier.
+    /// This is the querying chain, to which the result of the query is submitted.
     pub src_chain_id: ChainId,
+
+    /// Destination chain identifier.
+    /// This is the queried chain, on which the query is executed.
+    pub dst_chain_id: ChainId,
+
+    /// Identifier of the query, allocated on the querying chain.
     pub id: String,
 }
 
@@ -213,7 +221,7 @@ impl Object {
             Object::Channel(c) => &c.src_chain_id == src_chain_id,
             Object::Packet(p) => &p.src_chain_id == src_chain_id,
             Object::Wallet(_) => false,
-            Object::CrossChainQueryPacket(_) => true,
+            Object::CrossChainQueryPacket(_) => false,
         }
     }
 
@@ -225,7 +233,9 @@ impl Object {
             Object::Channel(c) => &c.src_chain_id == chain_id || &c.dst_chain_id == chain_id,
             Object::Packet(p) => &p.src_chain_id == chain_id || &p.dst_chain_id == chain_id,
             Object::Wallet(w) => &w.chain_id == chain_id,
-            Object::CrossChainQueryPacket(_) => true,
+            Object::CrossChainQueryPacket(q) => {
+                &q.src_chain_id == chain_id || &q.dst_chain_id == chain_id
+            }
         }
     }
 
@@ -308,8 +318,7 @@ impl Object {
             Self::Channel(ref channel) => &channel.dst_chain_id,
             Self::Packet(ref path) => &path.dst_chain_id,
             Self::Wallet(ref wallet) => &wallet.chain_id,
-            // TODO: set to dst chain id when needed
-            Self::CrossChainQueryPacket(ref path) => &path.src_chain_id,
+            Self::CrossChainQueryPacket(ref path) => &path.dst_chain_id,
         }
     }
 
@@ -509,6 +518,24 @@ impl Object {
         .into())
     }
 
+    /// Build the Channel object relaying the upgrade handshake step
+    /// following the one of the given [`UpgradeChannel`] event.
+    pub fn for_upgrade_channel(
+        e: &UpgradeChannel,
+        src_chain: &impl ChainHandle,
+    ) -> Result<Self, ObjectError> {
+        let dst_chain_id = counterparty_chain_from_channel(src_chain, &e.channel_id, &e.port_id)
+            .map_err(ObjectError::supervisor)?;
+
+        Ok(Channel {
+            dst_chain_id,
+            src_chain_id: src_chain.id(),
+            src_channel_id: e.channel_id.clone(),
+            src_port_id: e.port_id.clone(),
+        }
+        .into())
+    }
+
     pub fn for_cross_chain_query_packet(
         p: &CrossChainQuerySendPacket,
         src_chain: &impl ChainHandle,
@@ -516,6 +543,7 @@ impl Object {
         Ok(CrossChainQueryPacket {
             id: p.packet.id.to_string(),
             src_chain_id: src_chain.id(),
+            dst_chain_id: ChainId::from_string(&p.packet.chain_id),
         }
         .into())
     }
@@ -0,0 +1,362 @@
+//! Governance proposals for recovering a frozen or expired client,
+//! by replacing its state with the one of an active substitute client.
+//!
+//! The host chain only accepts a substitute client whose parameters match the ones of
+//! the subject client, except for those which are expected to change, e.g. the trusting
+//! period. Those parameters are checked before submitting the proposal, rather than
+//! once the proposal passed, so that the deposit is not spent on a proposal which fails.
+
+use flex_error::define_error;
+
+use tendermint::abci::transaction::Hash as TxHash;
+
+use ibc::clients::ics07_tendermint::client_state::ClientState as TmClientState;
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc_proto::cosmos::base::v1beta1::Coin;
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::client::v1::ClientUpdateProposal;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{IncludeProof, QueryClientStateRequest, QueryHeight};
+use crate::chain::tracking::TrackedMsgs;
+use crate::client_state::AnyClientState;
+use crate::config::ChainConfig;
+use crate::error::Error;
+use crate::gov::{gov_authority, submit_legacy_proposal, submit_proposal, GovVersion};
+
+pub const MSG_RECOVER_CLIENT_TYPE_URL: &str = "/ibc.core.client.v1.MsgRecoverClient";
+pub const CLIENT_UPDATE_PROPOSAL_TYPE_URL: &str = "/ibc.core.client.v1.ClientUpdateProposal";
+
+/// The `ibc.core.client.v1.MsgRecoverClient` of ibc-go v8, which the ibc-go version
+/// the definitions of `ibc-proto` are generated from predates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgRecoverClient {
+    #[prost(string, tag = "1")]
+    pub subject_client_id: String,
+    #[prost(string, tag = "2")]
+    pub substitute_client_id: String,
+    #[prost(string, tag = "3")]
+    pub signer: String,
+}
+
+define_error! {
+    RecoverClientError {
+        Query
+            [ Error ]
+            |_| { "error during a query" },
+
+        Key
+            [ Error ]
+            |_| { "key error" },
+
+        InvalidSubstitute
+            {
+                subject_client_id: ClientId,
+                substitute_client_id: ClientId,
+                reason: String,
+            }
+            |e| {
+                format!("client {0} cannot be recovered with client {1}: {2}",
+                    e.subject_client_id, e.substitute_client_id, e.reason)
+            },
+
+        GovAuthority
+            [ Error ]
+            |_| { "failed to derive the address of the governance module" },
+
+        Submit
+            { chain_id: ChainId }
+            [ Error ]
+            |e| {
+                format!("failed while submitting the client recovery proposal to chain {0}", e.chain_id)
+            },
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RecoverClientOptions {
+    pub chain_config: ChainConfig,
+    pub subject_client_id: ClientId,
+    pub substitute_client_id: ClientId,
+    pub amount: u64,
+    pub denom: String,
+}
+
+/// Submit a proposal to the given chain for recovering the subject client with the
+/// state of the substitute client, using the governance message flavour of that chain.
+///
+/// The proposal is only submitted if the substitute client can replace the subject
+/// client, see [`check_substitute_client`].
+pub fn build_and_send_recover_client_proposal(
+    chain: impl ChainHandle,
+    opts: &RecoverClientOptions,
+) -> Result<TxHash, RecoverClientError> {
+    let query_client_state = |client_id: &ClientId| {
+        chain
+            .query_client_state(
+                QueryClientStateRequest {
+                    client_id: client_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map(|(client_state, _)| client_state)
+            .map_err(RecoverClientError::query)
+    };
+
+    let subject = query_client_state(&opts.subject_client_id)?;
+    let substitute = query_client_state(&opts.substitute_client_id)?;
+
+    let gov_version = GovVersion::detect(&chain).map_err(RecoverClientError::query)?;
+
+    check_substitute_client(&subject, &substitute)
+        .and_then(|()| match gov_version {
+            // The legacy proposal handler also requires the subject client to allow it
+            GovVersion::Legacy => check_legacy_allow_update(&subject),
+            GovVersion::V1 => Ok(()),
+        })
+        .map_err(|reason| {
+            RecoverClientError::invalid_substitute(
+                opts.subject_client_id.clone(),
+                opts.substitute_client_id.clone(),
+                reason,
+            )
+        })?;
+
+    let title = format!("recover client {}", opts.subject_client_id);
+    let description = format!(
+        "replace the state of client {} with the state of client {}",
+        opts.subject_client_id, opts.substitute_client_id
+    );
+
+    let proposer = chain.get_signer().map_err(RecoverClientError::key)?;
+
+    let coins = Coin {
+        denom: opts.denom.clone(),
+        amount: opts.amount.to_string(),
+    };
+
+    let any_msg = match gov_version {
+        GovVersion::V1 => {
+            let authority = gov_authority(&opts.chain_config.account_prefix)
+                .map_err(RecoverClientError::gov_authority)?;
+
+            let msg = MsgRecoverClient {
+                subject_client_id: opts.subject_client_id.to_string(),
+                substitute_client_id: opts.substitute_client_id.to_string(),
+                signer: authority,
+            };
+
+            let mut buf_msg = Vec::new();
+            prost::Message::encode(&msg, &mut buf_msg).unwrap();
+            let any_recover = Any {
+                type_url: MSG_RECOVER_CLIENT_TYPE_URL.to_string(),
+                value: buf_msg,
+            };
+
+            submit_proposal(
+                vec![any_recover],
+                coins,
+                proposer.to_string(),
+                title,
+                description,
+            )
+        }
+        GovVersion::Legacy => {
+            let proposal = ClientUpdateProposal {
+                title,
+                description,
+                subject_client_id: opts.subject_client_id.to_string(),
+                substitute_client_id: opts.substitute_client_id.to_string(),
+            };
+
+            let mut buf_proposal = Vec::new();
+            prost::Message::encode(&proposal, &mut buf_proposal).unwrap();
+            let any_proposal = Any {
+                type_url: CLIENT_UPDATE_PROPOSAL_TYPE_URL.to_string(),
+                value: buf_proposal,
+            };
+
+            submit_legacy_proposal(any_proposal, coins, proposer.to_string())
+        }
+    };
+
+    // As for upgrade proposals, no IBC event is emitted by the
+    // submission of the proposal, so only wait for the CheckTx result.
+    let responses = chain
+        .send_messages_and_wait_check_tx(TrackedMsgs::new_single(any_msg, "recover client"))
+        .map_err(|e| RecoverClientError::submit(chain.id(), e))?;
+
+    Ok(responses[0].hash)
+}
+
+/// Checks that the given substitute client can replace the given subject client, i.e. that
+/// it is of the same type, is not frozen, is ahead of the subject client, and that all its
+/// parameters match the ones of the subject client except for its chain identifier,
+/// latest and frozen heights, and trusting period, as the host chain requires.
+pub fn check_substitute_client(
+    subject: &AnyClientState,
+    substitute: &AnyClientState,
+) -> Result<(), String> {
+    if subject.client_type() != substitute.client_type() {
+        return Err(format!(
+            "the substitute client is a {} client, while the subject client is a {} client",
+            substitute.client_type(),
+            subject.client_type()
+        ));
+    }
+
+    if substitute.is_frozen() {
+        return Err("the substitute client is frozen".to_string());
+    }
+
+    if substitute.latest_height() <= subject.latest_height() {
+        return Err(format!(
+            "the latest height of the substitute client ({}) is not greater than the one of the subject client ({})",
+            substitute.latest_height(),
+            subject.latest_height()
+        ));
+    }
+
+    match (subject, substitute) {
+        (AnyClientState::Tendermint(subject), AnyClientState::Tendermint(substitute)) => {
+            check_tendermint_substitute(subject, substitute)
+        }
+
+        #[cfg(test)]
+        _ => Ok(()),
+    }
+}
+
+fn check_tendermint_substitute(
+    subject: &TmClientState,
+    substitute: &TmClientState,
+) -> Result<(), String> {
+    let mismatches = [
+        ("trust level", subject.trust_level != substitute.trust_level),
+        (
+            "unbonding period",
+            subject.unbonding_period != substitute.unbonding_period,
+        ),
+        (
+            "max clock drift",
+            subject.max_clock_drift != substitute.max_clock_drift,
+        ),
+        ("proof specs", subject.proof_specs != substitute.proof_specs),
+        (
+            "upgrade path",
+            subject.upgrade_path != substitute.upgrade_path,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(param, mismatch)| mismatch.then(|| param))
+    .collect::<Vec<_>>();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "the parameters of the clients do not match: {}",
+            mismatches.join(", ")
+        ))
+    }
+}
+
+/// Checks that the subject client allows the legacy `ClientUpdateProposal` to update it,
+/// which requires it to allow updates after its expiry, or after misbehaviour if it is frozen.
+fn check_legacy_allow_update(subject: &AnyClientState) -> Result<(), String> {
+    match subject {
+        AnyClientState::Tendermint(subject) => {
+            let (allowed, flag) = if subject.is_frozen() {
+                (
+                    subject.allow_update.after_misbehaviour,
+                    "after misbehaviour",
+                )
+            } else {
+                (subject.allow_update.after_expiry, "after expiry")
+            };
+
+            if allowed {
+                Ok(())
+            } else {
+                Err(format!(
+                    "the subject client does not allow updates {}",
+                    flag
+                ))
+            }
+        }
+
+        #[cfg(test)]
+        AnyClientState::Mock(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::time::Duration;
+
+    use ibc::clients::ics07_tendermint::client_state::AllowUpdate;
+    use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc::core::ics23_commitment::specs::ProofSpecs;
+    use ibc::Height;
+
+    fn tm_client_state(latest_height: u64) -> AnyClientState {
+        AnyClientState::Tendermint(
+            TmClientState::new(
+                ChainId::from_string("ibc-0"),
+                TrustThreshold::ONE_THIRD,
+                Duration::from_secs(64000),
+                Duration::from_secs(128000),
+                Duration::from_millis(3000),
+                Height::new(0, latest_height).unwrap(),
+                ProofSpecs::default(),
+                vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+                AllowUpdate {
+                    after_expiry: true,
+                    after_misbehaviour: true,
+                },
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn substitute_clients() {
+        let subject = tm_client_state(10);
+
+        let mut substitute = tm_client_state(20);
+        if let AnyClientState::Tendermint(substitute) = &mut substitute {
+            // The trusting period of the substitute may differ
+            substitute.trusting_period = Duration::from_secs(32000);
+        }
+        assert!(check_substitute_client(&subject, &substitute).is_ok());
+
+        let behind = tm_client_state(5);
+        assert!(check_substitute_client(&subject, &behind).is_err());
+
+        let mut mismatching = tm_client_state(20);
+        if let AnyClientState::Tendermint(mismatching) = &mut mismatching {
+            mismatching.unbonding_period = Duration::from_secs(256000);
+            mismatching.upgrade_path = vec![];
+        }
+        let reason = check_substitute_client(&subject, &mismatching).unwrap_err();
+        assert!(
+            reason.ends_with("unbonding period, upgrade path"),
+            "{reason}"
+        );
+    }
+
+    #[test]
+    fn legacy_allow_update() {
+        let mut subject = tm_client_state(10);
+        assert!(check_legacy_allow_update(&subject).is_ok());
+
+        if let AnyClientState::Tendermint(subject) = &mut subject {
+            subject.allow_update.after_expiry = false;
+        }
+        assert!(check_legacy_allow_update(&subject).is_err());
+    }
+}
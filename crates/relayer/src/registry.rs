@@ -4,6 +4,7 @@ use alloc::collections::btree_map::BTreeMap as HashMap;
 use alloc::sync::Arc;
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use once_cell::sync::OnceCell;
 use tokio::runtime::Runtime as TokioRuntime;
 use tracing::{trace, warn};
 
@@ -13,18 +14,21 @@ use crate::{
     chain::handle::ChainHandle,
     config::Config,
     spawn::{spawn_chain_runtime, SpawnError},
-    util::lock::RwArc,
+    util::lock::{LockExt, RwArc},
 };
 
 /// Registry for keeping track of [`ChainHandle`]s indexed by a `ChainId`.
 ///
 /// The purpose of this type is to avoid spawning multiple runtimes for a single `ChainId`.
+///
+/// The chain runtimes are held in a [`RuntimePool`], which can be shared by several
+/// registries, e.g. for running several supervisors in the same process over a common
+/// set of chain connections. See [`Registry::with_pool`].
 #[derive(Debug)]
 pub struct Registry<Chain: ChainHandle> {
     config: Config,
     handles: HashMap<ChainId, Chain>,
-    rt: Arc<TokioRuntime>,
-    query_rt: Arc<TokioRuntime>,
+    pool: RuntimePool<Chain>,
 }
 
 #[derive(Clone)]
@@ -32,17 +36,178 @@ pub struct SharedRegistry<Chain: ChainHandle> {
     pub registry: RwArc<Registry<Chain>>,
 }
 
+/// The chain runtimes spawned for one or more [`Registry`], along with the
+/// Tokio runtimes they run on. Cloning the pool yields a handle to the same pool.
+///
+/// A chain runtime is spawned by the first registry which needs it, with the
+/// configuration of that registry, and is shut down once all the registries
+/// which use it have released it with [`Registry::shutdown`]. The chains shared
+/// between several registries should therefore be configured identically.
+#[derive(Debug)]
+pub struct RuntimePool<Chain: ChainHandle> {
+    inner: RwArc<PoolInner<Chain>>,
+}
+
+#[derive(Debug)]
+struct PoolInner<Chain: ChainHandle> {
+    handles: HashMap<ChainId, PoolSlot<Chain>>,
+    rt: Arc<TokioRuntime>,
+    query_rt: Arc<TokioRuntime>,
+}
+
+/// The handle to the runtime of a chain, set once the runtime is spawned, along
+/// with the number of registries using it. The runtime is spawned without holding
+/// the lock of the pool, for a chain which is slow to bootstrap not to hold up the
+/// others, and the registries acquiring it in the meantime wait for the handle.
+#[derive(Debug)]
+struct PoolSlot<Chain: ChainHandle> {
+    handle: Arc<OnceCell<Chain>>,
+    users: usize,
+}
+
+impl<Chain: ChainHandle> Clone for RuntimePool<Chain> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Chain: ChainHandle> Default for RuntimePool<Chain> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Chain: ChainHandle> RuntimePool<Chain> {
+    /// Create a new, empty pool, with its own Tokio runtimes.
+    pub fn new() -> Self {
+        Self {
+            inner: <RwArc<_>>::new_lock(PoolInner {
+                handles: HashMap::new(),
+                rt: Arc::new(TokioRuntime::new().unwrap()),
+                query_rt: Arc::new(TokioRuntime::new().unwrap()),
+            }),
+        }
+    }
+
+    /// Return the number of distinct chain runtimes in the pool.
+    pub fn size(&self) -> usize {
+        self.inner
+            .acquire_read()
+            .handles
+            .values()
+            .filter(|slot| slot.handle.get().is_some())
+            .count()
+    }
+
+    /// Add the handle to a runtime spawned outside of the pool for the
+    /// given chain, which is then handed out instead of spawning one,
+    /// e.g. to replay recorded events.
+    pub(crate) fn insert(&self, chain_id: &ChainId, handle: Chain) {
+        let slot = PoolSlot {
+            handle: Arc::new(OnceCell::with_value(handle)),
+            users: 0,
+        };
+
+        self.inner
+            .acquire_write()
+            .handles
+            .insert(chain_id.clone(), slot);
+    }
+
+    /// Get a handle to the runtime for the given chain, spawning it with
+    /// the given configuration if the pool does not contain it yet, and
+    /// count one more user of the runtime.
+    fn acquire(&self, config: &Config, chain_id: &ChainId) -> Result<Chain, SpawnError> {
+        let (handle, rt, query_rt) = {
+            let mut inner = self.inner.acquire_write();
+            let (rt, query_rt) = (inner.rt.clone(), inner.query_rt.clone());
+
+            let slot = inner
+                .handles
+                .entry(chain_id.clone())
+                .or_insert_with(|| PoolSlot {
+                    handle: Arc::new(OnceCell::new()),
+                    users: 0,
+                });
+
+            slot.users += 1;
+            trace!(chain = %chain_id, users = %slot.users, "acquiring chain runtime");
+
+            (slot.handle.clone(), rt, query_rt)
+        };
+
+        let result = handle.get_or_try_init(|| {
+            let handle = spawn_chain_runtime(config, chain_id, rt, query_rt)?;
+            trace!(chain = %chain_id, "spawned chain runtime");
+            Ok(handle)
+        });
+
+        match result {
+            Ok(handle) => Ok(handle.clone()),
+            Err(e) => {
+                // Give up the slot, for the runtime to be spawned again on the next attempt
+                self.release(chain_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Count one less user of the runtime for the given chain,
+    /// and shut it down if it is not used anymore.
+    fn release(&self, chain_id: &ChainId) {
+        let slot = {
+            let mut inner = self.inner.acquire_write();
+
+            let unused = match inner.handles.get_mut(chain_id) {
+                Some(slot) => {
+                    slot.users = slot.users.saturating_sub(1);
+                    slot.users == 0
+                }
+                None => false,
+            };
+
+            if !unused {
+                return;
+            }
+
+            inner.handles.remove(chain_id)
+        };
+
+        // Shut the runtime down without holding the lock of the pool
+        if let Some(handle) = slot.and_then(|slot| slot.handle.get().cloned()) {
+            if let Err(e) = handle.shutdown() {
+                warn!(chain = %chain_id, "chain runtime might have failed to shutdown properly: {}", e);
+            }
+        }
+    }
+}
+
 impl<Chain: ChainHandle> Registry<Chain> {
-    /// Construct a new [`Registry`] using the provided [`Config`]
+    /// Construct a new [`Registry`] using the provided [`Config`],
+    /// with its own pool of chain runtimes.
     pub fn new(config: Config) -> Self {
+        Self::with_pool(config, RuntimePool::new())
+    }
+
+    /// Construct a new [`Registry`] using the provided [`Config`], which
+    /// shares the chain runtimes of the given pool with the other registries
+    /// using it. Only the chains present in the config can be obtained
+    /// from the registry, even if the pool holds a runtime for others.
+    pub fn with_pool(config: Config, pool: RuntimePool<Chain>) -> Self {
         Self {
             config,
             handles: HashMap::new(),
-            rt: Arc::new(TokioRuntime::new().unwrap()),
-            query_rt: Arc::new(TokioRuntime::new().unwrap()),
+            pool,
         }
     }
 
+    /// Return the pool holding the chain runtimes of this registry.
+    pub fn pool(&self) -> &RuntimePool<Chain> {
+        &self.pool
+    }
+
     /// Return the size of the registry, i.e., the number of distinct chain runtimes.
     pub fn size(&self) -> usize {
         self.handles.len()
@@ -53,6 +218,12 @@ impl<Chain: ChainHandle> Registry<Chain> {
         self.handles.values()
     }
 
+    /// Get the [`ChainHandle`] associated with the given [`ChainId`],
+    /// if its runtime was spawned already.
+    pub fn get(&self, chain_id: &ChainId) -> Option<Chain> {
+        self.handles.get(chain_id).cloned()
+    }
+
     /// Get the [`ChainHandle`] associated with the given [`ChainId`].
     ///
     /// If there is no handle yet, this will first spawn the runtime and then
@@ -71,28 +242,58 @@ impl<Chain: ChainHandle> Registry<Chain> {
     /// Spawn a chain runtime for the chain with the given [`ChainId`],
     /// only if the registry does not contain a handle for that runtime already.
     ///
-    /// Returns whether or not the runtime was actually spawned.
+    /// If the runtime was already spawned in the pool for another registry,
+    /// then it is shared with that registry instead of being spawned again.
+    ///
+    /// Returns whether or not the registry obtained a new handle.
     pub fn spawn(&mut self, chain_id: &ChainId) -> Result<bool, SpawnError> {
-        if !self.handles.contains_key(chain_id) {
-            let handle = spawn_chain_runtime(
-                &self.config,
-                chain_id,
-                self.rt.clone(),
-                self.query_rt.clone(),
-            )?;
-            self.handles.insert(chain_id.clone(), handle);
-            trace!(chain = %chain_id, "spawned chain runtime");
-            Ok(true)
-        } else {
-            Ok(false)
+        if self.handles.contains_key(chain_id) {
+            return Ok(false);
+        }
+
+        if !self.config.has_chain(chain_id) {
+            return Err(SpawnError::missing_chain_config(chain_id.clone()));
         }
+
+        let handle = self.pool.acquire(&self.config, chain_id)?;
+        self.handles.insert(chain_id.clone(), handle);
+
+        Ok(true)
     }
 
-    /// Shutdown the runtime associated with the given chain identifier.
+    /// Release the runtime associated with the given chain identifier,
+    /// which is shut down unless it is still used by another registry.
     pub fn shutdown(&mut self, chain_id: &ChainId) {
-        if let Some(handle) = self.handles.remove(chain_id) {
-            if let Err(e) = handle.shutdown() {
-                warn!(chain = %chain_id, "chain runtime might have failed to shutdown properly: {}", e);
+        if self.handles.remove(chain_id).is_some() {
+            self.pool.release(chain_id);
+        }
+    }
+
+    /// A copy of this registry, using the same runtimes from its pool, e.g. to query
+    /// the chains without holding the lock of a [`SharedRegistry`] for long.
+    ///
+    /// The copy must be given back with [`Registry::absorb`], for this registry
+    /// to take over the runtimes it spawned.
+    pub fn snapshot(&self) -> Self {
+        let mut snapshot = Self::with_pool(self.config.clone(), self.pool.clone());
+
+        for chain_id in self.handles.keys() {
+            // Counts the copy as one more user of the runtime, which is reused from the pool
+            if let Ok(handle) = self.pool.acquire(&self.config, chain_id) {
+                snapshot.handles.insert(chain_id.clone(), handle);
+            }
+        }
+
+        snapshot
+    }
+
+    /// Take over the runtimes of the given copy of this registry, see [`Registry::snapshot`].
+    pub fn absorb(&mut self, snapshot: Self) {
+        for (chain_id, handle) in snapshot.handles {
+            if self.handles.contains_key(&chain_id) {
+                self.pool.release(&chain_id);
+            } else {
+                self.handles.insert(chain_id, handle);
             }
         }
     }
@@ -100,13 +301,24 @@ impl<Chain: ChainHandle> Registry<Chain> {
 
 impl<Chain: ChainHandle> SharedRegistry<Chain> {
     pub fn new(config: Config) -> Self {
-        let registry = Registry::new(config);
+        Self::with_pool(config, RuntimePool::new())
+    }
+
+    /// Create a new shared registry using the provided [`Config`], which shares
+    /// the chain runtimes of the given pool. See [`Registry::with_pool`].
+    pub fn with_pool(config: Config, pool: RuntimePool<Chain>) -> Self {
+        let registry = Registry::with_pool(config, pool);
 
         Self {
             registry: Arc::new(RwLock::new(registry)),
         }
     }
 
+    /// Return a handle to the pool holding the chain runtimes of this registry.
+    pub fn pool(&self) -> RuntimePool<Chain> {
+        self.read().pool().clone()
+    }
+
     pub fn get_or_spawn(&self, chain_id: &ChainId) -> Result<Chain, SpawnError> {
         self.registry.write().unwrap().get_or_spawn(chain_id)
     }
@@ -0,0 +1,96 @@
+//! Replay of the events recorded in a journal by the event monitors, with the
+//! queries made to the chains answered from fixtures and the messages submitted
+//! to them recorded instead of being sent, so that the handling of the events by
+//! the workers can be reproduced deterministically, e.g. to investigate a
+//! production incident or to write a regression test of the worker logic.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+
+use crossbeam_channel as channel;
+use tracing::warn;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::chain::handle::ChainHandle;
+use crate::config::Config;
+use crate::event::monitor::EventBatch;
+use crate::registry::RuntimePool;
+
+pub mod error;
+pub mod fixtures;
+pub mod journal;
+
+mod runtime;
+
+pub use error::Error;
+pub use runtime::{SubmittedLog, SubmittedMsgs};
+
+use fixtures::Fixtures;
+use runtime::ReplayRuntime;
+
+/// The replay runtimes of the chains present in the config.
+pub struct Replay<Chain: ChainHandle> {
+    pool: RuntimePool<Chain>,
+    senders: HashMap<ChainId, channel::Sender<EventBatch>>,
+    submitted: SubmittedLog,
+}
+
+impl<Chain: ChainHandle> Replay<Chain> {
+    /// Spawn a replay runtime for each chain in the config, answering
+    /// the queries made to it from its fixtures.
+    pub fn new(config: &Config, fixtures: &Fixtures) -> Self {
+        let pool = RuntimePool::new();
+        let submitted = SubmittedLog::default();
+        let mut senders = HashMap::new();
+
+        for chain_config in &config.chains {
+            let (handle, sender) = ReplayRuntime::spawn::<Chain>(
+                chain_config.clone(),
+                fixtures.for_chain(&chain_config.id),
+                submitted.clone(),
+            );
+
+            pool.insert(&chain_config.id, handle);
+            senders.insert(chain_config.id.clone(), sender);
+        }
+
+        Self {
+            pool,
+            senders,
+            submitted,
+        }
+    }
+
+    /// The pool holding the replay runtimes, for the supervisor
+    /// registry to obtain their handles from.
+    pub fn pool(&self) -> RuntimePool<Chain> {
+        self.pool.clone()
+    }
+
+    /// Replay the given batches of events, in order, to the subscribers of their chain.
+    /// Returns the number of batches replayed, skipping those of chains not in the config.
+    pub fn replay(&self, batches: Vec<EventBatch>) -> usize {
+        let mut replayed = 0;
+
+        for batch in batches {
+            match self.senders.get(&batch.chain_id) {
+                Some(sender) => {
+                    if sender.send(batch).is_ok() {
+                        replayed += 1;
+                    }
+                }
+                None => warn!(
+                    chain = %batch.chain_id,
+                    "skipping replay of events from a chain which is not in the config"
+                ),
+            }
+        }
+
+        replayed
+    }
+
+    /// The messages submitted to the chains so far, in the order they were submitted.
+    pub fn submitted(&self) -> Vec<SubmittedMsgs> {
+        self.submitted.entries()
+    }
+}
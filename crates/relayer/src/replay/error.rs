@@ -0,0 +1,20 @@
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    Error {
+        Io
+            { path: String }
+            [ TraceError<std::io::Error> ]
+            |e| { format_args!("failed to access file '{}'", e.path) },
+
+        DecodeJournal
+            { path: String, line: usize }
+            [ TraceError<serde_json::Error> ]
+            |e| { format_args!("invalid journal entry at line {} of '{}'", e.line, e.path) },
+
+        DecodeFixtures
+            { path: String }
+            [ TraceError<serde_json::Error> ]
+            |e| { format_args!("invalid fixtures in '{}'", e.path) },
+    }
+}
@@ -0,0 +1,268 @@
+//! Recorded answers to the queries made to the chains while replaying a journal,
+//! describing the state of each chain at the time of the incident being reproduced.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics03_connection::connection::IdentifiedConnectionEnd;
+use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use ibc::signer::Signer;
+use ibc::Height;
+
+use crate::client_state::{AnyClientState, IdentifiedAnyClientState};
+use crate::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
+
+use super::error::Error;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fixtures {
+    pub chains: Vec<ChainFixtures>,
+}
+
+impl Fixtures {
+    /// Load the fixtures from the JSON file at the given path.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::io(path.display().to_string(), e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| Error::decode_fixtures(path.display().to_string(), e))
+    }
+
+    /// The fixtures of the given chain, empty if there are none.
+    pub fn for_chain(&self, chain_id: &ChainId) -> ChainFixtures {
+        self.chains
+            .iter()
+            .find(|chain| &chain.chain_id == chain_id)
+            .cloned()
+            .unwrap_or_else(|| ChainFixtures {
+                chain_id: chain_id.clone(),
+                ..Default::default()
+            })
+    }
+}
+
+/// The sequences of the packets on a channel, e.g. with a commitment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacketSequences {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequences: Vec<Sequence>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsensusStateFixture {
+    pub client_id: ClientId,
+    pub height: Height,
+    pub consensus_state: AnyConsensusState,
+}
+
+/// The state of a chain, from which its queries are answered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainFixtures {
+    pub chain_id: ChainId,
+    /// The latest height of the chain, which defaults to the
+    /// height of the latest event replayed for this chain
+    pub latest_height: Option<Height>,
+    /// The timestamp of the latest block, in nanoseconds since the Unix epoch
+    pub timestamp: u64,
+    /// The signer of the messages submitted to the chain
+    pub signer: Option<Signer>,
+    pub clients: Vec<IdentifiedAnyClientState>,
+    pub consensus_states: Vec<ConsensusStateFixture>,
+    pub connections: Vec<IdentifiedConnectionEnd>,
+    pub channels: Vec<IdentifiedChannelEnd>,
+    /// The packets sent from this chain whose commitment is still stored,
+    /// ie. which were neither acknowledged nor timed out
+    pub packet_commitments: Vec<PacketSequences>,
+    /// The packets received by this chain and acknowledged
+    pub packet_acknowledgements: Vec<PacketSequences>,
+    /// The packets received by this chain
+    pub packet_receipts: Vec<PacketSequences>,
+}
+
+impl ChainFixtures {
+    pub fn client_state(&self, client_id: &ClientId) -> Option<&AnyClientState> {
+        self.clients
+            .iter()
+            .find(|client| &client.client_id == client_id)
+            .map(|client| &client.client_state)
+    }
+
+    pub fn consensus_state(
+        &self,
+        client_id: &ClientId,
+        height: Height,
+    ) -> Option<&AnyConsensusState> {
+        self.consensus_states
+            .iter()
+            .find(|state| &state.client_id == client_id && state.height == height)
+            .map(|state| &state.consensus_state)
+    }
+
+    pub fn consensus_states(&self, client_id: &ClientId) -> Vec<AnyConsensusStateWithHeight> {
+        self.consensus_states
+            .iter()
+            .filter(|state| &state.client_id == client_id)
+            .map(|state| AnyConsensusStateWithHeight {
+                height: state.height,
+                consensus_state: state.consensus_state.clone(),
+            })
+            .collect()
+    }
+
+    pub fn connection(&self, connection_id: &ConnectionId) -> Option<&IdentifiedConnectionEnd> {
+        self.connections
+            .iter()
+            .find(|connection| &connection.connection_id == connection_id)
+    }
+
+    pub fn client_connections(&self, client_id: &ClientId) -> Vec<ConnectionId> {
+        self.connections
+            .iter()
+            .filter(|connection| connection.connection_end.client_id() == client_id)
+            .map(|connection| connection.connection_id.clone())
+            .collect()
+    }
+
+    pub fn channel(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<&IdentifiedChannelEnd> {
+        self.channels
+            .iter()
+            .find(|channel| &channel.port_id == port_id && &channel.channel_id == channel_id)
+    }
+
+    pub fn connection_channels(&self, connection_id: &ConnectionId) -> Vec<IdentifiedChannelEnd> {
+        self.channels
+            .iter()
+            .filter(|channel| channel.channel_end.connection_hops().first() == Some(connection_id))
+            .cloned()
+            .collect()
+    }
+
+    /// The state of the client underlying the given channel.
+    pub fn channel_client_state(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<IdentifiedAnyClientState> {
+        let channel = self.channel(port_id, channel_id)?;
+        let connection_id = channel.channel_end.connection_hops().first()?;
+        let connection = self.connection(connection_id)?;
+        let client_id = connection.connection_end.client_id();
+
+        self.client_state(client_id)
+            .map(|client_state| IdentifiedAnyClientState {
+                client_id: client_id.clone(),
+                client_state: client_state.clone(),
+            })
+    }
+
+    pub fn packet_commitments(&self, port_id: &PortId, channel_id: &ChannelId) -> Vec<Sequence> {
+        sequences(&self.packet_commitments, port_id, channel_id)
+    }
+
+    pub fn packet_acknowledgements(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Vec<Sequence> {
+        sequences(&self.packet_acknowledgements, port_id, channel_id)
+    }
+
+    /// The given packets sent to this chain which it did not receive.
+    pub fn unreceived_packets(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequences_sent: &[Sequence],
+    ) -> Vec<Sequence> {
+        let received = sequences(&self.packet_receipts, port_id, channel_id);
+
+        sequences_sent
+            .iter()
+            .filter(|sequence| !received.contains(sequence))
+            .copied()
+            .collect()
+    }
+
+    /// The given packets sent from this chain whose acknowledgement was not
+    /// received, ie. whose commitment is still stored.
+    pub fn unreceived_acknowledgements(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequences_acked: &[Sequence],
+    ) -> Vec<Sequence> {
+        let commitments = self.packet_commitments(port_id, channel_id);
+
+        sequences_acked
+            .iter()
+            .filter(|sequence| commitments.contains(sequence))
+            .copied()
+            .collect()
+    }
+}
+
+fn sequences(all: &[PacketSequences], port_id: &PortId, channel_id: &ChannelId) -> Vec<Sequence> {
+    all.iter()
+        .filter(|packets| &packets.port_id == port_id && &packets.channel_id == channel_id)
+        .flat_map(|packets| packets.sequences.iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn unreceived_packets_and_acks() {
+        let (port_id, channel_id) = (
+            PortId::transfer(),
+            ChannelId::from_str("channel-0").unwrap(),
+        );
+
+        let packets = |sequences: &[u64]| {
+            vec![PacketSequences {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequences: sequences.iter().copied().map(Sequence::from).collect(),
+            }]
+        };
+
+        let fixtures = ChainFixtures {
+            packet_commitments: packets(&[3, 4]),
+            packet_receipts: packets(&[1, 2]),
+            ..Default::default()
+        };
+
+        let seqs = |sequences: &[u64]| -> Vec<Sequence> {
+            sequences.iter().copied().map(Sequence::from).collect()
+        };
+
+        assert_eq!(
+            fixtures.unreceived_packets(&port_id, &channel_id, &seqs(&[1, 2, 3])),
+            seqs(&[3])
+        );
+
+        assert_eq!(
+            fixtures.unreceived_acknowledgements(&port_id, &channel_id, &seqs(&[2, 3, 4])),
+            seqs(&[3, 4])
+        );
+
+        let other = ChannelId::from_str("channel-1").unwrap();
+        assert!(fixtures.packet_commitments(&port_id, &other).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+//! Journals of the events received from the chains over their websocket,
+//! stored as one JSON-encoded [`JournalEntry`] per line.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tendermint_rpc::event::Event as RpcEvent;
+use tracing::warn;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::chain::tracking::TrackingId;
+use crate::event::monitor::{sort_events, EventBatch};
+use crate::event::rpc::get_all_events;
+use crate::util::line_log::LineLog;
+use crate::util::state_file;
+
+use super::error::Error;
+
+/// The journal the events are recorded to, if recording is enabled.
+static RECORDER: LineLog<JournalEntry> = LineLog::new("event journal");
+
+/// An event received from a chain, as recorded in the journal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub chain_id: ChainId,
+    pub event: RpcEvent,
+}
+
+/// Record all the events received from now on by the event monitors
+/// of the process into the journal at the given path, appending
+/// to it if it exists already.
+///
+/// An existing journal which is corrupted, e.g. whose last entry was truncated
+/// by a crash, is moved aside first and a new journal is started instead.
+pub fn record_to(path: &Path) -> Result<(), Error> {
+    if let Ok(content) = std::fs::read_to_string(path) {
+        if let Err(e) = parse_journal(&content, &path.display().to_string()) {
+            let aside = state_file::move_aside(path)
+                .map_err(|e| Error::io(path.display().to_string(), e))?;
+
+            warn!(
+                "{}, moved the journal aside to {} and starting a new one",
+                e,
+                aside.display()
+            );
+        }
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| Error::io(path.display().to_string(), e))?;
+
+    if !RECORDER.start(file) {
+        warn!(
+            "the events are already recorded, not recording them to {}",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Record an event received from the given chain, if recording is enabled.
+pub fn record(chain_id: &ChainId, event: &RpcEvent) {
+    if !RECORDER.is_recording() {
+        return;
+    }
+
+    RECORDER.record(JournalEntry {
+        chain_id: chain_id.clone(),
+        event: event.clone(),
+    });
+}
+
+/// Read the entries of the journal at the given path.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, Error> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| Error::io(path.display().to_string(), e))?;
+
+    parse_journal(&content, &path.display().to_string())
+}
+
+/// Parse the entries of a journal, skipping the blank lines.
+pub fn parse_journal(content: &str, path: &str) -> Result<Vec<JournalEntry>, Error> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| Error::decode_journal(path.to_string(), idx + 1, e))
+        })
+        .collect()
+}
+
+/// Extract the IBC events from the journal entries, as the event monitor does,
+/// grouping the consecutive events from the same chain at the same height in a batch.
+pub fn into_batches(entries: Vec<JournalEntry>) -> Vec<EventBatch> {
+    let mut batches: Vec<EventBatch> = Vec::new();
+
+    for entry in entries {
+        let events = get_all_events(&entry.chain_id, entry.event).unwrap_or_default();
+
+        for event in events {
+            match batches.last_mut() {
+                Some(batch) if batch.chain_id == entry.chain_id && batch.height == event.height => {
+                    batch.events.push(event)
+                }
+                _ => batches.push(EventBatch {
+                    chain_id: entry.chain_id.clone(),
+                    tracking_id: TrackingId::new_uuid(),
+                    height: event.height,
+                    events: vec![event],
+                }),
+            }
+        }
+    }
+
+    for batch in batches.iter_mut() {
+        sort_events(&mut batch.events);
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+    use std::collections::BTreeMap;
+
+    use tendermint::abci::tag::{Key, Tag, Value};
+    use tendermint::abci::Event as AbciEvent;
+    use tendermint_rpc::event::{EventData, TxInfo, TxResult};
+
+    use crate::event::monitor::queries;
+
+    fn cross_chain_query_event(height: i64, query_id: &str) -> RpcEvent {
+        let tag = |key: &str, value: &str| Tag {
+            key: Key::from_str(key).unwrap(),
+            value: Value::from_str(value).unwrap(),
+        };
+
+        RpcEvent {
+            query: queries::ibc_query().to_string(),
+            data: EventData::Tx {
+                tx_result: TxInfo {
+                    height,
+                    index: None,
+                    tx: vec![],
+                    result: TxResult {
+                        log: None,
+                        gas_wanted: None,
+                        gas_used: None,
+                        events: vec![AbciEvent {
+                            type_str: "cross_chain_query".to_string(),
+                            attributes: vec![
+                                tag("query_id", query_id),
+                                tag("query_path", "https://example.com/query"),
+                                tag("query_height", "1"),
+                                tag("query_timeout_height", "100"),
+                                tag("query_timeout_timestamp", "123456789"),
+                            ],
+                        }],
+                    },
+                },
+            },
+            events: Some(BTreeMap::new()),
+        }
+    }
+
+    #[test]
+    fn replay_journal() {
+        let chain_id = ChainId::new("chain".to_string(), 1);
+
+        let journal = [(10, "1"), (10, "2"), (11, "3")]
+            .into_iter()
+            .map(|(height, query_id)| JournalEntry {
+                chain_id: chain_id.clone(),
+                event: cross_chain_query_event(height, query_id),
+            })
+            .map(|entry| serde_json::to_string(&entry).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let entries = parse_journal(&journal, "journal.jsonl").unwrap();
+        assert_eq!(entries.len(), 3);
+
+        let batches = into_batches(entries);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].height.revision_height(), 10);
+        assert_eq!(batches[0].events.len(), 2);
+        assert_eq!(batches[1].height.revision_height(), 11);
+        assert_eq!(batches[1].events.len(), 1);
+    }
+
+    #[test]
+    fn invalid_journal_line() {
+        let err = parse_journal("\nnot json\n", "journal.jsonl").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
@@ -0,0 +1,416 @@
+use alloc::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+use crossbeam_channel as channel;
+use serde::Serialize;
+use tracing::{debug, warn, Span};
+
+use ibc::core::ics04_channel::packet::Packet;
+use ibc::core::ics23_commitment::commitment::CommitmentProofBytes;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::events::{IbcEvent, WithBlockDataType};
+use ibc::proofs::Proofs;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+
+use crate::chain::endpoint::{ChainStatus, HealthCheck};
+use crate::chain::handle::{ChainHandle, ChainRequest, ReplyTo};
+use crate::chain::requests::{QueryPacketEventDataRequest, QueryTxRequest};
+use crate::chain::tracking::TrackedMsgs;
+use crate::config::priority::Priority;
+use crate::config::ChainConfig;
+use crate::error::Error;
+use crate::event::bus::EventBus;
+use crate::event::monitor::{EventBatch, Result as MonitorResult};
+use crate::event::IbcEventWithHeight;
+
+use super::fixtures::ChainFixtures;
+
+/// The messages which were submitted to a chain during a replay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SubmittedMsgs {
+    pub chain_id: ChainId,
+    pub tracking_id: String,
+    /// The type URLs of the messages, in the order they were submitted
+    pub type_urls: Vec<String>,
+}
+
+/// The log of the messages submitted to all the chains during a replay,
+/// shared by their runtimes, in the order they were submitted.
+#[derive(Clone, Debug, Default)]
+pub struct SubmittedLog {
+    entries: Arc<Mutex<Vec<SubmittedMsgs>>>,
+}
+
+impl SubmittedLog {
+    fn record(&self, chain_id: &ChainId, tracked_msgs: &TrackedMsgs) {
+        let entry = SubmittedMsgs {
+            chain_id: chain_id.clone(),
+            tracking_id: tracked_msgs.tracking_id().to_string(),
+            type_urls: tracked_msgs
+                .messages()
+                .iter()
+                .map(|msg| msg.type_url.clone())
+                .collect(),
+        };
+
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub fn entries(&self) -> Vec<SubmittedMsgs> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+/// A stand-in for the runtime of a chain, which answers the requests of its
+/// handles from fixtures, broadcasts the replayed events to its subscribers,
+/// and records the messages submitted to it instead of sending them.
+pub struct ReplayRuntime {
+    config: ChainConfig,
+    fixtures: ChainFixtures,
+    request_receiver: channel::Receiver<(Span, Priority, ChainRequest)>,
+    batch_receiver: channel::Receiver<EventBatch>,
+    event_bus: EventBus<Arc<MonitorResult<EventBatch>>>,
+    /// The events replayed so far, to answer the queries for past packet events
+    history: Vec<IbcEventWithHeight>,
+    latest_height: Option<Height>,
+    submitted: SubmittedLog,
+}
+
+impl ReplayRuntime {
+    /// Spawn the runtime for the given chain, returning a handle to it and
+    /// the sending side of the channel through which the events are replayed.
+    pub fn spawn<Handle: ChainHandle>(
+        config: ChainConfig,
+        fixtures: ChainFixtures,
+        submitted: SubmittedLog,
+    ) -> (Handle, channel::Sender<EventBatch>) {
+        let (request_sender, request_receiver) = channel::unbounded();
+        let (batch_sender, batch_receiver) = channel::unbounded();
+
+        let handle = Handle::new(config.id.clone(), request_sender);
+
+        let runtime = Self {
+            latest_height: fixtures.latest_height,
+            config,
+            fixtures,
+            request_receiver,
+            batch_receiver,
+            event_bus: EventBus::new(),
+            history: Vec::new(),
+            submitted,
+        };
+
+        thread::spawn(move || runtime.run());
+
+        (handle, batch_sender)
+    }
+
+    fn chain_id(&self) -> &ChainId {
+        &self.config.id
+    }
+
+    fn run(mut self) {
+        loop {
+            channel::select! {
+                recv(self.batch_receiver) -> batch => match batch {
+                    Ok(batch) => self.replay(batch),
+                    // The whole journal was replayed
+                    Err(_) => self.batch_receiver = channel::never(),
+                },
+                recv(self.request_receiver) -> request => match request {
+                    Ok((span, _, request)) => {
+                        let _span = span.entered();
+
+                        if !self.handle(request) {
+                            break;
+                        }
+                    }
+                    // All the handles were dropped
+                    Err(_) => break,
+                },
+            }
+        }
+    }
+
+    fn replay(&mut self, batch: EventBatch) {
+        if self
+            .latest_height
+            .map_or(true, |height| height < batch.height)
+        {
+            self.latest_height = Some(batch.height);
+        }
+
+        self.history.extend(batch.events.iter().cloned());
+        self.event_bus.broadcast(Arc::new(Ok(batch)));
+    }
+
+    fn missing<T>(&self, description: impl Into<String>) -> Result<T, Error> {
+        Err(Error::missing_fixture(
+            self.chain_id().clone(),
+            description.into(),
+        ))
+    }
+
+    fn latest_height(&self) -> Result<Height, Error> {
+        match self.latest_height {
+            Some(height) => Ok(height),
+            None => self.missing("the latest height"),
+        }
+    }
+
+    /// Build proofs with a placeholder content at the height following the given one,
+    /// as the messages built while replaying are recorded instead of being verified.
+    fn placeholder_proofs(&self, height: Height) -> Result<Proofs, Error> {
+        let proof =
+            CommitmentProofBytes::try_from(b"replay".to_vec()).map_err(Error::malformed_proof)?;
+
+        Proofs::new(proof, None, None, None, height.increment()).map_err(Error::malformed_proof)
+    }
+
+    /// The past packet events matching the given request.
+    fn packet_events(&self, request: &QueryPacketEventDataRequest) -> Vec<IbcEventWithHeight> {
+        let matches = |packet: &Packet| {
+            packet.source_port == request.source_port_id
+                && packet.source_channel == request.source_channel_id
+                && packet.destination_port == request.destination_port_id
+                && packet.destination_channel == request.destination_channel_id
+                && request.sequences.contains(&packet.sequence)
+        };
+
+        self.history
+            .iter()
+            .filter(|event| match (&request.event_id, &event.event) {
+                (WithBlockDataType::SendPacket, IbcEvent::SendPacket(e)) => matches(&e.packet),
+                (WithBlockDataType::WriteAck, IbcEvent::WriteAcknowledgement(e)) => {
+                    matches(&e.packet)
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn record(&self, tracked_msgs: &TrackedMsgs) {
+        debug!(
+            chain = %self.chain_id(),
+            "recording {} message(s) instead of submitting them",
+            tracked_msgs.messages().len()
+        );
+
+        self.submitted.record(self.chain_id(), tracked_msgs);
+    }
+
+    /// Handle the given request, returning whether the runtime should keep running.
+    fn handle(&mut self, request: ChainRequest) -> bool {
+        let fixtures = &self.fixtures;
+
+        match request {
+            ChainRequest::Shutdown { reply_to } => {
+                reply(reply_to, Ok(()));
+                return false;
+            }
+
+            ChainRequest::HealthCheck { reply_to } => reply(reply_to, Ok(HealthCheck::Healthy)),
+
+            ChainRequest::CheckKey { reply_to } => reply(reply_to, Ok(HealthCheck::Healthy)),
+
+            ChainRequest::Subscribe { reply_to } => reply(reply_to, Ok(self.event_bus.subscribe())),
+
+            ChainRequest::SendMessagesAndWaitCommit {
+                tracked_msgs,
+                reply_to,
+            } => {
+                self.record(&tracked_msgs);
+                reply(reply_to, Ok(vec![]))
+            }
+
+            ChainRequest::SendMessagesAndWaitCheckTx {
+                tracked_msgs,
+                reply_to,
+            } => {
+                self.record(&tracked_msgs);
+                reply(reply_to, Ok(vec![]))
+            }
+
+            ChainRequest::Config { reply_to } => reply(reply_to, Ok(self.config.clone())),
+
+            ChainRequest::Signer { reply_to } => {
+                let signer = match &fixtures.signer {
+                    Some(signer) => Ok(signer.clone()),
+                    None => self.missing("the signer"),
+                };
+
+                reply(reply_to, signer)
+            }
+
+            ChainRequest::QueryApplicationStatus { reply_to } => {
+                let status = self.latest_height().map(|height| ChainStatus {
+                    height,
+                    timestamp: Timestamp::from_nanoseconds(fixtures.timestamp)
+                        .unwrap_or_else(|_| Timestamp::none()),
+                });
+
+                reply(reply_to, status)
+            }
+
+            ChainRequest::QueryClients { reply_to, .. } => {
+                reply(reply_to, Ok(fixtures.clients.clone()))
+            }
+
+            ChainRequest::QueryClientState {
+                request, reply_to, ..
+            } => {
+                let client_state = match fixtures.client_state(&request.client_id) {
+                    Some(client_state) => Ok((client_state.clone(), None)),
+                    None => self.missing(format!("the state of client '{}'", request.client_id)),
+                };
+
+                reply(reply_to, client_state)
+            }
+
+            ChainRequest::QueryClientConnections { request, reply_to } => reply(
+                reply_to,
+                Ok(fixtures.client_connections(&request.client_id)),
+            ),
+
+            ChainRequest::QueryConsensusState {
+                request, reply_to, ..
+            } => {
+                let consensus_state =
+                    match fixtures.consensus_state(&request.client_id, request.consensus_height) {
+                        Some(consensus_state) => Ok((consensus_state.clone(), None)),
+                        None => self.missing(format!(
+                            "the consensus state of client '{}' at height {}",
+                            request.client_id, request.consensus_height
+                        )),
+                    };
+
+                reply(reply_to, consensus_state)
+            }
+
+            ChainRequest::QueryConsensusStates { request, reply_to } => {
+                reply(reply_to, Ok(fixtures.consensus_states(&request.client_id)))
+            }
+
+            ChainRequest::QueryConnection {
+                request, reply_to, ..
+            } => {
+                let connection = match fixtures.connection(&request.connection_id) {
+                    Some(connection) => Ok((connection.connection_end.clone(), None)),
+                    None => self.missing(format!("connection '{}'", request.connection_id)),
+                };
+
+                reply(reply_to, connection)
+            }
+
+            ChainRequest::QueryConnections { reply_to, .. } => {
+                reply(reply_to, Ok(fixtures.connections.clone()))
+            }
+
+            ChainRequest::QueryConnectionChannels { request, reply_to } => reply(
+                reply_to,
+                Ok(fixtures.connection_channels(&request.connection_id)),
+            ),
+
+            ChainRequest::QueryChannels { reply_to, .. } => {
+                reply(reply_to, Ok(fixtures.channels.clone()))
+            }
+
+            ChainRequest::QueryChannel {
+                request, reply_to, ..
+            } => {
+                let channel = match fixtures.channel(&request.port_id, &request.channel_id) {
+                    Some(channel) => Ok((channel.channel_end.clone(), None)),
+                    None => self.missing(format!(
+                        "channel '{}/{}'",
+                        request.port_id, request.channel_id
+                    )),
+                };
+
+                reply(reply_to, channel)
+            }
+
+            ChainRequest::QueryChannelClientState { request, reply_to } => reply(
+                reply_to,
+                Ok(fixtures.channel_client_state(&request.port_id, &request.channel_id)),
+            ),
+
+            ChainRequest::BuildChannelProofs {
+                height, reply_to, ..
+            } => reply(reply_to, self.placeholder_proofs(height)),
+
+            ChainRequest::BuildPacketProofs {
+                height, reply_to, ..
+            } => reply(reply_to, self.placeholder_proofs(height)),
+
+            ChainRequest::QueryPacketCommitments { request, reply_to } => {
+                let commitments = self.latest_height().map(|height| {
+                    (
+                        fixtures.packet_commitments(&request.port_id, &request.channel_id),
+                        height,
+                    )
+                });
+
+                reply(reply_to, commitments)
+            }
+
+            ChainRequest::QueryUnreceivedPackets { request, reply_to } => reply(
+                reply_to,
+                Ok(fixtures.unreceived_packets(
+                    &request.port_id,
+                    &request.channel_id,
+                    &request.packet_commitment_sequences,
+                )),
+            ),
+
+            ChainRequest::QueryPacketAcknowledgements { request, reply_to } => {
+                let acks = self.latest_height().map(|height| {
+                    let acks = fixtures
+                        .packet_acknowledgements(&request.port_id, &request.channel_id)
+                        .into_iter()
+                        .filter(|sequence| {
+                            request.packet_commitment_sequences.is_empty()
+                                || request.packet_commitment_sequences.contains(sequence)
+                        })
+                        .collect();
+
+                    (acks, height)
+                });
+
+                reply(reply_to, acks)
+            }
+
+            ChainRequest::QueryUnreceivedAcknowledgement { request, reply_to } => reply(
+                reply_to,
+                Ok(fixtures.unreceived_acknowledgements(
+                    &request.port_id,
+                    &request.channel_id,
+                    &request.packet_ack_sequences,
+                )),
+            ),
+
+            ChainRequest::QueryPacketEventDataFromTxs {
+                request: QueryTxRequest::Packet(request),
+                reply_to,
+            } => reply(reply_to, Ok(self.packet_events(&request))),
+
+            request => {
+                // Dropping the request closes its reply channel, so that the caller gets an error
+                warn!(
+                    chain = %self.chain_id(),
+                    "no fixture to replay request, dropping it: {:?}",
+                    request
+                );
+            }
+        }
+
+        true
+    }
+}
+
+fn reply<T>(reply_to: ReplyTo<T>, result: Result<T, Error>) {
+    // The caller may have given up on the reply
+    let _ = reply_to.send(result);
+}
@@ -0,0 +1,208 @@
+//! Replenishment of the fee token of the relayer account on a chain, by swapping the
+//! reserve token of the account on a DEX of the chain when the balance of the fee token
+//! drops below a threshold, see [`FeeReplenishment`].
+//!
+//! The swaps are bounded by the configuration: each of them swaps a fixed amount of the
+//! reserve token, fails if it would receive less than a minimum amount of the fee token,
+//! and at most a given number of them are made over any period of 24 hours.
+
+use alloc::collections::VecDeque;
+use core::time::Duration;
+use std::time::Instant;
+
+use tracing::{info, warn};
+
+use ibc::signer::Signer;
+use ibc_proto::cosmos::base::v1beta1::Coin;
+use ibc_proto::google::protobuf::Any;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::tracking::TrackedMsgs;
+use crate::config::fee_replenishment::{FeeReplenishment, SwapModule};
+use crate::error::Error;
+use crate::event::IbcEventWithHeight;
+
+pub const POOL_MANAGER_SWAP_TYPE_URL: &str = "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn";
+pub const GAMM_SWAP_TYPE_URL: &str = "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn";
+
+/// The period over which the number of swaps is limited.
+const SWAP_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The `MsgSwapExactAmountIn` message of Osmosis, identical in its `poolmanager` and `gamm` modules.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSwapExactAmountIn {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, repeated, tag = "2")]
+    pub routes: Vec<SwapAmountInRoute>,
+    #[prost(message, optional, tag = "3")]
+    pub token_in: Option<Coin>,
+    #[prost(string, tag = "4")]
+    pub token_out_min_amount: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SwapAmountInRoute {
+    #[prost(uint64, tag = "1")]
+    pub pool_id: u64,
+    #[prost(string, tag = "2")]
+    pub token_out_denom: String,
+}
+
+/// The message swapping the reserve token of the given account into the given fee token.
+pub fn swap_msg(replenishment: &FeeReplenishment, fee_denom: &str, sender: &Signer) -> Any {
+    let msg = MsgSwapExactAmountIn {
+        sender: sender.to_string(),
+        routes: vec![SwapAmountInRoute {
+            pool_id: replenishment.pool_id,
+            token_out_denom: fee_denom.to_string(),
+        }],
+        token_in: Some(Coin {
+            denom: replenishment.reserve_denom.clone(),
+            amount: replenishment.swap_amount.to_string(),
+        }),
+        token_out_min_amount: replenishment.min_amount_out.to_string(),
+    };
+
+    let type_url = match replenishment.module {
+        SwapModule::PoolManager => POOL_MANAGER_SWAP_TYPE_URL,
+        SwapModule::Gamm => GAMM_SWAP_TYPE_URL,
+    };
+
+    let mut buf_msg = Vec::new();
+    prost::Message::encode(&msg, &mut buf_msg).unwrap();
+
+    Any {
+        type_url: type_url.to_string(),
+        value: buf_msg,
+    }
+}
+
+/// Replenishes the fee token of the relayer account on a chain, within the limits of its configuration.
+#[derive(Debug)]
+pub struct Replenisher {
+    replenishment: FeeReplenishment,
+    fee_denom: String,
+    /// When the swaps of the last 24 hours were made
+    swaps: VecDeque<Instant>,
+}
+
+impl Replenisher {
+    pub fn new(replenishment: FeeReplenishment, fee_denom: String) -> Self {
+        Self {
+            replenishment,
+            fee_denom,
+            swaps: VecDeque::new(),
+        }
+    }
+
+    /// Swap the reserve token into the fee token if the given balance of the fee
+    /// token is below the threshold, and the limit on the number of swaps allows it.
+    /// Returns the events of the swap, if one was made.
+    pub fn replenish(
+        &mut self,
+        chain: &impl ChainHandle,
+        balance: u128,
+    ) -> Result<Option<Vec<IbcEventWithHeight>>, Error> {
+        if balance >= u128::from(self.replenishment.threshold) {
+            return Ok(None);
+        }
+
+        if !self.allows_swap(Instant::now()) {
+            warn!(
+                %balance,
+                denom = %self.fee_denom,
+                "fee token balance is below the replenishment threshold, \
+                but the daily limit of {} swaps was reached",
+                self.replenishment.max_swaps_per_day
+            );
+
+            return Ok(None);
+        }
+
+        info!(
+            %balance,
+            denom = %self.fee_denom,
+            "fee token balance is below the replenishment threshold, swapping {}{} for at least {}{}",
+            self.replenishment.swap_amount,
+            self.replenishment.reserve_denom,
+            self.replenishment.min_amount_out,
+            self.fee_denom
+        );
+
+        // The swap counts towards the limit even if it fails, so
+        // that a failing swap is not retried over and over again
+        self.swaps.push_back(Instant::now());
+
+        let signer = chain.get_signer()?;
+        let msg = swap_msg(&self.replenishment, &self.fee_denom, &signer);
+
+        let events =
+            chain.send_messages_and_wait_commit(TrackedMsgs::new_static(vec![msg], "replenish"))?;
+
+        Ok(Some(events))
+    }
+
+    /// Whether another swap can be made at the given instant, forgetting the swaps older than 24 hours.
+    fn allows_swap(&mut self, now: Instant) -> bool {
+        while let Some(&oldest) = self.swaps.front() {
+            if now.duration_since(oldest) < SWAP_WINDOW {
+                break;
+            }
+
+            self.swaps.pop_front();
+        }
+
+        self.swaps.len() < self.replenishment.max_swaps_per_day as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    fn replenishment() -> FeeReplenishment {
+        FeeReplenishment {
+            threshold: 1_000_000,
+            reserve_denom: "uatom".to_string(),
+            module: SwapModule::PoolManager,
+            pool_id: 1,
+            swap_amount: 5_000_000,
+            min_amount_out: 2_000_000,
+            max_swaps_per_day: 2,
+        }
+    }
+
+    #[test]
+    fn swaps_are_limited_per_day() {
+        let mut replenisher = Replenisher::new(replenishment(), "uosmo".to_string());
+
+        let start = Instant::now();
+        replenisher.swaps.push_back(start);
+        assert!(replenisher.allows_swap(start));
+
+        replenisher.swaps.push_back(start + Duration::from_secs(60));
+        assert!(!replenisher.allows_swap(start + Duration::from_secs(120)));
+
+        // The first swap is forgotten after 24 hours
+        assert!(replenisher.allows_swap(start + SWAP_WINDOW));
+        assert_eq!(replenisher.swaps.len(), 1);
+    }
+
+    #[test]
+    fn swap_msg_encoding() {
+        let sender = Signer::from_str("osmo1relayer").unwrap();
+        let msg = swap_msg(&replenishment(), "uosmo", &sender);
+
+        assert_eq!(msg.type_url, POOL_MANAGER_SWAP_TYPE_URL);
+
+        let decoded: MsgSwapExactAmountIn = prost::Message::decode(msg.value.as_slice()).unwrap();
+        assert_eq!(decoded.sender, "osmo1relayer");
+        assert_eq!(decoded.routes[0].pool_id, 1);
+        assert_eq!(decoded.routes[0].token_out_denom, "uosmo");
+        assert_eq!(decoded.token_in.unwrap().amount, "5000000");
+        assert_eq!(decoded.token_out_min_amount, "2000000");
+    }
+}
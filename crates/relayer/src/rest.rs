@@ -3,9 +3,12 @@ use tracing::{error, trace};
 
 use crate::{
     config::Config,
+    object::Object,
     rest::request::ReplySender,
     rest::request::{Request, VersionInfo},
+    supervisor::cmd::{ObjectSpec, PathSpec},
     supervisor::dump_state::SupervisorState,
+    util::recent_errors,
 };
 
 pub mod request;
@@ -31,6 +34,10 @@ pub type Receiver = crossbeam_channel::Receiver<Request>;
 //  e.g., adjusting chain config, removing chains, etc.
 pub enum Command {
     DumpState(ReplySender<SupervisorState>),
+    StartObject(ObjectSpec, ReplySender<Vec<Object>>),
+    StopObject(ObjectSpec, ReplySender<Vec<Object>>),
+    PausePath(PathSpec, ReplySender<Vec<Object>>),
+    ResumePath(PathSpec, ReplySender<Vec<Object>>),
 }
 
 /// Process incoming REST requests.
@@ -64,6 +71,14 @@ pub fn process_incoming_requests(config: &Config, channel: &Receiver) -> Option<
                     .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
             }
 
+            Request::RecentErrors { reply_to } => {
+                trace!("RecentErrors");
+
+                reply_to
+                    .send(Ok(recent_errors::recent()))
+                    .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+            }
+
             Request::GetChain { chain_id, reply_to } => {
                 trace!("GetChain {}", chain_id);
 
@@ -82,6 +97,30 @@ pub fn process_incoming_requests(config: &Config, channel: &Receiver) -> Option<
 
                 return Some(Command::DumpState(reply_to));
             }
+
+            Request::StartObject { object, reply_to } => {
+                trace!("StartObject {:?}", object);
+
+                return Some(Command::StartObject(object, reply_to));
+            }
+
+            Request::StopObject { object, reply_to } => {
+                trace!("StopObject {:?}", object);
+
+                return Some(Command::StopObject(object, reply_to));
+            }
+
+            Request::PausePath { path, reply_to } => {
+                trace!("PausePath {}", path);
+
+                return Some(Command::PausePath(path, reply_to));
+            }
+
+            Request::ResumePath { path, reply_to } => {
+                trace!("ResumePath {}", path);
+
+                return Some(Command::ResumePath(path, reply_to));
+            }
         },
         Err(e) => {
             if !matches!(e, TryRecvError::Empty) {
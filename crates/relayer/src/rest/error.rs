@@ -23,6 +23,18 @@ pub enum RestApiError {
     #[error("failed while parsing the request body into a chain configuration: {0}")]
     InvalidChainConfig(String),
 
+    #[error("failed while parsing the request body into an object specification: {0}")]
+    InvalidObjectSpec(String),
+
+    #[error("failed to start or stop relaying on the requested object: {0}")]
+    ObjectControl(String),
+
+    #[error("failed while parsing the request body into a path specification: {0}")]
+    InvalidPathSpec(String),
+
+    #[error("failed to pause or resume relaying on the requested path: {0}")]
+    PathControl(String),
+
     #[error("not implemented")]
     Unimplemented,
 }
@@ -36,6 +48,10 @@ impl RestApiError {
             RestApiError::ChainConfigNotFound(_) => "ChainConfigNotFound",
             RestApiError::InvalidChainId(_, _) => "InvalidChainId",
             RestApiError::InvalidChainConfig(_) => "InvalidChainConfig",
+            RestApiError::InvalidObjectSpec(_) => "InvalidObjectSpec",
+            RestApiError::ObjectControl(_) => "ObjectControl",
+            RestApiError::InvalidPathSpec(_) => "InvalidPathSpec",
+            RestApiError::PathControl(_) => "PathControl",
             RestApiError::Unimplemented => "Unimplemented",
         }
     }
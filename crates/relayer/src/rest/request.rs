@@ -2,7 +2,14 @@ use serde::Serialize;
 
 use ibc::core::ics24_host::identifier::ChainId;
 
-use crate::{config::ChainConfig, rest::RestApiError, supervisor::dump_state::SupervisorState};
+use crate::{
+    config::ChainConfig,
+    object::Object,
+    rest::RestApiError,
+    supervisor::cmd::{ObjectSpec, PathSpec},
+    supervisor::dump_state::SupervisorState,
+    util::recent_errors::RecentError,
+};
 
 pub type ReplySender<T> = crossbeam_channel::Sender<Result<T, RestApiError>>;
 pub type ReplyReceiver<T> = crossbeam_channel::Receiver<Result<T, RestApiError>>;
@@ -28,6 +35,10 @@ pub enum Request {
         reply_to: ReplySender<SupervisorState>,
     },
 
+    RecentErrors {
+        reply_to: ReplySender<Vec<RecentError>>,
+    },
+
     GetChains {
         reply_to: ReplySender<Vec<ChainId>>,
     },
@@ -36,4 +47,24 @@ pub enum Request {
         chain_id: ChainId,
         reply_to: ReplySender<ChainConfig>,
     },
+
+    StartObject {
+        object: ObjectSpec,
+        reply_to: ReplySender<Vec<Object>>,
+    },
+
+    StopObject {
+        object: ObjectSpec,
+        reply_to: ReplySender<Vec<Object>>,
+    },
+
+    PausePath {
+        path: PathSpec,
+        reply_to: ReplySender<Vec<Object>>,
+    },
+
+    ResumePath {
+        path: PathSpec,
+        reply_to: ReplySender<Vec<Object>>,
+    },
 }
@@ -0,0 +1,367 @@
+//! A high-level client of a chain, for the applications using the relayer as a library
+//! to query chains, send transfers and open channels, without having to deal with the
+//! chain runtimes, registries and supervisors underneath.
+//!
+//! ```ignore
+//! let hub = IbcClient::new(config.clone(), &ChainId::from_string("cosmoshub-4")).await?;
+//! let osmosis = IbcClient::new(config, &ChainId::from_string("osmosis-1")).await?;
+//!
+//! let channel = hub.create_channel(&osmosis, ChannelRequest::transfer()).await?;
+//! let events = hub.transfer(&osmosis, TransferRequest::new(channel.a_channel_id, amount, "uatom")).await?;
+//!
+//! hub.shutdown().await?;
+//! osmosis.shutdown().await?;
+//! ```
+//!
+//! The methods are async, and run the blocking operations of the relayer on the
+//! blocking threads of the Tokio runtime of the application, which they must
+//! therefore be called from. The chain runtimes of all the clients are spawned in
+//! a pool of their own, shared by the clients of the same chain, and shut down
+//! once none of them uses it anymore.
+
+use core::time::Duration;
+
+use flex_error::{define_error, TraceError};
+use once_cell::sync::Lazy;
+
+use ibc::applications::transfer::Amount;
+use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd, Order};
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortId};
+use ibc::events::IbcEvent;
+use ibc::Height;
+
+use crate::account::Balance;
+use crate::chain::handle::{BaseChainHandle, ChainHandle};
+use crate::chain::requests::{
+    IncludeProof, PageRequest, QueryChannelRequest, QueryChannelsRequest, QueryHeight,
+};
+use crate::channel::{Channel, ChannelError};
+use crate::config::default::connection_delay;
+use crate::config::Config;
+use crate::connection::{Connection, ConnectionError};
+use crate::error::Error as RelayerError;
+use crate::foreign_client::{ForeignClient, ForeignClientError};
+use crate::registry::{Registry, RuntimePool};
+use crate::spawn::SpawnError;
+use crate::transfer::{build_and_send_transfer_messages, TransferError, TransferOptions};
+
+/// The chain runtimes of all the clients, which are never dropped
+/// so that no Tokio runtime is dropped from an async context.
+static POOL: Lazy<RuntimePool<BaseChainHandle>> = Lazy::new(RuntimePool::new);
+
+define_error! {
+    SdkError {
+        Spawn
+            [ SpawnError ]
+            |_| { "failed to spawn the chain runtime" },
+
+        Query
+            { chain_id: ChainId }
+            [ RelayerError ]
+            |e| { format!("query to chain {} failed", e.chain_id) },
+
+        Transfer
+            [ TransferError ]
+            |_| { "transfer failed" },
+
+        ForeignClient
+            [ ForeignClientError ]
+            |_| { "failed to create the clients of the channel" },
+
+        Connection
+            [ ConnectionError ]
+            |_| { "failed to open the connection of the channel" },
+
+        Channel
+            [ ChannelError ]
+            |_| { "failed to open the channel" },
+
+        Join
+            [ TraceError<tokio::task::JoinError> ]
+            |_| { "the operation panicked or was cancelled" },
+    }
+}
+
+/// A client of a chain, see the [module documentation](self).
+#[derive(Debug)]
+pub struct IbcClient {
+    chain: BaseChainHandle,
+    /// Taken when the client is shut down or dropped
+    registry: Option<Registry<BaseChainHandle>>,
+}
+
+/// A transfer of tokens over a channel.
+#[derive(Clone, Debug)]
+pub struct TransferRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub amount: Amount,
+    pub denom: String,
+    /// The account receiving the tokens, the one of the relayer on the counterparty chain if `None`
+    pub receiver: Option<String>,
+    /// How long, according to the counterparty chain, the transfer can take to be received
+    pub timeout: Duration,
+    pub memo: Option<String>,
+}
+
+impl TransferRequest {
+    /// A transfer over the given channel of the `transfer` port, timing out after ten minutes.
+    pub fn new(channel_id: ChannelId, amount: Amount, denom: impl Into<String>) -> Self {
+        Self {
+            port_id: PortId::transfer(),
+            channel_id,
+            amount,
+            denom: denom.into(),
+            receiver: None,
+            timeout: Duration::from_secs(600),
+            memo: None,
+        }
+    }
+}
+
+/// A channel to open, along with the clients and connection underneath.
+#[derive(Clone, Debug)]
+pub struct ChannelRequest {
+    pub port_id: PortId,
+    pub counterparty_port_id: PortId,
+    pub ordering: Order,
+    /// The version to propose, the default one for the ports if `None`
+    pub version: Option<Version>,
+    pub connection_delay: Duration,
+}
+
+impl ChannelRequest {
+    /// An unordered channel between the `transfer` ports.
+    pub fn transfer() -> Self {
+        Self {
+            port_id: PortId::transfer(),
+            counterparty_port_id: PortId::transfer(),
+            ordering: Order::Unordered,
+            version: Some(Version::ics20()),
+            connection_delay: connection_delay(),
+        }
+    }
+}
+
+/// The identifiers of the channel opened by [`IbcClient::create_channel`], with `a`
+/// the chain of the client which opened it, and `b` its counterparty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenedChannel {
+    pub a_connection_id: ConnectionId,
+    pub a_channel_id: ChannelId,
+    pub b_connection_id: ConnectionId,
+    pub b_channel_id: ChannelId,
+}
+
+impl IbcClient {
+    /// A client of the chain with the given identifier, which must be in the given config.
+    pub async fn new(config: Config, chain_id: &ChainId) -> Result<Self, SdkError> {
+        let chain_id = chain_id.clone();
+
+        // Spawning the chain runtime connects to the chain, and blocks until it is done
+        run_blocking(move || {
+            let mut registry = Registry::with_pool(config, POOL.clone());
+            let chain = registry.get_or_spawn(&chain_id).map_err(SdkError::spawn)?;
+
+            Ok(Self {
+                chain,
+                registry: Some(registry),
+            })
+        })
+        .await
+    }
+
+    /// Release the chain runtime, and wait for it to be shut down unless other clients
+    /// of the chain still use it. Dropping the client releases it in the background.
+    pub async fn shutdown(mut self) -> Result<(), SdkError> {
+        let chain_id = self.chain.id();
+
+        if let Some(mut registry) = self.registry.take() {
+            run_blocking(move || {
+                registry.shutdown(&chain_id);
+                Ok(())
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain.id()
+    }
+
+    /// The handle to the chain runtime underneath, for the operations this client does not offer.
+    pub fn handle(&self) -> &BaseChainHandle {
+        &self.chain
+    }
+
+    pub async fn latest_height(&self) -> Result<Height, SdkError> {
+        self.query(|chain| chain.query_latest_height()).await
+    }
+
+    /// The balance of the key of the relayer on the chain, or of the given key.
+    pub async fn balance(&self, key_name: Option<String>) -> Result<Balance, SdkError> {
+        self.query(move |chain| chain.query_balance(key_name)).await
+    }
+
+    pub async fn channel(
+        &self,
+        port_id: PortId,
+        channel_id: ChannelId,
+    ) -> Result<ChannelEnd, SdkError> {
+        self.query(move |chain| {
+            let request = QueryChannelRequest {
+                port_id,
+                channel_id,
+                height: QueryHeight::Latest,
+            };
+
+            chain
+                .query_channel(request, IncludeProof::No)
+                .map(|(channel, _)| channel)
+        })
+        .await
+    }
+
+    pub async fn channels(&self) -> Result<Vec<IdentifiedChannelEnd>, SdkError> {
+        self.query(|chain| {
+            chain.query_channels(QueryChannelsRequest {
+                pagination: Some(PageRequest::all()),
+            })
+        })
+        .await
+    }
+
+    /// Send the given transfer of tokens from this chain to the given counterparty
+    /// chain, and return the events of the transaction once it is committed.
+    pub async fn transfer(
+        &self,
+        counterparty: &IbcClient,
+        request: TransferRequest,
+    ) -> Result<Vec<IbcEvent>, SdkError> {
+        let src_chain = self.chain.clone();
+        let dst_chain = counterparty.chain.clone();
+
+        let opts = TransferOptions {
+            packet_src_port_id: request.port_id,
+            packet_src_channel_id: request.channel_id,
+            amount: request.amount,
+            denom: request.denom,
+            receiver: request.receiver,
+            timeout_height_offset: 0,
+            timeout_duration: request.timeout,
+            number_msgs: 1,
+            memo: request.memo,
+        };
+
+        run_blocking(move || {
+            build_and_send_transfer_messages(&src_chain, &dst_chain, &opts)
+                .map_err(SdkError::transfer)
+        })
+        .await
+    }
+
+    /// Open a channel between this chain and the given counterparty chain, over
+    /// a new connection between new clients, as `hermes create channel` does.
+    pub async fn create_channel(
+        &self,
+        counterparty: &IbcClient,
+        request: ChannelRequest,
+    ) -> Result<OpenedChannel, SdkError> {
+        let chain_a = self.chain.clone();
+        let chain_b = counterparty.chain.clone();
+
+        run_blocking(move || {
+            let client_a = ForeignClient::new(chain_a.clone(), chain_b.clone())
+                .map_err(SdkError::foreign_client)?;
+            let client_b =
+                ForeignClient::new(chain_b, chain_a).map_err(SdkError::foreign_client)?;
+
+            let connection = Connection::new(client_a, client_b, request.connection_delay)
+                .map_err(SdkError::connection)?;
+
+            let a_connection_id = connection.a_side.connection_id().cloned();
+            let b_connection_id = connection.b_side.connection_id().cloned();
+
+            let channel = Channel::new(
+                connection,
+                request.ordering,
+                request.port_id,
+                request.counterparty_port_id,
+                request.version,
+            )
+            .map_err(SdkError::channel)?;
+
+            // The identifiers are all assigned once the handshakes complete
+            let expect = "identifier assigned by the completed handshake";
+
+            Ok(OpenedChannel {
+                a_connection_id: a_connection_id.expect(expect),
+                a_channel_id: channel.a_side.channel_id().cloned().expect(expect),
+                b_connection_id: b_connection_id.expect(expect),
+                b_channel_id: channel.b_side.channel_id().cloned().expect(expect),
+            })
+        })
+        .await
+    }
+
+    async fn query<T, F>(&self, query: F) -> Result<T, SdkError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&BaseChainHandle) -> Result<T, RelayerError> + Send + 'static,
+    {
+        let chain = self.chain.clone();
+
+        run_blocking(move || query(&chain).map_err(|e| SdkError::query(chain.id(), e))).await
+    }
+}
+
+impl Drop for IbcClient {
+    fn drop(&mut self) {
+        if let Some(mut registry) = self.registry.take() {
+            let chain_id = self.chain.id();
+            let shutdown = move || registry.shutdown(&chain_id);
+
+            // Do not block the thread dropping the client, which may run async tasks
+            match tokio::runtime::Handle::try_current() {
+                Ok(rt) => drop(rt.spawn_blocking(shutdown)),
+                Err(_) => shutdown(),
+            }
+        }
+    }
+}
+
+/// Run the given blocking operation on the blocking threads of the current Tokio runtime.
+async fn run_blocking<T, F>(operation: F) -> Result<T, SdkError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, SdkError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(operation)
+        .await
+        .map_err(SdkError::join)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::spawn::SpawnErrorDetail;
+
+    #[test]
+    fn client_of_unknown_chain() {
+        let chain_id = ChainId::from_string("ibc-0");
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let e = rt
+            .block_on(IbcClient::new(Config::default(), &chain_id))
+            .unwrap_err();
+
+        assert!(matches!(
+            e.detail(),
+            SdkErrorDetail::Spawn(e) if matches!(e.source, SpawnErrorDetail::MissingChainConfig(_))
+        ));
+    }
+}
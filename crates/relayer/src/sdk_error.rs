@@ -1,4 +1,6 @@
 use flex_error::define_error;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use tendermint::abci::Code;
 use tendermint_rpc::endpoint::broadcast::tx_commit::TxResult;
 
@@ -21,6 +23,16 @@ define_error! {
                 format_args!("unknown SDK error with code space: {}, code: {}", e.codespace, e.code)
             },
 
+        Module
+            {
+                codespace: String,
+                code: u32,
+                description: &'static str,
+            }
+            | e | {
+                format_args!("{} (code space: {}, code: {})", e.description, e.codespace, e.code)
+            },
+
         UnknownTxSync
             { code: u32 }
             | e | { format_args!("unknown TX sync response error: {}", e.code) },
@@ -163,6 +175,194 @@ fn client_error_from_code(code: u32) -> ClientError {
     }
 }
 
+/// How the relayer handles the messages of a transaction which failed with a given error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The same messages may succeed if they are built again and resubmitted,
+    /// e.g. after the account sequence is refreshed or the client is updated
+    Retryable,
+    /// Some message of the batch cannot succeed, e.g. because the packet was already
+    /// relayed by another relayer, but the others can once it is left out
+    FatalForMessage,
+    /// None of the messages of the batch can succeed until the operator steps in,
+    /// e.g. because the fees are too low or the client is frozen
+    FatalForBatch,
+}
+
+/// The description and class of the errors defined by ibc-go and cosmos-sdk
+/// in the given code space, following the Go code at:
+///  - cosmos-sdk/types/errors/errors.go (code space `sdk`)
+///  - ibc-go/modules/core/02-client/types/errors.go (code space `client`)
+///  - ibc-go/modules/core/03-connection/types/errors.go (code space `connection`)
+///  - ibc-go/modules/core/04-channel/types/errors.go (code space `channel`)
+fn lookup(codespace: &str, code: u32) -> Option<(&'static str, ErrorClass)> {
+    use ErrorClass::*;
+
+    let entry = match (codespace, code) {
+        ("sdk", 1) => ("internal error", Retryable),
+        ("sdk", 2) => ("tx parse error", FatalForBatch),
+        ("sdk", 3) => ("invalid sequence", Retryable),
+        ("sdk", 4) => ("unauthorized", FatalForBatch),
+        ("sdk", 5) => ("insufficient funds", FatalForBatch),
+        ("sdk", 6) => ("unknown request", FatalForMessage),
+        ("sdk", 7) => ("invalid address", FatalForBatch),
+        ("sdk", 8) => ("invalid pubkey", FatalForBatch),
+        ("sdk", 9) => ("unknown address", FatalForBatch),
+        ("sdk", 10) => ("invalid coins", FatalForMessage),
+        ("sdk", 11) => ("out of gas", FatalForBatch),
+        ("sdk", 12) => ("memo too large", FatalForBatch),
+        ("sdk", 13) => ("insufficient fee", FatalForBatch),
+        ("sdk", 14) => ("maximum number of signatures exceeded", FatalForBatch),
+        ("sdk", 15) => ("no signatures supplied", FatalForBatch),
+        ("sdk", 16) => ("failed to marshal JSON bytes", FatalForMessage),
+        ("sdk", 17) => ("failed to unmarshal JSON bytes", FatalForMessage),
+        ("sdk", 18) => ("invalid request", FatalForMessage),
+        ("sdk", 19) => ("tx already in mempool", Retryable),
+        ("sdk", 20) => ("mempool is full", Retryable),
+        ("sdk", 21) => ("tx too large", FatalForBatch),
+        ("sdk", 22) => ("key not found", FatalForBatch),
+        ("sdk", 23) => ("invalid account password", FatalForBatch),
+        ("sdk", 24) => (
+            "tx intended signer does not match the given signer",
+            FatalForBatch,
+        ),
+        ("sdk", 25) => ("invalid gas adjustment", FatalForBatch),
+        ("sdk", 26) => ("invalid height", Retryable),
+        ("sdk", 27) => ("invalid version", FatalForMessage),
+        ("sdk", 28) => ("invalid chain-id", FatalForBatch),
+        ("sdk", 29) => ("invalid type", FatalForMessage),
+        ("sdk", 30) => ("tx timeout height", Retryable),
+        ("sdk", 31) => ("unknown extension options", FatalForBatch),
+        ("sdk", 32) => ("incorrect account sequence", Retryable),
+        ("sdk", 33) => ("failed packing protobuf message to Any", FatalForMessage),
+        ("sdk", 34) => (
+            "failed unpacking protobuf message from Any",
+            FatalForMessage,
+        ),
+        ("sdk", 35) => ("internal logic error", FatalForMessage),
+        ("sdk", 36) => ("conflict", Retryable),
+        ("sdk", 37) => ("feature not supported", FatalForMessage),
+        ("sdk", 38) => ("not found", FatalForMessage),
+        ("sdk", 39) => ("Internal IO error", Retryable),
+        ("sdk", 40) => ("error in app.toml", FatalForBatch),
+        ("sdk", 41) => ("invalid gas limit", FatalForBatch),
+
+        ("client", 2) => ("light client already exists", FatalForMessage),
+        ("client", 3) => ("light client is invalid", FatalForBatch),
+        ("client", 4) => ("light client not found", FatalForBatch),
+        ("client", 5) => ("light client is frozen due to misbehaviour", FatalForBatch),
+        ("client", 6) => ("invalid client metadata", FatalForMessage),
+        ("client", 7) => ("consensus state not found", Retryable),
+        ("client", 8) => ("invalid consensus state", FatalForMessage),
+        ("client", 9) => ("client type not found", FatalForBatch),
+        ("client", 10) => ("invalid client type", FatalForBatch),
+        ("client", 11) => ("commitment root not found", Retryable),
+        ("client", 12) => ("invalid client header", Retryable),
+        ("client", 13) => ("invalid light client misbehaviour", FatalForMessage),
+        // The proofs may have been built at a height the client is not updated to yet
+        ("client", 14) => ("client state verification failed", Retryable),
+        ("client", 15) => ("client consensus state verification failed", Retryable),
+        ("client", 16) => ("connection state verification failed", Retryable),
+        ("client", 17) => ("channel state verification failed", Retryable),
+        ("client", 18) => ("packet commitment verification failed", Retryable),
+        ("client", 19) => ("packet acknowledgement verification failed", Retryable),
+        ("client", 20) => ("packet receipt verification failed", Retryable),
+        ("client", 21) => ("next sequence receive verification failed", Retryable),
+        ("client", 22) => ("self consensus state not found", FatalForMessage),
+        ("client", 23) => ("unable to update light client", Retryable),
+        ("client", 24) => ("invalid update client proposal", FatalForMessage),
+        ("client", 25) => ("invalid client upgrade", FatalForMessage),
+        ("client", 26) => ("invalid height", Retryable),
+        ("client", 27) => ("invalid client state substitute", FatalForMessage),
+        ("client", 28) => ("invalid upgrade proposal", FatalForMessage),
+        ("client", 29) => ("client is not active", FatalForBatch),
+
+        ("connection", 2) => ("connection already exists", FatalForMessage),
+        ("connection", 3) => ("connection not found", FatalForBatch),
+        ("connection", 4) => ("light client connection paths not found", FatalForBatch),
+        ("connection", 5) => (
+            "connection path is not associated to the given light client",
+            FatalForMessage,
+        ),
+        ("connection", 6) => ("invalid connection state", FatalForMessage),
+        ("connection", 7) => ("invalid counterparty connection", FatalForMessage),
+        ("connection", 8) => ("invalid connection", FatalForMessage),
+        ("connection", 9) => ("invalid connection version", FatalForMessage),
+        ("connection", 10) => ("connection version negotiation failed", FatalForMessage),
+        ("connection", 11) => ("invalid connection identifier", FatalForMessage),
+
+        ("channel", 2) => ("channel already exists", FatalForMessage),
+        ("channel", 3) => ("channel not found", FatalForBatch),
+        ("channel", 4) => ("invalid channel", FatalForMessage),
+        ("channel", 5) => ("invalid channel state", FatalForMessage),
+        ("channel", 6) => ("invalid channel ordering", FatalForMessage),
+        ("channel", 7) => ("invalid counterparty channel", FatalForMessage),
+        ("channel", 8) => ("invalid channel capability", FatalForMessage),
+        ("channel", 9) => ("channel capability not found", FatalForBatch),
+        ("channel", 10) => ("sequence send not found", FatalForBatch),
+        ("channel", 11) => ("sequence receive not found", FatalForBatch),
+        ("channel", 12) => ("sequence acknowledgement not found", FatalForBatch),
+        ("channel", 13) => ("invalid packet", FatalForMessage),
+        // The packet must be timed out on its source chain instead
+        ("channel", 14) => ("packet timeout", FatalForMessage),
+        ("channel", 15) => ("too many connection hops", FatalForMessage),
+        ("channel", 16) => ("invalid acknowledgement", FatalForMessage),
+        ("channel", 17) => ("acknowledgement for packet already exists", FatalForMessage),
+        ("channel", 18) => ("invalid channel identifier", FatalForMessage),
+        ("channel", 19) => ("packet already received", FatalForMessage),
+        ("channel", 20) => ("packet commitment not found", FatalForMessage),
+        // The previous packets on the ordered channel must be relayed first
+        ("channel", 21) => ("packet sequence is out of order", Retryable),
+        ("channel", 22) => (
+            "message is redundant, no-op will be performed",
+            FatalForMessage,
+        ),
+        ("channel", 23) => ("invalid channel version", FatalForMessage),
+        ("channel", 24) => ("packet has not been sent", FatalForMessage),
+        ("channel", 25) => ("invalid packet timeout", FatalForMessage),
+
+        _ => return None,
+    };
+
+    Some(entry)
+}
+
+/// How to handle the messages of a transaction which failed with the given error.
+/// The unknown errors are assumed to be retryable.
+pub fn error_class(codespace: &str, code: u32) -> ErrorClass {
+    lookup(codespace, code).map_or(ErrorClass::Retryable, |(_, class)| class)
+}
+
+/// The error reported in the message of a [`ChainError`](ibc::events::IbcEvent::ChainError)
+/// event, as formatted by the relayer: `codespace=<codespace>, code=Err(<code>)`.
+/// The errors reported by CheckTx do not include the code space, as the responses of
+/// `broadcast_tx_sync` do not report it; these are assumed to be in the `sdk` code space,
+/// as they are mostly reported by the ante handlers of the cosmos-sdk.
+pub fn parse_chain_error(message: &str) -> Option<(String, u32)> {
+    static CODE_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?:codespace=(?P<codespace>[^,\s]*), )?code=Err\((?P<code>\d+)\)"#).unwrap()
+    });
+
+    let captures = CODE_RE.captures(message)?;
+    let code = captures["code"].parse().ok()?;
+
+    let codespace = captures
+        .name("codespace")
+        .map(|codespace| codespace.as_str())
+        .filter(|codespace| !codespace.is_empty())
+        .unwrap_or("sdk");
+
+    Some((codespace.to_string(), code))
+}
+
+/// How to handle the messages of a transaction which failed
+/// with the error reported in the given `ChainError` message.
+pub fn chain_error_class(message: &str) -> ErrorClass {
+    parse_chain_error(message).map_or(ErrorClass::Retryable, |(codespace, code)| {
+        error_class(&codespace, code)
+    })
+}
+
 // Converts the error in a TxResult into SdkError with the same
 // mapping as defined in ibc-go and cosmos-sdk. This assumes the
 // target chain we are interacting with are using cosmos-sdk and ibc-go.
@@ -177,8 +377,10 @@ pub fn sdk_error_from_tx_result(result: &TxResult) -> SdkError {
             if codespace == "client" {
                 SdkError::client(client_error_from_code(code))
             } else {
-                // TODO: Implement mapping for other codespaces in ibc-go
-                SdkError::unknown_sdk(codespace, code)
+                match lookup(&codespace, code) {
+                    Some((description, _)) => SdkError::module(codespace, code, description),
+                    None => SdkError::unknown_sdk(codespace, code),
+                }
             }
         }
     }
@@ -195,6 +397,42 @@ pub fn sdk_error_from_tx_sync_error_code(code: u32) -> SdkError {
         // on Hermes side. We'll inform the user to check for misconfiguration.
         11 => SdkError::out_of_gas(code),
         13 => SdkError::insufficient_fee(code),
-        _ => SdkError::unknown_tx_sync(code),
+        _ => match lookup("sdk", code) {
+            Some((description, _)) => SdkError::module("sdk".to_string(), code, description),
+            None => SdkError::unknown_tx_sync(code),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_chain_errors() {
+        let deliver_tx = "deliver_tx for 7E2A reports error: codespace=channel, code=Err(19), \
+                          log=\"packet already received\"";
+
+        assert_eq!(
+            parse_chain_error(deliver_tx),
+            Some(("channel".to_string(), 19))
+        );
+        assert_eq!(chain_error_class(deliver_tx), ErrorClass::FatalForMessage);
+
+        // The errors reported by CheckTx are in the `sdk` code space
+        let check_tx = "check_tx (broadcast_tx_sync) on chain ibc-0 for Tx hash 7E2A \
+                        reports error: code=Err(13), log=\"insufficient fees\"";
+
+        assert_eq!(parse_chain_error(check_tx), Some(("sdk".to_string(), 13)));
+        assert_eq!(chain_error_class(check_tx), ErrorClass::FatalForBatch);
+
+        assert_eq!(
+            chain_error_class("deliver_tx for 7E2A reports error: codespace=sdk, code=Err(32)"),
+            ErrorClass::Retryable
+        );
+
+        // Unknown errors are retried
+        assert_eq!(chain_error_class("connection reset"), ErrorClass::Retryable);
+        assert_eq!(error_class("wasm", 5), ErrorClass::Retryable);
     }
 }
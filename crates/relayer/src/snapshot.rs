@@ -0,0 +1,255 @@
+//! Export of the complete IBC state of a chain at a given height, i.e. its clients along
+//! with their consensus states, its connections and its channels along with the packets
+//! pending on them, for audits, migrations and offline analysis.
+//!
+//! The clients, connections and channels are listed at the latest height of the chain,
+//! and their states, along with the consensus states of the clients and the packet
+//! commitments and acknowledgements of the channels, are then queried at the height
+//! of the export. The objects which cannot be queried at that height, e.g. because
+//! they were created after it, are reported as skipped instead.
+
+use serde::Serialize;
+use tracing::debug;
+
+use ibc::core::ics03_connection::connection::IdentifiedConnectionEnd;
+use ibc::core::ics04_channel::channel::{IdentifiedChannelEnd, State};
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc::Height;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{
+    IncludeProof, PageRequest, QueryChannelRequest, QueryChannelsRequest, QueryClientStateRequest,
+    QueryClientStatesRequest, QueryConnectionRequest, QueryConnectionsRequest,
+    QueryConsensusStatesRequest, QueryHeight, QueryNextSequenceReceiveRequest,
+    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest,
+};
+use crate::client_state::AnyClientState;
+use crate::consensus_state::AnyConsensusStateWithHeight;
+use crate::error::Error;
+
+/// The IBC state of a chain.
+#[derive(Clone, Debug, Serialize)]
+pub struct IbcSnapshot {
+    pub chain_id: ChainId,
+    /// The height at which the states of the objects were queried
+    pub height: Height,
+    pub clients: Vec<ClientSnapshot>,
+    pub connections: Vec<IdentifiedConnectionEnd>,
+    pub channels: Vec<ChannelSnapshot>,
+    /// The objects which could not be queried at the height of the snapshot
+    pub skipped: Vec<SkippedObject>,
+}
+
+/// A client, along with the consensus states it stores.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientSnapshot {
+    pub client_id: ClientId,
+    pub client_state: AnyClientState,
+    pub consensus_states: Vec<AnyConsensusStateWithHeight>,
+}
+
+/// A channel, along with the packets pending on it.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChannelSnapshot {
+    #[serde(flatten)]
+    pub channel: IdentifiedChannelEnd,
+    /// The sequences of the packets sent on the channel whose commitment was not cleared yet
+    pub packet_commitments: Vec<Sequence>,
+    /// The sequences of the packets received on the channel whose acknowledgement is stored
+    pub packet_acknowledgements: Vec<Sequence>,
+    /// The sequence of the next packet expected, on ordered channels
+    pub next_sequence_receive: Option<Sequence>,
+}
+
+/// An object which could not be exported, e.g. because it did not exist yet at
+/// the height of the snapshot.
+#[derive(Clone, Debug, Serialize)]
+pub struct SkippedObject {
+    pub object: String,
+    pub reason: String,
+}
+
+impl SkippedObject {
+    fn new(object: String, reason: impl ToString) -> Self {
+        let reason = reason.to_string();
+
+        debug!("skipping {}: {}", object, reason);
+
+        Self { object, reason }
+    }
+}
+
+/// Export the IBC state of the given chain at the given height.
+pub fn export_state(chain: &impl ChainHandle, height: QueryHeight) -> Result<IbcSnapshot, Error> {
+    let height = match height {
+        QueryHeight::Latest => chain.query_latest_height()?,
+        QueryHeight::Specific(height) => height,
+    };
+
+    let at = QueryHeight::Specific(height);
+    let mut skipped = Vec::new();
+
+    let mut clients = Vec::new();
+    for client in chain.query_clients(QueryClientStatesRequest {
+        pagination: Some(PageRequest::all()),
+    })? {
+        let request = QueryClientStateRequest {
+            client_id: client.client_id.clone(),
+            height: at,
+        };
+
+        let client_state = match chain.query_client_state(request, IncludeProof::No) {
+            Ok((client_state, _)) => client_state,
+            Err(e) => {
+                skipped.push(SkippedObject::new(
+                    format!("client {}", client.client_id),
+                    e,
+                ));
+                continue;
+            }
+        };
+
+        let consensus_states = match chain.query_consensus_states(QueryConsensusStatesRequest {
+            client_id: client.client_id.clone(),
+            pagination: Some(PageRequest::all()),
+            height: at,
+        }) {
+            Ok(consensus_states) => consensus_states,
+            Err(e) => {
+                skipped.push(SkippedObject::new(
+                    format!("consensus states of client {}", client.client_id),
+                    e,
+                ));
+                continue;
+            }
+        };
+
+        clients.push(ClientSnapshot {
+            client_id: client.client_id,
+            client_state,
+            consensus_states,
+        });
+    }
+
+    let mut connections = Vec::new();
+    for connection in chain.query_connections(QueryConnectionsRequest {
+        pagination: Some(PageRequest::all()),
+    })? {
+        let request = QueryConnectionRequest {
+            connection_id: connection.connection_id.clone(),
+            height: at,
+        };
+
+        match chain.query_connection(request, IncludeProof::No) {
+            Ok((connection_end, _)) => connections.push(IdentifiedConnectionEnd::new(
+                connection.connection_id,
+                connection_end,
+            )),
+            Err(e) => skipped.push(SkippedObject::new(
+                format!("connection {}", connection.connection_id),
+                e,
+            )),
+        }
+    }
+
+    let mut channels = Vec::new();
+    for channel in chain.query_channels(QueryChannelsRequest {
+        pagination: Some(PageRequest::all()),
+    })? {
+        let object = format!("channel {}/{}", channel.port_id, channel.channel_id);
+
+        let request = QueryChannelRequest {
+            port_id: channel.port_id.clone(),
+            channel_id: channel.channel_id.clone(),
+            height: at,
+        };
+
+        let channel_end = match chain.query_channel(request, IncludeProof::No) {
+            Ok((channel_end, _)) if channel_end.state != State::Uninitialized => channel_end,
+            Ok(_) => {
+                skipped.push(SkippedObject::new(object, "channel is uninitialized"));
+                continue;
+            }
+            Err(e) => {
+                skipped.push(SkippedObject::new(object, e));
+                continue;
+            }
+        };
+
+        let packet_commitments = chain.query_packet_commitments(QueryPacketCommitmentsRequest {
+            port_id: channel.port_id.clone(),
+            channel_id: channel.channel_id.clone(),
+            pagination: Some(PageRequest::all()),
+            height: at,
+        });
+
+        let packet_commitments = match packet_commitments {
+            Ok((packet_commitments, _)) => packet_commitments,
+            Err(e) => {
+                skipped.push(SkippedObject::new(
+                    format!("packet commitments of {}", object),
+                    e,
+                ));
+                continue;
+            }
+        };
+
+        let packet_acknowledgements =
+            chain.query_packet_acknowledgements(QueryPacketAcknowledgementsRequest {
+                port_id: channel.port_id.clone(),
+                channel_id: channel.channel_id.clone(),
+                pagination: Some(PageRequest::all()),
+                packet_commitment_sequences: Vec::new(),
+                height: at,
+            });
+
+        let packet_acknowledgements = match packet_acknowledgements {
+            Ok((packet_acknowledgements, _)) => packet_acknowledgements,
+            Err(e) => {
+                skipped.push(SkippedObject::new(
+                    format!("packet acknowledgements of {}", object),
+                    e,
+                ));
+                continue;
+            }
+        };
+
+        let next_sequence_receive = if channel_end.ordering.is_ordered() {
+            let request = QueryNextSequenceReceiveRequest {
+                port_id: channel.port_id.clone(),
+                channel_id: channel.channel_id.clone(),
+                height: at,
+            };
+
+            match chain.query_next_sequence_receive(request, IncludeProof::No) {
+                Ok((next_sequence_receive, _)) => Some(next_sequence_receive),
+                Err(e) => {
+                    skipped.push(SkippedObject::new(
+                        format!("next sequence receive of {}", object),
+                        e,
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        channels.push(ChannelSnapshot {
+            channel: IdentifiedChannelEnd::new(channel.port_id, channel.channel_id, channel_end),
+            packet_commitments,
+            packet_acknowledgements,
+            next_sequence_receive,
+        });
+    }
+
+    Ok(IbcSnapshot {
+        chain_id: chain.id(),
+        height,
+        clients,
+        connections,
+        channels,
+        skipped,
+    })
+}
@@ -0,0 +1,387 @@
+//! Log of the packet messages submitted by the relayer, stored as one JSON-encoded
+//! [`SubmissionEntry`] per line, from which the historical performance of the
+//! channels is reported, e.g. for capacity planning and SLA reporting.
+//!
+//! An entry is logged whenever the transactions built from a batch of packet events
+//! are confirmed, fail, or time out waiting for a confirmation. Only the transactions
+//! submitted by the workers which confirm them, i.e. when `tx_confirmation` is
+//! enabled, are logged.
+
+use core::time::Duration;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flex_error::{define_error, TraceError};
+use moka::sync::Cache as MokaCache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tendermint::abci::transaction::Hash as TxHash;
+use tracing::{info, warn};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+use crate::config::stats::StatsConfig;
+use crate::util::line_log::LineLog;
+use crate::util::state_file;
+
+/// Location of the log, relative to the home directory.
+pub const DEFAULT_LOG_PATH: &str = ".hermes/stats/submissions.jsonl";
+
+/// Maximum number of transactions whose gas is held until their submission is logged,
+/// for the transactions confirmed by queries which are not made by a worker.
+const MAX_PENDING_GAS: u64 = 10_000;
+
+/// How long the gas of a transaction is held until its submission is logged.
+const PENDING_GAS_TTL: Duration = Duration::from_secs(10 * 60);
+
+define_error! {
+    Error {
+        Io
+            { path: String }
+            [ TraceError<std::io::Error> ]
+            |e| { format_args!("failed to access file '{}'", e.path) },
+
+        Decode
+            { path: String, line: usize }
+            [ TraceError<serde_json::Error> ]
+            |e| { format_args!("invalid submission entry at line {} of '{}'", e.line, e.path) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode submission entry" },
+
+        NoHome
+            |_| { "home location is unavailable, the path of the log must be configured" },
+    }
+}
+
+/// The log the submissions are recorded to, if recording is enabled.
+static RECORDER: LineLog<SubmissionEntry> = LineLog::new("submission log");
+
+/// The gas used by the transactions confirmed, until their submission is logged.
+static GAS_USED: Lazy<MokaCache<TxHash, u64>> = Lazy::new(|| {
+    MokaCache::builder()
+        .max_capacity(MAX_PENDING_GAS)
+        .time_to_live(PENDING_GAS_TTL)
+        .build()
+});
+
+/// The transactions built from a batch of packet events and submitted
+/// to a chain, as recorded in the log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionEntry {
+    /// When the outcome of the submission was known, in seconds since the Unix epoch
+    pub time: u64,
+    /// The chain the transactions were submitted to
+    pub chain_id: ChainId,
+    pub counterparty_chain_id: ChainId,
+    /// The end of the channel on the chain the transactions were submitted to
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    /// Number of transactions submitted
+    pub txs: usize,
+    /// Number of transactions which failed or timed out waiting for a confirmation
+    pub failed_txs: usize,
+    /// Number of packets received, acknowledged or timed out by the confirmed transactions
+    pub packets: usize,
+    /// Time elapsed between the submission of the transactions and their outcome
+    pub latency_ms: u64,
+    /// Gas used by the confirmed transactions
+    pub gas_used: u64,
+}
+
+impl SubmissionEntry {
+    /// Whether the entry is about the given end of a channel, on any port if none is given.
+    pub fn is_on(
+        &self,
+        chain_id: &ChainId,
+        port_id: Option<&PortId>,
+        channel_id: &ChannelId,
+    ) -> bool {
+        self.chain_id == *chain_id
+            && self.channel_id == *channel_id
+            && port_id.map_or(true, |port_id| self.port_id == *port_id)
+    }
+}
+
+/// The path of the log configured, or the default one in the home directory.
+pub fn log_path(config: &StatsConfig) -> Result<PathBuf, Error> {
+    match &config.path {
+        Some(path) => Ok(path.clone()),
+        None => dirs_next::home_dir()
+            .map(|home| home.join(DEFAULT_LOG_PATH))
+            .ok_or_else(Error::no_home),
+    }
+}
+
+/// Record all the submissions made from now on by the workers of the process into
+/// the log configured, appending to it if it exists already, if the log is enabled.
+///
+/// The entries older than the configured retention are pruned from an existing log,
+/// and a log which is corrupted, e.g. whose last entry was truncated by a crash, is
+/// moved aside first and a new log is started instead.
+pub fn record_to(config: &StatsConfig) -> Result<(), Error> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let path = log_path(config)?;
+    let display = path.display().to_string();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io(display.clone(), e))?;
+    }
+
+    if let Ok(content) = fs::read_to_string(&path) {
+        match parse_log(&content, &display) {
+            Ok(entries) => prune(&path, entries, config.retention, now())?,
+            Err(e) => {
+                let aside =
+                    state_file::move_aside(&path).map_err(|e| Error::io(display.clone(), e))?;
+
+                warn!(
+                    "{}, moved the log aside to {} and starting a new one",
+                    e,
+                    aside.display()
+                );
+            }
+        }
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| Error::io(display.clone(), e))?;
+
+    if !RECORDER.start(file) {
+        warn!(
+            "the submissions are already logged, not logging them to {}",
+            display
+        );
+        return Ok(());
+    }
+
+    info!("logging the submissions of packet messages to {}", display);
+
+    Ok(())
+}
+
+/// Rewrite the log at the given path without the entries older than the retention.
+fn prune(
+    path: &Path,
+    entries: Vec<SubmissionEntry>,
+    retention: Duration,
+    now: u64,
+) -> Result<(), Error> {
+    let count = entries.len();
+    let kept = retain_since(entries, now.saturating_sub(retention.as_secs()));
+
+    if kept.len() == count {
+        return Ok(());
+    }
+
+    let mut content = String::new();
+    for entry in &kept {
+        content.push_str(&serde_json::to_string(entry).map_err(Error::encode)?);
+        content.push('\n');
+    }
+
+    let display = path.display().to_string();
+    let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+
+    fs::write(&tmp, content).map_err(|e| Error::io(tmp.display().to_string(), e))?;
+    fs::rename(&tmp, path).map_err(|e| Error::io(display, e))?;
+
+    Ok(())
+}
+
+/// Whether the submissions are recorded.
+pub fn is_recording() -> bool {
+    RECORDER.is_recording()
+}
+
+/// Note the gas used by a confirmed transaction, for it to be
+/// logged along with its submission, if recording is enabled.
+pub fn note_gas_used(tx_hash: &TxHash, gas_used: u64) {
+    if !is_recording() {
+        return;
+    }
+
+    GAS_USED.insert(*tx_hash, gas_used);
+}
+
+/// The gas noted for the given transactions, forgetting about it.
+pub fn take_gas_used<'a>(tx_hashes: impl IntoIterator<Item = &'a TxHash>) -> u64 {
+    tx_hashes
+        .into_iter()
+        .filter_map(|hash| {
+            let gas_used = GAS_USED.get(hash);
+            GAS_USED.invalidate(hash);
+            gas_used
+        })
+        .sum()
+}
+
+/// Record a submission, if recording is enabled.
+pub fn record(entry: &SubmissionEntry) {
+    RECORDER.record(entry.clone());
+}
+
+/// The current time, in seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Read the entries of the log at the given path.
+pub fn read_log(path: &Path) -> Result<Vec<SubmissionEntry>, Error> {
+    let content = fs::read_to_string(path).map_err(|e| Error::io(path.display().to_string(), e))?;
+
+    parse_log(&content, &path.display().to_string())
+}
+
+/// Parse the entries of a log, skipping the blank lines.
+pub fn parse_log(content: &str, path: &str) -> Result<Vec<SubmissionEntry>, Error> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line).map_err(|e| Error::decode(path.to_string(), idx + 1, e))
+        })
+        .collect()
+}
+
+/// The entries logged at or after the given time, in seconds since the Unix epoch.
+pub fn retain_since(entries: Vec<SubmissionEntry>, since: u64) -> Vec<SubmissionEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.time >= since)
+        .collect()
+}
+
+/// The performance of a channel end over a period, computed from the log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ChannelStats {
+    /// Number of batches of packet messages submitted
+    pub submissions: usize,
+    pub txs: usize,
+    pub failed_txs: usize,
+    /// Fraction of the transactions which failed or timed out, between 0 and 1
+    pub failure_rate: f64,
+    /// Number of packets received, acknowledged or timed out by the confirmed transactions
+    pub packets_relayed: usize,
+    /// Median of the time the batches of packets took to be confirmed
+    pub median_latency_ms: Option<u64>,
+    pub gas_used: u64,
+}
+
+impl ChannelStats {
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a SubmissionEntry>) -> Self {
+        let mut stats = Self::default();
+        let mut latencies = Vec::new();
+
+        for entry in entries {
+            stats.submissions += 1;
+            stats.txs += entry.txs;
+            stats.failed_txs += entry.failed_txs;
+            stats.packets_relayed += entry.packets;
+            stats.gas_used += entry.gas_used;
+
+            // The batches which relayed no packet were never confirmed,
+            // their latency is the time waited for a confirmation
+            if entry.packets > 0 {
+                latencies.push(entry.latency_ms);
+            }
+        }
+
+        if stats.txs > 0 {
+            stats.failure_rate = stats.failed_txs as f64 / stats.txs as f64;
+        }
+
+        stats.median_latency_ms = median(&mut latencies);
+
+        stats
+    }
+}
+
+fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_unstable();
+
+    let mid = values.len() / 2;
+
+    if values.len() % 2 == 0 {
+        Some((values[mid - 1] + values[mid]) / 2)
+    } else {
+        Some(values[mid])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    fn entry(time: u64, channel: &str, packets: usize, latency_ms: u64) -> SubmissionEntry {
+        SubmissionEntry {
+            time,
+            chain_id: ChainId::from_string("ibc-0"),
+            counterparty_chain_id: ChainId::from_string("ibc-1"),
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::from_str(channel).unwrap(),
+            txs: 1,
+            failed_txs: if packets == 0 { 1 } else { 0 },
+            packets,
+            latency_ms,
+            gas_used: 100_000,
+        }
+    }
+
+    #[test]
+    fn channel_stats_since() {
+        let log = [
+            entry(100, "channel-0", 5, 9000),
+            entry(200, "channel-0", 2, 3000),
+            entry(300, "channel-1", 4, 1000),
+            entry(400, "channel-0", 0, 300_000),
+            entry(500, "channel-0", 1, 5000),
+        ]
+        .iter()
+        .map(|entry| serde_json::to_string(entry).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let entries = parse_log(&log, "submissions.jsonl").unwrap();
+        let entries = retain_since(entries, 200);
+
+        let chain_id = ChainId::from_string("ibc-0");
+        let channel_id = ChannelId::from_str("channel-0").unwrap();
+
+        let stats = ChannelStats::from_entries(
+            entries
+                .iter()
+                .filter(|entry| entry.is_on(&chain_id, None, &channel_id)),
+        );
+
+        assert_eq!(stats.submissions, 3);
+        assert_eq!(stats.txs, 3);
+        assert_eq!(stats.failed_txs, 1);
+        assert_eq!(stats.packets_relayed, 3);
+        assert_eq!(stats.median_latency_ms, Some(4000));
+        assert_eq!(stats.gas_used, 300_000);
+    }
+
+    #[test]
+    fn invalid_log_line() {
+        let err = parse_log("\nnot json\n", "submissions.jsonl").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
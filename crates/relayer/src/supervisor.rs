@@ -4,34 +4,54 @@ use core::convert::Infallible;
 use core::ops::Deref;
 use core::time::Duration;
 use std::sync::RwLock;
+use std::thread;
+use std::time::Instant;
 
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 use itertools::Itertools;
 use tracing::{debug, error, error_span, info, instrument, trace, warn};
 
 use ibc::{
-    core::ics24_host::identifier::{ChainId, ChannelId, PortId},
+    core::{
+        ics02_client::client_state::ClientState,
+        ics24_host::identifier::{ChainId, ChannelId, PortId},
+    },
     events::IbcEvent,
     Height,
 };
 
 use crate::{
-    chain::{endpoint::HealthCheck, handle::ChainHandle, tracking::TrackingId},
-    config::Config,
+    chain::{
+        endpoint::HealthCheck,
+        handle::ChainHandle,
+        requests::{
+            IncludeProof, PageRequest, QueryChannelRequest, QueryClientStatesRequest, QueryHeight,
+            QueryNextSequenceReceiveRequest, QueryPacketCommitmentsRequest,
+            QueryUpgradedClientStateRequest,
+        },
+        tracking::TrackingId,
+    },
+    config::{restart::GiveUp, Config},
     event::{
         monitor::{self, Error as EventError, ErrorDetail as EventErrorDetail, EventBatch},
         IbcEventWithHeight,
     },
+    foreign_client::ForeignClient,
+    misbehaviour::{
+        broadcast::{self, DetectedMisbehaviour},
+        equivocation::duplicate_votes,
+        AnyMisbehaviour,
+    },
     object::{Object, Packet},
     registry::{Registry, SharedRegistry},
-    rest,
+    rest::{self, RestApiError},
     supervisor::scan::ScanMode,
     telemetry,
     util::{
-        lock::LockExt,
+        lock::{LockExt, RwArc},
         task::{spawn_background_task, Next, TaskError, TaskHandle},
     },
-    worker::WorkerMap,
+    worker::{RestartDecision, UnconfirmedTxs, WorkerMap},
 };
 
 pub mod client_state_filter;
@@ -46,14 +66,25 @@ pub mod dump_state;
 
 use dump_state::SupervisorState;
 
+pub mod halt;
+pub mod persist;
+pub mod quarantine;
 pub mod scan;
+pub mod sequence_gap;
 pub mod spawn;
 
 pub mod cmd;
 
-use cmd::SupervisorCmd;
+use cmd::{ObjectSpec, PathSpec, SupervisorCmd};
 
-use self::{scan::ChainScanner, spawn::SpawnContext};
+use self::{
+    halt::{HaltDetector, Progress},
+    persist::PersistedState,
+    quarantine::{QuarantineTracker, Transition},
+    scan::{ChainScan, ChainScanner, ChainsScan},
+    sequence_gap::{sequence_gap, GapDetector, GapProgress},
+    spawn::SpawnContext,
+};
 
 type ArcBatch = Arc<monitor::Result<EventBatch>>;
 type Subscription = Receiver<ArcBatch>;
@@ -67,6 +98,9 @@ errors caused by closed connections.
 pub struct SupervisorHandle {
     pub sender: Sender<SupervisorCmd>,
     tasks: Vec<TaskHandle>,
+    /// Kept so that the exit requests are never disconnected, e.g. when the
+    /// workers are not restarted, and are only notified of actual requests
+    exit: (Sender<()>, Receiver<()>),
 }
 
 /// Options for the supervisor
@@ -94,10 +128,22 @@ pub fn spawn_supervisor(
     options: SupervisorOptions,
 ) -> Result<SupervisorHandle, Error> {
     let (sender, receiver) = unbounded();
-
-    let tasks = spawn_supervisor_tasks(config, registry, rest_rx, receiver, options)?;
-
-    Ok(SupervisorHandle { sender, tasks })
+    let (exit_tx, exit_rx) = bounded(1);
+
+    let tasks = spawn_supervisor_tasks(
+        config,
+        registry,
+        rest_rx,
+        receiver,
+        exit_tx.clone(),
+        options,
+    )?;
+
+    Ok(SupervisorHandle {
+        sender,
+        tasks,
+        exit: (exit_tx, exit_rx),
+    })
 }
 
 impl SupervisorHandle {
@@ -122,6 +168,12 @@ impl SupervisorHandle {
             task.join();
         }
     }
+
+    /// Notified when the supervisor asks for Hermes to exit, e.g. after giving up
+    /// on a crashed worker, for it to be drained and shut down by its owner.
+    pub fn exit_requested(&self) -> Receiver<()> {
+        self.exit.1.clone()
+    }
 }
 
 pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
@@ -129,49 +181,133 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
     registry: SharedRegistry<Chain>,
     rest_rx: Option<rest::Receiver>,
     cmd_rx: Receiver<SupervisorCmd>,
+    exit_tx: Sender<()>,
     options: SupervisorOptions,
 ) -> Result<Vec<TaskHandle>, Error> {
     if options.health_check {
         health_check(&config, &mut registry.write());
     }
 
-    let workers = Arc::new(RwLock::new(WorkerMap::new()));
+    let (misbehaviour_tx, misbehaviour_rx) = unbounded();
+    let workers = Arc::new(RwLock::new(WorkerMap::new(misbehaviour_tx)));
     let client_state_filter = Arc::new(RwLock::new(FilterPolicy::default()));
 
-    let scan = chain_scanner(
-        &config,
-        &mut registry.write(),
-        &mut client_state_filter.acquire_write(),
-        if options.force_full_scan {
-            ScanMode::Full
-        } else {
-            ScanMode::Auto
-        },
-    )
-    .scan_chains();
+    let restored = if options.force_full_scan {
+        None
+    } else {
+        persist::load_fresh(&config)
+    };
 
-    info!("scanned chains:");
-    info!("{}", scan);
+    let scans = match restored {
+        Some(state) => {
+            info!(
+                "restored supervisor state saved {} ago, skipping scan",
+                humantime::format_duration(state.saved_at.elapsed().unwrap_or_default())
+            );
 
-    spawn_context(&config, &mut registry.write(), &mut workers.acquire_write()).spawn_workers(scan);
+            info!("scanned chains:");
+            info!(
+                "{}",
+                ChainsScan {
+                    chains: state.scans.iter().cloned().map(Ok).collect()
+                }
+            );
+
+            spawn_context(&config, &mut registry.write(), &mut workers.acquire_write())
+                .spawn_workers_for_objects(state.objects);
+
+            clear_unconfirmed(&workers.acquire_read(), &state.unconfirmed);
+
+            state.scans
+        }
+        None => {
+            let scan = chain_scanner(
+                &config,
+                &mut registry.write(),
+                &mut client_state_filter.acquire_write(),
+                if options.force_full_scan {
+                    ScanMode::Full
+                } else {
+                    ScanMode::Auto
+                },
+            )
+            .scan_chains();
+
+            info!("scanned chains:");
+            info!("{}", scan);
+
+            let scans = scan.chains.iter().flatten().cloned().collect_vec();
+
+            spawn_context(&config, &mut registry.write(), &mut workers.acquire_write())
+                .spawn_workers(scan);
+
+            // Save the state right away, so that the scan can be
+            // skipped on restart even if the relayer is killed.
+            if let Err(e) = persist_state(&config, &scans, &workers.acquire_read(), vec![]) {
+                warn!("{}", e);
+            }
+
+            scans
+        }
+    };
 
     let subscriptions = init_subscriptions(&config, &mut registry.write())?;
 
+    // Set when shutting down, for the batch workers to stop handling new events
+    let draining = <RwArc<_>>::new_lock(false);
+
     let batch_tasks = spawn_batch_workers(
         &config,
         registry.clone(),
-        client_state_filter,
+        client_state_filter.clone(),
         workers.clone(),
+        draining.clone(),
         subscriptions,
     );
 
-    let cmd_task = spawn_cmd_worker(registry.clone(), workers.clone(), cmd_rx);
+    let cmd_task = spawn_cmd_worker(
+        config.clone(),
+        registry.clone(),
+        client_state_filter.clone(),
+        workers.clone(),
+        draining,
+        scans,
+        cmd_rx,
+    );
 
     let mut tasks = vec![cmd_task];
     tasks.extend(batch_tasks);
 
+    if config.restart.enabled {
+        let restart_task =
+            spawn_restart_worker(config.clone(), registry.clone(), workers.clone(), exit_tx);
+        tasks.push(restart_task);
+    }
+
+    if config.quarantine.enabled {
+        let quarantine_task =
+            spawn_quarantine_worker(config.clone(), registry.clone(), workers.clone());
+        tasks.push(quarantine_task);
+    }
+
+    if config.halt.enabled {
+        let halt_task = spawn_halt_worker(config.clone(), registry.clone(), workers.clone());
+        tasks.push(halt_task);
+    }
+
+    if config.sequence_gaps.enabled {
+        let gap_task = spawn_sequence_gap_worker(config.clone(), registry.clone(), workers.clone());
+        tasks.push(gap_task);
+    }
+
+    if config.mode.clients.misbehaviour && !config.mode.watch_only {
+        let broadcast_task =
+            spawn_misbehaviour_broadcast_worker(config.clone(), registry.clone(), misbehaviour_rx);
+        tasks.push(broadcast_task);
+    }
+
     if let Some(rest_rx) = rest_rx {
-        let rest_task = spawn_rest_worker(config, registry, workers, rest_rx);
+        let rest_task = spawn_rest_worker(config, registry, client_state_filter, workers, rest_rx);
         tasks.push(rest_task);
     }
 
@@ -183,6 +319,7 @@ fn spawn_batch_workers<Chain: ChainHandle>(
     registry: SharedRegistry<Chain>,
     client_state_filter: Arc<RwLock<FilterPolicy>>,
     workers: Arc<RwLock<WorkerMap>>,
+    draining: RwArc<bool>,
     subscriptions: Vec<(Chain, Subscription)>,
 ) -> Vec<TaskHandle> {
     let mut handles = Vec::with_capacity(subscriptions.len());
@@ -192,12 +329,18 @@ fn spawn_batch_workers<Chain: ChainHandle>(
         let registry = registry.clone();
         let client_state_filter = client_state_filter.clone();
         let workers = workers.clone();
+        let draining = draining.clone();
 
         let handle = spawn_background_task(
             error_span!("worker.batch", chain = %chain.id()),
             Some(Duration::from_millis(5)),
             move || -> Result<Next, TaskError<Infallible>> {
                 if let Ok(batch) = subscription.try_recv() {
+                    if *draining.acquire_read() {
+                        trace!("shutting down, dropping batch of events");
+                        return Ok(Next::Continue);
+                    }
+
                     handle_batch(
                         &config,
                         &mut registry.write(),
@@ -219,8 +362,12 @@ fn spawn_batch_workers<Chain: ChainHandle>(
 }
 
 pub fn spawn_cmd_worker<Chain: ChainHandle>(
+    config: Config,
     registry: SharedRegistry<Chain>,
+    client_state_filter: Arc<RwLock<FilterPolicy>>,
     workers: Arc<RwLock<WorkerMap>>,
+    draining: RwArc<bool>,
+    scans: Vec<ChainScan>,
     cmd_rx: Receiver<SupervisorCmd>,
 ) -> TaskHandle {
     spawn_background_task(
@@ -232,6 +379,39 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
                     SupervisorCmd::DumpState(reply_to) => {
                         dump_state(&registry.read(), &workers.acquire_read(), reply_to);
                     }
+                    SupervisorCmd::StartObject(spec, reply_to) => {
+                        let result =
+                            start_object(&config, &registry, &client_state_filter, &workers, &spec);
+
+                        let _ = reply_to.try_send(result);
+                    }
+                    SupervisorCmd::StopObject(spec, reply_to) => {
+                        let result =
+                            stop_object(&config, &registry, &client_state_filter, &workers, &spec);
+
+                        let _ = reply_to.try_send(result);
+                    }
+                    SupervisorCmd::PausePath(spec, reply_to) => {
+                        let result =
+                            pause_path(&config, &registry, &client_state_filter, &workers, &spec);
+
+                        let _ = reply_to.try_send(result);
+                    }
+                    SupervisorCmd::ResumePath(spec, reply_to) => {
+                        let result =
+                            resume_path(&config, &registry, &client_state_filter, &workers, &spec);
+
+                        let _ = reply_to.try_send(result);
+                    }
+                    SupervisorCmd::PersistState(reply_to) => {
+                        let result =
+                            persist_state(&config, &scans, &workers.acquire_read(), vec![]);
+                        let _ = reply_to.try_send(result);
+                    }
+                    SupervisorCmd::Drain(grace_period, reply_to) => {
+                        let result = drain(&config, &scans, &workers, &draining, grace_period);
+                        let _ = reply_to.try_send(result);
+                    }
                 }
             }
 
@@ -240,9 +420,115 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
     )
 }
 
+/// Periodically restart the workers which crashed, according to the restart policies.
+/// Asks for Hermes to exit through `exit_tx` when so configured for the workers given up on.
+pub fn spawn_restart_worker<Chain: ChainHandle>(
+    config: Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+    exit_tx: Sender<()>,
+) -> TaskHandle {
+    spawn_background_task(
+        error_span!("worker.restart"),
+        Some(Duration::from_secs(1)),
+        move || -> Result<Next, TaskError<Infallible>> {
+            restart_crashed_workers(
+                &config,
+                &mut registry.write(),
+                &mut workers.acquire_write(),
+                &exit_tx,
+            );
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Periodically check the endpoints of the chains, quarantining the ones
+/// which keep failing and releasing them once they have recovered.
+pub fn spawn_quarantine_worker<Chain: ChainHandle>(
+    config: Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+) -> TaskHandle {
+    let mut tracker = QuarantineTracker::default();
+
+    spawn_background_task(
+        error_span!("worker.quarantine"),
+        Some(Duration::from_secs(1)),
+        move || -> Result<Next, TaskError<Infallible>> {
+            check_chains(&config, &registry, &workers, &mut tracker);
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Periodically check the latest height of the chains, pausing the workers relaying
+/// to and from the ones which stopped producing blocks until they resume.
+pub fn spawn_halt_worker<Chain: ChainHandle>(
+    config: Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+) -> TaskHandle {
+    let mut detector = HaltDetector::default();
+
+    spawn_background_task(
+        error_span!("worker.halt"),
+        Some(config.halt.check_interval),
+        move || -> Result<Next, TaskError<Infallible>> {
+            check_halts(&config, &registry, &workers, &mut detector);
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Periodically check the gaps between the packets committed on the ordered channels
+/// relayed and the next sequence expected by their counterparty, reporting the ones
+/// which are too large or whose counterparty stopped receiving their packets.
+pub fn spawn_sequence_gap_worker<Chain: ChainHandle>(
+    config: Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+) -> TaskHandle {
+    let mut detector = GapDetector::default();
+
+    spawn_background_task(
+        error_span!("worker.sequence_gap"),
+        Some(config.sequence_gaps.check_interval),
+        move || -> Result<Next, TaskError<Infallible>> {
+            check_sequence_gaps(&config, &registry, &workers, &mut detector);
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Periodically broadcast the evidence of the misbehaviours received from the clients
+/// to the faulty chains and to their other clients, hosted on the chains of the configuration.
+pub fn spawn_misbehaviour_broadcast_worker<Chain: ChainHandle>(
+    config: Config,
+    registry: SharedRegistry<Chain>,
+    misbehaviours: broadcast::Receiver,
+) -> TaskHandle {
+    spawn_background_task(
+        error_span!("worker.misbehaviour_broadcast"),
+        Some(Duration::from_secs(1)),
+        move || -> Result<Next, TaskError<Infallible>> {
+            for detected in misbehaviours.try_iter() {
+                broadcast_misbehaviour(&config, &registry, &detected);
+            }
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
 pub fn spawn_rest_worker<Chain: ChainHandle>(
     config: Config,
     registry: SharedRegistry<Chain>,
+    client_state_filter: Arc<RwLock<FilterPolicy>>,
     workers: Arc<RwLock<WorkerMap>>,
     rest_rx: rest::Receiver,
 ) -> TaskHandle {
@@ -250,7 +536,7 @@ pub fn spawn_rest_worker<Chain: ChainHandle>(
         error_span!("rest"),
         Some(Duration::from_millis(500)),
         move || -> Result<Next, TaskError<Infallible>> {
-            handle_rest_requests(&config, &registry.read(), &workers.acquire_read(), &rest_rx);
+            handle_rest_requests(&config, &registry, &client_state_filter, &workers, &rest_rx);
 
             Ok(Next::Continue)
         },
@@ -288,6 +574,12 @@ fn is_channel_allowed(
     config.packets_on_channel_allowed(chain_id, port_id, channel_id)
 }
 
+/// Whether or not a watch-only relayer monitors the given [`Object`]. The other objects
+/// require to submit transactions, e.g. to complete handshakes, and are ignored.
+fn is_watched(object: &Object) -> bool {
+    matches!(object, Object::Client(_) | Object::Packet(_))
+}
+
 /// Whether or not the relayer should relay packets
 /// or complete handshakes for the given [`Object`].
 fn relay_on_object<Chain: ChainHandle>(
@@ -505,6 +797,14 @@ pub fn collect_events(
                     || Object::for_close_init_channel(packet, src_chain).ok(),
                 );
             }
+            IbcEvent::UpgradeChannel(ref upgrade) => {
+                collect_event(
+                    &mut collected,
+                    event_with_height.clone(),
+                    mode.channels.enabled,
+                    || Object::for_upgrade_channel(upgrade, src_chain).ok(),
+                );
+            }
             IbcEvent::CrossChainQuery(ref packet) => {
                 collect_event(
                     &mut collected,
@@ -544,18 +844,29 @@ fn health_check<Chain: ChainHandle>(config: &Config, registry: &mut Registry<Cha
 
     let chains = &config.chains;
 
-    for config in chains {
-        let id = &config.id;
+    for chain_config in chains {
+        let id = &chain_config.id;
         let _span = error_span!("health_check", chain = %id).entered();
 
         let chain = registry.get_or_spawn(id);
 
         match chain {
-            Ok(chain) => match chain.health_check() {
-                Ok(Healthy) => info!("chain is healthy"),
-                Ok(Unhealthy(e)) => warn!("chain is not healthy: {}", e),
-                Err(e) => error!("failed to perform health check: {}", e),
-            },
+            Ok(chain) => {
+                match chain.health_check() {
+                    Ok(Healthy) => info!("chain is healthy"),
+                    Ok(Unhealthy(e)) => warn!("chain is not healthy: {}", e),
+                    Err(e) => error!("failed to perform health check: {}", e),
+                }
+
+                // A watch-only relayer signs no transaction and may have no key
+                if !config.mode.watch_only {
+                    match chain.check_key() {
+                        Ok(Healthy) => {}
+                        Ok(Unhealthy(e)) => warn!("key is not usable: {}", e),
+                        Err(e) => error!("failed to check the key: {}", e),
+                    }
+                }
+            }
             Err(e) => {
                 error!(
                     "skipping health check, reason: failed to spawn chain runtime with error: {}",
@@ -625,30 +936,788 @@ fn state<Chain: ChainHandle>(registry: &Registry<Chain>, workers: &WorkerMap) ->
     SupervisorState::new(chains, workers.handles())
 }
 
-fn handle_rest_requests<Chain: ChainHandle>(
+/// Save the given scans and the objects which currently have a worker
+/// to disk, if persistence is enabled in the configuration.
+fn persist_state(
     config: &Config,
-    registry: &Registry<Chain>,
+    scans: &[ChainScan],
     workers: &WorkerMap,
+    unconfirmed: Vec<UnconfirmedTxs>,
+) -> Result<(), Error> {
+    if !config.persistence.enabled {
+        return Ok(());
+    }
+
+    let path = persist::state_path(config).map_err(Error::persist)?;
+    let state = PersistedState::new(config, scans.to_vec(), workers.objects())
+        .with_unconfirmed(unconfirmed);
+
+    persist::save(&path, &state).map_err(Error::persist)
+}
+
+/// Prepare for shutting down: stop handling new events and submitting new
+/// transactions, wait up to the given grace period for the transactions
+/// already submitted by the workers to be confirmed, and save the state of the supervisor along with the transactions
+/// which are still unconfirmed, whose packets are then cleared on restart.
+#[instrument(name = "supervisor.drain", level = "error", skip_all)]
+fn drain(
+    config: &Config,
+    scans: &[ChainScan],
+    workers: &Arc<RwLock<WorkerMap>>,
+    draining: &RwArc<bool>,
+    grace_period: Duration,
+) -> Result<(), Error> {
+    *draining.acquire_write() = true;
+    workers.acquire_read().drain();
+
+    info!(
+        "stopped handling new events, waiting up to {} for in-flight transactions to be confirmed",
+        humantime::format_duration(grace_period)
+    );
+
+    let deadline = Instant::now() + grace_period;
+
+    let unconfirmed = loop {
+        let unconfirmed = workers.acquire_read().in_flight_txs();
+
+        if unconfirmed.is_empty() || Instant::now() >= deadline {
+            break unconfirmed;
+        }
+
+        let count: usize = unconfirmed.iter().map(|u| u.txs.len()).sum();
+        debug!(
+            "waiting for {} in-flight transactions to be confirmed",
+            count
+        );
+
+        thread::sleep(Duration::from_millis(500));
+    };
+
+    if unconfirmed.is_empty() {
+        info!("all in-flight transactions have been confirmed");
+    } else {
+        for u in &unconfirmed {
+            for txs in &u.txs {
+                warn!(
+                    "transactions {} submitted to {} by the worker for {} are still unconfirmed",
+                    txs.tx_hashes.join(", "),
+                    txs.chain_id,
+                    u.object.short_name()
+                );
+            }
+        }
+    }
+
+    persist_state(config, scans, &workers.acquire_read(), unconfirmed)
+}
+
+/// Clear the pending packets on the paths on which transactions were still
+/// unconfirmed when the relayer last exited, as these may have been dropped.
+fn clear_unconfirmed(workers: &WorkerMap, unconfirmed: &[UnconfirmedTxs]) {
+    for u in unconfirmed {
+        if let Some(worker) = workers.get(&u.object) {
+            info!(
+                "clearing pending packets for {}, which had unconfirmed transactions on exit",
+                u.object.short_name()
+            );
+
+            worker.clear_pending_packets();
+        }
+    }
+}
+
+/// Respawn the crashed workers which are due to be restarted, and alert
+/// about the ones which exhausted their restarts, or ask for Hermes to exit
+/// through `exit_tx` if so configured.
+fn restart_crashed_workers<Chain: ChainHandle>(
+    config: &Config,
+    registry: &mut Registry<Chain>,
+    workers: &mut WorkerMap,
+    exit_tx: &Sender<()>,
+) {
+    let decisions = workers.handle_crashed(&config.restart, Instant::now());
+    let mut restart = Vec::new();
+
+    for (object, decision) in decisions {
+        match decision {
+            RestartDecision::Restart { .. } => restart.push(object),
+            RestartDecision::GiveUp { restarts } => {
+                let policy = config.restart.policy_for(object.object_type());
+
+                error!(
+                    "giving up on crashed worker for {} after {} restarts",
+                    object.short_name(),
+                    restarts
+                );
+
+                // Hermes is drained and shut down by the owner of the supervisor,
+                // so that its state is saved and its leadership released
+                if policy.on_give_up == GiveUp::Exit {
+                    error!("shutting down, as configured when giving up on a worker");
+                    let _ = exit_tx.try_send(());
+                }
+            }
+            RestartDecision::Wait | RestartDecision::GaveUp => {}
+        }
+    }
+
+    if !restart.is_empty() {
+        spawn_context(config, registry, workers).spawn_workers_for_objects(restart);
+    }
+}
+
+/// Query the given chains concurrently, without holding the locks on the registry nor
+/// on the workers, so that a chain whose endpoints hang does not hold up the others.
+/// The results are collected until the given timeout only, the chains which have not
+/// answered by then being returned without a result, while their query is left to
+/// complete in the background.
+fn query_chains<Chain: ChainHandle, T: Send + 'static>(
+    chains: Vec<Chain>,
+    query: fn(&Chain) -> T,
+    timeout: Duration,
+) -> Vec<(Chain, Option<T>)> {
+    let deadline = Instant::now() + timeout;
+    let (sender, receiver) = unbounded();
+
+    for (index, chain) in chains.iter().cloned().enumerate() {
+        let sender = sender.clone();
+
+        thread::spawn(move || {
+            let _ = sender.send((index, query(&chain)));
+        });
+    }
+
+    let mut results = chains.iter().map(|_| None).collect::<Vec<_>>();
+
+    for _ in 0..chains.len() {
+        match receiver.recv_deadline(deadline) {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(_) => break,
+        }
+    }
+
+    chains.into_iter().zip(results).collect()
+}
+
+/// The chains of the configuration whose runtime was spawned, e.g. on startup.
+/// The other chains are not relayed, and so are neither checked.
+fn spawned_chains<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    mut filter: impl FnMut(&ChainId) -> bool,
+) -> Vec<Chain> {
+    let registry = registry.read();
+
+    config
+        .chains
+        .iter()
+        .filter(|chain_config| filter(&chain_config.id))
+        .filter_map(|chain_config| registry.get(&chain_config.id))
+        .collect()
+}
+
+/// Check the endpoints of the chains which are due, and quarantine or release them
+/// accordingly. The chains are queried concurrently, those which do not respond within
+/// the check timeout failing their check, see [`query_chains`].
+fn check_chains<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    tracker: &mut QuarantineTracker,
+) {
+    let now = Instant::now();
+    let chains = spawned_chains(config, registry, |chain_id| {
+        tracker.is_due(&config.quarantine, chain_id, now)
+    });
+
+    let timeout = config.quarantine.check_timeout;
+
+    for (chain, status) in query_chains(chains, |chain| chain.query_application_status(), timeout) {
+        let chain_id = &chain.id();
+
+        let _span = error_span!("quarantine", chain = %chain_id).entered();
+
+        let healthy = match status {
+            Some(Ok(_)) => true,
+            Some(Err(e)) => {
+                debug!("failed to query the status of the chain: {}", e);
+                false
+            }
+            None => {
+                debug!(
+                    "the chain did not respond to the status query within {:?}",
+                    timeout
+                );
+                false
+            }
+        };
+
+        match tracker.record(&config.quarantine, chain_id, healthy, Instant::now()) {
+            Transition::Quarantine => {
+                warn!(
+                    "quarantining chain after {} failed checks in a row, \
+                    stopping the workers relaying to and from it",
+                    config.quarantine.failure_threshold
+                );
+
+                telemetry!(chain_quarantined, chain_id, true);
+
+                let objects = workers.acquire_write().quarantine_chain(chain_id);
+
+                debug!("stopped {} workers", objects.len());
+            }
+            Transition::Release => {
+                info!(
+                    "releasing chain from quarantine after {} successful checks in a row, \
+                    spawning the workers relaying to and from it again",
+                    config.quarantine.recovery_threshold
+                );
+
+                telemetry!(chain_quarantined, chain_id, false);
+
+                let objects = workers.acquire_write().release_chain(chain_id);
+
+                spawn_context(config, &mut registry.write(), &mut workers.acquire_write())
+                    .spawn_workers_for_objects(objects);
+            }
+            Transition::Stay => {
+                if !healthy && !tracker.is_quarantined(chain_id) {
+                    warn!("chain endpoints failed to respond");
+                }
+            }
+        }
+    }
+}
+
+/// Check the gaps of the ordered channels whose packets are relayed by a worker.
+/// A channel whose ends cannot be queried is skipped until the next check.
+fn check_sequence_gaps<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    detector: &mut GapDetector,
+) {
+    let paths = workers
+        .acquire_read()
+        .objects()
+        .into_iter()
+        .filter_map(|object| match object {
+            Object::Packet(path) => Some(path),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    detector.retain(|(chain_id, port_id, channel_id)| {
+        paths.iter().any(|path| {
+            path.src_chain_id == *chain_id
+                && path.src_port_id == *port_id
+                && path.src_channel_id == *channel_id
+        })
+    });
+
+    for path in paths {
+        let _span = error_span!(
+            "sequence_gap",
+            chain = %path.src_chain_id,
+            counterparty_chain = %path.dst_chain_id,
+            port = %path.src_port_id,
+            channel = %path.src_channel_id,
+        )
+        .entered();
+
+        let (gap, next_sequence_recv) = match query_sequence_gap(registry, &path) {
+            Ok(Some(gap)) => gap,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!("failed to query the sequence gap of the channel: {}", e);
+                continue;
+            }
+        };
+
+        telemetry!(
+            sequence_gap,
+            &path.src_chain_id,
+            &path.src_channel_id,
+            &path.src_port_id,
+            &path.dst_chain_id,
+            gap
+        );
+
+        let channel = (
+            path.src_chain_id.clone(),
+            path.src_port_id.clone(),
+            path.src_channel_id.clone(),
+        );
+
+        match detector.record(
+            &config.sequence_gaps,
+            &channel,
+            gap,
+            next_sequence_recv,
+            Instant::now(),
+        ) {
+            GapProgress::Exceeded { gap } => warn!(
+                "{} packets committed on the ordered channel were not received by the counterparty, \
+                above the threshold of {}. the channel may be stuck on the first of them",
+                gap, config.sequence_gaps.max_gap
+            ),
+            GapProgress::Stalled { gap, duration } => warn!(
+                "{} packets committed on the ordered channel were not received by the counterparty, \
+                which received none of them for {:?}. the channel is effectively stuck until the first \
+                of them is received or times out",
+                gap, duration
+            ),
+            GapProgress::Closed => info!(
+                "all the packets committed on the ordered channel were received by the counterparty"
+            ),
+            GapProgress::Unchanged => {}
+        }
+    }
+}
+
+/// The number of packets committed on the source end of an ordered channel which were
+/// not received by its counterparty, along with the next sequence the counterparty
+/// expects, or `None` if the channel is not ordered.
+fn query_sequence_gap<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    path: &Packet,
+) -> Result<Option<(u64, u64)>, Error> {
+    let src_chain = registry
+        .get_or_spawn(&path.src_chain_id)
+        .map_err(Error::spawn)?;
+    let dst_chain = registry
+        .get_or_spawn(&path.dst_chain_id)
+        .map_err(Error::spawn)?;
+
+    let (channel_end, _) = src_chain
+        .query_channel(
+            QueryChannelRequest {
+                port_id: path.src_port_id.clone(),
+                channel_id: path.src_channel_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(Error::relayer)?;
+
+    if !channel_end.ordering.is_ordered() {
+        return Ok(None);
+    }
+
+    let counterparty_channel_id = match &channel_end.remote.channel_id {
+        Some(channel_id) => channel_id.clone(),
+        None => return Ok(None),
+    };
+
+    let (commitments, _) = src_chain
+        .query_packet_commitments(QueryPacketCommitmentsRequest {
+            port_id: path.src_port_id.clone(),
+            channel_id: path.src_channel_id.clone(),
+            pagination: Some(PageRequest::all()),
+            height: QueryHeight::Latest,
+        })
+        .map_err(Error::relayer)?;
+
+    let (next_sequence_recv, _) = dst_chain
+        .query_next_sequence_receive(
+            QueryNextSequenceReceiveRequest {
+                port_id: channel_end.remote.port_id.clone(),
+                channel_id: counterparty_channel_id,
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(Error::relayer)?;
+
+    let commitments = commitments.into_iter().map(u64::from).collect::<Vec<_>>();
+    let next_sequence_recv = u64::from(next_sequence_recv);
+
+    Ok(Some((
+        sequence_gap(&commitments, next_sequence_recv),
+        next_sequence_recv,
+    )))
+}
+
+/// Submit the evidence of the given misbehaviour to all the clients of the faulty chain
+/// hosted on the chains of the configuration, except the one it was detected on, and
+/// broadcast the evidence of the duplicate votes it contains to the faulty chain itself.
+fn broadcast_misbehaviour<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    detected: &DetectedMisbehaviour,
+) {
+    let _span = error_span!("misbehaviour_broadcast", chain = %detected.faulty_chain).entered();
+
+    let faulty_chain = match registry.get_or_spawn(&detected.faulty_chain) {
+        Ok(chain) => chain,
+        Err(e) => {
+            error!("failed to spawn the runtime of the faulty chain: {}", e);
+            return;
+        }
+    };
+
+    let equivocations = match &detected.evidence.misbehaviour {
+        AnyMisbehaviour::Tendermint(misbehaviour) => {
+            duplicate_votes(&misbehaviour.header1, &misbehaviour.header2)
+        }
+
+        #[cfg(test)]
+        AnyMisbehaviour::Mock(_) => vec![],
+    };
+
+    for evidence in equivocations {
+        match faulty_chain.broadcast_evidence(evidence) {
+            Ok(()) => info!("broadcast the duplicate vote evidence to the faulty chain"),
+            Err(e) => warn!("failed to broadcast the duplicate vote evidence: {}", e),
+        }
+    }
+
+    for chain_config in &config.chains {
+        if chain_config.id == detected.faulty_chain {
+            continue;
+        }
+
+        let host_chain = match registry.get_or_spawn(&chain_config.id) {
+            Ok(chain) => chain,
+            Err(e) => {
+                warn!(host_chain = %chain_config.id, "failed to spawn chain runtime: {}", e);
+                continue;
+            }
+        };
+
+        let clients = match host_chain.query_clients(QueryClientStatesRequest {
+            pagination: Some(PageRequest::all()),
+        }) {
+            Ok(clients) => clients,
+            Err(e) => {
+                warn!(host_chain = %chain_config.id, "failed to query the clients: {}", e);
+                continue;
+            }
+        };
+
+        let clients = clients.into_iter().filter(|client| {
+            client.client_state.chain_id() == detected.faulty_chain
+                && !client.client_state.is_frozen()
+                && !detected.submitted_to(&chain_config.id, &client.client_id)
+        });
+
+        for client in clients {
+            let client =
+                ForeignClient::restore(client.client_id, host_chain.clone(), faulty_chain.clone());
+
+            let result = client
+                .build_evidence_from(&detected.evidence)
+                .and_then(|evidence| client.submit_evidence(evidence));
+
+            match result {
+                Ok(_) => {
+                    info!(client = %client, "submitted the evidence of the misbehaviour");
+
+                    telemetry!(
+                        client_misbehaviours_submitted,
+                        &detected.faulty_chain,
+                        &chain_config.id,
+                        client.id(),
+                        1
+                    );
+                }
+                Err(e) => warn!(client = %client, "failed to submit the evidence: {}", e),
+            }
+        }
+    }
+}
+
+/// Check the latest height of the chains, and pause or resume the workers relaying
+/// to and from them accordingly. A chain which cannot be queried is left as it is,
+/// its endpoints failing being handled by the quarantine. The chains are queried
+/// concurrently, up to the check interval, see [`query_chains`].
+fn check_halts<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    detector: &mut HaltDetector,
+) {
+    let chains = spawned_chains(config, registry, |_| true);
+
+    // A chain which does not respond before the next check is left as it is
+    let timeout = config.halt.check_interval;
+
+    for (chain, status) in query_chains(chains, |chain| chain.query_application_status(), timeout) {
+        let chain_id = &chain.id();
+
+        let _span = error_span!("halt", chain = %chain_id).entered();
+
+        let height = match status {
+            Some(Ok(status)) => status.height,
+            Some(Err(e)) => {
+                debug!("failed to query the latest height of the chain: {}", e);
+                continue;
+            }
+            None => {
+                debug!(
+                    "the chain did not respond to the latest height query within {:?}",
+                    timeout
+                );
+                continue;
+            }
+        };
+
+        match detector.record(&config.halt, chain_id, height, Instant::now()) {
+            Progress::Halted { height } => {
+                // A chain halting for an upgrade stores the upgraded client state
+                // at its last height, for the clients of the chain to be upgraded.
+                let upgrade = chain
+                    .query_upgraded_client_state(QueryUpgradedClientStateRequest {
+                        upgrade_height: height.increment(),
+                    })
+                    .is_ok();
+
+                if upgrade {
+                    warn!(
+                        "chain halted at height {} for a planned upgrade, pausing the workers \
+                        relaying to and from it until it resumes. the clients of the chain \
+                        may need to be upgraded with `hermes upgrade clients` once it does",
+                        height
+                    );
+                } else {
+                    warn!(
+                        "chain did not produce any block for {:?} and is considered halted \
+                        at height {}, pausing the workers relaying to and from it until it resumes",
+                        config.halt.halt_threshold, height
+                    );
+                }
+
+                telemetry!(chain_halted, chain_id, true);
+
+                let objects = workers.acquire_write().halt_chain(chain_id);
+
+                debug!("stopped {} workers which cannot be paused", objects.len());
+            }
+            Progress::Resumed { height, downtime } => {
+                info!(
+                    "chain resumed producing blocks at height {} after being halted for {:?}, \
+                    resuming the workers relaying to and from it",
+                    height, downtime
+                );
+
+                telemetry!(chain_halted, chain_id, false);
+
+                let objects = workers.acquire_write().resume_chain(chain_id);
+
+                spawn_context(config, &mut registry.write(), &mut workers.acquire_write())
+                    .spawn_workers_for_objects(objects);
+            }
+            Progress::Unchanged => {}
+        }
+    }
+}
+
+/// Start relaying on the given client, connection or channel, and spawn the workers
+/// needed for it right away, as if it had been found when scanning the chains on startup.
+#[instrument(
+    name = "supervisor.start_object",
+    level = "error",
+    skip_all,
+    fields(chain = %spec.chain_id())
+)]
+fn start_object<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    spec: &ObjectSpec,
+) -> Result<Vec<Object>, Error> {
+    let scan = scan_object(config, registry, client_state_filter, spec)?;
+    let objects = scan.objects_for(spec);
+
+    info!("starting to relay on {:?}", spec);
+
+    let mut workers = workers.acquire_write();
+
+    for object in &objects {
+        workers.start_object(object);
+    }
+
+    spawn_context(config, &mut registry.write(), &mut workers).spawn_workers_for_chain(scan);
+
+    Ok(objects)
+}
+
+/// Stop relaying on the given client, connection or channel,
+/// and shut down the workers spawned for it.
+#[instrument(
+    name = "supervisor.stop_object",
+    level = "error",
+    skip_all,
+    fields(chain = %spec.chain_id())
+)]
+fn stop_object<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    spec: &ObjectSpec,
+) -> Result<Vec<Object>, Error> {
+    let scan = scan_object(config, registry, client_state_filter, spec)?;
+    let objects = scan.objects_for(spec);
+
+    info!("stopping relaying on {:?}", spec);
+
+    let mut workers = workers.acquire_write();
+
+    for object in &objects {
+        workers.stop_object(object);
+    }
+
+    Ok(objects)
+}
+
+/// Scan the given client, connection or channel without holding the locks of the
+/// registry and of the client state filter, which the handling of the events needs,
+/// while the chains are queried. The chains spawned and the permissions found by the
+/// scan are then handed back to them.
+fn scan_object<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    spec: &ObjectSpec,
+) -> Result<ChainScan, Error> {
+    let mut snapshot = registry.read().snapshot();
+    let mut filter = client_state_filter.acquire_read().clone();
+
+    let scan = chain_scanner(config, &mut snapshot, &mut filter, ScanMode::Full)
+        .scan_object(spec)
+        .map_err(Error::scan);
+
+    registry.write().absorb(snapshot);
+    client_state_filter.acquire_write().absorb(filter);
+
+    scan
+}
+
+/// Pause relaying on the given path, in both directions, keeping the state
+/// of its channel and packet workers so that they pick up where they left off
+/// once resumed.
+#[instrument(
+    name = "supervisor.pause_path",
+    level = "error",
+    skip_all,
+    fields(path = %spec)
+)]
+fn pause_path<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    spec: &PathSpec,
+) -> Result<Vec<Object>, Error> {
+    let channel = spec.channel();
+    let scan = scan_object(config, registry, client_state_filter, &channel)?;
+    let objects = scan.objects_for(&channel);
+
+    info!("pausing relaying on {}", spec);
+
+    let mut workers = workers.acquire_write();
+
+    for object in &objects {
+        workers.pause_object(object);
+    }
+
+    Ok(objects)
+}
+
+/// Resume relaying on the given path, in both directions.
+#[instrument(
+    name = "supervisor.resume_path",
+    level = "error",
+    skip_all,
+    fields(path = %spec)
+)]
+fn resume_path<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    spec: &PathSpec,
+) -> Result<Vec<Object>, Error> {
+    let channel = spec.channel();
+    let scan = scan_object(config, registry, client_state_filter, &channel)?;
+    let objects = scan.objects_for(&channel);
+
+    info!("resuming relaying on {}", spec);
+
+    let mut workers = workers.acquire_write();
+
+    for object in &objects {
+        workers.resume_object(object);
+    }
+
+    Ok(objects)
+}
+
+fn handle_rest_requests<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
     rest_rx: &rest::Receiver,
 ) {
     if let Some(cmd) = rest::process_incoming_requests(config, rest_rx) {
-        handle_rest_cmd(registry, workers, cmd);
+        handle_rest_cmd(config, registry, client_state_filter, workers, cmd);
     }
 }
 
 #[instrument(name = "supervisor.handle_rest_cmd", level = "error", skip_all)]
 fn handle_rest_cmd<Chain: ChainHandle>(
-    registry: &Registry<Chain>,
-    workers: &WorkerMap,
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    client_state_filter: &Arc<RwLock<FilterPolicy>>,
+    workers: &Arc<RwLock<WorkerMap>>,
     m: rest::Command,
 ) {
     match m {
         rest::Command::DumpState(reply) => {
-            let state = state(registry, workers);
+            let state = state(&registry.read(), &workers.acquire_read());
             reply
                 .send(Ok(state))
                 .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
         }
+
+        rest::Command::StartObject(spec, reply) => {
+            let result = start_object(config, registry, client_state_filter, workers, &spec)
+                .map_err(|e| RestApiError::ObjectControl(e.to_string()));
+
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+
+        rest::Command::StopObject(spec, reply) => {
+            let result = stop_object(config, registry, client_state_filter, workers, &spec)
+                .map_err(|e| RestApiError::ObjectControl(e.to_string()));
+
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+
+        rest::Command::PausePath(spec, reply) => {
+            let result = pause_path(config, registry, client_state_filter, workers, &spec)
+                .map_err(|e| RestApiError::PathControl(e.to_string()));
+
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+
+        rest::Command::ResumePath(spec, reply) => {
+            let result = resume_path(config, registry, client_state_filter, workers, &spec)
+                .map_err(|e| RestApiError::PathControl(e.to_string()));
+
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
     }
 }
 
@@ -694,13 +1763,55 @@ fn process_batch<Chain: ChainHandle>(
 
     // Forward the IBC events.
     for (object, events_with_heights) in collected.per_object.into_iter() {
-        if !relay_on_object(
-            config,
-            registry,
-            client_state_filter,
-            &src_chain.id(),
-            &object,
-        ) {
+        if workers.is_stopped(&object) {
+            trace!(
+                "skipping events for '{}'. reason: relaying on this object was stopped",
+                object.short_name()
+            );
+
+            continue;
+        }
+
+        if workers.is_quarantined(&object) {
+            trace!(
+                "skipping events for '{}'. reason: one of its chains is quarantined",
+                object.short_name()
+            );
+
+            continue;
+        }
+
+        // The workers of the halted chains which can be paused are kept, for the
+        // events to be relayed once the chain resumes, and the others are held.
+        if workers.is_halted(&object) && !workers.contains(&object) {
+            workers.hold_if_suspended(&object);
+
+            trace!(
+                "skipping events for '{}'. reason: one of its chains is halted",
+                object.short_name()
+            );
+
+            continue;
+        }
+
+        if config.mode.watch_only && !is_watched(&object) {
+            trace!(
+                "skipping events for '{}'. reason: the relayer is watch-only",
+                object.short_name()
+            );
+
+            continue;
+        }
+
+        if !workers.is_started(&object)
+            && !relay_on_object(
+                config,
+                registry,
+                client_state_filter,
+                &src_chain.id(),
+                &object,
+            )
+        {
             trace!(
                 "skipping events for '{}'. \
                 reason: filtering is enabled and channel does not match any allowed channels",
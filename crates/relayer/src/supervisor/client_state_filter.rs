@@ -67,7 +67,7 @@ impl FilterError {
 
 /// A cache storing filtering status (allow or deny) for
 /// arbitrary identifiers.
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct FilterPolicy {
     /// A cache associating a generic identifying key, such as
     /// client id, channel id, or connection id, with an
@@ -76,6 +76,11 @@ pub struct FilterPolicy {
 }
 
 impl FilterPolicy {
+    /// Take over the permissions cached by the given copy of this filter.
+    pub fn absorb(&mut self, other: FilterPolicy) {
+        self.permission_cache.extend(other.permission_cache);
+    }
+
     /// Given a connection end and the underlying client for that
     /// connection, controls both the client as well as the
     /// client on the counterparty chain.
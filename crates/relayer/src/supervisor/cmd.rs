@@ -1,8 +1,130 @@
+use core::fmt;
+use core::time::Duration;
+
 use crossbeam_channel::Sender;
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
 
-use super::dump_state::SupervisorState;
+use crate::object::Object;
+
+use super::{dump_state::SupervisorState, Error};
 
 #[derive(Clone, Debug)]
 pub enum SupervisorCmd {
     DumpState(Sender<SupervisorState>),
+
+    /// Start relaying on the given object, replying with
+    /// the objects whose workers are now allowed to run.
+    StartObject(ObjectSpec, Sender<Result<Vec<Object>, Error>>),
+
+    /// Stop relaying on the given object, replying with
+    /// the objects whose workers have been shut down.
+    StopObject(ObjectSpec, Sender<Result<Vec<Object>, Error>>),
+
+    /// Pause relaying on the given path, replying with
+    /// the objects whose workers are now paused.
+    PausePath(PathSpec, Sender<Result<Vec<Object>, Error>>),
+
+    /// Resume relaying on the given path, replying with
+    /// the objects whose workers are now resumed.
+    ResumePath(PathSpec, Sender<Result<Vec<Object>, Error>>),
+
+    /// Save the state of the supervisor to disk, if persistence is enabled,
+    /// so that it can be restored instead of scanning the chains on restart.
+    PersistState(Sender<Result<(), Error>>),
+
+    /// Stop handling new events and wait, up to the given grace period, for the
+    /// transactions already submitted to be confirmed, before saving the state
+    /// of the supervisor, in preparation for shutting down.
+    Drain(Duration, Sender<Result<(), Error>>),
+}
+
+/// A client, connection or channel on a chain, which the supervisor can be
+/// instructed to start or stop relaying on while it is running.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ObjectSpec {
+    Client {
+        chain_id: ChainId,
+        client_id: ClientId,
+    },
+    Connection {
+        chain_id: ChainId,
+        connection_id: ConnectionId,
+    },
+    Channel {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+    },
+}
+
+impl ObjectSpec {
+    pub fn chain_id(&self) -> &ChainId {
+        match self {
+            Self::Client { chain_id, .. } => chain_id,
+            Self::Connection { chain_id, .. } => chain_id,
+            Self::Channel { chain_id, .. } => chain_id,
+        }
+    }
+}
+
+/// A channel end on a chain, identifying the path relaying packets
+/// from and to it, which the supervisor can be instructed to pause or resume.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathSpec {
+    pub chain_id: ChainId,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+impl PathSpec {
+    /// The channel at the end of the path, whose objects are
+    /// relayed on in both directions once it is scanned.
+    pub fn channel(&self) -> ObjectSpec {
+        ObjectSpec::Channel {
+            chain_id: self.chain_id.clone(),
+            port_id: self.port_id.clone(),
+            channel_id: self.channel_id.clone(),
+        }
+    }
+}
+
+impl fmt::Display for PathSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}/{}", self.chain_id, self.port_id, self.channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_channel_spec() {
+        let spec: ObjectSpec = serde_json::from_str(
+            r#"{ "type": "Channel", "chain_id": "ibc-0", "port_id": "transfer", "channel_id": "channel-3" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec,
+            ObjectSpec::Channel {
+                chain_id: ChainId::from_string("ibc-0"),
+                port_id: PortId::transfer(),
+                channel_id: ChannelId::new(3),
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_path_spec() {
+        let spec: PathSpec = serde_json::from_str(
+            r#"{ "chain_id": "ibc-0", "port_id": "transfer", "channel_id": "channel-3" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.to_string(), "ibc-0:transfer/channel-3");
+    }
 }
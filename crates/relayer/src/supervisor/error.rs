@@ -5,6 +5,7 @@ use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortId
 
 use crate::error::Error as RelayerError;
 use crate::spawn::SpawnError;
+use crate::supervisor::persist::Error as PersistError;
 use crate::supervisor::scan::Error as ScanError;
 
 define_error! {
@@ -69,6 +70,10 @@ define_error! {
         Scan
             [ ScanError ]
             |_| { "supervisor encountered an error when scanning chains" },
+
+        Persist
+            [ PersistError ]
+            |_| { "supervisor was not able to save its state" },
     }
 }
 
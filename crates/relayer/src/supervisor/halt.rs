@@ -0,0 +1,141 @@
+//! Tracking of the latest height of the chains, to detect the ones which stopped
+//! producing blocks and the moment they resume.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use core::time::Duration;
+use std::time::Instant;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::Height;
+
+use crate::config::halt::HaltConfig;
+
+/// How the production of blocks by a chain evolved since its previous check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// The chain did not produce a block for too long, and is halted at the given height
+    Halted { height: Height },
+    /// The halted chain produced new blocks, up to the given height, after being halted for the given duration
+    Resumed { height: Height, downtime: Duration },
+    /// The chain is still producing blocks, or is still halted
+    Unchanged,
+}
+
+#[derive(Clone, Debug)]
+struct ChainProgress {
+    height: Height,
+    /// When the chain was first seen at its latest height
+    since: Instant,
+    halted: bool,
+}
+
+/// The progress of the chains checked by the supervisor.
+#[derive(Clone, Debug, Default)]
+pub struct HaltDetector {
+    chains: HashMap<ChainId, ChainProgress>,
+}
+
+impl HaltDetector {
+    /// Whether the given chain is halted.
+    pub fn is_halted(&self, chain_id: &ChainId) -> bool {
+        self.chains
+            .get(chain_id)
+            .map_or(false, |progress| progress.halted)
+    }
+
+    /// Record the latest height of the given chain,
+    /// and decide whether it halted or resumed.
+    pub fn record(
+        &mut self,
+        config: &HaltConfig,
+        chain_id: &ChainId,
+        height: Height,
+        now: Instant,
+    ) -> Progress {
+        let progress = self
+            .chains
+            .entry(chain_id.clone())
+            .or_insert_with(|| ChainProgress {
+                height,
+                since: now,
+                halted: false,
+            });
+
+        if height > progress.height {
+            let downtime = now.duration_since(progress.since);
+            let resumed = progress.halted;
+
+            progress.height = height;
+            progress.since = now;
+            progress.halted = false;
+
+            if resumed {
+                return Progress::Resumed { height, downtime };
+            }
+        } else if !progress.halted && now.duration_since(progress.since) >= config.halt_threshold {
+            progress.halted = true;
+
+            return Progress::Halted {
+                height: progress.height,
+            };
+        }
+
+        Progress::Unchanged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halt_and_resume() {
+        let config = HaltConfig {
+            enabled: true,
+            halt_threshold: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        let chain_id = ChainId::new("ibc".to_string(), 0);
+        let mut detector = HaltDetector::default();
+        let start = Instant::now();
+
+        let at = |secs| start + Duration::from_secs(secs);
+        let height = |h| Height::new(0, h).unwrap();
+
+        assert_eq!(
+            detector.record(&config, &chain_id, height(10), at(0)),
+            Progress::Unchanged
+        );
+
+        // Producing blocks resets the time since the latest height was first seen
+        assert_eq!(
+            detector.record(&config, &chain_id, height(11), at(50)),
+            Progress::Unchanged
+        );
+        assert_eq!(
+            detector.record(&config, &chain_id, height(11), at(100)),
+            Progress::Unchanged
+        );
+        assert_eq!(
+            detector.record(&config, &chain_id, height(11), at(110)),
+            Progress::Halted { height: height(11) }
+        );
+        assert!(detector.is_halted(&chain_id));
+
+        // The halt is only reported once
+        assert_eq!(
+            detector.record(&config, &chain_id, height(11), at(200)),
+            Progress::Unchanged
+        );
+
+        assert_eq!(
+            detector.record(&config, &chain_id, height(12), at(300)),
+            Progress::Resumed {
+                height: height(12),
+                downtime: Duration::from_secs(250),
+            }
+        );
+        assert!(!detector.is_halted(&chain_id));
+    }
+}
@@ -0,0 +1,362 @@
+//! Persistence of the supervisor state across restarts.
+//!
+//! Scanning the chains on startup can take several minutes on deployments with
+//! hundreds of channels. When persistence is enabled, the result of the scan and
+//! the objects which have a worker are saved to disk, and reloaded on the next
+//! start instead of scanning again, provided they are recent enough.
+//!
+//! The state file is checksummed: a corrupted state file is moved
+//! aside when loading it, and the chains are scanned again instead.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use flex_error::{define_error, TraceError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::util::state_file;
+use crate::{config::Config, object::Object, worker::UnconfirmedTxs};
+
+use super::scan::ChainScan;
+
+/// Location of the state file, relative to the home directory, when none is configured.
+pub const DEFAULT_STATE_PATH: &str = ".hermes/state/supervisor.json";
+
+define_error! {
+    Error {
+        HomeLocationUnavailable
+            |_| { "home location is unavailable" },
+
+        Io
+            { path: PathBuf }
+            [ TraceError<std::io::Error> ]
+            |e| { format!("I/O error on supervisor state file {}", e.path.display()) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode the supervisor state" },
+
+        Decode
+            { path: PathBuf }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode the supervisor state file {}", e.path.display()) },
+
+        Corrupted
+            { path: PathBuf, reason: String }
+            |e| { format!("supervisor state file {} is corrupted: {}", e.path.display(), e.reason) },
+    }
+}
+
+/// The state of the supervisor saved to disk across restarts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Version of the relayer which saved the state, as the format of
+    /// the state is not guaranteed to be stable across versions
+    pub version: String,
+    /// When the state was saved
+    pub saved_at: SystemTime,
+    /// The chains which were configured when the state was saved
+    pub chains: Vec<ChainId>,
+    /// Hash of the mode and of the packet filters which were configured when the state
+    /// was saved, which decide which objects get a worker
+    #[serde(default)]
+    pub filters_hash: String,
+    /// The result of the last scan of the chains
+    pub scans: Vec<ChainScan>,
+    /// The objects which had a worker when the state was saved
+    pub objects: Vec<Object>,
+    /// The transactions which were still waiting to be confirmed when
+    /// the relayer exited, whose packets are cleared on restart
+    #[serde(default)]
+    pub unconfirmed: Vec<UnconfirmedTxs>,
+}
+
+impl PersistedState {
+    pub fn new(config: &Config, scans: Vec<ChainScan>, objects: Vec<Object>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            saved_at: SystemTime::now(),
+            chains: configured_chains(config),
+            filters_hash: filters_hash(config),
+            scans,
+            objects,
+            unconfirmed: Vec::new(),
+        }
+    }
+
+    pub fn with_unconfirmed(mut self, unconfirmed: Vec<UnconfirmedTxs>) -> Self {
+        self.unconfirmed = unconfirmed;
+        self
+    }
+
+    /// Whether this state can be used instead of scanning the chains, i.e. it was saved
+    /// by this version of the relayer less than `max_age` ago, for the same set of chains,
+    /// the same mode and the same packet filters.
+    pub fn is_fresh(&self, config: &Config, max_age: Duration, now: SystemTime) -> bool {
+        if self.version != env!("CARGO_PKG_VERSION") {
+            debug!(
+                "supervisor state was saved by version {} of the relayer",
+                self.version
+            );
+
+            return false;
+        }
+
+        let age = now.duration_since(self.saved_at).unwrap_or(Duration::ZERO);
+
+        if age > max_age {
+            debug!("supervisor state was saved {:?} ago", age);
+            return false;
+        }
+
+        let mut chains = self.chains.clone();
+        chains.sort();
+
+        if chains != configured_chains(config) {
+            debug!("supervisor state was saved for a different set of chains");
+            return false;
+        }
+
+        if self.filters_hash != filters_hash(config) {
+            debug!("supervisor state was saved for a different mode or packet filters");
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Hash of the mode and of the packet filters of the chains in the given configuration.
+fn filters_hash(config: &Config) -> String {
+    let mut filters: Vec<_> = config
+        .chains
+        .iter()
+        .map(|c| (&c.id, &c.packet_filter))
+        .collect();
+
+    filters.sort_by_key(|(id, _)| *id);
+
+    // SAFETY: the mode and the packet filters are plain data which always serialize
+    let json = serde_json::to_vec(&(&config.mode, filters)).expect("serializable filters");
+
+    hex::encode(Sha256::digest(json))
+}
+
+fn configured_chains(config: &Config) -> Vec<ChainId> {
+    let mut chains: Vec<_> = config.chains.iter().map(|c| c.id.clone()).collect();
+    chains.sort();
+    chains
+}
+
+/// The path of the state file, as configured or else [`DEFAULT_STATE_PATH`].
+pub fn state_path(config: &Config) -> Result<PathBuf, Error> {
+    match &config.persistence.path {
+        Some(path) => Ok(path.clone()),
+        None => {
+            let home = dirs_next::home_dir().ok_or_else(Error::home_location_unavailable)?;
+            Ok(home.join(DEFAULT_STATE_PATH))
+        }
+    }
+}
+
+/// Save the given state to the given file, creating its parent directories if needed.
+pub fn save(path: &Path, state: &PersistedState) -> Result<(), Error> {
+    let json = serde_json::to_vec(state).map_err(Error::encode)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io(path.to_path_buf(), e))?;
+    }
+
+    // Write to a temporary file first, so that a crash while
+    // saving does not leave a truncated state file behind.
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, state_file::seal(&json)).map_err(|e| Error::io(tmp_path.clone(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+    info!("saved supervisor state to {}", path.display());
+
+    Ok(())
+}
+
+/// Load the state saved in the given file, if any.
+pub fn load(path: &Path) -> Result<Option<PersistedState>, Error> {
+    let file = match fs::read(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::io(path.to_path_buf(), e)),
+    };
+
+    let json =
+        state_file::unseal(&file).map_err(|reason| Error::corrupted(path.to_path_buf(), reason))?;
+
+    let state = serde_json::from_slice(json).map_err(|e| Error::decode(path.to_path_buf(), e))?;
+
+    Ok(Some(state))
+}
+
+/// Load the state saved for the given configuration, if persistence
+/// is enabled and the saved state is fresh enough to be used.
+pub fn load_fresh(config: &Config) -> Option<PersistedState> {
+    if !config.persistence.enabled {
+        return None;
+    }
+
+    let path = match state_path(config) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("cannot load supervisor state: {}", e);
+            return None;
+        }
+    };
+
+    match load(&path) {
+        Ok(Some(state))
+            if state.is_fresh(config, config.persistence.max_age, SystemTime::now()) =>
+        {
+            Some(state)
+        }
+        Ok(Some(_)) => {
+            info!(
+                "supervisor state saved in {} is stale, scanning chains",
+                path.display()
+            );
+
+            None
+        }
+        Ok(None) => {
+            info!(
+                "no supervisor state saved in {}, scanning chains",
+                path.display()
+            );
+
+            None
+        }
+        Err(e) => {
+            if matches!(
+                e.detail(),
+                ErrorDetail::Corrupted(_) | ErrorDetail::Decode(_)
+            ) {
+                match state_file::move_aside(&path) {
+                    Ok(aside) => warn!(
+                        "{}, moved it aside to {} and scanning chains",
+                        e,
+                        aside.display()
+                    ),
+                    Err(io_e) => {
+                        warn!("{}, failed to move it aside ({}), scanning chains", e, io_e)
+                    }
+                }
+            } else {
+                warn!("cannot load supervisor state, scanning chains: {}", e);
+            }
+
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        crate::config::load(path).expect("could not parse config")
+    }
+
+    #[test]
+    fn freshness() {
+        let config = example_config();
+        let state = PersistedState::new(&config, vec![], vec![]);
+        let max_age = Duration::from_secs(60);
+
+        assert!(state.is_fresh(&config, max_age, state.saved_at));
+        assert!(state.is_fresh(&config, max_age, state.saved_at + max_age));
+        assert!(!state.is_fresh(&config, max_age, state.saved_at + 2 * max_age));
+
+        let mut other_config = config.clone();
+        other_config.chains[0].id = ChainId::from_string("ibc-2");
+        assert!(!state.is_fresh(&other_config, max_age, state.saved_at));
+
+        let mut other_config = config.clone();
+        other_config.mode.packets.enabled = !other_config.mode.packets.enabled;
+        assert!(!state.is_fresh(&other_config, max_age, state.saved_at));
+
+        let mut other_config = config.clone();
+        other_config.chains[0].packet_filter = Default::default();
+        assert!(!state.is_fresh(&other_config, max_age, state.saved_at));
+
+        let mut old_state = state.clone();
+        old_state.version = "0.0.1".to_string();
+        assert!(!old_state.is_fresh(&config, max_age, state.saved_at));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let config = example_config();
+        let state = PersistedState::new(&config, vec![], vec![]);
+
+        let path = std::env::temp_dir()
+            .join(format!("hermes-state-{}", std::process::id()))
+            .join("supervisor.json");
+
+        save(&path, &state).unwrap();
+        let loaded = load(&path).unwrap().unwrap();
+
+        assert_eq!(loaded.chains, state.chains);
+        assert_eq!(loaded.saved_at, state.saved_at);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn unconfirmed_defaults_to_empty() {
+        let config = example_config();
+        let state = PersistedState::new(&config, vec![], vec![]);
+
+        let mut json = serde_json::to_value(&state).unwrap();
+        json.as_object_mut().unwrap().remove("unconfirmed");
+
+        let state: PersistedState = serde_json::from_value(json).unwrap();
+        assert!(state.unconfirmed.is_empty());
+    }
+
+    #[test]
+    fn move_corrupted_aside() {
+        let mut config = example_config();
+        config.persistence.enabled = true;
+
+        let dir =
+            std::env::temp_dir().join(format!("hermes-state-corrupted-{}", std::process::id()));
+        let path = dir.join("supervisor.json");
+        config.persistence.path = Some(path.clone());
+
+        let state = PersistedState::new(&config, vec![], vec![]);
+        save(&path, &state).unwrap();
+
+        // Truncate the state file, as a crash while writing it in place would
+        let file = fs::read(&path).unwrap();
+        fs::write(&path, &file[..file.len() - 2]).unwrap();
+
+        assert!(load_fresh(&config).is_none());
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_missing() {
+        let path = std::env::temp_dir().join("hermes-state-missing/supervisor.json");
+        assert!(load(&path).unwrap().is_none());
+    }
+}
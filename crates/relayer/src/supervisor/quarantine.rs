@@ -0,0 +1,163 @@
+//! Tracking of the health of the chains, to quarantine the ones whose endpoints
+//! keep failing and to release them from quarantine once they have recovered.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use std::time::Instant;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::config::quarantine::QuarantineConfig;
+
+/// What to do with a chain after checking its endpoints.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// The chain failed too many checks in a row and must be quarantined
+    Quarantine,
+    /// The quarantined chain passed enough checks in a row and must be released
+    Release,
+    /// The chain stays as it is
+    Stay,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ChainHealth {
+    quarantined: bool,
+    /// How many checks in a row failed, or succeeded if the chain is quarantined
+    streak: u32,
+    last_check: Option<Instant>,
+}
+
+/// The health of the chains checked by the supervisor.
+#[derive(Clone, Debug, Default)]
+pub struct QuarantineTracker {
+    chains: HashMap<ChainId, ChainHealth>,
+}
+
+impl QuarantineTracker {
+    /// Whether the given chain is quarantined.
+    pub fn is_quarantined(&self, chain_id: &ChainId) -> bool {
+        self.chains
+            .get(chain_id)
+            .map_or(false, |health| health.quarantined)
+    }
+
+    /// Whether the endpoints of the given chain are due to be checked,
+    /// according to whether it is quarantined or not.
+    pub fn is_due(&self, config: &QuarantineConfig, chain_id: &ChainId, now: Instant) -> bool {
+        let health = match self.chains.get(chain_id) {
+            Some(health) => health,
+            None => return true,
+        };
+
+        let interval = if health.quarantined {
+            config.retry_interval
+        } else {
+            config.check_interval
+        };
+
+        health
+            .last_check
+            .map_or(true, |at| now.duration_since(at) >= interval)
+    }
+
+    /// Record the outcome of a check of the endpoints of the given chain,
+    /// and decide whether it must be quarantined or released.
+    pub fn record(
+        &mut self,
+        config: &QuarantineConfig,
+        chain_id: &ChainId,
+        healthy: bool,
+        now: Instant,
+    ) -> Transition {
+        let health = self.chains.entry(chain_id.clone()).or_default();
+        health.last_check = Some(now);
+
+        // A healthy chain counts its failures in a row,
+        // and a quarantined one its successes in a row.
+        if healthy == health.quarantined {
+            health.streak += 1;
+        } else {
+            health.streak = 0;
+        }
+
+        if !health.quarantined && health.streak >= config.failure_threshold {
+            health.quarantined = true;
+            health.streak = 0;
+            Transition::Quarantine
+        } else if health.quarantined && health.streak >= config.recovery_threshold {
+            health.quarantined = false;
+            health.streak = 0;
+            Transition::Release
+        } else {
+            Transition::Stay
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::time::Duration;
+
+    #[test]
+    fn quarantine_and_release() {
+        let config = QuarantineConfig {
+            enabled: true,
+            failure_threshold: 2,
+            recovery_threshold: 2,
+            ..Default::default()
+        };
+
+        let chain_id = ChainId::new("ibc".to_string(), 0);
+        let mut tracker = QuarantineTracker::default();
+        let now = Instant::now();
+
+        let mut check = |healthy| tracker.record(&config, &chain_id, healthy, now);
+
+        // A success resets the count of failures in a row
+        assert_eq!(check(false), Transition::Stay);
+        assert_eq!(check(true), Transition::Stay);
+        assert_eq!(check(false), Transition::Stay);
+        assert_eq!(check(false), Transition::Quarantine);
+
+        // A failure resets the count of successes in a row
+        assert_eq!(check(true), Transition::Stay);
+        assert_eq!(check(false), Transition::Stay);
+        assert_eq!(check(true), Transition::Stay);
+        assert_eq!(check(true), Transition::Release);
+
+        assert!(!tracker.is_quarantined(&chain_id));
+    }
+
+    #[test]
+    fn check_intervals() {
+        let config = QuarantineConfig {
+            enabled: true,
+            check_interval: Duration::from_secs(30),
+            failure_threshold: 1,
+            retry_interval: Duration::from_secs(5),
+            ..Default::default()
+        };
+
+        let chain_id = ChainId::new("ibc".to_string(), 0);
+        let mut tracker = QuarantineTracker::default();
+        let now = Instant::now();
+
+        assert!(tracker.is_due(&config, &chain_id, now));
+
+        assert_eq!(
+            tracker.record(&config, &chain_id, true, now),
+            Transition::Stay
+        );
+        assert!(!tracker.is_due(&config, &chain_id, now + Duration::from_secs(5)));
+        assert!(tracker.is_due(&config, &chain_id, now + Duration::from_secs(30)));
+
+        assert_eq!(
+            tracker.record(&config, &chain_id, false, now),
+            Transition::Quarantine
+        );
+        assert!(tracker.is_quarantined(&chain_id));
+        assert!(tracker.is_due(&config, &chain_id, now + Duration::from_secs(5)));
+    }
+}
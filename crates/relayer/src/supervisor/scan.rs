@@ -2,6 +2,7 @@ use core::fmt::{Display, Error as FmtError, Formatter};
 use std::collections::BTreeMap;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, error_span, info, warn};
 
 use ibc::core::{
@@ -26,9 +27,11 @@ use crate::{
     },
     client_state::IdentifiedAnyClientState,
     config::{filter::ChannelFilters, ChainConfig, Config, PacketFilter},
+    object::{Channel, Client, Connection, Object, Packet},
     path::PathIdentifiers,
     registry::Registry,
     supervisor::client_state_filter::{FilterPolicy, Permission},
+    supervisor::cmd::ObjectSpec,
 };
 
 use crate::chain::counterparty::{unreceived_acknowledgements, unreceived_packets};
@@ -133,7 +136,7 @@ impl Display for ChainsScan {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChainScan {
     pub chain_id: ChainId,
     pub clients: BTreeMap<ClientId, ClientScan>,
@@ -146,9 +149,47 @@ impl ChainScan {
             clients: BTreeMap::new(),
         }
     }
+
+    /// The objects relayed on for the given client, connection or channel,
+    /// as found in this scan of the chain it lives on.
+    ///
+    /// Only the objects at the level of the given spec and below are returned,
+    /// e.g. the client of a channel is not returned for a channel spec,
+    /// as it is shared with the other channels built on top of it.
+    pub fn objects_for(&self, spec: &ObjectSpec) -> Vec<Object> {
+        let mut objects = Vec::new();
+
+        for client_scan in self.clients.values() {
+            let counterparty_chain_id = client_scan.counterparty_chain_id();
+
+            match spec {
+                ObjectSpec::Client { .. } => {
+                    objects.extend(client_scan.objects(&self.chain_id));
+                }
+                ObjectSpec::Connection { .. } => {
+                    for connection_scan in client_scan.connections.values() {
+                        objects.extend(
+                            connection_scan.objects(&self.chain_id, &counterparty_chain_id),
+                        );
+                    }
+                }
+                ObjectSpec::Channel { .. } => {
+                    for connection_scan in client_scan.connections.values() {
+                        for channel_scan in connection_scan.channels.values() {
+                            objects.extend(
+                                channel_scan.objects(&self.chain_id, &counterparty_chain_id),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        objects
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClientScan {
     pub client: IdentifiedAnyClientState,
     pub connections: BTreeMap<ConnectionId, ConnectionScan>,
@@ -169,9 +210,27 @@ impl ClientScan {
     pub fn counterparty_chain_id(&self) -> ChainId {
         self.client.client_state.chain_id()
     }
+
+    /// The objects relayed on for this client, hosted on the given chain,
+    /// and for all the connections and channels built on top of it.
+    pub fn objects(&self, chain_id: &ChainId) -> Vec<Object> {
+        let counterparty_chain_id = self.counterparty_chain_id();
+
+        let mut objects = vec![Object::Client(Client {
+            dst_client_id: self.id().clone(),
+            dst_chain_id: chain_id.clone(),
+            src_chain_id: counterparty_chain_id.clone(),
+        })];
+
+        for connection_scan in self.connections.values() {
+            objects.extend(connection_scan.objects(chain_id, &counterparty_chain_id));
+        }
+
+        objects
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConnectionScan {
     pub connection: IdentifiedConnectionEnd,
     pub counterparty_state: Option<ConnectionState>,
@@ -201,9 +260,38 @@ impl ConnectionScan {
     pub fn is_open(&self) -> bool {
         self.connection.connection_end.is_open()
     }
+
+    /// The objects relayed on for this connection, in both directions,
+    /// and for all the channels built on top of it.
+    pub fn objects(&self, chain_id: &ChainId, counterparty_chain_id: &ChainId) -> Vec<Object> {
+        let mut objects = vec![Object::Connection(Connection {
+            dst_chain_id: counterparty_chain_id.clone(),
+            src_chain_id: chain_id.clone(),
+            src_connection_id: self.id().clone(),
+        })];
+
+        if let Some(counterparty_connection_id) = self
+            .connection
+            .connection_end
+            .counterparty()
+            .connection_id()
+        {
+            objects.push(Object::Connection(Connection {
+                dst_chain_id: chain_id.clone(),
+                src_chain_id: counterparty_chain_id.clone(),
+                src_connection_id: counterparty_connection_id.clone(),
+            }));
+        }
+
+        for channel_scan in self.channels.values() {
+            objects.extend(channel_scan.objects(chain_id, counterparty_chain_id));
+        }
+
+        objects
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChannelScan {
     pub channel: IdentifiedChannelEnd,
     pub counterparty: Option<IdentifiedChannelEnd>,
@@ -225,6 +313,27 @@ impl ChannelScan {
         &self.channel.port_id
     }
 
+    /// The objects relayed on for this channel, in both directions.
+    pub fn objects(&self, chain_id: &ChainId, counterparty_chain_id: &ChainId) -> Vec<Object> {
+        let mut objects = channel_objects(
+            chain_id,
+            counterparty_chain_id,
+            &self.channel.port_id,
+            &self.channel.channel_id,
+        );
+
+        if let Some(counterparty) = &self.counterparty {
+            objects.extend(channel_objects(
+                counterparty_chain_id,
+                chain_id,
+                &counterparty.port_id,
+                &counterparty.channel_id,
+            ));
+        }
+
+        objects
+    }
+
     pub fn unreceived_packets_on_counterparty(
         &self,
         chain: &impl ChainHandle,
@@ -256,6 +365,28 @@ impl ChannelScan {
     }
 }
 
+fn channel_objects(
+    src_chain_id: &ChainId,
+    dst_chain_id: &ChainId,
+    src_port_id: &PortId,
+    src_channel_id: &ChannelId,
+) -> Vec<Object> {
+    vec![
+        Object::Channel(Channel {
+            dst_chain_id: dst_chain_id.clone(),
+            src_chain_id: src_chain_id.clone(),
+            src_channel_id: src_channel_id.clone(),
+            src_port_id: src_port_id.clone(),
+        }),
+        Object::Packet(Packet {
+            dst_chain_id: dst_chain_id.clone(),
+            src_chain_id: src_chain_id.clone(),
+            src_channel_id: src_channel_id.clone(),
+            src_port_id: src_port_id.clone(),
+        }),
+    ]
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ScanMode {
     Auto,
@@ -302,6 +433,10 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
 
         info!("scanning chain...");
 
+        if let Some(tenant) = &chain_config.tenant {
+            telemetry!(register_tenant, &chain_config.id, tenant);
+        }
+
         telemetry!(init_per_chain, &chain_config.id);
 
         let chain = match self.registry.get_or_spawn(&chain_config.id) {
@@ -328,7 +463,7 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
                 self.query_allowed_channels(&chain, spec, &mut scan)?;
             }
             _ => {
-                info!("scanning chain for all clients, connections and channels");
+                info!("scanning chain for the clients of the chains in the config, and their connections and channels");
                 self.scan_all_clients(&chain, &mut scan)?;
             }
         };
@@ -348,40 +483,7 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             let result = scan_allowed_channel(self.registry, chain, port_id, channel_id);
 
             match result {
-                Ok(ScannedChannel {
-                    channel,
-                    counterparty_channel,
-                    connection,
-                    counterparty_connection_state,
-                    client,
-                }) => {
-                    let counterparty_chain_id = client.client_state.chain_id();
-                    init_telemetry(
-                        &chain.id(),
-                        &client.client_id,
-                        &counterparty_chain_id,
-                        channel_id,
-                        port_id,
-                        self.config,
-                    );
-
-                    let client_scan = scan
-                        .clients
-                        .entry(client.client_id.clone())
-                        .or_insert_with(|| ClientScan::new(client));
-
-                    let connection_scan = client_scan
-                        .connections
-                        .entry(connection.connection_id.clone())
-                        .or_insert_with(|| {
-                            ConnectionScan::new(connection, counterparty_connection_state)
-                        });
-
-                    connection_scan
-                        .channels
-                        .entry(channel.channel_id.clone())
-                        .or_insert_with(|| ChannelScan::new(channel, counterparty_channel));
-                }
+                Ok(scanned_channel) => self.insert_scanned_channel(chain, scanned_channel, scan),
                 Err(e) => error!(channel = %channel_id, "failed to scan channel, reason: {}", e),
             }
         }
@@ -389,10 +491,131 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
         Ok(())
     }
 
+    /// Scan the given client, connection or channel only, e.g. for starting to
+    /// relay on it while the supervisor is running.
+    ///
+    /// Unlike when scanning a whole connection, a single channel is scanned
+    /// regardless of whether it is allowed by the packet filter of its chain.
+    pub fn scan_object(&mut self, spec: &ObjectSpec) -> Result<ChainScan, Error> {
+        let chain = self
+            .registry
+            .get_or_spawn(spec.chain_id())
+            .map_err(Error::spawn)?;
+
+        let mut scan = ChainScan::new(chain.id());
+
+        match spec {
+            ObjectSpec::Client { client_id, .. } => {
+                let client = query_client(&chain, client_id)?;
+
+                if let Some(client_scan) = self.scan_client(&chain, client)? {
+                    scan.clients.insert(client_scan.id().clone(), client_scan);
+                }
+            }
+            ObjectSpec::Connection { connection_id, .. } => {
+                let connection = query_connection(&chain, connection_id)?;
+                let client = query_client(&chain, connection.connection_end.client_id())?;
+
+                if let Some(connection_scan) = self.scan_connection(&chain, &client, connection)? {
+                    let mut client_scan = ClientScan::new(client);
+
+                    client_scan
+                        .connections
+                        .insert(connection_scan.id().clone(), connection_scan);
+
+                    scan.clients.insert(client_scan.id().clone(), client_scan);
+                }
+            }
+            ObjectSpec::Channel {
+                port_id,
+                channel_id,
+                ..
+            } => {
+                let scanned_channel =
+                    scan_allowed_channel(self.registry, &chain, port_id, channel_id)?;
+
+                self.insert_scanned_channel(&chain, scanned_channel, &mut scan);
+            }
+        }
+
+        Ok(scan)
+    }
+
+    fn insert_scanned_channel(
+        &self,
+        chain: &Chain,
+        scanned_channel: ScannedChannel,
+        scan: &mut ChainScan,
+    ) {
+        let ScannedChannel {
+            channel,
+            counterparty_channel,
+            connection,
+            counterparty_connection_state,
+            client,
+        } = scanned_channel;
+
+        let counterparty_chain_id = client.client_state.chain_id();
+        init_telemetry(
+            &chain.id(),
+            &client.client_id,
+            &counterparty_chain_id,
+            &channel.channel_id,
+            &channel.port_id,
+            self.config,
+        );
+
+        let client_scan = scan
+            .clients
+            .entry(client.client_id.clone())
+            .or_insert_with(|| ClientScan::new(client));
+
+        let connection_scan = client_scan
+            .connections
+            .entry(connection.connection_id.clone())
+            .or_insert_with(|| ConnectionScan::new(connection, counterparty_connection_state));
+
+        connection_scan
+            .channels
+            .entry(channel.channel_id.clone())
+            .or_insert_with(|| ChannelScan::new(channel, counterparty_channel));
+    }
+
     pub fn scan_all_clients(&mut self, chain: &Chain, scan: &mut ChainScan) -> Result<(), Error> {
         info!("scanning all clients...");
 
         let clients = query_all_clients(chain)?;
+        let total = clients.len();
+
+        // Only the clients of the chains present in the config can be relayed on, so
+        // skip all the others up front, without querying their connections and channels.
+        // On hub chains, this avoids going through most of the clients and their channels.
+        let clients = clients
+            .into_iter()
+            .filter(|client| self.config.has_chain(&client.client_state.chain_id()))
+            .collect_vec();
+
+        let configured = clients.len();
+
+        // Neither can the frozen clients, whose connections and channels are not scanned either
+        let (frozen, clients): (Vec<_>, Vec<_>) = clients
+            .into_iter()
+            .partition(|client| client.client_state.is_frozen());
+
+        for client in &frozen {
+            warn!(
+                client = %client.client_id,
+                counterparty_chain = %client.client_state.chain_id(),
+                "skipping client, reason: client is frozen",
+            );
+        }
+
+        info!(
+            "found {} clients, {} of which are for a chain present in the config, {} of them frozen",
+            total,
+            configured,
+            frozen.len()
+        );
 
         for client in clients {
             if let Some(client_scan) = self.scan_client(chain, client)? {
@@ -431,15 +654,6 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
 
         info!("scanning client...");
 
-        if !self.client_allowed(chain, &client) {
-            warn!(
-                trust_threshold = ?client.client_state.trust_threshold(),
-                "skipping client, reason: client is not allowed",
-            );
-
-            return Ok(None);
-        }
-
         let counterparty_chain_id = client.client_state.chain_id();
         let has_counterparty = self.config.has_chain(&counterparty_chain_id);
 
@@ -453,6 +667,15 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             return Ok(None);
         }
 
+        if !self.client_allowed(chain, &client) {
+            warn!(
+                trust_threshold = ?client.client_state.trust_threshold(),
+                "skipping client, reason: client is not allowed",
+            );
+
+            return Ok(None);
+        }
+
         let client_connections_ids = query_client_connections(chain, &client.client_id)?;
 
         let mut scan = ClientScan::new(client);
@@ -0,0 +1,187 @@
+//! Tracking of the gaps between the packets committed on the ordered channels and
+//! the next sequence expected by their counterparty, to detect the ones which exceed
+//! the configured threshold or whose counterparty stopped receiving their packets.
+//!
+//! A channel is stalled when its next sequence to receive does not move, rather than
+//! when its gap does not shrink, as the gap of a channel on which packets keep being
+//! sent can stay as large while its packets are relayed.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use core::time::Duration;
+use std::time::Instant;
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+use crate::config::sequence_gap::SequenceGapConfig;
+
+/// How the gap of a channel evolved since its previous check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GapProgress {
+    /// The gap exceeds the configured threshold
+    Exceeded { gap: u64 },
+    /// The counterparty received none of the packets for the given duration
+    Stalled { gap: u64, duration: Duration },
+    /// The gap which was reported closed
+    Closed,
+    /// The gap is below the threshold and its packets are being received, or was already reported
+    Unchanged,
+}
+
+/// An end of a channel, whose packets are sent from the given chain.
+pub type ChannelKey = (ChainId, PortId, ChannelId);
+
+#[derive(Clone, Debug)]
+struct ChannelGap {
+    next_sequence_recv: u64,
+    /// When the next sequence to receive last moved, or when the gap opened
+    since: Instant,
+    exceeded: bool,
+    stalled: bool,
+}
+
+/// The gaps of the ordered channels checked by the supervisor.
+#[derive(Clone, Debug, Default)]
+pub struct GapDetector {
+    channels: HashMap<ChannelKey, ChannelGap>,
+}
+
+impl GapDetector {
+    /// Record the number of packets committed on the given channel whose sequence
+    /// was not received yet by the counterparty, along with the next sequence the
+    /// counterparty expects, and decide whether to report it.
+    pub fn record(
+        &mut self,
+        config: &SequenceGapConfig,
+        channel: &ChannelKey,
+        gap: u64,
+        next_sequence_recv: u64,
+        now: Instant,
+    ) -> GapProgress {
+        if gap == 0 {
+            return match self.channels.remove(channel) {
+                Some(previous) if previous.exceeded || previous.stalled => GapProgress::Closed,
+                _ => GapProgress::Unchanged,
+            };
+        }
+
+        let state = self
+            .channels
+            .entry(channel.clone())
+            .or_insert_with(|| ChannelGap {
+                next_sequence_recv,
+                since: now,
+                exceeded: false,
+                stalled: false,
+            });
+
+        if next_sequence_recv != state.next_sequence_recv {
+            state.next_sequence_recv = next_sequence_recv;
+            state.since = now;
+            state.stalled = false;
+        }
+
+        if gap <= config.max_gap {
+            state.exceeded = false;
+        } else if !state.exceeded {
+            state.exceeded = true;
+
+            return GapProgress::Exceeded { gap };
+        }
+
+        let duration = now.duration_since(state.since);
+
+        if !state.stalled && duration >= config.stall_threshold {
+            state.stalled = true;
+
+            return GapProgress::Stalled { gap, duration };
+        }
+
+        GapProgress::Unchanged
+    }
+
+    /// Forget about the channels which are not checked anymore.
+    pub fn retain(&mut self, mut keep: impl FnMut(&ChannelKey) -> bool) {
+        self.channels.retain(|channel, _| keep(channel));
+    }
+}
+
+/// The number of packets of an ordered channel committed on its sending end,
+/// given their sequences, which the receiving end did not receive yet, given
+/// its next sequence to receive.
+pub fn sequence_gap(commitments: &[u64], next_sequence_recv: u64) -> u64 {
+    commitments
+        .iter()
+        .filter(|&&sequence| sequence >= next_sequence_recv)
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    #[test]
+    fn exceeded_stalled_and_closed() {
+        let config = SequenceGapConfig {
+            enabled: true,
+            max_gap: 10,
+            stall_threshold: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        let channel = (
+            ChainId::new("ibc".to_string(), 0),
+            PortId::from_str("oracle").unwrap(),
+            ChannelId::from_str("channel-0").unwrap(),
+        );
+
+        let mut detector = GapDetector::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(sequence_gap(&[3, 4, 5, 6], 5), 2);
+
+        assert_eq!(
+            detector.record(&config, &channel, 5, 1, at(0)),
+            GapProgress::Unchanged
+        );
+
+        // Packets being received reset the time since the last one was,
+        // even though the gap does not shrink as new packets are sent
+        assert_eq!(
+            detector.record(&config, &channel, 5, 3, at(50)),
+            GapProgress::Unchanged
+        );
+        assert_eq!(
+            detector.record(&config, &channel, 5, 3, at(100)),
+            GapProgress::Unchanged
+        );
+        assert_eq!(
+            detector.record(&config, &channel, 12, 3, at(105)),
+            GapProgress::Exceeded { gap: 12 }
+        );
+        assert_eq!(
+            detector.record(&config, &channel, 12, 3, at(110)),
+            GapProgress::Stalled {
+                gap: 12,
+                duration: Duration::from_secs(60)
+            }
+        );
+
+        // The gap is only reported once
+        assert_eq!(
+            detector.record(&config, &channel, 15, 3, at(200)),
+            GapProgress::Unchanged
+        );
+
+        assert_eq!(
+            detector.record(&config, &channel, 0, 14, at(300)),
+            GapProgress::Closed
+        );
+        assert_eq!(
+            detector.record(&config, &channel, 0, 14, at(400)),
+            GapProgress::Unchanged
+        );
+    }
+}
@@ -1,15 +1,23 @@
-use tracing::{error, info};
+use std::collections::{BTreeMap, HashMap};
+
+use tracing::{debug, error, info};
 
 use ibc::core::{
-    ics02_client::client_state::ClientState, ics03_connection::connection::IdentifiedConnectionEnd,
-    ics04_channel::channel::State as ChannelState, ics24_host::identifier::ChainId,
+    ics02_client::client_state::ClientState,
+    ics03_connection::connection::IdentifiedConnectionEnd,
+    ics04_channel::channel::State as ChannelState,
+    ics24_host::identifier::{ChainId, ChannelId},
 };
 
 use crate::{
-    chain::{counterparty::connection_state_on_destination, handle::ChainHandle},
+    chain::{
+        counterparty::{connection_state_on_destination, unreceived_packets_of_paths},
+        handle::ChainHandle,
+    },
     client_state::IdentifiedAnyClientState,
     config::Config,
     object::{Channel, Client, Connection, Object, Packet, Wallet},
+    path::PathIdentifiers,
     registry::Registry,
     supervisor::error::Error as SupervisorError,
     telemetry,
@@ -77,7 +85,92 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
         telemetry!(self.spawn_wallet_worker(chain));
     }
 
+    /// Spawn workers for the given objects directly, without checking the state
+    /// of the underlying clients, connections and channels first, e.g. when
+    /// restoring the workers which were running before a restart.
+    ///
+    /// The objects which the mode or the packet filters exclude are skipped,
+    /// as they would have been by a scan.
+    pub fn spawn_workers_for_objects(&mut self, objects: Vec<Object>) {
+        for object in objects {
+            if !self.object_enabled(&object) {
+                debug!(
+                    "skipping worker for {}, reason: excluded by the mode or the packet filter",
+                    object.short_name()
+                );
+
+                continue;
+            }
+
+            let src = match self.registry.get_or_spawn(object.src_chain_id()) {
+                Ok(src) => src,
+                Err(e) => {
+                    error!(
+                        "skipping worker for {}, reason: failed to spawn chain runtime with error: {}",
+                        object.short_name(),
+                        e
+                    );
+
+                    continue;
+                }
+            };
+
+            let dst = match self.registry.get_or_spawn(object.dst_chain_id()) {
+                Ok(dst) => dst,
+                Err(e) => {
+                    error!(
+                        "skipping worker for {}, reason: failed to spawn chain runtime with error: {}",
+                        object.short_name(),
+                        e
+                    );
+
+                    continue;
+                }
+            };
+
+            self.workers
+                .spawn(src, dst, &object, self.config)
+                .then(|| info!("spawned worker: {}", object.short_name()));
+        }
+    }
+
+    /// Whether the mode and the packet filter of its source chain let a worker be
+    /// spawned for the given object, as when the chains are scanned.
+    fn object_enabled(&self, object: &Object) -> bool {
+        let config = self.config;
+        let mode = &config.mode;
+
+        match object {
+            Object::Client(_) => mode.clients.enabled,
+            Object::Connection(_) => mode.connections.enabled && !mode.watch_only,
+            Object::Channel(c) => {
+                mode.channels.enabled
+                    && !mode.watch_only
+                    && config.packets_on_channel_allowed(
+                        &c.src_chain_id,
+                        &c.src_port_id,
+                        &c.src_channel_id,
+                    )
+            }
+            Object::Packet(p) => {
+                mode.packets.enabled
+                    && config.packets_on_channel_allowed(
+                        &p.src_chain_id,
+                        &p.src_port_id,
+                        &p.src_channel_id,
+                    )
+            }
+            Object::CrossChainQueryPacket(_) => mode.packets.enabled && !mode.watch_only,
+            Object::Wallet(_) => !mode.watch_only,
+        }
+    }
+
     pub fn spawn_wallet_worker(&mut self, chain: Chain) {
+        // A watch-only relayer has no wallet
+        if self.config.mode.watch_only {
+            return;
+        }
+
         let wallet_object = Object::Wallet(Wallet {
             chain_id: chain.id(),
         });
@@ -131,8 +224,21 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
             ),
         }
 
+        let unreceived_packets = if self.config.mode.packets.enabled {
+            self.channels_with_unreceived_packets(&chain, client, &connection_scan.channels)
+        } else {
+            HashMap::new()
+        };
+
         for (channel_id, channel_scan) in connection_scan.channels {
-            match self.spawn_workers_for_channel(chain.clone(), client, channel_scan) {
+            let has_unreceived_packets = unreceived_packets.get(&channel_id).copied();
+
+            match self.spawn_workers_for_channel(
+                chain.clone(),
+                client,
+                channel_scan,
+                has_unreceived_packets,
+            ) {
                 Ok(true) => info!(
                     chain = %chain.id(),
                     channel = %channel_id,
@@ -159,7 +265,8 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
         client: IdentifiedAnyClientState,
         connection: IdentifiedConnectionEnd,
     ) -> Result<bool, Error> {
-        let config_conn_enabled = self.config.mode.connections.enabled;
+        let config_conn_enabled =
+            self.config.mode.connections.enabled && !self.config.mode.watch_only;
 
         let counterparty_chain = self
             .registry
@@ -212,13 +319,61 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
         }
     }
 
+    /// Whether the packets sent on each of the given channels of the chain were not all
+    /// received yet on the counterparty chain, the queries about all the channels being
+    /// batched together. The channels which could not be queried are left out.
+    fn channels_with_unreceived_packets(
+        &mut self,
+        chain: &Chain,
+        client: &IdentifiedAnyClientState,
+        channels: &BTreeMap<ChannelId, ChannelScan>,
+    ) -> HashMap<ChannelId, bool> {
+        let counterparty_chain = match self.registry.get_or_spawn(&client.client_state.chain_id()) {
+            Ok(counterparty_chain) => counterparty_chain,
+            Err(_) => return HashMap::new(),
+        };
+
+        let (channel_ids, paths): (Vec<_>, Vec<_>) = channels
+            .iter()
+            .filter_map(|(channel_id, channel_scan)| {
+                let counterparty = channel_scan.counterparty.clone()?;
+                let path = PathIdentifiers::from_channel_end(counterparty)?;
+                Some((channel_id.clone(), path))
+            })
+            .unzip();
+
+        if paths.is_empty() {
+            return HashMap::new();
+        }
+
+        match unreceived_packets_of_paths(&counterparty_chain, chain, &paths) {
+            Ok(unreceived) => channel_ids
+                .into_iter()
+                .zip(unreceived)
+                .filter_map(|(channel_id, sequences)| {
+                    sequences.map(|sequences| (channel_id, !sequences.is_empty()))
+                })
+                .collect(),
+            Err(e) => {
+                error!(
+                    chain = %chain.id(),
+                    "failed to query the unreceived packets of the channels: {}", e
+                );
+
+                HashMap::new()
+            }
+        }
+    }
+
     /// Spawns all the [`WorkerHandle`](crate::worker::WorkerHandle)s that will
-    /// handle a given channel for a given source chain.
+    /// handle a given channel for a given source chain. Whether the channel has
+    /// unreceived packets is queried, unless already known.
     pub fn spawn_workers_for_channel(
         &mut self,
         chain: Chain,
         client: &IdentifiedAnyClientState,
         channel_scan: ChannelScan,
+        has_unreceived_packets: Option<bool>,
     ) -> Result<bool, Error> {
         let _span = tracing::error_span!("channel", channel = %channel_scan.id()).entered();
 
@@ -244,10 +399,37 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
             chan_state_dst
         );
 
+        // The ends of a channel being upgraded relay their in-flight packets until they
+        // are flushed, after which they are reopened by the channel worker
+        let relays_packets = |state: ChannelState| {
+            state.is_open() || matches!(state, ChannelState::Flushing | ChannelState::FlushComplete)
+        };
+
+        let upgrading = !chan_state_src.is_open() || !chan_state_dst.is_open();
+
         if (mode.clients.enabled || mode.packets.enabled)
-            && chan_state_src.is_open()
-            && chan_state_dst.is_open()
+            && relays_packets(chan_state_src)
+            && relays_packets(chan_state_dst)
         {
+            if upgrading && mode.channels.enabled && !mode.watch_only {
+                // Spawn the channel worker driving the upgrade in progress to completion
+                let channel_object = Object::Channel(Channel {
+                    dst_chain_id: counterparty_chain.id(),
+                    src_chain_id: chain.id(),
+                    src_channel_id: channel_scan.channel.channel_id.clone(),
+                    src_port_id: channel_scan.channel.port_id.clone(),
+                });
+
+                self.workers
+                    .spawn(
+                        chain.clone(),
+                        counterparty_chain.clone(),
+                        &channel_object,
+                        self.config,
+                    )
+                    .then(|| info!("spawned channel worker: {}", channel_object.short_name()));
+            }
+
             if mode.clients.enabled {
                 // Spawn the client worker
                 let client_object = Object::Client(Client {
@@ -268,10 +450,12 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
 
             if mode.packets.enabled {
                 let has_packets = || {
-                    !channel_scan
-                        .unreceived_packets_on_counterparty(&chain, &counterparty_chain)
-                        .unwrap_or_default()
-                        .is_empty()
+                    has_unreceived_packets.unwrap_or_else(|| {
+                        !channel_scan
+                            .unreceived_packets_on_counterparty(&chain, &counterparty_chain)
+                            .unwrap_or_default()
+                            .is_empty()
+                    })
                 };
 
                 let has_acks = || {
@@ -303,7 +487,26 @@ impl<'a, Chain: ChainHandle> SpawnContext<'a, Chain> {
             }
 
             Ok(mode.clients.enabled)
+        } else if mode.packets.enabled
+            && chan_state_src == ChannelState::Closed
+            && chan_state_dst.is_open()
+        {
+            // The channel was closed on the source chain only, spawn a packet worker
+            // to flush the remaining packets and complete the close handshake.
+            let path_object = Object::Packet(Packet {
+                dst_chain_id: counterparty_chain.id(),
+                src_chain_id: chain.id(),
+                src_channel_id: channel_scan.channel.channel_id.clone(),
+                src_port_id: channel_scan.channel.port_id.clone(),
+            });
+
+            self.workers
+                .spawn(chain, counterparty_chain, &path_object, self.config)
+                .then(|| info!("spawned packet worker: {}", path_object.short_name()));
+
+            Ok(false)
         } else if mode.channels.enabled
+            && !mode.watch_only
             && !chan_state_dst.is_open()
             && chan_state_dst.less_or_equal_progress(chan_state_src)
         {
@@ -1,3 +1,6 @@
+pub mod forward;
+pub mod unwind;
+
 use ibc::signer::SignerError;
 use std::str::FromStr;
 
@@ -7,6 +10,7 @@ use flex_error::{define_error, DetailOnly};
 use ibc::applications::transfer::error::Error as Ics20Error;
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
 use ibc::applications::transfer::Amount;
+use ibc::core::ics04_channel::packet::Sequence;
 use ibc::core::ics04_channel::timeout::TimeoutHeight;
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::events::IbcEvent;
@@ -15,11 +19,16 @@ use ibc::timestamp::{Timestamp, TimestampOverflowError};
 use ibc::tx_msg::Msg;
 use ibc_proto::cosmos::base::v1beta1::Coin;
 use ibc_proto::google::protobuf::Any;
+use tracing::warn;
 
+use crate::block_time;
 use crate::chain::endpoint::ChainStatus;
 use crate::chain::handle::ChainHandle;
 use crate::chain::tracking::TrackedMsgs;
 use crate::error::Error;
+use crate::spawn::SpawnError;
+
+use self::forward::ForwardMemoError;
 
 define_error! {
     TransferError {
@@ -66,6 +75,40 @@ define_error! {
 
         ZeroTimeout
             | _ | { "packet timeout height and packet timeout timestamp cannot both be 0" },
+
+        InvalidDenomTrace
+            { denom: String }
+            [ Ics20Error ]
+            |e| {
+                format!("invalid denomination trace for denom '{}'", e.denom)
+            },
+
+        Spawn
+            [ SpawnError ]
+            |_| { "failed to spawn chain runtime" },
+
+        MissingSendPacket
+            { chain_id: ChainId }
+            |e| {
+                format!("no SendPacket event found in the transfer response from chain {}",
+                    e.chain_id)
+            },
+
+        ForwardMemo
+            [ ForwardMemoError ]
+            |_| { "invalid packet-forward-middleware memo" },
+
+        UnwindHopTimeout
+            {
+                chain_id: ChainId,
+                port_id: PortId,
+                channel_id: ChannelId,
+                sequence: Sequence,
+            }
+            |e| {
+                format!("timed out waiting for packet {}/{}/{} to be received on chain {} while unwinding the transfer path",
+                    e.port_id, e.channel_id, e.sequence, e.chain_id)
+            },
     }
 }
 
@@ -124,6 +167,9 @@ pub struct TransferOptions {
     pub timeout_height_offset: u64,
     pub timeout_duration: Duration,
     pub number_msgs: usize,
+    /// Optional memo to attach to the transfer, see [`forward::ForwardMemo`]
+    /// for building memos understood by the packet-forward-middleware.
+    pub memo: Option<String>,
 }
 
 pub fn build_transfer_message(
@@ -147,11 +193,59 @@ pub fn build_transfer_message(
         receiver,
         timeout_height,
         timeout_timestamp,
+        memo: String::new(),
     };
 
     msg.to_any()
 }
 
+/// Margin added to the timeout derived from the estimated block time of the destination
+/// chain, so that the packet times out by the timeout given in the options in practice.
+const DERIVED_TIMEOUT_MARGIN: f64 = 1.2;
+
+/// The timeout height offset and duration of a transfer with the given options, where
+/// the one which is missing, if any, is derived from the other one according to the
+/// estimated block time of the destination chain, so that the packet does not remain
+/// pending forever if the chain halts, nor if its clock goes wrong.
+fn derive_timeout(
+    dst_chain: &impl ChainHandle,
+    opts: &TransferOptions,
+    dst_status: &ChainStatus,
+) -> (u64, Duration) {
+    let given = (opts.timeout_height_offset, opts.timeout_duration);
+
+    let missing_height = opts.timeout_height_offset == 0;
+    if missing_height == (opts.timeout_duration == Duration::ZERO) {
+        return given;
+    }
+
+    let block_time = match block_time::estimate_or_sample(dst_chain, dst_status) {
+        Ok(Some(block_time)) => block_time,
+        Ok(None) => return given,
+        Err(e) => {
+            warn!(
+                "failed to estimate the block time of chain {}, not deriving the timeout: {}",
+                dst_chain.id(),
+                e
+            );
+
+            return given;
+        }
+    };
+
+    if missing_height {
+        let blocks = block_time::blocks_in(opts.timeout_duration, block_time);
+        let offset = (blocks as f64 * DERIVED_TIMEOUT_MARGIN).ceil() as u64;
+
+        (offset, opts.timeout_duration)
+    } else {
+        let duration = block_time::duration_of(opts.timeout_height_offset, block_time)
+            .mul_f64(DERIVED_TIMEOUT_MARGIN);
+
+        (opts.timeout_height_offset, duration)
+    }
+}
+
 pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHandle>(
     packet_src_chain: &SrcChain, // the chain whose account is debited
     packet_dst_chain: &DstChain, // the chain whose account eventually gets credited
@@ -168,9 +262,12 @@ pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHa
         .query_application_status()
         .map_err(TransferError::relayer)?;
 
+    let (timeout_height_offset, timeout_duration) =
+        derive_timeout(packet_dst_chain, opts, &destination_chain_status);
+
     let timeout = TransferTimeout::new(
-        opts.timeout_height_offset,
-        opts.timeout_duration,
+        timeout_height_offset,
+        timeout_duration,
         &destination_chain_status,
     )?;
 
@@ -185,6 +282,7 @@ pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHa
         receiver,
         timeout_height: timeout.timeout_height,
         timeout_timestamp: timeout.timeout_timestamp,
+        memo: opts.memo.clone().unwrap_or_default(),
     };
 
     let raw_msg = msg.to_any();
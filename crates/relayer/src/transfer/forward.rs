@@ -0,0 +1,275 @@
+//! Builder for memos understood by the packet-forward-middleware (PFM).
+//!
+//! When a chain running the PFM receives an ICS20 packet whose memo contains
+//! a `forward` object, it forwards the received funds to the next chain
+//! specified in that object, possibly with further hops nested in `next`:
+//!
+//! ```json
+//! {
+//!   "forward": {
+//!     "receiver": "osmo1...",
+//!     "port": "transfer",
+//!     "channel": "channel-141",
+//!     "timeout": "600s",
+//!     "retries": 2,
+//!     "next": { "forward": { ... } }
+//!   }
+//! }
+//! ```
+//!
+//! The receiver of the `MsgTransfer` carrying such a memo is ignored by the
+//! PFM on the intermediate chain, as the funds only transit through it.
+//!
+//! Use [`ForwardMemo::builder`] to build a memo hop by hop, in the order in
+//! which the funds will travel.
+
+use core::str::FromStr;
+use core::time::Duration;
+
+use flex_error::{define_error, TraceError};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+define_error! {
+    ForwardMemoError {
+        NoHops
+            |_| { "a forward memo must contain at least one hop" },
+
+        EmptyReceiver
+            { hop: usize }
+            |e| {
+                format!("receiver of forward hop {} cannot be empty", e.hop)
+            },
+
+        InvalidHop
+            { hop: String }
+            |e| {
+                format!("invalid forward hop '{}', expected `{{port}}/{{channel}}/{{receiver}}`", e.hop)
+            },
+
+        ZeroTimeout
+            { hop: usize }
+            |e| {
+                format!("timeout of forward hop {} must be at least one second", e.hop)
+            },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode forward memo as JSON" },
+    }
+}
+
+/// A single hop of a multi-hop transfer through the packet-forward-middleware.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardHop {
+    /// The address receiving the funds on the chain at the end of this hop
+    pub receiver: String,
+    /// The port used to forward the funds on this hop
+    pub port: PortId,
+    /// The channel used to forward the funds on this hop
+    pub channel: ChannelId,
+    /// How long the forwarded packet remains valid, uses the PFM default if `None`
+    pub timeout: Option<Duration>,
+    /// How many times to retry forwarding on failure, uses the PFM default if `None`
+    pub retries: Option<u8>,
+}
+
+impl ForwardHop {
+    pub fn new(receiver: impl Into<String>, port: PortId, channel: ChannelId) -> Self {
+        Self {
+            receiver: receiver.into(),
+            port,
+            channel,
+            timeout: None,
+            retries: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u8) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+}
+
+/// Parses a hop of the form `{port}/{channel}/{receiver}`.
+impl FromStr for ForwardHop {
+    type Err = ForwardMemoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ForwardMemoError::invalid_hop(s.to_string());
+
+        let mut parts = s.splitn(3, '/');
+
+        let port = parts
+            .next()
+            .and_then(|port| PortId::from_str(port).ok())
+            .ok_or_else(invalid)?;
+
+        let channel = parts
+            .next()
+            .and_then(|channel| ChannelId::from_str(channel).ok())
+            .ok_or_else(invalid)?;
+
+        let receiver = parts
+            .next()
+            .filter(|receiver| !receiver.trim().is_empty())
+            .ok_or_else(invalid)?;
+
+        Ok(Self::new(receiver, port, channel))
+    }
+}
+
+/// A validated memo for the packet-forward-middleware.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ForwardMemo {
+    forward: Forward,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct Forward {
+    receiver: String,
+    port: String,
+    channel: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<Box<ForwardMemo>>,
+}
+
+impl ForwardMemo {
+    pub fn builder() -> ForwardMemoBuilder {
+        ForwardMemoBuilder::default()
+    }
+
+    /// Encode the memo as the JSON string to set in the `memo` field of a `MsgTransfer`.
+    pub fn to_json(&self) -> Result<String, ForwardMemoError> {
+        serde_json::to_string(self).map_err(ForwardMemoError::encode)
+    }
+}
+
+/// Builder for a [`ForwardMemo`], with hops added in the order in which
+/// the funds travel.
+#[derive(Clone, Debug, Default)]
+pub struct ForwardMemoBuilder {
+    hops: Vec<ForwardHop>,
+}
+
+impl ForwardMemoBuilder {
+    pub fn hop(mut self, hop: ForwardHop) -> Self {
+        self.hops.push(hop);
+        self
+    }
+
+    pub fn build(self) -> Result<ForwardMemo, ForwardMemoError> {
+        if self.hops.is_empty() {
+            return Err(ForwardMemoError::no_hops());
+        }
+
+        for (i, hop) in self.hops.iter().enumerate() {
+            if hop.receiver.trim().is_empty() {
+                return Err(ForwardMemoError::empty_receiver(i));
+            }
+
+            if matches!(hop.timeout, Some(timeout) if timeout.as_secs() == 0) {
+                return Err(ForwardMemoError::zero_timeout(i));
+            }
+        }
+
+        // Nest the hops starting from the last one
+        let memo = self.hops.into_iter().rev().fold(None, |next, hop| {
+            Some(ForwardMemo {
+                forward: Forward {
+                    receiver: hop.receiver,
+                    port: hop.port.to_string(),
+                    channel: hop.channel.to_string(),
+                    timeout: hop.timeout.map(|t| format!("{}s", t.as_secs())),
+                    retries: hop.retries,
+                    next: next.map(Box::new),
+                },
+            })
+        });
+
+        Ok(memo.expect("there is at least one hop"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    fn hop(receiver: &str, channel: &str) -> ForwardHop {
+        ForwardHop::new(
+            receiver,
+            PortId::transfer(),
+            ChannelId::from_str(channel).unwrap(),
+        )
+    }
+
+    #[test]
+    fn single_hop_memo() {
+        let memo = ForwardMemo::builder()
+            .hop(hop("osmo1receiver", "channel-0"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            memo.to_json().unwrap(),
+            r#"{"forward":{"receiver":"osmo1receiver","port":"transfer","channel":"channel-0"}}"#
+        );
+    }
+
+    #[test]
+    fn nested_hops_memo() {
+        let memo = ForwardMemo::builder()
+            .hop(
+                hop("juno1receiver", "channel-1")
+                    .with_timeout(Duration::from_secs(600))
+                    .with_retries(2),
+            )
+            .hop(hop("osmo1receiver", "channel-42"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            memo.to_json().unwrap(),
+            r#"{"forward":{"receiver":"juno1receiver","port":"transfer","channel":"channel-1","timeout":"600s","retries":2,"next":{"forward":{"receiver":"osmo1receiver","port":"transfer","channel":"channel-42"}}}}"#
+        );
+    }
+
+    #[test]
+    fn parse_hop() {
+        assert_eq!(
+            ForwardHop::from_str("transfer/channel-141/osmo1receiver").unwrap(),
+            hop("osmo1receiver", "channel-141")
+        );
+
+        assert!(ForwardHop::from_str("transfer/channel-141").is_err());
+        assert!(ForwardHop::from_str("transfer/channel-141/ ").is_err());
+        assert!(ForwardHop::from_str("transfer/osmo1receiver").is_err());
+    }
+
+    #[test]
+    fn invalid_memos() {
+        assert!(ForwardMemo::builder().build().is_err());
+
+        assert!(ForwardMemo::builder()
+            .hop(hop(" ", "channel-0"))
+            .build()
+            .is_err());
+
+        assert!(ForwardMemo::builder()
+            .hop(hop("osmo1receiver", "channel-0").with_timeout(Duration::from_millis(10)))
+            .build()
+            .is_err());
+    }
+}
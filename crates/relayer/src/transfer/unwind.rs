@@ -0,0 +1,599 @@
+//! Unwinding of the denomination trace of an ICS20 token.
+//!
+//! A token which went through several chains before reaching the chain
+//! it is currently held on carries a denomination trace of the form
+//! `{port_n}/{channel_n}/.../{port_1}/{channel_1}/{base_denom}`.
+//! Sending such a token directly to a third chain would add yet another
+//! prefix to its trace, making it non-fungible with the same token
+//! received via its origin chain.
+//!
+//! Unwinding consists in sending the token back along the path it came
+//! from, one hop at a time, until it reaches its origin chain, from which
+//! it can then be forwarded to its final destination. When all the chains
+//! on the route run the packet-forward-middleware, the whole route can
+//! instead be performed with a single transfer carrying a forward memo.
+
+use core::time::Duration;
+use std::str::FromStr;
+use std::thread;
+use std::time::Instant;
+
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use ibc::applications::transfer::denom::TracePath;
+use ibc::core::ics04_channel::packet::Packet;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::IbcEvent;
+
+use crate::block_time;
+use crate::chain::counterparty::counterparty_chain_from_channel;
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::QueryUnreceivedPacketsRequest;
+use crate::denom::DenomTrace;
+use crate::registry::Registry;
+
+use super::forward::{ForwardHop, ForwardMemo};
+use super::{build_and_send_transfer_messages, TransferError, TransferOptions};
+
+/// Prefix of the denominations of tokens received over IBC.
+const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// How often to check whether the packet sent on a given hop was received,
+/// when the block time of the chain it is sent to is unknown.
+const HOP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Receiver of the funds on the intermediate chains of a route performed through
+/// the packet-forward-middleware, which ignores it as the funds only transit there.
+const FORWARD_INTERMEDIATE_RECEIVER: &str = "pfm";
+
+/// How the hops of an unwinding route are performed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnwindMode {
+    /// Each hop is performed with a separate transfer, waiting at most the given
+    /// duration for the packet of a hop to be received before performing the next one
+    Sequential { hop_timeout: Duration },
+    /// A single transfer is sent on the first hop, with a memo instructing the
+    /// packet-forward-middleware of the chains on the route to perform the next ones
+    Forward,
+}
+
+/// A single hop of an unwinding route.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnwindHop {
+    /// The chain sending the token on this hop
+    pub src_chain_id: ChainId,
+    /// The port on which the token is sent
+    pub src_port_id: PortId,
+    /// The channel on which the token is sent
+    pub src_channel_id: ChannelId,
+    /// The chain receiving the token on this hop
+    pub dst_chain_id: ChainId,
+    /// The denomination of the token on the sending chain
+    pub denom: String,
+}
+
+/// The sequence of hops needed to bring a token back to its origin chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnwindRoute {
+    /// The chain where the token is held before unwinding
+    pub src_chain_id: ChainId,
+    /// The base denomination of the token on its origin chain
+    pub base_denom: String,
+    /// The hops to perform, in order
+    pub hops: Vec<UnwindHop>,
+}
+
+impl UnwindRoute {
+    /// The chain on which the token ends up after all hops are performed.
+    pub fn origin_chain_id(&self) -> &ChainId {
+        self.hops
+            .last()
+            .map(|hop| &hop.dst_chain_id)
+            .unwrap_or(&self.src_chain_id)
+    }
+
+    /// Whether or not the token is already on its origin chain.
+    pub fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+
+    /// The hops to be performed by the packet-forward-middleware once the token is
+    /// sent on the first hop of the route, followed by the transfer from the origin
+    /// chain over the given port and channel, if any, delivering the funds to the
+    /// given receiver.
+    pub fn forward_hops(
+        &self,
+        final_channel: Option<(&PortId, &ChannelId)>,
+        receiver: &str,
+        timeout: Option<Duration>,
+    ) -> Vec<ForwardHop> {
+        let mut channels = self
+            .hops
+            .iter()
+            .skip(1)
+            .map(|hop| (&hop.src_port_id, &hop.src_channel_id))
+            .chain(final_channel)
+            .peekable();
+
+        let mut hops = vec![];
+
+        while let Some((port_id, channel_id)) = channels.next() {
+            let receiver = if channels.peek().is_some() {
+                FORWARD_INTERMEDIATE_RECEIVER
+            } else {
+                receiver
+            };
+
+            let hop = ForwardHop::new(receiver, port_id.clone(), channel_id.clone());
+
+            hops.push(match timeout {
+                Some(timeout) => hop.with_timeout(timeout),
+                None => hop,
+            });
+        }
+
+        hops
+    }
+}
+
+/// Computes the on-chain denomination of a token with the given trace, ie.
+/// the base denomination if the trace path is empty, and
+/// `ibc/{hash}` otherwise, where `hash` is the uppercase hex-encoded SHA256
+/// hash of `{path}/{base_denom}`.
+pub fn ibc_denom(trace: &DenomTrace) -> String {
+    if trace.path.is_empty() {
+        return trace.base_denom.clone();
+    }
+
+    let full_path = format!("{}/{}", trace.path, trace.base_denom);
+    let hash = Sha256::digest(full_path.as_bytes());
+
+    format!("{}{}", IBC_DENOM_PREFIX, hex::encode_upper(hash))
+}
+
+/// Splits the given trace path into its `(port, channel)` prefixes,
+/// starting with the prefix added by the chain which received the token last.
+pub fn trace_prefixes(path: &str) -> Result<Vec<(PortId, ChannelId)>, TransferError> {
+    // Validate the path using the ICS20 domain type before splitting it.
+    TracePath::from_str(path)
+        .map_err(|e| TransferError::invalid_denom_trace(path.to_string(), e))?;
+
+    if path.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let parts = path.split('/').collect::<Vec<_>>();
+
+    let prefixes = parts
+        .chunks_exact(2)
+        .map(|pair| {
+            // Both identifiers were validated above
+            let port_id = PortId::from_str(pair[0]).expect("valid port identifier");
+            let channel_id = ChannelId::from_str(pair[1]).expect("valid channel identifier");
+            (port_id, channel_id)
+        })
+        .collect();
+
+    Ok(prefixes)
+}
+
+/// Computes the route to bring the token with denomination `denom`,
+/// held on chain `src_chain_id`, back to its origin chain.
+///
+/// The chain runtimes of the intermediate chains are spawned through the
+/// given registry, hence these chains must be present in its configuration.
+pub fn unwind_route<Chain: ChainHandle>(
+    registry: &mut Registry<Chain>,
+    src_chain_id: &ChainId,
+    denom: &str,
+) -> Result<UnwindRoute, TransferError> {
+    let hash = match denom.strip_prefix(IBC_DENOM_PREFIX) {
+        Some(hash) => hash,
+        None => {
+            // Native token, nothing to unwind
+            return Ok(UnwindRoute {
+                src_chain_id: src_chain_id.clone(),
+                base_denom: denom.to_string(),
+                hops: vec![],
+            });
+        }
+    };
+
+    let mut chain = registry
+        .get_or_spawn(src_chain_id)
+        .map_err(TransferError::spawn)?;
+
+    let trace = chain
+        .query_denom_trace(hash.to_string())
+        .map_err(TransferError::relayer)?;
+
+    let prefixes = trace_prefixes(&trace.path)?;
+
+    let mut hops = Vec::with_capacity(prefixes.len());
+
+    for (i, (port_id, channel_id)) in prefixes.iter().enumerate() {
+        let dst_chain_id = counterparty_chain_from_channel(&chain, channel_id, port_id)
+            .map_err(TransferError::relayer)?;
+
+        // The trace of the token on the current chain is made of the
+        // prefixes which have not been unwound yet.
+        let remaining_path = prefixes[i..]
+            .iter()
+            .map(|(port_id, channel_id)| format!("{}/{}", port_id, channel_id))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let denom = ibc_denom(&DenomTrace {
+            path: remaining_path,
+            base_denom: trace.base_denom.clone(),
+        });
+
+        hops.push(UnwindHop {
+            src_chain_id: chain.id(),
+            src_port_id: port_id.clone(),
+            src_channel_id: channel_id.clone(),
+            dst_chain_id: dst_chain_id.clone(),
+            denom,
+        });
+
+        if i + 1 < prefixes.len() {
+            chain = registry
+                .get_or_spawn(&dst_chain_id)
+                .map_err(TransferError::spawn)?;
+        }
+    }
+
+    Ok(UnwindRoute {
+        src_chain_id: src_chain_id.clone(),
+        base_denom: trace.base_denom,
+        hops,
+    })
+}
+
+/// Unwinds the path of the token specified in `opts.denom`, held on chain
+/// `src_chain_id`, and then sends it from its origin chain to chain
+/// `dst_chain_id` over the port and channel specified in `opts`.
+///
+/// In sequential mode, each hop is performed with a separate transfer, whose
+/// funds are received by the relayer account on the next chain. Before performing
+/// the next hop, this function waits (for at most `hop_timeout`) until the packet
+/// of the previous hop has been received, which requires a relayer to be running
+/// on the corresponding path. In forward mode, a single transfer is sent, see
+/// [`UnwindMode::Forward`].
+///
+/// If the origin chain of the token is `dst_chain_id` itself, the final
+/// transfer is skipped and the last hop delivers the funds to the receiver.
+///
+/// Returns the events emitted by all the transfers that were performed.
+pub fn build_and_send_unwind_transfer_messages<Chain: ChainHandle>(
+    registry: &mut Registry<Chain>,
+    src_chain_id: &ChainId,
+    dst_chain_id: &ChainId,
+    opts: &TransferOptions,
+    mode: UnwindMode,
+) -> Result<Vec<IbcEvent>, TransferError> {
+    let route = unwind_route(registry, src_chain_id, &opts.denom)?;
+
+    info!(
+        "unwinding denom '{}' from chain {} to origin chain {} in {} hop(s)",
+        opts.denom,
+        src_chain_id,
+        route.origin_chain_id(),
+        route.hops.len()
+    );
+
+    let hop_timeout = match mode {
+        UnwindMode::Sequential { hop_timeout } => hop_timeout,
+        UnwindMode::Forward => return send_forward_unwind(registry, &route, dst_chain_id, opts),
+    };
+
+    let arrives_at_destination = route.origin_chain_id() == dst_chain_id;
+    let hop_count = route.hops.len();
+
+    let mut events = vec![];
+
+    for (i, hop) in route.hops.iter().enumerate() {
+        let is_last_hop = i + 1 == hop_count;
+
+        let hop_src_chain = registry
+            .get_or_spawn(&hop.src_chain_id)
+            .map_err(TransferError::spawn)?;
+
+        let hop_dst_chain = registry
+            .get_or_spawn(&hop.dst_chain_id)
+            .map_err(TransferError::spawn)?;
+
+        // Intermediate hops credit the relayer account on the next chain,
+        // so that it can send the funds on the next hop. The memo is only
+        // meant for the transfer delivering the funds to the receiver.
+        let (receiver, memo) = if is_last_hop && arrives_at_destination {
+            (opts.receiver.clone(), opts.memo.clone())
+        } else {
+            (None, None)
+        };
+
+        let hop_opts = TransferOptions {
+            packet_src_port_id: hop.src_port_id.clone(),
+            packet_src_channel_id: hop.src_channel_id.clone(),
+            denom: hop.denom.clone(),
+            receiver,
+            memo,
+            number_msgs: 1,
+            ..opts.clone()
+        };
+
+        debug!(
+            "unwinding hop {}/{}: {} -> {} over {}/{}",
+            i + 1,
+            hop_count,
+            hop.src_chain_id,
+            hop.dst_chain_id,
+            hop.src_port_id,
+            hop.src_channel_id
+        );
+
+        let hop_events =
+            build_and_send_transfer_messages(&hop_src_chain, &hop_dst_chain, &hop_opts)?;
+
+        let packet = hop_events
+            .iter()
+            .find_map(|event| match event {
+                IbcEvent::SendPacket(send_packet) => Some(send_packet.packet.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| TransferError::missing_send_packet(hop.src_chain_id.clone()))?;
+
+        events.extend(hop_events);
+
+        if !(is_last_hop && arrives_at_destination) {
+            wait_for_packet_receipt(&hop_dst_chain, &packet, hop_timeout)?;
+        }
+    }
+
+    if !arrives_at_destination {
+        let origin_chain = registry
+            .get_or_spawn(route.origin_chain_id())
+            .map_err(TransferError::spawn)?;
+
+        let dst_chain = registry
+            .get_or_spawn(dst_chain_id)
+            .map_err(TransferError::spawn)?;
+
+        let final_opts = TransferOptions {
+            denom: route.base_denom.clone(),
+            ..opts.clone()
+        };
+
+        let final_events =
+            build_and_send_transfer_messages(&origin_chain, &dst_chain, &final_opts)?;
+
+        events.extend(final_events);
+    }
+
+    Ok(events)
+}
+
+/// Sends the token on the first hop of the route, with a memo for the
+/// packet-forward-middleware to perform the remaining hops and the final transfer.
+fn send_forward_unwind<Chain: ChainHandle>(
+    registry: &mut Registry<Chain>,
+    route: &UnwindRoute,
+    dst_chain_id: &ChainId,
+    opts: &TransferOptions,
+) -> Result<Vec<IbcEvent>, TransferError> {
+    let first_hop = match route.hops.first() {
+        Some(hop) => hop,
+        None => {
+            // Native token, sent directly to the destination chain
+            let src_chain = registry
+                .get_or_spawn(&route.src_chain_id)
+                .map_err(TransferError::spawn)?;
+
+            let dst_chain = registry
+                .get_or_spawn(dst_chain_id)
+                .map_err(TransferError::spawn)?;
+
+            return build_and_send_transfer_messages(&src_chain, &dst_chain, opts);
+        }
+    };
+
+    let receiver = match &opts.receiver {
+        Some(receiver) => receiver.clone(),
+        None => registry
+            .get_or_spawn(dst_chain_id)
+            .map_err(TransferError::spawn)?
+            .get_signer()
+            .map_err(TransferError::key)?
+            .to_string(),
+    };
+
+    let final_channel = if route.origin_chain_id() == dst_chain_id {
+        None
+    } else {
+        Some((&opts.packet_src_port_id, &opts.packet_src_channel_id))
+    };
+
+    let timeout = Some(opts.timeout_duration).filter(|timeout| !timeout.is_zero());
+    let forward_hops = route.forward_hops(final_channel, &receiver, timeout);
+
+    let (receiver, memo) = if forward_hops.is_empty() {
+        (receiver, opts.memo.clone())
+    } else {
+        let memo = forward_hops
+            .into_iter()
+            .fold(ForwardMemo::builder(), |builder, hop| builder.hop(hop))
+            .build()
+            .and_then(|memo| memo.to_json())
+            .map_err(TransferError::forward_memo)?;
+
+        (FORWARD_INTERMEDIATE_RECEIVER.to_string(), Some(memo))
+    };
+
+    let src_chain = registry
+        .get_or_spawn(&first_hop.src_chain_id)
+        .map_err(TransferError::spawn)?;
+
+    let next_chain = registry
+        .get_or_spawn(&first_hop.dst_chain_id)
+        .map_err(TransferError::spawn)?;
+
+    let forward_opts = TransferOptions {
+        packet_src_port_id: first_hop.src_port_id.clone(),
+        packet_src_channel_id: first_hop.src_channel_id.clone(),
+        denom: first_hop.denom.clone(),
+        receiver: Some(receiver),
+        memo,
+        number_msgs: 1,
+        ..opts.clone()
+    };
+
+    debug!(
+        "unwinding through the packet-forward-middleware from {} over {}/{}",
+        first_hop.src_chain_id, first_hop.src_port_id, first_hop.src_channel_id
+    );
+
+    build_and_send_transfer_messages(&src_chain, &next_chain, &forward_opts)
+}
+
+/// Waits until the given packet has been received on the destination chain.
+fn wait_for_packet_receipt<Chain: ChainHandle>(
+    dst_chain: &Chain,
+    packet: &Packet,
+    timeout: Duration,
+) -> Result<(), TransferError> {
+    let start = Instant::now();
+
+    loop {
+        let unreceived = dst_chain
+            .query_unreceived_packets(QueryUnreceivedPacketsRequest {
+                port_id: packet.destination_port.clone(),
+                channel_id: packet.destination_channel.clone(),
+                packet_commitment_sequences: vec![packet.sequence],
+            })
+            .map_err(TransferError::relayer)?;
+
+        if unreceived.is_empty() {
+            return Ok(());
+        }
+
+        if start.elapsed() > timeout {
+            return Err(TransferError::unwind_hop_timeout(
+                dst_chain.id(),
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+                packet.sequence,
+            ));
+        }
+
+        // Poll once per block of the destination chain
+        thread::sleep(block_time::estimate(&dst_chain.id()).unwrap_or(HOP_POLL_INTERVAL));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ibc_denom_of_native_token() {
+        let trace = DenomTrace {
+            path: "".to_string(),
+            base_denom: "uatom".to_string(),
+        };
+
+        assert_eq!(ibc_denom(&trace), "uatom");
+    }
+
+    #[test]
+    fn ibc_denom_of_transferred_token() {
+        let trace = DenomTrace {
+            path: "transfer/channel-0".to_string(),
+            base_denom: "uatom".to_string(),
+        };
+
+        // Well-known hash of `transfer/channel-0/uatom`
+        assert_eq!(
+            ibc_denom(&trace),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test]
+    fn trace_prefixes_in_unwind_order() {
+        let prefixes = trace_prefixes("transfer/channel-1/transfer/channel-5").unwrap();
+
+        assert_eq!(
+            prefixes,
+            vec![
+                (
+                    PortId::from_str("transfer").unwrap(),
+                    ChannelId::from_str("channel-1").unwrap()
+                ),
+                (
+                    PortId::from_str("transfer").unwrap(),
+                    ChannelId::from_str("channel-5").unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn forward_hops_of_route() {
+        let chain_id = |name: &str| ChainId::new(name.to_string(), 0);
+        let channel_id = |id: &str| ChannelId::from_str(id).unwrap();
+
+        let hop = |src: &str, dst: &str, channel: &str| UnwindHop {
+            src_chain_id: chain_id(src),
+            src_port_id: PortId::transfer(),
+            src_channel_id: channel_id(channel),
+            dst_chain_id: chain_id(dst),
+            denom: "ibc/DENOM".to_string(),
+        };
+
+        let route = UnwindRoute {
+            src_chain_id: chain_id("a"),
+            base_denom: "uatom".to_string(),
+            hops: vec![hop("a", "b", "channel-1"), hop("b", "c", "channel-2")],
+        };
+
+        let final_channel = channel_id("channel-3");
+        let hops = route.forward_hops(
+            Some((&PortId::transfer(), &final_channel)),
+            "receiver",
+            None,
+        );
+
+        assert_eq!(
+            hops,
+            vec![
+                ForwardHop::new(
+                    FORWARD_INTERMEDIATE_RECEIVER,
+                    PortId::transfer(),
+                    channel_id("channel-2")
+                ),
+                ForwardHop::new("receiver", PortId::transfer(), final_channel),
+            ]
+        );
+
+        // Arriving at the destination by unwinding, the last hop delivers the funds
+        assert_eq!(
+            route.forward_hops(None, "receiver", None),
+            vec![ForwardHop::new(
+                "receiver",
+                PortId::transfer(),
+                channel_id("channel-2")
+            )]
+        );
+    }
+
+    #[test]
+    fn trace_prefixes_of_empty_path() {
+        assert!(trace_prefixes("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn trace_prefixes_of_invalid_path() {
+        assert!(trace_prefixes("transfer").is_err());
+    }
+}
@@ -12,7 +12,6 @@ use ibc::clients::ics07_tendermint::client_state::UpgradeOptions;
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics24_host::identifier::{ChainId, ClientId};
 use ibc::downcast;
-use ibc_proto::cosmos::gov::v1beta1::MsgSubmitProposal;
 use ibc_proto::cosmos::upgrade::v1beta1::Plan;
 use ibc_proto::google::protobuf::Any;
 use ibc_proto::ibc::core::client::v1::UpgradeProposal;
@@ -23,6 +22,21 @@ use crate::chain::tracking::TrackedMsgs;
 use crate::client_state::AnyClientState;
 use crate::config::ChainConfig;
 use crate::error::Error;
+use crate::gov::{gov_authority, submit_legacy_proposal, submit_proposal, GovVersion};
+
+pub const MSG_IBC_SOFTWARE_UPGRADE_TYPE_URL: &str = "/ibc.core.client.v1.MsgIBCSoftwareUpgrade";
+
+/// The `ibc.core.client.v1.MsgIBCSoftwareUpgrade` of ibc-go v8, which the ibc-go
+/// version the definitions of `ibc-proto` are generated from predates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgIbcSoftwareUpgrade {
+    #[prost(message, optional, tag = "1")]
+    pub plan: Option<Plan>,
+    #[prost(message, optional, tag = "2")]
+    pub upgraded_client_state: Option<Any>,
+    #[prost(string, tag = "3")]
+    pub signer: String,
+}
 
 define_error! {
     UpgradeChainError {
@@ -48,7 +62,11 @@ define_error! {
             },
 
         TendermintOnly
-            |_| { "only Tendermint clients can be upgraded" }
+            |_| { "only Tendermint clients can be upgraded" },
+
+        GovAuthority
+            [ Error ]
+            |_| { "failed to derive the address of the governance module" },
     }
 }
 
@@ -101,27 +119,18 @@ pub fn build_and_send_ibc_upgrade_proposal(
         opts.upgraded_chain_id.clone(),
     );
 
-    let proposal = UpgradeProposal {
-        title: "proposal 0".to_string(),
-        description: "upgrade the chain software and unbonding period".to_string(),
-        upgraded_client_state: Some(Any::from(AnyClientState::from(client_state))),
-        plan: Some(Plan {
-            name: opts.upgrade_plan_name.clone(),
-            height: upgrade_height.revision_height() as i64,
-            info: "".to_string(),
-            ..Default::default() // deprecated fields - time & upgraded_client_state
-        }),
-    };
-
-    let proposal = Proposal::Default(proposal);
+    let upgraded_client_state = Any::from(AnyClientState::from(client_state));
 
-    let mut buf_proposal = Vec::new();
-    proposal.encode(&mut buf_proposal);
-    let any_proposal = Any {
-        type_url: proposal.type_url(),
-        value: buf_proposal,
+    let plan = Plan {
+        name: opts.upgrade_plan_name.clone(),
+        height: upgrade_height.revision_height() as i64,
+        info: "".to_string(),
+        ..Default::default() // deprecated fields - time & upgraded_client_state
     };
 
+    let title = "proposal 0".to_string();
+    let description = "upgrade the chain software and unbonding period".to_string();
+
     // build the msg submit proposal
     let proposer = dst_chain.get_signer().map_err(UpgradeChainError::key)?;
 
@@ -130,17 +139,53 @@ pub fn build_and_send_ibc_upgrade_proposal(
         amount: opts.amount.to_string(),
     };
 
-    let msg = MsgSubmitProposal {
-        content: Some(any_proposal),
-        initial_deposit: vec![coins],
-        proposer: proposer.to_string(),
-    };
-
-    let mut buf_msg = Vec::new();
-    prost::Message::encode(&msg, &mut buf_msg).unwrap();
-    let any_msg = Any {
-        type_url: "/cosmos.gov.v1beta1.MsgSubmitProposal".to_string(),
-        value: buf_msg,
+    let gov_version = GovVersion::detect(&dst_chain).map_err(UpgradeChainError::query)?;
+
+    let any_msg = match gov_version {
+        GovVersion::V1 => {
+            let authority = gov_authority(&opts.dst_chain_config.account_prefix)
+                .map_err(UpgradeChainError::gov_authority)?;
+
+            let msg = MsgIbcSoftwareUpgrade {
+                plan: Some(plan),
+                upgraded_client_state: Some(upgraded_client_state),
+                signer: authority,
+            };
+
+            let mut buf_msg = Vec::new();
+            prost::Message::encode(&msg, &mut buf_msg).unwrap();
+            let any_upgrade = Any {
+                type_url: MSG_IBC_SOFTWARE_UPGRADE_TYPE_URL.to_string(),
+                value: buf_msg,
+            };
+
+            submit_proposal(
+                vec![any_upgrade],
+                coins,
+                proposer.to_string(),
+                title,
+                description,
+            )
+        }
+        GovVersion::Legacy => {
+            let proposal = UpgradeProposal {
+                title,
+                description,
+                upgraded_client_state: Some(upgraded_client_state),
+                plan: Some(plan),
+            };
+
+            let proposal = Proposal::Default(proposal);
+
+            let mut buf_proposal = Vec::new();
+            proposal.encode(&mut buf_proposal);
+            let any_proposal = Any {
+                type_url: proposal.type_url(),
+                value: buf_proposal,
+            };
+
+            submit_legacy_proposal(any_proposal, coins, proposer.to_string())
+        }
     };
 
     // Can't use send_messages_and_wait_commit because no IBC events
@@ -3,9 +3,12 @@ pub use block_on::block_on;
 
 pub mod diff;
 pub mod iter;
+pub mod line_log;
 pub mod lock;
 pub mod pretty;
 pub mod queue;
+pub mod recent_errors;
 pub mod retry;
+pub mod state_file;
 pub mod stream;
 pub mod task;
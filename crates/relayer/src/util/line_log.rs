@@ -0,0 +1,80 @@
+//! Logs of JSON-encoded entries, one per line, appended to by a dedicated thread,
+//! so that the threads recording the entries, e.g. on the handling path of the
+//! events, never wait on each other nor on the file.
+
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::thread;
+
+use crossbeam_channel::{bounded, Sender, TrySendError};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tracing::warn;
+
+/// Maximum number of entries waiting to be written, beyond which the new entries are
+/// dropped rather than holding up the threads recording them.
+const MAX_PENDING_ENTRIES: usize = 10_000;
+
+/// A log to which the entries are recorded once it is started, multiple-producer
+/// and lock-free, meant to be held in a `static`.
+pub struct LineLog<T> {
+    name: &'static str,
+    sender: OnceCell<Sender<T>>,
+}
+
+impl<T: Serialize + Send + 'static> LineLog<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            sender: OnceCell::new(),
+        }
+    }
+
+    /// Start recording the entries to the given file, from a thread writing them in the
+    /// order they were recorded. Returns `false` if the log was already started.
+    pub fn start(&self, file: File) -> bool {
+        let (sender, receiver) = bounded::<T>(MAX_PENDING_ENTRIES);
+
+        if self.sender.set(sender).is_err() {
+            return false;
+        }
+
+        let name = self.name;
+
+        thread::spawn(move || {
+            let mut writer = LineWriter::new(file);
+
+            for entry in receiver {
+                let result = serde_json::to_string(&entry)
+                    .map_err(|e| e.to_string())
+                    .and_then(|line| writeln!(writer, "{}", line).map_err(|e| e.to_string()));
+
+                if let Err(e) = result {
+                    warn!("failed to record entry in the {}: {}", name, e);
+                }
+            }
+        });
+
+        true
+    }
+
+    /// Whether the log was started.
+    pub fn is_recording(&self) -> bool {
+        self.sender.get().is_some()
+    }
+
+    /// Record the given entry, if the log was started.
+    pub fn record(&self, entry: T) {
+        let sender = match self.sender.get() {
+            Some(sender) => sender,
+            None => return,
+        };
+
+        if let Err(TrySendError::Full(_)) = sender.try_send(entry) {
+            warn!(
+                "dropping entry of the {}, too many entries are waiting to be written",
+                self.name
+            );
+        }
+    }
+}
@@ -58,6 +58,13 @@ impl<T: Clone> Queue<T> {
     }
 }
 
+/// The clones of a queue share its entries.
+impl<T> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        Queue(self.0.clone())
+    }
+}
+
 impl<T> Default for Queue<T> {
     fn default() -> Self {
         Self::new()
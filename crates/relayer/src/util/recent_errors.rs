@@ -0,0 +1,60 @@
+//! A bounded log of the most recent errors encountered by the background
+//! tasks of the relayer, exposed through the REST API for operators.
+
+use alloc::collections::VecDeque;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// How many errors are kept in the log, the oldest ones being dropped first.
+pub const MAX_RECENT_ERRORS: usize = 100;
+
+static RECENT_ERRORS: Lazy<RwLock<VecDeque<RecentError>>> =
+    Lazy::new(|| RwLock::new(VecDeque::with_capacity(MAX_RECENT_ERRORS)));
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentError {
+    /// When the error occurred, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Name of the task which encountered the error
+    pub task: String,
+    /// The error message
+    pub message: String,
+    /// Whether the error aborted the task
+    pub fatal: bool,
+}
+
+/// Record an error encountered by the given task.
+pub fn record(task: &str, message: String, fatal: bool) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let error = RecentError {
+        timestamp,
+        task: task.to_string(),
+        message,
+        fatal,
+    };
+
+    let mut errors = RECENT_ERRORS.write().expect("poisoned lock");
+
+    if errors.len() >= MAX_RECENT_ERRORS {
+        errors.pop_front();
+    }
+
+    errors.push_back(error);
+}
+
+/// The recorded errors, from the most recent to the oldest.
+pub fn recent() -> Vec<RecentError> {
+    RECENT_ERRORS
+        .read()
+        .expect("poisoned lock")
+        .iter()
+        .rev()
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,91 @@
+//! Checksums of the files the relayer persists its state to, so that a file corrupted,
+//! e.g. by a crash or a full disk, is detected when loading it and moved aside, letting
+//! the relayer rebuild the state from the chains instead of failing to start.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+/// Prefix of the first line of a checksummed file, followed by the checksum of the rest.
+const CHECKSUM_PREFIX: &str = "sha256:";
+
+/// Prepend the checksum of the given content to it.
+pub fn seal(content: &[u8]) -> Vec<u8> {
+    let checksum = hex::encode(Sha256::digest(content));
+
+    let mut sealed = format!("{}{}\n", CHECKSUM_PREFIX, checksum).into_bytes();
+    sealed.extend_from_slice(content);
+    sealed
+}
+
+/// Verify the checksum of the given file content, and return the content without it.
+///
+/// The content of files saved before checksums were introduced is returned as is,
+/// leaving it to decoding it to detect any corruption.
+pub fn unseal(file: &[u8]) -> Result<&[u8], String> {
+    let rest = match file.strip_prefix(CHECKSUM_PREFIX.as_bytes()) {
+        Some(rest) => rest,
+        None => return Ok(file),
+    };
+
+    let newline = rest
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| "the checksum is not followed by any content".to_string())?;
+
+    let (expected, content) = (&rest[..newline], &rest[newline + 1..]);
+    let actual = hex::encode(Sha256::digest(content));
+
+    if expected == actual.as_bytes() {
+        Ok(content)
+    } else {
+        Err(format!(
+            "checksum mismatch, expected {} but the content hashes to {}",
+            String::from_utf8_lossy(expected),
+            actual
+        ))
+    }
+}
+
+/// Move the given corrupted file aside, next to it, so that it can be inspected
+/// later without being loaded again. Returns the path it was moved to.
+pub fn move_aside(path: &Path) -> io::Result<PathBuf> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".corrupted-{}", secs));
+
+    let aside = path.with_file_name(file_name);
+    fs::rename(path, &aside)?;
+
+    Ok(aside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_unseal() {
+        let content = br#"{"chains":[]}"#;
+        let sealed = seal(content);
+
+        assert_eq!(unseal(&sealed), Ok(&content[..]));
+
+        // Files without a checksum are loaded as is
+        assert_eq!(unseal(content), Ok(&content[..]));
+
+        // Truncated or otherwise altered content
+        assert!(unseal(&sealed[..sealed.len() - 1]).is_err());
+        assert!(unseal(&sealed[..10]).is_err());
+
+        let mut altered = sealed.clone();
+        *altered.last_mut().unwrap() = b']';
+        assert!(unseal(&altered).is_err());
+    }
+}
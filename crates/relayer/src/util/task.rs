@@ -6,7 +6,9 @@ use std::sync::{Arc, RwLock};
 use std::thread;
 use tracing::{debug, error, warn};
 
+use crate::chain::priority;
 use crate::util::lock::LockExt;
+use crate::util::recent_errors;
 
 /**
    A task handle holds the endpoints for stopping or waiting for a
@@ -22,6 +24,7 @@ use crate::util::lock::LockExt;
 pub struct TaskHandle {
     shutdown_sender: Sender<()>,
     stopped: Arc<RwLock<bool>>,
+    failed: Arc<RwLock<bool>>,
     join_handle: DropJoinHandle,
 }
 
@@ -93,11 +96,22 @@ pub fn spawn_background_task<E: Display>(
     debug!(parent: &span, "spawning task");
 
     let stopped = Arc::new(RwLock::new(false));
-    let write_stopped = stopped.clone();
+    let failed = Arc::new(RwLock::new(false));
+
+    let guard = StopGuard {
+        stopped: stopped.clone(),
+        failed: failed.clone(),
+    };
 
     let (shutdown_sender, receiver) = bounded(1);
 
+    // The requests made by the task to the chain runtimes
+    // have the same priority as the ones of its spawner.
+    let priority = priority::current_shared();
+
     let join_handle = thread::spawn(move || {
+        priority::set_current(priority);
+        let guard = guard;
         let _entered = span.enter();
         loop {
             match receiver.try_recv() {
@@ -112,9 +126,12 @@ pub fn spawn_background_task<E: Display>(
                     }
                     Err(TaskError::Ignore(e)) => {
                         warn!("task encountered ignorable error: {}", e);
+                        recent_errors::record(task_name(&span), e.to_string(), false);
                     }
                     Err(TaskError::Fatal(e)) => {
                         error!("task aborting after encountering fatal error: {}", e);
+                        recent_errors::record(task_name(&span), e.to_string(), true);
+                        *guard.failed.acquire_write() = true;
                         break;
                     }
                 },
@@ -124,18 +141,45 @@ pub fn spawn_background_task<E: Display>(
             }
         }
 
-        *write_stopped.acquire_write() = true;
-
         debug!("task terminated");
     });
 
     TaskHandle {
         shutdown_sender,
         stopped,
+        failed,
         join_handle: DropJoinHandle(Some(join_handle)),
     }
 }
 
+/**
+   Marks a background task as stopped when its thread terminates,
+   and as failed if the thread terminates because of a panic.
+*/
+struct StopGuard {
+    stopped: Arc<RwLock<bool>>,
+    failed: Arc<RwLock<bool>>,
+}
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            // Do not panic again if the lock was poisoned by the panic
+            if let Ok(mut failed) = self.failed.write() {
+                *failed = true;
+            }
+        }
+
+        if let Ok(mut stopped) = self.stopped.write() {
+            *stopped = true;
+        }
+    }
+}
+
+fn task_name(span: &tracing::Span) -> &'static str {
+    span.metadata().map_or("task", |metadata| metadata.name())
+}
+
 impl TaskHandle {
     /**
        Wait for the background task to terminate.
@@ -179,6 +223,14 @@ impl TaskHandle {
     pub fn is_stopped(&self) -> bool {
         *self.stopped.acquire_read()
     }
+
+    /**
+       Check whether a background task has been stopped because
+       it encountered a fatal error or panicked.
+    */
+    pub fn has_failed(&self) -> bool {
+        *self.failed.acquire_read()
+    }
 }
 
 impl Drop for DropJoinHandle {
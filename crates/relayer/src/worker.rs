@@ -1,16 +1,18 @@
 use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
-use ibc::core::ics04_channel::channel::Order;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tracing::error;
 
+use crate::foreign_client::expiry::RefreshPolicy;
 use crate::foreign_client::ForeignClient;
-use crate::link::{Link, LinkParameters, Resubmit};
+use crate::link::{InFlightView, Link, LinkParameters, Resubmit};
 use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
     config::Config,
+    misbehaviour::broadcast,
     object::Object,
+    util::lock::RwArc,
 };
 
 pub mod retry_strategy;
@@ -21,7 +23,7 @@ pub use error::RunError;
 
 mod handle;
 
-pub use handle::{WorkerData, WorkerHandle};
+pub use handle::{InFlightProbe, WorkerData, WorkerHandle};
 
 mod cmd;
 
@@ -29,13 +31,18 @@ pub use cmd::WorkerCmd;
 
 mod map;
 
-pub use map::WorkerMap;
+pub use map::{UnconfirmedTxs, WorkerMap};
+
+mod restart;
+
+pub use restart::RestartDecision;
 
 pub mod channel;
 pub mod client;
 pub mod connection;
 pub mod cross_chain_query_packet;
 pub mod packet;
+pub mod plugin;
 pub mod wallet;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -63,8 +70,12 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
     id: WorkerId,
     object: Object,
     config: &Config,
+    misbehaviours: &broadcast::Sender,
 ) -> WorkerHandle {
     let mut task_handles = Vec::new();
+    let mut in_flight: Option<InFlightProbe> = None;
+    let mut pause_flag: Option<RwArc<bool>> = None;
+    let mut drain_flag: Option<RwArc<bool>> = None;
 
     let (cmd_tx, data) = match &object {
         Object::Client(client) => {
@@ -72,15 +83,25 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
 
             let (mut refresh, mut misbehaviour) = (false, false);
 
-            let refresh_task = client::spawn_refresh_client(client.clone());
-            if let Some(refresh_task) = refresh_task {
+            if config.mode.watch_only {
+                // Report the age of the client instead of refreshing it
+                task_handles.push(client::spawn_watch_client(client.clone()));
+            } else if let Some(refresh_task) = client::spawn_refresh_client(
+                client.clone(),
+                RefreshPolicy::from(&config.mode.clients),
+            ) {
                 task_handles.push(refresh_task);
                 refresh = true;
             }
 
             let cmd_tx = if config.mode.clients.misbehaviour {
                 let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
-                let misbehavior_task = client::detect_misbehavior_task(cmd_rx, client);
+                let misbehavior_task = client::detect_misbehavior_task(
+                    cmd_rx,
+                    client,
+                    !config.mode.watch_only,
+                    misbehaviours.clone(),
+                );
                 if let Some(task) = misbehavior_task {
                     task_handles.push(task);
                     misbehaviour = true;
@@ -108,11 +129,23 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
         Object::Channel(channel) => {
             let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
-            let channel_task = channel::spawn_channel_worker(channel.clone(), chains, cmd_rx);
+            let paused = <RwArc<_>>::new_lock(false);
+            let channel_task =
+                channel::spawn_channel_worker(channel.clone(), chains, cmd_rx, paused.clone());
             task_handles.push(channel_task);
 
+            pause_flag = Some(paused);
+
             (Some(cmd_tx), None)
         }
+        Object::Packet(path) if config.mode.watch_only => {
+            // Report the pending packets instead of relaying them
+            let watch_task =
+                packet::spawn_packet_watch_worker(path.clone(), chains.a.clone(), chains.b.clone());
+            task_handles.push(watch_task);
+
+            (None, None)
+        }
         Object::Packet(path) => {
             let packets_config = config.mode.packets;
             let link_res = Link::new_from_opts(
@@ -128,25 +161,50 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
             match link_res {
                 Ok(link) => {
                     let channel_ordering = link.a_to_b.channel().ordering;
-                    let should_clear_on_start =
-                        packets_config.clear_on_start || channel_ordering == Order::Ordered;
+                    // A channel closed on the source chain only must be flushed
+                    // before the close handshake is completed on the destination.
+                    let close_confirm_pending =
+                        link.a_to_b.close_confirm_pending().unwrap_or(false);
+                    let should_clear_on_start = packets_config.clear_on_start
+                        || channel_ordering.is_ordered()
+                        || close_confirm_pending;
 
                     let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
+                    // Listed without locking the link, which a relaying task may hold for long
+                    let in_flight_views = link.a_to_b.in_flight();
                     let link = Arc::new(Mutex::new(link));
                     let resubmit = Resubmit::from_clear_interval(packets_config.clear_interval);
+                    let paused = <RwArc<_>>::new_lock(false);
+                    let draining = <RwArc<_>>::new_lock(false);
 
                     let packet_task = packet::spawn_packet_cmd_worker(
                         cmd_rx,
                         link.clone(),
+                        paused.clone(),
+                        draining.clone(),
                         should_clear_on_start,
+                        close_confirm_pending,
                         packets_config.clear_interval,
                         path.clone(),
                     );
                     task_handles.push(packet_task);
 
-                    let link_task = packet::spawn_packet_worker(path.clone(), link, resubmit);
+                    let link_task = packet::spawn_packet_worker(
+                        path.clone(),
+                        link,
+                        paused.clone(),
+                        draining.clone(),
+                        resubmit,
+                    );
                     task_handles.push(link_task);
 
+                    pause_flag = Some(paused);
+                    drain_flag = Some(draining);
+
+                    in_flight = Some(Box::new(move || {
+                        in_flight_views.iter().flat_map(InFlightView::txs).collect()
+                    }));
+
                     (Some(cmd_tx), None)
                 }
                 Err(e) => {
@@ -170,6 +228,7 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
             let cross_chain_query_task =
                 cross_chain_query_packet::spawn_cross_chain_query_packet_worker(
                     chains.a.clone(),
+                    chains.b.clone(),
                     cmd_rx,
                     cross_chain_query_packet.clone(),
                 );
@@ -179,5 +238,22 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
     };
 
-    WorkerHandle::new(id, object, data, cmd_tx, task_handles)
+    let (plugin_txs, plugin_tasks) = plugin::spawn_plugin_tasks(&plugin::registered(), &object);
+
+    let mut handle = WorkerHandle::new(id, object, data, cmd_tx, task_handles)
+        .with_plugins(plugin_txs, plugin_tasks);
+
+    if let Some(probe) = in_flight {
+        handle = handle.with_in_flight_probe(probe);
+    }
+
+    if let Some(paused) = pause_flag {
+        handle = handle.with_pause_flag(paused);
+    }
+
+    if let Some(draining) = drain_flag {
+        handle = handle.with_drain_flag(draining);
+    }
+
+    handle
 }
@@ -1,8 +1,11 @@
 use core::time::Duration;
 use crossbeam_channel::Receiver;
-use tracing::{debug, error_span};
+use tracing::{debug, error_span, warn};
+
+use ibc::events::IbcEvent;
 
 use crate::channel::Channel as RelayChannel;
+use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
@@ -14,15 +17,30 @@ use crate::{
 use super::error::RunError;
 use super::WorkerCmd;
 
+/// While the worker is paused, the commands are left in the channel,
+/// to be handled in order once the worker is resumed.
 pub fn spawn_channel_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     channel: Channel,
     chains: ChainHandlePair<ChainA, ChainB>,
     cmd_rx: Receiver<WorkerCmd>,
+    paused: RwArc<bool>,
 ) -> TaskHandle {
+    // The channel whose upgrade is in progress, if any, whose steps
+    // which follow no event of the counterparty are relayed at each new block
+    let mut upgrading_channel = None;
+
+    // Whether the worker checked for an upgrade already in progress when it started,
+    // e.g. when the supervisor starts, which it drives to completion as well
+    let mut checked_upgrade = false;
+
     spawn_background_task(
         error_span!("worker.channel", channel = %channel.short_name()),
         Some(Duration::from_millis(200)),
         move || {
+            if *paused.acquire_read() {
+                return Ok(Next::Continue);
+            }
+
             if let Ok(cmd) = cmd_rx.try_recv() {
                 match cmd {
                     WorkerCmd::IbcEvents { batch } => {
@@ -32,6 +50,33 @@ pub fn spawn_channel_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
                         debug!("starts processing {:?}", last_event);
 
                         if let Some(event_with_height) = last_event {
+                            let _span = error_span!(
+                                "relay.channel",
+                                channel = %channel.short_name(),
+                                event = event_with_height.event.event_type().as_str(),
+                                height = %event_with_height.height,
+                            )
+                            .entered();
+
+                            if let IbcEvent::UpgradeChannel(upgrade) = &event_with_height.event {
+                                let (upgrade_channel, _) = RelayChannel::restore_from_state(
+                                    chains.a.clone(),
+                                    chains.b.clone(),
+                                    channel.clone(),
+                                    event_with_height.height,
+                                )
+                                .map_err(|e| TaskError::Fatal(RunError::channel(e)))?;
+
+                                let next = retry_with_index(
+                                    retry_strategy::worker_default_strategy(),
+                                    |index| upgrade_channel.step_upgrade_event(upgrade, index),
+                                )
+                                .map_err(|e| TaskError::Fatal(RunError::retry(e)))?;
+
+                                upgrading_channel = Some(upgrade_channel);
+                                return Ok(next);
+                            }
+
                             let mut handshake_channel = RelayChannel::restore_from_event(
                                 chains.a.clone(),
                                 chains.b.clone(),
@@ -48,8 +93,32 @@ pub fn spawn_channel_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
                         }
                     }
 
-                    // nothing to do
-                    WorkerCmd::NewBlock { .. } => Ok(Next::Continue),
+                    WorkerCmd::NewBlock { height, .. } => {
+                        if !checked_upgrade && upgrading_channel.is_none() {
+                            match RelayChannel::restore_from_state(
+                                chains.a.clone(),
+                                chains.b.clone(),
+                                channel.clone(),
+                                height,
+                            ) {
+                                Ok((upgrade_channel, _)) => {
+                                    checked_upgrade = true;
+                                    upgrading_channel = Some(upgrade_channel);
+                                }
+                                Err(e) => warn!("failed to check for a channel upgrade: {}", e),
+                            }
+                        }
+
+                        if let Some(upgrade_channel) = &upgrading_channel {
+                            match upgrade_channel.step_upgrade_state() {
+                                Ok(true) => {}
+                                Ok(false) => upgrading_channel = None,
+                                Err(e) => warn!("failed to relay the channel upgrade: {}", e),
+                            }
+                        }
+
+                        Ok(Next::Continue)
+                    }
 
                     // nothing to do
                     WorkerCmd::ClearPendingPackets => Ok(Next::Continue),
@@ -2,12 +2,17 @@ use core::convert::Infallible;
 use core::time::Duration;
 use crossbeam_channel::Receiver;
 use std::time::Instant;
-use tracing::{debug, span, trace, warn};
+use tracing::{debug, error, info, span, trace, warn};
 
+use ibc::core::ics02_client::events::UpdateClient;
 use ibc::events::IbcEvent;
 use retry::delay::Fibonacci;
 use retry::retry_with_index;
 
+use crate::foreign_client::expiry::RefreshPolicy;
+use crate::foreign_client::{ForeignClientError, HasExpiredOrFrozenError};
+use crate::misbehaviour::broadcast;
+use crate::telemetry;
 use crate::util::retry::clamp_total;
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 use crate::{
@@ -21,9 +26,18 @@ const REFRESH_INTERVAL_SECONDS: u64 = 2;
 const INITIAL_FIBONACCI_VALUE: u64 = 1;
 const MAX_REFRESH_DELAY_SECONDS: u64 = 60 * 60; // 1 hour
 const MAX_REFRESH_TOTAL_DELAY_SECONDS: u64 = 60 * 60 * 24; // 1 day
+const WATCH_INTERVAL_SECONDS: u64 = 60;
+const MAX_FORECAST_INTERVAL_SECONDS: u64 = 10 * 60; // 10 minutes
 
+/// Spawns a task which refreshes the client once the refresh rate of its trusting
+/// period has elapsed since its latest update, and warns when it is about to expire.
+///
+/// The task sleeps until the client is due for a refresh, as forecast from its latest
+/// update, checking it again at least every [`MAX_FORECAST_INTERVAL_SECONDS`] in case
+/// it was updated by someone else in the meantime.
 pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
     mut client: ForeignClient<ChainA, ChainB>,
+    policy: RefreshPolicy,
 ) -> Option<TaskHandle> {
     if client.is_expired_or_frozen() {
         warn!(
@@ -32,8 +46,6 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
         );
         None
     } else {
-        // Compute the refresh interval as a fraction of the client's trusting period
-        // If the trusting period or the client state is not retrieved, fallback to a default value.
         let mut next_refresh = Instant::now() + Duration::from_secs(REFRESH_INTERVAL_SECONDS);
         Some(spawn_background_task(
             span!(
@@ -51,24 +63,23 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
                     return Ok(Next::Continue);
                 }
 
-                // Use retry mechanism only if `client.refresh()` fails.
+                // Use retry mechanism only if the refresh fails.
                 let res = retry_with_index(
                     clamp_total(
                         Fibonacci::from(Duration::from_secs(INITIAL_FIBONACCI_VALUE)),
                         Duration::from_secs(MAX_REFRESH_DELAY_SECONDS),
                         Duration::from_secs(MAX_REFRESH_TOTAL_DELAY_SECONDS),
                     ),
-                    |_| client.refresh(),
+                    |_| refresh_when_due(&mut client, &policy),
                 );
 
                 match res {
-                    // If `client.refresh()` was successful, update the `next_refresh` call.
-                    Ok(_) => {
-                        next_refresh =
-                            Instant::now() + Duration::from_secs(REFRESH_INTERVAL_SECONDS);
+                    // If the refresh was successful or not due yet, schedule the next check.
+                    Ok(check_in) => {
+                        next_refresh = Instant::now() + check_in;
                         Ok(Next::Continue)
                     }
-                    // If `client.refresh()` failed and the retry mechanism
+                    // If the refresh failed and the retry mechanism
                     // exceeded the maximum delay, return a fatal error.
                     Err(e) => Err(TaskError::Fatal(e)),
                 }
@@ -77,9 +88,109 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
     }
 }
 
+/// Refresh the client if it is due for a refresh according to the given policy,
+/// and return how long to wait before checking it again.
+fn refresh_when_due<ChainA: ChainHandle, ChainB: ChainHandle>(
+    client: &mut ForeignClient<ChainA, ChainB>,
+    policy: &RefreshPolicy,
+) -> Result<Duration, ForeignClientError> {
+    let max_interval = Duration::from_secs(MAX_FORECAST_INTERVAL_SECONDS);
+
+    let forecast = match client.expiry_forecast()? {
+        Some(forecast) => forecast,
+        // The clients without a trusting period never expire
+        None => return Ok(max_interval),
+    };
+
+    let time_to_expiry = forecast.time_to_expiry();
+
+    telemetry!(
+        client_time_to_expiry,
+        &client.src_chain.id(),
+        &client.dst_chain.id(),
+        &client.id,
+        time_to_expiry.as_secs()
+    );
+
+    if forecast.is_expiring(policy) {
+        warn!(
+            ?time_to_expiry,
+            trusting_period = ?forecast.trusting_period,
+            "client is about to expire"
+        );
+    }
+
+    let refresh_in = forecast.refresh_in(policy);
+
+    if !refresh_in.is_zero() {
+        trace!(?refresh_in, "client was updated recently enough");
+        return Ok(refresh_in.min(max_interval));
+    }
+
+    info!(elapsed = ?forecast.elapsed, ?time_to_expiry, "client needs to be refreshed");
+
+    client.refresh_now()?;
+
+    Ok(Duration::from_secs(REFRESH_INTERVAL_SECONDS))
+}
+
+/// Spawns a task which periodically reports the time elapsed since the latest update of
+/// the client, and warns once the client would have been refreshed, in watch-only mode.
+pub fn spawn_watch_client<ChainA: ChainHandle, ChainB: ChainHandle>(
+    client: ForeignClient<ChainA, ChainB>,
+) -> TaskHandle {
+    spawn_background_task(
+        span!(
+            tracing::Level::ERROR,
+            "worker.client.watch",
+            client = %client.id,
+            src_chain = %client.src_chain.id(),
+            dst_chain = %client.dst_chain.id(),
+        ),
+        Some(Duration::from_secs(WATCH_INTERVAL_SECONDS)),
+        move || {
+            let (client_state, elapsed) = match client.validated_client_state() {
+                Ok(validated) => validated,
+                Err(e) if e.is_expired_or_frozen_error() => {
+                    error!("client is expired or frozen, stopping to watch it: {}", e);
+                    return Ok(Next::Abort);
+                }
+                Err(e) => return Err(TaskError::Ignore(e)),
+            };
+
+            if let Some(elapsed) = elapsed {
+                telemetry!(
+                    client_update_age,
+                    &client.src_chain.id(),
+                    &client.dst_chain.id(),
+                    &client.id,
+                    elapsed.as_secs()
+                );
+
+                match client_state.refresh_period() {
+                    Some(refresh_window) if elapsed > refresh_window => warn!(
+                        ?elapsed,
+                        ?refresh_window,
+                        "client needs to be refreshed, but the relayer is watch-only"
+                    ),
+                    _ => trace!(?elapsed, "client was updated recently enough"),
+                }
+            }
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Spawns a task which detects the misbehaviour of the client, and submits its
+/// evidence unless `submit` is false, in which case the misbehaviour is only reported.
+/// The misbehaviours whose evidence is submitted are sent with the given sender, for
+/// their evidence to be broadcast to the faulty chain and to its other clients.
 pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
     receiver: Receiver<WorkerCmd>,
     client: ForeignClient<ChainB, ChainA>,
+    submit: bool,
+    broadcast: broadcast::Sender,
 ) -> Option<TaskHandle> {
     if client.is_expired_or_frozen() {
         warn!(
@@ -104,7 +215,7 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
             if !first_check_done {
                 first_check_done = true;
                 debug!("doing first check");
-                let misbehavior_result = client.detect_misbehaviour_and_submit_evidence(None);
+                let misbehavior_result = detect_misbehaviour(&client, None, submit, &broadcast);
                 trace!("detect misbehavior result: {:?}", misbehavior_result);
             }
 
@@ -117,7 +228,7 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
                             if let IbcEvent::UpdateClient(ref update) = event_with_height.event {
                                 debug!("checking misbehavior for updated client");
                                 let misbehavior_result =
-                                    client.detect_misbehaviour_and_submit_evidence(Some(update));
+                                    detect_misbehaviour(&client, Some(update), submit, &broadcast);
                                 trace!("detect misbehavior result: {:?}", misbehavior_result);
 
                                 match misbehavior_result {
@@ -129,6 +240,10 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
                                         // if evidence was submitted successfully then exit
                                         return Ok(Next::Abort);
                                     }
+                                    MisbehaviourResults::EvidenceDetected => {
+                                        // the misbehaviour was reported, no need to report it again
+                                        return Ok(Next::Abort);
+                                    }
                                     MisbehaviourResults::CannotExecute => {
                                         // skip misbehaviour checking if chain does not have support for it (i.e. client
                                         // update event does not include the header)
@@ -150,3 +265,16 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
 
     Some(handle)
 }
+
+fn detect_misbehaviour<ChainA: ChainHandle, ChainB: ChainHandle>(
+    client: &ForeignClient<ChainB, ChainA>,
+    update: Option<&UpdateClient>,
+    submit: bool,
+    broadcast: &broadcast::Sender,
+) -> MisbehaviourResults {
+    if submit {
+        client.detect_misbehaviour_and_broadcast_evidence(update, broadcast)
+    } else {
+        client.detect_misbehaviour_and_report(update)
+    }
+}
@@ -33,6 +33,14 @@ pub fn spawn_connection_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
                         debug!("starts processing {:?}", last_event_with_height);
 
                         if let Some(event_with_height) = last_event_with_height {
+                            let _span = error_span!(
+                                "relay.connection",
+                                connection = %connection.short_name(),
+                                event = event_with_height.event.event_type().as_str(),
+                                height = %event_with_height.height,
+                            )
+                            .entered();
+
                             let mut handshake_connection = RelayConnection::restore_from_event(
                                 chains.a.clone(),
                                 chains.b.clone(),
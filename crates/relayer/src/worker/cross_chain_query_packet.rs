@@ -1,61 +1,192 @@
+//! Relaying of the cross-chain queries of ICS-31: the queries requested on the querying
+//! chain are executed on the queried chain, and their results are submitted back to the
+//! querying chain, with a `MsgSubmitCrossChainQueryResult`.
+//!
+//! A query which cannot be executed, e.g. as the queried chain is unreachable, or whose
+//! result cannot be submitted, is retried, up to [`MAX_QUERY_ATTEMPTS`] times and for at
+//! most [`QUERY_TIMEOUT`], after which it is answered with a timeout result, so that the
+//! querying chain does not wait on it forever.
+
 use super::error::RunError;
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::CrossChainQueryRequest;
+use crate::chain::responses::CrossChainQueryResponse;
 use crate::chain::tracking::TrackedMsgs;
 use crate::object::CrossChainQueryPacket;
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 use crate::worker::WorkerCmd;
 use crossbeam_channel::Receiver;
-use std::time::Duration;
-use tracing::{info, info_span};
+use std::time::{Duration, Instant};
+use tracing::{error, info, info_span, warn};
 use uuid::Uuid;
 
-pub fn spawn_cross_chain_query_packet_worker<ChainA: ChainHandle>(
-    handle: ChainA,
+/// How many times a query is executed and its result submitted before it is answered
+/// with a timeout result.
+pub const MAX_QUERY_ATTEMPTS: u32 = 5;
+
+/// How long after it is requested a query is answered with a timeout result, if its
+/// result was not submitted yet.
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The delay before the first retry of a query, which grows by as much at each retry.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// A query requested on the querying chain, whose result is yet to be submitted
+struct PendingQuery {
+    request: CrossChainQueryRequest,
+    attempts: u32,
+    requested_at: Instant,
+    next_attempt_at: Instant,
+}
+
+impl PendingQuery {
+    fn new(request: CrossChainQueryRequest) -> Self {
+        let now = Instant::now();
+
+        Self {
+            request,
+            attempts: 0,
+            requested_at: now,
+            next_attempt_at: now,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.attempts >= MAX_QUERY_ATTEMPTS || self.requested_at.elapsed() >= QUERY_TIMEOUT
+    }
+
+    fn retry_later(&mut self) {
+        self.attempts += 1;
+        self.next_attempt_at = Instant::now() + RETRY_DELAY * self.attempts;
+    }
+}
+
+pub fn spawn_cross_chain_query_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
+    querying_chain: ChainA,
+    queried_chain: ChainB,
     cmd_rx: Receiver<WorkerCmd>,
     cross_chain_query_packet: CrossChainQueryPacket,
 ) -> TaskHandle {
-    spawn_background_task(
-        info_span!("cross_chain_query"),
-        Some(Duration::from_millis(1000)),
-        move || {
-            if let Ok(cmd) = cmd_rx.try_recv() {
-                handle_cross_chain_query_packet(handle.clone(), cmd, &cross_chain_query_packet)?;
+    let span = info_span!(
+        "cross_chain_query",
+        query = %cross_chain_query_packet.id,
+        src_chain = %cross_chain_query_packet.src_chain_id,
+        dst_chain = %cross_chain_query_packet.dst_chain_id,
+    );
+
+    let mut pending: Option<PendingQuery> = None;
+
+    spawn_background_task(span, Some(Duration::from_millis(1000)), move || {
+        while let Ok(cmd) = cmd_rx.try_recv() {
+            if pending.is_none() {
+                pending = requested_query(&cmd, &cross_chain_query_packet).map(PendingQuery::new);
             }
-            Ok(Next::Continue)
-        },
-    )
+        }
+
+        match pending.as_mut() {
+            Some(query) if query.next_attempt_at <= Instant::now() => {
+                handle_cross_chain_query(&querying_chain, &queried_chain, query)
+            }
+            _ => Ok(Next::Continue),
+        }
+    })
 }
 
-fn handle_cross_chain_query_packet<ChainA: ChainHandle>(
-    handle: ChainA,
-    cmd: WorkerCmd,
-    _cross_chain_query_packet: &CrossChainQueryPacket,
-) -> Result<(), TaskError<RunError>> {
-    if let WorkerCmd::IbcEvents { batch } = &cmd {
-        let queries: Vec<CrossChainQueryRequest> = batch
+/// The query of the given object requested by the events of the given command, if any.
+fn requested_query(
+    cmd: &WorkerCmd,
+    cross_chain_query_packet: &CrossChainQueryPacket,
+) -> Option<CrossChainQueryRequest> {
+    match cmd {
+        WorkerCmd::IbcEvents { batch } => batch
             .events
             .iter()
-            .filter_map(|ev| ev.try_into().ok())
-            .collect();
-
-        let response = handle.cross_chain_query(queries);
-        if let Ok(res) = response {
-            res.iter()
-                .for_each(|r| info!("response arrived: query_id: {}", r.id));
-            let any_msgs = res
-                .clone()
-                .into_iter()
-                .map(|r| r.to_any(&handle))
-                .collect::<Vec<_>>();
-
-            handle
-                .send_messages_and_wait_check_tx(TrackedMsgs::new_uuid(any_msgs, Uuid::new_v4()))
-                .map_err(|_| TaskError::Ignore(RunError::query()))?;
-        }
-        Ok(())
+            .filter_map(|ev| CrossChainQueryRequest::try_from(ev).ok())
+            .find(|request| request.id == cross_chain_query_packet.id),
+        _ => None,
+    }
+}
+
+/// Execute the given query and submit its result, or its timeout if it expired,
+/// stopping the worker once the result is submitted.
+fn handle_cross_chain_query<ChainA: ChainHandle, ChainB: ChainHandle>(
+    querying_chain: &ChainA,
+    queried_chain: &ChainB,
+    query: &mut PendingQuery,
+) -> Result<Next, TaskError<RunError>> {
+    let expired = query.is_expired();
+
+    let response = if expired {
+        warn!(
+            attempts = query.attempts,
+            "the query could not be answered in time, submitting a timeout result"
+        );
+
+        CrossChainQueryResponse::timeout(query.request.id.clone(), query.request.height.clone())
     } else {
-        Ok(())
+        let response = queried_chain
+            .cross_chain_query(vec![query.request.clone()])
+            .map(|responses| responses.into_iter().find(|r| r.id == query.request.id));
+
+        match response {
+            Ok(Some(response)) => response,
+            Ok(None) => {
+                query.retry_later();
+                warn!(
+                    attempts = query.attempts,
+                    "the queried chain returned no response to the query, retrying later"
+                );
+                return Ok(Next::Continue);
+            }
+            Err(e) => {
+                query.retry_later();
+                warn!(
+                    attempts = query.attempts,
+                    "failed to execute the query, retrying later: {}", e
+                );
+                return Ok(Next::Continue);
+            }
+        }
+    };
+
+    info!(result = response.result, "response arrived: {}", response);
+
+    match submit_response(querying_chain, &response) {
+        Ok(()) => {
+            info!("submitted the result of the query");
+            Ok(Next::Abort)
+        }
+        Err(e) if expired => {
+            error!(
+                "failed to submit the timeout result of the query, giving up: {}",
+                e
+            );
+            Ok(Next::Abort)
+        }
+        Err(e) => {
+            query.retry_later();
+            warn!(
+                attempts = query.attempts,
+                "failed to submit the result of the query, retrying later: {}", e
+            );
+            Ok(Next::Continue)
+        }
+    }
+}
+
+fn submit_response<ChainA: ChainHandle>(
+    querying_chain: &ChainA,
+    response: &CrossChainQueryResponse,
+) -> Result<(), String> {
+    let msg = response.to_any(querying_chain).map_err(|e| e.to_string())?;
+
+    let tx_responses = querying_chain
+        .send_messages_and_wait_check_tx(TrackedMsgs::new_uuid(vec![msg], Uuid::new_v4()))
+        .map_err(|e| e.to_string())?;
+
+    match tx_responses.iter().find(|r| r.code.is_err()) {
+        Some(failed) => Err(format!("the transaction failed its check: {}", failed.log)),
+        None => Ok(()),
     }
 }
 
@@ -100,9 +231,9 @@ mod test_cross_chain_query_packet {
                 height: Height::new(1, 1).unwrap(),
                 events: vec![IbcEventWithHeight {
                     event: IbcEvent::CrossChainQuery(SendPacket::new(
-                        "ibc-0".to_string(),
+                        "chain_a-1".to_string(),
                         "1".into(),
-                        "https://www.google.com/".into(),
+                        hex::encode("store/bank/key?0201"),
                         "1".into(),
                     )),
                     height: Height::new(1, 1).unwrap(),
@@ -112,11 +243,14 @@ mod test_cross_chain_query_packet {
 
         tx.send(worker_cmd).unwrap();
 
+        // The worker stops once the result of the query is submitted
         spawn_cross_chain_query_packet_worker(
+            a_chain.clone(),
             a_chain,
             rx,
             CrossChainQueryPacket {
                 src_chain_id: ChainId::new("chain_a".into(), 1),
+                dst_chain_id: ChainId::new("chain_a".into(), 1),
                 id: "1".to_string(),
             },
         )
@@ -13,6 +13,7 @@ use ibc::{
 
 use crate::chain::tracking::TrackingId;
 use crate::event::IbcEventWithHeight;
+use crate::link::InFlightTxs;
 use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::TaskHandle;
 use crate::{event::monitor::EventBatch, object::Object};
@@ -25,12 +26,20 @@ pub enum WorkerData {
     Client { misbehaviour: bool, refresh: bool },
 }
 
+/// Reports the transactions submitted by a worker which are not confirmed yet.
+pub type InFlightProbe = Box<dyn Fn() -> Vec<InFlightTxs> + Send + Sync>;
+
 pub struct WorkerHandle {
     id: WorkerId,
     object: Object,
     data: Option<WorkerData>,
     tx: RwArc<Option<Sender<WorkerCmd>>>,
     task_handles: Vec<TaskHandle>,
+    in_flight: Option<InFlightProbe>,
+    paused: Option<RwArc<bool>>,
+    draining: Option<RwArc<bool>>,
+    /// Senders of the commands to the tasks of the plugins running for the object
+    plugins: RwArc<Vec<Sender<WorkerCmd>>>,
 }
 
 impl WorkerHandle {
@@ -47,10 +56,96 @@ impl WorkerHandle {
             data,
             tx: <RwArc<_>>::new_lock(tx),
             task_handles,
+            in_flight: None,
+            paused: None,
+            draining: None,
+            plugins: <RwArc<_>>::new_lock(Vec::new()),
+        }
+    }
+
+    /// Set the senders of the commands to the tasks of the plugins
+    /// running for the object, which get a copy of each command.
+    pub fn with_plugins(
+        mut self,
+        senders: Vec<Sender<WorkerCmd>>,
+        task_handles: Vec<TaskHandle>,
+    ) -> Self {
+        self.plugins = <RwArc<_>>::new_lock(senders);
+        self.task_handles.extend(task_handles);
+        self
+    }
+
+    /// Set the probe reporting the transactions submitted by the
+    /// worker which are not confirmed yet.
+    pub fn with_in_flight_probe(mut self, probe: InFlightProbe) -> Self {
+        self.in_flight = Some(probe);
+        self
+    }
+
+    /// The transactions submitted by the worker which are not confirmed yet.
+    pub fn in_flight_txs(&self) -> Vec<InFlightTxs> {
+        self.in_flight
+            .as_ref()
+            .map(|probe| probe())
+            .unwrap_or_default()
+    }
+
+    /// Set the flag checked by the tasks of the worker before building
+    /// and submitting messages, for the worker to be paused and resumed.
+    pub fn with_pause_flag(mut self, paused: RwArc<bool>) -> Self {
+        self.paused = Some(paused);
+        self
+    }
+
+    /// Pause the worker, which keeps its state but stops building and submitting
+    /// messages until it is resumed. Returns whether or not the worker can be paused.
+    pub fn pause(&self) -> bool {
+        self.set_paused(true)
+    }
+
+    /// Resume the worker, if it was paused.
+    pub fn resume(&self) -> bool {
+        self.set_paused(false)
+    }
+
+    fn set_paused(&self, paused: bool) -> bool {
+        match &self.paused {
+            Some(flag) => {
+                *flag.acquire_write() = paused;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the worker is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+            .as_ref()
+            .map_or(false, |flag| *flag.acquire_read())
+    }
+
+    /// Set the flag checked by the tasks of the worker before building
+    /// and submitting messages, for the worker to be drained on shutdown.
+    pub fn with_drain_flag(mut self, draining: RwArc<bool>) -> Self {
+        self.draining = Some(draining);
+        self
+    }
+
+    /// Drain the worker, which stops building and submitting messages
+    /// for good, but keeps confirming the transactions already submitted.
+    pub fn drain(&self) {
+        if let Some(flag) = &self.draining {
+            *flag.acquire_write() = true;
         }
     }
 
     pub fn try_send_command(&self, cmd: WorkerCmd) {
+        // Drop the senders of the plugin tasks which terminated
+        self.plugins
+            .acquire_write()
+            .retain(|tx| tx.send(cmd.clone()).is_ok());
+
         let res = if let Some(tx) = self.tx.acquire_read().as_ref() {
             tx.send(cmd)
         } else {
@@ -116,6 +211,12 @@ impl WorkerHandle {
         true
     }
 
+    /// Whether one of the tasks of the worker was stopped
+    /// because it encountered a fatal error or panicked.
+    pub fn has_crashed(&self) -> bool {
+        self.task_handles.iter().any(|task| task.has_failed())
+    }
+
     /// Wait for the worker thread to finish.
     pub fn join(mut self) {
         let task_handles = mem::take(&mut self.task_handles);
@@ -1,43 +1,86 @@
 use alloc::collections::btree_map::BTreeMap as HashMap;
+use alloc::collections::btree_set::BTreeSet as HashSet;
 use core::mem;
+use std::thread;
+use std::time::Instant;
 
 use ibc::core::ics02_client::events::NewBlock;
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc::Height;
-use tracing::{debug, trace};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, trace};
 
 use crate::{
-    chain::handle::{ChainHandle, ChainHandlePair},
-    config::Config,
+    chain::{
+        handle::{ChainHandle, ChainHandlePair},
+        priority::{self, SharedPriority},
+        requests::{IncludeProof, QueryChannelRequest, QueryHeight},
+    },
+    config::{priority::Priority, restart::RestartConfig, Config},
+    link::InFlightTxs,
+    misbehaviour::broadcast,
     object::Object,
     telemetry,
 };
 
-use super::{spawn_worker_tasks, WorkerHandle, WorkerId};
+use super::restart::RestartTracker;
+use super::{spawn_worker_tasks, RestartDecision, WorkerHandle, WorkerId};
+
+/// The transactions submitted by the worker for an object which are not confirmed yet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnconfirmedTxs {
+    pub object: Object,
+    pub txs: Vec<InFlightTxs>,
+}
 
 /// Manage the lifecycle of [`WorkerHandle`]s associated with [`Object`]s.
 #[derive(Debug)]
 pub struct WorkerMap {
     workers: HashMap<Object, WorkerHandle>,
     latest_worker_id: WorkerId,
+    /// Objects which relaying on was explicitly started at runtime,
+    /// regardless of the packet filter configured for their chain.
+    started: HashSet<Object>,
+    /// Objects which relaying on was explicitly stopped at runtime,
+    /// and for which no worker should be spawned until they are started again.
+    stopped: HashSet<Object>,
+    /// Objects which relaying on was paused at runtime, whose workers
+    /// stop building and submitting messages until they are resumed.
+    paused: HashSet<Object>,
+    /// Chains which are quarantined, for which no worker should be
+    /// spawned until they are released
+    quarantined: HashSet<ChainId>,
+    /// Chains which are halted, for which the workers are paused until they
+    /// produce blocks again
+    halted: HashSet<ChainId>,
+    /// Objects whose workers were shut down when one of their chains was
+    /// quarantined or halted, for which workers are spawned again on release
+    held: HashSet<Object>,
+    /// Restarts of the workers which crashed
+    restarts: RestartTracker,
+    /// Where the misbehaviour tasks of the client workers send the misbehaviours
+    /// they detect, for their evidence to be broadcast by the supervisor
+    misbehaviours: broadcast::Sender,
 }
 
-impl Default for WorkerMap {
-    fn default() -> Self {
+impl WorkerMap {
+    /// Create a new worker map, which will spawn workers with
+    /// the given channel for sending the misbehaviours they detect back to the
+    /// [supervisor](crate::supervisor::SupervisorHandle).
+    pub fn new(misbehaviours: broadcast::Sender) -> Self {
         Self {
             workers: HashMap::new(),
             latest_worker_id: WorkerId::new(0),
+            started: HashSet::new(),
+            stopped: HashSet::new(),
+            paused: HashSet::new(),
+            quarantined: HashSet::new(),
+            halted: HashSet::new(),
+            held: HashSet::new(),
+            restarts: RestartTracker::default(),
+            misbehaviours,
         }
     }
-}
-
-impl WorkerMap {
-    /// Create a new worker map, which will spawn workers with
-    /// the given channel for sending messages back to the
-    /// [supervisor](crate::supervisor::SupervisorHandle).
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     /// Returns `true` if there is a spawned [`WorkerHandle`] associated with the given [`Object`].
     pub fn contains(&self, object: &Object) -> bool {
@@ -142,7 +185,21 @@ impl WorkerMap {
         object: &Object,
         config: &Config,
     ) -> bool {
-        if !self.workers.contains_key(object) {
+        if self.is_stopped(object) {
+            debug!(
+                "not spawning worker for {}, reason: relaying on it was stopped",
+                object.short_name()
+            );
+
+            false
+        } else if self.hold_if_suspended(object) {
+            debug!(
+                "not spawning worker for {}, reason: one of its chains is quarantined or halted",
+                object.short_name()
+            );
+
+            false
+        } else if !self.workers.contains_key(object) {
             let worker = self.spawn_worker(src, dst, object, config);
             self.workers.entry(object.clone()).or_insert(worker);
             true
@@ -161,12 +218,23 @@ impl WorkerMap {
     ) -> WorkerHandle {
         telemetry!(worker, metric_type(object), 1);
 
-        spawn_worker_tasks(
+        // The threads of the worker inherit the priority of the object
+        // for the requests they make to the chain runtimes.
+        let _priority = priority::scoped(object_priority(object, &src, config));
+
+        let handle = spawn_worker_tasks(
             ChainHandlePair { a: src, b: dst },
             self.next_worker_id(),
             object.clone(),
             config,
-        )
+            &self.misbehaviours,
+        );
+
+        if self.is_paused(object) {
+            handle.pause();
+        }
+
+        handle
     }
 
     /// Compute the next worker id
@@ -186,6 +254,40 @@ impl WorkerMap {
             .collect()
     }
 
+    /// List all the [`Object`]s for which there is an associated worker.
+    pub fn objects(&self) -> Vec<Object> {
+        self.workers.keys().cloned().collect()
+    }
+
+    /// List the transactions submitted by the workers which are
+    /// not confirmed yet, grouped by the object of the worker.
+    pub fn in_flight_txs(&self) -> Vec<UnconfirmedTxs> {
+        self.workers
+            .iter()
+            .filter_map(|(object, handle)| {
+                let txs = handle.in_flight_txs();
+
+                (!txs.is_empty()).then(|| UnconfirmedTxs {
+                    object: object.clone(),
+                    txs,
+                })
+            })
+            .collect()
+    }
+
+    /// Drain all the workers, which stop submitting new transactions
+    /// but keep confirming the ones already submitted.
+    pub fn drain(&self) {
+        for handle in self.workers.values() {
+            handle.drain();
+        }
+    }
+
+    /// Find the worker associated with the given object, if any.
+    pub fn get(&self, object: &Object) -> Option<&WorkerHandle> {
+        self.workers.get(object)
+    }
+
     /// List the [`WorkerHandle`]s associated with the given chain.
     pub fn workers_for_chain(&self, chain_id: &ChainId) -> Vec<&WorkerHandle> {
         self.workers
@@ -209,6 +311,219 @@ impl WorkerMap {
         // Drop handle automatically handles the waiting for tasks to terminate.
     }
 
+    /// Whether relaying on the given [`Object`] was explicitly started at runtime.
+    pub fn is_started(&self, object: &Object) -> bool {
+        self.started.contains(object)
+    }
+
+    /// Whether relaying on the given [`Object`] was explicitly stopped at runtime.
+    pub fn is_stopped(&self, object: &Object) -> bool {
+        self.stopped.contains(object)
+    }
+
+    /// Allow workers to be spawned for the given [`Object`] again,
+    /// even if the packet filter of its chain does not allow it.
+    pub fn start_object(&mut self, object: &Object) {
+        self.stopped.remove(object);
+        self.started.insert(object.clone());
+        self.restarts.forget(object);
+    }
+
+    /// Shutdown the worker associated with the given [`Object`], if any,
+    /// and prevent a new one from being spawned until the object is started again.
+    pub fn stop_object(&mut self, object: &Object) {
+        self.started.remove(object);
+        self.stopped.insert(object.clone());
+        self.restarts.forget(object);
+        self.shutdown_worker(object);
+    }
+
+    /// Whether relaying on the given [`Object`] was paused at runtime.
+    pub fn is_paused(&self, object: &Object) -> bool {
+        self.paused.contains(object)
+    }
+
+    /// Pause the worker associated with the given [`Object`], if any, keeping
+    /// its state, as well as the workers spawned for it until it is resumed.
+    pub fn pause_object(&mut self, object: &Object) {
+        self.paused.insert(object.clone());
+
+        if let Some(handle) = self.workers.get(object) {
+            handle.pause();
+        }
+    }
+
+    /// Resume the worker associated with the given [`Object`], if it was paused.
+    pub fn resume_object(&mut self, object: &Object) {
+        self.paused.remove(object);
+
+        if self.is_halted(object) {
+            return;
+        }
+
+        if let Some(handle) = self.workers.get(object) {
+            handle.resume();
+        }
+    }
+
+    /// Whether one of the chains of the given [`Object`] is quarantined.
+    pub fn is_quarantined(&self, object: &Object) -> bool {
+        self.quarantined
+            .iter()
+            .any(|chain_id| relays_on(object, chain_id))
+    }
+
+    /// Whether one of the chains of the given [`Object`] is halted.
+    pub fn is_halted(&self, object: &Object) -> bool {
+        self.halted
+            .iter()
+            .any(|chain_id| relays_on(object, chain_id))
+    }
+
+    /// Whether one of the chains of the given [`Object`] is quarantined or halted, in which
+    /// case it is recorded for its worker to be spawned once the chain is released.
+    pub fn hold_if_suspended(&mut self, object: &Object) -> bool {
+        let suspended = self.is_quarantined(object) || self.is_halted(object);
+
+        if suspended {
+            self.held.insert(object.clone());
+        }
+
+        suspended
+    }
+
+    /// Quarantine the given chain: shut down the workers relaying to or from it,
+    /// and prevent new ones from being spawned until it is released.
+    /// Returns the objects whose workers were shut down.
+    pub fn quarantine_chain(&mut self, chain_id: &ChainId) -> Vec<Object> {
+        self.quarantined.insert(chain_id.clone());
+
+        let objects: Vec<Object> = self
+            .workers
+            .keys()
+            .filter(|object| relays_on(object, chain_id))
+            .cloned()
+            .collect();
+
+        for object in &objects {
+            self.held.insert(object.clone());
+            self.shutdown_worker(object);
+        }
+
+        objects
+    }
+
+    /// Release the given chain from quarantine. Returns the objects whose workers were
+    /// shut down while it was quarantined and which can be spawned again, ie. which are
+    /// not relaying on another quarantined or halted chain nor were stopped in the meantime.
+    pub fn release_chain(&mut self, chain_id: &ChainId) -> Vec<Object> {
+        self.quarantined.remove(chain_id);
+        self.take_held()
+    }
+
+    /// Mark the given chain as halted: pause the workers relaying to or from it, and
+    /// shut down the ones which cannot be paused, e.g. to refresh clients, so that no
+    /// message nor client update is built from its stale state until it resumes.
+    /// Returns the objects whose workers were shut down.
+    pub fn halt_chain(&mut self, chain_id: &ChainId) -> Vec<Object> {
+        self.halted.insert(chain_id.clone());
+
+        let unpausable: Vec<Object> = self
+            .workers
+            .iter()
+            .filter(|(object, _)| relays_on(object, chain_id))
+            .filter(|(_, handle)| !handle.pause())
+            .map(|(object, _)| object.clone())
+            .collect();
+
+        for object in &unpausable {
+            self.held.insert(object.clone());
+            self.shutdown_worker(object);
+        }
+
+        unpausable
+    }
+
+    /// Mark the given chain as producing blocks again, resuming the workers relaying to
+    /// or from it which are not paused for another reason. Returns the objects whose
+    /// workers were shut down while it was halted and which can be spawned again.
+    pub fn resume_chain(&mut self, chain_id: &ChainId) -> Vec<Object> {
+        self.halted.remove(chain_id);
+
+        for (object, handle) in &self.workers {
+            if relays_on(object, chain_id) && !self.is_paused(object) && !self.is_halted(object) {
+                handle.resume();
+            }
+        }
+
+        self.take_held()
+    }
+
+    /// Take the held objects which are neither relaying on a quarantined
+    /// nor on a halted chain anymore, and which were not stopped in the meantime.
+    fn take_held(&mut self) -> Vec<Object> {
+        let released: Vec<Object> = self
+            .held
+            .iter()
+            .filter(|object| !self.is_quarantined(object) && !self.is_halted(object))
+            .cloned()
+            .collect();
+
+        for object in &released {
+            self.held.remove(object);
+        }
+
+        released
+            .into_iter()
+            .filter(|object| !self.is_stopped(object))
+            .collect()
+    }
+
+    /// Decide what to do with each of the workers which crashed, according to
+    /// the restart policy for their type. The workers to restart are shut down
+    /// and removed from the map, for new ones to be spawned in their place.
+    pub fn handle_crashed(
+        &mut self,
+        config: &RestartConfig,
+        now: Instant,
+    ) -> Vec<(Object, RestartDecision)> {
+        let crashed: Vec<Object> = self
+            .workers
+            .iter()
+            .filter(|(object, handle)| handle.has_crashed() && !self.stopped.contains(object))
+            .map(|(object, _)| object.clone())
+            .collect();
+
+        let mut decisions = Vec::with_capacity(crashed.len());
+
+        for object in crashed {
+            let policy = config.policy_for(object.object_type());
+            let decision = self.restarts.decide(&object, policy, now);
+
+            match decision {
+                RestartDecision::Restart { attempt } => {
+                    info!(
+                        "restarting crashed worker for {} (restart #{})",
+                        object.short_name(),
+                        attempt
+                    );
+
+                    telemetry!(worker_restart, metric_type(&object));
+
+                    self.shutdown_worker(&object);
+                }
+                RestartDecision::GiveUp { .. } => {
+                    telemetry!(worker_give_up, metric_type(&object));
+                }
+                RestartDecision::Wait | RestartDecision::GaveUp => continue,
+            }
+
+            decisions.push((object, decision));
+        }
+
+        decisions
+    }
+
     /// Shut down all the workers, asynchronously.
     pub fn shutdown(&mut self) {
         let workers = mem::take(&mut self.workers);
@@ -219,6 +534,11 @@ impl WorkerMap {
     }
 }
 
+/// Whether the worker for the given [`Object`] relays to or from the given chain.
+fn relays_on(object: &Object, chain_id: &ChainId) -> bool {
+    object.src_chain_id() == chain_id || object.dst_chain_id() == chain_id
+}
+
 // Drop handle to send shutdown signals to background tasks in parallel
 // before waiting for all of them to terminate.
 impl Drop for WorkerMap {
@@ -240,3 +560,69 @@ fn metric_type(o: &Object) -> ibc_telemetry::state::WorkerType {
         Object::CrossChainQueryPacket(_) => WorkerType::CrossChainQueryPacket,
     }
 }
+
+/// The priority of the requests made on behalf of the given object, i.e. the highest
+/// of the priorities configured for the channel it relays on, on the source chain, and
+/// for the counterparty channel, on the destination chain, which also serves the path.
+///
+/// The counterparty channel is queried in the background, as the workers are spawned
+/// under the lock of the [`WorkerMap`], and raises the priority once it is known. It
+/// is not queried if the destination chain has no priority configured.
+fn object_priority<Chain: ChainHandle>(
+    object: &Object,
+    src: &Chain,
+    config: &Config,
+) -> SharedPriority {
+    let (dst_chain_id, src_port_id, src_channel_id) = match object {
+        Object::Channel(c) => (&c.dst_chain_id, &c.src_port_id, &c.src_channel_id),
+        Object::Packet(p) => (&p.dst_chain_id, &p.src_port_id, &p.src_channel_id),
+        _ => return SharedPriority::default(),
+    };
+
+    let src_priority = config.channel_priority(&src.id(), src_port_id, src_channel_id);
+    let priority = SharedPriority::new(src_priority);
+
+    if src_priority == Priority::High {
+        return priority;
+    }
+
+    let dst_config = match config.find_chain(dst_chain_id) {
+        Some(dst_config) if dst_config.has_channel_priorities() => dst_config.clone(),
+        _ => return priority,
+    };
+
+    let src = src.clone();
+    let src_port_id = src_port_id.clone();
+    let src_channel_id = src_channel_id.clone();
+    let shared = priority.clone();
+
+    thread::spawn(move || {
+        let channel = src.query_channel(
+            QueryChannelRequest {
+                port_id: src_port_id.clone(),
+                channel_id: src_channel_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        );
+
+        match channel {
+            Ok((channel_end, _)) => {
+                let counterparty = channel_end.counterparty();
+
+                if let Some(dst_channel_id) = counterparty.channel_id() {
+                    shared
+                        .raise(dst_config.channel_priority(counterparty.port_id(), dst_channel_id));
+                }
+            }
+            Err(e) => {
+                debug!(
+                    "failed to query channel {}/{} for the priority of its counterparty: {}",
+                    src_port_id, src_channel_id, e
+                );
+            }
+        }
+    });
+
+    priority
+}
@@ -1,23 +1,33 @@
+use alloc::collections::BTreeSet;
 use core::time::Duration;
 use std::sync::{Arc, Mutex};
 
 use crossbeam_channel::Receiver;
-use tracing::{error, error_span, trace};
+use tracing::{error, error_span, info, trace};
 
+use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
+use ibc::events::IbcEvent;
 use ibc::Height;
 
+use crate::chain::counterparty::pending_packet_summary;
 use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{IncludeProof, QueryChannelRequest, QueryHeight};
+use crate::error::Error;
 use crate::event::monitor::EventBatch;
 use crate::foreign_client::HasExpiredOrFrozenError;
 use crate::link::Resubmit;
 use crate::link::{error::LinkError, Link};
 use crate::object::Packet;
 use crate::telemetry;
+use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 
 use super::error::RunError;
 use super::WorkerCmd;
 
+/// Interval at which the pending packets are listed in watch-only mode.
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
 fn handle_link_error_in_task(e: LinkError) -> TaskError<RunError> {
     if e.is_expired_or_frozen_error() {
         // If the client is expired or frozen, terminate the packet worker
@@ -30,10 +40,15 @@ fn handle_link_error_in_task(e: LinkError) -> TaskError<RunError> {
 
 /// Spawns a packet worker task in the background that handles the work of
 /// processing pending txs between `ChainA` and `ChainB`.
+///
+/// Nothing is done while the worker is paused. While it is draining, the
+/// transactions already submitted are confirmed, but none is submitted.
 pub fn spawn_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     path: Packet,
     // Mutex is used to prevent race condition between the packet workers
     link: Arc<Mutex<Link<ChainA, ChainB>>>,
+    paused: RwArc<bool>,
+    draining: RwArc<bool>,
     resubmit: Resubmit,
 ) -> TaskHandle {
     let span = {
@@ -48,16 +63,34 @@ pub fn spawn_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     };
 
     spawn_background_task(span, Some(Duration::from_millis(1000)), move || {
+        if *draining.acquire_read() {
+            handle_pending_txs(&link.lock().unwrap(), &path, Resubmit::No);
+            return Ok(Next::Continue);
+        }
+
+        if *paused.acquire_read() {
+            return Ok(Next::Continue);
+        }
+
         handle_execute_schedule(&mut link.lock().unwrap(), &path, resubmit)?;
         Ok(Next::Continue)
     })
 }
 
+/// Spawns a packet worker task in the background that handles the commands
+/// sent to the worker.
+///
+/// While the worker is paused, the commands are left in the channel,
+/// to be handled in order once the worker is resumed. They are left
+/// there for good once the worker is draining.
 pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     cmd_rx: Receiver<WorkerCmd>,
     // Mutex is used to prevent race condition between the packet workers
     link: Arc<Mutex<Link<ChainA, ChainB>>>,
+    paused: RwArc<bool>,
+    draining: RwArc<bool>,
     mut should_clear_on_start: bool,
+    mut closing: bool,
     clear_interval: u64,
     path: Packet,
 ) -> TaskHandle {
@@ -73,6 +106,10 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     };
 
     spawn_background_task(span, Some(Duration::from_millis(200)), move || {
+        if *paused.acquire_read() || *draining.acquire_read() {
+            return Ok(Next::Continue);
+        }
+
         if let Ok(cmd) = cmd_rx.try_recv() {
             // Try to clear pending packets. At different levels down in `handle_packet_cmd` there
             // are retries mechanisms for MAX_RETRIES (current value hardcoded at 5).
@@ -82,6 +119,7 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
             handle_packet_cmd(
                 &mut link.lock().unwrap(),
                 &mut should_clear_on_start,
+                &mut closing,
                 clear_interval,
                 &path,
                 cmd,
@@ -92,13 +130,87 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     })
 }
 
+/// Spawns a task which periodically lists the packets pending on the channel of the
+/// given path and reports them in the backlog metrics, without relaying them, in
+/// watch-only mode.
+pub fn spawn_packet_watch_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
+    path: Packet,
+    src_chain: ChainA,
+    dst_chain: ChainB,
+) -> TaskHandle {
+    let span = error_span!(
+        "worker.packet.watch",
+        src_chain = %path.src_chain_id,
+        src_port = %path.src_port_id,
+        src_channel = %path.src_channel_id,
+        dst_chain = %path.dst_chain_id,
+    );
+
+    // The sequences of the packets reported in the backlog
+    let mut reported = BTreeSet::new();
+
+    spawn_background_task(span, Some(WATCH_INTERVAL), move || {
+        let pending = pending_packets(&src_chain, &dst_chain, &path).map_err(TaskError::Ignore)?;
+
+        if pending.is_empty() {
+            trace!("no packets are pending");
+        } else {
+            info!(
+                pending = pending.len(),
+                "packets are pending, but the relayer is watch-only"
+            );
+        }
+
+        telemetry!(backlog_metrics(&path, &reported, &pending));
+
+        reported = pending;
+
+        Ok(Next::Continue)
+    })
+}
+
+/// The sequences of the packets sent on the channel of the given path which
+/// were not received or whose acknowledgement was not relayed back yet.
+fn pending_packets<ChainA: ChainHandle, ChainB: ChainHandle>(
+    src_chain: &ChainA,
+    dst_chain: &ChainB,
+    path: &Packet,
+) -> Result<BTreeSet<u64>, Error> {
+    let (channel_end, _) = src_chain.query_channel(
+        QueryChannelRequest {
+            port_id: path.src_port_id.clone(),
+            channel_id: path.src_channel_id.clone(),
+            height: QueryHeight::Latest,
+        },
+        IncludeProof::No,
+    )?;
+
+    let channel = IdentifiedChannelEnd::new(
+        path.src_port_id.clone(),
+        path.src_channel_id.clone(),
+        channel_end,
+    );
+
+    let pending = pending_packet_summary(src_chain, dst_chain, &channel)?;
+
+    Ok(pending
+        .unreceived_packets
+        .into_iter()
+        .chain(pending.unreceived_acks)
+        .map(u64::from)
+        .collect())
+}
+
 /// Receives worker commands and handles them accordingly.
 ///
 /// Given an `IbcEvent` command, updates the schedule and initiates
-/// packet clearing if the `should_clear_on_start` flag has been toggled.
+/// packet clearing if the `should_clear_on_start` flag has been toggled
+/// or if the channel is being closed.
 ///
 /// Given a `NewBlock` command, checks if packet clearing should occur
-/// and performs it if so.
+/// and performs it if so. While the channel is being closed, i.e. it is closed
+/// on the source chain only, it also completes the close handshake once the
+/// packets in flight are flushed.
 ///
 /// Given a `ClearPendingPackets` command, clears pending packets.
 ///
@@ -107,14 +219,22 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
 fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
     link: &mut Link<ChainA, ChainB>,
     should_clear_on_start: &mut bool,
+    closing: &mut bool,
     clear_interval: u64,
     path: &Packet,
     cmd: WorkerCmd,
 ) -> Result<(), TaskError<RunError>> {
     // Handle packet clearing which is triggered from a command
     let (do_clear, maybe_height) = match &cmd {
+        // A `CloseInit` event for this channel means that no more packets
+        // can be sent on it, so flush the pending ones before relaying the
+        // `CloseConfirm` to the counterparty.
         WorkerCmd::IbcEvents { batch } => {
-            if *should_clear_on_start {
+            if has_close_init(batch) {
+                *closing = true;
+            }
+
+            if *should_clear_on_start || has_close_init(batch) {
                 (true, Some(batch.height))
             } else {
                 (false, None)
@@ -124,7 +244,13 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
         // Handle the arrival of an event signaling that the
         // source chain has advanced to a new block
         WorkerCmd::NewBlock { height, .. } => {
-            if *should_clear_on_start || should_clear_packets(clear_interval, *height) {
+            // The packets deferred outside of the windows of the path are cleared once one opens
+            let window_opened = link.a_to_b.relay_window_opened();
+
+            if *should_clear_on_start
+                || should_clear_packets(clear_interval, *height)
+                || window_opened
+            {
                 (true, Some(*height))
             } else {
                 (false, None)
@@ -144,10 +270,13 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
     }
 
     // Handle command-specific task
-    if let WorkerCmd::IbcEvents { batch } = cmd {
-        handle_update_schedule(link, clear_interval, path, batch)
-    } else {
-        Ok(())
+    match cmd {
+        WorkerCmd::IbcEvents { batch } => handle_update_schedule(link, clear_interval, path, batch),
+        WorkerCmd::NewBlock { .. } if *closing => {
+            *closing = handle_close_confirm(link, clear_interval, path)?;
+            Ok(())
+        }
+        _ => Ok(()),
     }
 }
 
@@ -161,6 +290,15 @@ fn should_clear_packets(clear_interval: u64, height: Height) -> bool {
     clear_interval != 0 && height.revision_height() % clear_interval == 0
 }
 
+/// Whether the batch contains a `CloseInit` event. Events are batched per object,
+/// so such an event is necessarily about the channel this worker relays on.
+fn has_close_init(batch: &EventBatch) -> bool {
+    batch
+        .events
+        .iter()
+        .any(|e| matches!(e.event, IbcEvent::CloseInitChannel(_)))
+}
+
 fn handle_update_schedule<ChainA: ChainHandle, ChainB: ChainHandle>(
     link: &mut Link<ChainA, ChainB>,
     clear_interval: u64,
@@ -187,6 +325,24 @@ fn handle_clear_packet<ChainA: ChainHandle, ChainB: ChainHandle>(
     handle_execute_schedule(link, path, Resubmit::from_clear_interval(clear_interval))
 }
 
+/// Relays the `ChanCloseConfirm` of the channel if it is flushed, returning
+/// whether the channel remains to be closed on the destination chain.
+fn handle_close_confirm<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: &mut Link<ChainA, ChainB>,
+    clear_interval: u64,
+    path: &Packet,
+) -> Result<bool, TaskError<RunError>> {
+    link.a_to_b
+        .schedule_close_confirm()
+        .map_err(handle_link_error_in_task)?;
+
+    handle_execute_schedule(link, path, Resubmit::from_clear_interval(clear_interval))?;
+
+    link.a_to_b
+        .close_confirm_pending()
+        .map_err(handle_link_error_in_task)
+}
+
 fn handle_execute_schedule<ChainA: ChainHandle, ChainB: ChainHandle>(
     link: &mut Link<ChainA, ChainB>,
     _path: &Packet,
@@ -205,14 +361,23 @@ fn handle_execute_schedule<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
     })?;
 
+    handle_pending_txs(link, _path, resubmit);
+
+    Ok(())
+}
+
+/// Confirm the transactions already submitted, reporting their outcome.
+fn handle_pending_txs<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: &Link<ChainA, ChainB>,
+    _path: &Packet,
+    resubmit: Resubmit,
+) {
     let summary = link.a_to_b.process_pending_txs(resubmit);
 
     if !summary.is_empty() {
         trace!("produced relay summary: {:?}", summary);
         telemetry!(packet_metrics(_path, &summary));
     }
-
-    Ok(())
 }
 
 #[cfg(feature = "telemetry")]
@@ -225,14 +390,41 @@ fn packet_metrics(path: &Packet, summary: &RelaySummary) {
     timeout_metrics(path, summary);
 }
 
+#[cfg(feature = "telemetry")]
+fn backlog_metrics(path: &Packet, reported: &BTreeSet<u64>, pending: &BTreeSet<u64>) {
+    for sequence in pending.difference(reported) {
+        telemetry!(
+            backlog_insert,
+            *sequence,
+            &path.src_chain_id,
+            &path.src_channel_id,
+            &path.src_port_id,
+            &path.dst_chain_id
+        );
+    }
+
+    for sequence in reported.difference(pending) {
+        telemetry!(
+            backlog_remove,
+            *sequence,
+            &path.src_chain_id,
+            &path.src_channel_id,
+            &path.src_port_id,
+            &path.dst_chain_id
+        );
+    }
+}
+
 #[cfg(feature = "telemetry")]
 fn receive_packet_metrics(path: &Packet, summary: &RelaySummary) {
-    use ibc::events::IbcEvent::WriteAcknowledgement;
+    use ibc::events::IbcEvent::ReceivePacket;
 
+    // The acknowledgement of a packet may be written asynchronously by its application,
+    // eg. by a contract, and not in the transaction receiving it
     let count = summary
         .events
         .iter()
-        .filter(|e| matches!(e, WriteAcknowledgement(_)))
+        .filter(|e| matches!(e, ReceivePacket(_)))
         .count();
 
     telemetry!(
@@ -0,0 +1,201 @@
+//! Plugins running custom logic for the objects relayed on, alongside the
+//! built-in workers, e.g. to mirror the packets relayed into a database.
+//!
+//! Plugins are compiled in: a binary embedding the relayer registers its plugins
+//! with [`register`] before spawning the supervisor. Each plugin then gets its own task
+//! for each object it [applies to](WorkerPlugin::applies_to), which receives a copy of
+//! all the commands sent to the worker of the object, such as the batches of events.
+//! A plugin which panics takes the worker down with it, which is then restarted
+//! according to the restart policies.
+//!
+//! ```rust,ignore
+//! struct PacketMirror { db: Database }
+//!
+//! impl WorkerPlugin for PacketMirror {
+//!     fn name(&self) -> &str {
+//!         "packet-mirror"
+//!     }
+//!
+//!     fn applies_to(&self, object: &Object) -> bool {
+//!         matches!(object, Object::Packet(_))
+//!     }
+//!
+//!     fn handle_cmd(&self, _object: &Object, cmd: &WorkerCmd) -> Result<(), PluginError> {
+//!         if let WorkerCmd::IbcEvents { batch } = cmd {
+//!             self.db
+//!                 .insert_events(&batch.events)
+//!                 .map_err(|e| PluginError::failed(e.to_string()))?;
+//!         }
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! plugin::register(PacketMirror { db });
+//! ```
+
+use alloc::sync::Arc;
+use core::time::Duration;
+use std::sync::RwLock;
+
+use crossbeam_channel::{Receiver, Sender};
+use flex_error::define_error;
+use once_cell::sync::Lazy;
+use tracing::{error_span, warn};
+
+use crate::object::Object;
+use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
+
+use super::WorkerCmd;
+
+define_error! {
+    PluginError {
+        Failed
+            { reason: String }
+            | e | { format_args!("plugin failed: {}", e.reason) },
+    }
+}
+
+/// Custom logic run for the objects relayed on, alongside their built-in workers.
+pub trait WorkerPlugin: Send + Sync {
+    /// The name of the plugin, which identifies it in the logs.
+    fn name(&self) -> &str;
+
+    /// Whether the plugin runs for the given object. All objects by default.
+    fn applies_to(&self, _object: &Object) -> bool {
+        true
+    }
+
+    /// Handle a command sent to the worker of the given object,
+    /// e.g. a [`WorkerCmd::IbcEvents`] batch of events to relay.
+    ///
+    /// An error is logged, and the plugin goes on with the next command.
+    fn handle_cmd(&self, object: &Object, cmd: &WorkerCmd) -> Result<(), PluginError>;
+}
+
+static PLUGINS: Lazy<RwLock<Vec<Arc<dyn WorkerPlugin>>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Register a plugin, to be run for the objects of the workers spawned from now on.
+pub fn register(plugin: impl WorkerPlugin + 'static) {
+    PLUGINS.write().unwrap().push(Arc::new(plugin));
+}
+
+/// The registered plugins.
+pub fn registered() -> Vec<Arc<dyn WorkerPlugin>> {
+    PLUGINS.read().unwrap().clone()
+}
+
+/// Spawn a task for each of the given plugins which applies to the given object.
+/// Returns the senders of the commands to the tasks, along with their handles.
+pub fn spawn_plugin_tasks(
+    plugins: &[Arc<dyn WorkerPlugin>],
+    object: &Object,
+) -> (Vec<Sender<WorkerCmd>>, Vec<TaskHandle>) {
+    plugins
+        .iter()
+        .filter(|plugin| plugin.applies_to(object))
+        .map(|plugin| {
+            let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
+            let task = spawn_plugin_task(plugin.clone(), object.clone(), cmd_rx);
+
+            (cmd_tx, task)
+        })
+        .unzip()
+}
+
+fn spawn_plugin_task(
+    plugin: Arc<dyn WorkerPlugin>,
+    object: Object,
+    cmd_rx: Receiver<WorkerCmd>,
+) -> TaskHandle {
+    let span = error_span!(
+        "worker.plugin",
+        plugin = %plugin.name(),
+        object = %object.short_name(),
+    );
+
+    spawn_background_task(
+        span,
+        Some(Duration::from_millis(200)),
+        move || -> Result<Next, TaskError<PluginError>> {
+            for cmd in cmd_rx.try_iter() {
+                if let Err(e) = plugin.handle_cmd(&object, &cmd) {
+                    warn!("failed to handle {}: {}", cmd, e);
+                }
+            }
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    use crate::object::Wallet;
+
+    struct RecordingPlugin {
+        chain_id: ChainId,
+        handled: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl WorkerPlugin for RecordingPlugin {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn applies_to(&self, object: &Object) -> bool {
+            object.src_chain_id() == &self.chain_id
+        }
+
+        fn handle_cmd(&self, object: &Object, cmd: &WorkerCmd) -> Result<(), PluginError> {
+            self.handled
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", object.short_name(), cmd));
+
+            Err(PluginError::failed("ignored".to_string()))
+        }
+    }
+
+    fn wallet(chain: &str) -> Object {
+        Object::Wallet(Wallet {
+            chain_id: ChainId::new(chain.to_string(), 0),
+        })
+    }
+
+    #[test]
+    fn plugin_receives_commands() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+
+        let plugins: Vec<Arc<dyn WorkerPlugin>> = vec![Arc::new(RecordingPlugin {
+            chain_id: ChainId::new("ibc".to_string(), 0),
+            handled: handled.clone(),
+        })];
+
+        // The plugin does not apply to the objects of the other chains
+        assert!(spawn_plugin_tasks(&plugins, &wallet("other")).0.is_empty());
+
+        let (txs, tasks) = spawn_plugin_tasks(&plugins, &wallet("ibc"));
+        assert_eq!(txs.len(), 1);
+
+        // Errors do not stop the plugin from handling the next commands
+        for _ in 0..2 {
+            txs[0].send(WorkerCmd::ClearPendingPackets).unwrap();
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while handled.lock().unwrap().len() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        assert_eq!(handled.lock().unwrap().len(), 2);
+        assert!(tasks.iter().all(|task| !task.has_failed()));
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use std::time::Instant;
+
+use crate::config::restart::RestartPolicy;
+use crate::object::Object;
+
+/// What to do with a crashed worker, according to its restart policy.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RestartDecision {
+    /// Restart the worker now, which is its `attempt`-th restart
+    Restart { attempt: u32 },
+    /// Wait for the backoff to elapse before restarting the worker
+    Wait,
+    /// Give up on the worker, which exhausted its restarts
+    GiveUp { restarts: u32 },
+    /// The worker was already given up on
+    GaveUp,
+}
+
+#[derive(Clone, Debug, Default)]
+struct RestartState {
+    /// Number of restarts since the count was last reset
+    restarts: u32,
+    /// When the last restart happened
+    last_restart: Option<Instant>,
+    /// When the worker is due to be restarted, if it crashed and is waiting for its backoff
+    next_attempt: Option<Instant>,
+    gave_up: bool,
+}
+
+/// Track the restarts of the crashed workers, per object.
+#[derive(Clone, Debug, Default)]
+pub struct RestartTracker {
+    states: HashMap<Object, RestartState>,
+}
+
+impl RestartTracker {
+    /// Decide what to do with the crashed worker for the given object at the given time.
+    ///
+    /// The first call after a crash schedules the restart after the backoff
+    /// for the number of restarts so far, which is reset if the worker ran for
+    /// longer than `reset_after` since its last restart.
+    pub fn decide(
+        &mut self,
+        object: &Object,
+        policy: &RestartPolicy,
+        now: Instant,
+    ) -> RestartDecision {
+        let state = self.states.entry(object.clone()).or_default();
+
+        if state.gave_up {
+            return RestartDecision::GaveUp;
+        }
+
+        let next_attempt = match state.next_attempt {
+            Some(next_attempt) => next_attempt,
+            None => {
+                let stable = state
+                    .last_restart
+                    .map_or(false, |last| now.duration_since(last) >= policy.reset_after);
+
+                if stable {
+                    state.restarts = 0;
+                }
+
+                if state.restarts >= policy.max_restarts {
+                    state.gave_up = true;
+
+                    return RestartDecision::GiveUp {
+                        restarts: state.restarts,
+                    };
+                }
+
+                let next_attempt = now + policy.backoff(state.restarts);
+                state.next_attempt = Some(next_attempt);
+                next_attempt
+            }
+        };
+
+        if now < next_attempt {
+            return RestartDecision::Wait;
+        }
+
+        state.restarts += 1;
+        state.last_restart = Some(now);
+        state.next_attempt = None;
+
+        RestartDecision::Restart {
+            attempt: state.restarts,
+        }
+    }
+
+    /// Forget the restarts of the worker for the given object,
+    /// e.g. when relaying on it is stopped or started again.
+    pub fn forget(&mut self, object: &Object) {
+        self.states.remove(object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    use super::*;
+    use crate::object::Wallet;
+
+    fn object() -> Object {
+        Object::Wallet(Wallet {
+            chain_id: ChainId::new("ibc".to_string(), 0),
+        })
+    }
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 2,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            backoff_multiplier: 2,
+            reset_after: Duration::from_secs(60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn restarts_with_backoff_then_gives_up() {
+        let (object, policy) = (object(), policy());
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(
+            tracker.decide(&object, &policy, at(0)),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            tracker.decide(&object, &policy, at(1)),
+            RestartDecision::Restart { attempt: 1 }
+        );
+
+        // Crashes again: the backoff doubled
+        assert_eq!(
+            tracker.decide(&object, &policy, at(2)),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            tracker.decide(&object, &policy, at(3)),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            tracker.decide(&object, &policy, at(4)),
+            RestartDecision::Restart { attempt: 2 }
+        );
+
+        assert_eq!(
+            tracker.decide(&object, &policy, at(5)),
+            RestartDecision::GiveUp { restarts: 2 }
+        );
+        assert_eq!(
+            tracker.decide(&object, &policy, at(6)),
+            RestartDecision::GaveUp
+        );
+
+        tracker.forget(&object);
+        assert_eq!(
+            tracker.decide(&object, &policy, at(7)),
+            RestartDecision::Wait
+        );
+    }
+
+    #[test]
+    fn resets_after_running_long_enough() {
+        let (object, policy) = (object(), policy());
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        tracker.decide(&object, &policy, at(0));
+        tracker.decide(&object, &policy, at(1));
+        tracker.decide(&object, &policy, at(2));
+        assert_eq!(
+            tracker.decide(&object, &policy, at(4)),
+            RestartDecision::Restart { attempt: 2 }
+        );
+
+        // Ran for longer than `reset_after` before crashing again
+        assert_eq!(
+            tracker.decide(&object, &policy, at(100)),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            tracker.decide(&object, &policy, at(101)),
+            RestartDecision::Restart { attempt: 1 }
+        );
+    }
+
+    #[test]
+    fn never_restarts_without_restarts_allowed() {
+        let object = object();
+        let policy = RestartPolicy {
+            max_restarts: 0,
+            ..policy()
+        };
+
+        let mut tracker = RestartTracker::default();
+
+        assert_eq!(
+            tracker.decide(&object, &policy, Instant::now()),
+            RestartDecision::GiveUp { restarts: 0 }
+        );
+    }
+}
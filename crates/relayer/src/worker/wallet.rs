@@ -1,16 +1,46 @@
 use std::time::Duration;
 
-use tracing::{error_span, trace, warn};
+use ibc::events::IbcEvent;
+use tracing::{error, error_span, info, trace, warn};
 
 use crate::{
     chain::handle::ChainHandle,
+    config::ChainConfig,
+    keyring::KeyRing,
+    replenish::Replenisher,
     telemetry,
     util::task::{spawn_background_task, Next, TaskError, TaskHandle},
 };
 
+/// Spawns a task which reports the balance of the fee token of the relayer account,
+/// and replenishes it when the chain is configured with a `fee_replenishment`.
+///
+/// The balances of the accounts of the keys signing in turn with the key of `key_name`
+/// are reported as well, warning when they drop below the replenishment threshold, as
+/// only the account of `key_name` is replenished.
 pub fn spawn_wallet_worker<Chain: ChainHandle>(chain: Chain) -> TaskHandle {
     let span = error_span!("wallet", chain = %chain.id());
 
+    let (mut replenisher, rotated_keys, threshold) = match chain.config() {
+        Ok(config) => {
+            let rotated_keys = rotated_key_accounts(&config);
+            let threshold = config
+                .fee_replenishment
+                .as_ref()
+                .map(|replenishment| replenishment.threshold);
+
+            let replenisher = config
+                .fee_replenishment
+                .map(|replenishment| Replenisher::new(replenishment, config.gas_price.denom));
+
+            (replenisher, rotated_keys, threshold)
+        }
+        Err(e) => {
+            error!("failed to get the config of the chain, fees will not be replenished: {e}");
+            (None, Vec::new(), None)
+        }
+    };
+
     spawn_background_task(span, Some(Duration::from_secs(5)), move || {
         let key = chain.get_key().map_err(|e| {
             TaskError::Fatal(format!("failed to get key in use by the relayer: {e}"))
@@ -38,10 +68,122 @@ pub fn spawn_wallet_worker<Chain: ChainHandle>(chain: Chain) -> TaskHandle {
                 );
             }
         }
+
+        for (key_name, account) in &rotated_keys {
+            report_rotated_key_balance(&chain, key_name, account, threshold);
+        }
+
+        if let Some(replenisher) = replenisher.as_mut() {
+            let amount = balance.amount.parse::<u128>().map_err(|e| {
+                TaskError::Ignore(format!(
+                    "unable to parse the wallet balance, fees will not be replenished: {e}"
+                ))
+            })?;
+
+            match replenisher.replenish(&chain, amount) {
+                Ok(Some(events)) => {
+                    if let Some(e) = events
+                        .iter()
+                        .find(|e| matches!(e.event, IbcEvent::ChainError(_)))
+                    {
+                        error!("failed to swap the reserve token into the fee token: {}", e);
+                    } else {
+                        info!("swapped the reserve token into the fee token");
+                        telemetry!(fee_replenishments, &chain.id(), &balance.denom);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => error!("failed to replenish the fee token: {e}"),
+            }
+        }
+
         Ok(Next::Continue)
     })
 }
 
+/// The names and account addresses of the keys signing in turn with the key of `key_name`.
+fn rotated_key_accounts(config: &ChainConfig) -> Vec<(String, String)> {
+    let key_names: Vec<&String> = config
+        .key_names
+        .iter()
+        .filter(|key_name| **key_name != config.key_name)
+        .collect();
+
+    if key_names.is_empty() {
+        return Vec::new();
+    }
+
+    let keyring = match KeyRing::new_namespaced(
+        config.key_store_type,
+        &config.account_prefix,
+        &config.id,
+        config.tenant.as_deref(),
+        config.key_store_folder.as_deref(),
+    ) {
+        Ok(keyring) => keyring,
+        Err(e) => {
+            error!(
+                "failed to open the keyring, the balances of `key_names` will not be reported: {e}"
+            );
+            return Vec::new();
+        }
+    };
+
+    key_names
+        .into_iter()
+        .filter_map(|key_name| match keyring.get_key(key_name) {
+            Ok(key) => Some((key_name.clone(), key.account)),
+            Err(e) => {
+                error!(key = %key_name, "failed to get the key, its balance will not be reported: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn report_rotated_key_balance<Chain: ChainHandle>(
+    chain: &Chain,
+    key_name: &str,
+    account: &str,
+    threshold: Option<u64>,
+) {
+    let balance = match chain.query_balance(Some(key_name.to_string())) {
+        Ok(balance) => balance,
+        Err(e) => {
+            warn!(key = %key_name, "failed to query balance for the account: {e}");
+            return;
+        }
+    };
+
+    match balance.amount.parse::<f64>() {
+        Ok(amount) => {
+            telemetry!(wallet_balance, &chain.id(), account, amount, &balance.denom,);
+            trace!(%amount, denom = %balance.denom, %account, "wallet balance");
+        }
+        Err(e) => {
+            warn!(
+                %balance.amount, denom = %balance.denom, %account,
+                "unable to parse the wallet balance into a f64, the balance will therefore not be reported to telemetry. Reason: {}", e
+            );
+        }
+    }
+
+    let below_threshold = threshold.map_or(false, |threshold| {
+        balance
+            .amount
+            .parse::<u128>()
+            .map_or(false, |amount| amount < u128::from(threshold))
+    });
+
+    if below_threshold {
+        warn!(
+            key = %key_name, %account, %balance.amount, denom = %balance.denom,
+            "fee token balance of a key of `key_names` is below the replenishment threshold, \
+            only the account of `key_name` is replenished, fund it to keep signing with it"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ibc::bigint::U256;
@@ -83,12 +83,39 @@ pub struct TelemetryState {
     /// Number of workers per type
     workers: UpDownCounter<i64>,
 
+    /// Number of restarts of crashed workers per type
+    worker_restarts: Counter<u64>,
+
+    /// Number of crashed workers given up on per type, after exhausting their restarts
+    worker_give_ups: Counter<u64>,
+
+    /// Whether each chain is quarantined, after its endpoints kept failing
+    quarantined_chains: UpDownCounter<i64>,
+
+    /// Whether each chain is halted, after it stopped producing blocks
+    halted_chains: UpDownCounter<i64>,
+
+    /// Number of headers which differed between the primary and the secondary RPC endpoints of a chain
+    header_mismatches: Counter<u64>,
+
+    /// Number of queries retried against the archive endpoint of a chain, after its full node pruned the queried height
+    archive_queries: Counter<u64>,
+
     /// Number of client update messages submitted per client
     client_updates_submitted: Counter<u64>,
 
     /// Number of misbehaviours detected and submitted per client
     client_misbehaviours_submitted: Counter<u64>,
 
+    /// Number of misbehaviours detected per client by a watch-only relayer, which does not submit them
+    client_misbehaviours_detected: Counter<u64>,
+
+    /// Time elapsed since the latest update of each client, in seconds, reported by a watch-only relayer
+    client_update_age: ObservableGauge<u64>,
+
+    /// Time left before each client refreshed by the relayer expires, in seconds
+    client_time_to_expiry: ObservableGauge<u64>,
+
     /// Number of confirmed receive packets per channel
     receive_packets_confirmed: Counter<u64>,
 
@@ -98,6 +125,9 @@ pub struct TelemetryState {
     /// Number of confirmed timeout packets per channel
     timeout_packets_confirmed: Counter<u64>,
 
+    /// Number of events of the contracts called while handling the relayed packets, per channel and event type
+    contract_events: Counter<u64>,
+
     /// Number of queries submitted by Hermes, per chain and query type
     queries: Counter<u64>,
 
@@ -116,6 +146,9 @@ pub struct TelemetryState {
     /// The balance of each wallet Hermes uses per chain
     wallet_balance: ObservableGauge<f64>,
 
+    /// Number of swaps made to replenish the fee token of the wallet Hermes uses per chain
+    fee_replenishments: Counter<u64>,
+
     /// Indicates the latency for all transactions submitted to a specific chain,
     /// i.e. the difference between the moment when Hermes received a batch of events
     /// until the corresponding transaction(s) were submitted. Milliseconds.
@@ -130,6 +163,15 @@ pub struct TelemetryState {
     /// Used for computing the `tx_latency` metric.
     in_flight_events: moka::sync::Cache<String, Instant>,
 
+    /// The latency of the packets, i.e. the difference between the moment when Hermes
+    /// observed their SendPacket event until it observed the corresponding
+    /// WriteAcknowledgement event on the counterparty chain. Milliseconds.
+    packet_latency: ObservableGauge<u64>,
+
+    /// Records the time at which we observed the SendPacket event of the pending packets.
+    /// Used for computing the `packet_latency` metric.
+    in_flight_packets: moka::sync::Cache<String, Instant>,
+
     /// Number of SendPacket events received
     send_packet_events: Counter<u64>,
 
@@ -158,6 +200,13 @@ pub struct TelemetryState {
     /// Records the length of the backlog, i.e., how many packets are pending.
     backlog_size: ObservableGauge<u64>,
 
+    /// Number of packets committed on an ordered channel whose sequence
+    /// was not received yet by the counterparty
+    sequence_gap: ObservableGauge<u64>,
+
+    /// Number of packets denied by the packet policies
+    packets_denied: Counter<u64>,
+
     /// Stores the backlogs for all the paths the relayer is active on.
     /// This is a map of multiple inner backlogs, one inner backlog per path.
     ///
@@ -166,6 +215,9 @@ pub struct TelemetryState {
     /// that the relayer observed, and for which there was no associated Acknowledgement or
     /// Timeout event.
     backlogs: DashMap<PathIdentifier, DashMap<u64, u64>>,
+
+    /// The tenants the chains belong to, by which the metrics of the chains are labelled
+    tenants: DashMap<ChainId, String>,
 }
 
 impl TelemetryState {
@@ -174,6 +226,22 @@ impl TelemetryState {
         self.exporter.registry().gather()
     }
 
+    /// Label the metrics of the given chain with the given tenant
+    pub fn register_tenant(&self, chain_id: &ChainId, tenant: &str) {
+        self.tenants.insert(chain_id.clone(), tenant.to_string());
+    }
+
+    /// The label of the tenant of the given chain, empty if it belongs to no tenant
+    fn tenant_label(&self, chain_id: &ChainId) -> KeyValue {
+        let tenant = self
+            .tenants
+            .get(chain_id)
+            .map(|tenant| tenant.value().clone())
+            .unwrap_or_default();
+
+        KeyValue::new("tenant", tenant)
+    }
+
     pub fn init_worker_by_type(&self, worker_type: WorkerType) {
         self.worker(worker_type, 0);
     }
@@ -181,11 +249,17 @@ impl TelemetryState {
     pub fn init_per_chain(&self, chain_id: &ChainId) {
         let cx = Context::current();
 
-        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
 
         self.ws_reconnect.add(&cx, 0, labels);
         self.ws_events.add(&cx, 0, labels);
         self.total_messages_submitted.add(&cx, 0, labels);
+        self.quarantined_chains.add(&cx, 0, labels);
+        self.halted_chains.add(&cx, 0, labels);
+        self.header_mismatches.add(&cx, 0, labels);
 
         self.init_queries(chain_id);
     }
@@ -200,6 +274,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
             KeyValue::new("src_channel", src_channel.to_string()),
             KeyValue::new("src_port", src_port.to_string()),
         ];
@@ -221,6 +296,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain.to_string()),
+            self.tenant_label(chain),
             KeyValue::new("counterparty", counterparty.to_string()),
             KeyValue::new("channel", channel.to_string()),
             KeyValue::new("port", port.to_string()),
@@ -251,6 +327,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
             KeyValue::new("dst_chain", dst_chain.to_string()),
             KeyValue::new("client", client.to_string()),
         ];
@@ -259,6 +336,7 @@ impl TelemetryState {
 
         if misbehaviour {
             self.client_misbehaviours_submitted.add(&cx, 0, labels);
+            self.client_misbehaviours_detected.add(&cx, 0, labels);
         }
     }
 
@@ -268,6 +346,7 @@ impl TelemetryState {
         for query_type in QUERY_TYPES {
             let labels = &[
                 KeyValue::new("chain", chain_id.to_string()),
+                self.tenant_label(chain_id),
                 KeyValue::new("query_type", query_type),
             ];
 
@@ -277,6 +356,7 @@ impl TelemetryState {
         for query_type in QUERY_TYPES_CACHE {
             let labels = &[
                 KeyValue::new("chain", chain_id.to_string()),
+                self.tenant_label(chain_id),
                 KeyValue::new("query_type", query_type),
             ];
 
@@ -291,6 +371,107 @@ impl TelemetryState {
         self.workers.add(&cx, count, labels);
     }
 
+    /// Record the restart of a crashed worker
+    pub fn worker_restart(&self, worker_type: WorkerType) {
+        let cx = Context::current();
+        let labels = &[KeyValue::new("type", worker_type.to_string())];
+        self.worker_restarts.add(&cx, 1, labels);
+    }
+
+    /// Record that a crashed worker will not be restarted anymore
+    pub fn worker_give_up(&self, worker_type: WorkerType) {
+        let cx = Context::current();
+        let labels = &[KeyValue::new("type", worker_type.to_string())];
+        self.worker_give_ups.add(&cx, 1, labels);
+    }
+
+    /// Record that a chain was quarantined, or released from quarantine
+    pub fn chain_quarantined(&self, chain_id: &ChainId, quarantined: bool) {
+        let cx = Context::current();
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
+        self.quarantined_chains
+            .add(&cx, if quarantined { 1 } else { -1 }, labels);
+    }
+
+    /// Record that a chain halted, or resumed producing blocks
+    pub fn chain_halted(&self, chain_id: &ChainId, halted: bool) {
+        let cx = Context::current();
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
+        self.halted_chains
+            .add(&cx, if halted { 1 } else { -1 }, labels);
+    }
+
+    /// Record the number of packets committed on an ordered channel
+    /// which were not received by the counterparty
+    pub fn sequence_gap(
+        &self,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+        gap: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+            KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+
+        self.sequence_gap.observe(&cx, gap, labels);
+    }
+
+    /// Record a packet sent from the given chain which a packet policy denied
+    pub fn packets_denied(
+        &self,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+            KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+
+        self.packets_denied.add(&cx, 1, labels);
+    }
+
+    /// Record that a header differed between the primary and the secondary RPC endpoints of a chain
+    pub fn header_mismatch(&self, chain_id: &ChainId) {
+        let cx = Context::current();
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
+        self.header_mismatches.add(&cx, 1, labels);
+    }
+
+    /// Record that a query was sent to the archive endpoint of a chain
+    pub fn archive_query(&self, chain_id: &ChainId, query: &str) {
+        let cx = Context::current();
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+            KeyValue::new("query", query.to_string()),
+        ];
+        self.archive_queries.add(&cx, 1, labels);
+    }
+
     /// Update the number of client updates per client
     pub fn client_updates_submitted(
         &self,
@@ -303,6 +484,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
             KeyValue::new("dst_chain", dst_chain.to_string()),
             KeyValue::new("client", client.to_string()),
         ];
@@ -322,6 +504,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
             KeyValue::new("dst_chain", dst_chain.to_string()),
             KeyValue::new("client", client.to_string()),
         ];
@@ -329,6 +512,67 @@ impl TelemetryState {
         self.client_misbehaviours_submitted.add(&cx, count, labels);
     }
 
+    /// Number of client misbehaviours detected but not submitted, per client
+    pub fn client_misbehaviours_detected(
+        &self,
+        src_chain: &ChainId,
+        dst_chain: &ChainId,
+        client: &ClientId,
+        count: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
+            KeyValue::new("dst_chain", dst_chain.to_string()),
+            KeyValue::new("client", client.to_string()),
+        ];
+
+        self.client_misbehaviours_detected.add(&cx, count, labels);
+    }
+
+    /// Record the time elapsed since the latest update of a client, in seconds
+    pub fn client_update_age(
+        &self,
+        src_chain: &ChainId,
+        dst_chain: &ChainId,
+        client: &ClientId,
+        age: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
+            KeyValue::new("dst_chain", dst_chain.to_string()),
+            KeyValue::new("client", client.to_string()),
+        ];
+
+        self.client_update_age.observe(&cx, age, labels);
+    }
+
+    /// Record the time left before a client expires, in seconds
+    pub fn client_time_to_expiry(
+        &self,
+        src_chain: &ChainId,
+        dst_chain: &ChainId,
+        client: &ClientId,
+        time_to_expiry: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("src_chain", src_chain.to_string()),
+            self.tenant_label(src_chain),
+            KeyValue::new("dst_chain", dst_chain.to_string()),
+            KeyValue::new("client", client.to_string()),
+        ];
+
+        self.client_time_to_expiry
+            .observe(&cx, time_to_expiry, labels);
+    }
+
     /// Number of receive packets relayed, per channel
     pub fn receive_packets_confirmed(
         &self,
@@ -342,6 +586,7 @@ impl TelemetryState {
         if count > 0 {
             let labels = &[
                 KeyValue::new("src_chain", src_chain.to_string()),
+                self.tenant_label(src_chain),
                 KeyValue::new("src_channel", src_channel.to_string()),
                 KeyValue::new("src_port", src_port.to_string()),
             ];
@@ -363,6 +608,7 @@ impl TelemetryState {
         if count > 0 {
             let labels = &[
                 KeyValue::new("src_chain", src_chain.to_string()),
+                self.tenant_label(src_chain),
                 KeyValue::new("src_channel", src_channel.to_string()),
                 KeyValue::new("src_port", src_port.to_string()),
             ];
@@ -385,6 +631,7 @@ impl TelemetryState {
         if count > 0 {
             let labels = &[
                 KeyValue::new("src_chain", src_chain.to_string()),
+                self.tenant_label(src_chain),
                 KeyValue::new("src_channel", src_channel.to_string()),
                 KeyValue::new("src_port", src_port.to_string()),
             ];
@@ -393,12 +640,34 @@ impl TelemetryState {
         }
     }
 
+    /// Record an event of a contract called while handling a relayed packet, eg. by `ibc-hooks`
+    pub fn contract_event(
+        &self,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        kind: &str,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+            KeyValue::new("kind", kind.to_string()),
+        ];
+
+        self.contract_events.add(&cx, 1, labels);
+    }
+
     /// Number of queries emitted by the relayer, per chain and query type
     pub fn query(&self, chain_id: &ChainId, query_type: &'static str) {
         let cx = Context::current();
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("query_type", query_type),
         ];
 
@@ -411,6 +680,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("query_type", query_type),
         ];
 
@@ -421,7 +691,10 @@ impl TelemetryState {
     pub fn ws_reconnect(&self, chain_id: &ChainId) {
         let cx = Context::current();
 
-        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
 
         self.ws_reconnect.add(&cx, 1, labels);
     }
@@ -430,7 +703,10 @@ impl TelemetryState {
     pub fn ws_events(&self, chain_id: &ChainId, count: u64) {
         let cx = Context::current();
 
-        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
 
         self.ws_events.add(&cx, count, labels);
     }
@@ -439,7 +715,10 @@ impl TelemetryState {
     pub fn total_messages_submitted(&self, chain_id: &ChainId, count: u64) {
         let cx = Context::current();
 
-        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+        ];
 
         self.total_messages_submitted.add(&cx, count, labels);
     }
@@ -451,6 +730,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("account", account.to_string()),
             KeyValue::new("denom", denom.to_string()),
         ];
@@ -458,6 +738,19 @@ impl TelemetryState {
         self.wallet_balance.observe(&cx, amount, labels);
     }
 
+    /// Record a swap replenishing the fee token of the wallet on the given chain
+    pub fn fee_replenishments(&self, chain_id: &ChainId, denom: &str) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
+            KeyValue::new("denom", denom.to_string()),
+        ];
+
+        self.fee_replenishments.add(&cx, 1, labels);
+    }
+
     pub fn received_event_batch(&self, tracking_id: impl ToString) {
         self.in_flight_events
             .insert(tracking_id.to_string(), Instant::now());
@@ -482,6 +775,7 @@ impl TelemetryState {
             let labels = &[
                 // KeyValue::new("tracking_id", tracking_id),
                 KeyValue::new("chain", chain_id.to_string()),
+                self.tenant_label(chain_id),
                 KeyValue::new("counterparty", counterparty_chain_id.to_string()),
                 KeyValue::new("channel", channel_id.to_string()),
                 KeyValue::new("port", port_id.to_string()),
@@ -512,6 +806,7 @@ impl TelemetryState {
             let labels = &[
                 // KeyValue::new("tracking_id", tracking_id),
                 KeyValue::new("chain", chain_id.to_string()),
+                self.tenant_label(chain_id),
                 KeyValue::new("counterparty", counterparty_chain_id.to_string()),
                 KeyValue::new("channel", channel_id.to_string()),
                 KeyValue::new("port", port_id.to_string()),
@@ -536,6 +831,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -557,6 +853,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -576,6 +873,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -595,8 +893,9 @@ impl TelemetryState {
     ) {
         let cx = Context::current();
 
-        let labels: &[KeyValue; 4] = &[
+        let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -616,8 +915,9 @@ impl TelemetryState {
     ) {
         let cx = Context::current();
 
-        let labels: &[KeyValue; 4] = &[
+        let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -626,6 +926,51 @@ impl TelemetryState {
         self.cleared_acknowledgment_events.add(&cx, 1, labels);
     }
 
+    /// Records the moment when the relayer observed the SendPacket event for the
+    /// given sequence number, unless it already did.
+    pub fn packet_sent(
+        &self,
+        seq_nr: u64,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+    ) {
+        let key = packet_key(seq_nr, chain_id, channel_id, port_id);
+
+        self.in_flight_packets.get_with(key, Instant::now);
+    }
+
+    /// Records the latency of the packet with the given sequence number, sent on the given
+    /// chain, when the relayer observed its WriteAcknowledgement event on the counterparty.
+    pub fn packet_acknowledged(
+        &self,
+        seq_nr: u64,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+    ) {
+        let cx = Context::current();
+
+        let key = packet_key(seq_nr, chain_id, channel_id, port_id);
+
+        if let Some(start) = self.in_flight_packets.get(&key) {
+            self.in_flight_packets.invalidate(&key);
+
+            let latency = start.elapsed().as_millis() as u64;
+
+            let labels = &[
+                KeyValue::new("chain", chain_id.to_string()),
+                self.tenant_label(chain_id),
+                KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+                KeyValue::new("channel", channel_id.to_string()),
+                KeyValue::new("port", port_id.to_string()),
+            ];
+
+            self.packet_latency.observe(&cx, latency, labels);
+        }
+    }
+
     /// Inserts in the backlog a new event for the given sequence number.
     /// This happens when the relayer observed a new SendPacket event.
     pub fn backlog_insert(
@@ -647,6 +992,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -729,6 +1075,7 @@ impl TelemetryState {
 
         let labels = &[
             KeyValue::new("chain", chain_id.to_string()),
+            self.tenant_label(chain_id),
             KeyValue::new("counterparty", counterparty_chain_id.to_string()),
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
@@ -760,6 +1107,11 @@ impl TelemetryState {
     }
 }
 
+/// Unique identifier of a packet, by its sequence number and the chain/channel/port it was sent on.
+fn packet_key(seq_nr: u64, chain_id: &ChainId, channel_id: &ChannelId, port_id: &PortId) -> String {
+    format!("{}/{}/{}/{}", chain_id, port_id, channel_id, seq_nr)
+}
+
 use std::sync::Arc;
 
 use opentelemetry::metrics::Unit;
@@ -778,6 +1130,9 @@ impl AggregatorSelector for CustomAggregatorSelector {
             "backlog_oldest_sequence" => Some(Arc::new(last_value())),
             "backlog_oldest_timestamp" => Some(Arc::new(last_value())),
             "backlog_size" => Some(Arc::new(last_value())),
+            "sequence_gap" => Some(Arc::new(last_value())),
+            "client_update_age" => Some(Arc::new(last_value())),
+            "client_time_to_expiry" => Some(Arc::new(last_value())),
             // Prometheus' supports only collector for histogram, sum, and last value aggregators.
             // https://docs.rs/opentelemetry-prometheus/0.11.0/src/opentelemetry_prometheus/lib.rs.html#411-418
             // TODO: Once quantile sketches are supported, replace histograms with that.
@@ -787,6 +1142,9 @@ impl AggregatorSelector for CustomAggregatorSelector {
             "tx_latency_confirmed" => Some(Arc::new(histogram(&[
                 1000.0, 5000.0, 9000.0, 13000.0, 17000.0, 20000.0,
             ]))),
+            "packet_latency" => Some(Arc::new(histogram(&[
+                1000.0, 5000.0, 10000.0, 30000.0, 60000.0, 300000.0, 1800000.0,
+            ]))),
             _ => Some(Arc::new(sum())),
         }
     }
@@ -818,6 +1176,42 @@ impl Default for TelemetryState {
                 .with_description("Number of workers")
                 .init(),
 
+            worker_restarts: meter
+                .u64_counter("worker_restarts")
+                .with_description("Number of restarts of crashed workers")
+                .init(),
+
+            worker_give_ups: meter
+                .u64_counter("worker_give_ups")
+                .with_description(
+                    "Number of crashed workers which were not restarted after exhausting their restarts",
+                )
+                .init(),
+
+            quarantined_chains: meter
+                .i64_up_down_counter("quarantined_chains")
+                .with_description("Whether a chain is quarantined, after its endpoints kept failing")
+                .init(),
+
+            halted_chains: meter
+                .i64_up_down_counter("halted_chains")
+                .with_description("Whether a chain is halted, after it stopped producing blocks")
+                .init(),
+
+            header_mismatches: meter
+                .u64_counter("header_mismatches")
+                .with_description(
+                    "Number of headers which differed between the primary and the secondary RPC endpoints",
+                )
+                .init(),
+
+            archive_queries: meter
+                .u64_counter("archive_queries")
+                .with_description(
+                    "Number of queries sent to the archive endpoint, after the full node pruned the queried height or below its retained height",
+                )
+                .init(),
+
             client_updates_submitted: meter
                 .u64_counter("client_updates_submitted")
                 .with_description("Number of client update messages submitted")
@@ -828,6 +1222,23 @@ impl Default for TelemetryState {
                 .with_description("Number of misbehaviours detected and submitted")
                 .init(),
 
+            client_misbehaviours_detected: meter
+                .u64_counter("client_misbehaviours_detected")
+                .with_description("Number of misbehaviours detected but not submitted, in watch-only mode")
+                .init(),
+
+            client_update_age: meter
+                .u64_observable_gauge("client_update_age")
+                .with_unit(Unit::new("seconds"))
+                .with_description("Time elapsed since the latest update of the client, in watch-only mode")
+                .init(),
+
+            client_time_to_expiry: meter
+                .u64_observable_gauge("client_time_to_expiry")
+                .with_unit(Unit::new("seconds"))
+                .with_description("Time left before the client expires, unless it is refreshed")
+                .init(),
+
             receive_packets_confirmed: meter
                 .u64_counter("receive_packets_confirmed")
                 .with_description("Number of confirmed receive packets. Available if relayer runs with Tx confirmation enabled")
@@ -843,6 +1254,13 @@ impl Default for TelemetryState {
                 .with_description("Number of confirmed timeout packets. Available if relayer runs with Tx confirmation enabled")
                 .init(),
 
+            contract_events: meter
+                .u64_counter("contract_events")
+                .with_description(
+                    "Number of events of the contracts called while handling the relayed packets",
+                )
+                .init(),
+
             queries: meter
                 .u64_counter("queries")
                 .with_description(
@@ -875,6 +1293,11 @@ impl Default for TelemetryState {
                 .with_description("The balance of each wallet Hermes uses per chain. Please note that when converting the balance to f64 a loss in precision might be introduced in the displayed value")
                 .init(),
 
+            fee_replenishments: meter
+                .u64_counter("fee_replenishments")
+                .with_description("Number of swaps made to replenish the fee token of the wallet Hermes uses per chain")
+                .init(),
+
             send_packet_events: meter
                 .u64_counter("send_packet_events")
                 .with_description("Number of SendPacket events received")
@@ -921,8 +1344,23 @@ impl Default for TelemetryState {
                 .time_to_idle(Duration::from_secs(30 * 60)) // Remove entries if they have been idle for 30 minutes
                 .build(),
 
+            packet_latency: meter
+                .u64_observable_gauge("packet_latency")
+                .with_unit(Unit::new("milliseconds"))
+                .with_description("The latency of the packets, i.e. the difference between the moment when Hermes \
+                    observed their SendPacket event until it observed the corresponding WriteAcknowledgement \
+                    event on the counterparty chain. Milliseconds.")
+                .init(),
+
+            in_flight_packets: moka::sync::Cache::builder()
+                .max_capacity(100_000) // Bound the number of pending packets tracked
+                .time_to_live(Duration::from_secs(24 * 60 * 60)) // Remove entries after 1 day
+                .build(),
+
             backlogs: DashMap::new(),
 
+            tenants: DashMap::new(),
+
             backlog_oldest_sequence: meter
                 .u64_observable_gauge("backlog_oldest_sequence")
                 .with_description("Sequence number of the oldest SendPacket event in the backlog")
@@ -938,6 +1376,16 @@ impl Default for TelemetryState {
                 .u64_observable_gauge("backlog_size")
                 .with_description("Total number of SendPacket events in the backlog")
                 .init(),
+
+            sequence_gap: meter
+                .u64_observable_gauge("sequence_gap")
+                .with_description("Number of packets committed on an ordered channel which were not received by the counterparty")
+                .init(),
+
+            packets_denied: meter
+                .u64_counter("packets_denied")
+                .with_description("Number of packets denied by the packet policies")
+                .init(),
         }
     }
 }
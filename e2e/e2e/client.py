@@ -16,9 +16,16 @@ class ClientCreated:
 class TxCreateClient(Cmd[ClientCreated]):
     dst_chain_id: ChainId
     src_chain_id: ChainId
+    trusting_period: Optional[str] = None
 
     def args(self) -> List[str]:
-        return ["--host-chain", self.dst_chain_id, "--reference-chain", self.src_chain_id]
+        args = ["--host-chain", self.dst_chain_id,
+                "--reference-chain", self.src_chain_id]
+
+        if self.trusting_period is not None:
+            args.extend(["--trusting-period", self.trusting_period])
+
+        return args
 
     def process(self, result: Any) -> ClientCreated:
         return from_dict(ClientCreated, result['CreateClient'])
@@ -96,8 +103,9 @@ class QueryClientState(Cmd[ClientState]):
 # =============================================================================
 
 
-def create_client(c: Config, dst: ChainId, src: ChainId) -> ClientCreated:
-    cmd = TxCreateClient(dst_chain_id=dst, src_chain_id=src)
+def create_client(c: Config, dst: ChainId, src: ChainId, trusting_period: Optional[str] = None) -> ClientCreated:
+    cmd = TxCreateClient(dst_chain_id=dst, src_chain_id=src,
+                         trusting_period=trusting_period)
     client = cmd.run(c).success()
     l.info(f'Created client: {client.client_id}')
     return client
@@ -128,3 +136,27 @@ def create_update_query_client(c: Config, dst: ChainId, src: ChainId) -> ClientI
     query_client_state(c, dst, client.client_id)
     split()
     return client.client_id
+
+
+def client_expiry(c: Config, dst: ChainId, src: ChainId, trusting_period: int = 30) -> ClientId:
+    """
+    Create a client with a short trusting period, let it expire,
+    and check that it can no longer be updated.
+    """
+
+    client = create_client(c, dst, src, trusting_period=f'{trusting_period}s')
+    split()
+    update_client(c, dst, client.client_id)
+    split()
+
+    l.info(f'Waiting for client {client.client_id} to expire...')
+    sleep(trusting_period + 10)
+
+    cmd = TxUpdateClient(dst_chain_id=dst, dst_client_id=client.client_id)
+    status = cmd.run(c).result.get('status')
+
+    assert (status != 'success'), (client.client_id,
+                                   "update of expired client succeeded (expected failure)")
+
+    l.info(f'Expired client {client.client_id} cannot be updated')
+    return client.client_id
@@ -0,0 +1,106 @@
+import json
+import subprocess
+from pathlib import Path
+
+from .cmd import *
+from .common import *
+import e2e.client as client
+
+
+@dataclass
+@cmd("tx upgrade-chain")
+class TxUpgradeChain(Cmd[Any]):
+    dst_chain_id: ChainId
+    src_chain_id: ChainId
+    dst_client_id: ClientId
+    amount: int
+    height_offset: int
+
+    def args(self) -> List[str]:
+        return ["--reference-chain", self.src_chain_id,
+                "--host-chain", self.dst_chain_id,
+                "--host-client", self.dst_client_id,
+                "--amount", str(self.amount),
+                "--height-offset", str(self.height_offset)]
+
+    def process(self, result: Any) -> Any:
+        return result
+
+
+@dataclass
+@cmd("upgrade client")
+class UpgradeClient(Cmd[Any]):
+    dst_chain_id: ChainId
+    dst_client_id: ClientId
+    upgrade_height: int
+
+    def args(self) -> List[str]:
+        return ["--host-chain", self.dst_chain_id,
+                "--client", self.dst_client_id,
+                "--upgrade-height", str(self.upgrade_height)]
+
+    def process(self, result: Any) -> Any:
+        return result
+
+
+# =============================================================================
+# Governance of the upgraded chain, through its full node binary
+# =============================================================================
+
+
+@dataclass
+class Gaiad:
+    binary: str
+    chain_data: Path
+    node: str
+
+    def run(self, chain_id: ChainId, args: List[str]) -> Any:
+        full_cmd = [self.binary, '--home', str(self.chain_data / chain_id),
+                    '--node', self.node.format(chain_id=chain_id), '--output', 'json']
+        full_cmd.extend(args)
+        l.debug(' '.join(full_cmd))
+
+        res = subprocess.run(full_cmd, capture_output=True, text=True, check=True)
+        return json.loads(res.stdout)
+
+    def last_proposal_id(self, chain_id: ChainId) -> int:
+        proposals = self.run(chain_id, ['query', 'gov', 'proposals'])['proposals']
+        return int(proposals[-1]['proposal_id'])
+
+    def upgrade_plan_height(self, chain_id: ChainId) -> int:
+        return int(self.run(chain_id, ['query', 'upgrade', 'plan'])['height'])
+
+    def vote_yes(self, chain_id: ChainId, proposal_id: int):
+        self.run(chain_id, ['tx', 'gov', 'vote', str(proposal_id), 'yes',
+                            '--from', 'validator', '--keyring-backend', 'test',
+                            '--chain-id', chain_id, '--yes'])
+
+
+def upgrade(c: Config, g: Gaiad, dst: ChainId, src: ChainId, height_offset: int = 250):
+    """
+    Upgrade the chain `src` through a governance proposal,
+    and upgrade the client of `src` on `dst` once `src` halted.
+    """
+
+    client_id = client.create_client(c, dst, src).client_id
+    split()
+
+    TxUpgradeChain(dst_chain_id=dst, src_chain_id=src, dst_client_id=client_id,
+                   amount=10000000, height_offset=height_offset).run(c).success()
+    sleep(5.0)
+
+    proposal_id = g.last_proposal_id(src)
+    upgrade_height = g.upgrade_plan_height(src)
+    l.info(f'Voting on the upgrade proposal {proposal_id} of {src} at height {upgrade_height}')
+
+    g.vote_yes(src, proposal_id)
+
+    # Waits for the chain `src` to halt at the upgrade height
+    UpgradeClient(dst_chain_id=dst, dst_client_id=client_id,
+                  upgrade_height=upgrade_height).run(c).success()
+
+    _, state = client.query_client_state(c, dst, client_id)
+    assert (state.latest_height.revision_height >= upgrade_height), (
+        state.latest_height, "client was not upgraded past the upgrade height")
+
+    l.info(f'Upgraded client {client_id} on {dst}')
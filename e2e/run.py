@@ -11,6 +11,7 @@ import e2e.client as client
 import e2e.connection as connection
 import e2e.packet as packet
 import e2e.relayer as relayer
+import e2e.upgrade as upgrade
 from e2e.cmd import Config
 from e2e.common import *
 
@@ -198,6 +199,49 @@ def raw(c: Config, ibc0: ChainId, ibc1: ChainId, port_id: PortId) -> Tuple[Clien
     return ibc0_client_id, ibc0_conn_id, ibc0_chan_id, ibc1_client_id, ibc1_conn_id, ibc1_chan_id
 
 
+def channel_between(c: Config, ibc0: ChainId, ibc1: ChainId, port_id: PortId) -> Tuple[ChannelId, ChannelId]:
+    ibc0_client_id = client.create_update_query_client(c, ibc0, ibc1)
+    ibc1_client_id = client.create_update_query_client(c, ibc1, ibc0)
+
+    ibc0_conn_id, ibc1_conn_id = connection.handshake(
+        c, ibc0, ibc1, ibc0_client_id, ibc1_client_id)
+
+    return channel.handshake(c, ibc0, ibc1, ibc0_conn_id, ibc1_conn_id, port_id)
+
+
+def all_scenarios(c: Config, ibc0: ChainId, ibc1: ChainId, port_id: PortId):
+    ibc0_client_id, ibc0_conn_id, ibc0_chan_id, ibc1_client_id, ibc1_conn_id, ibc1_chan_id = raw(
+        c, ibc0, ibc1, port_id)
+    sleep(2.0)
+
+    passive_packets(c, ibc0, ibc1, port_id, ibc0_chan_id, ibc1_chan_id)
+    sleep(2.0)
+
+    connection.passive_connection_init_then_start(
+        c, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
+    sleep(2.0)
+
+    connection.passive_connection_start_then_init(
+        c, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
+    sleep(2.0)
+
+    connection.passive_connection_try_then_start(
+        c, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
+    sleep(2.0)
+
+    channel.passive_channel_start_then_init(
+        c, ibc1, ibc0, ibc1_conn_id, port_id)
+    sleep(2.0)
+
+    channel.passive_channel_init_then_start(
+        c, ibc1, ibc0, ibc1_conn_id, port_id)
+    sleep(2.0)
+
+    channel.passive_channel_try_then_start(
+        c, ibc1, ibc0, ibc1_conn_id, ibc0_conn_id, port_id)
+    sleep(2.0)
+
+
 def main():
     parser = argparse.ArgumentParser(
         description='Test all relayer commands, end-to-end')
@@ -213,6 +257,33 @@ def main():
                         metavar='CMD',
                         default='cargo run --bin hermes --')
 
+    parser.add_argument('--scenario',
+                        help='scenario to run (default: all, which excludes client-expiry and upgrade)',
+                        choices=['all', 'transfer', 'timeout',
+                                 'client-expiry', 'upgrade'],
+                        default='all')
+
+    parser.add_argument('--chains',
+                        help='identifiers of the two chains to run the scenario between (default: the first two of the configuration)',
+                        metavar='CHAIN_ID',
+                        nargs=2)
+
+    parser.add_argument('--gaiad',
+                        help='full node binary used to vote on the upgrade proposal (default: gaiad)',
+                        metavar='BINARY',
+                        default='gaiad')
+
+    parser.add_argument('--chain-data',
+                        help='directory holding the home directory of each chain (default: ./data)',
+                        metavar='DIR',
+                        default=Path('data'),
+                        type=Path)
+
+    parser.add_argument('--gaiad-node',
+                        help='RPC address of the node of a chain, where {chain_id} is replaced by its identifier (default: tcp://localhost:26657)',
+                        metavar='URL',
+                        default='tcp://localhost:26657')
+
     parser.add_argument('--log-level',
                         help='minimum log level (default: debug)',
                         metavar='LOG',
@@ -235,42 +306,31 @@ def main():
         format='%(asctime)s [%(levelname)8s] %(message)s',
         datefmt='%Y-%m-%d %H:%M:%S')
 
-    chains = toml.load(config.config_file)['chains']
+    if args.chains is not None:
+        ibc0, ibc1 = args.chains
+    else:
+        chains = toml.load(config.config_file)['chains']
 
-    ibc0 = chains[0]['id']
-    ibc1 = chains[1]['id']
+        ibc0 = chains[0]['id']
+        ibc1 = chains[1]['id']
     port_id = PortId('transfer')
 
-    ibc0_client_id, ibc0_conn_id, ibc0_chan_id, ibc1_client_id, ibc1_conn_id, ibc1_chan_id = raw(
-        config, ibc0, ibc1, port_id)
-    sleep(2.0)
-
-    passive_packets(config, ibc0, ibc1, port_id, ibc0_chan_id, ibc1_chan_id)
-    sleep(2.0)
-
-    connection.passive_connection_init_then_start(
-        config, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
-    sleep(2.0)
-
-    connection.passive_connection_start_then_init(
-        config, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
-    sleep(2.0)
-
-    connection.passive_connection_try_then_start(
-        config, ibc1, ibc0, ibc1_client_id, ibc0_client_id)
-    sleep(2.0)
-
-    channel.passive_channel_start_then_init(
-        config, ibc1, ibc0, ibc1_conn_id, port_id)
-    sleep(2.0)
-
-    channel.passive_channel_init_then_start(
-        config, ibc1, ibc0, ibc1_conn_id, port_id)
-    sleep(2.0)
-
-    channel.passive_channel_try_then_start(
-        config, ibc1, ibc0, ibc1_conn_id, ibc0_conn_id, port_id)
-    sleep(2.0)
+    if args.scenario == 'transfer':
+        ibc0_chan_id, ibc1_chan_id = channel_between(
+            config, ibc0, ibc1, port_id)
+        packet.ping_pong(config, ibc0, ibc1, ibc0_chan_id, ibc1_chan_id)
+    elif args.scenario == 'timeout':
+        ibc0_chan_id, ibc1_chan_id = channel_between(
+            config, ibc0, ibc1, port_id)
+        packet.timeout(config, ibc0, ibc1, ibc0_chan_id, ibc1_chan_id)
+    elif args.scenario == 'client-expiry':
+        client.client_expiry(config, ibc0, ibc1)
+    elif args.scenario == 'upgrade':
+        gaiad = upgrade.Gaiad(binary=args.gaiad, chain_data=args.chain_data,
+                              node=args.gaiad_node)
+        upgrade.upgrade(config, gaiad, dst=ibc0, src=ibc1)
+    else:
+        all_scenarios(config, ibc0, ibc1, port_id)
 
 
 if __name__ == "__main__":
@@ -2,8 +2,8 @@ use std::io::Write;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 
 use ibc_relayer::config::{
-    Channels as ConfigChannels, Clients as ConfigClients, Connections as ConfigConnections,
-    ModeConfig, Packets as ConfigPackets,
+    default, Channels as ConfigChannels, Clients as ConfigClients,
+    Connections as ConfigConnections, ModeConfig, Packets as ConfigPackets,
 };
 
 use ibc_test_framework::prelude::*;
@@ -145,9 +145,12 @@ impl TestOverrides for IbcTransferMBT {
 
     fn modify_relayer_config(&self, config: &mut Config) {
         config.mode = ModeConfig {
+            watch_only: false,
             clients: ConfigClients {
                 enabled: true,
                 refresh: true,
+                refresh_rate: default::client_refresh_rate(),
+                expiry_warning_rate: default::client_expiry_warning_rate(),
                 misbehaviour: true,
             },
             connections: ConfigConnections { enabled: true },
@@ -3,8 +3,8 @@ use std::time::Duration;
 
 use ibc::core::ics04_channel::packet::Sequence;
 use ibc_relayer::chain::requests::{
-    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest, QueryUnreceivedAcksRequest,
-    QueryUnreceivedPacketsRequest,
+    QueryHeight, QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest,
+    QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
 };
 use ibc_test_framework::ibc::denom::Denom;
 use ibc_test_framework::prelude::*;
@@ -99,6 +99,7 @@ pub fn get_committed_packets_at_src<ChainA: ChainHandle, ChainB: ChainHandle>(
         port_id: port_id_a.clone(),
         channel_id: channel_id_a.clone(),
         pagination: None,
+        height: QueryHeight::Latest,
     };
     let (sequences, _) = chain.query_packet_commitments(request)?;
     Ok(sequences)
@@ -129,6 +130,7 @@ pub fn get_acknowledged_packets_at_dst<ChainA: ChainHandle, ChainB: ChainHandle>
         channel_id: channel_id_a.clone(),
         pagination: None,
         packet_commitment_sequences: Vec::new(),
+        height: QueryHeight::Latest,
     };
     Ok(chain.query_packet_acknowledgements(request)?.0)
 }
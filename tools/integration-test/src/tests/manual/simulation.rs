@@ -90,6 +90,7 @@ fn tx_raw_ft_transfer<SrcChain: ChainHandle, DstChain: ChainHandle>(
         timeout_height_offset,
         timeout_duration,
         number_msgs: number_messages,
+        memo: None,
     };
 
     let events = build_and_send_transfer_messages(src_handle, dst_handle, &transfer_options)?;
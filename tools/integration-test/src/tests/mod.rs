@@ -32,3 +32,6 @@ pub mod manual;
 
 #[cfg(any(doc, feature = "example"))]
 pub mod example;
+
+#[cfg(any(doc, feature = "mbt"))]
+pub mod packet_lifecycle;
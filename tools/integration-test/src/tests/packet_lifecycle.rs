@@ -0,0 +1,287 @@
+/*!
+   A model-based test of the relaying of packets, driving the relayer through
+   random interleavings of IBC transfers, packet timeouts, restarts and
+   transaction failures, and checking against a model of the expected outcome
+   of each packet that every committed packet is eventually either received
+   or timed out, exactly once.
+
+   The steps and amounts are derived from a seed, which is logged at the start
+   of the test and can be set with `PACKET_LIFECYCLE_SEED` to replay a failed run.
+
+   The test runs against real chains, and takes several minutes, so it is only
+   built with the `mbt` feature, along with the other model-based tests:
+
+   ```text
+   cargo test -p ibc-integration-test --features mbt test_packet_lifecycle
+   ```
+*/
+
+use std::env;
+
+use ibc_relayer::chain::requests::{PageRequest, QueryHeight, QueryPacketCommitmentsRequest};
+use ibc_test_framework::ibc::denom::derive_ibc_denom;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::util::random::{random_u64, SeededRandom};
+
+#[test]
+fn test_packet_lifecycle() -> Result<(), Error> {
+    let seed = match env::var("PACKET_LIFECYCLE_SEED") {
+        Ok(seed) => seed.parse().map_err(handle_generic_error)?,
+        Err(_) => random_u64(),
+    };
+
+    run_binary_channel_test(&PacketLifecycleTest { seed })
+}
+
+/// Number of random steps the relayer is driven through.
+const STEPS: usize = 12;
+
+/// Timeout of the packets which are meant to be received, well past the end of the test.
+const LONG_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Timeout of the packets which are meant to time out, while the relayer is stopped.
+const SHORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct PacketLifecycleTest {
+    /// Seed of the random steps and amounts
+    pub seed: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    /// Transfer tokens, the packet being received while or once the relayer runs
+    Transfer,
+    /// Stop the relayer and transfer tokens, the packet timing out before the relayer restarts
+    TransferTimingOut,
+    /// Stop the relayer, if running
+    Stop,
+    /// Start the relayer, if stopped
+    Start,
+    /// Restart the relayer, losing its in-memory state
+    Restart,
+    /// Make the next transaction of the relayer on the destination chain fail,
+    /// by desynchronizing the account sequence it caches
+    FailNextTx,
+}
+
+impl Step {
+    fn random(random: &mut SeededRandom) -> Self {
+        match random.u64_range(0, 6) {
+            0 => Self::Transfer,
+            1 => Self::TransferTimingOut,
+            2 => Self::Stop,
+            3 => Self::Start,
+            4 => Self::Restart,
+            _ => Self::FailNextTx,
+        }
+    }
+}
+
+/// The expected outcome of a packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Received,
+    TimedOut,
+}
+
+/// The model of the packets sent during the test.
+#[derive(Default)]
+struct Model {
+    packets: Vec<(u64, Outcome)>,
+}
+
+impl Model {
+    fn amount(&self, outcome: Outcome) -> u64 {
+        self.packets
+            .iter()
+            .filter(|(_, o)| *o == outcome)
+            .map(|(amount, _)| amount)
+            .sum()
+    }
+}
+
+impl TestOverrides for PacketLifecycleTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        config.mode.packets.enabled = true;
+        config.mode.packets.clear_on_start = true;
+        config.mode.packets.clear_interval = 10;
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+
+    fn channel_order(&self) -> Order {
+        Order::Unordered
+    }
+}
+
+impl BinaryChannelTest for PacketLifecycleTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let denom_a = chains.node_a.denom();
+
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+        let relayer_wallet_b = chains.node_b.wallets().relayer().cloned();
+
+        let balance_a = chains
+            .node_a
+            .chain_driver()
+            .query_balance(&wallet_a.address(), &denom_a)?;
+
+        let mut random = SeededRandom::new(self.seed);
+        info!("random seed of the test: {}", random.seed());
+
+        let steps: Vec<Step> = (0..STEPS).map(|_| Step::random(&mut random)).collect();
+        info!("driving the relayer through the steps {:?}", steps);
+
+        let mut model = Model::default();
+        let mut supervisor: Option<SupervisorHandle> = None;
+
+        for step in steps {
+            info!("step: {:?}", step);
+
+            match step {
+                Step::Transfer => {
+                    let amount = random.u64_range(1000, 5000);
+
+                    chains
+                        .node_a
+                        .chain_driver()
+                        .ibc_transfer_token_with_timeout(
+                            &channel.port_a.as_ref(),
+                            &channel.channel_id_a.as_ref(),
+                            &wallet_a.as_ref(),
+                            &wallet_b.address(),
+                            &denom_a,
+                            amount,
+                            LONG_TIMEOUT,
+                        )?;
+
+                    model.packets.push((amount, Outcome::Received));
+                }
+                Step::TransferTimingOut => {
+                    if let Some(handle) = supervisor.take() {
+                        handle.shutdown();
+                    }
+
+                    let amount = random.u64_range(1000, 5000);
+
+                    chains
+                        .node_a
+                        .chain_driver()
+                        .ibc_transfer_token_with_timeout(
+                            &channel.port_a.as_ref(),
+                            &channel.channel_id_a.as_ref(),
+                            &wallet_a.as_ref(),
+                            &wallet_b.address(),
+                            &denom_a,
+                            amount,
+                            SHORT_TIMEOUT,
+                        )?;
+
+                    model.packets.push((amount, Outcome::TimedOut));
+
+                    // Let the packet time out on the destination chain
+                    sleep(SHORT_TIMEOUT * 2);
+                }
+                Step::Stop => {
+                    if let Some(handle) = supervisor.take() {
+                        handle.shutdown();
+                    }
+                }
+                Step::Start => {
+                    if supervisor.is_none() {
+                        supervisor = Some(relayer.spawn_supervisor()?);
+                    }
+                }
+                Step::Restart => {
+                    if let Some(handle) = supervisor.take() {
+                        handle.shutdown();
+                    }
+
+                    supervisor = Some(relayer.spawn_supervisor()?);
+                }
+                Step::FailNextTx => {
+                    chains.node_b.chain_driver().local_transfer_token(
+                        &relayer_wallet_b.as_ref(),
+                        &wallet_b.address(),
+                        100,
+                        &chains.node_b.denom(),
+                    )?;
+                }
+            }
+
+            sleep(Duration::from_secs(1));
+        }
+
+        // Let the relayer run until all the packets are either received or timed out
+        let _supervisor = match supervisor {
+            Some(handle) => handle,
+            None => relayer.spawn_supervisor()?,
+        };
+
+        let received = model.amount(Outcome::Received);
+        let timed_out = model.amount(Outcome::TimedOut);
+
+        info!(
+            "expecting {} tokens to be received and {} tokens to be refunded",
+            received, timed_out
+        );
+
+        let denom_b = derive_ibc_denom(
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        // The amounts of the packets timed out are refunded once, and the ones
+        // of the packets received are credited once.
+        chains.node_a.chain_driver().assert_eventual_wallet_amount(
+            &wallet_a.address(),
+            balance_a - received,
+            &denom_a,
+        )?;
+
+        chains.node_b.chain_driver().assert_eventual_wallet_amount(
+            &wallet_b.address(),
+            received,
+            &denom_b.as_ref(),
+        )?;
+
+        // No packet is left pending on the source chain
+        assert_eventually_succeed(
+            "all packet commitments are cleared",
+            20,
+            Duration::from_secs(1),
+            || {
+                let (sequences, _) =
+                    chains
+                        .handle_a()
+                        .query_packet_commitments(QueryPacketCommitmentsRequest {
+                            port_id: channel.port_a.value().clone(),
+                            channel_id: channel.channel_id_a.value().clone(),
+                            pagination: Some(PageRequest::all()),
+                            height: QueryHeight::Latest,
+                        })?;
+
+                if sequences.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::generic(eyre!(
+                        "packets {:?} are neither received nor timed out",
+                        sequences
+                    )))
+                }
+            },
+        )?;
+
+        Ok(())
+    }
+}
@@ -17,9 +17,12 @@ struct SupervisorTest;
 impl TestOverrides for SupervisorTest {
     fn modify_relayer_config(&self, config: &mut Config) {
         config.mode = ModeConfig {
+            watch_only: false,
             clients: config::Clients {
                 enabled: true,
                 refresh: true,
+                refresh_rate: config::default::client_refresh_rate(),
+                expiry_warning_rate: config::default::client_expiry_warning_rate(),
                 misbehaviour: true,
             },
             connections: config::Connections { enabled: true },
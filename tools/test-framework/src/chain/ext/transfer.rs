@@ -1,9 +1,11 @@
+use core::time::Duration;
+
 use crate::chain::cli::transfer::local_transfer_token;
 use crate::chain::driver::ChainDriver;
 use crate::chain::tagged::TaggedChainDriverExt;
 use crate::error::Error;
 use crate::ibc::denom::Denom;
-use crate::relayer::transfer::ibc_token_transfer;
+use crate::relayer::transfer::{ibc_token_transfer, ibc_token_transfer_with_timeout};
 use crate::types::id::{TaggedChannelIdRef, TaggedPortIdRef};
 use crate::types::tagged::*;
 use crate::types::wallet::{Wallet, WalletAddress};
@@ -39,6 +41,24 @@ pub trait ChainTransferMethodsExt<Chain> {
         amount: u64,
     ) -> Result<(), Error>;
 
+    /**
+       Submits an IBC token transfer transaction like [`ibc_transfer_token`],
+       with the packet timing out after the given duration.
+
+       [`ibc_transfer_token`]: ChainTransferMethodsExt::ibc_transfer_token
+    */
+    #[allow(clippy::too_many_arguments)]
+    fn ibc_transfer_token_with_timeout<Counterparty>(
+        &self,
+        port_id: &TaggedPortIdRef<Chain, Counterparty>,
+        channel_id: &TaggedChannelIdRef<Chain, Counterparty>,
+        sender: &MonoTagged<Chain, &Wallet>,
+        recipient: &MonoTagged<Counterparty, &WalletAddress>,
+        denom: &MonoTagged<Chain, &Denom>,
+        amount: u64,
+        timeout: Duration,
+    ) -> Result<(), Error>;
+
     fn local_transfer_token(
         &self,
         sender: &MonoTagged<Chain, &Wallet>,
@@ -69,6 +89,30 @@ impl<'a, Chain: Send> ChainTransferMethodsExt<Chain> for MonoTagged<Chain, &'a C
         ))
     }
 
+    fn ibc_transfer_token_with_timeout<Counterparty>(
+        &self,
+        port_id: &TaggedPortIdRef<Chain, Counterparty>,
+        channel_id: &TaggedChannelIdRef<Chain, Counterparty>,
+        sender: &MonoTagged<Chain, &Wallet>,
+        recipient: &MonoTagged<Counterparty, &WalletAddress>,
+        denom: &MonoTagged<Chain, &Denom>,
+        amount: u64,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        self.value()
+            .runtime
+            .block_on(ibc_token_transfer_with_timeout(
+                &self.tx_config(),
+                port_id,
+                channel_id,
+                sender,
+                recipient,
+                denom,
+                amount,
+                timeout,
+            ))
+    }
+
     fn local_transfer_token(
         &self,
         sender: &MonoTagged<Chain, &Wallet>,
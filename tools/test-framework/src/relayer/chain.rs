@@ -23,10 +23,12 @@ is still a [`ChainHandle`].
 use crossbeam_channel as channel;
 use tracing::Span;
 
+use ibc::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc::core::ics02_client::events::UpdateClient;
 use ibc::core::ics03_connection::connection::IdentifiedConnectionEnd;
 use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
 use ibc::core::ics04_channel::packet::{PacketMsgType, Sequence};
+use ibc::core::ics04_channel::upgrade::{ErrorReceipt, Upgrade};
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 use ibc::{
     core::ics03_connection::connection::ConnectionEnd,
@@ -43,22 +45,27 @@ use ibc::{
 };
 use ibc_relayer::account::Balance;
 use ibc_relayer::chain::client::ClientSettings;
-use ibc_relayer::chain::endpoint::{ChainStatus, HealthCheck};
+use ibc_relayer::chain::endpoint::{ChainStatus, HealthCheck, SimulatedTx};
 use ibc_relayer::chain::handle::{ChainHandle, ChainRequest, Subscription};
+use ibc_relayer::chain::proof::QueryProof;
 use ibc_relayer::chain::requests::{
-    CrossChainQueryRequest, IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest,
-    QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
-    QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
-    QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
-    QueryConsensusStatesRequest, QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
-    QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
-    QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
-    QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    BatchQueryRequest, CrossChainQueryRequest, IncludeProof, QueryBlockRequest,
+    QueryChannelClientStateRequest, QueryChannelRequest, QueryChannelsRequest,
+    QueryClientConnectionsRequest, QueryClientStateRequest, QueryClientStatesRequest,
+    QueryConnectionChannelsRequest, QueryConnectionRequest, QueryConnectionsRequest,
+    QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryCounterpartyPayeeRequest,
+    QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryPacketAcknowledgementRequest,
+    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentRequest,
+    QueryPacketCommitmentsRequest, QueryPacketReceiptRequest, QueryTxRequest,
+    QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest, QueryUpgradeErrorRequest,
+    QueryUpgradeRequest, QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    QueryWasmChecksumsRequest,
 };
-use ibc_relayer::chain::responses::CrossChainQueryResponse;
+use ibc_relayer::chain::responses::{BatchQueryResponse, CrossChainQueryResponse};
 use ibc_relayer::chain::tracking::TrackedMsgs;
 use ibc_relayer::client_state::{AnyClientState, IdentifiedAnyClientState};
+use ibc_relayer::config::priority::Priority;
 use ibc_relayer::config::ChainConfig;
 use ibc_relayer::connection::ConnectionMsgType;
 use ibc_relayer::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
@@ -81,7 +88,7 @@ where
     Tag: Send + Sync + 'static,
     Handle: ChainHandle,
 {
-    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, Priority, ChainRequest)>) -> Self {
         Self::new(Handle::new(chain_id, sender))
     }
 
@@ -97,6 +104,10 @@ where
         self.value().health_check()
     }
 
+    fn check_key(&self) -> Result<HealthCheck, Error> {
+        self.value().check_key()
+    }
+
     fn subscribe(&self) -> Result<Subscription, Error> {
         self.value().subscribe()
     }
@@ -115,6 +126,14 @@ where
         self.value().send_messages_and_wait_check_tx(tracked_msgs)
     }
 
+    fn simulate_messages(&self, tracked_msgs: TrackedMsgs) -> Result<Vec<SimulatedTx>, Error> {
+        self.value().simulate_messages(tracked_msgs)
+    }
+
+    fn broadcast_evidence(&self, evidence: tendermint::evidence::Evidence) -> Result<(), Error> {
+        self.value().broadcast_evidence(evidence)
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.value().get_signer()
     }
@@ -135,6 +154,10 @@ where
         self.value().ibc_version()
     }
 
+    fn sdk_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.value().sdk_version()
+    }
+
     fn query_application_status(&self) -> Result<ChainStatus, Error> {
         self.value().query_application_status()
     }
@@ -154,7 +177,7 @@ where
         &self,
         request: QueryClientStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyClientState, Option<QueryProof>), Error> {
         self.value().query_client_state(request, include_proof)
     }
 
@@ -176,7 +199,7 @@ where
         &self,
         request: QueryConsensusStateRequest,
         include_proof: IncludeProof,
-    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+    ) -> Result<(AnyConsensusState, Option<QueryProof>), Error> {
         self.value().query_consensus_state(request, include_proof)
     }
 
@@ -206,7 +229,7 @@ where
         &self,
         request: QueryConnectionRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ConnectionEnd, Option<QueryProof>), Error> {
         self.value().query_connection(request, include_proof)
     }
 
@@ -228,7 +251,7 @@ where
         &self,
         request: QueryNextSequenceReceiveRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+    ) -> Result<(Sequence, Option<QueryProof>), Error> {
         self.value()
             .query_next_sequence_receive(request, include_proof)
     }
@@ -244,7 +267,7 @@ where
         &self,
         request: QueryChannelRequest,
         include_proof: IncludeProof,
-    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+    ) -> Result<(ChannelEnd, Option<QueryProof>), Error> {
         self.value().query_channel(request, include_proof)
     }
 
@@ -334,7 +357,7 @@ where
         &self,
         request: QueryPacketCommitmentRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.value().query_packet_commitment(request, include_proof)
     }
 
@@ -349,7 +372,7 @@ where
         &self,
         request: QueryPacketReceiptRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.value().query_packet_receipt(request, include_proof)
     }
 
@@ -364,7 +387,7 @@ where
         &self,
         request: QueryPacketAcknowledgementRequest,
         include_proof: IncludeProof,
-    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+    ) -> Result<(Vec<u8>, Option<QueryProof>), Error> {
         self.value()
             .query_packet_acknowledgement(request, include_proof)
     }
@@ -383,6 +406,13 @@ where
         self.value().query_unreceived_acknowledgements(request)
     }
 
+    fn batch_query(
+        &self,
+        requests: Vec<BatchQueryRequest>,
+    ) -> Result<Vec<Result<BatchQueryResponse, Error>>, Error> {
+        self.value().batch_query(requests)
+    }
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error> {
         self.value().query_txs(request)
     }
@@ -415,4 +445,41 @@ where
     ) -> Result<Vec<CrossChainQueryResponse>, Error> {
         self.value().cross_chain_query(request)
     }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Option<IdentifiedPacketFees>, Error> {
+        self.value().query_incentivized_packet(request)
+    }
+
+    fn query_counterparty_payee(
+        &self,
+        request: QueryCounterpartyPayeeRequest,
+    ) -> Result<Option<Signer>, Error> {
+        self.value().query_counterparty_payee(request)
+    }
+
+    fn query_wasm_checksums(
+        &self,
+        request: QueryWasmChecksumsRequest,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.value().query_wasm_checksums(request)
+    }
+
+    fn query_upgrade(
+        &self,
+        request: QueryUpgradeRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Upgrade, Option<QueryProof>), Error> {
+        self.value().query_upgrade(request, include_proof)
+    }
+
+    fn query_upgrade_error(
+        &self,
+        request: QueryUpgradeErrorRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ErrorReceipt, Option<QueryProof>), Error> {
+        self.value().query_upgrade_error(request, include_proof)
+    }
 }
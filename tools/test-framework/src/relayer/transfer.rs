@@ -28,9 +28,33 @@ pub fn build_transfer_message<SrcChain, DstChain>(
     recipient: &MonoTagged<DstChain, &WalletAddress>,
     denom: &MonoTagged<SrcChain, &Denom>,
     amount: u64,
+) -> Result<Any, Error> {
+    build_transfer_message_with_timeout(
+        port_id,
+        channel_id,
+        sender,
+        recipient,
+        denom,
+        amount,
+        Duration::from_secs(60),
+    )
+}
+
+/**
+   Build an IBC transfer message timing out after the given duration,
+   measured with the local clock.
+*/
+pub fn build_transfer_message_with_timeout<SrcChain, DstChain>(
+    port_id: &TaggedPortIdRef<'_, SrcChain, DstChain>,
+    channel_id: &TaggedChannelIdRef<'_, SrcChain, DstChain>,
+    sender: &MonoTagged<SrcChain, &Wallet>,
+    recipient: &MonoTagged<DstChain, &WalletAddress>,
+    denom: &MonoTagged<SrcChain, &Denom>,
+    amount: u64,
+    timeout: Duration,
 ) -> Result<Any, Error> {
     let timeout_timestamp = Timestamp::now()
-        .add(Duration::from_secs(60))
+        .add(timeout)
         .map_err(handle_generic_error)?;
 
     let sender = sender
@@ -90,3 +114,27 @@ pub async fn ibc_token_transfer<SrcChain, DstChain>(
 
     Ok(())
 }
+
+/**
+   Perform an IBC token transfer timing out after the given duration,
+   for the tests which explicitly need the packet to time out.
+*/
+#[allow(clippy::too_many_arguments)]
+pub async fn ibc_token_transfer_with_timeout<SrcChain, DstChain>(
+    tx_config: &MonoTagged<SrcChain, &TxConfig>,
+    port_id: &TaggedPortIdRef<'_, SrcChain, DstChain>,
+    channel_id: &TaggedChannelIdRef<'_, SrcChain, DstChain>,
+    sender: &MonoTagged<SrcChain, &Wallet>,
+    recipient: &MonoTagged<DstChain, &WalletAddress>,
+    denom: &MonoTagged<SrcChain, &Denom>,
+    amount: u64,
+    timeout: Duration,
+) -> Result<(), Error> {
+    let message = build_transfer_message_with_timeout(
+        port_id, channel_id, sender, recipient, denom, amount, timeout,
+    )?;
+
+    simple_send_tx(tx_config.value(), &sender.value().key, vec![message]).await?;
+
+    Ok(())
+}
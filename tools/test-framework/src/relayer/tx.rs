@@ -14,7 +14,8 @@ use ibc_relayer::chain::cosmos::types::gas::GasConfig;
 use ibc_relayer::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
 use ibc_relayer::chain::cosmos::wait::wait_for_block_commits;
 use ibc_relayer::config::{AddressType, GasPrice};
-use ibc_relayer::keyring::KeyEntry;
+use ibc_relayer::keyring::{KeyEntry, LocalSigner};
+use std::sync::Arc;
 use tendermint_rpc::{HttpClient, Url};
 
 use crate::error::{handle_generic_error, Error};
@@ -60,6 +61,10 @@ pub fn new_tx_config_for_test(
 
     let rpc_timeout = Duration::from_secs(30);
 
+    let query_timeout = Duration::from_secs(60);
+
+    let broadcast_timeout = Duration::from_secs(30);
+
     let extension_options = Default::default();
 
     Ok(TxConfig {
@@ -68,9 +73,17 @@ pub fn new_tx_config_for_test(
         rpc_client,
         rpc_address,
         grpc_address,
+        grpc_settings: Default::default(),
         rpc_timeout,
+        query_timeout,
+        broadcast_timeout,
         address_type,
         extension_options,
+        top_of_block: Default::default(),
+        fee_payer: None,
+        signing_provider: Arc::new(LocalSigner),
+        in_flight: Default::default(),
+        fee_allowances: Default::default(),
     })
 }
 
@@ -91,9 +104,13 @@ pub async fn simple_send_tx(
     key_entry: &KeyEntry,
     messages: Vec<Any>,
 ) -> Result<(), Error> {
-    let account = query_account(&config.grpc_address, &key_entry.account)
-        .await?
-        .into();
+    let account = query_account(
+        &config.grpc_address,
+        &config.grpc_settings,
+        &key_entry.account,
+    )
+    .await?
+    .into();
 
     let message_count = messages.len();
 
@@ -114,6 +131,8 @@ pub async fn simple_send_tx(
         &config.rpc_client,
         &config.rpc_address,
         &config.rpc_timeout,
+        &config.query_timeout,
+        &config.in_flight,
         &mut tx_sync_results,
     )
     .await?;
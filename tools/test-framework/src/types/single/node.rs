@@ -127,16 +127,26 @@ impl FullNode {
             id: self.chain_driver.chain_id.clone(),
             r#type: ChainType::CosmosSdk,
             rpc_addr: Url::from_str(&self.chain_driver.rpc_address())?,
+            secondary_rpc_addr: None,
+            archive_rpc_addr: None,
             websocket_addr: Url::from_str(&self.chain_driver.websocket_address())?,
             grpc_addr: Url::from_str(&self.chain_driver.grpc_address())?,
+            grpc_transport: Default::default(),
+            proxy: None,
             rpc_timeout: Duration::from_secs(10),
+            query_timeout: Duration::from_secs(60),
+            query_page_size: None,
+            broadcast_timeout: Duration::from_secs(30),
             account_prefix: self.chain_driver.account_prefix.clone(),
             key_name: self.wallets.relayer.id.0.clone(),
+            key_names: Vec::new(),
 
             // By default we use in-memory key store to avoid polluting
             // ~/.hermes/keys. See
             // https://github.com/informalsystems/ibc-rs/issues/1541
             key_store_type: Store::Memory,
+            key_store_folder: None,
+            tenant: None,
 
             store_prefix: "ibc".to_string(),
             default_gas: None,
@@ -144,6 +154,7 @@ impl FullNode {
             gas_adjustment: None,
             gas_multiplier: Default::default(),
             fee_granter: None,
+            fee_payer: None,
             max_msg_num: Default::default(),
             max_tx_size: Default::default(),
             max_block_time: Duration::from_secs(30),
@@ -152,11 +163,27 @@ impl FullNode {
             trust_threshold: Default::default(),
             gas_price: config::GasPrice::new(0.001, "stake".to_string()),
             packet_filter: Default::default(),
+            quotas: Default::default(),
+            top_of_block: Default::default(),
+            event_source: Default::default(),
+            fee_replenishment: None,
+            dynamic_gas_price: None,
+            remote_signer: None,
+            client_tls: None,
+            priorities: Vec::new(),
+            packet_limits: Vec::new(),
+            schedules: Vec::new(),
+            min_fees: Vec::new(),
+            channel_versions: Vec::new(),
+            pre_send_check: Default::default(),
             address_type: chain_type.address_type(),
             memo_prefix: Default::default(),
+            tx_memo_template: None,
             proof_specs: Default::default(),
             extension_options: Default::default(),
+            check_query_proof_consistency: false,
             sequential_batch_tx: false,
+            client_wrapper: None,
         })
     }
 
@@ -2,7 +2,8 @@
    Utilities for random value generation.
 */
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::net::{Ipv4Addr, SocketAddrV4, TcpListener};
 
 /// Generates a random `u32` value.
@@ -23,6 +24,31 @@ pub fn random_u64_range(min: u64, max: u64) -> u64 {
     rng.gen_range(min..max)
 }
 
+/// A generator of random values derived from a seed, so that a test using it
+/// can be replayed with the same values from the seed of a failed run.
+pub struct SeededRandom {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Generates a random `u64` value between the given min and max.
+    pub fn u64_range(&mut self, min: u64, max: u64) -> u64 {
+        self.rng.gen_range(min..max)
+    }
+}
+
 /// Generates a random string value, in the form of `u64` hex for simplicity.
 pub fn random_string() -> String {
     format!("{:x}", random_u64())